@@ -8,6 +8,9 @@ use std::path::Path;
 /// Size of sample to read for binary detection
 const SAMPLE_SIZE: usize = 8192;
 
+/// Bytes read for magic number detection (tar's magic sits at offset 257)
+pub(crate) const HEADER_SIZE: usize = 512;
+
 /// Threshold for non-printable character ratio to classify as binary
 const BINARY_THRESHOLD: f32 = 0.3;
 
@@ -64,13 +67,35 @@ pub fn is_binary(path: &Path) -> io::Result<bool> {
 /// The detected binary type, or BinaryType::Unknown if not recognized.
 pub fn detect_binary_type(path: &Path) -> Option<BinaryType> {
     let mut file = File::open(path).ok()?;
-    let mut header = [0u8; 16]; // Read first 16 bytes for magic number detection
+    let mut header = [0u8; HEADER_SIZE];
     let bytes_read = file.read(&mut header).ok()?;
 
     if bytes_read < 4 {
         return Some(BinaryType::Unknown);
     }
 
+    if let Some(binary_type) = binary_type_of(&header[..bytes_read]) {
+        return Some(binary_type);
+    }
+
+    // If it's binary but we don't recognize the format
+    if is_binary(path).unwrap_or(false) {
+        Some(BinaryType::Unknown)
+    } else {
+        None
+    }
+}
+
+/// Identify a binary format from the first bytes of a file
+///
+/// Used directly for archive members, which are read as a stream.
+/// Returns None for headers that match no known magic number.
+pub(crate) fn binary_type_of(header: &[u8]) -> Option<BinaryType> {
+    let bytes_read = header.len();
+    if bytes_read < 4 {
+        return None;
+    }
+
     // HDF5: 89 48 44 46 0D 0A 1A 0A (137, 72, 68, 70, 13, 10, 26, 10)
     if bytes_read >= 8
         && header[0] == 137
@@ -115,22 +140,54 @@ pub fn detect_binary_type(path: &Path) -> Option<BinaryType> {
         return Some(BinaryType::Pdf);
     }
 
-    // NetCDF: CDF\x01 or CDF\x02
+    // NetCDF classic: CDF\x01, CDF\x02 or CDF\x05 (netCDF-4 files are HDF5)
     if bytes_read >= 4
         && header[0] == b'C'
         && header[1] == b'D'
         && header[2] == b'F'
-        && (header[3] == 0x01 || header[3] == 0x02)
+        && matches!(header[3], 0x01 | 0x02 | 0x05)
     {
         return Some(BinaryType::Netcdf);
     }
 
-    // If it's binary but we don't recognize the format
-    if is_binary(path).unwrap_or(false) {
-        Some(BinaryType::Unknown)
-    } else {
-        None
+    // FITS: the first card is "SIMPLE  =" (the header itself is ASCII)
+    if bytes_read >= 9 && &header[..9] == b"SIMPLE  =" {
+        return Some(BinaryType::Fits);
     }
+
+    // Parquet: PAR1 (the footer ends with the same magic)
+    if bytes_read >= 4 && &header[..4] == b"PAR1" {
+        return Some(BinaryType::Parquet);
+    }
+
+    // SQLite 3 database: "SQLite format 3\0"
+    if bytes_read >= 16 && &header[..16] == b"SQLite format 3\0" {
+        return Some(BinaryType::Sqlite);
+    }
+
+    // NumPy array: \x93NUMPY
+    if bytes_read >= 6 && &header[..6] == b"\x93NUMPY" {
+        return Some(BinaryType::Npy);
+    }
+
+    // TIFF: II*\0 or MM\0* (version 42), BigTIFF: II+\0 or MM\0+ (version 43)
+    if bytes_read >= 4
+        && matches!(&header[..4], b"II*\0" | b"MM\0*" | b"II+\0" | b"MM\0+")
+    {
+        return Some(BinaryType::Tiff);
+    }
+
+    // ZIP (including .npz): PK\x03\x04, or PK\x05\x06 for an empty archive
+    if &header[..4] == b"PK\x03\x04" || &header[..4] == b"PK\x05\x06" {
+        return Some(BinaryType::Zip);
+    }
+
+    // tar: "ustar" at offset 257 (POSIX "ustar\0", GNU "ustar  \0")
+    if bytes_read >= 262 && &header[257..262] == b"ustar" {
+        return Some(BinaryType::Tar);
+    }
+
+    None
 }
 
 /// Check if a byte represents a printable character
@@ -277,6 +334,82 @@ mod tests {
         std::fs::remove_dir_all(temp_dir).ok();
     }
 
+    #[test]
+    fn test_detect_fits() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_fits");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("image.fits");
+        std::fs::write(&file_path, format!("{:<80}", "SIMPLE  =                    T")).unwrap();
+
+        let result = detect_binary_type(&file_path);
+        assert_eq!(result, Some(BinaryType::Fits));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_detect_parquet() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_parquet");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("table.parquet");
+        std::fs::write(&file_path, b"PAR1\x15\x00\x15\x00PAR1").unwrap();
+
+        let result = detect_binary_type(&file_path);
+        assert_eq!(result, Some(BinaryType::Parquet));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_detect_sqlite() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_sqlite");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("survey.db");
+        let mut header = b"SQLite format 3\0".to_vec();
+        header.extend_from_slice(&[0x10, 0x00, 0x01, 0x01]);
+        std::fs::write(&file_path, header).unwrap();
+
+        let result = detect_binary_type(&file_path);
+        assert_eq!(result, Some(BinaryType::Sqlite));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_detect_numpy() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_numpy");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let npy_path = temp_dir.join("array.npy");
+        std::fs::write(&npy_path, b"\x93NUMPY\x01\x00\x76\x00{'descr': '<f8'").unwrap();
+        assert_eq!(detect_binary_type(&npy_path), Some(BinaryType::Npy));
+
+        let npz_path = temp_dir.join("arrays.npz");
+        std::fs::write(&npz_path, b"PK\x03\x04\x14\x00\x00\x00\x00\x00").unwrap();
+        assert_eq!(detect_binary_type(&npz_path), Some(BinaryType::Zip));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_detect_tiff() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_tiff");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let tiff_path = temp_dir.join("stack.tif");
+        std::fs::write(&tiff_path, b"II*\x00\x08\x00\x00\x00").unwrap();
+        assert_eq!(detect_binary_type(&tiff_path), Some(BinaryType::Tiff));
+
+        let big_path = temp_dir.join("raster.tiff");
+        std::fs::write(&big_path, b"MM\x00+\x00\x08\x00\x00").unwrap();
+        assert_eq!(detect_binary_type(&big_path), Some(BinaryType::Tiff));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
     #[test]
     fn test_detect_unknown_binary() {
         let temp_dir = std::env::temp_dir().join("genesis_preflight_binary_unknown");
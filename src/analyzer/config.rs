@@ -0,0 +1,118 @@
+//! YAML and TOML analysis
+//!
+//! Configuration and metadata files (instrument settings, CITATION.cff,
+//! pyproject.toml) are parsed in full. The analysis records whether the
+//! file parses, the first syntax error with its line, and the top-level
+//! keys, so a broken or incomplete file is caught before a pipeline or a
+//! citation tool reads it.
+
+use super::toml::parse_toml;
+use super::yaml::parse_yaml;
+use super::AnalysisError;
+use crate::compression::DataReader;
+use crate::types::{ConfigAnalysis, ConfigFormat, ConfigSyntaxError};
+use std::io::Read;
+use std::path::Path;
+
+/// Largest file parsed (configuration files are small; anything bigger is
+/// data that happens to have a config extension)
+const MAX_CONFIG_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Analyze a YAML or TOML file
+///
+/// # Arguments
+///
+/// * `path` - Path to the file
+/// * `format` - Which format to parse it as
+///
+/// # Returns
+///
+/// A ConfigAnalysis with validity, the first syntax error and the
+/// top-level keys.
+pub fn analyze_config(path: &Path, format: ConfigFormat) -> Result<ConfigAnalysis, AnalysisError> {
+    analyze_config_from(DataReader::open(path)?, format)
+}
+
+/// Analyze YAML or TOML read from a file or archive member
+pub(crate) fn analyze_config_from(data: DataReader, format: ConfigFormat) -> Result<ConfigAnalysis, AnalysisError> {
+    let mut bytes = Vec::new();
+    data.take(MAX_CONFIG_SIZE + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > MAX_CONFIG_SIZE {
+        return Err(AnalysisError::FileTooLarge);
+    }
+
+    let parsed = match std::str::from_utf8(&bytes) {
+        Ok(text) => match format {
+            ConfigFormat::Yaml => parse_yaml(text),
+            ConfigFormat::Toml => parse_toml(text).map(|document| vec![document]),
+        },
+        Err(e) => Err(ConfigSyntaxError {
+            message: "Invalid UTF-8".to_string(),
+            line: bytes[..e.valid_up_to()].iter().filter(|&&b| b == b'\n').count() + 1,
+        }),
+    };
+
+    Ok(match parsed {
+        Ok(documents) => ConfigAnalysis {
+            format,
+            is_valid: true,
+            top_level_keys: documents
+                .first()
+                .and_then(|document| document.as_object())
+                .map(|members| members.iter().map(|(key, _)| key.clone()).collect())
+                .unwrap_or_default(),
+            document_count: documents.len(),
+            error: None,
+        },
+        Err(error) => ConfigAnalysis {
+            format,
+            is_valid: false,
+            top_level_keys: Vec::new(),
+            document_count: 0,
+            error: Some(error),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_analyze_config_files() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_config_analysis");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let cff = temp_dir.join("CITATION.cff");
+        fs::write(&cff, "cff-version: 1.2.0\ntitle: Survey\nauthors:\n  - name: Ada\n").unwrap();
+        let analysis = analyze_config(&cff, ConfigFormat::Yaml).unwrap();
+        assert!(analysis.is_valid);
+        assert_eq!(analysis.top_level_keys, vec!["cff-version", "title", "authors"]);
+        assert_eq!(analysis.document_count, 1);
+
+        let toml = temp_dir.join("settings.toml");
+        fs::write(&toml, "name = \"run\"\n[detector]\ngain = 2\n").unwrap();
+        let analysis = analyze_config(&toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(analysis.top_level_keys, vec!["name", "detector"]);
+
+        let broken = temp_dir.join("broken.toml");
+        fs::write(&broken, "name = \"run\"\nname = \"again\"\n").unwrap();
+        let analysis = analyze_config(&broken, ConfigFormat::Toml).unwrap();
+        assert!(!analysis.is_valid);
+        assert_eq!(
+            analysis.error,
+            Some(ConfigSyntaxError {
+                message: "Duplicate key 'name'".to_string(),
+                line: 2
+            })
+        );
+
+        let binary = temp_dir.join("binary.yaml");
+        fs::write(&binary, b"a: 1\nb: \xff\n").unwrap();
+        let analysis = analyze_config(&binary, ConfigFormat::Yaml).unwrap();
+        assert_eq!(analysis.error.map(|e| (e.message, e.line)), Some(("Invalid UTF-8".to_string(), 2)));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+}
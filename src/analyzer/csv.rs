@@ -5,10 +5,10 @@
 
 use super::inference::{is_float, is_integer};
 use super::AnalysisError;
+use crate::compression::{is_corrupt, DataReader};
 use crate::types::{ColumnInfo, ColumnType, CsvAnalysis};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::Path;
 
 /// Maximum number of lines to sample for delimiter detection
@@ -22,7 +22,7 @@ const MAX_DISPLAY_SAMPLES: usize = 5;
 
 /// Streaming column statistics for memory-efficient analysis
 #[derive(Debug)]
-struct StreamingColumnStats {
+pub(crate) struct StreamingColumnStats {
     /// Total non-null values seen
     total_count: u64,
     /// Count of empty/null values
@@ -53,7 +53,7 @@ struct TypeCounts {
 }
 
 impl StreamingColumnStats {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             total_count: 0,
             null_count: 0,
@@ -68,7 +68,7 @@ impl StreamingColumnStats {
     }
 
     /// Update statistics with a new value
-    fn update(&mut self, value: &str) {
+    pub(crate) fn update(&mut self, value: &str) {
         if value.is_empty() {
             self.null_count += 1;
             return;
@@ -77,7 +77,7 @@ impl StreamingColumnStats {
         self.total_count += 1;
 
         // Track unique values (with cap)
-        if !self.unique_saturated {
+        if !self.unique_saturated && !self.unique_values.contains(value) {
             if self.unique_values.len() < MAX_UNIQUE_SAMPLES {
                 self.unique_values.insert(value.to_string());
             } else {
@@ -115,6 +115,39 @@ impl StreamingColumnStats {
         }
     }
 
+    /// Build the column description from the statistics gathered
+    pub(crate) fn into_column_info(self, index: usize, name: Option<String>) -> ColumnInfo {
+        let mut info = ColumnInfo::new(index);
+
+        // Infer type from full-file statistics
+        let inferred_type = self.infer_final_type(name.as_deref());
+        if let Some(name) = name {
+            info = info.with_name(name);
+        }
+        info = info.with_type(inferred_type);
+
+        // Set null count
+        info = info.with_null_count(self.null_count as usize);
+
+        // Set distinct count and numeric range
+        info = info.with_unique_count(self.unique_values.len(), self.unique_saturated);
+        if matches!(inferred_type, ColumnType::Integer | ColumnType::Float) {
+            info = info.with_range(self.numeric_min, self.numeric_max);
+        }
+
+        // Add sample values
+        for sample in self.sample_values {
+            info = info.add_sample(sample);
+        }
+
+        info
+    }
+
+    /// Count values known to be missing without visiting them one by one
+    pub(crate) fn add_nulls(&mut self, count: u64) {
+        self.null_count += count;
+    }
+
     fn update_numeric_stats(&mut self, value: f64) {
         self.numeric_sum += value;
         self.numeric_min = Some(self.numeric_min.map_or(value, |min| min.min(value)));
@@ -278,22 +311,34 @@ fn is_date_prefix(bytes: &[u8]) -> bool {
 ///
 /// A CsvAnalysis struct with detected characteristics, or an error.
 pub fn analyze_csv(path: &Path) -> Result<CsvAnalysis, AnalysisError> {
-    let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
+    analyze_csv_from(|| DataReader::open(path))
+}
+
+/// Analyze CSV data from a source that can be opened twice
+///
+/// The first pass samples lines for delimiter and header detection, the
+/// second streams every row. Used for archive members as well as files.
+pub(crate) fn analyze_csv_from(open: impl Fn() -> io::Result<DataReader>) -> Result<CsvAnalysis, AnalysisError> {
+    let mut reader = BufReader::new(open()?);
 
     // Read first few lines for delimiter detection
     let mut sample_lines = Vec::new();
     let mut line = String::new();
     for _ in 0..DELIMITER_SAMPLE_SIZE {
         line.clear();
-        if reader.read_line(&mut line)? == 0 {
-            break;
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => sample_lines.push(line.trim_end().to_string()),
+            // A damaged .gz is still analyzed up to the damage
+            Err(e) if is_corrupt(&e) => break,
+            Err(e) => return Err(e.into()),
         }
-        sample_lines.push(line.trim_end().to_string());
     }
 
     if sample_lines.is_empty() {
-        return Ok(CsvAnalysis::new(',', false));
+        let mut analysis = CsvAnalysis::new(',', false);
+        analysis.gzip = reader.get_ref().gzip_info();
+        return Ok(analysis);
     }
 
     // Detect delimiter
@@ -304,7 +349,9 @@ pub fn analyze_csv(path: &Path) -> Result<CsvAnalysis, AnalysisError> {
     let column_count = first_line_fields.len();
 
     if column_count == 0 {
-        return Ok(CsvAnalysis::new(delimiter, false));
+        let mut analysis = CsvAnalysis::new(delimiter, false);
+        analysis.gzip = reader.get_ref().gzip_info();
+        return Ok(analysis);
     }
 
     // Check if first line is a header
@@ -322,13 +369,16 @@ pub fn analyze_csv(path: &Path) -> Result<CsvAnalysis, AnalysisError> {
     };
 
     // Stream through entire file for full analysis
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    let mut reader = BufReader::new(open()?);
     let mut row_count: usize = 0;
     let mut data_row_count: usize = 0;
 
-    for line_result in reader.lines() {
-        let line = line_result?;
+    for line_result in reader.by_ref().lines() {
+        let line = match line_result {
+            Ok(line) => line,
+            Err(e) if is_corrupt(&e) => break,
+            Err(e) => return Err(e.into()),
+        };
         if line.trim().is_empty() {
             continue;
         }
@@ -356,31 +406,9 @@ pub fn analyze_csv(path: &Path) -> Result<CsvAnalysis, AnalysisError> {
     // Build column info from streaming stats
     let columns: Vec<ColumnInfo> = column_stats
         .into_iter()
+        .zip(header_names)
         .enumerate()
-        .map(|(idx, stats)| {
-            let mut info = ColumnInfo::new(idx);
-
-            // Set name if we have header
-            if let Some(Some(name)) = header_names.get(idx) {
-                info = info.with_name(name.clone());
-            }
-
-            // Infer type from full-file statistics
-            let inferred_type = stats.infer_final_type(
-                header_names.get(idx).and_then(|n| n.as_deref())
-            );
-            info = info.with_type(inferred_type);
-
-            // Set null count
-            info = info.with_null_count(stats.null_count as usize);
-
-            // Add sample values
-            for sample in stats.sample_values {
-                info = info.add_sample(sample);
-            }
-
-            info
-        })
+        .map(|(idx, (stats, name))| stats.into_column_info(idx, name))
         .collect();
 
     Ok(CsvAnalysis {
@@ -389,6 +417,7 @@ pub fn analyze_csv(path: &Path) -> Result<CsvAnalysis, AnalysisError> {
         column_count,
         row_count: data_row_count,
         columns,
+        gzip: reader.get_ref().gzip_info(),
     })
 }
 
@@ -463,7 +492,7 @@ fn detect_header(lines: &[String], delimiter: char) -> bool {
 ///
 /// Note: Does NOT handle multiline fields (fields with embedded newlines)
 /// as we process line-by-line for streaming.
-fn parse_line_rfc4180(line: &str, delimiter: char) -> Vec<String> {
+pub(crate) fn parse_line_rfc4180(line: &str, delimiter: char) -> Vec<String> {
     let mut fields = Vec::new();
     let mut current_field = String::new();
     let mut in_quotes = false;
@@ -515,6 +544,7 @@ fn parse_line_simple(line: &str, delimiter: char) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs::File;
     use std::io::Write;
 
     #[test]
@@ -666,4 +696,61 @@ mod tests {
 
         std::fs::remove_dir_all(temp_dir).ok();
     }
+
+    #[test]
+    fn test_analyze_csv_unique_and_range() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_csv_stats");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("test.csv");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "site,depth").unwrap();
+            writeln!(file, "north,12.5").unwrap();
+            writeln!(file, "south,-3.0").unwrap();
+            writeln!(file, "north,40.25").unwrap();
+        }
+
+        let result = analyze_csv(&file_path).unwrap();
+        assert_eq!(result.columns[0].unique_count, 2);
+        assert!(!result.columns[0].unique_count_capped);
+        assert_eq!(result.columns[0].min_value, None);
+        assert_eq!(result.columns[1].unique_count, 3);
+        assert_eq!(result.columns[1].min_value, Some(-3.0));
+        assert_eq!(result.columns[1].max_value, Some(40.25));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_csv_gzip() {
+        use crate::compression::fixture::{hex, OBS_MEMBER, ROW_MEMBER};
+
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_csv_gzip");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let mut data = hex(OBS_MEMBER);
+        data.extend(hex(ROW_MEMBER));
+        let file_path = temp_dir.join("obs.csv.gz");
+        std::fs::write(&file_path, &data).unwrap();
+
+        let result = analyze_csv(&file_path).unwrap();
+        assert!(result.has_header);
+        assert_eq!(result.row_count, 2);
+        assert_eq!(result.columns[1].max_value, Some(3.5));
+        let gzip = result.gzip.unwrap();
+        assert_eq!(gzip.compressed_bytes, data.len() as u64);
+        assert_eq!(gzip.uncompressed_bytes, 21);
+        assert_eq!(gzip.error, None);
+
+        // The rows before the damage are still analyzed
+        let crc = data.len() - 8;
+        data[crc] ^= 0xFF;
+        std::fs::write(&file_path, &data).unwrap();
+        let result = analyze_csv(&file_path).unwrap();
+        assert_eq!(result.row_count, 2);
+        assert!(result.gzip.unwrap().error.unwrap().starts_with("CRC mismatch"));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
 }
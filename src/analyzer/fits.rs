@@ -0,0 +1,409 @@
+//! FITS header parsing
+//!
+//! Walks the header-data units (HDUs) of a FITS file. Each header is a run
+//! of 80-character keyword cards in 2880-byte blocks, closed by an `END`
+//! card; the size of the data that follows is fixed by `BITPIX`, `NAXISn`,
+//! `PCOUNT` and `GCOUNT`, which is enough to step to the next HDU without
+//! reading any data.
+
+use super::AnalysisError;
+use crate::types::{FitsAnalysis, FitsColumn, FitsHdu, FitsHduKind, FITS_BLOCK_SIZE};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Size of one header card
+const CARD_SIZE: usize = 80;
+
+/// Longest header we read (in blocks) before giving up on finding `END`
+const MAX_HEADER_BLOCKS: u64 = 10_000;
+
+/// Most HDUs listed for one file
+const MAX_HDUS: usize = 1_000;
+
+/// Valid `BITPIX` values
+const BITPIX_VALUES: [i64; 6] = [8, 16, 32, 64, -32, -64];
+
+/// One keyword card from a header
+struct Card {
+    keyword: String,
+    value: Option<String>,
+}
+
+/// Outcome of reading a header
+enum Header {
+    /// The cards before `END`, and the header's size in bytes
+    Found(Vec<Card>, u64),
+    /// The first card is not the expected keyword
+    NotAnHdu,
+    /// The header starts correctly but cannot be read to its end
+    Damaged(String),
+}
+
+/// Analyze the headers of a FITS file
+///
+/// Fails only if the primary header cannot be read; a damaged extension
+/// is recorded in `warnings` and the HDUs before it are still listed.
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with a `SIMPLE  =` card
+///
+/// # Returns
+///
+/// Every HDU with its kind, axes, table columns, missing mandatory
+/// keywords and position in the file.
+pub fn analyze_fits(path: &Path) -> Result<FitsAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut analysis = FitsAnalysis::new(file_size);
+    let mut offset = 0u64;
+
+    while offset < file_size {
+        let index = analysis.hdus.len();
+        if index >= MAX_HDUS {
+            analysis.warnings.push(format!("only the first {} HDUs were read", MAX_HDUS));
+            break;
+        }
+
+        let expected = if index == 0 { "SIMPLE" } else { "XTENSION" };
+        let (cards, header_size) = match read_header(&mut file, offset, file_size, expected)? {
+            Header::Found(cards, size) => (cards, size),
+            // Not another extension: whatever follows is trailing bytes
+            Header::NotAnHdu if index > 0 => break,
+            Header::NotAnHdu => return Err(invalid("first card is not SIMPLE")),
+            Header::Damaged(message) if index == 0 => return Err(invalid(message)),
+            Header::Damaged(message) => {
+                analysis.warnings.push(format!("HDU {} at byte {}: {}", index, offset, message));
+                break;
+            }
+        };
+
+        let (hdu, locatable) = build_hdu(index, offset, offset + header_size, &cards);
+        offset = hdu.padded_end();
+        analysis.hdus.push(hdu);
+
+        if !locatable {
+            analysis.warnings.push(format!(
+                "HDU {}: data size cannot be determined, so later HDUs were not read",
+                index
+            ));
+            break;
+        }
+    }
+
+    Ok(analysis)
+}
+
+/// Build an `InvalidFormat` error with a `FITS:` prefix
+fn invalid(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidFormat(format!("FITS: {}", message.into()))
+}
+
+/// Read the header starting at `offset`, whose first keyword must be `expected`
+fn read_header(file: &mut File, offset: u64, file_size: u64, expected: &str) -> Result<Header, AnalysisError> {
+    let mut cards = Vec::new();
+    let mut block = vec![0u8; FITS_BLOCK_SIZE as usize];
+    file.seek(SeekFrom::Start(offset))?;
+
+    for blocks in 0..MAX_HEADER_BLOCKS {
+        if offset + (blocks + 1) * FITS_BLOCK_SIZE > file_size {
+            if blocks == 0 && offset > 0 {
+                return Ok(Header::NotAnHdu); // a partial block is trailing bytes
+            }
+            return Ok(Header::Damaged("header is truncated (no END card)".to_string()));
+        }
+        file.read_exact(&mut block)?;
+
+        for raw in block.chunks_exact(CARD_SIZE) {
+            let card = parse_card(raw);
+            if cards.is_empty() && card.keyword != expected {
+                return Ok(Header::NotAnHdu);
+            }
+            if card.keyword == "END" {
+                return Ok(Header::Found(cards, (blocks + 1) * FITS_BLOCK_SIZE));
+            }
+            cards.push(card);
+        }
+    }
+
+    Ok(Header::Damaged(format!("no END card in the first {} header blocks", MAX_HEADER_BLOCKS)))
+}
+
+/// Split a card into its keyword and value
+fn parse_card(raw: &[u8]) -> Card {
+    let keyword = String::from_utf8_lossy(&raw[..8]).trim_end().to_string();
+    let value = if &raw[8..10] == b"= " {
+        Some(parse_value(&String::from_utf8_lossy(&raw[10..])))
+    } else {
+        None
+    };
+    Card { keyword, value }
+}
+
+/// Parse a card value: a quoted string (`''` is a literal quote) or
+/// anything up to the `/` comment separator
+fn parse_value(field: &str) -> String {
+    let field = field.trim_start();
+    if let Some(quoted) = field.strip_prefix('\'') {
+        let mut value = String::new();
+        let mut chars = quoted.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            value.push(c);
+        }
+        return value.trim_end().to_string();
+    }
+    field.split('/').next().unwrap_or("").trim().to_string()
+}
+
+/// Interpret a header, returning the HDU and whether its data size is known
+fn build_hdu(index: usize, header_offset: u64, data_offset: u64, cards: &[Card]) -> (FitsHdu, bool) {
+    let value = |keyword: &str| {
+        cards
+            .iter()
+            .find(|c| c.keyword == keyword)
+            .and_then(|c| c.value.as_deref())
+    };
+    let integer = |keyword: &str| value(keyword).and_then(|v| v.parse::<i64>().ok());
+    let mut missing = Vec::new();
+
+    let kind = if index == 0 {
+        FitsHduKind::Primary
+    } else {
+        match value("XTENSION").unwrap_or("") {
+            "IMAGE" => FitsHduKind::Image,
+            "TABLE" => FitsHduKind::AsciiTable,
+            "BINTABLE" | "A3DTABLE" => FitsHduKind::BinaryTable,
+            other => FitsHduKind::Other(other.to_string()),
+        }
+    };
+
+    let bitpix = integer("BITPIX").filter(|b| BITPIX_VALUES.contains(b));
+    if bitpix.is_none() {
+        missing.push("BITPIX".to_string());
+    }
+
+    let mut axes = Vec::new();
+    let mut axes_known = true;
+    match integer("NAXIS").filter(|n| (0..=999).contains(n)) {
+        Some(naxis) => {
+            for n in 1..=naxis {
+                let keyword = format!("NAXIS{}", n);
+                match integer(&keyword).filter(|&length| length >= 0) {
+                    Some(length) => axes.push(length as u64),
+                    None => {
+                        missing.push(keyword);
+                        axes_known = false;
+                    }
+                }
+            }
+        }
+        None => {
+            missing.push("NAXIS".to_string());
+            axes_known = false;
+        }
+    }
+
+    // Extensions must declare PCOUNT and GCOUNT; the primary HDU only
+    // carries them for random groups
+    let mut count = |keyword: &str, default: i64| match integer(keyword).filter(|&c| c >= 0) {
+        Some(c) => c as u64,
+        None => {
+            if index > 0 {
+                missing.push(keyword.to_string());
+            }
+            default as u64
+        }
+    };
+    let pcount = count("PCOUNT", 0);
+    let gcount = count("GCOUNT", 1);
+
+    let mut columns = Vec::new();
+    if kind.is_table() {
+        match integer("TFIELDS").filter(|n| (0..=999).contains(n)) {
+            Some(fields) => {
+                for n in 1..=fields {
+                    if kind == FitsHduKind::AsciiTable && integer(&format!("TBCOL{}", n)).is_none() {
+                        missing.push(format!("TBCOL{}", n));
+                    }
+                    let format = value(&format!("TFORM{}", n)).unwrap_or("").to_string();
+                    if format.is_empty() {
+                        missing.push(format!("TFORM{}", n));
+                    }
+                    columns.push(FitsColumn {
+                        name: value(&format!("TTYPE{}", n)).map(str::to_string),
+                        format,
+                        unit: value(&format!("TUNIT{}", n))
+                            .filter(|u| !u.is_empty())
+                            .map(str::to_string),
+                    });
+                }
+            }
+            None => missing.push("TFIELDS".to_string()),
+        }
+    }
+
+    // Random groups: NAXIS1 = 0 and GROUPS = T, the first axis is not counted
+    let random_groups = index == 0 && value("GROUPS") == Some("T") && axes.first() == Some(&0);
+    let counted_axes = if random_groups { &axes[1..] } else { &axes[..] };
+    let elements = if counted_axes.is_empty() {
+        Some(0)
+    } else {
+        counted_axes.iter().try_fold(1u64, |acc, &n| acc.checked_mul(n))
+    };
+
+    let data_size = match (bitpix, axes_known, elements) {
+        (Some(bitpix), true, Some(elements)) => elements
+            .checked_add(pcount)
+            .and_then(|n| n.checked_mul(gcount))
+            .and_then(|n| n.checked_mul(bitpix.unsigned_abs() / 8)),
+        _ => None,
+    };
+
+    let hdu = FitsHdu {
+        index,
+        kind,
+        name: value("EXTNAME").map(str::to_string),
+        bitpix: bitpix.unwrap_or(0),
+        axes,
+        columns,
+        missing_keywords: missing,
+        header_offset,
+        data_offset,
+        data_size: data_size.unwrap_or(0),
+    };
+    (hdu, data_size.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::write_temp_file;
+    use crate::types::fits_padded_size;
+    use std::fs;
+
+    /// Render cards into a header padded to whole blocks
+    fn header(cards: &[&str]) -> Vec<u8> {
+        let mut bytes: Vec<u8> = cards
+            .iter()
+            .chain(std::iter::once(&"END"))
+            .flat_map(|card| format!("{:<80}", card).into_bytes())
+            .collect();
+        bytes.resize(fits_padded_size(bytes.len() as u64) as usize, b' ');
+        bytes
+    }
+
+    /// A 10x20 float image followed by a two-column binary table
+    fn image_and_table() -> Vec<u8> {
+        let mut bytes = header(&[
+            "SIMPLE  =                    T / conforms to FITS standard",
+            "BITPIX  =                  -32",
+            "NAXIS   =                    2",
+            "NAXIS1  =                   10",
+            "NAXIS2  =                   20",
+            "OBJECT  = 'M31 ''core'''",
+        ]);
+        bytes.resize(bytes.len() + fits_padded_size(10 * 20 * 4) as usize, 0);
+        bytes.extend(header(&[
+            "XTENSION= 'BINTABLE'",
+            "BITPIX  =                    8",
+            "NAXIS   =                    2",
+            "NAXIS1  =                   24",
+            "NAXIS2  =                    3",
+            "PCOUNT  =                    0",
+            "GCOUNT  =                    1",
+            "TFIELDS =                    2",
+            "TTYPE1  = 'FLUX    '",
+            "TFORM1  = '1D      '",
+            "TUNIT1  = 'Jy      '",
+            "TTYPE2  = 'SOURCE  '",
+            "TFORM2  = '16A     '",
+            "EXTNAME = 'CATALOG '",
+        ]));
+        bytes.resize(bytes.len() + fits_padded_size(24 * 3) as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_analyze_image_and_table() {
+        let path = write_temp_file("genesis_preflight_fits_hdus", "observation.fits", &image_and_table());
+        let analysis = analyze_fits(&path).unwrap();
+
+        assert!(analysis.warnings.is_empty());
+        assert_eq!(analysis.hdus.len(), 2);
+
+        let primary = &analysis.hdus[0];
+        assert_eq!(primary.kind, FitsHduKind::Primary);
+        assert_eq!(primary.bitpix, -32);
+        assert_eq!(primary.axes, vec![10, 20]);
+        assert_eq!(primary.data_offset, 2880);
+        assert_eq!(primary.data_size, 800);
+        assert!(primary.missing_keywords.is_empty());
+
+        let table = &analysis.hdus[1];
+        assert_eq!(table.kind, FitsHduKind::BinaryTable);
+        assert_eq!(table.name.as_deref(), Some("CATALOG"));
+        assert_eq!(table.header_offset, 5760);
+        assert_eq!(table.data_size, 72);
+        assert_eq!(table.columns.len(), 2);
+        assert_eq!(table.columns[0].name.as_deref(), Some("FLUX"));
+        assert_eq!(table.columns[0].unit.as_deref(), Some("Jy"));
+        assert!(table.columns[1].unit.is_none());
+        assert!(table.columns[1].is_unitless());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_missing_keywords_and_truncation() {
+        let mut bytes = header(&[
+            "SIMPLE  =                    T",
+            "BITPIX  =                   16",
+            "NAXIS   =                    1",
+            "NAXIS1  =                 5000",
+        ]);
+        bytes.extend(vec![0u8; 100]);
+        let path = write_temp_file("genesis_preflight_fits_keywords", "observation.fits", &bytes);
+        let analysis = analyze_fits(&path).unwrap();
+        assert_eq!(analysis.hdus[0].data_size, 10_000);
+        assert!(analysis.hdus[0].data_offset + analysis.hdus[0].data_size > analysis.file_size);
+
+        let mut bytes = header(&[
+            "SIMPLE  =                    T",
+            "BITPIX  =                    8",
+            "NAXIS   =                    0",
+        ]);
+        bytes.extend(header(&[
+            "XTENSION= 'TABLE   '",
+            "BITPIX  =                    8",
+            "NAXIS   =                    2",
+            "NAXIS1  =                   10",
+            "NAXIS2  =                    0",
+            "TFIELDS =                    1",
+        ]));
+        let path = write_temp_file("genesis_preflight_fits_keywords", "observation.fits", &bytes);
+        let analysis = analyze_fits(&path).unwrap();
+        assert_eq!(analysis.hdus[1].kind, FitsHduKind::AsciiTable);
+        assert_eq!(analysis.hdus[1].missing_keywords, vec!["PCOUNT", "GCOUNT", "TBCOL1", "TFORM1"]);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_invalid_primary_header() {
+        let path = write_temp_file("genesis_preflight_fits_invalid", "observation.fits", &header(&["XTENSION= 'IMAGE'"]));
+        assert!(analyze_fits(&path).is_err());
+
+        let path = write_temp_file("genesis_preflight_fits_invalid", "observation.fits", &header(&["SIMPLE  =                    T"])[..1000]);
+        assert!(analyze_fits(&path).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
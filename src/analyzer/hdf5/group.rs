@@ -0,0 +1,131 @@
+//! Old-style HDF5 groups: v1 B-trees, symbol table nodes and local heaps
+
+use super::object::{c_string, Link};
+use super::reader::{invalid, Reader};
+use crate::analyzer::AnalysisError;
+use std::collections::HashSet;
+
+/// Deepest B-tree we follow
+const MAX_BTREE_DEPTH: usize = 32;
+
+/// Most members listed for one group
+const MAX_GROUP_MEMBERS: usize = 100_000;
+
+/// List the members of a group stored as a symbol table
+///
+/// Walks the group's v1 B-tree down to its symbol table nodes and resolves
+/// each member name from the group's local heap. Members come out in name
+/// order, as the B-tree stores them.
+pub(super) fn symbol_table_links(
+    reader: &mut Reader,
+    btree_address: u64,
+    heap_address: u64,
+) -> Result<Vec<Link>, AnalysisError> {
+    let heap = read_local_heap(reader, heap_address)?;
+    let mut links = Vec::new();
+    let mut visited = HashSet::new();
+    walk_btree(reader, btree_address, &heap, 0, &mut visited, &mut links)?;
+    Ok(links)
+}
+
+/// Read the data segment of a local heap
+fn read_local_heap(reader: &mut Reader, address: u64) -> Result<Vec<u8>, AnalysisError> {
+    let header_len = 8 + 2 * reader.length_size as u64 + reader.offset_size as u64;
+    let header = reader.read_at(address, header_len)?;
+    if &header[..4] != b"HEAP" {
+        return Err(invalid("local heap signature not found"));
+    }
+
+    let mut cursor = reader.cursor(&header[8..]);
+    let data_size = cursor.length()?;
+    cursor.length()?; // free list head
+    let data_address = cursor.offset()?;
+
+    reader.read_at(data_address, data_size)
+}
+
+/// Recursively walk a group B-tree node
+fn walk_btree(
+    reader: &mut Reader,
+    address: u64,
+    heap: &[u8],
+    depth: usize,
+    visited: &mut HashSet<u64>,
+    links: &mut Vec<Link>,
+) -> Result<(), AnalysisError> {
+    if depth > MAX_BTREE_DEPTH || !visited.insert(address) {
+        return Err(invalid("group B-tree is too deep or cyclic"));
+    }
+
+    let offset_size = reader.offset_size as u64;
+    let length_size = reader.length_size as u64;
+
+    let header = reader.read_at(address, 8 + 2 * offset_size)?;
+    if &header[..4] != b"TREE" {
+        return Err(invalid("group B-tree signature not found"));
+    }
+    if header[4] != 0 {
+        return Err(invalid("B-tree is not a group node"));
+    }
+    let level = header[5];
+    let entries = u16::from_le_bytes([header[6], header[7]]) as u64;
+
+    // Keys and children interleave: key, child, key, child, ..., key
+    let body_len = entries * (length_size + offset_size) + length_size;
+    let body = reader.read_at(address + 8 + 2 * offset_size, body_len)?;
+    let mut cursor = reader.cursor(&body);
+
+    let mut children = Vec::with_capacity(entries as usize);
+    for _ in 0..entries {
+        cursor.length()?;
+        children.push(cursor.offset()?);
+    }
+
+    for child in children {
+        if level > 0 {
+            walk_btree(reader, child, heap, depth + 1, visited, links)?;
+        } else {
+            read_symbol_node(reader, child, heap, links)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the entries of a symbol table node (`SNOD`)
+fn read_symbol_node(
+    reader: &mut Reader,
+    address: u64,
+    heap: &[u8],
+    links: &mut Vec<Link>,
+) -> Result<(), AnalysisError> {
+    let header = reader.read_at(address, 8)?;
+    if &header[..4] != b"SNOD" {
+        return Err(invalid("symbol table node signature not found"));
+    }
+    let count = u16::from_le_bytes([header[6], header[7]]) as u64;
+
+    let entry_len = 2 * reader.offset_size as u64 + 24;
+    let body = reader.read_at(address + 8, count * entry_len)?;
+    let mut cursor = reader.cursor(&body);
+
+    for _ in 0..count {
+        let name_offset = cursor.offset()? as usize;
+        let object_address = cursor.offset()?;
+        cursor.skip(24)?; // cache type, reserved, scratch pad
+
+        if links.len() >= MAX_GROUP_MEMBERS {
+            return Err(invalid("group has too many members"));
+        }
+        let name = heap
+            .get(name_offset..)
+            .map(c_string)
+            .ok_or_else(|| invalid("link name is outside the local heap"))?;
+        links.push(Link {
+            name,
+            address: object_address,
+        });
+    }
+
+    Ok(())
+}
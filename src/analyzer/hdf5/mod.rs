@@ -0,0 +1,536 @@
+//! HDF5 structural introspection
+//!
+//! A read-only parser for the parts of the HDF5 format needed to describe a
+//! file: the superblock (versions 0-3), version 1 and 2 object headers,
+//! old-style groups (v1 B-trees, symbol table nodes, local heaps) and
+//! compact new-style groups (link messages). Dataset shapes, datatypes,
+//! chunking, filter ids and attributes are extracted; raw data is never
+//! read. Dense (fractal heap) link and attribute storage is reported as a
+//! warning rather than parsed.
+
+mod group;
+mod object;
+mod reader;
+
+use super::AnalysisError;
+use crate::types::{Hdf5Analysis, Hdf5Dataset, Hdf5Group};
+use reader::{invalid, Reader};
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::Path;
+
+/// HDF5 format signature
+const SIGNATURE: [u8; 8] = [0x89, b'H', b'D', b'F', b'\r', b'\n', 0x1A, b'\n'];
+
+/// Deepest group nesting we follow
+const MAX_DEPTH: usize = 64;
+
+/// Most objects (groups and datasets) listed for one file
+const MAX_OBJECTS: usize = 10_000;
+
+/// Analyze the structure of an HDF5 file
+///
+/// Fails only if the superblock or root group cannot be read; problems
+/// further down the hierarchy are collected in `warnings` and the rest of
+/// the file is still listed.
+pub fn analyze_hdf5(path: &Path) -> Result<Hdf5Analysis, AnalysisError> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = Reader::new(file, file_len);
+
+    let (version, root_address) = read_superblock(&mut reader)?;
+
+    let mut walker = Walker {
+        reader,
+        analysis: Hdf5Analysis::new(version),
+        visited: HashSet::new(),
+    };
+    walker.visit_root(root_address)?;
+
+    Ok(walker.analysis)
+}
+
+/// Locate and decode the superblock, returning its version and the root
+/// group's object header address
+///
+/// The superblock sits at offset 0 or, after a user block, at 512, 1024,
+/// 2048, ... bytes.
+fn read_superblock(reader: &mut Reader) -> Result<(u8, u64), AnalysisError> {
+    let mut position = 0u64;
+    loop {
+        if position + 8 > reader.file_len() {
+            return Err(invalid("signature not found"));
+        }
+        if reader.read_absolute(position, 8)? == SIGNATURE {
+            break;
+        }
+        position = if position == 0 { 512 } else { position * 2 };
+    }
+
+    let available = (reader.file_len() - position).min(256);
+    let block = reader.read_absolute(position, available)?;
+    if block.len() < 16 {
+        return Err(invalid("superblock is truncated"));
+    }
+
+    let version = block[8];
+    let (offset_size, length_size, fields_start) = match version {
+        0 => (block[13], block[14], 24),
+        1 => (block[13], block[14], 28),
+        2 | 3 => (block[9], block[10], 12),
+        _ => return Err(invalid(format!("unsupported superblock version {}", version))),
+    };
+    if ![2, 4, 8].contains(&offset_size) {
+        return Err(invalid(format!("unsupported offset size {}", offset_size)));
+    }
+
+    // Base address, three more addresses and the root's two (version 0/1),
+    // or base, two more and the root (version 2/3)
+    let address_count = if version < 2 { 6 } else { 4 };
+    if block.len() < fields_start + address_count * offset_size as usize {
+        return Err(invalid("superblock is truncated"));
+    }
+
+    let mut cursor = reader::Cursor::new(&block[fields_start..], offset_size, length_size);
+    let base = cursor.offset()?;
+    let root_address = if version < 2 {
+        // Free-space, end-of-file and driver addresses, then the root
+        // group's symbol table entry (link name offset, object header)
+        cursor.skip(3 * offset_size as usize)?;
+        cursor.offset()?;
+        cursor.offset()?
+    } else {
+        // Superblock extension and end-of-file addresses
+        cursor.skip(2 * offset_size as usize)?;
+        cursor.offset()?
+    };
+
+    reader.configure(base, offset_size, length_size)?;
+    Ok((version, root_address))
+}
+
+/// Depth-first traversal of the group hierarchy
+struct Walker {
+    reader: Reader,
+    analysis: Hdf5Analysis,
+    visited: HashSet<u64>,
+}
+
+impl Walker {
+    /// Visit the root group; unlike other objects it must be readable
+    fn visit_root(&mut self, address: u64) -> Result<(), AnalysisError> {
+        let info = object::read_object(&mut self.reader, address)?;
+        self.visited.insert(address);
+        self.record("/", info, 0);
+        Ok(())
+    }
+
+    /// Visit the object at `address`, reached as `path`
+    fn visit(&mut self, path: String, address: u64, depth: usize) {
+        // Hard links can reach an object more than once (or form cycles)
+        if !self.visited.insert(address) {
+            return;
+        }
+        if self.visited.len() > MAX_OBJECTS {
+            if self.visited.len() == MAX_OBJECTS + 1 {
+                self.analysis
+                    .warnings
+                    .push(format!("more than {} objects; the rest are not listed", MAX_OBJECTS));
+            }
+            return;
+        }
+
+        match object::read_object(&mut self.reader, address) {
+            Ok(info) => self.record(&path, info, depth),
+            Err(e) => self.analysis.warnings.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    /// Record a decoded object and descend into groups
+    fn record(&mut self, path: &str, mut info: object::ObjectInfo, depth: usize) {
+        for warning in &info.warnings {
+            self.analysis.warnings.push(format!("{}: {}", path, warning));
+        }
+
+        // An object with a datatype and a dataspace is a dataset
+        if let (Some(datatype), Some(dataspace)) = (info.datatype, info.dataspace.take()) {
+            self.analysis.datasets.push(Hdf5Dataset {
+                path: path.to_string(),
+                shape: dataspace.dims,
+                datatype: datatype.kind,
+                chunks: info.chunks,
+                filters: info.filters,
+                attributes: info.attributes,
+            });
+            return;
+        }
+
+        // Anything else that is not a group (e.g. a committed datatype) is skipped
+        if !info.is_group() && path != "/" {
+            return;
+        }
+
+        self.analysis.groups.push(Hdf5Group {
+            path: path.to_string(),
+            attributes: info.attributes,
+        });
+
+        let links = match info.symbol_table {
+            Some((btree, heap)) => match group::symbol_table_links(&mut self.reader, btree, heap) {
+                Ok(links) => links,
+                Err(e) => {
+                    self.analysis.warnings.push(format!("{}: {}", path, e));
+                    Vec::new()
+                }
+            },
+            None => {
+                let mut links = info.links;
+                links.sort_by(|a, b| a.name.cmp(&b.name));
+                links
+            }
+        };
+
+        if depth >= MAX_DEPTH {
+            if !links.is_empty() {
+                self.analysis
+                    .warnings
+                    .push(format!("{}: groups nested too deeply; members not listed", path));
+            }
+            return;
+        }
+
+        for link in links {
+            let child = if path == "/" {
+                format!("/{}", link.name)
+            } else {
+                format!("{}/{}", path, link.name)
+            };
+            self.visit(child, link.address, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Hand-assembled HDF5 files for tests
+    //!
+    //! Builds a small file following the format specification: a version 0
+    //! superblock, an old-style root group (symbol table, B-tree, local
+    //! heap), a chunked and deflated float dataset with fixed-length,
+    //! variable-length and numeric attributes, and a new-style subgroup
+    //! with a version 2 object header holding a uint16 dataset.
+
+    const UNDEFINED: u64 = u64::MAX;
+
+    /// File image being assembled; addresses are relative to the base
+    struct Image {
+        bytes: Vec<u8>,
+    }
+
+    impl Image {
+        /// Append 8-byte aligned data, returning its address
+        fn put(&mut self, data: &[u8]) -> u64 {
+            while !self.bytes.len().is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let address = self.bytes.len() as u64;
+            self.bytes.extend_from_slice(data);
+            address
+        }
+    }
+
+    fn u16le(v: u16) -> [u8; 2] {
+        v.to_le_bytes()
+    }
+
+    fn u32le(v: u32) -> [u8; 4] {
+        v.to_le_bytes()
+    }
+
+    fn u64le(v: u64) -> [u8; 8] {
+        v.to_le_bytes()
+    }
+
+    fn pad8(data: &[u8]) -> Vec<u8> {
+        let mut out = data.to_vec();
+        while !out.len().is_multiple_of(8) {
+            out.push(0);
+        }
+        out
+    }
+
+    /// Version 1 object header
+    fn object_header_v1(messages: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (kind, data) in messages {
+            let data = pad8(data);
+            body.extend_from_slice(&u16le(*kind));
+            body.extend_from_slice(&u16le(data.len() as u16));
+            body.extend_from_slice(&[0, 0, 0, 0]);
+            body.extend_from_slice(&data);
+        }
+        let mut out = vec![1, 0];
+        out.extend_from_slice(&u16le(messages.len() as u16));
+        out.extend_from_slice(&u32le(1));
+        out.extend_from_slice(&u32le(body.len() as u32));
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Version 2 object header (checksum left as zero; it is not verified)
+    fn object_header_v2(messages: &[(u8, Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (kind, data) in messages {
+            body.push(*kind);
+            body.extend_from_slice(&u16le(data.len() as u16));
+            body.push(0);
+            body.extend_from_slice(data);
+        }
+        let mut out = b"OHDR".to_vec();
+        out.extend_from_slice(&[2, 0, body.len() as u8]);
+        out.extend_from_slice(&body);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out
+    }
+
+    /// Version 1 dataspace with the given dimensions (scalar if empty)
+    fn dataspace_v1(dims: &[u64]) -> Vec<u8> {
+        let mut out = vec![1, dims.len() as u8, 0, 0, 0, 0, 0, 0];
+        for d in dims {
+            out.extend_from_slice(&u64le(*d));
+        }
+        out
+    }
+
+    /// IEEE little-endian float64 datatype
+    fn float64() -> Vec<u8> {
+        let mut out = vec![0x11, 0x20, 0x3F, 0x00];
+        out.extend_from_slice(&u32le(8));
+        out.extend_from_slice(&[0, 0, 64, 0, 52, 11, 0, 52]);
+        out.extend_from_slice(&u32le(1023));
+        out
+    }
+
+    /// Version 1 attribute message
+    fn attribute_v1(name: &str, datatype: &[u8], dataspace: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut name_bytes = name.as_bytes().to_vec();
+        name_bytes.push(0);
+        let mut out = vec![1, 0];
+        out.extend_from_slice(&u16le(name_bytes.len() as u16));
+        out.extend_from_slice(&u16le(datatype.len() as u16));
+        out.extend_from_slice(&u16le(dataspace.len() as u16));
+        out.extend_from_slice(&pad8(&name_bytes));
+        out.extend_from_slice(&pad8(datatype));
+        out.extend_from_slice(&pad8(dataspace));
+        out.extend_from_slice(data);
+        out
+    }
+
+    /// Build the fixture file image; `user_block` bytes of padding precede the superblock
+    pub fn sample_file(user_block: usize) -> Vec<u8> {
+        let mut image = Image { bytes: vec![0; 96] };
+        let long_name = b"Air temperature";
+
+        // Global heap collection holding the variable-length long_name
+        let mut objects = Vec::new();
+        objects.extend_from_slice(&u16le(1));
+        objects.extend_from_slice(&u16le(1));
+        objects.extend_from_slice(&[0, 0, 0, 0]);
+        objects.extend_from_slice(&u64le(long_name.len() as u64));
+        objects.extend_from_slice(&pad8(long_name));
+        objects.extend_from_slice(&[0; 16]); // free space object (index 0)
+        let mut gcol = b"GCOL".to_vec();
+        gcol.extend_from_slice(&[1, 0, 0, 0]);
+        gcol.extend_from_slice(&u64le(16 + objects.len() as u64));
+        gcol.extend_from_slice(&objects);
+        let gcol_address = image.put(&gcol);
+
+        // /temperature: 10x20 float64, chunked 5x10, deflate
+        let mut layout = vec![3, 2, 3];
+        layout.extend_from_slice(&u64le(UNDEFINED));
+        for dim in [5u32, 10, 8] {
+            layout.extend_from_slice(&u32le(dim));
+        }
+        let filters = vec![2, 1, 1, 0, 0, 0, 1, 0, 6, 0, 0, 0];
+
+        let string1 = vec![0x13, 0, 0, 0, 1, 0, 0, 0];
+        let mut vlen_string = vec![0x19, 0x01, 0, 0];
+        vlen_string.extend_from_slice(&u32le(16));
+        vlen_string.extend_from_slice(&[0x10, 0, 0, 0, 1, 0, 0, 0, 0, 0, 8, 0]);
+        let mut vlen_data = u32le(long_name.len() as u32).to_vec();
+        vlen_data.extend_from_slice(&u64le(gcol_address));
+        vlen_data.extend_from_slice(&u32le(1));
+        let mut range = 200.5f64.to_le_bytes().to_vec();
+        range.extend_from_slice(&330.0f64.to_le_bytes());
+
+        let temperature = object_header_v1(&[
+            (0x0001, dataspace_v1(&[10, 20])),
+            (0x0003, float64()),
+            (0x0008, layout),
+            (0x000B, filters),
+            (0x000C, attribute_v1("units", &string1, &dataspace_v1(&[]), b"K")),
+            (0x000C, attribute_v1("long_name", &vlen_string, &dataspace_v1(&[]), &vlen_data)),
+            (0x000C, attribute_v1("valid_range", &float64(), &dataspace_v1(&[2]), &range)),
+        ]);
+        let temperature_address = image.put(&temperature);
+
+        // /raw/counts: 5 uint16, contiguous, version 2 header
+        let mut dataspace_v2 = vec![2, 1, 0, 1];
+        dataspace_v2.extend_from_slice(&u64le(5));
+        let uint16 = vec![0x10, 0, 0, 0, 2, 0, 0, 0, 0, 0, 16, 0];
+        let mut contiguous = vec![3, 1];
+        contiguous.extend_from_slice(&u64le(UNDEFINED));
+        contiguous.extend_from_slice(&u64le(10));
+        let counts = object_header_v2(&[(0x01, dataspace_v2), (0x03, uint16), (0x08, contiguous)]);
+        let counts_address = image.put(&counts);
+
+        // /raw: new-style compact group
+        let mut link_info = vec![0, 0];
+        link_info.extend_from_slice(&u64le(UNDEFINED));
+        link_info.extend_from_slice(&u64le(UNDEFINED));
+        let mut link = vec![1, 0, 6];
+        link.extend_from_slice(b"counts");
+        link.extend_from_slice(&u64le(counts_address));
+        let raw = object_header_v2(&[(0x02, link_info), (0x06, link)]);
+        let raw_address = image.put(&raw);
+
+        // Root group: local heap, symbol table node and B-tree
+        let heap_data = b"\0\0\0\0\0\0\0\0raw\0\0\0\0\0temperature\0\0\0\0\0";
+        let heap_data_address = image.put(heap_data);
+        let mut heap = b"HEAP".to_vec();
+        heap.extend_from_slice(&[0, 0, 0, 0]);
+        heap.extend_from_slice(&u64le(heap_data.len() as u64));
+        heap.extend_from_slice(&u64le(UNDEFINED));
+        heap.extend_from_slice(&u64le(heap_data_address));
+        let heap_address = image.put(&heap);
+
+        let mut snod = b"SNOD".to_vec();
+        snod.extend_from_slice(&[1, 0]);
+        snod.extend_from_slice(&u16le(2));
+        for (name_offset, address) in [(8u64, raw_address), (16, temperature_address)] {
+            snod.extend_from_slice(&u64le(name_offset));
+            snod.extend_from_slice(&u64le(address));
+            snod.extend_from_slice(&[0; 24]);
+        }
+        let snod_address = image.put(&snod);
+
+        let mut tree = b"TREE".to_vec();
+        tree.extend_from_slice(&[0, 0]);
+        tree.extend_from_slice(&u16le(1));
+        tree.extend_from_slice(&u64le(UNDEFINED));
+        tree.extend_from_slice(&u64le(UNDEFINED));
+        tree.extend_from_slice(&u64le(0));
+        tree.extend_from_slice(&u64le(snod_address));
+        tree.extend_from_slice(&u64le(16));
+        let tree_address = image.put(&tree);
+
+        let mut symbol_table = u64le(tree_address).to_vec();
+        symbol_table.extend_from_slice(&u64le(heap_address));
+        let root = object_header_v1(&[(0x0011, symbol_table)]);
+        let root_address = image.put(&root);
+
+        // Version 0 superblock
+        let mut sb = super::SIGNATURE.to_vec();
+        sb.extend_from_slice(&[0, 0, 0, 0, 0, 8, 8, 0]);
+        sb.extend_from_slice(&u16le(4));
+        sb.extend_from_slice(&u16le(16));
+        sb.extend_from_slice(&u32le(0));
+        sb.extend_from_slice(&u64le(user_block as u64));
+        sb.extend_from_slice(&u64le(UNDEFINED));
+        sb.extend_from_slice(&u64le(image.bytes.len() as u64));
+        sb.extend_from_slice(&u64le(UNDEFINED));
+        sb.extend_from_slice(&u64le(0));
+        sb.extend_from_slice(&u64le(root_address));
+        sb.extend_from_slice(&u32le(1));
+        sb.extend_from_slice(&u32le(0));
+        sb.extend_from_slice(&u64le(tree_address));
+        sb.extend_from_slice(&u64le(heap_address));
+        image.bytes[..96].copy_from_slice(&sb);
+
+        let mut file = vec![0; user_block];
+        file.extend_from_slice(&image.bytes);
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Hdf5Datatype;
+    use std::fs;
+
+    fn write_sample(name: &str, user_block: usize) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.h5");
+        fs::write(&path, fixture::sample_file(user_block)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_analyze_hdf5_structure() {
+        let path = write_sample("genesis_preflight_hdf5_structure", 0);
+        let analysis = analyze_hdf5(&path).unwrap();
+
+        assert_eq!(analysis.superblock_version, 0);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+
+        let groups: Vec<&str> = analysis.groups.iter().map(|g| g.path.as_str()).collect();
+        assert_eq!(groups, vec!["/", "/raw"]);
+
+        let paths: Vec<&str> = analysis.datasets.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["/raw/counts", "/temperature"]);
+
+        let counts = &analysis.datasets[0];
+        assert_eq!(counts.shape, vec![5]);
+        assert_eq!(counts.datatype, Hdf5Datatype::Integer { size: 2, signed: false });
+        assert_eq!(counts.chunks, None);
+
+        let temperature = &analysis.datasets[1];
+        assert_eq!(temperature.shape, vec![10, 20]);
+        assert_eq!(temperature.datatype.to_string(), "float64");
+        assert_eq!(temperature.chunks, Some(vec![5, 10]));
+        assert_eq!(temperature.filters, vec![1]);
+        assert_eq!(temperature.attribute("units").unwrap().value, "K");
+        assert_eq!(temperature.attribute("long_name").unwrap().value, "Air temperature");
+        assert_eq!(temperature.attribute("valid_range").unwrap().value, "[200.5, 330]");
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_analyze_hdf5_user_block() {
+        let path = write_sample("genesis_preflight_hdf5_userblock", 512);
+        let analysis = analyze_hdf5(&path).unwrap();
+        assert_eq!(analysis.datasets.len(), 2);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_analyze_hdf5_rejects_garbage() {
+        let dir = std::env::temp_dir().join("genesis_preflight_hdf5_garbage");
+        fs::create_dir_all(&dir).unwrap();
+
+        // Valid signature, truncated superblock
+        let path = dir.join("broken.h5");
+        fs::write(&path, SIGNATURE).unwrap();
+        assert!(analyze_hdf5(&path).is_err());
+
+        // Version 0 superblock cut off before its addresses start
+        let mut bytes = fixture::sample_file(0);
+        bytes.truncate(18);
+        fs::write(&path, &bytes).unwrap();
+        let error = analyze_hdf5(&path).unwrap_err();
+        assert!(error.to_string().contains("superblock is truncated"), "{}", error);
+
+        // Root object header points past the end of the file
+        let mut bytes = fixture::sample_file(0);
+        bytes[64..72].copy_from_slice(&u64::MAX.to_le_bytes()[..]);
+        fs::write(&path, &bytes).unwrap();
+        assert!(analyze_hdf5(&path).is_err());
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,637 @@
+//! HDF5 object headers and the header messages we care about
+
+use super::reader::{invalid, Cursor, Reader};
+use crate::analyzer::AnalysisError;
+use crate::types::{Hdf5Attribute, Hdf5Datatype};
+use std::collections::VecDeque;
+
+/// Dataspace message
+const MSG_DATASPACE: u16 = 0x0001;
+/// Link info message (new-style groups)
+const MSG_LINK_INFO: u16 = 0x0002;
+/// Datatype message
+const MSG_DATATYPE: u16 = 0x0003;
+/// Link message (new-style compact groups)
+const MSG_LINK: u16 = 0x0006;
+/// Data layout message
+const MSG_LAYOUT: u16 = 0x0008;
+/// Filter pipeline message
+const MSG_FILTERS: u16 = 0x000B;
+/// Attribute message
+const MSG_ATTRIBUTE: u16 = 0x000C;
+/// Object header continuation message
+const MSG_CONTINUATION: u16 = 0x0010;
+/// Symbol table message (old-style groups)
+const MSG_SYMBOL_TABLE: u16 = 0x0011;
+/// Attribute info message
+const MSG_ATTRIBUTE_INFO: u16 = 0x0015;
+
+/// Message flag: the message is stored elsewhere and shared
+const FLAG_SHARED: u8 = 0x02;
+
+/// Most values rendered for an attribute before eliding the rest
+const MAX_RENDERED_VALUES: usize = 8;
+
+/// Most continuation blocks followed for one object header
+const MAX_CONTINUATIONS: usize = 256;
+
+/// A raw header message
+struct Message {
+    kind: u16,
+    flags: u8,
+    data: Vec<u8>,
+}
+
+/// Decoded datatype with the layout details needed to read values
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Datatype {
+    pub kind: Hdf5Datatype,
+    pub big_endian: bool,
+}
+
+/// Decoded dataspace
+#[derive(Debug, Clone)]
+pub(super) struct Dataspace {
+    pub dims: Vec<u64>,
+    pub is_null: bool,
+}
+
+impl Dataspace {
+    /// Number of elements (1 for scalars, 0 for null dataspaces)
+    fn element_count(&self) -> u64 {
+        if self.is_null {
+            0
+        } else {
+            self.dims.iter().product()
+        }
+    }
+}
+
+/// A hard link from a group to a child object
+#[derive(Debug, Clone)]
+pub(super) struct Link {
+    pub name: String,
+    pub address: u64,
+}
+
+/// Everything we extract from one object header
+#[derive(Debug, Default)]
+pub(super) struct ObjectInfo {
+    pub datatype: Option<Datatype>,
+    pub dataspace: Option<Dataspace>,
+    pub chunks: Option<Vec<u64>>,
+    pub filters: Vec<u16>,
+    pub attributes: Vec<Hdf5Attribute>,
+    /// Old-style group: (v1 B-tree address, local heap address)
+    pub symbol_table: Option<(u64, u64)>,
+    /// New-style compact group links
+    pub links: Vec<Link>,
+    /// Whether the object is a new-style group (has a link info message)
+    pub has_link_info: bool,
+    /// Things that could not be decoded
+    pub warnings: Vec<String>,
+}
+
+impl ObjectInfo {
+    /// Whether the object is a group rather than a dataset
+    pub fn is_group(&self) -> bool {
+        self.symbol_table.is_some() || self.has_link_info || !self.links.is_empty()
+    }
+}
+
+/// Read and decode the object header at `address`
+pub(super) fn read_object(reader: &mut Reader, address: u64) -> Result<ObjectInfo, AnalysisError> {
+    let messages = read_messages(reader, address)?;
+    let mut info = ObjectInfo::default();
+
+    for message in messages {
+        if message.flags & FLAG_SHARED != 0 {
+            if message.kind == MSG_DATATYPE {
+                match shared_datatype(reader, &message.data) {
+                    Ok(datatype) => info.datatype = Some(datatype),
+                    Err(e) => info.warnings.push(format!("shared datatype: {}", e)),
+                }
+            } else if matches!(message.kind, MSG_DATASPACE | MSG_FILTERS | MSG_ATTRIBUTE) {
+                info.warnings
+                    .push(format!("shared message type {} is not supported", message.kind));
+            }
+            continue;
+        }
+
+        let result = match message.kind {
+            MSG_DATASPACE => parse_dataspace(reader, &message.data).map(|d| info.dataspace = Some(d)),
+            MSG_DATATYPE => parse_datatype(&message.data).map(|d| info.datatype = Some(d)),
+            MSG_LAYOUT => parse_layout(reader, &message.data).map(|c| info.chunks = c),
+            MSG_FILTERS => parse_filters(&message.data).map(|f| info.filters = f),
+            MSG_ATTRIBUTE => parse_attribute(reader, &message.data).map(|a| info.attributes.push(a)),
+            MSG_SYMBOL_TABLE => {
+                let mut cursor = reader.cursor(&message.data);
+                cursor
+                    .offset()
+                    .and_then(|btree| Ok((btree, cursor.offset()?)))
+                    .map(|table| info.symbol_table = Some(table))
+            }
+            MSG_LINK => parse_link(reader, &message.data).map(|link| info.links.extend(link)),
+            MSG_LINK_INFO => {
+                info.has_link_info = true;
+                dense_storage_address(reader, &message.data, true).map(|heap| {
+                    if heap.is_some() {
+                        info.warnings.push(
+                            "dense link storage (fractal heap) is not supported; group members are not listed"
+                                .to_string(),
+                        );
+                    }
+                })
+            }
+            MSG_ATTRIBUTE_INFO => dense_storage_address(reader, &message.data, false).map(|heap| {
+                if heap.is_some() {
+                    info.warnings.push(
+                        "dense attribute storage (fractal heap) is not supported; some attributes are not listed"
+                            .to_string(),
+                    );
+                }
+            }),
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            info.warnings
+                .push(format!("header message type {}: {}", message.kind, e));
+        }
+    }
+
+    Ok(info)
+}
+
+/// Collect all header messages, following continuation blocks
+fn read_messages(reader: &mut Reader, address: u64) -> Result<Vec<Message>, AnalysisError> {
+    let prefix = reader.read_at_most(address, 16)?;
+    if prefix.len() >= 4 && &prefix[..4] == b"OHDR" {
+        read_messages_v2(reader, address)
+    } else if prefix.first() == Some(&1) {
+        read_messages_v1(reader, address, &prefix)
+    } else {
+        Err(invalid(format!("no object header at address {}", address)))
+    }
+}
+
+/// Version 1 object header: 16-byte prefix, 8-byte aligned messages
+fn read_messages_v1(reader: &mut Reader, address: u64, prefix: &[u8]) -> Result<Vec<Message>, AnalysisError> {
+    let mut cursor = reader.cursor(prefix);
+    cursor.skip(2)?;
+    let message_count = cursor.u16()? as usize;
+    cursor.skip(4)?;
+    let header_size = cursor.u32()? as u64;
+
+    let mut messages = Vec::new();
+    let mut blocks = VecDeque::from([(address + 16, header_size)]);
+    let mut followed = 0;
+
+    while let Some((block_address, block_len)) = blocks.pop_front() {
+        let block = reader.read_at(block_address, block_len)?;
+        let mut cursor = reader.cursor(&block);
+
+        while cursor.remaining() >= 8 && messages.len() < message_count {
+            let kind = cursor.u16()?;
+            let size = cursor.u16()? as usize;
+            let flags = cursor.u8()?;
+            cursor.skip(3)?;
+            let data = cursor.bytes(size)?.to_vec();
+            cursor.align(8);
+
+            if kind == MSG_CONTINUATION {
+                followed += 1;
+                if followed > MAX_CONTINUATIONS {
+                    return Err(invalid("too many object header continuations"));
+                }
+                let mut c = reader.cursor(&data);
+                blocks.push_back((c.offset()?, c.length()?));
+            }
+            messages.push(Message { kind, flags, data });
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Version 2 object header: `OHDR` signature, compact message headers, checksums
+fn read_messages_v2(reader: &mut Reader, address: u64) -> Result<Vec<Message>, AnalysisError> {
+    let header = reader.read_at_most(address, 32)?;
+    let mut cursor = reader.cursor(&header);
+    cursor.skip(4)?;
+    if cursor.u8()? != 2 {
+        return Err(invalid("unsupported object header version"));
+    }
+    let flags = cursor.u8()?;
+    if flags & 0x20 != 0 {
+        cursor.skip(16)?; // access, modification, change and birth times
+    }
+    if flags & 0x10 != 0 {
+        cursor.skip(4)?; // attribute storage phase change values
+    }
+    let chunk_size = cursor.uint(1 << (flags & 0x03))?;
+    let tracks_order = flags & 0x04 != 0;
+
+    let mut messages = Vec::new();
+    let mut blocks = VecDeque::from([(address + cursor.position() as u64, chunk_size)]);
+    let mut followed = 0;
+
+    while let Some((block_address, block_len)) = blocks.pop_front() {
+        let block = reader.read_at(block_address, block_len)?;
+        let mut cursor = reader.cursor(&block);
+        let message_header_len = if tracks_order { 6 } else { 4 };
+
+        while cursor.remaining() >= message_header_len {
+            let kind = cursor.u8()? as u16;
+            let size = cursor.u16()? as usize;
+            let message_flags = cursor.u8()?;
+            if tracks_order {
+                cursor.skip(2)?;
+            }
+            let data = cursor.bytes(size)?.to_vec();
+
+            if kind == MSG_CONTINUATION {
+                followed += 1;
+                if followed > MAX_CONTINUATIONS {
+                    return Err(invalid("too many object header continuations"));
+                }
+                let mut c = reader.cursor(&data);
+                let (offset, length) = (c.offset()?, c.length()?);
+                // Skip the OCHK signature; the length includes it and the checksum
+                if length >= 8 {
+                    blocks.push_back((offset + 4, length - 8));
+                }
+            }
+            messages.push(Message {
+                kind,
+                flags: message_flags,
+                data,
+            });
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Decode a dataspace message
+fn parse_dataspace(reader: &Reader, data: &[u8]) -> Result<Dataspace, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    let version = cursor.u8()?;
+    let rank = cursor.u8()? as usize;
+    let flags = cursor.u8()?;
+
+    let is_null = match version {
+        1 => {
+            cursor.skip(5)?;
+            false
+        }
+        2 => cursor.u8()? == 2,
+        _ => return Err(invalid(format!("unsupported dataspace version {}", version))),
+    };
+
+    let mut dims = Vec::with_capacity(rank);
+    for _ in 0..rank {
+        dims.push(cursor.length()?);
+    }
+    // Maximum dimensions (flags & 1) follow but are not needed
+    let _ = flags;
+
+    Ok(Dataspace { dims, is_null })
+}
+
+/// Decode a datatype message
+pub(super) fn parse_datatype(data: &[u8]) -> Result<Datatype, AnalysisError> {
+    let mut cursor = Cursor::new(data, 8, 8);
+    let class_and_version = cursor.u8()?;
+    let bits = cursor.bytes(3)?;
+    let size = cursor.u32()?;
+
+    let class = class_and_version & 0x0F;
+    let big_endian = bits[0] & 0x01 != 0;
+
+    let kind = match class {
+        0 => Hdf5Datatype::Integer {
+            size,
+            signed: bits[0] & 0x08 != 0,
+        },
+        1 => Hdf5Datatype::Float { size },
+        2 => Hdf5Datatype::Time,
+        3 => Hdf5Datatype::String { size },
+        4 => Hdf5Datatype::Bitfield,
+        5 => Hdf5Datatype::Opaque,
+        6 => Hdf5Datatype::Compound {
+            members: u16::from_le_bytes([bits[0], bits[1]]) as u32,
+        },
+        7 => Hdf5Datatype::Reference,
+        8 => Hdf5Datatype::Enum,
+        9 if bits[0] & 0x0F == 1 => Hdf5Datatype::VarString,
+        9 => Hdf5Datatype::VarSequence,
+        10 => Hdf5Datatype::Array,
+        _ => Hdf5Datatype::Unknown,
+    };
+
+    Ok(Datatype { kind, big_endian })
+}
+
+/// Resolve a shared (committed) datatype to its definition
+fn shared_datatype(reader: &mut Reader, data: &[u8]) -> Result<Datatype, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    let version = cursor.u8()?;
+    let kind = cursor.u8()?;
+    let address = match version {
+        1 => {
+            cursor.skip(6)?;
+            cursor.offset()?
+        }
+        2 => cursor.offset()?,
+        3 if kind == 2 => cursor.offset()?,
+        _ => return Err(invalid("datatypes in the shared message heap are not supported")),
+    };
+
+    let messages = read_messages(reader, address)?;
+    messages
+        .iter()
+        .find(|m| m.kind == MSG_DATATYPE && m.flags & FLAG_SHARED == 0)
+        .ok_or_else(|| invalid("committed datatype has no datatype message"))
+        .and_then(|m| parse_datatype(&m.data))
+}
+
+/// Decode a data layout message, returning chunk dimensions if chunked
+fn parse_layout(reader: &Reader, data: &[u8]) -> Result<Option<Vec<u64>>, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    let version = cursor.u8()?;
+
+    let dims = match version {
+        1 | 2 => {
+            let rank = cursor.u8()? as usize;
+            let class = cursor.u8()?;
+            cursor.skip(5)?;
+            if class != 2 {
+                return Ok(None);
+            }
+            cursor.offset()?;
+            (0..rank).map(|_| cursor.u32().map(u64::from)).collect::<Result<Vec<_>, _>>()?
+        }
+        3 => {
+            if cursor.u8()? != 2 {
+                return Ok(None);
+            }
+            let rank = cursor.u8()? as usize;
+            cursor.offset()?;
+            (0..rank).map(|_| cursor.u32().map(u64::from)).collect::<Result<Vec<_>, _>>()?
+        }
+        4 | 5 => {
+            if cursor.u8()? != 2 {
+                return Ok(None);
+            }
+            cursor.skip(1)?; // flags
+            let rank = cursor.u8()? as usize;
+            let width = cursor.u8()? as usize;
+            if width == 0 || width > 8 {
+                return Err(invalid("invalid chunk dimension width"));
+            }
+            (0..rank).map(|_| cursor.uint(width)).collect::<Result<Vec<_>, _>>()?
+        }
+        _ => return Err(invalid(format!("unsupported layout version {}", version))),
+    };
+
+    // The last chunk dimension is the element size, not a data dimension
+    Ok(Some(dims[..dims.len().saturating_sub(1)].to_vec()))
+}
+
+/// Decode a filter pipeline message into filter ids
+fn parse_filters(data: &[u8]) -> Result<Vec<u16>, AnalysisError> {
+    let mut cursor = Cursor::new(data, 8, 8);
+    let version = cursor.u8()?;
+    let count = cursor.u8()? as usize;
+    if version == 1 {
+        cursor.skip(6)?;
+    }
+
+    let mut ids = Vec::with_capacity(count);
+    for _ in 0..count {
+        let id = cursor.u16()?;
+        let name_len = if version == 1 || id >= 256 {
+            cursor.u16()? as usize
+        } else {
+            0
+        };
+        cursor.skip(2)?; // flags
+        let values = cursor.u16()? as usize;
+        if version == 1 {
+            cursor.skip(name_len.div_ceil(8) * 8)?;
+            cursor.skip(values * 4)?;
+            if values % 2 == 1 {
+                cursor.skip(4)?;
+            }
+        } else {
+            cursor.skip(name_len)?;
+            cursor.skip(values * 4)?;
+        }
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
+
+/// Decode an attribute message, rendering its value
+fn parse_attribute(reader: &mut Reader, data: &[u8]) -> Result<Hdf5Attribute, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    let version = cursor.u8()?;
+    let flags = cursor.u8()?;
+    let name_size = cursor.u16()? as usize;
+    let datatype_size = cursor.u16()? as usize;
+    let dataspace_size = cursor.u16()? as usize;
+    if version == 3 {
+        cursor.skip(1)?; // name character set
+    }
+
+    let pad = |n: usize| if version == 1 { n.div_ceil(8) * 8 } else { n };
+
+    let name_bytes = cursor.bytes(pad(name_size))?;
+    let name = c_string(&name_bytes[..name_size.min(name_bytes.len())]);
+
+    if version > 1 && flags & 0x03 != 0 {
+        return Ok(Hdf5Attribute {
+            name,
+            datatype: Hdf5Datatype::Unknown,
+            value: "<shared datatype>".to_string(),
+        });
+    }
+
+    let datatype = parse_datatype(cursor.bytes(pad(datatype_size))?)?;
+    let dataspace = parse_dataspace(reader, cursor.bytes(pad(dataspace_size))?)?;
+    let count = dataspace.element_count();
+    let raw = &data[cursor.position()..];
+
+    let value = render_value(reader, &datatype, count, raw)
+        .unwrap_or_else(|_| format!("<{}>", datatype.kind));
+    let value = if dataspace.dims.is_empty() || count == 1 {
+        value
+    } else {
+        format!("[{}]", value)
+    };
+
+    Ok(Hdf5Attribute {
+        name,
+        datatype: datatype.kind,
+        value,
+    })
+}
+
+/// Render up to `MAX_RENDERED_VALUES` elements of attribute data
+fn render_value(reader: &mut Reader, datatype: &Datatype, count: u64, raw: &[u8]) -> Result<String, AnalysisError> {
+    let shown = count.min(MAX_RENDERED_VALUES as u64) as usize;
+    let mut values = Vec::with_capacity(shown);
+
+    match datatype.kind {
+        Hdf5Datatype::Integer { size, signed } if size <= 8 => {
+            let mut cursor = Cursor::new(raw, 8, 8);
+            for _ in 0..shown {
+                let bytes = cursor.bytes(size as usize)?;
+                let value = decode_uint(bytes, datatype.big_endian);
+                if signed {
+                    let shift = 64 - size * 8;
+                    values.push((((value << shift) as i64) >> shift).to_string());
+                } else {
+                    values.push(value.to_string());
+                }
+            }
+        }
+        Hdf5Datatype::Float { size } if size == 4 || size == 8 => {
+            let mut cursor = Cursor::new(raw, 8, 8);
+            for _ in 0..shown {
+                let bits = decode_uint(cursor.bytes(size as usize)?, datatype.big_endian);
+                if size == 4 {
+                    values.push(f32::from_bits(bits as u32).to_string());
+                } else {
+                    values.push(f64::from_bits(bits).to_string());
+                }
+            }
+        }
+        Hdf5Datatype::String { size } => {
+            let mut cursor = Cursor::new(raw, 8, 8);
+            for _ in 0..shown {
+                values.push(c_string(cursor.bytes(size as usize)?).trim_end().to_string());
+            }
+        }
+        Hdf5Datatype::VarString => {
+            let mut cursor = reader.cursor(raw);
+            for _ in 0..shown {
+                let length = cursor.u32()? as usize;
+                let collection = cursor.offset()?;
+                let index = cursor.u32()?;
+                if length == 0 || reader.is_undefined(collection) {
+                    values.push(String::new());
+                    continue;
+                }
+                let bytes = reader.global_heap_object(collection, index)?;
+                values.push(c_string(&bytes[..length.min(bytes.len())]));
+            }
+        }
+        _ => return Err(invalid("value not rendered")),
+    }
+
+    let mut text = values.join(", ");
+    if count > shown as u64 {
+        text.push_str(&format!(", ... ({} values)", count));
+    }
+    Ok(text)
+}
+
+/// Decode a link message; soft and external links yield `None`
+fn parse_link(reader: &Reader, data: &[u8]) -> Result<Option<Link>, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    if cursor.u8()? != 1 {
+        return Err(invalid("unsupported link message version"));
+    }
+    let flags = cursor.u8()?;
+    let link_type = if flags & 0x08 != 0 { cursor.u8()? } else { 0 };
+    if flags & 0x04 != 0 {
+        cursor.skip(8)?; // creation order
+    }
+    if flags & 0x10 != 0 {
+        cursor.skip(1)?; // character set
+    }
+    let name_len = cursor.uint(1 << (flags & 0x03))? as usize;
+    let name = String::from_utf8_lossy(cursor.bytes(name_len)?).to_string();
+
+    if link_type != 0 {
+        return Ok(None);
+    }
+    Ok(Some(Link {
+        name,
+        address: cursor.offset()?,
+    }))
+}
+
+/// Fractal heap address of a link/attribute info message, if dense storage is used
+fn dense_storage_address(reader: &Reader, data: &[u8], is_link_info: bool) -> Result<Option<u64>, AnalysisError> {
+    let mut cursor = reader.cursor(data);
+    cursor.skip(1)?; // version
+    let flags = cursor.u8()?;
+    if flags & 0x01 != 0 {
+        // Maximum creation index: 8 bytes for links, 2 for attributes
+        cursor.skip(if is_link_info { 8 } else { 2 })?;
+    }
+    let heap = cursor.offset()?;
+    Ok(if reader.is_undefined(heap) { None } else { Some(heap) })
+}
+
+/// Decode an unsigned integer of up to 8 bytes
+fn decode_uint(bytes: &[u8], big_endian: bool) -> u64 {
+    let fold = |acc: u64, b: &u8| (acc << 8) | *b as u64;
+    if big_endian {
+        bytes.iter().fold(0, fold)
+    } else {
+        bytes.iter().rev().fold(0, fold)
+    }
+}
+
+/// Text up to the first NUL byte
+pub(super) fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datatype_classes() {
+        // Signed 32-bit little-endian integer
+        let int = parse_datatype(&[0x10, 0x08, 0, 0, 4, 0, 0, 0]).unwrap();
+        assert_eq!(int.kind, Hdf5Datatype::Integer { size: 4, signed: true });
+        assert_eq!(int.kind.to_string(), "int32");
+
+        // Big-endian 64-bit float
+        let float = parse_datatype(&[0x11, 0x01, 0, 0, 8, 0, 0, 0]).unwrap();
+        assert_eq!(float.kind.to_string(), "float64");
+        assert!(float.big_endian);
+
+        // Variable-length UTF-8 string
+        let vlen = parse_datatype(&[0x19, 0x01, 0x01, 0, 16, 0, 0, 0]).unwrap();
+        assert_eq!(vlen.kind, Hdf5Datatype::VarString);
+    }
+
+    #[test]
+    fn test_parse_filters_versions() {
+        // Version 2: deflate (level 4) then fletcher32
+        let v2 = [2, 2, 1, 0, 0, 0, 1, 0, 4, 0, 0, 0, 3, 0, 0, 0, 0, 0];
+        assert_eq!(parse_filters(&v2).unwrap(), vec![1, 3]);
+
+        // Version 1: shuffle with one client value and an 8-byte name
+        let mut v1 = vec![1, 1, 0, 0, 0, 0, 0, 0];
+        v1.extend_from_slice(&[2, 0, 8, 0, 0, 0, 1, 0]);
+        v1.extend_from_slice(b"shuffle\0");
+        v1.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(parse_filters(&v1).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_decode_uint_endianness() {
+        assert_eq!(decode_uint(&[0x01, 0x02], false), 0x0201);
+        assert_eq!(decode_uint(&[0x01, 0x02], true), 0x0102);
+    }
+}
@@ -0,0 +1,258 @@
+//! Random-access reads and little-endian decoding for HDF5 metadata
+
+use crate::analyzer::AnalysisError;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Largest single metadata block we are willing to read
+const MAX_BLOCK_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Build an `InvalidFormat` error with an `HDF5:` prefix
+pub(super) fn invalid(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidFormat(format!("HDF5: {}", message.into()))
+}
+
+/// Reads metadata blocks from an HDF5 file
+///
+/// Addresses are relative to the superblock's base address, as they are
+/// everywhere in the format.
+pub(super) struct Reader {
+    file: File,
+    file_len: u64,
+    base: u64,
+    /// Size of file addresses in bytes
+    pub offset_size: u8,
+    /// Size of lengths in bytes
+    pub length_size: u8,
+    global_heaps: HashMap<u64, Vec<u8>>,
+}
+
+impl Reader {
+    /// Wrap an open file; offset/length sizes are set once the superblock is read
+    pub fn new(file: File, file_len: u64) -> Self {
+        Reader {
+            file,
+            file_len,
+            base: 0,
+            offset_size: 8,
+            length_size: 8,
+            global_heaps: HashMap::new(),
+        }
+    }
+
+    /// Set the base address and field sizes from the superblock
+    pub fn configure(&mut self, base: u64, offset_size: u8, length_size: u8) -> Result<(), AnalysisError> {
+        if ![2, 4, 8].contains(&offset_size) || ![2, 4, 8].contains(&length_size) {
+            return Err(invalid(format!(
+                "unsupported field sizes (offsets {}, lengths {})",
+                offset_size, length_size
+            )));
+        }
+        self.base = base;
+        self.offset_size = offset_size;
+        self.length_size = length_size;
+        Ok(())
+    }
+
+    /// Total size of the file in bytes
+    pub fn file_len(&self) -> u64 {
+        self.file_len
+    }
+
+    /// Read `len` bytes at an absolute file position
+    pub fn read_absolute(&mut self, pos: u64, len: u64) -> Result<Vec<u8>, AnalysisError> {
+        if len > MAX_BLOCK_SIZE {
+            return Err(invalid(format!("metadata block of {} bytes is too large", len)));
+        }
+        let end = pos
+            .checked_add(len)
+            .filter(|&end| end <= self.file_len)
+            .ok_or_else(|| invalid(format!("address {} is past the end of the file", pos)))?;
+
+        let mut buffer = vec![0u8; (end - pos) as usize];
+        self.file.seek(SeekFrom::Start(pos))?;
+        self.file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Read `len` bytes at a file address
+    pub fn read_at(&mut self, address: u64, len: u64) -> Result<Vec<u8>, AnalysisError> {
+        let pos = self
+            .base
+            .checked_add(address)
+            .ok_or_else(|| invalid("address overflow"))?;
+        self.read_absolute(pos, len)
+    }
+
+    /// Read up to `len` bytes at a file address, stopping at end of file
+    pub fn read_at_most(&mut self, address: u64, len: u64) -> Result<Vec<u8>, AnalysisError> {
+        let pos = self.base.saturating_add(address);
+        let available = self.file_len.saturating_sub(pos);
+        self.read_absolute(pos, len.min(available))
+    }
+
+    /// Whether an address is the "undefined address" (all bits set)
+    pub fn is_undefined(&self, address: u64) -> bool {
+        address == undefined_address(self.offset_size)
+    }
+
+    /// Cursor over a block using this file's field sizes
+    pub fn cursor<'a>(&self, data: &'a [u8]) -> Cursor<'a> {
+        Cursor::new(data, self.offset_size, self.length_size)
+    }
+
+    /// Fetch an object from a global heap collection (variable-length data)
+    pub fn global_heap_object(&mut self, collection: u64, index: u32) -> Result<Vec<u8>, AnalysisError> {
+        if !self.global_heaps.contains_key(&collection) {
+            let header_len = 8 + self.length_size as u64;
+            let header = self.read_at(collection, header_len)?;
+            if &header[..4] != b"GCOL" {
+                return Err(invalid("global heap collection signature not found"));
+            }
+            let size = self.cursor(&header[8..]).length()?;
+            let data = self.read_at_most(collection, size)?;
+            self.global_heaps.insert(collection, data);
+        }
+
+        let data = &self.global_heaps[&collection];
+        let mut cursor = Cursor::new(data, self.offset_size, self.length_size);
+        cursor.skip(8 + self.length_size as usize)?;
+
+        while cursor.remaining() >= 8 + self.length_size as usize {
+            let object_index = cursor.u16()?;
+            cursor.skip(6)?;
+            let size = cursor.length()? as usize;
+            if object_index == 0 {
+                break; // free space runs to the end of the collection
+            }
+            if object_index as u32 == index {
+                return Ok(cursor.bytes(size)?.to_vec());
+            }
+            cursor.skip(size)?;
+            cursor.align(8);
+        }
+
+        Err(invalid(format!("global heap object {} not found", index)))
+    }
+}
+
+/// The undefined address for a given offset size
+pub(super) fn undefined_address(offset_size: u8) -> u64 {
+    if offset_size >= 8 {
+        u64::MAX
+    } else {
+        (1u64 << (offset_size as u32 * 8)) - 1
+    }
+}
+
+/// Little-endian decoder over a metadata block
+pub(super) struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    offset_size: u8,
+    length_size: u8,
+}
+
+impl<'a> Cursor<'a> {
+    /// Create a cursor at the start of `data`
+    pub fn new(data: &'a [u8], offset_size: u8, length_size: u8) -> Self {
+        Cursor {
+            data,
+            pos: 0,
+            offset_size,
+            length_size,
+        }
+    }
+
+    /// Current position within the block
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Bytes left in the block
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    /// Take the next `n` bytes
+    pub fn bytes(&mut self, n: usize) -> Result<&'a [u8], AnalysisError> {
+        if n > self.remaining() {
+            return Err(invalid("metadata block is truncated"));
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    /// Skip `n` bytes
+    pub fn skip(&mut self, n: usize) -> Result<(), AnalysisError> {
+        self.bytes(n).map(|_| ())
+    }
+
+    /// Advance to the next multiple of `alignment` (relative to the block start)
+    pub fn align(&mut self, alignment: usize) {
+        let rem = self.pos % alignment;
+        if rem != 0 {
+            self.pos = (self.pos + alignment - rem).min(self.data.len());
+        }
+    }
+
+    /// Read an unsigned little-endian integer of `n` bytes (n <= 8)
+    pub fn uint(&mut self, n: usize) -> Result<u64, AnalysisError> {
+        let bytes = self.bytes(n)?;
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    /// Read a `u8`
+    pub fn u8(&mut self) -> Result<u8, AnalysisError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Read a little-endian `u16`
+    pub fn u16(&mut self) -> Result<u16, AnalysisError> {
+        Ok(self.uint(2)? as u16)
+    }
+
+    /// Read a little-endian `u32`
+    pub fn u32(&mut self) -> Result<u32, AnalysisError> {
+        Ok(self.uint(4)? as u32)
+    }
+
+    /// Read a file address
+    pub fn offset(&mut self) -> Result<u64, AnalysisError> {
+        self.uint(self.offset_size as usize)
+    }
+
+    /// Read a length
+    pub fn length(&mut self) -> Result<u64, AnalysisError> {
+        self.uint(self.length_size as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_decoding() {
+        let data = [0x01, 0x34, 0x12, 0x78, 0x56, 0x34, 0x12, 0xAA];
+        let mut cursor = Cursor::new(&data, 4, 4);
+
+        assert_eq!(cursor.u8().unwrap(), 1);
+        assert_eq!(cursor.u16().unwrap(), 0x1234);
+        assert_eq!(cursor.offset().unwrap(), 0x1234_5678);
+        cursor.align(8);
+        assert_eq!(cursor.remaining(), 0);
+        assert!(cursor.u8().is_err());
+    }
+
+    #[test]
+    fn test_undefined_address() {
+        assert_eq!(undefined_address(8), u64::MAX);
+        assert_eq!(undefined_address(4), 0xFFFF_FFFF);
+    }
+}
@@ -0,0 +1,376 @@
+//! PNG and JPEG header parsing
+//!
+//! Reads image dimensions and sample layout without decoding pixels: the
+//! PNG `IHDR` chunk (plus `acTL` for animation and `eXIf` for orientation)
+//! and the JPEG start-of-frame marker (plus the EXIF `APP1` segment).
+
+use super::AnalysisError;
+use crate::types::{AnalysisResult, ImageInfo};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// PNG file signature
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Most PNG chunks or JPEG segments examined before giving up
+const MAX_SEGMENTS: usize = 10_000;
+
+/// Largest ancillary chunk or segment read into memory
+const MAX_METADATA_SIZE: u32 = 1 << 20;
+
+/// EXIF tag holding the orientation
+const ORIENTATION_TAG: u16 = 0x0112;
+
+/// Read the header fields of a PNG or JPEG image
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with the PNG signature or a JPEG SOI marker
+///
+/// # Returns
+///
+/// Dimensions, bit depth, colour type, channel count, frame count and EXIF
+/// orientation.
+pub fn analyze_image(path: &Path) -> Result<ImageInfo, AnalysisError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic[..2])?;
+    if magic[..2] == [0xFF, 0xD8] {
+        return read_jpeg(&mut reader);
+    }
+    reader.read_exact(&mut magic[2..])?;
+    if &magic == PNG_SIGNATURE {
+        return read_png(&mut reader);
+    }
+    Err(AnalysisError::InvalidFormat("not a PNG or JPEG image".to_string()))
+}
+
+/// Walk PNG chunks up to the first `IDAT`
+fn read_png<R: Read + Seek>(reader: &mut R) -> Result<ImageInfo, AnalysisError> {
+    let mut info: Option<ImageInfo> = None;
+
+    for _ in 0..MAX_SEGMENTS {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let kind = &header[4..8];
+
+        match kind {
+            b"IHDR" | b"acTL" | b"eXIf" if length <= MAX_METADATA_SIZE => {
+                let mut data = vec![0u8; length as usize];
+                reader.read_exact(&mut data)?;
+                reader.seek(SeekFrom::Current(4))?;
+                match (kind, info.as_mut()) {
+                    (b"IHDR", None) => info = Some(parse_ihdr(&data)?),
+                    (b"acTL", Some(info)) if data.len() >= 4 => {
+                        info.frames = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+                    }
+                    (b"eXIf", Some(info)) => info.orientation = exif_orientation(&data),
+                    _ => {}
+                }
+            }
+            b"IDAT" | b"IEND" => break,
+            _ if info.is_none() => {
+                return Err(AnalysisError::InvalidFormat("IHDR is not the first chunk".to_string()))
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64 + 4))?;
+            }
+        }
+    }
+
+    info.ok_or_else(|| AnalysisError::InvalidFormat("missing IHDR chunk".to_string()))
+}
+
+/// Decode the PNG `IHDR` chunk
+fn parse_ihdr(data: &[u8]) -> Result<ImageInfo, AnalysisError> {
+    if data.len() < 13 {
+        return Err(AnalysisError::InvalidFormat("IHDR is shorter than 13 bytes".to_string()));
+    }
+    let (color_type, channels) = match data[9] {
+        0 => ("grayscale", 1),
+        2 => ("RGB", 3),
+        3 => ("palette", 3),
+        4 => ("grayscale+alpha", 2),
+        6 => ("RGBA", 4),
+        other => {
+            return Err(AnalysisError::InvalidFormat(format!(
+                "unknown PNG colour type {}",
+                other
+            )))
+        }
+    };
+    Ok(ImageInfo {
+        width: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+        height: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+        bit_depth: data[8],
+        color_type: color_type.to_string(),
+        channels,
+        frames: 1,
+        orientation: None,
+    })
+}
+
+/// Walk JPEG marker segments up to the start of scan
+fn read_jpeg<R: Read + Seek>(reader: &mut R) -> Result<ImageInfo, AnalysisError> {
+    let mut info: Option<ImageInfo> = None;
+    let mut orientation = None;
+
+    for _ in 0..MAX_SEGMENTS {
+        // Markers are 0xFF followed by a code; extra 0xFF bytes are fill
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        if byte[0] != 0xFF {
+            continue;
+        }
+        let mut marker = 0xFF;
+        while marker == 0xFF {
+            reader.read_exact(&mut byte)?;
+            marker = byte[0];
+        }
+        match marker {
+            // Standalone markers without a length
+            0x00 | 0x01 | 0xD0..=0xD7 => continue,
+            // Start of scan or end of image: the header is over
+            0xDA | 0xD9 => break,
+            _ => {}
+        }
+
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length)?;
+        let length = u16::from_be_bytes(length).saturating_sub(2) as u32;
+
+        let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if (is_sof || marker == 0xE1) && length <= MAX_METADATA_SIZE {
+            let mut data = vec![0u8; length as usize];
+            reader.read_exact(&mut data)?;
+            if is_sof && info.is_none() {
+                info = Some(parse_sof(&data)?);
+            } else if marker == 0xE1 && data.starts_with(b"Exif\0\0") {
+                orientation = exif_orientation(&data[6..]);
+            }
+        } else {
+            reader.seek(SeekFrom::Current(length as i64))?;
+        }
+    }
+
+    let mut info = info.ok_or_else(|| AnalysisError::InvalidFormat("no SOF marker before the scan".to_string()))?;
+    info.orientation = orientation;
+    Ok(info)
+}
+
+/// Decode a JPEG start-of-frame segment
+fn parse_sof(data: &[u8]) -> Result<ImageInfo, AnalysisError> {
+    if data.len() < 6 {
+        return Err(AnalysisError::InvalidFormat("SOF segment is shorter than 6 bytes".to_string()));
+    }
+    let channels = data[5];
+    let color_type = match channels {
+        1 => "grayscale",
+        3 => "YCbCr",
+        4 => "CMYK",
+        _ => "unknown",
+    };
+    Ok(ImageInfo {
+        width: u16::from_be_bytes([data[3], data[4]]) as u32,
+        height: u16::from_be_bytes([data[1], data[2]]) as u32,
+        bit_depth: data[0],
+        color_type: color_type.to_string(),
+        channels,
+        frames: 1,
+        orientation: None,
+    })
+}
+
+/// Orientation tag from the first IFD of an EXIF (TIFF-structured) block
+fn exif_orientation(data: &[u8]) -> Option<u16> {
+    let little_endian = match data.get(..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| {
+        let bytes = [*data.get(offset)?, *data.get(offset + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize| {
+        let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    let count = u16_at(ifd)? as usize;
+    (0..count)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|entry| u16_at(*entry) == Some(ORIENTATION_TAG))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|value| (1..=8).contains(value))
+}
+
+/// Count images per size and channel count, most common first
+///
+/// Only PNG and JPEG files whose header could be read are counted.
+pub fn image_distribution(analyses: &[AnalysisResult]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for analysis in analyses {
+        if let AnalysisResult::Binary(binary) = analysis {
+            if let Some(image) = &binary.image {
+                *counts.entry(image.shape_label()).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+#[cfg(test)]
+mod fixture {
+    /// A PNG with the given IHDR fields, an optional acTL frame count and
+    /// an optional eXIf orientation (CRCs are not checked and left zero)
+    pub fn png(width: u32, height: u32, color_type: u8, frames: Option<u32>, orientation: Option<u16>) -> Vec<u8> {
+        let mut out = super::PNG_SIGNATURE.to_vec();
+        let mut chunk = |kind: &[u8], data: &[u8]| {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(kind);
+            out.extend_from_slice(data);
+            out.extend_from_slice(&[0; 4]);
+        };
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        chunk(b"IHDR", &ihdr);
+        chunk(b"tEXt", b"Software\0test");
+        if let Some(frames) = frames {
+            let mut actl = frames.to_be_bytes().to_vec();
+            actl.extend_from_slice(&0u32.to_be_bytes());
+            chunk(b"acTL", &actl);
+        }
+        if let Some(orientation) = orientation {
+            chunk(b"eXIf", &exif(orientation));
+        }
+        chunk(b"IDAT", &[0; 16]);
+        chunk(b"IEND", &[]);
+        out
+    }
+
+    /// A baseline JPEG header with an optional EXIF orientation
+    pub fn jpeg(width: u16, height: u16, components: u8, orientation: Option<u16>) -> Vec<u8> {
+        let mut out = vec![0xFF, 0xD8];
+        let mut segment = |marker: u8, data: &[u8]| {
+            out.extend_from_slice(&[0xFF, marker]);
+            out.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+            out.extend_from_slice(data);
+        };
+        segment(0xE0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        if let Some(orientation) = orientation {
+            let mut app1 = b"Exif\0\0".to_vec();
+            app1.extend_from_slice(&exif(orientation));
+            segment(0xE1, &app1);
+        }
+        segment(0xDB, &[0; 65]);
+        let mut sof = vec![8];
+        sof.extend_from_slice(&height.to_be_bytes());
+        sof.extend_from_slice(&width.to_be_bytes());
+        sof.push(components);
+        for id in 1..=components {
+            sof.extend_from_slice(&[id, 0x11, 0]);
+        }
+        segment(0xC0, &sof);
+        segment(0xDA, &[0; 8]);
+        out.extend_from_slice(&[0x12, 0x34, 0xFF, 0xD9]);
+        out
+    }
+
+    /// Big-endian EXIF block with a single orientation entry
+    fn exif(orientation: u16) -> Vec<u8> {
+        let mut out = b"MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        out.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1]);
+        out.extend_from_slice(&orientation.to_be_bytes());
+        out.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BinaryAnalysis, BinaryType};
+    use std::fs;
+
+    fn analyze(test: &str, name: &str, bytes: &[u8]) -> Result<ImageInfo, AnalysisError> {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_image_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join(name);
+        fs::write(&path, bytes).unwrap();
+        let result = analyze_image(&path);
+        fs::remove_dir_all(temp_dir).ok();
+        result
+    }
+
+    #[test]
+    fn test_png_header() {
+        let info = analyze("png", "cat.png", &fixture::png(224, 160, 2, None, None)).unwrap();
+        assert_eq!((info.width, info.height, info.bit_depth), (224, 160, 8));
+        assert_eq!((info.color_type.as_str(), info.channels, info.frames), ("RGB", 3, 1));
+        assert_eq!(info.orientation, None);
+        assert_eq!(info.shape_label(), "224x160, 3 channels");
+
+        let info = analyze("apng", "anim.png", &fixture::png(32, 32, 6, Some(12), Some(6))).unwrap();
+        assert_eq!((info.color_type.as_str(), info.channels), ("RGBA", 4));
+        assert_eq!(info.frames, 12);
+        assert_eq!(info.orientation, Some(6));
+
+        let mut broken = fixture::png(8, 8, 0, None, None);
+        broken.truncate(20);
+        assert!(analyze("png_broken", "broken.png", &broken).is_err());
+    }
+
+    #[test]
+    fn test_jpeg_header() {
+        let info = analyze("jpeg", "dog.jpg", &fixture::jpeg(640, 480, 3, Some(8))).unwrap();
+        assert_eq!((info.width, info.height, info.bit_depth), (640, 480, 8));
+        assert_eq!((info.color_type.as_str(), info.channels), ("YCbCr", 3));
+        assert_eq!(info.orientation, Some(8));
+
+        let info = analyze("jpeg_gray", "scan.jpg", &fixture::jpeg(100, 50, 1, None)).unwrap();
+        assert_eq!(info.shape_label(), "100x50, 1 channel");
+        assert_eq!(info.orientation, None);
+
+        // Headers cut before the frame
+        assert!(analyze("jpeg_broken", "cut.jpg", &fixture::jpeg(1, 1, 1, None)[..24]).is_err());
+    }
+
+    #[test]
+    fn test_image_distribution() {
+        let image = |w, h, channels| {
+            let mut info = parse_sof(&[8, 0, 0, 0, 0, channels]).unwrap();
+            info.width = w;
+            info.height = h;
+            AnalysisResult::Binary(BinaryAnalysis::new(BinaryType::Jpeg).with_image(info))
+        };
+        let analyses = vec![
+            image(64, 64, 3),
+            image(32, 32, 1),
+            AnalysisResult::NotAnalyzed,
+            image(64, 64, 3),
+        ];
+        assert_eq!(
+            image_distribution(&analyses),
+            vec![("64x64, 3 channels".to_string(), 2), ("32x32, 1 channel".to_string(), 1)]
+        );
+    }
+}
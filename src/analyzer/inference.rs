@@ -42,6 +42,7 @@ pub fn infer_column_type(values: &[&str], column_name: Option<&str>) -> Inferred
         (ColumnType::Time, count_matches(values, is_time))];
 
     // Sort by match count (descending)
+    #[allow(clippy::unnecessary_sort_by)]
     type_counts.sort_by(|a, b| b.1.cmp(&a.1));
 
     // Get the best match
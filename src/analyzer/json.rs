@@ -1,15 +1,21 @@
 //! JSON file analysis
 
 use super::AnalysisError;
-use crate::types::{JsonAnalysis, JsonRootType};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::compression::{is_corrupt, DataReader};
+use super::json_stream::read_structure;
+use crate::types::{JsonAnalysis, JsonRootType, JsonSyntaxError};
+use std::collections::HashMap;
+use std::io::{self, BufReader};
 use std::path::Path;
 
+/// Deepest nesting `parse_json` accepts (bounds recursion on hostile input)
+const MAX_DEPTH: usize = 128;
+
 /// Analyze a JSON file
 ///
-/// Validates JSON syntax and extracts structural information.
-/// Uses a simple recursive descent parser built from scratch.
+/// Validates JSON syntax and extracts structural information. The file is
+/// streamed through a byte-level tokenizer, so files of any size are
+/// analyzed in bounded memory.
 ///
 /// # Arguments
 ///
@@ -19,54 +25,198 @@ use std::path::Path;
 ///
 /// A JsonAnalysis struct with validation results and structure info.
 pub fn analyze_json(path: &Path) -> Result<JsonAnalysis, AnalysisError> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-
-    // Read entire file into string
-    // For very large JSON files, this could be optimized with streaming
-    let mut content = String::new();
-    for line_result in reader.lines() {
-        let line = line_result?;
-        content.push_str(&line);
-        content.push('\n');
-    }
-
-    // Parse JSON
-    let mut parser = JsonParser::new(&content);
-    match parser.parse() {
-        Ok(value) => {
-            let (root_type, top_level_keys) = match value {
-                JsonValue::Object(keys) => (JsonRootType::Object, keys),
-                JsonValue::Array(_) => (JsonRootType::Array, Vec::new()),
-                _ => return Ok(JsonAnalysis::invalid()),
-            };
+    analyze_json_from(DataReader::open(path)?)
+}
 
-            Ok(JsonAnalysis {
-                is_valid: true,
-                root_type,
-                top_level_keys,
-            })
+/// Analyze a JSON document read from a file or archive member
+pub(crate) fn analyze_json_from(data: DataReader) -> Result<JsonAnalysis, AnalysisError> {
+    let mut reader = BufReader::new(data);
+
+    let summary = match read_structure(&mut reader) {
+        Ok(summary) => summary,
+        // A truncated document is not worth describing; the damage is reported instead
+        Err(e) if is_corrupt(&e) => {
+            let mut analysis = JsonAnalysis::invalid();
+            analysis.gzip = reader.get_ref().gzip_info();
+            return Ok(analysis);
         }
-        Err(_) => Ok(JsonAnalysis::invalid()),
+        Err(e) => return Err(e.into()),
+    };
+
+    if let Some(error) = summary.error {
+        // Finish a compressed stream, so its integrity is still checked
+        if reader.get_ref().gzip_info().is_some() {
+            match io::copy(&mut reader, &mut io::sink()) {
+                Ok(_) => {}
+                Err(e) if is_corrupt(&e) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        return Ok(JsonAnalysis {
+            error: Some(error),
+            gzip: reader.get_ref().gzip_info(),
+            ..JsonAnalysis::invalid()
+        });
+    }
+
+    let root_type = summary.root_type.unwrap_or(JsonRootType::Object);
+    let top_level_keys = match root_type {
+        JsonRootType::Object => summary.structure.properties.iter().map(|(key, _)| key.clone()).collect(),
+        JsonRootType::Array => Vec::new(),
+    };
+    Ok(JsonAnalysis {
+        is_valid: true,
+        root_type,
+        top_level_keys,
+        structure: Some(summary.structure),
+        error: None,
+        gzip: reader.get_ref().gzip_info(),
+    })
+}
+
+/// Find the first syntax error in a JSON document held in memory
+///
+/// Applies the same checks as `analyze_json`: the root must be an object or
+/// array with nothing after it.
+///
+/// # Arguments
+///
+/// * `input` - The JSON text
+///
+/// # Returns
+///
+/// The error with its position, or None if the document is valid.
+pub fn json_syntax_error(input: &str) -> Option<JsonSyntaxError> {
+    // Reading from memory cannot fail
+    read_structure(input.as_bytes()).ok()?.error
+}
+
+/// Parse a JSON document held in memory
+///
+/// Used for small documents such as metadata.json or croissant.json whose
+/// values (not just structure) need to be inspected.
+///
+/// # Arguments
+///
+/// * `input` - The JSON text
+///
+/// # Returns
+///
+/// The parsed root value, or a description of the first syntax error.
+pub fn parse_json(input: &str) -> Result<JsonValue, String> {
+    JsonParser::new(input).parse_document()
+}
+
+/// Parse a JSON document held in memory, noting the line of every value
+///
+/// Used where problems with individual values are reported, so each can
+/// point at its line (see [`JsonLocations`]).
+///
+/// # Arguments
+///
+/// * `input` - The JSON text
+///
+/// # Returns
+///
+/// The parsed root value and the line of each value, or a description of
+/// the first syntax error.
+pub fn parse_json_with_locations(input: &str) -> Result<(JsonValue, JsonLocations), String> {
+    let mut parser = JsonParser::new(input);
+    parser.locations = Some(JsonLocations::default());
+    let value = parser.parse_document()?;
+    Ok((value, parser.locations.unwrap_or_default()))
+}
+
+/// Where the values of a parsed document start, by JSON pointer (RFC 6901)
+#[derive(Debug, Clone, Default)]
+pub struct JsonLocations {
+    lines: HashMap<String, usize>,
+}
+
+impl JsonLocations {
+    /// Line (1-based) on which the value at `pointer` starts
+    ///
+    /// The root is `""`; members and elements are `/key` and `/0`.
+    pub fn line(&self, pointer: &str) -> Option<usize> {
+        self.lines.get(pointer).copied()
     }
 }
 
+/// Escape an object key for use as a JSON pointer segment
+pub fn json_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
 /// JSON value representation for parsing
-#[derive(Debug)]
-#[allow(dead_code)]
-enum JsonValue {
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
     Null,
     Boolean(bool),
     Number(f64),
     String(String),
     Array(Vec<JsonValue>),
-    Object(Vec<String>), // Store only keys for top-level objects
+    /// Object members in document order
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Look up a member of an object by key
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Get the string value, if this is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the numeric value, if this is a number
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get the elements, if this is an array
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Get the members, if this is an object
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match self {
+            JsonValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
 }
 
 /// Simple JSON parser
 struct JsonParser {
     chars: Vec<char>,
     pos: usize,
+    /// Line of each value, if asked for
+    locations: Option<JsonLocations>,
+    /// Pointer segments of the value being parsed (while recording locations)
+    pointer: Vec<String>,
+    /// Line number at `line_pos` (advanced lazily)
+    line: usize,
+    line_pos: usize,
+    /// Arrays and objects open around the value being parsed
+    depth: usize,
 }
 
 impl JsonParser {
@@ -74,6 +224,34 @@ impl JsonParser {
         JsonParser {
             chars: input.chars().collect(),
             pos: 0,
+            locations: None,
+            pointer: Vec::new(),
+            line: 1,
+            line_pos: 0,
+            depth: 0,
+        }
+    }
+
+    /// Parse a complete document, with nothing but whitespace after it
+    fn parse_document(&mut self) -> Result<JsonValue, String> {
+        let value = self.parse()?;
+        self.skip_whitespace();
+        if self.pos < self.chars.len() {
+            return Err(format!(
+                "Unexpected trailing character: {}",
+                self.chars[self.pos]
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Note the line of the value starting at `pos`
+    fn record_location(&mut self) {
+        if let Some(locations) = self.locations.as_mut() {
+            self.line += self.chars[self.line_pos..self.pos].iter().filter(|&&c| c == '\n').count();
+            self.line_pos = self.pos;
+            let pointer: String = self.pointer.iter().map(|segment| format!("/{}", segment)).collect();
+            locations.lines.insert(pointer, self.line);
         }
     }
 
@@ -88,13 +266,25 @@ impl JsonParser {
         if self.pos >= self.chars.len() {
             return Err("Unexpected end of input".to_string());
         }
+        self.record_location();
 
         match self.chars[self.pos] {
             'n' => self.parse_null(),
             't' | 'f' => self.parse_boolean(),
             '"' => self.parse_string(),
-            '[' => self.parse_array(),
-            '{' => self.parse_object(),
+            '[' | '{' => {
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(format!("Value nested too deeply (more than {} levels)", MAX_DEPTH));
+                }
+                let value = if self.chars[self.pos] == '[' {
+                    self.parse_array()
+                } else {
+                    self.parse_object()
+                };
+                self.depth -= 1;
+                value
+            }
             '-' | '0'..='9' => self.parse_number(),
             _ => Err(format!("Unexpected character: {}", self.chars[self.pos])),
         }
@@ -185,6 +375,12 @@ impl JsonParser {
                         'n' => result.push('\n'),
                         'r' => result.push('\r'),
                         't' => result.push('\t'),
+                        'b' => result.push('\u{8}'),
+                        'f' => result.push('\u{c}'),
+                        'u' => {
+                            result.push(self.parse_unicode_escape()?);
+                            continue;
+                        }
                         _ => result.push(self.chars[self.pos]),
                     }
                     self.pos += 1;
@@ -214,7 +410,13 @@ impl JsonParser {
         }
 
         loop {
+            if self.locations.is_some() {
+                self.pointer.push(elements.len().to_string());
+            }
             elements.push(self.parse_value()?);
+            if self.locations.is_some() {
+                self.pointer.pop();
+            }
             self.skip_whitespace();
 
             if self.pos >= self.chars.len() {
@@ -241,22 +443,22 @@ impl JsonParser {
         }
         self.pos += 1;
 
-        let mut keys = Vec::new();
+        let mut members = Vec::new();
         self.skip_whitespace();
 
         if self.pos < self.chars.len() && self.chars[self.pos] == '}' {
             self.pos += 1;
-            return Ok(JsonValue::Object(keys));
+            return Ok(JsonValue::Object(members));
         }
 
         loop {
             self.skip_whitespace();
 
             // Parse key (must be string)
-            let key = self.parse_string()?;
-            if let JsonValue::String(k) = key {
-                keys.push(k);
-            }
+            let key = match self.parse_string()? {
+                JsonValue::String(k) => k,
+                _ => return Err("Expected string key".to_string()),
+            };
 
             self.skip_whitespace();
 
@@ -265,8 +467,14 @@ impl JsonParser {
             }
             self.pos += 1;
 
-            // Parse value (but don't store it for efficiency)
-            self.parse_value()?;
+            if self.locations.is_some() {
+                self.pointer.push(json_pointer_token(&key));
+            }
+            let value = self.parse_value()?;
+            if self.locations.is_some() {
+                self.pointer.pop();
+            }
+            members.push((key, value));
 
             self.skip_whitespace();
 
@@ -281,13 +489,47 @@ impl JsonParser {
                 }
                 '}' => {
                     self.pos += 1;
-                    return Ok(JsonValue::Object(keys));
+                    return Ok(JsonValue::Object(members));
                 }
                 _ => return Err("Expected comma or closing brace".to_string()),
             }
         }
     }
 
+    /// Parse the four hex digits after `\u`, combining surrogate pairs
+    ///
+    /// Expects `pos` on the `u`; leaves it after the last digit consumed.
+    fn parse_unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.read_hex4()?;
+        if (0xD800..0xDC00).contains(&high)
+            && self.pos + 1 < self.chars.len()
+            && self.chars[self.pos] == '\\'
+            && self.chars[self.pos + 1] == 'u'
+        {
+            self.pos += 1;
+            let low = self.read_hex4()?;
+            if (0xDC00..0xE000).contains(&low) {
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return Ok(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            return Ok('\u{FFFD}');
+        }
+        Ok(char::from_u32(high).unwrap_or('\u{FFFD}'))
+    }
+
+    /// Read `u` followed by four hex digits
+    fn read_hex4(&mut self) -> Result<u32, String> {
+        self.pos += 1; // Skip 'u'
+        if self.pos + 4 > self.chars.len() {
+            return Err("Invalid unicode escape".to_string());
+        }
+        let hex: String = self.chars[self.pos..self.pos + 4].iter().collect();
+        let code = u32::from_str_radix(&hex, 16)
+            .map_err(|_| "Invalid unicode escape".to_string())?;
+        self.pos += 4;
+        Ok(code)
+    }
+
     fn consume_literal(&mut self, literal: &str) -> bool {
         let chars: Vec<char> = literal.chars().collect();
         if self.pos + chars.len() > self.chars.len() {
@@ -358,13 +600,62 @@ mod tests {
     fn test_parse_object() {
         let mut parser = JsonParser::new(r#"{"key": "value"}"#);
         let result = parser.parse().unwrap();
-        if let JsonValue::Object(keys) = result {
+        if let JsonValue::Object(members) = result {
+            let keys: Vec<&str> = members.iter().map(|(key, _)| key.as_str()).collect();
             assert_eq!(keys, vec!["key"]);
+            assert_eq!(members[0].1.as_str(), Some("value"));
         } else {
             panic!("Expected object");
         }
     }
 
+    #[test]
+    fn test_parse_json_nested_lookup() {
+        let value = parse_json(r#"{"contact": {"name": "Ada"}, "tags": ["a", "b"]}"#).unwrap();
+        assert_eq!(
+            value.get("contact").and_then(|c| c.get("name")).and_then(|n| n.as_str()),
+            Some("Ada")
+        );
+        assert_eq!(value.get("tags").and_then(|t| t.as_array()).map(|t| t.len()), Some(2));
+        assert!(value.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_json_unicode_escape() {
+        let value = parse_json(r#""caf\u00e9 \ud83d\ude00""#).unwrap();
+        assert_eq!(value.as_str(), Some("caf\u{e9} \u{1F600}"));
+    }
+
+    #[test]
+    fn test_parse_json_rejects_trailing_content() {
+        assert!(parse_json("{} {}").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_rejects_deep_nesting() {
+        let deep = format!("{}{}", "[".repeat(50_000), "]".repeat(50_000));
+        assert_eq!(parse_json(&deep).unwrap_err(), "Value nested too deeply (more than 128 levels)");
+        assert!(parse_json_with_locations(&deep).is_err());
+
+        let nested = format!("{}{}", "{\"a\": ".repeat(MAX_DEPTH), "}".repeat(MAX_DEPTH));
+        assert!(parse_json(&nested.replacen("}", "1}", 1)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_json_with_locations() {
+        let (value, locations) = parse_json_with_locations(
+            "{\n  \"title\": \"Survey\",\n  \"keywords\": [\n    \"ocean\",\n    \"salinity\"\n  ],\n  \"a/b\": {\"c\": 1}\n}",
+        )
+        .unwrap();
+        assert_eq!(value.get("title").and_then(JsonValue::as_str), Some("Survey"));
+        assert_eq!(locations.line(""), Some(1));
+        assert_eq!(locations.line("/title"), Some(2));
+        assert_eq!(locations.line("/keywords"), Some(3));
+        assert_eq!(locations.line("/keywords/1"), Some(5));
+        assert_eq!(locations.line("/a~1b/c"), Some(7));
+        assert_eq!(locations.line("/missing"), None);
+    }
+
     #[test]
     fn test_analyze_valid_json() {
         let temp_dir = std::env::temp_dir().join("genesis_preflight_json_valid");
@@ -391,6 +682,9 @@ mod tests {
 
         let result = analyze_json(&file_path).unwrap();
         assert!(!result.is_valid);
+        let error = result.error.unwrap();
+        assert_eq!(error.message, "Expected a value, found '}'");
+        assert_eq!((error.line, error.column, error.offset), (1, 13, 12));
 
         std::fs::remove_dir_all(temp_dir).ok();
     }
@@ -0,0 +1,676 @@
+//! Streaming JSON validation
+//!
+//! JSON data files can run to many gigabytes, so they are not parsed into
+//! memory. A byte-level tokenizer reads the document once through a
+//! buffered reader, tracking nesting on an explicit stack, and merges each
+//! value into the document's structure as it goes. Only object keys are
+//! kept, and only up to a limit per object; the stack holds one byte per
+//! open container, so memory grows with nesting depth, never with the
+//! size of the file.
+
+use crate::types::{JsonRootType, JsonShape, JsonSyntaxError};
+use std::io::{self, BufRead};
+
+/// Deepest nesting described in the structure; deeper values are checked
+/// but not recorded
+const MAX_SHAPE_DEPTH: usize = 32;
+
+/// Most members described per object shape (bounds memory when keys are
+/// data, e.g. a map from sample ID to value)
+const MAX_SHAPE_PROPERTIES: usize = 1000;
+
+/// Longest object key kept (longer keys are checked, then shortened)
+const MAX_KEY_LENGTH: usize = 1024;
+
+/// Longest number read for the numeric range (longer ones are checked only)
+const MAX_NUMBER_LENGTH: usize = 64;
+
+/// Bytes of the error line shown on either side of a syntax error
+const SNIPPET_CONTEXT: usize = 40;
+
+/// What a single pass over a JSON document found
+#[derive(Debug)]
+pub(crate) struct JsonSummary {
+    /// Type of the root value, if it is an object or array
+    pub root_type: Option<JsonRootType>,
+    /// Merged structure of the values read (up to the error, if any)
+    pub structure: JsonShape,
+    /// The first syntax error
+    pub error: Option<JsonSyntaxError>,
+}
+
+/// Validate a JSON document and summarize its structure in one pass
+///
+/// The root must be an object or array, and nothing but whitespace may
+/// follow it.
+///
+/// # Errors
+///
+/// Returns read errors; syntax errors are reported in the summary.
+pub(crate) fn read_structure(reader: impl BufRead) -> io::Result<JsonSummary> {
+    let mut parser = Parser {
+        cursor: Cursor {
+            reader,
+            offset: 0,
+            line: 1,
+            column: 1,
+            recent: Vec::new(),
+        },
+        stack: Vec::new(),
+        path: Vec::new(),
+        structure: JsonShape::default(),
+        root_type: None,
+    };
+    let error = match parser.document() {
+        Ok(()) => None,
+        Err(Failure::Syntax(error)) => Some(error),
+        Err(Failure::Io(e)) => return Err(e),
+    };
+    Ok(JsonSummary {
+        root_type: parser.root_type,
+        structure: parser.structure,
+        error,
+    })
+}
+
+/// Why parsing stopped
+enum Failure {
+    Io(io::Error),
+    Syntax(JsonSyntaxError),
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        Failure::Io(error)
+    }
+}
+
+/// A buffered byte reader that knows where it is
+struct Cursor<R> {
+    reader: R,
+    /// Bytes consumed
+    offset: u64,
+    /// Line of the next byte (1-based)
+    line: usize,
+    /// Column of the next byte, in characters (1-based)
+    column: usize,
+    /// The last bytes read on the current line, for error snippets
+    recent: Vec<u8>,
+}
+
+impl<R: BufRead> Cursor<R> {
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.peek()?;
+        if let Some(b) = byte {
+            self.reader.consume(1);
+            self.offset += 1;
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+                self.recent.clear();
+            } else {
+                if b & 0xC0 != 0x80 {
+                    // UTF-8 continuation bytes belong to the previous character
+                    self.column += 1;
+                }
+                if self.recent.len() == 2 * SNIPPET_CONTEXT {
+                    self.recent.drain(..SNIPPET_CONTEXT);
+                }
+                self.recent.push(b);
+            }
+        }
+        Ok(byte)
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<()> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    /// The current line around the next byte, reading ahead to the end of
+    /// the line (or the context limit)
+    fn snippet(&mut self) -> String {
+        let start = self.recent.len().saturating_sub(SNIPPET_CONTEXT);
+        let mut bytes = self.recent[start..].to_vec();
+        // Start on a character boundary
+        let skip = bytes.iter().take_while(|&&b| b & 0xC0 == 0x80).count();
+        bytes.drain(..skip);
+        let truncated_before = start + skip > 0;
+        let before = bytes.len();
+
+        let mut truncated_after = false;
+        'read: while let Ok(buffer) = self.reader.fill_buf() {
+            if buffer.is_empty() {
+                break;
+            }
+            let mut used = 0;
+            for &b in buffer {
+                if b == b'\n' || b == b'\r' {
+                    break 'read;
+                }
+                if bytes.len() - before >= SNIPPET_CONTEXT {
+                    truncated_after = true;
+                    break 'read;
+                }
+                bytes.push(b);
+                used += 1;
+            }
+            self.reader.consume(used);
+        }
+
+        // A character cut at the end shows as a replacement character
+        let text = String::from_utf8_lossy(&bytes);
+        let text = text.trim_end_matches('\u{FFFD}').trim();
+        format!(
+            "{}{}{}",
+            if truncated_before { "..." } else { "" },
+            text,
+            if truncated_after { "..." } else { "" }
+        )
+    }
+}
+
+/// Kind of an open container
+#[derive(Clone, Copy)]
+enum Container {
+    Object,
+    Array,
+}
+
+/// Where in the structure the value being read belongs
+enum Step {
+    /// A member of the enclosing object shape (by index)
+    Member(usize),
+    /// An element of the enclosing array shape
+    Item,
+    /// Too deep, or past the member limit: not recorded
+    Untracked,
+}
+
+struct Parser<R> {
+    cursor: Cursor<R>,
+    /// Containers currently open
+    stack: Vec<Container>,
+    /// Position in the structure of the value being read
+    path: Vec<Step>,
+    structure: JsonShape,
+    root_type: Option<JsonRootType>,
+}
+
+impl<R: BufRead> Parser<R> {
+    /// A syntax error at the next byte
+    fn syntax(&mut self, message: impl Into<String>) -> Failure {
+        let (offset, line, column) = (self.cursor.offset, self.cursor.line, self.cursor.column);
+        Failure::Syntax(JsonSyntaxError {
+            message: message.into(),
+            offset,
+            line,
+            column,
+            snippet: self.cursor.snippet(),
+        })
+    }
+
+    /// Error for the next byte, which does not fit where it is
+    fn unexpected(&mut self, expected: &str) -> Failure {
+        match self.cursor.peek() {
+            Ok(Some(byte)) => self.syntax(format!("{}, found {}", expected, describe(byte))),
+            Ok(None) => self.syntax("Unexpected end of input"),
+            Err(e) => Failure::Io(e),
+        }
+    }
+
+    fn document(&mut self) -> Result<(), Failure> {
+        // A UTF-8 byte order mark is tolerated before the document
+        if self.cursor.peek()? == Some(0xEF) {
+            for expected in [0xEF, 0xBB, 0xBF] {
+                if self.cursor.peek()? != Some(expected) {
+                    return Err(self.unexpected("Expected an object or array at the top level"));
+                }
+                self.cursor.bump()?;
+            }
+        }
+        self.cursor.skip_whitespace()?;
+        self.root_type = match self.cursor.peek()? {
+            Some(b'{') => Some(JsonRootType::Object),
+            Some(b'[') => Some(JsonRootType::Array),
+            _ => return Err(self.unexpected("Expected an object or array at the top level")),
+        };
+        self.value()?;
+
+        self.cursor.skip_whitespace()?;
+        match self.cursor.peek()? {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("Expected end of input after the top-level value")),
+        }
+    }
+
+    /// Read one complete value, however deeply nested
+    fn value(&mut self) -> Result<(), Failure> {
+        let base = self.stack.len();
+        loop {
+            self.cursor.skip_whitespace()?;
+            match self.cursor.peek()? {
+                Some(b'{') => {
+                    self.cursor.bump()?;
+                    self.record(|shape| shape.object_count += 1);
+                    self.stack.push(Container::Object);
+                    self.cursor.skip_whitespace()?;
+                    if self.cursor.peek()? != Some(b'}') {
+                        self.key()?;
+                        continue;
+                    }
+                    self.cursor.bump()?;
+                    self.stack.pop();
+                }
+                Some(b'[') => {
+                    self.cursor.bump()?;
+                    self.record(|shape| shape.array_count += 1);
+                    self.stack.push(Container::Array);
+                    self.cursor.skip_whitespace()?;
+                    if self.cursor.peek()? != Some(b']') {
+                        let step = self.item_step();
+                        self.path.push(step);
+                        continue;
+                    }
+                    self.cursor.bump()?;
+                    self.stack.pop();
+                }
+                Some(b'"') => {
+                    self.string(None)?;
+                    self.record(|shape| shape.string_count += 1);
+                }
+                Some(b't') => {
+                    self.literal("true")?;
+                    self.record(|shape| shape.boolean_count += 1);
+                }
+                Some(b'f') => {
+                    self.literal("false")?;
+                    self.record(|shape| shape.boolean_count += 1);
+                }
+                Some(b'n') => {
+                    self.literal("null")?;
+                    self.record(|shape| shape.null_count += 1);
+                }
+                Some(b'-' | b'0'..=b'9') => {
+                    let number = self.number()?;
+                    self.record(|shape| {
+                        match number {
+                            Some(n) if n.fract() != 0.0 => shape.number_count += 1,
+                            _ => shape.integer_count += 1,
+                        }
+                        if let Some(n) = number {
+                            shape.min_value = Some(shape.min_value.map_or(n, |min| min.min(n)));
+                            shape.max_value = Some(shape.max_value.map_or(n, |max| max.max(n)));
+                        }
+                    });
+                }
+                _ => return Err(self.unexpected("Expected a value")),
+            }
+
+            // A value is complete: close finished containers, or move on to
+            // the next member or element
+            loop {
+                if self.stack.len() == base {
+                    return Ok(());
+                }
+                self.path.pop();
+                self.cursor.skip_whitespace()?;
+                let container = self.stack[self.stack.len() - 1];
+                match (self.cursor.peek()?, container) {
+                    (Some(b','), Container::Object) => {
+                        self.cursor.bump()?;
+                        self.cursor.skip_whitespace()?;
+                        if self.cursor.peek()? == Some(b'}') {
+                            return Err(self.syntax("Trailing comma before closing brace"));
+                        }
+                        self.key()?;
+                        break;
+                    }
+                    (Some(b','), Container::Array) => {
+                        self.cursor.bump()?;
+                        self.cursor.skip_whitespace()?;
+                        if self.cursor.peek()? == Some(b']') {
+                            return Err(self.syntax("Trailing comma before closing bracket"));
+                        }
+                        let step = self.item_step();
+                        self.path.push(step);
+                        break;
+                    }
+                    (Some(b'}'), Container::Object) | (Some(b']'), Container::Array) => {
+                        self.cursor.bump()?;
+                        // The container was itself a value, now complete
+                        self.stack.pop();
+                    }
+                    (_, Container::Object) => return Err(self.unexpected("Expected comma or closing brace")),
+                    (_, Container::Array) => return Err(self.unexpected("Expected comma or closing bracket")),
+                }
+            }
+        }
+    }
+
+    /// Read an object key and its colon, and step into its member shape
+    fn key(&mut self) -> Result<(), Failure> {
+        self.cursor.skip_whitespace()?;
+        if self.cursor.peek()? != Some(b'"') {
+            return Err(self.unexpected("Expected a string key"));
+        }
+        let mut key = Vec::new();
+        self.string(Some(&mut key))?;
+
+        self.cursor.skip_whitespace()?;
+        if self.cursor.peek()? != Some(b':') {
+            return Err(self.unexpected("Expected colon after key"));
+        }
+        self.cursor.bump()?;
+
+        let key = String::from_utf8_lossy(&key).into_owned();
+        let step = match self.tracked_shape() {
+            Some(shape) => match shape.properties.iter().position(|(name, _)| *name == key) {
+                Some(idx) => Step::Member(idx),
+                None if shape.properties.len() < MAX_SHAPE_PROPERTIES => {
+                    shape.properties.push((key, JsonShape::default()));
+                    Step::Member(shape.properties.len() - 1)
+                }
+                None => Step::Untracked,
+            },
+            None => Step::Untracked,
+        };
+        self.path.push(step);
+        Ok(())
+    }
+
+    /// Step for the next element of the array just entered or continued
+    fn item_step(&mut self) -> Step {
+        match self.tracked_shape() {
+            Some(_) => Step::Item,
+            None => Step::Untracked,
+        }
+    }
+
+    /// Shape of the innermost open container, if it is being recorded
+    fn tracked_shape(&mut self) -> Option<&mut JsonShape> {
+        if self.path.len() >= MAX_SHAPE_DEPTH {
+            return None;
+        }
+        shape_at(&mut self.structure, &self.path)
+    }
+
+    /// Count a value at the current position in the structure
+    fn record(&mut self, update: impl FnOnce(&mut JsonShape)) {
+        if let Some(shape) = shape_at(&mut self.structure, &self.path) {
+            update(shape);
+        }
+    }
+
+    /// Read a string, keeping its bytes (escapes decoded) if asked
+    fn string(&mut self, mut keep: Option<&mut Vec<u8>>) -> Result<(), Failure> {
+        self.cursor.bump()?; // opening quote
+        let mut pending_high = None;
+        loop {
+            let byte = match self.cursor.peek()? {
+                Some(byte) => byte,
+                None => return Err(self.syntax("Unterminated string")),
+            };
+            if byte < 0x20 {
+                return Err(self.syntax(format!("Unescaped control character {} in string", describe(byte))));
+            }
+            self.cursor.bump()?;
+            let decoded = match byte {
+                b'"' => return Ok(()),
+                b'\\' => match self.cursor.peek()? {
+                    Some(escape @ (b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't')) => {
+                        self.cursor.bump()?;
+                        Some(match escape {
+                            b'b' => '\u{8}',
+                            b'f' => '\u{c}',
+                            b'n' => '\n',
+                            b'r' => '\r',
+                            b't' => '\t',
+                            other => other as char,
+                        })
+                    }
+                    Some(b'u') => {
+                        self.cursor.bump()?;
+                        let unit = self.hex4()?;
+                        match (pending_high.take(), unit) {
+                            (None, 0xD800..=0xDBFF) => {
+                                pending_high = Some(unit);
+                                None
+                            }
+                            (Some(high), 0xDC00..=0xDFFF) => {
+                                char::from_u32(0x10000 + ((high - 0xD800) << 10) + (unit - 0xDC00))
+                            }
+                            (_, unit) => Some(char::from_u32(unit).unwrap_or('\u{FFFD}')),
+                        }
+                    }
+                    _ => return Err(self.unexpected("Invalid escape in string")),
+                },
+                other => {
+                    if let Some(ref mut key) = keep {
+                        if key.len() < MAX_KEY_LENGTH {
+                            key.push(other);
+                        }
+                    }
+                    continue;
+                }
+            };
+            if let (Some(c), Some(ref mut key)) = (decoded, &mut keep) {
+                if key.len() < MAX_KEY_LENGTH {
+                    let mut utf8 = [0u8; 4];
+                    key.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Read the four hex digits of a `\u` escape
+    fn hex4(&mut self) -> Result<u32, Failure> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = match self.cursor.peek()? {
+                Some(byte) => (byte as char).to_digit(16),
+                None => None,
+            };
+            match digit {
+                Some(digit) => {
+                    self.cursor.bump()?;
+                    value = value * 16 + digit;
+                }
+                None => return Err(self.unexpected("Invalid unicode escape")),
+            }
+        }
+        Ok(value)
+    }
+
+    /// Read `true`, `false` or `null`
+    fn literal(&mut self, word: &str) -> Result<(), Failure> {
+        for expected in word.bytes() {
+            if self.cursor.peek()? != Some(expected) {
+                return Err(self.unexpected(&format!("Invalid literal (expected {})", word)));
+            }
+            self.cursor.bump()?;
+        }
+        Ok(())
+    }
+
+    /// Read a number; its value, unless it is too long to bother with
+    fn number(&mut self) -> Result<Option<f64>, Failure> {
+        let mut text = String::new();
+
+        if self.cursor.peek()? == Some(b'-') {
+            self.take_byte(&mut text)?;
+        }
+        match self.cursor.peek()? {
+            Some(b'0') => self.take_byte(&mut text)?,
+            Some(b'1'..=b'9') => self.take_digits(&mut text)?,
+            _ => return Err(self.unexpected("Invalid number: expected a digit")),
+        }
+        if self.cursor.peek()? == Some(b'.') {
+            self.take_byte(&mut text)?;
+            if !matches!(self.cursor.peek()?, Some(b'0'..=b'9')) {
+                return Err(self.unexpected("Invalid number: expected a digit after the decimal point"));
+            }
+            self.take_digits(&mut text)?;
+        }
+        if let Some(b'e' | b'E') = self.cursor.peek()? {
+            self.take_byte(&mut text)?;
+            if let Some(b'+' | b'-') = self.cursor.peek()? {
+                self.take_byte(&mut text)?;
+            }
+            if !matches!(self.cursor.peek()?, Some(b'0'..=b'9')) {
+                return Err(self.unexpected("Invalid number: expected a digit in the exponent"));
+            }
+            self.take_digits(&mut text)?;
+        }
+
+        if text.len() > MAX_NUMBER_LENGTH {
+            return Ok(None);
+        }
+        Ok(text.parse().ok())
+    }
+
+    fn take_digits(&mut self, text: &mut String) -> io::Result<()> {
+        while let Some(b'0'..=b'9') = self.cursor.peek()? {
+            self.take_byte(text)?;
+        }
+        Ok(())
+    }
+
+    fn take_byte(&mut self, text: &mut String) -> io::Result<()> {
+        if let Some(byte) = self.cursor.bump()? {
+            if text.len() <= MAX_NUMBER_LENGTH {
+                text.push(byte as char);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The shape at a position in the structure, unless it is not recorded
+fn shape_at<'a>(structure: &'a mut JsonShape, path: &[Step]) -> Option<&'a mut JsonShape> {
+    let mut shape = structure;
+    for step in path {
+        shape = match step {
+            Step::Member(idx) => &mut shape.properties[*idx].1,
+            Step::Item => shape.items.get_or_insert_with(Default::default),
+            Step::Untracked => return None,
+        };
+    }
+    Some(shape)
+}
+
+/// A byte as it should appear in an error message
+fn describe(byte: u8) -> String {
+    match byte {
+        b'\n' => "line break".to_string(),
+        b'\r' => "carriage return".to_string(),
+        b'\t' => "tab".to_string(),
+        0x20..=0x7E => format!("'{}'", byte as char),
+        _ => format!("byte 0x{:02X}", byte),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str) -> JsonSummary {
+        read_structure(input.as_bytes()).unwrap()
+    }
+
+    fn error(input: &str) -> JsonSyntaxError {
+        read(input).error.expect("input should be rejected")
+    }
+
+    #[test]
+    fn test_merged_structure() {
+        let summary = read(
+            r#"[
+                {"id": 1, "pos": {"lat": 52.1, "lon": 4}, "tags": ["a"], "note": null},
+                {"id": 2, "pos": {"lat": -3, "lon": 5.5}, "tags": []},
+                {"id": 7, "pos": {"lat": 0, "lon": 0}, "tags": ["b", "c"], "note": "late"}
+            ]"#,
+        );
+        assert!(summary.error.is_none());
+        assert_eq!(summary.root_type, Some(JsonRootType::Array));
+
+        let shape = summary.structure;
+        assert_eq!(shape.json_types(), vec!["array"]);
+        let record = shape.items.as_deref().unwrap();
+        assert_eq!(record.object_count, 3);
+        let names: Vec<&str> = record.properties.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["id", "pos", "tags", "note"]);
+
+        let (_, id) = &record.properties[0];
+        assert!(record.is_required(id));
+        assert_eq!(id.json_types(), vec!["integer"]);
+        assert_eq!((id.min_value, id.max_value), (Some(1.0), Some(7.0)));
+
+        let (_, pos) = &record.properties[1];
+        assert_eq!(pos.properties[0].1.json_types(), vec!["number"]);
+        assert_eq!(pos.properties[0].1.min_value, Some(-3.0));
+
+        let (_, tags) = &record.properties[2];
+        assert_eq!(tags.array_count, 3);
+        assert_eq!(tags.items.as_ref().unwrap().string_count, 3);
+
+        let (_, note) = &record.properties[3];
+        assert!(!record.is_required(note));
+        assert_eq!(note.json_types(), vec!["string", "null"]);
+    }
+
+    #[test]
+    fn test_escaped_keys_and_deep_nesting() {
+        let summary = read(r#"{"caf\u00e9 \ud83d\ude00": 1, "a\"b": [[[]]]}"#);
+        assert!(summary.error.is_none());
+        let names: Vec<&str> = summary.structure.properties.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["caf\u{e9} \u{1F600}", "a\"b"]);
+
+        // Far deeper than the structure records, and than the call stack would allow
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(read(&deep).error.is_none());
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let e = error("{\n  \"a\": 1,\n  \"b\": tru\n}");
+        assert_eq!(e.message, "Invalid literal (expected true), found line break");
+        assert_eq!((e.line, e.column, e.offset), (3, 11, 22));
+
+        let e = error("[1, 2,]");
+        assert_eq!(e.message, "Trailing comma before closing bracket");
+        assert_eq!((e.line, e.column), (1, 7));
+
+        let e = error("{\"é\": 1 \"b\": 2}");
+        assert_eq!(e.message, "Expected comma or closing brace, found '\"'");
+        assert_eq!((e.line, e.column, e.offset), (1, 9, 9));
+
+        assert_eq!(error("{\"a\": 1,\n}").message, "Trailing comma before closing brace");
+        assert_eq!(error("{\"a\": ]").message, "Expected a value, found ']'");
+        assert_eq!(error("[1, 2").message, "Unexpected end of input");
+        assert_eq!(error("{} {}").message, "Expected end of input after the top-level value, found '{'");
+        assert_eq!(error("42").message, "Expected an object or array at the top level, found '4'");
+        assert_eq!(error("[01]").message, "Expected comma or closing bracket, found '1'");
+        assert_eq!(error("[1.]").message, "Invalid number: expected a digit after the decimal point, found ']'");
+        assert_eq!(error("[\"a\tb\"]").message, "Unescaped control character tab in string");
+        assert_eq!(error("[\"\\x\"]").message, "Invalid escape in string, found 'x'");
+        assert_eq!(error("").message, "Unexpected end of input");
+    }
+
+    #[test]
+    fn test_error_snippet() {
+        assert_eq!(error("{\n  \"a\": 1,\n  \"b\": tru\n}").snippet, "\"b\": tru");
+        assert_eq!(error("{'a': 1}").snippet, "{'a': 1}");
+
+        let long = format!("[{}NaN, {}2]", "1, ".repeat(30), "2, ".repeat(30));
+        let e = error(&long);
+        assert_eq!(e.column, 92);
+        assert_eq!(e.snippet, format!("...{}NaN, {}2,...", "1, ".repeat(13), "2, ".repeat(11)));
+    }
+}
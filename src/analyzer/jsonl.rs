@@ -0,0 +1,305 @@
+//! JSON Lines (NDJSON) analysis
+//!
+//! Every non-blank line is parsed as a JSON document of its own. The
+//! top-level fields of object records are merged into one record schema:
+//! how many records carry each field, and with which JSON types. The values
+//! also go through the CSV column statistics, so a JSON Lines file gets the
+//! same column profile as a CSV file.
+
+use super::csv::StreamingColumnStats;
+use super::json::{parse_json, JsonValue};
+use super::AnalysisError;
+use crate::compression::{is_corrupt, DataReader};
+use crate::types::{ColumnType, CsvAnalysis, JsonLinesAnalysis, JsonLinesField};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Invalid lines kept for reporting
+const MAX_INVALID_SAMPLES: usize = 10;
+
+/// Most distinct fields profiled (bounds memory when keys are data, e.g. IDs)
+const MAX_FIELDS: usize = 1000;
+
+/// Analyze a JSON Lines file
+///
+/// Streams the file line by line, so files of any size are analyzed in
+/// bounded memory.
+///
+/// # Arguments
+///
+/// * `path` - Path to the JSON Lines file (optionally gzip-compressed)
+///
+/// # Returns
+///
+/// A JsonLinesAnalysis with record counts, invalid lines and the merged
+/// record schema.
+pub fn analyze_jsonl(path: &Path) -> Result<JsonLinesAnalysis, AnalysisError> {
+    analyze_jsonl_from(DataReader::open(path)?)
+}
+
+/// Analyze JSON Lines read from a file or archive member
+pub(crate) fn analyze_jsonl_from(data: DataReader) -> Result<JsonLinesAnalysis, AnalysisError> {
+    let mut reader = BufReader::new(data);
+    let mut fields: Vec<JsonLinesField> = Vec::new();
+    let mut stats: Vec<StreamingColumnStats> = Vec::new();
+    // Object record each field was last seen in, to skip repeated keys
+    let mut last_seen: Vec<usize> = Vec::new();
+    let mut field_index: HashMap<String, usize> = HashMap::new();
+    let mut record_count = 0;
+    let mut object_count = 0;
+    let mut invalid_line_count = 0;
+    let mut invalid_lines = Vec::new();
+
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            // A damaged .gz is still analyzed up to the damage
+            Err(e) if is_corrupt(&e) => break,
+            Err(e) => return Err(e.into()),
+        }
+        line_number += 1;
+
+        let parsed = match std::str::from_utf8(&line) {
+            Ok(text) => {
+                let text = text.trim_start_matches('\u{feff}').trim();
+                if text.is_empty() {
+                    continue;
+                }
+                parse_json(text)
+            }
+            Err(_) => Err("Invalid UTF-8".to_string()),
+        };
+        let record = match parsed {
+            Ok(record) => record,
+            Err(error) => {
+                invalid_line_count += 1;
+                if invalid_lines.len() < MAX_INVALID_SAMPLES {
+                    invalid_lines.push((line_number, error));
+                }
+                continue;
+            }
+        };
+
+        record_count += 1;
+        let members = match record {
+            JsonValue::Object(members) => members,
+            _ => continue,
+        };
+        object_count += 1;
+
+        for (key, value) in &members {
+            let idx = match field_index.get(key) {
+                Some(&idx) => idx,
+                None if fields.len() < MAX_FIELDS => {
+                    field_index.insert(key.clone(), fields.len());
+                    fields.push(JsonLinesField {
+                        name: key.clone(),
+                        present_count: 0,
+                        type_counts: Vec::new(),
+                    });
+                    stats.push(StreamingColumnStats::new());
+                    last_seen.push(0);
+                    fields.len() - 1
+                }
+                None => continue,
+            };
+
+            // A repeated key within a record counts once
+            if last_seen[idx] == object_count {
+                continue;
+            }
+            last_seen[idx] = object_count;
+
+            let field = &mut fields[idx];
+            field.present_count += 1;
+            let type_name = json_type(value);
+            match field.type_counts.iter_mut().find(|(name, _)| *name == type_name) {
+                Some((_, count)) => *count += 1,
+                None => field.type_counts.push((type_name, 1)),
+            }
+            stats[idx].update(&cell_text(value));
+        }
+    }
+
+    let mut table = CsvAnalysis::new(',', true);
+    table.row_count = object_count;
+    table.column_count = fields.len();
+    table.gzip = reader.get_ref().gzip_info();
+    table.columns = stats
+        .into_iter()
+        .zip(&fields)
+        .enumerate()
+        .map(|(idx, (mut column, field))| {
+            // Records without the field count as nulls
+            column.add_nulls((object_count - field.present_count) as u64);
+            let mut info = column.into_column_info(idx, Some(field.name.clone()));
+            // Quoted numbers ("00501") are strings, whatever they look like
+            let types = field.value_types();
+            if types.len() == 1 && types[0].0 == "string" {
+                if let ColumnType::Integer | ColumnType::Float | ColumnType::Boolean = info.inferred_type {
+                    info = info.with_type(ColumnType::String).with_range(None, None);
+                }
+            }
+            info
+        })
+        .collect();
+
+    Ok(JsonLinesAnalysis {
+        record_count,
+        non_object_count: record_count - object_count,
+        invalid_line_count,
+        invalid_lines,
+        fields,
+        table,
+    })
+}
+
+/// JSON type of a value, with whole numbers told apart as `integer`
+fn json_type(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Boolean(_) => "boolean",
+        JsonValue::Number(n) if n.fract() == 0.0 => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+/// A value as a CSV cell: null is empty, nested values are compact JSON
+fn cell_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        other => compact_json(other),
+    }
+}
+
+/// Render a value as single-line JSON
+fn compact_json(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Boolean(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => quote(s),
+        JsonValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(compact_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        JsonValue::Object(members) => {
+            let members: Vec<String> = members
+                .iter()
+                .map(|(key, value)| format!("{}:{}", quote(key), compact_json(value)))
+                .collect();
+            format!("{{{}}}", members.join(","))
+        }
+    }
+}
+
+/// Quote a string as a JSON string literal
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn analyze(test: &str, content: &str) -> JsonLinesAnalysis {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_jsonl_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("records.jsonl");
+        fs::write(&path, content).unwrap();
+        let analysis = analyze_jsonl(&path).unwrap();
+        fs::remove_dir_all(temp_dir).ok();
+        analysis
+    }
+
+    #[test]
+    fn test_record_schema() {
+        let analysis = analyze(
+            "schema",
+            concat!(
+                "{\"id\": 1, \"temp\": 21.5, \"site\": \"north\", \"tags\": [\"a\"]}\n",
+                "{\"id\": 2, \"temp\": null, \"site\": \"south\"}\n",
+                "\n",
+                "{\"id\": 3, \"temp\": 19, \"site\": \"north\", \"zip\": \"00501\"}\n",
+            ),
+        );
+
+        assert_eq!(analysis.record_count, 3);
+        assert_eq!(analysis.invalid_line_count, 0);
+        let names: Vec<&str> = analysis.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "temp", "site", "tags", "zip"]);
+        assert_eq!(analysis.fields[1].present_count, 3);
+        assert_eq!(analysis.fields[1].type_counts, vec![("number", 1), ("null", 1), ("integer", 1)]);
+        assert_eq!(analysis.fields[3].present_count, 1);
+
+        let table = &analysis.table;
+        assert_eq!(table.row_count, 3);
+        assert_eq!(table.column_count, 5);
+        assert_eq!(table.columns[0].inferred_type, ColumnType::Identifier);
+        assert_eq!(table.columns[1].inferred_type, ColumnType::Float);
+        assert_eq!(table.columns[1].null_count, 1);
+        assert_eq!(table.columns[1].max_value, Some(21.5));
+        assert_eq!(table.columns[2].unique_count, 2);
+        assert_eq!(table.columns[3].null_count, 2);
+        assert_eq!(table.columns[3].sample_values, vec!["[\"a\"]"]);
+        assert_eq!(table.columns[4].inferred_type, ColumnType::String);
+    }
+
+    #[test]
+    fn test_invalid_and_non_object_lines() {
+        let analysis = analyze(
+            "invalid",
+            "{\"a\": 1}\n{\"a\": 2,}\n[1, 2]\n{\"a\": 3} trailing\r\n{\"a\": 4, \"a\": 5}\n",
+        );
+
+        assert_eq!(analysis.record_count, 3);
+        assert_eq!(analysis.non_object_count, 1);
+        assert_eq!(analysis.invalid_line_count, 2);
+        assert_eq!(analysis.invalid_lines[0].0, 2);
+        assert_eq!(analysis.invalid_lines[1], (4, "Unexpected trailing character: t".to_string()));
+        assert_eq!(analysis.fields[0].present_count, 2);
+        assert_eq!(analysis.table.row_count, 2);
+    }
+
+    #[test]
+    fn test_deeply_nested_line_is_invalid() {
+        let content = format!("{{\"a\": 1}}\n{}{}\n", "[".repeat(50_000), "]".repeat(50_000));
+        let analysis = analyze("deep", &content);
+
+        assert_eq!(analysis.record_count, 1);
+        assert_eq!(
+            analysis.invalid_lines,
+            vec![(2, "Value nested too deeply (more than 128 levels)".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_compact_json() {
+        let value = parse_json(r#"{"k": [1, 2.5, true, null, "q\"\n"]}"#).unwrap();
+        assert_eq!(compact_json(&value), r#"{"k":[1,2.5,true,null,"q\"\n"]}"#);
+    }
+}
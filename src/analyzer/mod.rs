@@ -4,20 +4,65 @@
 //! and other characteristics needed for validation and documentation.
 
 mod binary;
+mod config;
 mod csv;
+mod fits;
+mod hdf5;
+mod image;
 mod inference;
 mod json;
+mod json_stream;
+mod jsonl;
+mod netcdf;
+mod numpy;
+mod parquet;
+mod pdf;
+mod sequence;
+mod sqlite;
 mod text;
+mod tiff;
+mod toml;
+mod vcf;
+mod xml;
+mod yaml;
+mod zarr;
 
-use crate::types::{AnalysisResult, FileInfo, FileType};
+use crate::archive;
+use crate::compression::DataReader;
+use crate::types::{
+    AnalysisResult, ArchiveMember, BinaryAnalysis, BinaryType, ConfigFormat, FileInfo, FileType,
+    SequenceFormat,
+};
 use std::fmt;
-use std::io;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
 
 pub use binary::{detect_binary_type, is_binary};
+pub use config::analyze_config;
 pub use csv::analyze_csv;
+pub(crate) use csv::parse_line_rfc4180;
+pub use fits::analyze_fits;
+pub use hdf5::analyze_hdf5;
+pub use image::{analyze_image, image_distribution};
 pub use inference::infer_column_type;
-pub use json::analyze_json;
+pub use json::{
+    analyze_json, json_pointer_token, json_syntax_error, parse_json, parse_json_with_locations, JsonLocations,
+    JsonValue,
+};
+pub use jsonl::analyze_jsonl;
+pub use netcdf::analyze_netcdf;
+pub use numpy::analyze_numpy;
+pub use parquet::analyze_parquet;
+pub use pdf::analyze_pdf;
+pub use sequence::analyze_sequences;
+pub use sqlite::analyze_sqlite;
 pub use text::analyze_text;
+pub use tiff::analyze_tiff;
+pub use toml::parse_toml;
+pub use vcf::analyze_vcf;
+pub use xml::{analyze_xml, metadata_standard};
+pub use yaml::parse_yaml;
+pub use zarr::analyze_zarr;
 
 /// Errors that can occur during file analysis
 #[derive(Debug)]
@@ -78,6 +123,10 @@ impl std::error::Error for AnalysisError {}
 /// let result = analyze_file(&file_info);
 /// ```
 pub fn analyze_file(file_info: &FileInfo) -> AnalysisResult {
+    if let Some(member) = &file_info.archive_member {
+        return analyze_member(file_info, member);
+    }
+
     match file_info.file_type {
         FileType::Csv | FileType::Tsv => {
             match csv::analyze_csv(&file_info.full_path) {
@@ -91,6 +140,36 @@ pub fn analyze_file(file_info: &FileInfo) -> AnalysisResult {
                 Err(_) => AnalysisResult::NotAnalyzed,
             }
         }
+        FileType::JsonLines => {
+            match jsonl::analyze_jsonl(&file_info.full_path) {
+                Ok(analysis) => AnalysisResult::JsonLines(analysis),
+                Err(_) => AnalysisResult::NotAnalyzed,
+            }
+        }
+        FileType::Yaml | FileType::Toml => {
+            match config::analyze_config(&file_info.full_path, config_format(file_info.file_type)) {
+                Ok(analysis) => AnalysisResult::Config(analysis),
+                Err(_) => AnalysisResult::NotAnalyzed,
+            }
+        }
+        FileType::Xml => {
+            match xml::analyze_xml(&file_info.full_path) {
+                Ok(analysis) => AnalysisResult::Xml(analysis),
+                Err(_) => AnalysisResult::NotAnalyzed,
+            }
+        }
+        FileType::Fasta | FileType::Fastq => {
+            match sequence::analyze_sequences(&file_info.full_path, sequence_format(file_info.file_type)) {
+                Ok(analysis) => AnalysisResult::Sequence(analysis),
+                Err(_) => AnalysisResult::NotAnalyzed,
+            }
+        }
+        FileType::Vcf => {
+            match vcf::analyze_vcf(&file_info.full_path) {
+                Ok(analysis) => AnalysisResult::Vcf(analysis),
+                Err(_) => AnalysisResult::NotAnalyzed,
+            }
+        }
         FileType::Text | FileType::Markdown => {
             match text::analyze_text(&file_info.full_path) {
                 Ok(analysis) => AnalysisResult::Text(analysis),
@@ -100,12 +179,147 @@ pub fn analyze_file(file_info: &FileInfo) -> AnalysisResult {
         FileType::Binary => {
             let binary_type = binary::detect_binary_type(&file_info.full_path)
                 .unwrap_or(crate::types::BinaryType::Unknown);
+            match binary_type {
+                crate::types::BinaryType::Hdf5 => {
+                    if let Ok(analysis) = hdf5::analyze_hdf5(&file_info.full_path) {
+                        return AnalysisResult::Hdf5(analysis);
+                    }
+                }
+                crate::types::BinaryType::Netcdf => {
+                    if let Ok(analysis) = netcdf::analyze_netcdf(&file_info.full_path) {
+                        return AnalysisResult::Netcdf(analysis);
+                    }
+                }
+                crate::types::BinaryType::Fits => {
+                    if let Ok(analysis) = fits::analyze_fits(&file_info.full_path) {
+                        return AnalysisResult::Fits(analysis);
+                    }
+                }
+                crate::types::BinaryType::Parquet => {
+                    if let Ok(analysis) = parquet::analyze_parquet(&file_info.full_path) {
+                        return AnalysisResult::Parquet(analysis);
+                    }
+                }
+                crate::types::BinaryType::Sqlite => {
+                    if let Ok(analysis) = sqlite::analyze_sqlite(&file_info.full_path) {
+                        return AnalysisResult::Sqlite(analysis);
+                    }
+                }
+                crate::types::BinaryType::Zip | crate::types::BinaryType::Tar => {
+                    // .zip and .tar are archives; any other ZIP (.npz) holds NumPy arrays
+                    if let Some(format) = archive::format_from_path(&file_info.full_path) {
+                        if let Ok(analysis) = archive::read_archive(&file_info.full_path, format) {
+                            return AnalysisResult::Archive(analysis);
+                        }
+                    } else if let Ok(analysis) = numpy::analyze_numpy(&file_info.full_path) {
+                        return AnalysisResult::Numpy(analysis);
+                    }
+                }
+                crate::types::BinaryType::Npy => {
+                    if let Ok(analysis) = numpy::analyze_numpy(&file_info.full_path) {
+                        return AnalysisResult::Numpy(analysis);
+                    }
+                }
+                crate::types::BinaryType::Png | crate::types::BinaryType::Jpeg => {
+                    if let Ok(image) = image::analyze_image(&file_info.full_path) {
+                        return AnalysisResult::Binary(
+                            crate::types::BinaryAnalysis::new(binary_type).with_image(image),
+                        );
+                    }
+                }
+                crate::types::BinaryType::Pdf => {
+                    if let Ok(analysis) = pdf::analyze_pdf(&file_info.full_path) {
+                        return AnalysisResult::Pdf(analysis);
+                    }
+                }
+                crate::types::BinaryType::Tiff => {
+                    if let Ok(analysis) = tiff::analyze_tiff(&file_info.full_path) {
+                        return AnalysisResult::Tiff(analysis);
+                    }
+                }
+                _ => {}
+            }
             AnalysisResult::Binary(crate::types::BinaryAnalysis::new(binary_type))
         }
+        FileType::Zarr => match zarr::analyze_zarr(&file_info.full_path) {
+            Ok(analysis) => AnalysisResult::Zarr(analysis),
+            Err(_) => AnalysisResult::NotAnalyzed,
+        },
         FileType::Unknown => AnalysisResult::NotAnalyzed,
     }
 }
 
+/// Analyze a member of a ZIP or tar archive by streaming its contents
+///
+/// CSV/TSV, JSON, JSON Lines, YAML, TOML, XML and text members get the same analysis as
+/// files on disk.
+/// Binary members are identified by their magic number only: the format
+/// analyzers need random access, which a compressed member does not allow.
+fn analyze_member(file_info: &FileInfo, member: &ArchiveMember) -> AnalysisResult {
+    let result = match file_info.file_type {
+        FileType::Csv | FileType::Tsv => {
+            csv::analyze_csv_from(|| DataReader::open_member(member)).map(AnalysisResult::Csv)
+        }
+        FileType::Json => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(json::analyze_json_from)
+            .map(AnalysisResult::Json),
+        FileType::JsonLines => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(jsonl::analyze_jsonl_from)
+            .map(AnalysisResult::JsonLines),
+        FileType::Yaml | FileType::Toml => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(|data| config::analyze_config_from(data, config_format(file_info.file_type)))
+            .map(AnalysisResult::Config),
+        FileType::Xml => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(xml::analyze_xml_from)
+            .map(AnalysisResult::Xml),
+        FileType::Fasta | FileType::Fastq => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(|data| sequence::analyze_sequences_from(data, sequence_format(file_info.file_type)))
+            .map(AnalysisResult::Sequence),
+        FileType::Vcf => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(vcf::analyze_vcf_from)
+            .map(AnalysisResult::Vcf),
+        FileType::Text | FileType::Markdown => DataReader::open_member(member)
+            .map_err(AnalysisError::from)
+            .and_then(|data| text::analyze_text_from(BufReader::new(data), Path::new(&member.entry.name)))
+            .map(AnalysisResult::Text),
+        FileType::Binary => DataReader::open_member(member)
+            .and_then(|data| {
+                let mut header = Vec::with_capacity(binary::HEADER_SIZE);
+                data.take(binary::HEADER_SIZE as u64).read_to_end(&mut header)?;
+                Ok(header)
+            })
+            .map(|header| {
+                let binary_type = binary::binary_type_of(&header).unwrap_or(BinaryType::Unknown);
+                AnalysisResult::Binary(BinaryAnalysis::new(binary_type))
+            })
+            .map_err(AnalysisError::from),
+        FileType::Zarr | FileType::Unknown => return AnalysisResult::NotAnalyzed,
+    };
+    result.unwrap_or(AnalysisResult::NotAnalyzed)
+}
+
+/// The format a YAML or TOML file type is parsed as
+fn config_format(file_type: FileType) -> ConfigFormat {
+    match file_type {
+        FileType::Toml => ConfigFormat::Toml,
+        _ => ConfigFormat::Yaml,
+    }
+}
+
+/// The format a FASTA or FASTQ file type is read as
+fn sequence_format(file_type: FileType) -> SequenceFormat {
+    match file_type {
+        FileType::Fastq => SequenceFormat::Fastq,
+        _ => SequenceFormat::Fasta,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,6 +373,48 @@ mod tests {
         fs::remove_dir_all(temp_dir).ok();
     }
 
+    #[test]
+    fn test_analyze_yaml_and_toml() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_config");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        fs::write(temp_dir.join("CITATION.cff"), "cff-version: 1.2.0\ntitle: Survey\n").unwrap();
+        fs::write(temp_dir.join("pyproject.toml"), "[project]\nname = \"survey\"\n").unwrap();
+
+        for (name, format) in [("CITATION.cff", ConfigFormat::Yaml), ("pyproject.toml", ConfigFormat::Toml)] {
+            let file_info = FileInfo::new(temp_dir.join(name), PathBuf::from(name));
+            match analyze_file(&file_info) {
+                AnalysisResult::Config(analysis) => {
+                    assert_eq!(analysis.format, format);
+                    assert!(analysis.is_valid);
+                }
+                _ => panic!("Expected config analysis result for {}", name),
+            }
+        }
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_xml() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_xml");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("datacite.xml");
+        fs::write(&file_path, r#"<resource xmlns="http://datacite.org/schema/kernel-4"/>"#).unwrap();
+
+        let file_info = FileInfo::new(file_path.clone(), PathBuf::from("datacite.xml"));
+        match analyze_file(&file_info) {
+            AnalysisResult::Xml(analysis) => {
+                assert!(analysis.is_well_formed);
+                assert_eq!(analysis.standard, Some(crate::types::MetadataStandard::DataCite));
+            }
+            _ => panic!("Expected XML analysis result"),
+        }
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
     #[test]
     fn test_analyze_text() {
         let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_text");
@@ -179,4 +435,121 @@ mod tests {
 
         fs::remove_dir_all(temp_dir).ok();
     }
+
+    #[test]
+    fn test_analyze_parquet_as_table() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_parquet");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("readings.parquet");
+        fs::write(&file_path, parquet::fixture::sample_file()).unwrap();
+
+        let file_info = FileInfo::new(file_path.clone(), PathBuf::from("readings.parquet"));
+        let result = analyze_file(&file_info);
+
+        assert!(matches!(result, AnalysisResult::Parquet(_)));
+        let table = result.table().expect("Parquet should expose a table");
+        assert_eq!(table.row_count, 5);
+        assert_eq!(table.columns[2].name.as_deref(), Some("station"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_sqlite_tables() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_sqlite");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("survey.db");
+        fs::write(&file_path, sqlite::fixture::sample_database()).unwrap();
+
+        let file_info = FileInfo::new(file_path.clone(), PathBuf::from("survey.db"));
+        let result = analyze_file(&file_info);
+
+        assert!(matches!(result, AnalysisResult::Sqlite(_)));
+        assert!(result.table().is_none());
+        let tables = result.tables();
+        let names: Vec<Option<&str>> = tables.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec![Some("samples"), Some("log"), Some("codes")]);
+        assert_eq!(tables[0].1.row_count, 4);
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_sequence_and_vcf() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_genomics");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let fasta_path = temp_dir.join("contigs.fa");
+        fs::write(&fasta_path, ">c1\nACGT\n>c2\nGG\n").unwrap();
+        let vcf_path = temp_dir.join("calls.vcf");
+        fs::write(&vcf_path, "##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\nc1\t2\t.\tC\tT\t.\t.\t.\n")
+            .unwrap();
+
+        let fasta_info = FileInfo::new(fasta_path, PathBuf::from("contigs.fa")).with_type(FileType::Fasta);
+        match analyze_file(&fasta_info) {
+            AnalysisResult::Sequence(analysis) => assert_eq!(analysis.record_count, 2),
+            _ => panic!("Expected sequence analysis result"),
+        }
+        let vcf_info = FileInfo::new(vcf_path, PathBuf::from("calls.vcf")).with_type(FileType::Vcf);
+        match analyze_file(&vcf_info) {
+            AnalysisResult::Vcf(analysis) => assert_eq!(analysis.variant_count, 1),
+            _ => panic!("Expected VCF analysis result"),
+        }
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_jsonl_as_table() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_jsonl");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("events.ndjson");
+        fs::write(&file_path, "{\"t\": 1, \"kind\": \"start\"}\n{\"t\": 2, \"kind\": \"stop\"}\n").unwrap();
+
+        let file_info = FileInfo::new(file_path.clone(), PathBuf::from("events.ndjson"));
+        let result = analyze_file(&file_info);
+
+        assert!(matches!(result, AnalysisResult::JsonLines(_)));
+        let table = result.table().expect("JSON Lines should expose a table");
+        assert_eq!(table.row_count, 2);
+        assert_eq!(table.columns[1].name.as_deref(), Some("kind"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_archive_members() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_analyze_archive_members");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("bundle.tar");
+        let parquet = parquet::fixture::sample_file();
+        fs::write(
+            &file_path,
+            archive::tar::fixture::tar(&[("obs.csv", b"id,value\n1,2.5\n2,3.5\n"), ("readings.parquet", &parquet)]),
+        )
+        .unwrap();
+
+        let archive_info = FileInfo::new(file_path.clone(), PathBuf::from("bundle.tar"));
+        assert!(matches!(analyze_file(&archive_info), AnalysisResult::Archive(_)));
+
+        let config = crate::types::Config::default();
+        let members = crate::scanner::build_member_infos(&archive_info, &config).unwrap();
+        match analyze_file(&members[0]) {
+            AnalysisResult::Csv(analysis) => {
+                assert_eq!(analysis.row_count, 2);
+                assert_eq!(analysis.columns[1].name.as_deref(), Some("value"));
+            }
+            other => panic!("Expected CSV analysis result, got {:?}", other),
+        }
+        match analyze_file(&members[1]) {
+            AnalysisResult::Binary(binary) => assert_eq!(binary.binary_type, BinaryType::Parquet),
+            other => panic!("Expected binary analysis result, got {:?}", other),
+        }
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
 }
@@ -0,0 +1,464 @@
+//! NetCDF classic header parsing
+//!
+//! Reads the header of classic (CDF-1), 64-bit offset (CDF-2) and 64-bit
+//! data (CDF-5) files: dimensions, global attributes, and variables with
+//! their attributes. Variable data is never read. NetCDF-4 files are HDF5
+//! files underneath and are handled by the HDF5 analyzer.
+
+use super::AnalysisError;
+use crate::types::{
+    NetcdfAnalysis, NetcdfAttribute, NetcdfDimension, NetcdfFormat, NetcdfType, NetcdfVariable,
+};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Tag of a dimension list
+const NC_DIMENSION: u32 = 0x0A;
+
+/// Tag of a variable list
+const NC_VARIABLE: u32 = 0x0B;
+
+/// Tag of an attribute list
+const NC_ATTRIBUTE: u32 = 0x0C;
+
+/// Record count written by streaming writers that never patch the header
+const STREAMING: u64 = 0xFFFF_FFFF;
+
+/// Largest single header field we are willing to read
+const MAX_FIELD_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Numeric attribute values kept per attribute
+const MAX_RENDERED_VALUES: usize = 8;
+
+/// Build an `InvalidFormat` error with a `NetCDF:` prefix
+fn invalid(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidFormat(format!("NetCDF: {}", message.into()))
+}
+
+/// Analyze the header of a netCDF classic file
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with `CDF\x01`, `CDF\x02` or `CDF\x05`
+///
+/// # Returns
+///
+/// The dimensions, global attributes and variables declared in the header.
+pub fn analyze_netcdf(path: &Path) -> Result<NetcdfAnalysis, AnalysisError> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut header = Header {
+        reader: BufReader::new(file),
+        remaining: file_len,
+        wide_counts: false,
+        wide_offsets: false,
+    };
+
+    let magic = header.bytes(4)?;
+    if &magic[..3] != b"CDF" {
+        return Err(invalid("signature not found"));
+    }
+    let format = match magic[3] {
+        1 => NetcdfFormat::Classic,
+        2 => NetcdfFormat::Offset64,
+        5 => NetcdfFormat::Data64,
+        other => return Err(invalid(format!("unsupported format version {}", other))),
+    };
+    header.wide_counts = format == NetcdfFormat::Data64;
+    header.wide_offsets = format != NetcdfFormat::Classic;
+
+    let mut analysis = NetcdfAnalysis::new(format);
+    let record_count = header.non_neg()?;
+    analysis.record_count = if !header.wide_counts && record_count == STREAMING {
+        0
+    } else {
+        record_count
+    };
+
+    analysis.dimensions = read_dimensions(&mut header, analysis.record_count)?;
+    analysis.global_attributes = read_attributes(&mut header)?;
+    analysis.variables = read_variables(&mut header, &analysis.dimensions)?;
+
+    Ok(analysis)
+}
+
+/// Read the dimension list
+fn read_dimensions(header: &mut Header, record_count: u64) -> Result<Vec<NetcdfDimension>, AnalysisError> {
+    let count = header.list(NC_DIMENSION, "dimension")?;
+    let mut dimensions = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name = header.name()?;
+        let length = header.non_neg()?;
+        let is_unlimited = length == 0;
+        if is_unlimited && dimensions.iter().any(|d: &NetcdfDimension| d.is_unlimited) {
+            return Err(invalid("more than one unlimited dimension"));
+        }
+        dimensions.push(NetcdfDimension {
+            name,
+            length: if is_unlimited { record_count } else { length },
+            is_unlimited,
+        });
+    }
+    Ok(dimensions)
+}
+
+/// Read a global or variable attribute list
+fn read_attributes(header: &mut Header) -> Result<Vec<NetcdfAttribute>, AnalysisError> {
+    let count = header.list(NC_ATTRIBUTE, "attribute")?;
+    let mut attributes = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name = header.name()?;
+        let nc_type = header.nc_type()?;
+        let values = header.non_neg()?;
+        let size = values
+            .checked_mul(nc_type.size())
+            .ok_or_else(|| invalid(format!("attribute '{}' is too large", name)))?;
+        let data = header.bytes(size)?;
+        header.skip_padding(size)?;
+
+        let (value, numbers) = render_values(nc_type, &data);
+        attributes.push(NetcdfAttribute {
+            name,
+            nc_type,
+            count: values,
+            value,
+            numbers,
+        });
+    }
+    Ok(attributes)
+}
+
+/// Read the variable list
+fn read_variables(
+    header: &mut Header,
+    dimensions: &[NetcdfDimension],
+) -> Result<Vec<NetcdfVariable>, AnalysisError> {
+    let count = header.list(NC_VARIABLE, "variable")?;
+    let mut variables = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name = header.name()?;
+        let rank = header.count()?;
+        let mut dimension_names = Vec::with_capacity(rank);
+        let mut shape = Vec::with_capacity(rank);
+        for _ in 0..rank {
+            let id = header.non_neg()?;
+            let dimension = dimensions
+                .get(id as usize)
+                .ok_or_else(|| invalid(format!("variable '{}' uses unknown dimension {}", name, id)))?;
+            dimension_names.push(dimension.name.clone());
+            shape.push(dimension.length);
+        }
+        let attributes = read_attributes(header)?;
+        let nc_type = header.nc_type()?;
+        header.non_neg()?; // vsize
+        header.offset()?; // begin
+
+        variables.push(NetcdfVariable {
+            name,
+            dimensions: dimension_names,
+            shape,
+            nc_type,
+            attributes,
+        });
+    }
+    Ok(variables)
+}
+
+/// Render attribute data as text, plus the leading numeric values
+fn render_values(nc_type: NetcdfType, data: &[u8]) -> (String, Vec<f64>) {
+    if nc_type == NetcdfType::Char {
+        let text = String::from_utf8_lossy(data);
+        return (text.trim_end_matches('\0').to_string(), Vec::new());
+    }
+
+    let size = nc_type.size() as usize;
+    let total = data.len() / size;
+    let mut rendered = Vec::new();
+    let mut numbers = Vec::new();
+
+    for chunk in data.chunks_exact(size).take(MAX_RENDERED_VALUES) {
+        let raw = chunk.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        let (text, number) = match nc_type {
+            NetcdfType::Byte => ((raw as u8 as i8).to_string(), raw as u8 as i8 as f64),
+            NetcdfType::Short => ((raw as u16 as i16).to_string(), raw as u16 as i16 as f64),
+            NetcdfType::Int => ((raw as u32 as i32).to_string(), raw as u32 as i32 as f64),
+            NetcdfType::Int64 => ((raw as i64).to_string(), raw as i64 as f64),
+            NetcdfType::Float => {
+                let value = f32::from_bits(raw as u32);
+                (value.to_string(), value as f64)
+            }
+            NetcdfType::Double => {
+                let value = f64::from_bits(raw);
+                (value.to_string(), value)
+            }
+            _ => (raw.to_string(), raw as f64),
+        };
+        rendered.push(text);
+        numbers.push(number);
+    }
+
+    let mut text = rendered.join(", ");
+    if total > MAX_RENDERED_VALUES {
+        text.push_str(&format!(", ... ({} values)", total));
+    }
+    (text, numbers)
+}
+
+/// Sequential big-endian reader over a netCDF header
+struct Header {
+    reader: BufReader<File>,
+    /// Bytes left in the file; counts are checked against this
+    remaining: u64,
+    /// Whether counts (`NON_NEG`) are 64-bit (CDF-5)
+    wide_counts: bool,
+    /// Whether variable offsets are 64-bit (CDF-2 and CDF-5)
+    wide_offsets: bool,
+}
+
+impl Header {
+    /// Read the next `n` bytes
+    fn bytes(&mut self, n: u64) -> Result<Vec<u8>, AnalysisError> {
+        if n > self.remaining {
+            return Err(invalid("header is truncated"));
+        }
+        if n > MAX_FIELD_SIZE {
+            return Err(invalid(format!("header field of {} bytes is too large", n)));
+        }
+        let mut buffer = vec![0u8; n as usize];
+        self.reader.read_exact(&mut buffer)?;
+        self.remaining -= n;
+        Ok(buffer)
+    }
+
+    /// Read a big-endian unsigned integer of `n` bytes
+    fn uint(&mut self, n: u64) -> Result<u64, AnalysisError> {
+        Ok(self
+            .bytes(n)?
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    /// Read a `NON_NEG` count or length
+    fn non_neg(&mut self) -> Result<u64, AnalysisError> {
+        self.uint(if self.wide_counts { 8 } else { 4 })
+    }
+
+    /// Read a variable data offset
+    fn offset(&mut self) -> Result<u64, AnalysisError> {
+        self.uint(if self.wide_offsets { 8 } else { 4 })
+    }
+
+    /// Read an element count, rejecting counts the file is too small to hold
+    fn count(&mut self) -> Result<usize, AnalysisError> {
+        let count = self.non_neg()?;
+        if count > self.remaining {
+            return Err(invalid(format!("count {} is larger than the file", count)));
+        }
+        Ok(count as usize)
+    }
+
+    /// Read an `nc_type` code
+    fn nc_type(&mut self) -> Result<NetcdfType, AnalysisError> {
+        let code = self.uint(4)? as u32;
+        NetcdfType::from_code(code).ok_or_else(|| invalid(format!("unknown type code {}", code)))
+    }
+
+    /// Read a list header, returning the number of elements
+    ///
+    /// An absent list is written as a zero tag and a zero count.
+    fn list(&mut self, tag: u32, what: &str) -> Result<usize, AnalysisError> {
+        let found = self.uint(4)? as u32;
+        let count = self.count()?;
+        if found == 0 && count == 0 {
+            return Ok(0);
+        }
+        if found != tag {
+            return Err(invalid(format!("expected {} list, found tag {:#x}", what, found)));
+        }
+        Ok(count)
+    }
+
+    /// Read a name: a length, the UTF-8 bytes and padding to 4 bytes
+    fn name(&mut self) -> Result<String, AnalysisError> {
+        let length = self.non_neg()?;
+        let bytes = self.bytes(length)?;
+        self.skip_padding(length)?;
+        String::from_utf8(bytes).map_err(|_| invalid("name is not valid UTF-8"))
+    }
+
+    /// Skip the padding that rounds a field of `length` bytes up to 4 bytes
+    fn skip_padding(&mut self, length: u64) -> Result<(), AnalysisError> {
+        let padding = (4 - length % 4) % 4;
+        self.bytes(padding).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::write_temp_file;
+    use std::fs;
+
+    /// Writes a netCDF header field by field
+    struct Writer {
+        bytes: Vec<u8>,
+        wide: bool,
+    }
+
+    impl Writer {
+        fn new(version: u8) -> Self {
+            Writer {
+                bytes: vec![b'C', b'D', b'F', version],
+                wide: version == 5,
+            }
+        }
+
+        fn u32(&mut self, value: u32) -> &mut Self {
+            self.bytes.extend_from_slice(&value.to_be_bytes());
+            self
+        }
+
+        fn non_neg(&mut self, value: u64) -> &mut Self {
+            if self.wide {
+                self.bytes.extend_from_slice(&value.to_be_bytes());
+            } else {
+                self.bytes.extend_from_slice(&(value as u32).to_be_bytes());
+            }
+            self
+        }
+
+        fn padded(&mut self, data: &[u8]) -> &mut Self {
+            self.bytes.extend_from_slice(data);
+            while !self.bytes.len().is_multiple_of(4) {
+                self.bytes.push(0);
+            }
+            self
+        }
+
+        fn name(&mut self, name: &str) -> &mut Self {
+            self.non_neg(name.len() as u64).padded(name.as_bytes())
+        }
+
+        fn list(&mut self, tag: u32, count: u64) -> &mut Self {
+            if count == 0 {
+                self.u32(0).non_neg(0)
+            } else {
+                self.u32(tag).non_neg(count)
+            }
+        }
+
+        fn text_attribute(&mut self, name: &str, value: &str) -> &mut Self {
+            self.name(name).u32(2).non_neg(value.len() as u64).padded(value.as_bytes())
+        }
+
+        fn float_attribute(&mut self, name: &str, values: &[f32]) -> &mut Self {
+            let data: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+            self.name(name).u32(5).non_neg(values.len() as u64).padded(&data)
+        }
+
+        fn variable(&mut self, name: &str, dims: &[u64]) -> &mut Self {
+            self.name(name).non_neg(dims.len() as u64);
+            for &dim in dims {
+                self.non_neg(dim);
+            }
+            self
+        }
+
+        fn variable_end(&mut self, nc_type: u32, offset: u64) -> &mut Self {
+            self.u32(nc_type).non_neg(0);
+            if self.wide || self.bytes[3] == 2 {
+                self.bytes.extend_from_slice(&offset.to_be_bytes());
+            } else {
+                self.u32(offset as u32);
+            }
+            self
+        }
+    }
+
+    /// A classic file with time(unlimited, 3 records), lat(2) and
+    /// temperature(time, lat)
+    fn classic_file() -> Vec<u8> {
+        let mut w = Writer::new(1);
+        w.non_neg(3);
+        w.list(NC_DIMENSION, 2).name("time").non_neg(0).name("lat").non_neg(2);
+        w.list(NC_ATTRIBUTE, 2)
+            .text_attribute("Conventions", "CF-1.8")
+            .text_attribute("title", "Station air temperature");
+        w.list(NC_VARIABLE, 2);
+        w.variable("time", &[0])
+            .list(NC_ATTRIBUTE, 1)
+            .text_attribute("units", "days since 2024-01-01")
+            .variable_end(6, 376);
+        w.variable("temperature", &[0, 1])
+            .list(NC_ATTRIBUTE, 3)
+            .text_attribute("units", "K")
+            .float_attribute("_FillValue", &[-999.0])
+            .float_attribute("valid_range", &[180.0, 340.5])
+            .variable_end(5, 400);
+        w.bytes.resize(424, 0);
+        w.bytes
+    }
+
+    #[test]
+    fn test_analyze_classic_header() {
+        let path = write_temp_file("genesis_preflight_netcdf_classic", "data.nc", &classic_file());
+        let analysis = analyze_netcdf(&path).unwrap();
+
+        assert_eq!(analysis.format, NetcdfFormat::Classic);
+        assert_eq!(analysis.record_count, 3);
+        assert_eq!(analysis.dimensions.len(), 2);
+        assert!(analysis.dimensions[0].is_unlimited);
+        assert_eq!(analysis.dimensions[0].length, 3);
+        assert_eq!(analysis.global_attribute("Conventions").unwrap().value, "CF-1.8");
+
+        let temperature = analysis.variable("temperature").unwrap();
+        assert_eq!(temperature.dimensions, vec!["time", "lat"]);
+        assert_eq!(temperature.shape, vec![3, 2]);
+        assert_eq!(temperature.nc_type, NetcdfType::Float);
+        assert_eq!(temperature.attribute("units").unwrap().value, "K");
+        let range = temperature.attribute("valid_range").unwrap();
+        assert_eq!(range.value, "180, 340.5");
+        assert_eq!(range.numbers, vec![180.0, 340.5]);
+        assert!(analysis.variable("time").unwrap().is_coordinate());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_analyze_cdf5_header() {
+        let mut w = Writer::new(5);
+        w.non_neg(0);
+        w.list(NC_DIMENSION, 1).name("x").non_neg(4);
+        w.list(NC_ATTRIBUTE, 0);
+        w.list(NC_VARIABLE, 1);
+        w.variable("counts", &[0]).list(NC_ATTRIBUTE, 0).variable_end(11, 100);
+        let path = write_temp_file("genesis_preflight_netcdf_cdf5", "data.nc", &w.bytes);
+
+        let analysis = analyze_netcdf(&path).unwrap();
+        assert_eq!(analysis.format, NetcdfFormat::Data64);
+        assert!(analysis.global_attributes.is_empty());
+        assert_eq!(analysis.variables[0].nc_type, NetcdfType::UInt64);
+        assert_eq!(analysis.variables[0].shape, vec![4]);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_invalid_headers() {
+        let truncated = classic_file()[..60].to_vec();
+        let path = write_temp_file("genesis_preflight_netcdf_invalid", "truncated.nc", &truncated);
+        assert!(analyze_netcdf(&path).is_err());
+
+        let path = write_temp_file("genesis_preflight_netcdf_invalid", "version.nc", b"CDF\x03\0\0\0\0");
+        assert!(analyze_netcdf(&path).is_err());
+
+        let mut w = Writer::new(1);
+        w.non_neg(0).list(NC_DIMENSION, 0).list(NC_ATTRIBUTE, 0).list(NC_VARIABLE, 1);
+        w.variable("bad", &[7]).list(NC_ATTRIBUTE, 0).variable_end(5, 0);
+        let path = write_temp_file("genesis_preflight_netcdf_invalid", "dimension.nc", &w.bytes);
+        assert!(analyze_netcdf(&path).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
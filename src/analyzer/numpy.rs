@@ -0,0 +1,559 @@
+//! NumPy `.npy` and `.npz` header parsing
+//!
+//! A `.npy` file starts with `\x93NUMPY`, a version, and a Python dict
+//! literal giving the dtype (`descr`), memory order and shape; the raw data
+//! follows. An `.npz` is a ZIP archive of `.npy` members, listed by the
+//! central directory at the end of the file. Headers of stored members are
+//! read in place, and those of deflated members (`np.savez_compressed`)
+//! through a streaming decoder, so only the start of each member is
+//! decompressed; members with other compression are listed without one.
+
+use super::AnalysisError;
+use crate::archive::zip::{self, le32, ZIP_LOCAL_HEADER};
+use crate::compression::Inflater;
+use crate::types::{ArchiveEntry, ArchiveMethod, NpyHeader, NumpyAnalysis, NumpyArray};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Magic at the start of every `.npy` file (and `.npz` member)
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Longest header dict we accept
+const MAX_HEADER_SIZE: usize = 1024 * 1024;
+
+/// Analyze a NumPy `.npy` file or `.npz` archive
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with `\x93NUMPY` or a ZIP signature
+///
+/// # Returns
+///
+/// The header of each array, with the number of bytes stored for it.
+/// Archives without any `.npy` member are rejected.
+pub fn analyze_numpy(path: &Path) -> Result<NumpyAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+
+    let mut magic = [0u8; 6];
+    let read = file.read(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    if read == 6 && &magic == NPY_MAGIC {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut analysis = NumpyAnalysis {
+            archive: false,
+            arrays: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let header = match read_header(&mut file) {
+            Ok(header) => Some(header),
+            Err(message) => {
+                analysis.warnings.push(message);
+                None
+            }
+        };
+        analysis.arrays.push(NumpyArray {
+            name,
+            header,
+            stored_size: file_size,
+            compression: None,
+        });
+        return Ok(analysis);
+    }
+
+    if read >= 4 && le32(&magic, 0) == ZIP_LOCAL_HEADER {
+        return analyze_npz(&mut file, file_size);
+    }
+
+    Err(AnalysisError::InvalidFormat("NumPy: magic not found".to_string()))
+}
+
+/// Read the header of each `.npy` member of an `.npz` archive
+fn analyze_npz(file: &mut File, file_size: u64) -> Result<NumpyAnalysis, AnalysisError> {
+    let entries = zip::read_central_directory(file, file_size).map_err(|e| match e.kind() {
+        io::ErrorKind::InvalidData => AnalysisError::InvalidFormat(format!("NumPy: {}", e)),
+        _ => AnalysisError::Io(e),
+    })?;
+    let mut analysis = NumpyAnalysis {
+        archive: true,
+        arrays: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for entry in entries {
+        let name = match entry.name.strip_suffix(".npy") {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let mut array = NumpyArray {
+            name,
+            header: None,
+            stored_size: entry.size,
+            compression: Some(entry.method.to_string()),
+        };
+
+        if entry.encrypted {
+            analysis.warnings.push(format!("member '{}' is encrypted", entry.name));
+        } else if matches!(entry.method, ArchiveMethod::Stored | ArchiveMethod::Deflate) {
+            match read_member_header(file, file_size, &entry) {
+                Ok(header) => array.header = Some(header),
+                Err(message) => analysis
+                    .warnings
+                    .push(format!("member '{}': {}", entry.name, message)),
+            }
+        }
+        analysis.arrays.push(array);
+    }
+
+    if analysis.arrays.is_empty() {
+        return Err(AnalysisError::InvalidFormat(
+            "NumPy: archive has no .npy members".to_string(),
+        ));
+    }
+    Ok(analysis)
+}
+
+/// Read the header of a stored or deflated archive member
+fn read_member_header(file: &mut File, file_size: u64, entry: &ArchiveEntry) -> Result<NpyHeader, String> {
+    let data_offset = zip::data_offset(file, file_size, entry).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(data_offset))
+        .map_err(|e| e.to_string())?;
+    let data = file.take(entry.compressed_size);
+    match entry.method {
+        ArchiveMethod::Deflate => read_header(&mut Inflater::new(data).take(entry.size)),
+        _ => read_header(&mut data.take(entry.size)),
+    }
+}
+
+/// Parse a `.npy` header from the start of `reader`
+fn read_header(reader: &mut impl Read) -> Result<NpyHeader, String> {
+    let mut preamble = [0u8; 8];
+    reader
+        .read_exact(&mut preamble)
+        .map_err(|_| "header is truncated".to_string())?;
+    if &preamble[..6] != NPY_MAGIC {
+        return Err("\\x93NUMPY magic not found".to_string());
+    }
+    let version = (preamble[6], preamble[7]);
+
+    let (length, length_size) = match version.0 {
+        1 => {
+            let mut raw = [0u8; 2];
+            reader.read_exact(&mut raw).map_err(|_| "header is truncated".to_string())?;
+            (u16::from_le_bytes(raw) as usize, 2)
+        }
+        2 | 3 => {
+            let mut raw = [0u8; 4];
+            reader.read_exact(&mut raw).map_err(|_| "header is truncated".to_string())?;
+            (u32::from_le_bytes(raw) as usize, 4)
+        }
+        major => return Err(format!("format version {}.{} is not supported", major, version.1)),
+    };
+    if length > MAX_HEADER_SIZE {
+        return Err(format!("header length {} is too large", length));
+    }
+
+    let mut raw = vec![0u8; length];
+    reader
+        .read_exact(&mut raw)
+        .map_err(|_| "header is truncated".to_string())?;
+    // Versions 1 and 2 are latin-1, version 3 is UTF-8
+    let text: String = if version.0 == 3 {
+        String::from_utf8_lossy(&raw).into_owned()
+    } else {
+        raw.iter().map(|&b| b as char).collect()
+    };
+
+    let (dict, descr_text) = Parser::new(&text).header_dict()?;
+    let descr = dict
+        .iter()
+        .find(|(key, _)| key == "descr")
+        .map(|(_, value)| value)
+        .ok_or("header has no 'descr'")?;
+    let fortran_order = match dict.iter().find(|(key, _)| key == "fortran_order") {
+        Some((_, Literal::Bool(value))) => *value,
+        Some(_) => return Err("'fortran_order' is not a boolean".to_string()),
+        None => return Err("header has no 'fortran_order'".to_string()),
+    };
+    let shape = match dict.iter().find(|(key, _)| key == "shape") {
+        Some((_, Literal::Seq(items))) => items
+            .iter()
+            .map(|item| match item {
+                Literal::Int(n) if *n >= 0 => Ok(*n as u64),
+                _ => Err("'shape' has a value that is not a non-negative integer".to_string()),
+            })
+            .collect::<Result<Vec<u64>, String>>()?,
+        Some(_) => return Err("'shape' is not a tuple".to_string()),
+        None => return Err("header has no 'shape'".to_string()),
+    };
+
+    Ok(NpyHeader {
+        version,
+        descr: descr_text,
+        fortran_order,
+        shape,
+        item_size: item_size(descr),
+        header_size: (8 + length_size + length) as u64,
+    })
+}
+
+/// Size in bytes of one element of a dtype descriptor
+///
+/// Simple descriptors are strings such as `<f8` or `|S10`; structured ones
+/// are lists of `(name, descr[, shape])` fields. Object dtypes have no
+/// fixed size.
+fn item_size(descr: &Literal) -> Option<u64> {
+    match descr {
+        Literal::Str(text) => simple_item_size(text),
+        Literal::Seq(fields) => {
+            let mut total = 0u64;
+            for field in fields {
+                let parts = match field {
+                    Literal::Seq(parts) if parts.len() >= 2 => parts,
+                    _ => return None,
+                };
+                let count = match parts.get(2) {
+                    Some(Literal::Int(n)) if *n >= 0 => *n as u64,
+                    Some(Literal::Seq(dims)) => dims.iter().try_fold(1u64, |count, dim| match dim {
+                        Literal::Int(n) if *n >= 0 => Some(count.saturating_mul(*n as u64)),
+                        _ => None,
+                    })?,
+                    Some(_) => return None,
+                    None => 1,
+                };
+                total = total.saturating_add(item_size(&parts[1])?.saturating_mul(count));
+            }
+            Some(total)
+        }
+        _ => None,
+    }
+}
+
+/// Size of a simple dtype string such as `<i4`, `|b1`, `<U12` or `<M8[ns]`
+fn simple_item_size(descr: &str) -> Option<u64> {
+    let descr = descr.trim_start_matches(['<', '>', '|', '=']);
+    let mut chars = descr.chars();
+    let kind = chars.next()?;
+    let digits: String = chars.take_while(|c| c.is_ascii_digit()).collect();
+    let size = digits.parse::<u64>().ok();
+    match kind {
+        'b' | 'i' | 'u' | 'f' | 'c' | 'M' | 'm' | 'S' | 'a' | 'V' => size,
+        '?' => Some(size.unwrap_or(1)),
+        'U' => size.map(|n| n * 4),
+        _ => None,
+    }
+}
+
+/// A Python literal from a `.npy` header dict
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    None,
+    /// Tuples and lists
+    Seq(Vec<Literal>),
+    Dict(Vec<(String, Literal)>),
+}
+
+/// Parser for the subset of Python literal syntax `numpy.save` writes
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Parser {
+            chars: text.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    /// Parse the header dict, returning its entries and the source text of `descr`
+    fn header_dict(&mut self) -> Result<(Vec<(String, Literal)>, String), String> {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return Err("header is not a dict".to_string());
+        }
+        self.pos += 1;
+
+        let mut entries = Vec::new();
+        let mut descr_text = String::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                break;
+            }
+            let key = match self.value(0)? {
+                Literal::Str(key) => key,
+                _ => return Err("header dict key is not a string".to_string()),
+            };
+            self.expect(':')?;
+            self.skip_whitespace();
+            let start = self.pos;
+            let value = self.value(0)?;
+            if key == "descr" {
+                descr_text = self.chars[start..self.pos].iter().collect();
+            }
+            entries.push((key, value));
+            if !self.separator('}')? {
+                self.pos += 1;
+                break;
+            }
+        }
+        Ok((entries, descr_text))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Literal, String> {
+        if depth > 32 {
+            return Err("header is nested too deeply".to_string());
+        }
+        self.skip_whitespace();
+        match self.peek().ok_or("header is truncated")? {
+            quote @ ('\'' | '"') => {
+                self.pos += 1;
+                let mut text = String::new();
+                loop {
+                    match self.next().ok_or("unterminated string in header")? {
+                        '\\' => text.push(self.next().ok_or("unterminated string in header")?),
+                        c if c == quote => break,
+                        c => text.push(c),
+                    }
+                }
+                Ok(Literal::Str(text))
+            }
+            open @ ('(' | '[') => {
+                self.pos += 1;
+                let close = if open == '(' { ')' } else { ']' };
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(close) {
+                        self.pos += 1;
+                        break;
+                    }
+                    items.push(self.value(depth + 1)?);
+                    if !self.separator(close)? {
+                        self.pos += 1;
+                        break;
+                    }
+                }
+                Ok(Literal::Seq(items))
+            }
+            '{' => {
+                let (entries, _) = self.header_dict()?;
+                Ok(Literal::Dict(entries))
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                // Python 2 headers may write long integers as `3L`
+                if self.peek() == Some('L') {
+                    self.pos += 1;
+                }
+                text.parse::<i64>()
+                    .map(Literal::Int)
+                    .map_err(|_| format!("invalid integer '{}' in header", text))
+            }
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.pos += 1;
+                }
+                let word: String = self.chars[start..self.pos].iter().collect();
+                match word.as_str() {
+                    "True" => Ok(Literal::Bool(true)),
+                    "False" => Ok(Literal::Bool(false)),
+                    "None" => Ok(Literal::None),
+                    _ => Err(format!("unexpected '{}' in header", self.chars[start])),
+                }
+            }
+        }
+    }
+
+    /// After an item: consume a comma (true) or stop at `close` (false)
+    fn separator(&mut self, close: char) -> Result<bool, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(',') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(c) if c == close => Ok(false),
+            _ => Err(format!("expected ',' or '{}' in header", close)),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' in header", expected))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Builds small NumPy files for tests
+
+    /// A version 1.0 `.npy` file with the given header dict and `data_len` data bytes
+    pub fn npy(dict: &str, data_len: usize) -> Vec<u8> {
+        // numpy pads the header with spaces so the data is 64-byte aligned
+        let mut header = dict.to_string();
+        while !(10 + header.len() + 1).is_multiple_of(64) {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.resize(bytes.len() + data_len, 0);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::fixture::hex;
+    use crate::testing::write_temp_file;
+    use std::fs;
+
+    #[test]
+    fn test_analyze_npy() {
+        let bytes = fixture::npy("{'descr': '<f8', 'fortran_order': False, 'shape': (3, 4), }", 96);
+        let path = write_temp_file("genesis_preflight_numpy_npy", "weights.npy", &bytes);
+        let analysis = analyze_numpy(&path).unwrap();
+
+        assert!(!analysis.archive);
+        assert_eq!(analysis.arrays.len(), 1);
+        let array = &analysis.arrays[0];
+        assert_eq!(array.name, "weights");
+        assert_eq!(array.stored_size, 224);
+
+        let header = array.header.as_ref().unwrap();
+        assert_eq!(header.version, (1, 0));
+        assert_eq!(header.descr, "'<f8'");
+        assert!(!header.fortran_order);
+        assert_eq!(header.shape, vec![3, 4]);
+        assert_eq!(header.element_count(), 12);
+        assert_eq!(header.item_size, Some(8));
+        assert_eq!(header.header_size, 128);
+        assert_eq!(header.expected_size(), Some(224));
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_analyze_npz() {
+        let labels = fixture::npy("{'descr': '<i4', 'fortran_order': False, 'shape': (5,), }", 20);
+        let scalar = fixture::npy("{'descr': '|b1', 'fortran_order': True, 'shape': (), }", 1);
+        // An (2, 8) float32 array as np.savez_compressed writes it
+        let images = hex(
+            "9bec17ea1b10c9c850c650ad9e925a9c5ca46ea5a06e9366a2aea3a09e965f54529498179f5f94920a12774bcc294e058a17672416a402f91a463a0a169a3a0ab50a64032e060a0100",
+        );
+        let bytes = crate::archive::zip::fixture::zip_sized(&[
+            ("labels.npy", 0, &labels, labels.len() as u32),
+            ("flag.npy", 0, &scalar, scalar.len() as u32),
+            ("images.npy", 8, &images, 192),
+            ("notes.txt", 0, b"not an array", 12),
+        ]);
+        let path = write_temp_file("genesis_preflight_numpy_npz", "train.npz", &bytes);
+        let analysis = analyze_numpy(&path).unwrap();
+
+        assert!(analysis.archive);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+        let names: Vec<&str> = analysis.arrays.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["labels", "flag", "images"]);
+
+        let labels = analysis.arrays[0].header.as_ref().unwrap();
+        assert_eq!(labels.shape, vec![5]);
+        assert_eq!(labels.expected_size(), Some(analysis.arrays[0].stored_size));
+
+        let flag = analysis.arrays[1].header.as_ref().unwrap();
+        assert!(flag.fortran_order);
+        assert_eq!(flag.element_count(), 1);
+
+        assert_eq!(analysis.arrays[2].compression.as_deref(), Some("deflate"));
+        let images = analysis.arrays[2].header.as_ref().unwrap();
+        assert_eq!(images.shape, vec![2, 8]);
+        assert_eq!(images.expected_size(), Some(analysis.arrays[2].stored_size));
+
+        // Damaged compressed data is reported, not skipped
+        let bytes = crate::archive::zip::fixture::zip_sized(&[("images.npy", 8, b"\xff\xff compressed", 192)]);
+        let path = write_temp_file("genesis_preflight_numpy_npz", "damaged.npz", &bytes);
+        let analysis = analyze_numpy(&path).unwrap();
+        assert!(analysis.arrays[0].header.is_none());
+        assert_eq!(analysis.warnings.len(), 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_structured_and_special_dtypes() {
+        let header = |dict: &str| {
+            let bytes = fixture::npy(dict, 0);
+            read_header(&mut &bytes[..]).unwrap()
+        };
+
+        let record = header(
+            "{'descr': [('x', '<f8'), ('y', '<i4', (2, 3)), ('name', '<U4')], 'fortran_order': False, 'shape': (2,), }",
+        );
+        assert_eq!(record.item_size, Some(8 + 4 * 6 + 16));
+        assert!(record.descr.starts_with("[('x', '<f8')"));
+
+        assert_eq!(header("{'descr': '<M8[ns]', 'fortran_order': False, 'shape': (4,), }").item_size, Some(8));
+        assert_eq!(header("{'descr': '|O', 'fortran_order': False, 'shape': (4,), }").item_size, None);
+    }
+
+    #[test]
+    fn test_damaged_headers() {
+        let damaged = |dict: &str| {
+            let bytes = fixture::npy(dict, 0);
+            read_header(&mut &bytes[..]).unwrap_err()
+        };
+        assert!(damaged("{'descr': '<f8', 'shape': (3,), }").contains("fortran_order"));
+        assert!(damaged("{'descr': '<f8', 'fortran_order': False, 'shape': (-1,), }").contains("shape"));
+        assert!(damaged("{'descr': '<f8'").contains("header"));
+
+        let truncated = &fixture::npy("{'descr': '<f8', 'fortran_order': False, 'shape': (3,), }", 0)[..20];
+        assert_eq!(read_header(&mut &truncated[..]).unwrap_err(), "header is truncated");
+
+        let path = write_temp_file("genesis_preflight_numpy_damaged", "bad.npy", b"\x93NUMPY\x09\x00");
+        let analysis = analyze_numpy(&path).unwrap();
+        assert!(analysis.arrays[0].header.is_none());
+        assert_eq!(analysis.warnings.len(), 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
@@ -0,0 +1,722 @@
+//! Apache Parquet footer analysis
+//!
+//! A Parquet file ends with its metadata: a Thrift compact-protocol
+//! `FileMetaData` struct, the struct's length as a little-endian `u32`, and
+//! the magic `PAR1`. Decoding the footer yields the schema, row groups,
+//! codecs and per-chunk column statistics without reading any data pages.
+
+mod thrift;
+
+use super::AnalysisError;
+use crate::types::{
+    ColumnInfo, ColumnType, CsvAnalysis, ParquetAnalysis, ParquetColumn, ParquetRowGroup,
+};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use thrift::Value;
+
+/// Magic at the start and end of a Parquet file
+const MAGIC: &[u8; 4] = b"PAR1";
+
+/// Trailing magic of a file with an encrypted footer
+const ENCRYPTED_MAGIC: &[u8; 4] = b"PARE";
+
+/// Largest footer we are willing to read
+const MAX_FOOTER_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Deepest schema nesting we follow
+const MAX_SCHEMA_DEPTH: usize = 64;
+
+/// Characters of string statistics kept
+const MAX_STAT_LENGTH: usize = 64;
+
+/// Physical type names by Thrift enum value
+const PHYSICAL_TYPES: [&str; 8] = [
+    "BOOLEAN",
+    "INT32",
+    "INT64",
+    "INT96",
+    "FLOAT",
+    "DOUBLE",
+    "BYTE_ARRAY",
+    "FIXED_LEN_BYTE_ARRAY",
+];
+
+/// Compression codec names by Thrift enum value
+const CODECS: [&str; 8] = ["UNCOMPRESSED", "SNAPPY", "GZIP", "LZO", "BROTLI", "LZ4", "ZSTD", "LZ4_RAW"];
+
+/// Build an `InvalidFormat` error with a `Parquet:` prefix
+pub(super) fn invalid(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidFormat(format!("Parquet: {}", message.into()))
+}
+
+/// Analyze the footer of a Parquet file
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting and ending with `PAR1`
+///
+/// # Returns
+///
+/// The schema's leaf columns with types, nullability, codecs and
+/// statistics, the row groups, and the same columns as a tabular analysis.
+pub fn analyze_parquet(path: &Path) -> Result<ParquetAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    if file_len < 12 {
+        return Err(invalid("file is too small"));
+    }
+
+    let mut head = [0u8; 4];
+    file.read_exact(&mut head)?;
+    if &head != MAGIC {
+        return Err(invalid("PAR1 magic not found"));
+    }
+
+    let mut tail = [0u8; 8];
+    file.seek(SeekFrom::End(-8))?;
+    file.read_exact(&mut tail)?;
+    if &tail[4..] == ENCRYPTED_MAGIC {
+        return Err(invalid("encrypted footers are not supported"));
+    }
+    if &tail[4..] != MAGIC {
+        return Err(invalid("footer magic not found (the file may be truncated)"));
+    }
+
+    let footer_len = u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]) as u64;
+    if footer_len + 12 > file_len || footer_len > MAX_FOOTER_SIZE {
+        return Err(invalid(format!("footer length {} is invalid", footer_len)));
+    }
+    let mut footer = vec![0u8; footer_len as usize];
+    file.seek(SeekFrom::Start(file_len - 8 - footer_len))?;
+    file.read_exact(&mut footer)?;
+
+    interpret(&thrift::read_struct(&footer)?)
+}
+
+/// A leaf column being assembled, with what is needed to decode its statistics
+struct Leaf {
+    column: ParquetColumn,
+    physical: i64,
+    logical: Logical,
+    min: Option<Stat>,
+    max: Option<Stat>,
+    stats_complete: bool,
+}
+
+/// Logical interpretation of a column's values
+#[derive(Clone, Copy, PartialEq)]
+enum Logical {
+    Plain,
+    String,
+    Date,
+    Time,
+    Timestamp { micros_per_unit: f64 },
+    Decimal { scale: i64 },
+    Unsigned,
+    Identifier,
+    Other,
+}
+
+/// A decoded statistic: a sortable number with its rendering, or text
+#[derive(Clone)]
+enum Stat {
+    Number(f64, String),
+    Text(String),
+}
+
+/// Turn the decoded `FileMetaData` into an analysis
+fn interpret(metadata: &Value) -> Result<ParquetAnalysis, AnalysisError> {
+    let schema = metadata.list(2);
+    let root = schema.first().ok_or_else(|| invalid("schema is empty"))?;
+
+    let mut leaves = Vec::new();
+    let mut index = 1;
+    for _ in 0..root.int(5).unwrap_or(0) {
+        flatten(schema, &mut index, "", false, false, 0, &mut leaves)?;
+    }
+
+    let mut row_groups = Vec::new();
+    for group in metadata.list(4) {
+        row_groups.push(ParquetRowGroup {
+            row_count: group.int(3).unwrap_or(0).max(0) as u64,
+            total_byte_size: group.int(2).unwrap_or(0).max(0) as u64,
+        });
+        for (leaf, chunk) in leaves.iter_mut().zip(group.list(1)) {
+            match chunk.field(3) {
+                Some(meta) => add_chunk(leaf, meta),
+                None => {
+                    leaf.column.null_count = None;
+                    leaf.stats_complete = false;
+                }
+            }
+        }
+    }
+
+    let row_count = metadata.int(3).unwrap_or(0).max(0) as u64;
+    let columns: Vec<ParquetColumn> = leaves.into_iter().map(finish_leaf).collect();
+
+    let mut table = CsvAnalysis::new(',', true);
+    table.row_count = row_count as usize;
+    table.column_count = columns.len();
+    table.columns = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| column_info(index, column))
+        .collect();
+
+    Ok(ParquetAnalysis {
+        version: metadata.int(1).unwrap_or(0),
+        created_by: metadata.string(6),
+        row_count,
+        row_groups,
+        columns,
+        table,
+    })
+}
+
+/// Walk one schema element and its children depth-first, collecting leaves
+fn flatten(
+    schema: &[Value],
+    index: &mut usize,
+    prefix: &str,
+    nullable: bool,
+    repeated: bool,
+    depth: usize,
+    leaves: &mut Vec<Leaf>,
+) -> Result<(), AnalysisError> {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Err(invalid("schema is nested too deeply"));
+    }
+    let element = schema.get(*index).ok_or_else(|| invalid("schema is truncated"))?;
+    *index += 1;
+
+    let name = element.string(4).unwrap_or_default();
+    let path = if prefix.is_empty() {
+        name
+    } else {
+        format!("{}.{}", prefix, name)
+    };
+    let nullable = nullable || element.int(3) == Some(1);
+    let repeated = repeated || element.int(3) == Some(2);
+
+    let children = element.int(5).unwrap_or(0);
+    if children > 0 {
+        for _ in 0..children {
+            flatten(schema, index, &path, nullable, repeated, depth + 1, leaves)?;
+        }
+        return Ok(());
+    }
+
+    let physical = element.int(1).unwrap_or(-1);
+    let (logical, logical_type) = logical_type(element);
+    leaves.push(Leaf {
+        column: ParquetColumn {
+            name: path,
+            physical_type: PHYSICAL_TYPES
+                .get(physical as usize)
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| format!("type {}", physical)),
+            logical_type,
+            nullable,
+            repeated,
+            codecs: Vec::new(),
+            null_count: Some(0),
+            min_value: None,
+            max_value: None,
+            compressed_size: 0,
+            uncompressed_size: 0,
+        },
+        physical,
+        logical,
+        min: None,
+        max: None,
+        stats_complete: true,
+    });
+    Ok(())
+}
+
+/// Read the logical type (or legacy converted type) of a schema element
+fn logical_type(element: &Value) -> (Logical, Option<String>) {
+    if let Some((member, detail)) = element.field(10).and_then(Value::union_member) {
+        let time_unit = |detail: &Value| {
+            let utc = if detail.bool(1) == Some(true) { "UTC" } else { "local" };
+            let (unit, micros) = match detail.field(2).and_then(Value::union_member) {
+                Some((1, _)) => ("MILLIS", 1_000.0),
+                Some((3, _)) => ("NANOS", 0.001),
+                _ => ("MICROS", 1.0),
+            };
+            (format!("{},{}", unit, utc), micros)
+        };
+        return match member {
+            1 => (Logical::String, Some("STRING".to_string())),
+            4 => (Logical::String, Some("ENUM".to_string())),
+            5 => {
+                let scale = detail.int(1).unwrap_or(0);
+                let precision = detail.int(2).unwrap_or(0);
+                (Logical::Decimal { scale }, Some(format!("DECIMAL({},{})", precision, scale)))
+            }
+            6 => (Logical::Date, Some("DATE".to_string())),
+            7 => (Logical::Time, Some(format!("TIME({})", time_unit(detail).0))),
+            8 => {
+                let (unit, micros_per_unit) = time_unit(detail);
+                (Logical::Timestamp { micros_per_unit }, Some(format!("TIMESTAMP({})", unit)))
+            }
+            10 => {
+                let bits = detail.int(1).unwrap_or(0);
+                if detail.bool(2) == Some(false) {
+                    (Logical::Unsigned, Some(format!("UINT({})", bits)))
+                } else {
+                    (Logical::Plain, Some(format!("INT({})", bits)))
+                }
+            }
+            12 => (Logical::String, Some("JSON".to_string())),
+            13 => (Logical::Other, Some("BSON".to_string())),
+            14 => (Logical::Identifier, Some("UUID".to_string())),
+            15 => (Logical::Plain, Some("FLOAT16".to_string())),
+            other => (Logical::Other, Some(format!("logical type {}", other))),
+        };
+    }
+
+    match element.int(6) {
+        Some(0) => (Logical::String, Some("STRING".to_string())),
+        Some(4) => (Logical::String, Some("ENUM".to_string())),
+        Some(5) => {
+            let scale = element.int(7).unwrap_or(0);
+            let precision = element.int(8).unwrap_or(0);
+            (Logical::Decimal { scale }, Some(format!("DECIMAL({},{})", precision, scale)))
+        }
+        Some(6) => (Logical::Date, Some("DATE".to_string())),
+        Some(7) => (Logical::Time, Some("TIME(MILLIS,UTC)".to_string())),
+        Some(8) => (Logical::Time, Some("TIME(MICROS,UTC)".to_string())),
+        Some(9) => (
+            Logical::Timestamp { micros_per_unit: 1_000.0 },
+            Some("TIMESTAMP(MILLIS,UTC)".to_string()),
+        ),
+        Some(10) => (
+            Logical::Timestamp { micros_per_unit: 1.0 },
+            Some("TIMESTAMP(MICROS,UTC)".to_string()),
+        ),
+        Some(code @ 11..=14) => (Logical::Unsigned, Some(format!("UINT({})", 8 << (code - 11)))),
+        Some(code @ 15..=18) => (Logical::Plain, Some(format!("INT({})", 8 << (code - 15)))),
+        Some(19) => (Logical::String, Some("JSON".to_string())),
+        Some(20) => (Logical::Other, Some("BSON".to_string())),
+        Some(21) => (Logical::Other, Some("INTERVAL".to_string())),
+        _ => (Logical::Plain, None),
+    }
+}
+
+/// Fold one column chunk's metadata into its leaf
+fn add_chunk(leaf: &mut Leaf, meta: &Value) {
+    if let Some(codec) = meta.int(4) {
+        let name = CODECS
+            .get(codec as usize)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| format!("codec {}", codec));
+        if !leaf.column.codecs.contains(&name) {
+            leaf.column.codecs.push(name);
+        }
+    }
+    leaf.column.uncompressed_size += meta.int(6).unwrap_or(0).max(0) as u64;
+    leaf.column.compressed_size += meta.int(7).unwrap_or(0).max(0) as u64;
+
+    let stats = match meta.field(12) {
+        Some(stats) => stats,
+        None => {
+            leaf.column.null_count = None;
+            leaf.stats_complete = false;
+            return;
+        }
+    };
+
+    match (stats.int(3), leaf.column.null_count) {
+        (Some(nulls), Some(total)) => leaf.column.null_count = Some(total + nulls.max(0) as u64),
+        _ => leaf.column.null_count = None,
+    }
+
+    // The deprecated min/max fields used signed comparison, which is only
+    // right for signed numeric columns
+    let legacy_ok = matches!(leaf.physical, 0..=2 | 4 | 5) && leaf.logical != Logical::Unsigned;
+    let min = stats.bytes(6).or_else(|| stats.bytes(2).filter(|_| legacy_ok));
+    let max = stats.bytes(5).or_else(|| stats.bytes(1).filter(|_| legacy_ok));
+
+    match min.and_then(|b| decode_stat(leaf, b)) {
+        Some(value) => {
+            if leaf.min.as_ref().map(|m| less(&value, m)).unwrap_or(true) {
+                leaf.min = Some(value);
+            }
+        }
+        None => leaf.stats_complete = false,
+    }
+    match max.and_then(|b| decode_stat(leaf, b)) {
+        Some(value) => {
+            if leaf.max.as_ref().map(|m| less(m, &value)).unwrap_or(true) {
+                leaf.max = Some(value);
+            }
+        }
+        None => leaf.stats_complete = false,
+    }
+}
+
+/// Whether statistic `a` sorts before `b`
+fn less(a: &Stat, b: &Stat) -> bool {
+    match (a, b) {
+        (Stat::Number(x, _), Stat::Number(y, _)) => x < y,
+        (Stat::Text(x), Stat::Text(y)) => x < y,
+        _ => false,
+    }
+}
+
+/// Decode a plain-encoded statistic value
+fn decode_stat(leaf: &Leaf, bytes: &[u8]) -> Option<Stat> {
+    let int = |len: usize| -> Option<i64> {
+        let raw = bytes.get(..len)?;
+        let mut buffer = [0u8; 8];
+        buffer[..len].copy_from_slice(raw);
+        let value = u64::from_le_bytes(buffer);
+        Some(match (len, leaf.logical) {
+            (4, Logical::Unsigned) => value as u32 as i64,
+            (4, _) => value as u32 as i32 as i64,
+            _ => value as i64,
+        })
+    };
+
+    let number = |value: f64| Stat::Number(value, format_number(value));
+    match leaf.physical {
+        0 => Some(Stat::Text((bytes.first()? & 1 == 1).to_string())),
+        1 | 2 => {
+            let value = int(if leaf.physical == 1 { 4 } else { 8 })?;
+            Some(match leaf.logical {
+                Logical::Date => Stat::Number(value as f64, civil_date(value)),
+                Logical::Timestamp { micros_per_unit } => {
+                    let micros = value as f64 * micros_per_unit;
+                    let seconds = (micros / 1_000_000.0).floor() as i64;
+                    let clock = seconds.rem_euclid(86_400);
+                    let text = format!(
+                        "{}T{:02}:{:02}:{:02}Z",
+                        civil_date(seconds.div_euclid(86_400)),
+                        clock / 3600,
+                        clock % 3600 / 60,
+                        clock % 60
+                    );
+                    Stat::Number(micros, text)
+                }
+                Logical::Decimal { scale } => number(value as f64 / 10f64.powi(scale as i32)),
+                Logical::Unsigned if leaf.physical == 2 => number(value as u64 as f64),
+                _ => number(value as f64),
+            })
+        }
+        4 => {
+            let raw: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+            Some(number(f32::from_le_bytes(raw) as f64))
+        }
+        5 => {
+            let raw: [u8; 8] = bytes.get(..8)?.try_into().ok()?;
+            Some(number(f64::from_le_bytes(raw)))
+        }
+        6 if leaf.logical == Logical::String => {
+            let text: String = String::from_utf8_lossy(bytes).chars().take(MAX_STAT_LENGTH).collect();
+            Some(Stat::Text(text))
+        }
+        _ => None,
+    }
+}
+
+/// Format a number without a trailing `.0` for whole values
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render days since 1970-01-01 as `YYYY-MM-DD`
+///
+/// Uses Howard Hinnant's civil-from-days algorithm.
+fn civil_date(days: i64) -> String {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Finish a leaf: min/max are kept only if every row group had them
+fn finish_leaf(leaf: Leaf) -> ParquetColumn {
+    let mut column = leaf.column;
+    if leaf.stats_complete {
+        let text = |stat: Stat| match stat {
+            Stat::Number(_, text) | Stat::Text(text) => text,
+        };
+        column.min_value = leaf.min.map(text);
+        column.max_value = leaf.max.map(text);
+    }
+    column
+}
+
+/// Describe a Parquet column the way the CSV analyzer describes a CSV column
+fn column_info(index: usize, column: &ParquetColumn) -> ColumnInfo {
+    let logical = column.logical_type.as_deref().unwrap_or("");
+    let inferred_type = match column.physical_type.as_str() {
+        _ if logical == "UUID" => ColumnType::Identifier,
+        _ if logical == "DATE" => ColumnType::Date,
+        _ if logical.starts_with("TIMESTAMP") => ColumnType::Timestamp,
+        _ if logical.starts_with("TIME") => ColumnType::Time,
+        _ if logical.starts_with("DECIMAL") || logical == "FLOAT16" => ColumnType::Float,
+        "BOOLEAN" => ColumnType::Boolean,
+        "INT32" | "INT64" => ColumnType::Integer,
+        "INT96" => ColumnType::Timestamp,
+        "FLOAT" | "DOUBLE" => ColumnType::Float,
+        "BYTE_ARRAY" if !logical.is_empty() && logical != "BSON" => ColumnType::String,
+        _ => ColumnType::Unknown,
+    };
+
+    let numeric = matches!(inferred_type, ColumnType::Integer | ColumnType::Float);
+    let parse = |value: &Option<String>| value.as_deref().and_then(|v| v.parse::<f64>().ok());
+    let (min, max) = if numeric {
+        (parse(&column.min_value), parse(&column.max_value))
+    } else {
+        (None, None)
+    };
+
+    ColumnInfo::new(index)
+        .with_name(column.name.clone())
+        .with_type(inferred_type)
+        .with_null_count(column.null_count.unwrap_or(0) as usize)
+        .with_range(min, max)
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Builds small Parquet files for tests
+
+    /// Thrift compact protocol writer
+    #[derive(Default)]
+    pub struct Writer {
+        pub bytes: Vec<u8>,
+        last: Vec<i16>,
+    }
+
+    impl Writer {
+        fn varint(&mut self, mut value: u64) {
+            while value >= 0x80 {
+                self.bytes.push((value as u8 & 0x7F) | 0x80);
+                value >>= 7;
+            }
+            self.bytes.push(value as u8);
+        }
+
+        fn zigzag(&mut self, value: i64) {
+            self.varint(((value << 1) ^ (value >> 63)) as u64);
+        }
+
+        fn header(&mut self, id: i16, kind: u8) {
+            let last = self.last.last().copied().unwrap_or(0);
+            let delta = id - last;
+            if (1..=15).contains(&delta) {
+                self.bytes.push(((delta as u8) << 4) | kind);
+            } else {
+                self.bytes.push(kind);
+                self.zigzag(id as i64);
+            }
+            if let Some(last) = self.last.last_mut() {
+                *last = id;
+            }
+        }
+
+        pub fn int(&mut self, id: i16, value: i64) -> &mut Self {
+            self.header(id, 6);
+            self.zigzag(value);
+            self
+        }
+
+        pub fn binary(&mut self, id: i16, value: &[u8]) -> &mut Self {
+            self.header(id, 8);
+            self.varint(value.len() as u64);
+            self.bytes.extend_from_slice(value);
+            self
+        }
+
+        /// Open a struct field (or, with `id` 0, a struct list element)
+        pub fn begin(&mut self, id: i16) -> &mut Self {
+            if id != 0 {
+                self.header(id, 12);
+            }
+            self.last.push(0);
+            self
+        }
+
+        pub fn end(&mut self) -> &mut Self {
+            self.bytes.push(0);
+            self.last.pop();
+            self
+        }
+
+        /// Open a list field of `size` elements of compact type `kind`
+        pub fn list(&mut self, id: i16, kind: u8, size: usize) -> &mut Self {
+            self.header(id, 9);
+            self.bytes.push(((size.min(15) as u8) << 4) | kind);
+            if size >= 15 {
+                self.varint(size as u64);
+            }
+            self
+        }
+    }
+
+    /// Plain-encoded (min, max) statistics of one column chunk
+    type Bounds<'a> = (&'a [u8], &'a [u8]);
+
+    /// A Parquet file with columns `id` (INT64, required), `temperature`
+    /// (DOUBLE, optional) and `station` (STRING, optional) in two row
+    /// groups of 3 and 2 rows
+    pub fn sample_file() -> Vec<u8> {
+        let mut w = Writer::default();
+        w.begin(0);
+        w.int(1, 1);
+
+        w.list(2, 12, 4);
+        w.begin(0).binary(4, b"schema").int(5, 3).end();
+        w.begin(0).int(1, 2).int(3, 0).binary(4, b"id").end();
+        w.begin(0).int(1, 5).int(3, 1).binary(4, b"temperature").end();
+        w.begin(0).int(1, 6).int(3, 1).binary(4, b"station").int(6, 0);
+        w.begin(10).begin(1).end().end();
+        w.end();
+
+        w.int(3, 5);
+
+        let groups: [(i64, [Bounds; 3], [i64; 3]); 2] = [
+            (
+                3,
+                [
+                    (&1i64.to_le_bytes(), &3i64.to_le_bytes()),
+                    (&(-4.5f64).to_le_bytes(), &12.25f64.to_le_bytes()),
+                    (b"north", b"south"),
+                ],
+                [0, 1, 0],
+            ),
+            (
+                2,
+                [
+                    (&4i64.to_le_bytes(), &5i64.to_le_bytes()),
+                    (&3.0f64.to_le_bytes(), &20.5f64.to_le_bytes()),
+                    (b"east", b"west"),
+                ],
+                [0, 0, 2],
+            ),
+        ];
+        w.list(4, 12, groups.len());
+        for (rows, stats, nulls) in groups {
+            w.begin(0);
+            w.list(1, 12, 3);
+            for (column, ((min, max), null_count)) in stats.iter().zip(nulls).enumerate() {
+                let physical = [2, 5, 6][column];
+                let codec = if column == 2 { 6 } else { 1 };
+                w.begin(0).int(2, 4);
+                w.begin(3)
+                    .int(1, physical)
+                    .int(4, codec)
+                    .int(5, rows)
+                    .int(6, 100)
+                    .int(7, 60)
+                    .int(9, 4);
+                w.begin(12).int(3, null_count).binary(5, max).binary(6, min).end();
+                w.end().end();
+            }
+            w.int(2, 300).int(3, rows);
+            w.end();
+        }
+        w.binary(6, b"genesis test writer");
+        w.end();
+
+        let mut file = b"PAR1".to_vec();
+        file.extend_from_slice(&[0u8; 32]); // stands in for the data pages
+        file.extend_from_slice(&w.bytes);
+        file.extend_from_slice(&(w.bytes.len() as u32).to_le_bytes());
+        file.extend_from_slice(b"PAR1");
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::write_temp_file;
+    use std::fs;
+
+    #[test]
+    fn test_analyze_footer() {
+        let path = write_temp_file("genesis_preflight_parquet_footer", "readings.parquet", &fixture::sample_file());
+        let analysis = analyze_parquet(&path).unwrap();
+
+        assert_eq!(analysis.version, 1);
+        assert_eq!(analysis.row_count, 5);
+        assert_eq!(analysis.created_by.as_deref(), Some("genesis test writer"));
+        assert_eq!(analysis.row_groups.len(), 2);
+        assert_eq!(analysis.row_groups[0].row_count, 3);
+
+        let names: Vec<&str> = analysis.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "temperature", "station"]);
+
+        let id = &analysis.columns[0];
+        assert_eq!(id.physical_type, "INT64");
+        assert!(!id.nullable);
+        assert_eq!(id.codecs, vec!["SNAPPY"]);
+        assert_eq!(id.min_value.as_deref(), Some("1"));
+        assert_eq!(id.max_value.as_deref(), Some("5"));
+        assert_eq!(id.compressed_size, 120);
+
+        let temperature = &analysis.columns[1];
+        assert!(temperature.nullable);
+        assert_eq!(temperature.null_count, Some(1));
+        assert_eq!(temperature.min_value.as_deref(), Some("-4.5"));
+        assert_eq!(temperature.max_value.as_deref(), Some("20.5"));
+
+        let station = &analysis.columns[2];
+        assert_eq!(station.logical_type.as_deref(), Some("STRING"));
+        assert_eq!(station.codecs, vec!["ZSTD"]);
+        assert_eq!(station.min_value.as_deref(), Some("east"));
+        assert_eq!(station.max_value.as_deref(), Some("west"));
+
+        let table = &analysis.table;
+        assert_eq!(table.row_count, 5);
+        assert_eq!(table.columns[0].inferred_type, ColumnType::Integer);
+        assert_eq!(table.columns[1].inferred_type, ColumnType::Float);
+        assert_eq!(table.columns[1].min_value, Some(-4.5));
+        assert_eq!(table.columns[2].inferred_type, ColumnType::String);
+        assert_eq!(table.columns[2].null_count, 2);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let mut truncated = fixture::sample_file();
+        truncated.truncate(truncated.len() - 3);
+        let path = write_temp_file("genesis_preflight_parquet_invalid", "readings.parquet", &truncated);
+        assert!(analyze_parquet(&path).is_err());
+
+        let mut bad_length = fixture::sample_file();
+        let len = bad_length.len();
+        bad_length[len - 8..len - 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let path = write_temp_file("genesis_preflight_parquet_invalid", "readings.parquet", &bad_length);
+        assert!(analyze_parquet(&path).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_logical_values() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(19_723), "2024-01-01");
+        assert_eq!(civil_date(-1), "1969-12-31");
+        assert_eq!(format_number(3.0), "3");
+        assert_eq!(format_number(0.25), "0.25");
+    }
+}
@@ -0,0 +1,267 @@
+//! Thrift compact protocol decoding
+//!
+//! Parquet serializes its footer with Apache Thrift's compact protocol.
+//! Rather than generating code from `parquet.thrift`, the footer is decoded
+//! into a generic value tree and the fields we need are looked up by id;
+//! unknown and future fields are skipped for free.
+
+use super::invalid;
+use crate::analyzer::AnalysisError;
+
+/// Deepest struct/list nesting accepted
+const MAX_NESTING: usize = 64;
+
+/// Compact protocol type codes
+const TYPE_STOP: u8 = 0;
+const TYPE_TRUE: u8 = 1;
+const TYPE_FALSE: u8 = 2;
+const TYPE_BYTE: u8 = 3;
+const TYPE_I16: u8 = 4;
+const TYPE_I32: u8 = 5;
+const TYPE_I64: u8 = 6;
+const TYPE_DOUBLE: u8 = 7;
+const TYPE_BINARY: u8 = 8;
+const TYPE_LIST: u8 = 9;
+const TYPE_SET: u8 = 10;
+const TYPE_MAP: u8 = 11;
+const TYPE_STRUCT: u8 = 12;
+
+/// A decoded Thrift value
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    Bool(bool),
+    /// Any integer width (byte, i16, i32, i64)
+    Int(i64),
+    Double(f64),
+    /// Strings and binary fields
+    Binary(Vec<u8>),
+    /// Lists and sets
+    List(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    /// Fields by id, in wire order
+    Struct(Vec<(i16, Value)>),
+}
+
+impl Value {
+    /// Look up a struct field by id
+    pub fn field(&self, id: i16) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields.iter().find(|(f, _)| *f == id).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Integer value of a struct field
+    pub fn int(&self, id: i16) -> Option<i64> {
+        match self.field(id)? {
+            Value::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Boolean value of a struct field
+    pub fn bool(&self, id: i16) -> Option<bool> {
+        match self.field(id)? {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Binary value of a struct field
+    pub fn bytes(&self, id: i16) -> Option<&[u8]> {
+        match self.field(id)? {
+            Value::Binary(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// String value of a struct field (lossy UTF-8)
+    pub fn string(&self, id: i16) -> Option<String> {
+        self.bytes(id).map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    /// List elements of a struct field (empty if absent)
+    pub fn list(&self, id: i16) -> &[Value] {
+        match self.field(id) {
+            Some(Value::List(items)) => items,
+            _ => &[],
+        }
+    }
+
+    /// For a union, the id of the member that is set
+    pub fn union_member(&self) -> Option<(i16, &Value)> {
+        match self {
+            Value::Struct(fields) => fields.first().map(|(id, v)| (*id, v)),
+            _ => None,
+        }
+    }
+}
+
+/// Decode one struct from the start of `data`
+pub(super) fn read_struct(data: &[u8]) -> Result<Value, AnalysisError> {
+    let mut decoder = Decoder { data, pos: 0 };
+    decoder.value(TYPE_STRUCT, 0)
+}
+
+/// Compact protocol decoder over a byte slice
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    /// Decode a value of the given compact type
+    fn value(&mut self, kind: u8, depth: usize) -> Result<Value, AnalysisError> {
+        if depth > MAX_NESTING {
+            return Err(invalid("metadata is nested too deeply"));
+        }
+        Ok(match kind {
+            TYPE_TRUE => Value::Bool(true),
+            TYPE_FALSE => Value::Bool(false),
+            TYPE_BYTE => Value::Int(self.byte()? as i8 as i64),
+            TYPE_I16 | TYPE_I32 | TYPE_I64 => Value::Int(self.zigzag()?),
+            TYPE_DOUBLE => {
+                let bytes = self.take(8)?;
+                let mut raw = [0u8; 8];
+                raw.copy_from_slice(bytes);
+                Value::Double(f64::from_le_bytes(raw))
+            }
+            TYPE_BINARY => {
+                let length = self.varint()? as usize;
+                Value::Binary(self.take(length)?.to_vec())
+            }
+            TYPE_LIST | TYPE_SET => {
+                let header = self.byte()?;
+                let element = header & 0x0F;
+                let size = match header >> 4 {
+                    15 => self.varint()? as usize,
+                    size => size as usize,
+                };
+                self.check_count(size)?;
+                let mut items = Vec::with_capacity(size);
+                for _ in 0..size {
+                    items.push(self.element(element, depth + 1)?);
+                }
+                Value::List(items)
+            }
+            TYPE_MAP => {
+                let size = self.varint()? as usize;
+                let mut entries = Vec::new();
+                if size > 0 {
+                    self.check_count(size)?;
+                    let types = self.byte()?;
+                    for _ in 0..size {
+                        let key = self.element(types >> 4, depth + 1)?;
+                        let value = self.element(types & 0x0F, depth + 1)?;
+                        entries.push((key, value));
+                    }
+                }
+                Value::Map(entries)
+            }
+            TYPE_STRUCT => {
+                let mut fields = Vec::new();
+                let mut last_id = 0i16;
+                loop {
+                    let header = self.byte()?;
+                    let field_type = header & 0x0F;
+                    if field_type == TYPE_STOP {
+                        break;
+                    }
+                    let delta = (header >> 4) as i16;
+                    let id = if delta == 0 {
+                        self.zigzag()? as i16
+                    } else {
+                        last_id.wrapping_add(delta)
+                    };
+                    last_id = id;
+                    fields.push((id, self.value(field_type, depth + 1)?));
+                }
+                Value::Struct(fields)
+            }
+            other => return Err(invalid(format!("unknown Thrift type {}", other))),
+        })
+    }
+
+    /// Decode a list, set or map element
+    ///
+    /// Booleans inside containers take a whole byte instead of living in
+    /// the type nibble.
+    fn element(&mut self, kind: u8, depth: usize) -> Result<Value, AnalysisError> {
+        if kind == TYPE_TRUE || kind == TYPE_FALSE {
+            return Ok(Value::Bool(self.byte()? == TYPE_TRUE));
+        }
+        self.value(kind, depth)
+    }
+
+    /// Reject element counts the remaining bytes cannot hold
+    fn check_count(&self, count: usize) -> Result<(), AnalysisError> {
+        if count > self.data.len() - self.pos {
+            return Err(invalid("container size is larger than the metadata"));
+        }
+        Ok(())
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], AnalysisError> {
+        if n > self.data.len() - self.pos {
+            return Err(invalid("metadata is truncated"));
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, AnalysisError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Unsigned LEB128 varint
+    fn varint(&mut self) -> Result<u64, AnalysisError> {
+        let mut result = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(invalid("varint is too long"))
+    }
+
+    /// Zigzag-encoded signed varint
+    fn zigzag(&mut self) -> Result<i64, AnalysisError> {
+        let raw = self.varint()?;
+        Ok((raw >> 1) as i64 ^ -((raw & 1) as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_struct() {
+        let data = [
+            0x15, 0x04, // field 1, i32 = 2
+            0x16, 0x83, 0x01, // field 2, i64 = -66
+            0x18, 0x02, b'h', b'i', // field 3, binary "hi"
+            0x19, 0x25, 0x02, 0x04, // field 4, list<i32> [1, 2]
+            0x11, // field 5, bool true
+            0x0C, 0x28, 0x00, // field 20 (long form), empty struct
+            0x00, // stop
+        ];
+        let value = read_struct(&data).unwrap();
+
+        assert_eq!(value.int(1), Some(2));
+        assert_eq!(value.int(2), Some(-66));
+        assert_eq!(value.string(3).as_deref(), Some("hi"));
+        assert_eq!(value.list(4), &[Value::Int(1), Value::Int(2)]);
+        assert_eq!(value.bool(5), Some(true));
+        assert_eq!(value.field(20), Some(&Value::Struct(vec![])));
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        assert!(read_struct(&[0x15]).is_err());
+        assert!(read_struct(&[0x19, 0xF5, 0xFF, 0xFF, 0x0F]).is_err());
+    }
+}
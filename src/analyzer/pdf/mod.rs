@@ -0,0 +1,630 @@
+//! PDF document structure
+//!
+//! Locates objects through the cross-reference table named by the
+//! trailing `startxref` (following `/Prev` through incremental updates),
+//! then reads the trailer's `/Info` dictionary and walks the page tree
+//! from `/Root` for the page count and font resources. When the table is
+//! missing, damaged or a compressed cross-reference stream, objects are
+//! located by scanning the file for `N G obj` headers instead.
+
+mod object;
+
+use super::AnalysisError;
+use crate::types::PdfAnalysis;
+use object::{decode_text, Object, ParseError, Parser};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// First read when parsing at an offset
+const INITIAL_WINDOW: u64 = 64 * 1024;
+
+/// Largest read when an object or xref table does not fit the first one
+const MAX_WINDOW: u64 = 64 << 20;
+
+/// Bytes at the end of the file searched for `startxref`
+const TAIL_SIZE: u64 = 4096;
+
+/// Chunk size of the object-header scan used for recovery
+const SCAN_CHUNK: u64 = 1 << 20;
+
+/// Most pages examined for font resources
+const MAX_PAGES_CHECKED: usize = 10_000;
+
+/// Most `/Prev` links followed through incremental updates
+const MAX_XREF_SECTIONS: usize = 1_000;
+
+/// One cross-reference section
+enum XrefSection {
+    /// A classic `xref` table: in-use entries and the trailer dictionary
+    Table(Vec<(u32, u64)>, Object),
+    /// A cross-reference stream (PDF 1.5), whose dictionary doubles as the trailer
+    Stream(Object),
+}
+
+/// Random access to the objects of a PDF file
+struct Document {
+    file: File,
+    size: u64,
+    offsets: HashMap<u32, u64>,
+    /// Whether a cross-reference stream was found, so that objects may
+    /// sit in compressed object streams this reader does not open
+    object_streams: bool,
+}
+
+/// Why an indirect object could not be read
+#[derive(Debug)]
+enum ReadError {
+    /// No offset is known for the object
+    NotFound(u32),
+    /// The object is there but does not parse
+    Damaged(String),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::NotFound(number) => write!(f, "object {} is not in the cross-reference table", number),
+            ReadError::Damaged(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Document {
+    /// Run `parse` over the bytes at `offset`, reading more if it runs out
+    fn parse_at<T>(
+        &mut self,
+        offset: u64,
+        parse: impl Fn(&mut Parser) -> Result<T, ParseError>,
+    ) -> Result<T, String> {
+        if offset >= self.size {
+            return Err(format!("offset {} is past the end of the file", offset));
+        }
+        let available = self.size - offset;
+        let mut window = INITIAL_WINDOW;
+        loop {
+            let len = window.min(available);
+            let mut buf = vec![0u8; len as usize];
+            self.file
+                .seek(SeekFrom::Start(offset))
+                .and_then(|_| self.file.read_exact(&mut buf))
+                .map_err(|e| e.to_string())?;
+            match parse(&mut Parser::new(&buf)) {
+                Ok(value) => return Ok(value),
+                Err(ParseError::Truncated) if len < available && window < MAX_WINDOW => window *= 4,
+                Err(ParseError::Truncated) => return Err(format!("object at offset {} is cut off", offset)),
+                Err(ParseError::Invalid(message)) => {
+                    return Err(format!("{} (at offset {})", message, offset))
+                }
+            }
+        }
+    }
+
+    /// Read indirect object `number`
+    fn load(&mut self, number: u32) -> Result<Object, ReadError> {
+        let offset = *self.offsets.get(&number).ok_or(ReadError::NotFound(number))?;
+        self.parse_at(offset, |p| {
+            let found = p.parse_integer()?;
+            p.parse_integer()?;
+            if found != number as u64 || !p.eat_keyword("obj") {
+                return Err(ParseError::Invalid(format!("object {} is not where the table says", number)));
+            }
+            p.parse_object()
+        })
+        .map_err(ReadError::Damaged)
+    }
+
+    /// Follow an indirect reference; other objects are returned as they are
+    fn resolve(&mut self, object: &Object) -> Result<Object, ReadError> {
+        match object {
+            Object::Ref(number, _) => self.load(*number),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Parse the cross-reference section at `offset`
+    fn read_xref(&mut self, offset: u64) -> Result<XrefSection, String> {
+        self.parse_at(offset, |p| {
+            if !p.eat_keyword("xref") {
+                // A cross-reference stream is an ordinary indirect object
+                p.parse_integer()?;
+                p.parse_integer()?;
+                if !p.eat_keyword("obj") {
+                    return Err(ParseError::Invalid("expected 'xref' or an XRef stream".to_string()));
+                }
+                let dict = p.parse_object()?;
+                if dict.get("Type").and_then(|t| t.as_name()) != Some("XRef") {
+                    return Err(ParseError::Invalid("expected 'xref' or an XRef stream".to_string()));
+                }
+                return Ok(XrefSection::Stream(dict));
+            }
+
+            let mut entries = Vec::new();
+            while !p.eat_keyword("trailer") {
+                let start = p.parse_integer()?;
+                let count = p.parse_integer()?;
+                for i in 0..count {
+                    let entry_offset = p.parse_integer()?;
+                    p.parse_integer()?;
+                    if p.eat_keyword("n") {
+                        entries.push(((start + i) as u32, entry_offset));
+                    } else if !p.eat_keyword("f") {
+                        return Err(ParseError::Invalid(format!(
+                            "bad entry for object {} in the xref table",
+                            start + i
+                        )));
+                    }
+                }
+            }
+            Ok(XrefSection::Table(entries, p.parse_object()?))
+        })
+    }
+
+    /// Locate objects by scanning the whole file for `N G obj` headers
+    ///
+    /// Later definitions win, as with incremental updates. Returns the
+    /// offset of the last `trailer` keyword, if any.
+    fn scan_objects(&mut self) -> Result<Option<u64>, String> {
+        let mut trailer = None;
+        let mut start = 0u64;
+        while start < self.size {
+            // Read a margin on both sides so headers on a boundary are seen whole
+            let buf_start = start.saturating_sub(64);
+            let buf_end = (start + SCAN_CHUNK + 16).min(self.size);
+            let mut buf = vec![0u8; (buf_end - buf_start) as usize];
+            self.file
+                .seek(SeekFrom::Start(buf_start))
+                .and_then(|_| self.file.read_exact(&mut buf))
+                .map_err(|e| e.to_string())?;
+
+            let first = (start - buf_start) as usize;
+            let last = ((start + SCAN_CHUNK).min(self.size) - buf_start) as usize;
+            for i in first..last {
+                if buf[i..].starts_with(b"trailer") {
+                    trailer = Some(buf_start + i as u64);
+                } else if buf[i..].starts_with(b"obj") {
+                    if let Some((number, at)) = object_header_before(&buf, i) {
+                        self.offsets.insert(number, buf_start + at as u64);
+                    }
+                }
+            }
+            start += SCAN_CHUNK;
+        }
+        Ok(trailer)
+    }
+}
+
+/// Record that `what` could not be read
+///
+/// Objects missing from a file with object streams are skipped silently:
+/// they are most likely compressed, not lost.
+fn warn(doc: &Document, warnings: &mut Vec<String>, what: &str, error: ReadError) {
+    if !(doc.object_streams && matches!(error, ReadError::NotFound(_))) {
+        warnings.push(format!("{} could not be read: {}", what, error));
+    }
+}
+
+/// The object number and start of an `N G obj` header ending at `obj_pos`
+fn object_header_before(buf: &[u8], obj_pos: usize) -> Option<(u32, usize)> {
+    let after = buf.get(obj_pos + 3).copied();
+    if after.is_some_and(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let mut i = obj_pos;
+    let mut fields = [0usize; 2];
+    for field in fields.iter_mut().rev() {
+        let end = i;
+        while i > 0 && buf[i - 1].is_ascii_whitespace() {
+            i -= 1;
+        }
+        if i == end {
+            return None;
+        }
+        let digits_end = i;
+        while i > 0 && buf[i - 1].is_ascii_digit() {
+            i -= 1;
+        }
+        if i == digits_end {
+            return None;
+        }
+        *field = i;
+    }
+    if i > 0 && !buf[i - 1].is_ascii_whitespace() {
+        return None;
+    }
+    let number_end = buf[fields[0]..].iter().position(|b| !b.is_ascii_digit())? + fields[0];
+    let number = std::str::from_utf8(&buf[fields[0]..number_end]).ok()?.parse().ok()?;
+    Some((number, fields[0]))
+}
+
+/// Analyze the structure and metadata of a PDF file
+///
+/// Fails only if the file does not start with a `%PDF-` header. Damaged
+/// cross-reference data falls back to scanning for objects, and parts that
+/// still cannot be read are recorded in `warnings`.
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with `%PDF-`
+///
+/// # Returns
+///
+/// The PDF version, encryption flag, Info dictionary fields, page count and
+/// whether any page uses fonts.
+pub fn analyze_pdf(path: &Path) -> Result<PdfAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut header = [0u8; 16];
+    let header_len = file.read(&mut header)?;
+    let version = header[..header_len]
+        .strip_prefix(b"%PDF-")
+        .map(|rest| {
+            let end = rest.iter().position(|b| b.is_ascii_whitespace()).unwrap_or(rest.len());
+            String::from_utf8_lossy(&rest[..end]).into_owned()
+        })
+        .ok_or_else(|| AnalysisError::InvalidFormat("missing %PDF- header".to_string()))?;
+
+    let mut analysis = PdfAnalysis::new(version);
+    let mut doc = Document {
+        file,
+        size,
+        offsets: HashMap::new(),
+        object_streams: false,
+    };
+
+    let trailer = match read_trailer(&mut doc) {
+        Ok((trailer, complete)) => {
+            if !complete {
+                doc.object_streams = true;
+                doc.scan_objects().map_err(AnalysisError::InvalidFormat)?;
+            }
+            trailer
+        }
+        Err(e) => {
+            analysis.warnings.push(format!(
+                "the cross-reference table could not be read ({}); objects were located by scanning the file",
+                e
+            ));
+            let trailer_offset = doc.scan_objects().map_err(AnalysisError::InvalidFormat)?;
+            match trailer_offset.map(|offset| doc.parse_at(offset, |p| {
+                p.eat_keyword("trailer");
+                p.parse_object()
+            })) {
+                Some(Ok(trailer)) => trailer,
+                _ => {
+                    analysis.warnings.push("no trailer dictionary was found".to_string());
+                    return Ok(analysis);
+                }
+            }
+        }
+    };
+
+    analysis.encrypted = trailer.get("Encrypt").is_some();
+
+    // Strings in an encrypted file are encrypted too
+    if let (Some(info), false) = (trailer.get("Info"), analysis.encrypted) {
+        match doc.resolve(info) {
+            Ok(info) => {
+                let text = |key: &str| match info.get(key) {
+                    Some(Object::String(bytes)) => Some(decode_text(bytes)).filter(|t| !t.trim().is_empty()),
+                    _ => None,
+                };
+                analysis.title = text("Title");
+                analysis.author = text("Author");
+                analysis.creation_date = text("CreationDate").map(|d| format_date(&d));
+            }
+            Err(e) => warn(&doc, &mut analysis.warnings, "the Info dictionary", e),
+        }
+    }
+
+    match trailer.get("Root").map(|root| doc.resolve(root)) {
+        Some(Ok(root)) => read_pages(&mut doc, &root, &mut analysis),
+        Some(Err(e)) => warn(&doc, &mut analysis.warnings, "the document catalog", e),
+        None => analysis.warnings.push("the trailer has no /Root".to_string()),
+    }
+
+    Ok(analysis)
+}
+
+/// Read the cross-reference sections from `startxref` back through `/Prev`
+///
+/// Returns the merged trailer (newer keys win) and whether the object
+/// table is complete; it is not when any section is a compressed stream.
+fn read_trailer(doc: &mut Document) -> Result<(Object, bool), String> {
+    let tail_start = doc.size.saturating_sub(TAIL_SIZE);
+    let mut tail = vec![0u8; (doc.size - tail_start) as usize];
+    doc.file
+        .seek(SeekFrom::Start(tail_start))
+        .and_then(|_| doc.file.read_exact(&mut tail))
+        .map_err(|e| e.to_string())?;
+    let marker = tail
+        .windows(9)
+        .rposition(|w| w == b"startxref")
+        .ok_or("no startxref")?;
+    let mut offset = Parser::new(&tail[marker + 9..])
+        .parse_integer()
+        .map_err(|_| "startxref is not followed by an offset".to_string())?;
+
+    let mut trailer: Vec<(String, Object)> = Vec::new();
+    let mut complete = true;
+    let mut visited = HashSet::new();
+    while visited.insert(offset) && visited.len() <= MAX_XREF_SECTIONS {
+        let dict = match doc.read_xref(offset)? {
+            XrefSection::Table(entries, dict) => {
+                for (number, entry_offset) in entries {
+                    doc.offsets.entry(number).or_insert(entry_offset);
+                }
+                dict
+            }
+            XrefSection::Stream(dict) => {
+                complete = false;
+                dict
+            }
+        };
+        for (key, value) in dict.as_dict().unwrap_or_default() {
+            if !trailer.iter().any(|(k, _)| k == key) {
+                trailer.push((key.clone(), value.clone()));
+            }
+        }
+        match dict.get("Prev").and_then(|p| p.as_number()) {
+            Some(prev) => offset = prev as u64,
+            None => break,
+        }
+    }
+    Ok((Object::Dict(trailer), complete))
+}
+
+/// Read the page count and look for font resources in the page tree
+fn read_pages(doc: &mut Document, root: &Object, analysis: &mut PdfAnalysis) {
+    let pages = match root.get("Pages").map(|pages| doc.resolve(pages)) {
+        Some(Ok(pages)) => pages,
+        Some(Err(e)) => {
+            warn(doc, &mut analysis.warnings, "the page tree", e);
+            return;
+        }
+        None => {
+            analysis.warnings.push("the document catalog has no /Pages".to_string());
+            return;
+        }
+    };
+    analysis.page_count = pages.get("Count").and_then(|c| c.as_number()).map(|c| c as u64);
+
+    // Depth-first walk, carrying inherited /Resources down to the pages
+    let mut stack = vec![(pages, None::<Object>)];
+    let mut visited = HashSet::new();
+    let mut pages_checked = 0;
+    while let Some((node, inherited)) = stack.pop() {
+        let resources = node.get("Resources").cloned().or(inherited);
+        match node.get("Kids").and_then(|k| k.as_array()) {
+            Some(kids) => {
+                for kid in kids.iter().rev() {
+                    if let Object::Ref(number, _) = kid {
+                        if !visited.insert(*number) {
+                            continue;
+                        }
+                    }
+                    match doc.resolve(kid) {
+                        Ok(kid) => stack.push((kid, resources.clone())),
+                        Err(e) => {
+                            warn(doc, &mut analysis.warnings, "a page", e);
+                            return;
+                        }
+                    }
+                }
+            }
+            None => {
+                if let Some(resources) = resources {
+                    if uses_fonts(doc, &resources) {
+                        analysis.has_fonts = Some(true);
+                        return;
+                    }
+                }
+                pages_checked += 1;
+                if pages_checked == MAX_PAGES_CHECKED {
+                    break;
+                }
+            }
+        }
+    }
+    if pages_checked > 0 {
+        analysis.has_fonts = Some(false);
+    }
+}
+
+/// Whether a resource dictionary (or a form XObject in it) has fonts
+fn uses_fonts(doc: &mut Document, resources: &Object) -> bool {
+    let non_empty = |object: Option<Object>| {
+        object.as_ref().and_then(|o| o.as_dict()).is_some_and(|d| !d.is_empty())
+    };
+    let resources = match doc.resolve(resources) {
+        Ok(resources) => resources,
+        Err(_) => return false,
+    };
+    if non_empty(resources.get("Font").and_then(|f| doc.resolve(f).ok())) {
+        return true;
+    }
+
+    // Text drawn inside form XObjects, one level deep
+    let xobjects = match resources.get("XObject").and_then(|x| doc.resolve(x).ok()) {
+        Some(Object::Dict(entries)) => entries,
+        _ => return false,
+    };
+    xobjects.iter().any(|(_, xobject)| {
+        let form = match doc.resolve(xobject) {
+            Ok(form) if form.get("Subtype").and_then(|s| s.as_name()) == Some("Form") => form,
+            _ => return false,
+        };
+        let fonts = form
+            .get("Resources")
+            .and_then(|r| doc.resolve(r).ok())
+            .and_then(|r| r.get("Font").and_then(|f| doc.resolve(f).ok()));
+        non_empty(fonts)
+    })
+}
+
+/// Render a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`) in ISO 8601 form
+///
+/// Returns the text unchanged if it is not a PDF date.
+fn format_date(raw: &str) -> String {
+    let text = raw.trim().trim_start_matches("D:");
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if digits < 4 {
+        return raw.to_string();
+    }
+    let part = |start: usize| &text[start..start + 2];
+
+    let mut out = text[..4].to_string();
+    for (start, separator) in [(4, "-"), (6, "-"), (8, "T"), (10, ":"), (12, ":")] {
+        if digits >= start + 2 {
+            out.push_str(separator);
+            out.push_str(part(start));
+        }
+    }
+    if (12..14).contains(&digits) {
+        out.push_str(":00");
+    }
+
+    // Time zone: Z, or +HH'mm' / -HH'mm'
+    let zone = &text[digits..];
+    if zone.starts_with('Z') {
+        out.push('Z');
+    } else if let Some(sign) = zone.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let zone_digits: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+        if zone_digits.len() >= 2 {
+            let minutes = zone_digits.get(2..4).unwrap_or("00");
+            out.push_str(&format!("{}{}:{}", sign, &zone_digits[..2], minutes));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A PDF with the given objects (numbered from 1), an xref table and a
+    /// trailer with `/Root 1 0 R` plus `trailer_extra`
+    fn pdf(objects: &[&str], trailer_extra: &str) -> Vec<u8> {
+        let mut out = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R {} >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                trailer_extra,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+
+    fn analyze(test: &str, bytes: &[u8]) -> PdfAnalysis {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_pdf_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("doc.pdf");
+        fs::write(&path, bytes).unwrap();
+        let analysis = analyze_pdf(&path).unwrap();
+        fs::remove_dir_all(temp_dir).ok();
+        analysis
+    }
+
+    const TEXT_PDF: &[&str] = &[
+        "<< /Type /Catalog /Pages 2 0 R >>",
+        "<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 /Resources << /Font << /F1 5 0 R >> >> >>",
+        "<< /Type /Page /Parent 2 0 R /Contents 7 0 R >>",
+        "<< /Type /Page /Parent 2 0 R /Contents 7 0 R >>",
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+        "<< /Title <FEFF005200650070002000E9> /Author (A. Researcher) /CreationDate (D:20240115093000+01'00') >>",
+        "<< /Length 0 >>\nstream\n\nendstream",
+    ];
+
+    #[test]
+    fn test_text_pdf() {
+        let analysis = analyze("text", &pdf(TEXT_PDF, "/Info 6 0 R"));
+        assert_eq!(analysis.version, "1.7");
+        assert!(!analysis.encrypted);
+        assert_eq!(analysis.page_count, Some(2));
+        assert_eq!(analysis.has_fonts, Some(true));
+        assert_eq!(analysis.title.as_deref(), Some("Rep \u{e9}"));
+        assert_eq!(analysis.author.as_deref(), Some("A. Researcher"));
+        assert_eq!(analysis.creation_date.as_deref(), Some("2024-01-15T09:30:00+01:00"));
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+    }
+
+    #[test]
+    fn test_scanned_encrypted_pdf() {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>",
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+            "<< /Type /Page /Parent 2 0 R /Resources << /XObject << /Im0 4 0 R >> >> >>",
+            "<< /Type /XObject /Subtype /Image /Width 2480 /Height 3508 /Length 0 >>\nstream\n\nendstream",
+            "<< /Title (encrypted bytes) >>",
+            "<< /Filter /Standard /V 2 >>",
+        ];
+        let analysis = analyze("scanned", &pdf(&objects, "/Info 5 0 R /Encrypt 6 0 R"));
+        assert!(analysis.encrypted);
+        assert_eq!(analysis.title, None);
+        assert_eq!(analysis.page_count, Some(1));
+        assert_eq!(analysis.has_fonts, Some(false));
+    }
+
+    #[test]
+    fn test_recovery_scan() {
+        // startxref pointing nowhere: objects are found by scanning
+        let mut bytes = pdf(TEXT_PDF, "/Info 6 0 R");
+        let marker = bytes.windows(9).rposition(|w| w == b"startxref").unwrap();
+        bytes.truncate(marker);
+        bytes.extend_from_slice(b"startxref\n999999\n%%EOF\n");
+        let analysis = analyze("recovery", &bytes);
+        assert_eq!(analysis.page_count, Some(2));
+        assert_eq!(analysis.has_fonts, Some(true));
+        assert_eq!(analysis.author.as_deref(), Some("A. Researcher"));
+        assert_eq!(analysis.warnings.len(), 1);
+        assert!(analysis.warnings[0].starts_with("the cross-reference table could not be read"));
+
+        // A compressed xref stream: the scan fills in the table silently
+        let mut bytes = b"%PDF-1.5\n".to_vec();
+        let mut xref = 0;
+        let mut objects = TEXT_PDF.to_vec();
+        objects.push("<< /Type /XRef /Size 9 /Root 1 0 R /Info 6 0 R /Filter /FlateDecode /Length 0 >>\nstream\n\nendstream");
+        for (i, object) in objects.iter().enumerate() {
+            xref = bytes.len();
+            bytes.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        bytes.extend_from_slice(format!("startxref\n{}\n%%EOF\n", xref).as_bytes());
+        let analysis = analyze("xref_stream", &bytes);
+        assert_eq!(analysis.version, "1.5");
+        assert_eq!(analysis.page_count, Some(2));
+        assert_eq!(analysis.title.as_deref(), Some("Rep \u{e9}"));
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+
+        // Objects inside (unread) object streams are not reported as damage
+        let bytes = String::from_utf8_lossy(&bytes).replace("/Root 1 0 R", "/Root 99 0 R");
+        let analysis = analyze("object_stream", bytes.as_bytes());
+        assert_eq!(analysis.page_count, None);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date("D:20240115093000Z"), "2024-01-15T09:30:00Z");
+        assert_eq!(format_date("D:2024"), "2024");
+        assert_eq!(format_date("D:20240115"), "2024-01-15");
+        assert_eq!(format_date("D:202401151230-05'00"), "2024-01-15T12:30:00-05:00");
+        assert_eq!(format_date("yesterday"), "yesterday");
+    }
+}
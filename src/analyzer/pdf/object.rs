@@ -0,0 +1,431 @@
+//! PDF object syntax
+//!
+//! Parses the COS objects PDF files are built from: numbers, names,
+//! strings, arrays, dictionaries and indirect references (`12 0 R`).
+//! Stream data is not read; a stream object parses as its dictionary.
+
+/// A parsed PDF object
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Name(String),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(Vec<(String, Object)>),
+    /// Indirect reference: object number and generation
+    Ref(u32, u16),
+    /// A bare keyword such as `obj`, `xref` or `trailer`
+    Keyword(String),
+}
+
+impl Object {
+    /// Value of `key` if this is a dictionary
+    pub fn get(&self, key: &str) -> Option<&Object> {
+        match self {
+            Object::Dict(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Object::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_name(&self) -> Option<&str> {
+        match self {
+            Object::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Object]> {
+        match self {
+            Object::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&[(String, Object)]> {
+        match self {
+            Object::Dict(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+/// Why parsing stopped
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The buffer ended inside an object; a larger read may succeed
+    Truncated,
+    /// The bytes are not valid PDF syntax
+    Invalid(String),
+}
+
+/// Deepest array/dictionary nesting accepted
+const MAX_DEPTH: usize = 100;
+
+/// Cursor over a buffer of PDF syntax
+pub struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Parser { data, pos: 0 }
+    }
+
+    /// Skip whitespace and comments
+    pub fn skip_whitespace(&mut self) {
+        while let Some(&b) = self.data.get(self.pos) {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while let Some(&c) = self.data.get(self.pos) {
+                    if c == b'\n' || c == b'\r' {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Consume `keyword` if it is the next token
+    pub fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let end = self.pos + keyword.len();
+        let matches = self.data.get(self.pos..end) == Some(keyword.as_bytes())
+            && self.data.get(end).is_none_or(|&b| is_whitespace(b) || is_delimiter(b));
+        if matches {
+            self.pos = end;
+        }
+        matches
+    }
+
+    /// Parse a non-negative integer token
+    pub fn parse_integer(&mut self) -> Result<u64, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.data.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        if self.pos == self.data.len() {
+            return Err(ParseError::Truncated);
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| ParseError::Invalid(format!("expected an integer at byte {}", start)))
+    }
+
+    /// Parse the next object
+    pub fn parse_object(&mut self) -> Result<Object, ParseError> {
+        self.parse_nested(0)
+    }
+
+    fn parse_nested(&mut self, depth: usize) -> Result<Object, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::Invalid("objects nested too deeply".to_string()));
+        }
+        self.skip_whitespace();
+        let b = *self.data.get(self.pos).ok_or(ParseError::Truncated)?;
+        match b {
+            b'/' => {
+                self.pos += 1;
+                Ok(Object::Name(self.parse_name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                self.parse_literal_string().map(Object::String)
+            }
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let mut entries = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.data.get(self.pos..self.pos + 2) {
+                        Some(b">>") => {
+                            self.pos += 2;
+                            return Ok(Object::Dict(entries));
+                        }
+                        Some([b'/', _]) => {
+                            self.pos += 1;
+                            let key = self.parse_name();
+                            let value = self.parse_nested(depth + 1)?;
+                            entries.push((key, value));
+                        }
+                        Some(_) => {
+                            return Err(ParseError::Invalid(format!(
+                                "expected a name key at byte {}",
+                                self.pos
+                            )))
+                        }
+                        None => return Err(ParseError::Truncated),
+                    }
+                }
+            }
+            b'<' => {
+                self.pos += 1;
+                self.parse_hex_string().map(Object::String)
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.data.get(self.pos) {
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Object::Array(items));
+                        }
+                        Some(_) => items.push(self.parse_nested(depth + 1)?),
+                        None => return Err(ParseError::Truncated),
+                    }
+                }
+            }
+            b'0'..=b'9' | b'+' | b'-' | b'.' => self.parse_number_or_ref(),
+            _ if is_delimiter(b) => Err(ParseError::Invalid(format!(
+                "unexpected '{}' at byte {}",
+                b as char, self.pos
+            ))),
+            _ => {
+                let word = self.parse_regular();
+                match word.as_str() {
+                    "true" => Ok(Object::Bool(true)),
+                    "false" => Ok(Object::Bool(false)),
+                    "null" => Ok(Object::Null),
+                    _ => Ok(Object::Keyword(word)),
+                }
+            }
+        }
+    }
+
+    /// A run of regular (non-whitespace, non-delimiter) characters
+    fn parse_regular(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|&b| !is_whitespace(b) && !is_delimiter(b))
+        {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.data[start..self.pos]).into_owned()
+    }
+
+    /// A name after its `/`, with `#xx` escapes decoded
+    fn parse_name(&mut self) -> String {
+        let raw = self.parse_regular();
+        if !raw.contains('#') {
+            return raw;
+        }
+        let bytes = raw.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+            match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+                (b'#', Some(value)) => {
+                    out.push(value);
+                    i += 3;
+                }
+                (b, _) => {
+                    out.push(b);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// A number, or an indirect reference if followed by `<gen> R`
+    fn parse_number_or_ref(&mut self) -> Result<Object, ParseError> {
+        let start = self.pos;
+        let text = self.parse_regular();
+        if self.pos == self.data.len() {
+            return Err(ParseError::Truncated);
+        }
+        let number: f64 = text
+            .parse()
+            .map_err(|_| ParseError::Invalid(format!("invalid number '{}' at byte {}", text, start)))?;
+
+        if text.bytes().all(|b| b.is_ascii_digit()) {
+            let after_number = self.pos;
+            self.skip_whitespace();
+            let gen_start = self.pos;
+            while self.data.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                self.pos += 1;
+            }
+            if self.pos > gen_start {
+                let generation = std::str::from_utf8(&self.data[gen_start..self.pos])
+                    .ok()
+                    .and_then(|g| g.parse::<u16>().ok());
+                if self.pos + 1 >= self.data.len() {
+                    return Err(ParseError::Truncated);
+                }
+                if let (Some(generation), true) = (generation, self.eat_keyword("R")) {
+                    if let Ok(number) = text.parse::<u32>() {
+                        return Ok(Object::Ref(number, generation));
+                    }
+                }
+            }
+            self.pos = after_number;
+        }
+        Ok(Object::Number(number))
+    }
+
+    /// A `( ... )` string after its opening parenthesis
+    fn parse_literal_string(&mut self) -> Result<Vec<u8>, ParseError> {
+        let mut out = Vec::new();
+        let mut depth = 1;
+        loop {
+            let b = *self.data.get(self.pos).ok_or(ParseError::Truncated)?;
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    out.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(out);
+                    }
+                    out.push(b);
+                }
+                b'\\' => {
+                    let e = *self.data.get(self.pos).ok_or(ParseError::Truncated)?;
+                    self.pos += 1;
+                    match e {
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'b' => out.push(b'\x08'),
+                        b'f' => out.push(b'\x0c'),
+                        b'0'..=b'7' => {
+                            let mut value = (e - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.data.get(self.pos) {
+                                    Some(&d @ b'0'..=b'7') => {
+                                        value = value * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        // A backslash before an end of line continues the string
+                        b'\r' => {
+                            if self.data.get(self.pos) == Some(&b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => out.push(other),
+                    }
+                }
+                _ => out.push(b),
+            }
+        }
+    }
+
+    /// A `< ... >` hex string after its opening bracket
+    fn parse_hex_string(&mut self) -> Result<Vec<u8>, ParseError> {
+        let mut digits = Vec::new();
+        loop {
+            let b = *self.data.get(self.pos).ok_or(ParseError::Truncated)?;
+            self.pos += 1;
+            match b {
+                b'>' => break,
+                b if b.is_ascii_hexdigit() => digits.push((b as char).to_digit(16).unwrap_or(0) as u8),
+                b if is_whitespace(b) => {}
+                other => {
+                    return Err(ParseError::Invalid(format!(
+                        "invalid '{}' in hex string",
+                        other as char
+                    )))
+                }
+            }
+        }
+        // An odd final digit is followed by an implied 0
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        Ok(digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect())
+    }
+}
+
+/// Decode a PDF text string (UTF-16BE or UTF-8 with a byte-order mark,
+/// otherwise PDFDocEncoding, read here as Latin-1)
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(b"\xFE\xFF") {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        String::from_utf8_lossy(utf8).into_owned()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Object, ParseError> {
+        Parser::new(text.as_bytes()).parse_object()
+    }
+
+    #[test]
+    fn test_parse_objects() {
+        let dict = parse("<< /Type /Page /Kids [3 0 R 4 0 R] /Count 2 /Rotate -90.5 /A#20B true /N null >> ").unwrap();
+        assert_eq!(dict.get("Type"), Some(&Object::Name("Page".to_string())));
+        assert_eq!(
+            dict.get("Kids").and_then(|k| k.as_array()),
+            Some(&[Object::Ref(3, 0), Object::Ref(4, 0)][..])
+        );
+        assert_eq!(dict.get("Count").and_then(|c| c.as_number()), Some(2.0));
+        assert_eq!(dict.get("Rotate").and_then(|c| c.as_number()), Some(-90.5));
+        assert_eq!(dict.get("A B"), Some(&Object::Bool(true)));
+        assert_eq!(dict.get("N"), Some(&Object::Null));
+
+        // Two integers not followed by R stay numbers
+        let array = parse("[1 2 3] ").unwrap();
+        assert_eq!(array.as_array().unwrap().len(), 3);
+
+        assert_eq!(parse("<< /Type /Pa"), Err(ParseError::Truncated));
+        assert!(matches!(parse(") "), Err(ParseError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_parse_strings() {
+        assert_eq!(
+            parse(r"(a (nested) \(escaped\) \101\n) ").unwrap(),
+            Object::String(b"a (nested) (escaped) A\n".to_vec())
+        );
+        assert_eq!(parse("<48 65 6C6C 6F2>").unwrap(), Object::String(b"Hello ".to_vec()));
+        assert_eq!(decode_text(b"\xFE\xFF\x00H\x00\xE9"), "H\u{e9}");
+        assert_eq!(decode_text(b"Caf\xE9"), "Caf\u{e9}");
+    }
+}
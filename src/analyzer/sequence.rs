@@ -0,0 +1,550 @@
+//! FASTA and FASTQ analysis
+//!
+//! Sequence files are streamed line by line (gzipped files through the
+//! decompressor), so read sets of any size are analyzed in bounded memory.
+//! FASTA records are a `>` header followed by any number of sequence
+//! lines; FASTQ reads are exactly four lines: `@` header, bases, `+`
+//! separator and a quality string as long as the bases. Every residue is
+//! counted by character, which gives the alphabet and anything outside it.
+
+use super::AnalysisError;
+use crate::compression::{is_corrupt, DataReader};
+use crate::types::{LengthStats, ResidueAnomaly, SequenceAlphabet, SequenceAnalysis, SequenceFormat};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Malformed records kept for reporting
+pub(super) const MAX_INVALID_SAMPLES: usize = 10;
+
+/// Lengths below this are counted exactly; longer ones are binned to three
+/// significant digits, which bounds memory for long-read data
+const EXACT_LENGTH_LIMIT: u64 = 10_000;
+
+/// Share of residues that must be A, C, G, T, U or N for nucleotides
+const NUCLEOTIDE_SHARE: f64 = 0.9;
+
+/// Analyze a FASTA or FASTQ file
+///
+/// # Arguments
+///
+/// * `path` - Path to the file (optionally gzip-compressed)
+/// * `format` - Which format to read it as
+///
+/// # Returns
+///
+/// A SequenceAnalysis with record counts, the length distribution, the
+/// alphabet and characters outside it, FASTQ qualities and malformed
+/// records.
+pub fn analyze_sequences(path: &Path, format: SequenceFormat) -> Result<SequenceAnalysis, AnalysisError> {
+    analyze_sequences_from(DataReader::open(path)?, format)
+}
+
+/// Analyze FASTA or FASTQ read from a file or archive member
+pub(crate) fn analyze_sequences_from(
+    data: DataReader,
+    format: SequenceFormat,
+) -> Result<SequenceAnalysis, AnalysisError> {
+    let mut reader = BufReader::new(data);
+    let mut scan = Scan::new(format);
+
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            // A damaged .gz is still analyzed up to the damage
+            Err(e) if is_corrupt(&e) => break,
+            Err(e) => return Err(e.into()),
+        }
+        line_number += 1;
+        let text = trim_line_end(&line);
+        match format {
+            SequenceFormat::Fasta => scan.fasta_line(text, line_number),
+            SequenceFormat::Fastq => scan.fastq_line(text, line_number),
+        }
+    }
+
+    let mut analysis = scan.finish(line_number);
+    analysis.gzip = reader.get_ref().gzip_info();
+    Ok(analysis)
+}
+
+/// A line without its `\n` or `\r\n`
+pub(super) fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Malformed records: how many, and the first few
+#[derive(Default)]
+pub(super) struct InvalidRecords {
+    pub count: usize,
+    pub samples: Vec<(usize, String)>,
+}
+
+impl InvalidRecords {
+    pub fn push(&mut self, line: usize, message: impl Into<String>) {
+        self.count += 1;
+        if self.samples.len() < MAX_INVALID_SAMPLES {
+            self.samples.push((line, message.into()));
+        }
+    }
+}
+
+/// Occurrences of each byte in residue strings, and where each first appears
+pub(super) struct Residues {
+    counts: [u64; 256],
+    first_lines: [usize; 256],
+}
+
+impl Residues {
+    pub fn new() -> Self {
+        Residues {
+            counts: [0; 256],
+            first_lines: [0; 256],
+        }
+    }
+
+    pub fn add(&mut self, residues: &[u8], line: usize) {
+        for &b in residues {
+            if self.counts[b as usize] == 0 {
+                self.first_lines[b as usize] = line;
+            }
+            self.counts[b as usize] += 1;
+        }
+    }
+
+    /// Occurrences of the given characters, in either case
+    fn count_of(&self, characters: &[u8]) -> u64 {
+        characters
+            .iter()
+            .map(|&c| self.counts[c.to_ascii_uppercase() as usize] + self.counts[c.to_ascii_lowercase() as usize])
+            .sum()
+    }
+
+    /// Characters for which `valid` is false, most frequent first
+    ///
+    /// Bytes of multi-byte UTF-8 characters are reported together as U+FFFD.
+    pub fn anomalies(&self, valid: impl Fn(u8) -> bool) -> Vec<ResidueAnomaly> {
+        let mut anomalies: Vec<ResidueAnomaly> = Vec::new();
+        for b in 0..=255u8 {
+            let count = self.counts[b as usize];
+            if count == 0 || valid(b) {
+                continue;
+            }
+            let character = if b.is_ascii() { b as char } else { char::REPLACEMENT_CHARACTER };
+            let first_line = self.first_lines[b as usize];
+            match anomalies.iter_mut().find(|a| a.character == character) {
+                Some(anomaly) => {
+                    anomaly.count += count;
+                    anomaly.first_line = anomaly.first_line.min(first_line);
+                }
+                None => anomalies.push(ResidueAnomaly {
+                    character,
+                    count,
+                    first_line,
+                }),
+            }
+        }
+        anomalies.sort_by_key(|a| std::cmp::Reverse(a.count));
+        anomalies
+    }
+}
+
+/// IUPAC nucleotide codes, gaps included
+pub(super) fn is_nucleotide_code(b: u8) -> bool {
+    matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'U' | b'R' | b'Y' | b'S' | b'W' | b'K' | b'M' | b'B' | b'D' | b'H' | b'V' | b'N' | b'-' | b'.')
+}
+
+/// IUPAC amino acid codes (every letter is assigned), stop and gap
+fn is_amino_acid_code(b: u8) -> bool {
+    b.is_ascii_alphabetic() || matches!(b, b'*' | b'-')
+}
+
+/// Record lengths, exact below `EXACT_LENGTH_LIMIT`
+struct Lengths {
+    bins: BTreeMap<u64, u64>,
+    count: u64,
+    total: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Lengths {
+    fn new() -> Self {
+        Lengths {
+            bins: BTreeMap::new(),
+            count: 0,
+            total: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    fn add(&mut self, length: u64) {
+        let mut scale = 1;
+        if length >= EXACT_LENGTH_LIMIT {
+            while length / scale >= 1000 {
+                scale *= 10;
+            }
+        }
+        *self.bins.entry(length / scale * scale).or_insert(0) += 1;
+        self.count += 1;
+        self.total += length;
+        self.min = self.min.min(length);
+        self.max = self.max.max(length);
+    }
+
+    fn stats(&self) -> Option<LengthStats> {
+        if self.count == 0 {
+            return None;
+        }
+        let mut seen = 0;
+        let median = self
+            .bins
+            .iter()
+            .find(|(_, &count)| {
+                seen += count;
+                seen * 2 >= self.count
+            })
+            .map_or(0, |(&length, _)| length);
+        let binned_total: u64 = self.bins.iter().map(|(length, count)| length * count).sum();
+        let mut covered = 0;
+        let n50 = self
+            .bins
+            .iter()
+            .rev()
+            .find(|(&length, &count)| {
+                covered += length * count;
+                covered * 2 >= binned_total
+            })
+            .map_or(0, |(&length, _)| length);
+        Some(LengthStats {
+            min: self.min,
+            max: self.max,
+            mean: self.total as f64 / self.count as f64,
+            median,
+            n50,
+        })
+    }
+}
+
+/// Where a FASTQ read is up to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FastqLine {
+    Header,
+    Bases,
+    Separator,
+    Quality,
+}
+
+/// State of a scan through a sequence file
+struct Scan {
+    format: SequenceFormat,
+    record_count: usize,
+    empty_records: usize,
+    lengths: Lengths,
+    residues: Residues,
+    invalid: InvalidRecords,
+    /// Length of the FASTA record being read
+    current: Option<u64>,
+    /// Whether sequence lines before the first FASTA header were reported
+    orphan_reported: bool,
+    /// Next FASTQ line expected, and the read's header line and length
+    expected: FastqLine,
+    read_line: usize,
+    read_length: usize,
+    /// Whether lines are being skipped to find the next FASTQ header
+    resyncing: bool,
+    quality_sum: u64,
+    quality_count: u64,
+    quality_min: u8,
+    quality_max: u8,
+}
+
+impl Scan {
+    fn new(format: SequenceFormat) -> Self {
+        Scan {
+            format,
+            record_count: 0,
+            empty_records: 0,
+            lengths: Lengths::new(),
+            residues: Residues::new(),
+            invalid: InvalidRecords::default(),
+            current: None,
+            orphan_reported: false,
+            expected: FastqLine::Header,
+            read_line: 0,
+            read_length: 0,
+            resyncing: false,
+            quality_sum: 0,
+            quality_count: 0,
+            quality_min: u8::MAX,
+            quality_max: 0,
+        }
+    }
+
+    fn end_record(&mut self, length: u64) {
+        self.record_count += 1;
+        if length == 0 {
+            self.empty_records += 1;
+        }
+        self.lengths.add(length);
+    }
+
+    fn fasta_line(&mut self, text: &[u8], line: usize) {
+        match text.first() {
+            None => {}
+            Some(b'>') => {
+                if let Some(length) = self.current.take() {
+                    self.end_record(length);
+                }
+                if text[1..].first().is_none_or(|b| b.is_ascii_whitespace()) {
+                    self.invalid.push(line, "Header without an identifier after '>'");
+                }
+                self.current = Some(0);
+            }
+            // Comment line of the original FASTA format
+            Some(b';') => {}
+            Some(_) => match self.current.as_mut() {
+                Some(length) => {
+                    *length += text.len() as u64;
+                    self.residues.add(text, line);
+                }
+                None if !self.orphan_reported => {
+                    self.orphan_reported = true;
+                    self.invalid.push(line, "Sequence data before the first '>' header");
+                }
+                None => {}
+            },
+        }
+    }
+
+    fn fastq_line(&mut self, text: &[u8], line: usize) {
+        match self.expected {
+            FastqLine::Header => {
+                if text.first() == Some(&b'@') {
+                    self.resyncing = false;
+                    self.read_line = line;
+                    self.expected = FastqLine::Bases;
+                } else if !text.is_empty() && !self.resyncing {
+                    // Report once, then skip to the next header
+                    self.resyncing = true;
+                    self.invalid.push(line, "Expected a read header starting with '@'");
+                }
+            }
+            FastqLine::Bases => {
+                self.read_length = text.len();
+                self.residues.add(text, line);
+                self.expected = FastqLine::Separator;
+            }
+            FastqLine::Separator => {
+                if text.first() == Some(&b'+') {
+                    self.expected = FastqLine::Quality;
+                } else {
+                    self.invalid.push(
+                        line,
+                        format!("Read at line {} has no '+' separator line after its bases", self.read_line),
+                    );
+                    self.expected = FastqLine::Header;
+                    self.fastq_line(text, line);
+                }
+            }
+            FastqLine::Quality => {
+                if text.len() != self.read_length {
+                    self.invalid.push(
+                        line,
+                        format!(
+                            "Quality string has {} characters but the read has {} bases",
+                            text.len(),
+                            self.read_length
+                        ),
+                    );
+                } else if text.iter().any(|&q| !(b'!'..=b'~').contains(&q)) {
+                    self.invalid.push(line, "Quality string has characters outside '!' to '~'");
+                } else {
+                    self.quality_sum += text.iter().map(|&q| q as u64).sum::<u64>();
+                    self.quality_count += text.len() as u64;
+                    self.quality_min = text.iter().copied().fold(self.quality_min, u8::min);
+                    self.quality_max = text.iter().copied().fold(self.quality_max, u8::max);
+                }
+                self.end_record(self.read_length as u64);
+                self.expected = FastqLine::Header;
+            }
+        }
+    }
+
+    fn finish(mut self, last_line: usize) -> SequenceAnalysis {
+        if let Some(length) = self.current.take() {
+            self.end_record(length);
+        }
+        if self.expected != FastqLine::Header {
+            self.invalid.push(
+                last_line,
+                format!("Read at line {} is cut off at the end of the file", self.read_line),
+            );
+        }
+
+        let total = self.residues.counts.iter().sum::<u64>();
+        let alphabet = if total == 0 {
+            None
+        } else if self.format == SequenceFormat::Fastq
+            || self.residues.count_of(b"ACGTUN") as f64 >= NUCLEOTIDE_SHARE * total as f64
+        {
+            if self.residues.count_of(b"U") > 0 && self.residues.count_of(b"T") == 0 {
+                Some(SequenceAlphabet::Rna)
+            } else {
+                Some(SequenceAlphabet::Dna)
+            }
+        } else {
+            Some(SequenceAlphabet::Protein)
+        };
+        let anomalies = match alphabet {
+            Some(SequenceAlphabet::Protein) => self.residues.anomalies(is_amino_acid_code),
+            Some(_) => self.residues.anomalies(is_nucleotide_code),
+            None => Vec::new(),
+        };
+
+        // Phred+64 files never use characters below '@' and reach past 'K',
+        // the top of Phred+33 Illumina scores; anything else is Phred+33
+        let phred64 = self.quality_min >= b'@' && self.quality_max > b'K';
+        let quality_offset = match self.format {
+            SequenceFormat::Fastq if self.quality_count > 0 => Some(if phred64 { 64 } else { 33 }),
+            _ => None,
+        };
+        let mean_quality = quality_offset.map(|offset| {
+            (self.quality_sum - offset as u64 * self.quality_count) as f64 / self.quality_count as f64
+        });
+
+        SequenceAnalysis {
+            format: self.format,
+            record_count: self.record_count,
+            total_length: self.lengths.total,
+            lengths: self.lengths.stats(),
+            empty_records: self.empty_records,
+            alphabet,
+            anomalies,
+            quality_offset,
+            mean_quality,
+            invalid_record_count: self.invalid.count,
+            invalid_records: self.invalid.samples,
+            gzip: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn analyze(test: &str, name: &str, content: &[u8], format: SequenceFormat) -> SequenceAnalysis {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_sequence_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join(name);
+        fs::write(&path, content).unwrap();
+        let analysis = analyze_sequences(&path, format).unwrap();
+        fs::remove_dir_all(temp_dir).ok();
+        analysis
+    }
+
+    #[test]
+    fn test_fasta_nucleotides() {
+        let analysis = analyze(
+            "fasta",
+            "contigs.fa",
+            b">chr1 assembled\nACGTACGTACGTACGTACGT\nacgtnn\r\n\n>chr2\nACGT ACGT1\n>\n>chr3\nNR\n",
+            SequenceFormat::Fasta,
+        );
+
+        assert_eq!(analysis.record_count, 4);
+        assert_eq!(analysis.total_length, 38);
+        assert_eq!(analysis.empty_records, 1);
+        assert_eq!(analysis.alphabet, Some(SequenceAlphabet::Dna));
+        let lengths = analysis.lengths.unwrap();
+        assert_eq!((lengths.min, lengths.max, lengths.median, lengths.n50), (0, 26, 2, 26));
+        assert_eq!(
+            analysis.anomalies,
+            vec![
+                ResidueAnomaly { character: ' ', count: 1, first_line: 6 },
+                ResidueAnomaly { character: '1', count: 1, first_line: 6 },
+            ]
+        );
+        assert_eq!(analysis.invalid_records, vec![(7, "Header without an identifier after '>'".to_string())]);
+    }
+
+    #[test]
+    fn test_fasta_protein_and_orphan_lines() {
+        let analysis = analyze(
+            "protein",
+            "proteins.faa",
+            b"MKT\n>sp|P69905|HBA_HUMAN\nMVLSPADKTNVKAAWGKVGAHAGEYGAEALERMFLSFPTTKTYFPHF*\n",
+            SequenceFormat::Fasta,
+        );
+
+        assert_eq!(analysis.record_count, 1);
+        assert_eq!(analysis.alphabet, Some(SequenceAlphabet::Protein));
+        assert!(analysis.anomalies.is_empty());
+        assert_eq!(analysis.invalid_records[0], (1, "Sequence data before the first '>' header".to_string()));
+    }
+
+    #[test]
+    fn test_fastq_records() {
+        let analysis = analyze(
+            "fastq",
+            "reads.fastq",
+            concat!(
+                "@r1\nACGTN\n+\nIIII#\n",
+                "@r2\nACGT\n+r2\nIII\n",
+                "@r3\nACGG\nIIII\n",
+                "@r4\nTTTT\n+\n5555\n",
+                "@r5\nAC",
+            )
+            .as_bytes(),
+            SequenceFormat::Fastq,
+        );
+
+        assert_eq!(analysis.record_count, 3);
+        assert_eq!(analysis.alphabet, Some(SequenceAlphabet::Dna));
+        assert_eq!(analysis.quality_offset, Some(33));
+        // (40 * 4 + 2 + 20 * 4) / 9 bases with valid qualities
+        assert!((analysis.mean_quality.unwrap() - 242.0 / 9.0).abs() < 1e-9);
+        assert_eq!(analysis.invalid_record_count, 4);
+        assert_eq!(
+            analysis.invalid_records,
+            vec![
+                (8, "Quality string has 3 characters but the read has 4 bases".to_string()),
+                (11, "Read at line 9 has no '+' separator line after its bases".to_string()),
+                (11, "Expected a read header starting with '@'".to_string()),
+                (17, "Read at line 16 is cut off at the end of the file".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gzipped_fastq() {
+        // "@r1\nACGT\n+\nIIII\n" gzipped
+        let gzipped = crate::compression::fixture::hex(
+            "1f8b0800000000000203732832e47274760fe1d2e6f204022e00fe49162710000000",
+        );
+        let analysis = analyze("gzip", "reads.fq.gz", &gzipped, SequenceFormat::Fastq);
+        assert_eq!(analysis.record_count, 1);
+        assert_eq!(analysis.mean_quality, Some(40.0));
+        assert!(analysis.gzip.is_some_and(|gzip| gzip.error.is_none()));
+    }
+
+    #[test]
+    fn test_length_bins() {
+        let mut lengths = Lengths::new();
+        for length in [150, 150, 151, 23_456, 1_234_567] {
+            lengths.add(length);
+        }
+        let stats = lengths.stats().unwrap();
+        assert_eq!((stats.min, stats.max, stats.median), (150, 1_234_567, 151));
+        // Long lengths are binned to three significant digits
+        assert_eq!(stats.n50, 1_230_000);
+    }
+}
@@ -0,0 +1,314 @@
+//! SQLite pages, b-trees and records
+//!
+//! Pages are read on demand. Every b-tree is walked from its root with an
+//! explicit stack, visiting each cell of table leaves (one row each) and of
+//! index pages (one key each; `WITHOUT ROWID` tables keep their rows this
+//! way). Payloads that spill onto overflow pages are reassembled.
+
+use super::invalid;
+use crate::analyzer::AnalysisError;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Deepest b-tree accepted (real databases are rarely deeper than 5)
+const MAX_DEPTH: usize = 64;
+
+/// Largest payload reassembled from overflow pages; bigger rows are
+/// counted but not profiled
+const MAX_PAYLOAD: u64 = 16 * 1024 * 1024;
+
+const TABLE_LEAF: u8 = 0x0D;
+const TABLE_INTERIOR: u8 = 0x05;
+const INDEX_LEAF: u8 = 0x0A;
+const INDEX_INTERIOR: u8 = 0x02;
+
+/// Page access for a database file
+pub(super) struct Pager<R> {
+    reader: R,
+    pub page_size: usize,
+    /// Bytes of each page available to b-trees (page size less reserved space)
+    pub usable_size: usize,
+    pub page_count: u32,
+}
+
+impl<R: Read + Seek> Pager<R> {
+    pub fn new(reader: R, page_size: usize, usable_size: usize, page_count: u32) -> Self {
+        Pager {
+            reader,
+            page_size,
+            usable_size,
+            page_count,
+        }
+    }
+
+    /// Read a page (numbered from 1)
+    pub fn page(&mut self, number: u32) -> Result<Vec<u8>, AnalysisError> {
+        if number == 0 || number > self.page_count {
+            return Err(invalid(format!(
+                "page {} is outside the database ({} pages; the file may be truncated)",
+                number, self.page_count
+            )));
+        }
+        let mut page = vec![0u8; self.page_size];
+        self.reader.seek(SeekFrom::Start((number as u64 - 1) * self.page_size as u64))?;
+        self.reader.read_exact(&mut page)?;
+        Ok(page)
+    }
+}
+
+/// A b-tree entry: a table row, or an index key
+pub(super) struct Cell {
+    /// Row ID (table b-trees only)
+    pub rowid: Option<i64>,
+    /// The record, or None if it is too large to reassemble
+    pub payload: Option<Vec<u8>>,
+}
+
+/// Visit every entry of the b-tree rooted at `root`
+pub(super) fn walk<R: Read + Seek>(
+    pager: &mut Pager<R>,
+    root: u32,
+    mut visit: impl FnMut(Cell),
+) -> Result<(), AnalysisError> {
+    let mut stack = vec![(root, 0usize)];
+    let mut visited = 0u64;
+    while let Some((number, depth)) = stack.pop() {
+        visited += 1;
+        if depth > MAX_DEPTH || visited > pager.page_count as u64 {
+            return Err(invalid(format!("the b-tree rooted at page {} loops or is too deep", root)));
+        }
+        let page = pager.page(number)?;
+        // Page 1 starts with the 100-byte database header
+        let offset = if number == 1 { 100 } else { 0 };
+        let kind = page[offset];
+        let interior = match kind {
+            TABLE_LEAF | INDEX_LEAF => false,
+            TABLE_INTERIOR | INDEX_INTERIOR => true,
+            _ => return Err(invalid(format!("page {} is not a b-tree page (type {:#04x})", number, kind))),
+        };
+        let cell_count = be16(&page, offset + 3).ok_or_else(|| truncated_cell(number))? as usize;
+        let pointers = offset + if interior { 12 } else { 8 };
+        if pointers + 2 * cell_count > page.len() {
+            return Err(invalid(format!("page {} has more cells than fit", number)));
+        }
+
+        let mut children = Vec::with_capacity(cell_count + 1);
+        for idx in 0..cell_count {
+            let mut pos = be16(&page, pointers + 2 * idx).ok_or_else(|| truncated_cell(number))? as usize;
+            if pos >= pager.usable_size {
+                return Err(invalid(format!("page {} has a cell outside the page", number)));
+            }
+            if interior {
+                children.push(be32(&page, pos).ok_or_else(|| truncated_cell(number))?);
+                pos += 4;
+            }
+            match kind {
+                TABLE_LEAF => {
+                    let (payload_len, used) = varint(&page, pos).ok_or_else(|| truncated_cell(number))?;
+                    let (rowid, used_rowid) = varint(&page, pos + used).ok_or_else(|| truncated_cell(number))?;
+                    let payload = read_payload(pager, &page, pos + used + used_rowid, payload_len, true, number)?;
+                    visit(Cell {
+                        rowid: Some(rowid as i64),
+                        payload,
+                    });
+                }
+                INDEX_LEAF | INDEX_INTERIOR => {
+                    let (payload_len, used) = varint(&page, pos).ok_or_else(|| truncated_cell(number))?;
+                    let payload = read_payload(pager, &page, pos + used, payload_len, false, number)?;
+                    visit(Cell { rowid: None, payload });
+                }
+                // Table interior cells only hold a key for navigation
+                _ => {}
+            }
+        }
+        if interior {
+            children.push(be32(&page, offset + 8).ok_or_else(|| truncated_cell(number))?);
+        }
+        stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+    }
+    Ok(())
+}
+
+fn truncated_cell(page: u32) -> AnalysisError {
+    invalid(format!("page {} has a truncated cell", page))
+}
+
+/// A cell's payload, following its overflow chain if it has one
+fn read_payload<R: Read + Seek>(
+    pager: &mut Pager<R>,
+    page: &[u8],
+    start: usize,
+    payload_len: u64,
+    table_leaf: bool,
+    number: u32,
+) -> Result<Option<Vec<u8>>, AnalysisError> {
+    // How much of the payload is stored on the page itself
+    let usable = pager.usable_size as u64;
+    let max_local = if table_leaf { usable - 35 } else { (usable - 12) * 64 / 255 - 23 };
+    let local = if payload_len <= max_local {
+        payload_len
+    } else {
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let k = min_local + (payload_len - min_local) % (usable - 4);
+        if k <= max_local {
+            k
+        } else {
+            min_local
+        }
+    } as usize;
+    if start + local > page.len() {
+        return Err(truncated_cell(number));
+    }
+    if payload_len > MAX_PAYLOAD {
+        return Ok(None);
+    }
+
+    let mut payload = Vec::with_capacity(payload_len as usize);
+    payload.extend_from_slice(&page[start..start + local]);
+    if local as u64 == payload_len {
+        return Ok(Some(payload));
+    }
+    let mut next = be32(page, start + local).ok_or_else(|| truncated_cell(number))?;
+    let mut hops = 0;
+    while (payload.len() as u64) < payload_len {
+        hops += 1;
+        if next == 0 || hops > pager.page_count {
+            return Err(invalid(format!("the overflow chain of a cell on page {} is broken", number)));
+        }
+        let overflow = pager.page(next)?;
+        let wanted = (payload_len as usize - payload.len()).min(pager.usable_size - 4);
+        payload.extend_from_slice(&overflow[4..4 + wanted]);
+        next = be32(&overflow, 0).ok_or_else(|| truncated_cell(number))?;
+    }
+    Ok(Some(payload))
+}
+
+/// A value stored in a record
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    /// Text in the database encoding
+    Text(Vec<u8>),
+    /// A blob, by its length
+    Blob(usize),
+}
+
+/// Decode a record: a header of serial types, then the values
+pub(super) fn decode_record(payload: &[u8]) -> Option<Vec<Value>> {
+    let (header_len, mut pos) = varint(payload, 0)?;
+    let header_len = header_len as usize;
+    let mut data = header_len;
+    let mut values = Vec::new();
+    while pos < header_len {
+        let (serial_type, used) = varint(payload, pos)?;
+        pos += used;
+        let size = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type as usize,
+            5 => 6,
+            6 | 7 => 8,
+            10 | 11 => return None,
+            n => ((n - 12) / 2) as usize,
+        };
+        let bytes = payload.get(data..data + size)?;
+        data += size;
+        values.push(match serial_type {
+            0 => Value::Null,
+            8 => Value::Integer(0),
+            9 => Value::Integer(1),
+            1..=6 => {
+                // Big-endian two's complement, sign-extended from the top byte
+                let mut n: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+                for &b in bytes {
+                    n = (n << 8) | b as i64;
+                }
+                Value::Integer(n)
+            }
+            7 => Value::Real(f64::from_be_bytes(bytes.try_into().ok()?)),
+            n if n % 2 == 0 => Value::Blob(size),
+            _ => Value::Text(bytes.to_vec()),
+        });
+    }
+    Some(values)
+}
+
+/// A SQLite variable-length integer: returns the value and its length
+pub(super) fn varint(bytes: &[u8], pos: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for idx in 0..9 {
+        let b = *bytes.get(pos + idx)?;
+        if idx == 8 {
+            return Some(((value << 8) | b as u64, 9));
+        }
+        value = (value << 7) | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            return Some((value, idx + 1));
+        }
+    }
+    None
+}
+
+/// A big-endian u16, or None if it runs past the end of `bytes`
+fn be16(bytes: &[u8], pos: usize) -> Option<u16> {
+    let b = bytes.get(pos..pos + 2)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+/// A big-endian u32, or None if it runs past the end of `bytes`
+fn be32(bytes: &[u8], pos: usize) -> Option<u32> {
+    let b = bytes.get(pos..pos + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_and_record() {
+        assert_eq!(varint(&[0x05], 0), Some((5, 1)));
+        assert_eq!(varint(&[0x81, 0x00], 0), Some((128, 2)));
+        assert_eq!(varint(&[0xFF; 9], 0), Some((u64::MAX, 9)));
+        assert_eq!(varint(&[0x81], 0), None);
+
+        // Header: length 7; NULL, int8, int16, zero, text(2), real
+        let record = [
+            7, 0, 1, 2, 8, 17, 7, 0xFE, 0x01, 0x00, b'o', b'k', 0x3F, 0xF8, 0, 0, 0, 0, 0, 0,
+        ];
+        assert_eq!(
+            decode_record(&record),
+            Some(vec![
+                Value::Null,
+                Value::Integer(-2),
+                Value::Integer(256),
+                Value::Integer(0),
+                Value::Text(b"ok".to_vec()),
+                Value::Real(1.5),
+            ])
+        );
+        // A value running past the payload
+        assert_eq!(decode_record(&[2, 6, 0, 1]), None);
+    }
+
+    #[test]
+    fn test_cells_at_page_end_are_truncated() {
+        // Page 2: a table interior page whose only cell pointer is in the
+        // last 3 bytes, so its child page number runs off the page
+        let mut data = vec![0u8; 2048];
+        data[1024..1029].copy_from_slice(&[TABLE_INTERIOR, 0, 0, 0, 1]);
+        data[1024 + 12..1024 + 14].copy_from_slice(&1021u16.to_be_bytes());
+        let mut pager = Pager::new(std::io::Cursor::new(data.clone()), 1024, 1024, 2);
+        let error = walk(&mut pager, 2, |_| {}).unwrap_err();
+        assert!(error.to_string().contains("page 2 has a truncated cell"), "{}", error);
+
+        // A table leaf cell whose 2000-byte payload keeps 980 bytes on the
+        // page, leaving no room for the overflow page number after them
+        data[1024..1029].copy_from_slice(&[TABLE_LEAF, 0, 0, 0, 1]);
+        data[1024 + 8..1024 + 10].copy_from_slice(&40u16.to_be_bytes());
+        data[1024 + 40..1024 + 43].copy_from_slice(&[0x8F, 0x50, 0x01]);
+        let mut pager = Pager::new(std::io::Cursor::new(data), 1024, 1024, 2);
+        let error = walk(&mut pager, 2, |_| {}).unwrap_err();
+        assert!(error.to_string().contains("page 2 has a truncated cell"), "{}", error);
+    }
+}
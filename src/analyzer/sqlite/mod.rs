@@ -0,0 +1,623 @@
+//! SQLite database analysis
+//!
+//! A SQLite database is a single file of fixed-size pages. Page 1 starts
+//! with a 100-byte header and holds the root of `sqlite_schema`, the table
+//! listing every table, index and view with the SQL that created it. Each
+//! table is a b-tree whose leaves hold its rows as records. The file is
+//! only read; nothing is written or locked.
+
+mod btree;
+mod sql;
+
+use super::csv::StreamingColumnStats;
+use super::AnalysisError;
+use crate::types::{ColumnType, CsvAnalysis, SqliteAnalysis, SqliteIndex, SqliteTable};
+use btree::{decode_record, walk, Pager, Value};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Magic at the start of every SQLite 3 database
+const MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// Size of the database header on page 1
+const HEADER_SIZE: usize = 100;
+
+/// Build an `InvalidFormat` error with a `SQLite:` prefix
+pub(super) fn invalid(message: impl Into<String>) -> AnalysisError {
+    AnalysisError::InvalidFormat(format!("SQLite: {}", message.into()))
+}
+
+/// A big-endian u16 field of the database header
+fn header_u16(header: &[u8; HEADER_SIZE], pos: usize) -> u16 {
+    u16::from_be_bytes([header[pos], header[pos + 1]])
+}
+
+/// A big-endian u32 field of the database header
+fn header_u32(header: &[u8; HEADER_SIZE], pos: usize) -> u32 {
+    u32::from_be_bytes([header[pos], header[pos + 1], header[pos + 2], header[pos + 3]])
+}
+
+/// Text encodings by header value
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8,
+    Utf16le,
+    Utf16be,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16le => "UTF-16le",
+            Encoding::Utf16be => "UTF-16be",
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> String {
+        let units = |to_u16: fn([u8; 2]) -> u16| -> String {
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| to_u16([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        };
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Encoding::Utf16le => units(u16::from_le_bytes),
+            Encoding::Utf16be => units(u16::from_be_bytes),
+        }
+    }
+}
+
+/// Analyze a SQLite database
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with `SQLite format 3\0`
+///
+/// # Returns
+///
+/// The header fields, each table with its declared columns, row count and
+/// rows profiled as a tabular analysis, the indexes and the views. A
+/// damaged table is reported in `error` after the tables read before it.
+pub fn analyze_sqlite(path: &Path) -> Result<SqliteAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut header = [0u8; HEADER_SIZE];
+    if file_len < HEADER_SIZE as u64 {
+        return Err(invalid("file is smaller than the 100-byte header"));
+    }
+    file.read_exact(&mut header)?;
+    if &header[..16] != MAGIC {
+        return Err(invalid("'SQLite format 3' magic not found"));
+    }
+
+    let page_size = match header_u16(&header, 16) {
+        1 => 65536,
+        size if size >= 512 && size.is_power_of_two() => size as usize,
+        size => return Err(invalid(format!("invalid page size {}", size))),
+    };
+    let usable_size = page_size - header[20] as usize;
+    if usable_size < 480 {
+        return Err(invalid(format!("{} reserved bytes leave too little of each page", header[20])));
+    }
+    // The page count in the header is only trusted if it was written by
+    // the same change as the header (older writers did not maintain it)
+    let file_pages = (file_len / page_size as u64).min(u32::MAX as u64) as u32;
+    let page_count = match header_u32(&header, 28) {
+        count if count > 0 && header_u32(&header, 24) == header_u32(&header, 92) => count,
+        _ => file_pages,
+    };
+    let encoding = match header_u32(&header, 56) {
+        0 | 1 => Encoding::Utf8,
+        2 => Encoding::Utf16le,
+        3 => Encoding::Utf16be,
+        other => return Err(invalid(format!("unknown text encoding {}", other))),
+    };
+
+    let mut analysis = SqliteAnalysis {
+        page_size: page_size as u32,
+        page_count,
+        text_encoding: encoding.name().to_string(),
+        user_version: header_u32(&header, 60),
+        wal_mode: header[18] == 2 || header[19] == 2,
+        tables: Vec::new(),
+        indexes: Vec::new(),
+        views: Vec::new(),
+        error: None,
+    };
+
+    // Pages past the end of a truncated file are reported when reached
+    let mut pager = Pager::new(BufReader::new(file), page_size, usable_size, page_count.min(file_pages));
+    let mut entries = Vec::new();
+    walk(&mut pager, 1, |cell| entries.push(cell.payload.and_then(|payload| decode_record(&payload))))?;
+
+    for values in entries {
+        let values = values.ok_or_else(|| invalid("a sqlite_schema record is damaged"))?;
+        let text = |idx: usize| match values.get(idx) {
+            Some(Value::Text(bytes)) => Some(encoding.decode(bytes)),
+            _ => None,
+        };
+        let (kind, name, table_name, sql) = match (text(0), text(1), text(2)) {
+            (Some(kind), Some(name), Some(table_name)) => (kind, name, table_name, text(4)),
+            _ => return Err(invalid("a sqlite_schema record is damaged")),
+        };
+        let root = match values.get(3) {
+            Some(Value::Integer(root)) => u32::try_from(*root).unwrap_or(0),
+            _ => 0,
+        };
+        match kind.as_str() {
+            "table" if !name.starts_with("sqlite_") => {
+                let (table, error) = read_table(&mut pager, encoding, name, root, sql.as_deref().unwrap_or(""));
+                analysis.tables.push(table);
+                if let Some(message) = error {
+                    analysis.error.get_or_insert(message);
+                }
+            }
+            "index" => {
+                let (unique, columns) = match sql.as_deref() {
+                    Some(sql) => sql::parse_create_index(sql).unwrap_or_default(),
+                    // PRIMARY KEY and UNIQUE constraints have no SQL of their own
+                    None => (true, Vec::new()),
+                };
+                analysis.indexes.push(SqliteIndex {
+                    name,
+                    table: table_name,
+                    columns,
+                    unique,
+                    automatic: sql.is_none(),
+                });
+            }
+            "view" => analysis.views.push(name),
+            _ => {}
+        }
+    }
+
+    // Shadow tables hold the data of virtual tables (an FTS5 table `docs`
+    // keeps its index in `docs_data`, `docs_idx`, ...)
+    let shadow_prefixes: Vec<String> =
+        analysis.tables.iter().filter(|t| t.is_virtual).map(|t| format!("{}_", t.name)).collect();
+    analysis
+        .tables
+        .retain(|t| !shadow_prefixes.iter().any(|prefix| t.name.starts_with(prefix.as_str())));
+
+    Ok(analysis)
+}
+
+/// Read a table's rows; on damage, return what was read and the error
+fn read_table<R: Read + std::io::Seek>(
+    pager: &mut Pager<R>,
+    encoding: Encoding,
+    name: String,
+    root: u32,
+    sql: &str,
+) -> (SqliteTable, Option<String>) {
+    let definition = sql::parse_create_table(sql);
+    let mut table = SqliteTable {
+        name,
+        columns: definition.as_ref().map(|d| d.columns.clone()).unwrap_or_default(),
+        row_count: 0,
+        without_rowid: definition.as_ref().is_some_and(|d| d.without_rowid),
+        is_virtual: root == 0 || definition.is_none(),
+        table: CsvAnalysis::new(',', true),
+    };
+    if table.is_virtual {
+        return (table, None);
+    }
+
+    // Record position of each column: WITHOUT ROWID tables store the
+    // primary key columns first
+    let mut order: Vec<usize> = (0..table.columns.len()).collect();
+    if table.without_rowid {
+        let key_position = |column: &usize| {
+            let column_name = &table.columns[*column].name;
+            definition
+                .as_ref()
+                .and_then(|d| d.primary_key.iter().position(|key| key.eq_ignore_ascii_case(column_name)))
+                .unwrap_or(usize::MAX)
+        };
+        order.sort_by_key(key_position);
+    }
+    // A lone INTEGER PRIMARY KEY column is the rowid and stored as NULL
+    let rowid_alias = match definition.as_ref().map(|d| d.primary_key.as_slice()) {
+        Some([key]) if !table.without_rowid => table
+            .columns
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(key) && c.declared_type.eq_ignore_ascii_case("INTEGER")),
+        _ => None,
+    };
+
+    let mut stats: Vec<StreamingColumnStats> = table.columns.iter().map(|_| StreamingColumnStats::new()).collect();
+    let mut row_count = 0u64;
+    let mut damaged_rows = 0u64;
+    let result = walk(pager, root, |cell| {
+        row_count += 1;
+        let values = match cell.payload {
+            Some(payload) => match decode_record(&payload) {
+                Some(values) => values,
+                None => {
+                    damaged_rows += 1;
+                    return;
+                }
+            },
+            // Rows too large to reassemble are counted only
+            None => return,
+        };
+        for (position, &column) in order.iter().enumerate() {
+            let cell_text = match values.get(position) {
+                _ if rowid_alias == Some(column) => cell.rowid.map(|rowid| rowid.to_string()).unwrap_or_default(),
+                Some(value) => value_text(value, encoding),
+                // Columns added by ALTER TABLE are missing from older rows
+                None => String::new(),
+            };
+            stats[column].update(&cell_text);
+        }
+    });
+
+    table.row_count = row_count;
+    table.table.row_count = row_count as usize;
+    table.table.column_count = table.columns.len();
+    table.table.columns = stats
+        .into_iter()
+        .zip(&table.columns)
+        .enumerate()
+        .map(|(idx, (column, declared))| {
+            let info = column.into_column_info(idx, Some(declared.name.clone()));
+            match (declared_affinity(&declared.declared_type), info.inferred_type) {
+                // An empty column is described by its declaration
+                (Some(declared), _) if info.sample_values.is_empty() => info.with_type(declared),
+                // Digits in a TEXT column are codes, not numbers
+                (Some(ColumnType::String), ColumnType::Integer | ColumnType::Float | ColumnType::Boolean) => {
+                    info.with_type(ColumnType::String).with_range(None, None)
+                }
+                // SQLite stores booleans as 0 and 1
+                (Some(ColumnType::Boolean), ColumnType::Integer) => {
+                    info.with_type(ColumnType::Boolean).with_range(None, None)
+                }
+                _ => info,
+            }
+        })
+        .collect();
+
+    let error = match result {
+        Err(AnalysisError::InvalidFormat(message)) => Some(message.trim_start_matches("SQLite: ").to_string()),
+        Err(other) => Some(other.to_string()),
+        Ok(()) if damaged_rows > 0 => Some(format!("{} rows have damaged records", damaged_rows)),
+        Ok(()) => None,
+    };
+    let error = error.map(|message| format!("table {}: {}", table.name, message));
+    (table, error)
+}
+
+/// A stored value as the text the column statistics expect (empty for NULL)
+fn value_text(value: &Value, encoding: Encoding) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Integer(n) => n.to_string(),
+        Value::Real(f) => format!("{:?}", f),
+        Value::Text(bytes) => encoding.decode(bytes),
+        Value::Blob(len) => format!("[{} byte blob]", len),
+    }
+}
+
+/// The column type a declared SQL type implies, following SQLite's
+/// affinity rules and the conventional names for dates and booleans
+fn declared_affinity(declared_type: &str) -> Option<ColumnType> {
+    let declared = declared_type.to_ascii_uppercase();
+    let has = |part: &str| declared.contains(part);
+    if declared.is_empty() || has("BLOB") {
+        None
+    } else if has("INT") {
+        Some(ColumnType::Integer)
+    } else if has("CHAR") || has("CLOB") || has("TEXT") {
+        Some(ColumnType::String)
+    } else if has("REAL") || has("FLOA") || has("DOUB") || has("NUMERIC") || has("DECIMAL") {
+        Some(ColumnType::Float)
+    } else if has("BOOL") {
+        Some(ColumnType::Boolean)
+    } else if has("DATETIME") || has("TIMESTAMP") {
+        Some(ColumnType::Timestamp)
+    } else if has("DATE") {
+        Some(ColumnType::Date)
+    } else if has("TIME") {
+        Some(ColumnType::Time)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Builds a small SQLite database for tests
+    //!
+    //! The pages are laid out by hand (`PRAGMA integrity_check` accepts the
+    //! result): 1024-byte pages, a two-level `samples` table whose third row
+    //! spills onto an overflow page, an index on `samples`, an empty table,
+    //! a `WITHOUT ROWID` table and a view.
+
+    const PAGE_SIZE: usize = 1024;
+
+    /// A value to store
+    pub(crate) enum Field<'a> {
+        Null,
+        Int(i64),
+        Real(f64),
+        Text(&'a str),
+    }
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut groups = vec![(value & 0x7F) as u8];
+        value >>= 7;
+        while value > 0 {
+            groups.push((value & 0x7F) as u8 | 0x80);
+            value >>= 7;
+        }
+        groups.reverse();
+        groups
+    }
+
+    /// Encode a record
+    pub(crate) fn record(fields: &[Field]) -> Vec<u8> {
+        let mut types = Vec::new();
+        let mut body = Vec::new();
+        for field in fields {
+            match field {
+                Field::Null => types.push(0),
+                Field::Int(0) => types.push(8),
+                Field::Int(1) => types.push(9),
+                Field::Int(n) if i8::try_from(*n).is_ok() => {
+                    types.push(1);
+                    body.push(*n as u8);
+                }
+                Field::Int(n) => {
+                    types.push(6);
+                    body.extend_from_slice(&n.to_be_bytes());
+                }
+                Field::Real(f) => {
+                    types.push(7);
+                    body.extend_from_slice(&f.to_be_bytes());
+                }
+                Field::Text(text) => {
+                    types.push(13 + 2 * text.len() as u64);
+                    body.extend_from_slice(text.as_bytes());
+                }
+            }
+        }
+        let header: Vec<u8> = types.into_iter().flat_map(varint).collect();
+        assert!(header.len() < 127);
+        let mut record = vec![header.len() as u8 + 1];
+        record.extend(header);
+        record.extend(body);
+        record
+    }
+
+    /// A b-tree page with its cells packed at the end
+    fn page(kind: u8, cells: &[Vec<u8>], right_child: Option<u32>, first: bool) -> Vec<u8> {
+        let mut page = vec![0u8; PAGE_SIZE];
+        let offset = if first { 100 } else { 0 };
+        let mut content = PAGE_SIZE;
+        let mut pointer = offset + if right_child.is_some() { 12 } else { 8 };
+        for cell in cells {
+            content -= cell.len();
+            page[content..content + cell.len()].copy_from_slice(cell);
+            page[pointer..pointer + 2].copy_from_slice(&(content as u16).to_be_bytes());
+            pointer += 2;
+        }
+        assert!(pointer <= content, "fixture page overflows");
+        page[offset] = kind;
+        page[offset + 3..offset + 5].copy_from_slice(&(cells.len() as u16).to_be_bytes());
+        page[offset + 5..offset + 7].copy_from_slice(&(content as u16).to_be_bytes());
+        if let Some(child) = right_child {
+            page[offset + 8..offset + 12].copy_from_slice(&child.to_be_bytes());
+        }
+        page
+    }
+
+    fn table_cell(rowid: u64, payload: &[u8]) -> Vec<u8> {
+        let mut cell = varint(payload.len() as u64);
+        cell.extend(varint(rowid));
+        cell.extend_from_slice(payload);
+        cell
+    }
+
+    fn index_cell(payload: &[u8]) -> Vec<u8> {
+        let mut cell = varint(payload.len() as u64);
+        cell.extend_from_slice(payload);
+        cell
+    }
+
+    /// The test database
+    pub(crate) fn sample_database() -> Vec<u8> {
+        use Field::*;
+        let schema = [
+            ("table", "samples", "samples", 2, "CREATE TABLE samples(id INTEGER PRIMARY KEY, site TEXT NOT NULL, depth REAL, zone TEXT, notes)"),
+            ("index", "idx_site", "samples", 6, "CREATE INDEX idx_site ON samples(site)"),
+            ("table", "log", "log", 7, "CREATE TABLE log(at TIMESTAMP, ok BOOLEAN)"),
+            ("table", "codes", "codes", 8, "CREATE TABLE codes(label TEXT, code TEXT PRIMARY KEY) WITHOUT ROWID"),
+            ("view", "deep", "deep", 0, "CREATE VIEW deep AS SELECT * FROM samples WHERE depth > 10"),
+        ];
+        let schema_cells: Vec<Vec<u8>> = schema
+            .iter()
+            .enumerate()
+            .map(|(idx, (kind, name, table, root, sql))| {
+                table_cell(idx as u64 + 1, &record(&[Text(kind), Text(name), Text(table), Int(*root), Text(sql)]))
+            })
+            .collect();
+
+        // Page 1: header and schema
+        let mut page1 = page(0x0D, &schema_cells, None, true);
+        page1[..16].copy_from_slice(b"SQLite format 3\0");
+        page1[16..18].copy_from_slice(&(PAGE_SIZE as u16).to_be_bytes());
+        page1[18] = 1;
+        page1[19] = 1;
+        page1[21] = 64;
+        page1[22] = 32;
+        page1[23] = 32;
+        page1[24..28].copy_from_slice(&1u32.to_be_bytes());
+        page1[28..32].copy_from_slice(&8u32.to_be_bytes());
+        page1[40..44].copy_from_slice(&1u32.to_be_bytes());
+        page1[44..48].copy_from_slice(&4u32.to_be_bytes());
+        page1[56..60].copy_from_slice(&1u32.to_be_bytes());
+        page1[60..64].copy_from_slice(&3u32.to_be_bytes());
+        page1[92..96].copy_from_slice(&1u32.to_be_bytes());
+        page1[96..100].copy_from_slice(&3_040_001u32.to_be_bytes());
+
+        // Page 2: interior page of samples (rows 1-2 on page 3, 3-4 on page 4)
+        let mut divider = 3u32.to_be_bytes().to_vec();
+        divider.extend(varint(2));
+        let page2 = page(0x05, &[divider], Some(4), false);
+
+        let page3 = page(
+            0x0D,
+            &[
+                table_cell(1, &record(&[Null, Text("north"), Real(12.5), Text("07"), Null])),
+                table_cell(2, &record(&[Null, Text("east"), Real(3.0), Text("12"), Text("clear")])),
+            ],
+            None,
+            false,
+        );
+
+        // Row 3's 1200-character note overflows onto page 5: the cell keeps
+        // the minimum local payload and a pointer to the rest
+        let notes = "x".repeat(1200);
+        let long_row = record(&[Null, Text("south"), Null, Text("07"), Text(&notes)]);
+        let usable = PAGE_SIZE as u64;
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let local = (min_local + (long_row.len() as u64 - min_local) % (usable - 4)) as usize;
+        let mut overflow_cell = varint(long_row.len() as u64);
+        overflow_cell.extend(varint(3));
+        overflow_cell.extend_from_slice(&long_row[..local]);
+        overflow_cell.extend_from_slice(&5u32.to_be_bytes());
+        let page4 = page(
+            0x0D,
+            &[
+                overflow_cell,
+                table_cell(4, &record(&[Null, Text("west"), Real(40.25), Text("31"), Null])),
+            ],
+            None,
+            false,
+        );
+        let mut page5 = vec![0u8; PAGE_SIZE];
+        let rest = &long_row[local..];
+        assert!(rest.len() <= PAGE_SIZE - 4);
+        page5[4..4 + rest.len()].copy_from_slice(rest);
+
+        // Page 6: idx_site, keys in site order
+        let page6 = page(
+            0x0A,
+            &[("east", 2), ("north", 1), ("south", 3), ("west", 4)]
+                .iter()
+                .map(|(site, rowid)| index_cell(&record(&[Text(site), Int(*rowid)])))
+                .collect::<Vec<_>>(),
+            None,
+            false,
+        );
+
+        // Page 7: the empty log table
+        let page7 = page(0x0D, &[], None, false);
+
+        // Page 8: codes, stored as (code, label) in code order
+        let page8 = page(
+            0x0A,
+            &[
+                index_cell(&record(&[Text("a1"), Text("alpha")])),
+                index_cell(&record(&[Text("b2"), Text("beta")])),
+            ],
+            None,
+            false,
+        );
+
+        [page1, page2, page3, page4, page5, page6, page7, page8].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_analyze_sample_database() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_sqlite_sample");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("survey.sqlite");
+        fs::write(&path, fixture::sample_database()).unwrap();
+
+        let analysis = analyze_sqlite(&path).unwrap();
+        assert_eq!(analysis.page_size, 1024);
+        assert_eq!(analysis.page_count, 8);
+        assert_eq!(analysis.text_encoding, "UTF-8");
+        assert_eq!(analysis.user_version, 3);
+        assert!(!analysis.wal_mode);
+        assert_eq!(analysis.error, None);
+        assert_eq!(analysis.views, vec!["deep"]);
+        assert_eq!(analysis.indexes.len(), 1);
+        assert_eq!(analysis.indexes[0].name, "idx_site");
+        assert_eq!(analysis.indexes[0].columns, vec!["site"]);
+        assert!(!analysis.indexes[0].unique && !analysis.indexes[0].automatic);
+
+        let names: Vec<&str> = analysis.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["samples", "log", "codes"]);
+
+        let samples = &analysis.tables[0];
+        assert_eq!(samples.row_count, 4);
+        assert_eq!(samples.table.row_count, 4);
+        let columns = &samples.table.columns;
+        assert_eq!(columns.len(), 5);
+        // The rowid stands in for the INTEGER PRIMARY KEY
+        assert_eq!(columns[0].null_count, 0);
+        assert_eq!(columns[0].unique_count, 4);
+        assert_eq!(columns[2].inferred_type, ColumnType::Float);
+        assert_eq!(columns[2].null_count, 1);
+        assert_eq!(columns[2].max_value, Some(40.25));
+        assert_eq!(columns[3].inferred_type, ColumnType::String);
+        assert_eq!(columns[3].min_value, None);
+        // The overflowing note is reassembled
+        assert_eq!(columns[4].null_count, 2);
+        assert!(columns[4].sample_values.iter().any(|v| v.len() == 1200));
+
+        let log = &analysis.tables[1];
+        assert_eq!(log.row_count, 0);
+        assert_eq!(log.table.columns[0].inferred_type, ColumnType::Timestamp);
+        assert_eq!(log.table.columns[1].inferred_type, ColumnType::Boolean);
+
+        let codes = &analysis.tables[2];
+        assert!(codes.without_rowid);
+        assert_eq!(codes.row_count, 2);
+        assert_eq!(codes.table.columns[0].name.as_deref(), Some("label"));
+        assert!(codes.table.columns[0].sample_values.contains(&"alpha".to_string()));
+        assert!(codes.table.columns[1].sample_values.contains(&"a1".to_string()));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_truncated_and_foreign_files() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_sqlite_truncated");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        // The second leaf of samples (page 4) is cut off
+        let path = temp_dir.join("cut.sqlite");
+        fs::write(&path, &fixture::sample_database()[..3 * 1024]).unwrap();
+        let analysis = analyze_sqlite(&path).unwrap();
+        let error = analysis.error.expect("truncation should be reported");
+        assert!(error.starts_with("table samples: page 4 is outside the database"), "{}", error);
+        assert_eq!(analysis.tables[0].row_count, 2);
+
+        let path = temp_dir.join("notes.db");
+        fs::write(&path, vec![b'x'; 200]).unwrap();
+        assert!(analyze_sqlite(&path).is_err());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_declared_affinity() {
+        assert_eq!(declared_affinity("BIGINT"), Some(ColumnType::Integer));
+        assert_eq!(declared_affinity("varchar(40)"), Some(ColumnType::String));
+        assert_eq!(declared_affinity("DECIMAL(6, 2)"), Some(ColumnType::Float));
+        assert_eq!(declared_affinity("DATETIME"), Some(ColumnType::Timestamp));
+        assert_eq!(declared_affinity("DATE"), Some(ColumnType::Date));
+        assert_eq!(declared_affinity(""), None);
+        assert_eq!(declared_affinity("BLOB"), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Column and index declarations from the SQL in `sqlite_schema`
+//!
+//! SQLite keeps each table's `CREATE TABLE` statement as written. Only the
+//! parts needed to describe a table are read: column names, declared
+//! types, PRIMARY KEY and NOT NULL constraints, and `WITHOUT ROWID`.
+
+use crate::types::SqliteColumn;
+
+/// A token, with its byte span in the statement
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    /// Identifier or keyword text (unquoted), or the punctuation character
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    /// A bare word: keyword, identifier or number
+    Word,
+    /// A quoted identifier or string literal
+    Quoted,
+    /// `(`, `)`, `,` or another symbol
+    Symbol,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol && self.text.starts_with(symbol)
+    }
+}
+
+/// Split a statement into tokens, dropping comments
+fn tokenize(sql: &str) -> Vec<Token> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let b = bytes[pos];
+        if b.is_ascii_whitespace() {
+            pos += 1;
+        } else if sql[pos..].starts_with("--") {
+            pos = sql[pos..].find('\n').map_or(bytes.len(), |end| pos + end);
+        } else if sql[pos..].starts_with("/*") {
+            pos = sql[pos + 2..].find("*/").map_or(bytes.len(), |end| pos + 2 + end + 2);
+        } else if let Some(close) = match b {
+            b'"' => Some('"'),
+            b'\'' => Some('\''),
+            b'`' => Some('`'),
+            b'[' => Some(']'),
+            _ => None,
+        } {
+            // A doubled closing quote stands for itself
+            let mut text = String::new();
+            let mut chars = sql[pos + 1..].char_indices().peekable();
+            pos = bytes.len();
+            while let Some((idx, c)) = chars.next() {
+                if c == close {
+                    if close != ']' && chars.peek().is_some_and(|&(_, next)| next == close) {
+                        chars.next();
+                        text.push(c);
+                        continue;
+                    }
+                    pos = start + 1 + idx + 1;
+                    break;
+                }
+                text.push(c);
+            }
+            tokens.push(Token {
+                kind: TokenKind::Quoted,
+                text,
+                start,
+                end: pos,
+            });
+        } else if b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80 {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || matches!(bytes[pos], b'_' | b'$' | b'.') || bytes[pos] >= 0x80) {
+                pos += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Word,
+                text: sql[start..pos].to_string(),
+                start,
+                end: pos,
+            });
+        } else {
+            let len = sql[pos..].chars().next().map_or(1, char::len_utf8);
+            pos += len;
+            tokens.push(Token {
+                kind: TokenKind::Symbol,
+                text: sql[start..pos].to_string(),
+                start,
+                end: pos,
+            });
+        }
+    }
+    tokens
+}
+
+/// The tokens inside the first top-level parentheses, split on top-level
+/// commas, and the tokens after the closing parenthesis
+fn parenthesized(tokens: &[Token]) -> Option<(Vec<&[Token]>, &[Token])> {
+    let open = tokens.iter().position(|t| t.is_symbol('('))?;
+    let mut depth = 0;
+    let mut items = Vec::new();
+    let mut item_start = open + 1;
+    for (idx, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+            if depth == 0 {
+                items.push(&tokens[item_start..idx]);
+                return Some((items, &tokens[idx + 1..]));
+            }
+        } else if token.is_symbol(',') && depth == 1 {
+            items.push(&tokens[item_start..idx]);
+            item_start = idx + 1;
+        }
+    }
+    None
+}
+
+/// Keywords that end a column's type name and start its constraints
+const COLUMN_CONSTRAINTS: [&str; 11] = [
+    "CONSTRAINT",
+    "PRIMARY",
+    "NOT",
+    "NULL",
+    "UNIQUE",
+    "CHECK",
+    "DEFAULT",
+    "COLLATE",
+    "REFERENCES",
+    "GENERATED",
+    "AS",
+];
+
+/// Keywords that start a table constraint instead of a column
+const TABLE_CONSTRAINTS: [&str; 5] = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+
+/// What a `CREATE TABLE` statement declares
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TableDefinition {
+    pub columns: Vec<SqliteColumn>,
+    pub without_rowid: bool,
+    /// Primary key columns in key order
+    pub primary_key: Vec<String>,
+}
+
+/// Parse a `CREATE TABLE` statement
+///
+/// Returns None for statements without a column list (`CREATE VIRTUAL
+/// TABLE` module arguments are not column definitions).
+pub(super) fn parse_create_table(sql: &str) -> Option<TableDefinition> {
+    let tokens = tokenize(sql);
+    if tokens.iter().take(3).any(|t| t.is_keyword("VIRTUAL")) {
+        return None;
+    }
+    let (items, rest) = parenthesized(&tokens)?;
+
+    let mut columns: Vec<SqliteColumn> = Vec::new();
+    let mut primary_key = Vec::new();
+    for item in items {
+        let first = match item.first() {
+            Some(first) => first,
+            None => continue,
+        };
+        if first.kind == TokenKind::Word && TABLE_CONSTRAINTS.iter().any(|k| first.is_keyword(k)) {
+            // PRIMARY KEY (a, b) names the key columns
+            if let Some(idx) = item.iter().position(|t| t.is_keyword("PRIMARY")) {
+                if let Some((keys, _)) = parenthesized(&item[idx..]) {
+                    primary_key.extend(keys.iter().filter_map(|key| key.first()).map(|t| t.text.clone()));
+                }
+            }
+            continue;
+        }
+
+        let type_end = item
+            .iter()
+            .skip(1)
+            .position(|t| COLUMN_CONSTRAINTS.iter().any(|k| t.is_keyword(k)))
+            .map_or(item.len(), |idx| idx + 1);
+        let declared_type = match (item.get(1), item.get(type_end - 1)) {
+            (Some(start), Some(end)) if type_end > 1 => sql[start.start..end.end].split_whitespace().collect::<Vec<_>>().join(" "),
+            _ => String::new(),
+        };
+        let constraints = &item[type_end..];
+        let column_key = constraints.windows(2).any(|w| w[0].is_keyword("PRIMARY") && w[1].is_keyword("KEY"));
+        if column_key {
+            primary_key.push(first.text.clone());
+        }
+        columns.push(SqliteColumn {
+            name: first.text.clone(),
+            declared_type,
+            primary_key: false,
+            not_null: constraints.windows(2).any(|w| w[0].is_keyword("NOT") && w[1].is_keyword("NULL")),
+        });
+    }
+
+    for column in &mut columns {
+        column.primary_key = primary_key.iter().any(|key| key.eq_ignore_ascii_case(&column.name));
+    }
+    let without_rowid = rest.windows(2).any(|w| w[0].is_keyword("WITHOUT") && w[1].is_keyword("ROWID"));
+    Some(TableDefinition {
+        columns,
+        without_rowid,
+        primary_key,
+    })
+}
+
+/// Parse a `CREATE INDEX` statement: whether it is unique, and the indexed
+/// columns or expressions
+pub(super) fn parse_create_index(sql: &str) -> Option<(bool, Vec<String>)> {
+    let tokens = tokenize(sql);
+    let unique = tokens.get(1).is_some_and(|t| t.is_keyword("UNIQUE"));
+    let on = tokens.iter().position(|t| t.is_keyword("ON"))?;
+    let (items, _) = parenthesized(&tokens[on..])?;
+    let columns = items
+        .iter()
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            // Drop a trailing ASC/DESC and COLLATE clause
+            let mut end = item.len();
+            if item[end - 1].is_keyword("ASC") || item[end - 1].is_keyword("DESC") {
+                end -= 1;
+            }
+            if let Some(collate) = item[..end].iter().position(|t| t.is_keyword("COLLATE")) {
+                end = collate.max(1);
+            }
+            match &item[..end] {
+                [single] => single.text.clone(),
+                expression => sql[expression[0].start..expression[expression.len() - 1].end].to_string(),
+            }
+        })
+        .collect();
+    Some((unique, columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_table() {
+        let definition = parse_create_table(
+            "CREATE TABLE \"sample sites\" (\n  id INTEGER PRIMARY KEY AUTOINCREMENT,\n  [site name] VARCHAR (40) NOT NULL, -- as labelled\n  depth_m DECIMAL(6, 2) DEFAULT 0,\n  notes,\n  `taken` TIMESTAMP CHECK (taken > '2000-01-01'),\n  UNIQUE (\"site name\", taken)\n)",
+        )
+        .unwrap();
+        let described: Vec<(&str, &str, bool, bool)> = definition
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.declared_type.as_str(), c.primary_key, c.not_null))
+            .collect();
+        assert_eq!(
+            described,
+            vec![
+                ("id", "INTEGER", true, false),
+                ("site name", "VARCHAR (40)", false, true),
+                ("depth_m", "DECIMAL(6, 2)", false, false),
+                ("notes", "", false, false),
+                ("taken", "TIMESTAMP", false, false),
+            ]
+        );
+        assert!(!definition.without_rowid);
+
+        let definition =
+            parse_create_table("CREATE TABLE kv(k TEXT, v BLOB, PRIMARY KEY (k)) WITHOUT ROWID").unwrap();
+        assert!(definition.without_rowid);
+        assert_eq!(definition.primary_key, vec!["k"]);
+        assert!(definition.columns[0].primary_key);
+
+        assert_eq!(parse_create_table("CREATE VIRTUAL TABLE docs USING fts5(body)"), None);
+    }
+
+    #[test]
+    fn test_create_index() {
+        assert_eq!(
+            parse_create_index("CREATE UNIQUE INDEX idx_site ON samples (site COLLATE NOCASE, taken DESC)"),
+            Some((true, vec!["site".to_string(), "taken".to_string()]))
+        );
+        assert_eq!(
+            parse_create_index("CREATE INDEX idx_lower ON samples(lower(site)) WHERE site IS NOT NULL"),
+            Some((false, vec!["lower(site)".to_string()]))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Text file analysis
 
 use super::AnalysisError;
+use crate::compression::is_corrupt;
 use crate::types::TextAnalysis;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -20,8 +21,14 @@ use std::path::Path;
 /// A TextAnalysis struct with analysis results.
 pub fn analyze_text(path: &Path) -> Result<TextAnalysis, AnalysisError> {
     let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    analyze_text_from(BufReader::new(file), path)
+}
 
+/// Analyze text read from a file or archive member
+///
+/// `path` is the file's name (or the member's name inside its archive),
+/// used to recognise documentation.
+pub(crate) fn analyze_text_from(reader: impl BufRead, path: &Path) -> Result<TextAnalysis, AnalysisError> {
     let mut analysis = TextAnalysis::new();
     let mut has_markdown_headers = false;
     let mut has_doc_sections = false;
@@ -29,6 +36,8 @@ pub fn analyze_text(path: &Path) -> Result<TextAnalysis, AnalysisError> {
     for (line_num, line_result) in reader.lines().enumerate() {
         let line = match line_result {
             Ok(l) => l,
+            // Damaged compressed data ends the text
+            Err(e) if is_corrupt(&e) => break,
             Err(e) => {
                 // Check for encoding issues
                 analysis.encoding_issues.push(format!(
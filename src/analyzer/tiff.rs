@@ -0,0 +1,695 @@
+//! TIFF and GeoTIFF tag inspection
+//!
+//! Walks the chain of image file directories (IFDs) in a TIFF or BigTIFF
+//! file. Each IFD is a sorted list of tagged entries whose values are
+//! stored inline or at an offset; the tags describe the image layout, and
+//! for GeoTIFF the GeoKey directory and model tiepoints tie pixels to a
+//! coordinate reference system. Pixel data is never read.
+
+use super::AnalysisError;
+use crate::types::{GeoKey, GeoTiffInfo, TiffAnalysis, TiffPage};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Most IFDs followed before the rest of the chain is skipped
+const MAX_PAGES: usize = 100_000;
+
+/// Most entries accepted in one IFD
+const MAX_ENTRIES: u64 = 10_000;
+
+/// Most values read from one tag
+const MAX_VALUES: u64 = 1_000_000;
+
+// Baseline tags
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const PHOTOMETRIC: u16 = 262;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const TILE_WIDTH: u16 = 322;
+const SAMPLE_FORMAT: u16 = 339;
+
+// GeoTIFF tags
+const MODEL_PIXEL_SCALE: u16 = 33550;
+const MODEL_TIEPOINT: u16 = 33922;
+const MODEL_TRANSFORMATION: u16 = 34264;
+const GEO_KEY_DIRECTORY: u16 = 34735;
+const GEO_DOUBLE_PARAMS: u16 = 34736;
+const GEO_ASCII_PARAMS: u16 = 34737;
+
+/// One entry of an IFD
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u64,
+    /// The raw value field (4 bytes in TIFF, 8 in BigTIFF)
+    field: [u8; 8],
+}
+
+/// Byte-order and layout aware access to the file
+struct Reader {
+    file: File,
+    size: u64,
+    little_endian: bool,
+    big_tiff: bool,
+}
+
+impl Reader {
+    fn read_at(&mut self, offset: u64, len: u64) -> Result<Vec<u8>, AnalysisError> {
+        if offset.checked_add(len).is_none_or(|end| end > self.size) {
+            return Err(AnalysisError::InvalidFormat(format!(
+                "{} bytes at offset {} run past the end of the file",
+                len, offset
+            )));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Unsigned integer of 1, 2, 4 or 8 bytes in the file's byte order
+    fn uint(&self, bytes: &[u8]) -> u64 {
+        let fold = |acc: u64, b: &u8| (acc << 8) | *b as u64;
+        if self.little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        }
+    }
+
+    /// Width of an offset field
+    fn offset_size(&self) -> u64 {
+        if self.big_tiff {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Read an IFD, returning its entries and the offset of the next IFD
+    fn read_ifd(&mut self, offset: u64) -> Result<(Vec<Entry>, u64), AnalysisError> {
+        let (count_size, entry_size) = if self.big_tiff { (8, 20) } else { (2, 12) };
+        let count_bytes = self.read_at(offset, count_size)?;
+        let count = self.uint(&count_bytes);
+        if count > MAX_ENTRIES {
+            return Err(AnalysisError::InvalidFormat(format!(
+                "IFD at offset {} claims {} entries",
+                offset, count
+            )));
+        }
+        let table = self.read_at(offset + count_size, count * entry_size + self.offset_size())?;
+
+        let field_size = self.offset_size() as usize;
+        let mut entries = Vec::with_capacity(count as usize);
+        for raw in table.chunks_exact(entry_size as usize) {
+            let count_end = 4 + field_size;
+            let mut field = [0u8; 8];
+            field[..field_size].copy_from_slice(&raw[count_end..count_end + field_size]);
+            entries.push(Entry {
+                tag: self.uint(&raw[0..2]) as u16,
+                kind: self.uint(&raw[2..4]) as u16,
+                count: self.uint(&raw[4..count_end]),
+                field,
+            });
+        }
+        let next = self.uint(&table[table.len() - field_size..]);
+        Ok((entries, next))
+    }
+
+    /// Raw bytes of an entry's value, wherever they are stored
+    fn value_bytes(&mut self, entry: &Entry) -> Result<Vec<u8>, AnalysisError> {
+        let size = type_size(entry.kind).ok_or_else(|| {
+            AnalysisError::InvalidFormat(format!("tag {} has unknown type {}", entry.tag, entry.kind))
+        })?;
+        if entry.count > MAX_VALUES {
+            return Err(AnalysisError::InvalidFormat(format!(
+                "tag {} holds {} values",
+                entry.tag, entry.count
+            )));
+        }
+        let len = entry.count * size;
+        let field_size = self.offset_size();
+        if len <= field_size {
+            Ok(entry.field[..len as usize].to_vec())
+        } else {
+            let offset = self.uint(&entry.field[..field_size as usize]);
+            self.read_at(offset, len)
+        }
+    }
+
+    /// An entry's values as numbers (rationals are divided out)
+    fn numbers(&mut self, entry: &Entry) -> Result<Vec<f64>, AnalysisError> {
+        let bytes = self.value_bytes(entry)?;
+        let size = type_size(entry.kind).unwrap_or(1) as usize;
+        let values = bytes
+            .chunks_exact(size)
+            .map(|b| match entry.kind {
+                // SBYTE, SSHORT, SLONG, SLONG8
+                6 | 8 | 9 | 17 => {
+                    let shift = 64 - 8 * size as u32;
+                    (((self.uint(b) << shift) as i64) >> shift) as f64
+                }
+                // RATIONAL, SRATIONAL
+                5 | 10 => {
+                    let (num, den) = (self.uint(&b[..4]), self.uint(&b[4..]));
+                    let (num, den) = if entry.kind == 10 {
+                        (num as u32 as i32 as f64, den as u32 as i32 as f64)
+                    } else {
+                        (num as f64, den as f64)
+                    };
+                    num / den
+                }
+                // FLOAT, DOUBLE
+                11 => f32::from_bits(self.uint(b) as u32) as f64,
+                12 => f64::from_bits(self.uint(b)),
+                _ => self.uint(b) as f64,
+            })
+            .collect();
+        Ok(values)
+    }
+
+    /// An entry's values as text (ASCII tags), NULs and all
+    fn text(&mut self, entry: &Entry) -> Result<String, AnalysisError> {
+        Ok(String::from_utf8_lossy(&self.value_bytes(entry)?).into_owned())
+    }
+}
+
+/// Size in bytes of one value of a TIFF field type
+fn type_size(kind: u16) -> Option<u64> {
+    match kind {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 | 16 | 17 | 18 => Some(8),
+        _ => None,
+    }
+}
+
+/// Analyze the tags of a TIFF or BigTIFF file
+///
+/// Fails only if the header or the first IFD cannot be read; a damaged
+/// later IFD is recorded in `warnings` and the pages before it are still
+/// listed.
+///
+/// # Arguments
+///
+/// * `path` - Path to a file starting with `II` or `MM`
+///
+/// # Returns
+///
+/// Every page with its dimensions and sample layout, and the GeoTIFF
+/// georeferencing of the first page.
+pub fn analyze_tiff(path: &Path) -> Result<TiffAnalysis, AnalysisError> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut header = [0u8; 16];
+    let header_len = file.read(&mut header)?;
+
+    let little_endian = match &header[..2] {
+        b"II" => true,
+        b"MM" => false,
+        _ => return Err(AnalysisError::InvalidFormat("missing TIFF byte-order mark".to_string())),
+    };
+    let mut reader = Reader {
+        file,
+        size,
+        little_endian,
+        big_tiff: false,
+    };
+    let first_ifd = match reader.uint(&header[2..4]) {
+        42 if header_len >= 8 => reader.uint(&header[4..8]),
+        43 if header_len >= 16 && reader.uint(&header[4..6]) == 8 => {
+            reader.big_tiff = true;
+            reader.uint(&header[8..16])
+        }
+        version => {
+            return Err(AnalysisError::InvalidFormat(format!(
+                "unsupported TIFF version {}",
+                version
+            )))
+        }
+    };
+
+    let mut analysis = TiffAnalysis {
+        big_tiff: reader.big_tiff,
+        pages: Vec::new(),
+        geo: None,
+        warnings: Vec::new(),
+    };
+    let mut visited = HashSet::new();
+    let mut offset = first_ifd;
+
+    while offset != 0 {
+        if analysis.pages.len() == MAX_PAGES {
+            analysis
+                .warnings
+                .push(format!("only the first {} pages were read", MAX_PAGES));
+            break;
+        }
+        if !visited.insert(offset) {
+            analysis
+                .warnings
+                .push(format!("the IFD chain loops back to offset {}", offset));
+            break;
+        }
+
+        let page = reader.read_ifd(offset).and_then(|(entries, next)| {
+            let page = read_page(&mut reader, &entries)?;
+            if analysis.pages.is_empty() {
+                analysis.geo = read_geo(&mut reader, &entries, &mut analysis.warnings);
+            }
+            Ok((page, next))
+        });
+        match page {
+            Ok((page, next)) => {
+                analysis.pages.push(page);
+                offset = next;
+            }
+            Err(e) if analysis.pages.is_empty() => return Err(e),
+            Err(e) => {
+                analysis
+                    .warnings
+                    .push(format!("page {} could not be read: {}", analysis.pages.len() + 1, e));
+                break;
+            }
+        }
+    }
+
+    if analysis.pages.is_empty() {
+        return Err(AnalysisError::InvalidFormat("the file has no IFD".to_string()));
+    }
+    Ok(analysis)
+}
+
+/// Describe one page from its baseline tags
+fn read_page(reader: &mut Reader, entries: &[Entry]) -> Result<TiffPage, AnalysisError> {
+    let mut first = |tag: u16| -> Result<Option<f64>, AnalysisError> {
+        match entries.iter().find(|e| e.tag == tag) {
+            Some(entry) => Ok(reader.numbers(entry)?.first().copied()),
+            None => Ok(None),
+        }
+    };
+
+    let width = first(IMAGE_WIDTH)?;
+    let height = first(IMAGE_LENGTH)?;
+    let (width, height) = match (width, height) {
+        (Some(w), Some(h)) => (w as u64, h as u64),
+        _ => {
+            return Err(AnalysisError::InvalidFormat(
+                "ImageWidth or ImageLength is missing".to_string(),
+            ))
+        }
+    };
+    let compression = first(COMPRESSION)?.map_or(1, |c| c as u16);
+    let photometric = first(PHOTOMETRIC)?.map(|p| p as u16);
+    let samples_per_pixel = first(SAMPLES_PER_PIXEL)?.map_or(1, |s| s as u16);
+    let sample_format = first(SAMPLE_FORMAT)?.map_or(1, |f| f as u16);
+
+    // BitsPerSample has one value per sample and defaults to 1
+    let bits_per_sample = match entries.iter().find(|e| e.tag == BITS_PER_SAMPLE) {
+        Some(entry) => reader.numbers(entry)?.iter().map(|b| *b as u16).collect(),
+        None => vec![1],
+    };
+
+    Ok(TiffPage {
+        width,
+        height,
+        bits_per_sample,
+        samples_per_pixel,
+        sample_format: sample_format_name(sample_format),
+        compression: compression_name(compression),
+        photometric: photometric.map(photometric_name),
+        tiled: entries.iter().any(|e| e.tag == TILE_WIDTH),
+    })
+}
+
+/// Read the GeoTIFF tags of the first page, if any are present
+///
+/// Problems with individual GeoTIFF tags are recorded as warnings so the
+/// page itself is still listed.
+fn read_geo(reader: &mut Reader, entries: &[Entry], warnings: &mut Vec<String>) -> Option<GeoTiffInfo> {
+    let find = |tag: u16| entries.iter().find(|e| e.tag == tag);
+    let geo_tags = [GEO_KEY_DIRECTORY, MODEL_TIEPOINT, MODEL_PIXEL_SCALE, MODEL_TRANSFORMATION];
+    if !geo_tags.iter().any(|tag| find(*tag).is_some()) {
+        return None;
+    }
+
+    let mut numbers = |tag: u16, name: &str, warnings: &mut Vec<String>| match find(tag) {
+        Some(entry) => reader.numbers(entry).unwrap_or_else(|e| {
+            warnings.push(format!("{} could not be read: {}", name, e));
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+    let mut geo = GeoTiffInfo {
+        tiepoints: numbers(MODEL_TIEPOINT, "ModelTiepointTag", warnings),
+        pixel_scale: numbers(MODEL_PIXEL_SCALE, "ModelPixelScaleTag", warnings),
+        has_transformation: find(MODEL_TRANSFORMATION).is_some(),
+        keys: Vec::new(),
+    };
+    let directory = numbers(GEO_KEY_DIRECTORY, "GeoKeyDirectoryTag", warnings);
+    let doubles = numbers(GEO_DOUBLE_PARAMS, "GeoDoubleParamsTag", warnings);
+    let ascii = match find(GEO_ASCII_PARAMS) {
+        Some(entry) => reader.text(entry).unwrap_or_else(|e| {
+            warnings.push(format!("GeoAsciiParamsTag could not be read: {}", e));
+            String::new()
+        }),
+        None => String::new(),
+    };
+
+    if directory.len() >= 4 {
+        let key_count = directory[3] as usize;
+        let keys: Vec<&[f64]> = directory[4..].chunks_exact(4).take(key_count).collect();
+        if keys.len() < key_count {
+            warnings.push(format!(
+                "the GeoKey directory declares {} keys but holds {}",
+                key_count,
+                keys.len()
+            ));
+        }
+        for key in keys {
+            let id = key[0] as u16;
+            let (location, count, value) = (key[1] as u16, key[2] as usize, key[3] as usize);
+            let value = match location {
+                0 => value.to_string(),
+                GEO_DOUBLE_PARAMS => match doubles.get(value..value + count) {
+                    Some(values) => values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "),
+                    None => {
+                        warnings.push(format!("GeoKey {} points past GeoDoubleParamsTag", id));
+                        continue;
+                    }
+                },
+                GEO_ASCII_PARAMS => match ascii.get(value..value + count) {
+                    // Strings in GeoAsciiParams are terminated by '|'
+                    Some(text) => text.trim_end_matches(['|', '\0']).to_string(),
+                    None => {
+                        warnings.push(format!("GeoKey {} points past GeoAsciiParamsTag", id));
+                        continue;
+                    }
+                },
+                other => format!("(stored in tag {})", other),
+            };
+            geo.keys.push(GeoKey {
+                id,
+                name: geo_key_name(id),
+                value,
+            });
+        }
+    } else if find(GEO_KEY_DIRECTORY).is_some() {
+        warnings.push("the GeoKey directory is shorter than its header".to_string());
+    }
+
+    Some(geo)
+}
+
+fn compression_name(code: u16) -> String {
+    match code {
+        1 => "none",
+        2 => "CCITT RLE",
+        3 => "CCITT Group 3",
+        4 => "CCITT Group 4",
+        5 => "LZW",
+        6 | 7 => "JPEG",
+        8 | 32946 => "Deflate",
+        32773 => "PackBits",
+        34712 => "JPEG 2000",
+        34887 => "LERC",
+        34925 => "LZMA",
+        50000 => "Zstandard",
+        50001 => "WebP",
+        _ => return format!("unknown ({})", code),
+    }
+    .to_string()
+}
+
+fn sample_format_name(code: u16) -> String {
+    match code {
+        1 => "unsigned integer",
+        2 => "signed integer",
+        3 => "float",
+        4 => "undefined",
+        5 => "complex integer",
+        6 => "complex float",
+        _ => return format!("unknown ({})", code),
+    }
+    .to_string()
+}
+
+fn photometric_name(code: u16) -> String {
+    match code {
+        0 => "WhiteIsZero",
+        1 => "BlackIsZero",
+        2 => "RGB",
+        3 => "Palette",
+        4 => "TransparencyMask",
+        5 => "CMYK",
+        6 => "YCbCr",
+        8 => "CIELab",
+        _ => return format!("unknown ({})", code),
+    }
+    .to_string()
+}
+
+/// Names of the GeoKeys defined by the GeoTIFF 1.0 specification
+fn geo_key_name(id: u16) -> String {
+    match id {
+        1024 => "GTModelTypeGeoKey",
+        1025 => "GTRasterTypeGeoKey",
+        1026 => "GTCitationGeoKey",
+        2048 => "GeographicTypeGeoKey",
+        2049 => "GeogCitationGeoKey",
+        2050 => "GeogGeodeticDatumGeoKey",
+        2051 => "GeogPrimeMeridianGeoKey",
+        2052 => "GeogLinearUnitsGeoKey",
+        2054 => "GeogAngularUnitsGeoKey",
+        2056 => "GeogEllipsoidGeoKey",
+        2057 => "GeogSemiMajorAxisGeoKey",
+        2058 => "GeogSemiMinorAxisGeoKey",
+        2059 => "GeogInvFlatteningGeoKey",
+        3072 => "ProjectedCSTypeGeoKey",
+        3073 => "PCSCitationGeoKey",
+        3074 => "ProjectionGeoKey",
+        3075 => "ProjCoordTransGeoKey",
+        3076 => "ProjLinearUnitsGeoKey",
+        4096 => "VerticalCSTypeGeoKey",
+        4097 => "VerticalCitationGeoKey",
+        4098 => "VerticalDatumGeoKey",
+        4099 => "VerticalUnitsGeoKey",
+        _ => return format!("GeoKey {}", id),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A tag value for the fixture writer
+    #[derive(Clone)]
+    enum Value {
+        Short(Vec<u16>),
+        Long(Vec<u32>),
+        Double(Vec<f64>),
+        Ascii(&'static str),
+    }
+
+    /// Write a TIFF (or BigTIFF) with one IFD per page, each followed by
+    /// the values that do not fit in their entries
+    fn tiff(little_endian: bool, big_tiff: bool, pages: &[Vec<(u16, Value)>]) -> Vec<u8> {
+        let put = |out: &mut Vec<u8>, value: u64, width: usize| {
+            let bytes = value.to_be_bytes();
+            let mut bytes = bytes[8 - width..].to_vec();
+            if little_endian {
+                bytes.reverse();
+            }
+            out.extend_from_slice(&bytes);
+        };
+        let (count_size, entry_size, field_size) = if big_tiff { (8, 20, 8) } else { (2, 12, 4) };
+
+        let mut out = Vec::new();
+        out.extend_from_slice(if little_endian { b"II" } else { b"MM" });
+        if big_tiff {
+            put(&mut out, 43, 2);
+            put(&mut out, 8, 2);
+            put(&mut out, 0, 2);
+        } else {
+            put(&mut out, 42, 2);
+        }
+        let mut next_pointer = out.len();
+        put(&mut out, 0, field_size);
+
+        for page in pages {
+            let ifd = out.len() as u64;
+            let mut pointer = Vec::new();
+            put(&mut pointer, ifd, field_size);
+            out.splice(next_pointer..next_pointer + field_size, pointer);
+
+            let mut data = Vec::new();
+            let data_start = ifd + (count_size + page.len() * entry_size + field_size) as u64;
+            put(&mut out, page.len() as u64, count_size);
+            for (tag, value) in page {
+                let mut bytes = Vec::new();
+                let (kind, count) = match value {
+                    Value::Short(v) => {
+                        v.iter().for_each(|x| put(&mut bytes, *x as u64, 2));
+                        (3, v.len())
+                    }
+                    Value::Long(v) => {
+                        v.iter().for_each(|x| put(&mut bytes, *x as u64, 4));
+                        (4, v.len())
+                    }
+                    Value::Double(v) => {
+                        v.iter().for_each(|x| put(&mut bytes, x.to_bits(), 8));
+                        (12, v.len())
+                    }
+                    Value::Ascii(s) => {
+                        bytes.extend_from_slice(s.as_bytes());
+                        bytes.push(0);
+                        (2, s.len() + 1)
+                    }
+                };
+                put(&mut out, *tag as u64, 2);
+                put(&mut out, kind, 2);
+                put(&mut out, count as u64, field_size);
+                if bytes.len() <= field_size {
+                    bytes.resize(field_size, 0);
+                    out.extend_from_slice(&bytes);
+                } else {
+                    put(&mut out, data_start + data.len() as u64, field_size);
+                    data.extend_from_slice(&bytes);
+                }
+            }
+            next_pointer = out.len();
+            put(&mut out, 0, field_size);
+            out.extend_from_slice(&data);
+        }
+        out
+    }
+
+    fn write(test: &str, bytes: &[u8]) -> (std::path::PathBuf, std::path::PathBuf) {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_tiff_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("image.tif");
+        fs::write(&path, bytes).unwrap();
+        (temp_dir, path)
+    }
+
+    fn page(width: u32, height: u32) -> Vec<(u16, Value)> {
+        vec![
+            (IMAGE_WIDTH, Value::Long(vec![width])),
+            (IMAGE_LENGTH, Value::Long(vec![height])),
+            (BITS_PER_SAMPLE, Value::Short(vec![16])),
+            (COMPRESSION, Value::Short(vec![5])),
+            (PHOTOMETRIC, Value::Short(vec![1])),
+        ]
+    }
+
+    #[test]
+    fn test_microscopy_stack() {
+        let pages: Vec<_> = (0..3).map(|_| page(512, 256)).collect();
+        let (temp_dir, path) = write("stack", &tiff(true, false, &pages));
+
+        let analysis = analyze_tiff(&path).unwrap();
+        assert!(!analysis.big_tiff);
+        assert_eq!(analysis.pages.len(), 3);
+        let first = &analysis.pages[0];
+        assert_eq!((first.width, first.height), (512, 256));
+        assert_eq!(first.bits_per_sample, vec![16]);
+        assert_eq!(first.samples_per_pixel, 1);
+        assert_eq!(first.sample_format, "unsigned integer");
+        assert_eq!(first.compression, "LZW");
+        assert_eq!(first.photometric.as_deref(), Some("BlackIsZero"));
+        assert!(!first.tiled);
+        assert!(analysis.geo.is_none());
+        assert!(analysis.warnings.is_empty());
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_geotiff_keys() {
+        let mut first = vec![
+            (IMAGE_WIDTH, Value::Short(vec![100])),
+            (IMAGE_LENGTH, Value::Short(vec![50])),
+            (BITS_PER_SAMPLE, Value::Short(vec![32])),
+            (SAMPLES_PER_PIXEL, Value::Short(vec![1])),
+            (TILE_WIDTH, Value::Short(vec![256])),
+            (SAMPLE_FORMAT, Value::Short(vec![3])),
+            (MODEL_PIXEL_SCALE, Value::Double(vec![0.5, 0.5, 0.0])),
+            (MODEL_TIEPOINT, Value::Double(vec![0.0, 0.0, 0.0, 440720.0, 3751320.0, 0.0])),
+        ];
+        #[rustfmt::skip]
+        let directory = vec![
+            1, 1, 0, 4,
+            1024, 0, 1, 1,
+            1026, GEO_ASCII_PARAMS, 17, 0,
+            2057, GEO_DOUBLE_PARAMS, 1, 0,
+            3072, 0, 1, 32611,
+        ];
+        first.push((GEO_KEY_DIRECTORY, Value::Short(directory)));
+        first.push((GEO_DOUBLE_PARAMS, Value::Double(vec![6378137.0])));
+        first.push((GEO_ASCII_PARAMS, Value::Ascii("UTM Zone 11 North|")));
+
+        for (little_endian, big_tiff) in [(true, false), (false, true)] {
+            let bytes = tiff(little_endian, big_tiff, &[first.clone()]);
+            let (temp_dir, path) = write("geo", &bytes);
+
+            let analysis = analyze_tiff(&path).unwrap();
+            assert_eq!(analysis.big_tiff, big_tiff);
+            let page = &analysis.pages[0];
+            assert_eq!((page.width, page.height), (100, 50));
+            assert_eq!(page.sample_format, "float");
+            assert_eq!(page.compression, "none");
+            assert!(page.tiled);
+
+            let geo = analysis.geo.unwrap();
+            assert_eq!(geo.pixel_scale, vec![0.5, 0.5, 0.0]);
+            assert_eq!(geo.tiepoints[3], 440720.0);
+            assert!(geo.is_georeferenced());
+            assert_eq!(geo.crs().as_deref(), Some("EPSG:32611"));
+            let keys: Vec<(&str, &str)> = geo.keys.iter().map(|k| (k.name.as_str(), k.value.as_str())).collect();
+            assert_eq!(
+                keys,
+                vec![
+                    ("GTModelTypeGeoKey", "1"),
+                    ("GTCitationGeoKey", "UTM Zone 11 North"),
+                    ("GeogSemiMajorAxisGeoKey", "6378137"),
+                    ("ProjectedCSTypeGeoKey", "32611"),
+                ]
+            );
+            assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+
+            fs::remove_dir_all(temp_dir).ok();
+        }
+    }
+
+    #[test]
+    fn test_damaged_chain() {
+        // A second page whose IFD points back at the first
+        let mut bytes = tiff(true, false, &[page(8, 8), page(8, 8)]);
+        let len = bytes.len();
+        let second = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize + 2 + 5 * 12;
+        let second_ifd = u32::from_le_bytes(bytes[second..second + 4].try_into().unwrap()) as usize;
+        let last = second_ifd + 2 + 5 * 12;
+        bytes[last..last + 4].copy_from_slice(&8u32.to_le_bytes());
+        let (temp_dir, path) = write("loop", &bytes);
+        let analysis = analyze_tiff(&path).unwrap();
+        assert_eq!(analysis.pages.len(), 2);
+        assert_eq!(analysis.warnings, vec!["the IFD chain loops back to offset 8"]);
+
+        // A truncated second page is reported; a truncated first page fails
+        fs::write(&path, &bytes[..len - 20]).unwrap();
+        let analysis = analyze_tiff(&path).unwrap();
+        assert_eq!(analysis.pages.len(), 1);
+        assert!(analysis.warnings[0].starts_with("page 2 could not be read"));
+        fs::write(&path, &bytes[..20]).unwrap();
+        assert!(analyze_tiff(&path).is_err());
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+}
@@ -0,0 +1,774 @@
+//! TOML parsing
+//!
+//! A dependency-free parser for TOML 1.0: key/value pairs with bare, quoted
+//! and dotted keys, `[table]` and `[[array of tables]]` headers, all four
+//! string forms, integers (decimal, hex, octal, binary), floats, booleans,
+//! arrays and inline tables. Dates and times are kept as strings. The
+//! document is returned as a `JsonValue` tree, so TOML and JSON metadata
+//! can be inspected the same way.
+
+use super::JsonValue;
+use crate::types::ConfigSyntaxError;
+use std::collections::HashSet;
+
+/// Deepest nesting of arrays and inline tables accepted (bounds recursion
+/// on hostile input)
+const MAX_DEPTH: usize = 128;
+
+/// Parse a TOML document
+///
+/// # Arguments
+///
+/// * `input` - The TOML text
+///
+/// # Returns
+///
+/// The document as an object, or the first syntax error with its line.
+pub fn parse_toml(input: &str) -> Result<JsonValue, ConfigSyntaxError> {
+    let mut parser = TomlParser {
+        chars: input.trim_start_matches('\u{feff}').chars().collect(),
+        pos: 0,
+        line: 1,
+        root: Vec::new(),
+        current: Vec::new(),
+        defined: HashSet::new(),
+        array_tables: HashSet::new(),
+        frozen: HashSet::new(),
+        depth: 0,
+    };
+    match parser.parse() {
+        Ok(()) => Ok(JsonValue::Object(parser.root)),
+        Err(message) => Err(ConfigSyntaxError {
+            message,
+            line: parser.line,
+        }),
+    }
+}
+
+type Members = Vec<(String, JsonValue)>;
+
+struct TomlParser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Line of `pos` (1-based)
+    line: usize,
+    root: Members,
+    /// Header path of the table key/value pairs go into
+    current: Vec<String>,
+    /// Tables defined by a header, by resolved path
+    defined: HashSet<Vec<String>>,
+    /// Arrays created by `[[header]]`, by resolved path
+    array_tables: HashSet<Vec<String>>,
+    /// Inline tables and arrays, which cannot be extended, by resolved path
+    frozen: HashSet<Vec<String>>,
+    /// Arrays and inline tables open around the value being parsed
+    depth: usize,
+}
+
+impl TomlParser {
+    fn parse(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_blank_lines();
+            match self.peek() {
+                None => return Ok(()),
+                Some('[') => self.parse_header()?,
+                Some(_) => {
+                    let keys = self.parse_key()?;
+                    self.skip_spaces();
+                    if self.peek() != Some('=') {
+                        return Err(format!("Expected '=' after key '{}'", keys.join(".")));
+                    }
+                    self.pos += 1;
+                    self.skip_spaces();
+                    let mut path = self.current.clone();
+                    path.extend(keys.iter().cloned());
+                    let value = self.parse_value(Some(&path))?;
+                    self.insert(&keys, value)?;
+                }
+            }
+            self.expect_line_end()?;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(' ' | '\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    /// Skip whitespace, comments and line breaks
+    fn skip_blank_lines(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match (self.peek(), self.peek_at(1)) {
+                (Some('\n'), _) | (Some('\r'), Some('\n')) => {
+                    while self.advance() != Some('\n') {}
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Only a comment may follow a key/value pair or header on its line
+    fn expect_line_end(&mut self) -> Result<(), String> {
+        self.skip_spaces();
+        self.skip_comment();
+        match (self.peek(), self.peek_at(1)) {
+            (None, _) | (Some('\n'), _) | (Some('\r'), Some('\n')) => Ok(()),
+            (Some(c), _) => Err(format!("Expected end of line, found '{}'", c)),
+        }
+    }
+
+    /// A `[table]` or `[[array of tables]]` header
+    fn parse_header(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        let array = self.peek() == Some('[');
+        if array {
+            self.pos += 1;
+        }
+        self.skip_spaces();
+        let keys = self.parse_key()?;
+        self.skip_spaces();
+        let close = if array { "]]" } else { "]" };
+        for expected in close.chars() {
+            if self.peek() != Some(expected) {
+                return Err(format!("Expected '{}' to close the table header", close));
+            }
+            self.pos += 1;
+        }
+
+        let (parent, last) = keys.split_at(keys.len() - 1);
+        let last = &last[0];
+        let (table, mut resolved) = Self::resolve_in(&mut self.root, &self.frozen, &self.array_tables, parent)?;
+        resolved.push(last.clone());
+        let existing = table.iter().position(|(key, _)| key == last);
+        let name = keys.join(".");
+
+        if array {
+            match existing {
+                None => {
+                    table.push((last.clone(), JsonValue::Array(vec![JsonValue::Object(Vec::new())])));
+                    self.array_tables.insert(resolved);
+                }
+                Some(idx) if self.array_tables.contains(&resolved) => {
+                    if let JsonValue::Array(items) = &mut table[idx].1 {
+                        items.push(JsonValue::Object(Vec::new()));
+                    }
+                }
+                Some(_) => return Err(format!("Cannot define [[{}]]: the key is already defined", name)),
+            }
+        } else {
+            match existing {
+                None => table.push((last.clone(), JsonValue::Object(Vec::new()))),
+                Some(idx) => {
+                    let implicit_table = matches!(table[idx].1, JsonValue::Object(_))
+                        && !self.defined.contains(&resolved)
+                        && !self.frozen.contains(&resolved);
+                    if !implicit_table {
+                        return Err(format!("Table [{}] is defined more than once", name));
+                    }
+                }
+            }
+            self.defined.insert(resolved);
+        }
+        self.current = keys;
+        Ok(())
+    }
+
+    /// Insert a key/value pair into the current table
+    fn insert(&mut self, keys: &[String], value: JsonValue) -> Result<(), String> {
+        let mut path = self.current.clone();
+        path.extend(keys.iter().take(keys.len() - 1).cloned());
+        let last = &keys[keys.len() - 1];
+        let (table, _) = self.resolve(&path)?;
+        if table.iter().any(|(key, _)| key == last) {
+            return Err(format!("Duplicate key '{}'", keys.join(".")));
+        }
+        table.push((last.clone(), value));
+        Ok(())
+    }
+
+    /// Find (creating as needed) the table at a path, stepping into the
+    /// last element of arrays of tables; also returns the resolved path
+    fn resolve(&mut self, path: &[String]) -> Result<(&mut Members, Vec<String>), String> {
+        Self::resolve_in(&mut self.root, &self.frozen, &self.array_tables, path)
+    }
+
+    fn resolve_in<'t>(
+        root: &'t mut Members,
+        frozen: &HashSet<Vec<String>>,
+        array_tables: &HashSet<Vec<String>>,
+        path: &[String],
+    ) -> Result<(&'t mut Members, Vec<String>), String> {
+        let mut table = root;
+        let mut resolved = Vec::new();
+        for key in path {
+            resolved.push(key.clone());
+            let idx = match table.iter().position(|(name, _)| name == key) {
+                Some(idx) => idx,
+                None => {
+                    table.push((key.clone(), JsonValue::Object(Vec::new())));
+                    table.len() - 1
+                }
+            };
+            if frozen.contains(&resolved) {
+                return Err(format!("Cannot add to '{}': inline tables and arrays are complete as written", key));
+            }
+            let is_array_table = array_tables.contains(&resolved);
+            table = match &mut table[idx].1 {
+                JsonValue::Object(members) => members,
+                JsonValue::Array(items) if is_array_table => {
+                    resolved.push((items.len() - 1).to_string());
+                    match items.last_mut() {
+                        Some(JsonValue::Object(members)) => members,
+                        _ => return Err(format!("'{}' is not a table", key)),
+                    }
+                }
+                _ => return Err(format!("Key '{}' is already defined as a value, not a table", key)),
+            };
+        }
+        Ok((table, resolved))
+    }
+
+    /// A bare, quoted or dotted key
+    fn parse_key(&mut self) -> Result<Vec<String>, String> {
+        let mut keys = Vec::new();
+        loop {
+            self.skip_spaces();
+            let key = match self.peek() {
+                Some('"') => match self.parse_basic_string()? {
+                    JsonValue::String(s) => s,
+                    _ => unreachable!(),
+                },
+                Some('\'') => self.parse_literal_string()?,
+                Some(c) if is_bare_key_char(c) => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(is_bare_key_char) {
+                        self.pos += 1;
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+                Some(c) => return Err(format!("Invalid character '{}' in key", c)),
+                None => return Err("Expected a key".to_string()),
+            };
+            keys.push(key);
+            self.skip_spaces();
+            if self.peek() != Some('.') {
+                return Ok(keys);
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// A value; the key path of a top-level value locates it, so inline
+    /// tables and arrays can be marked complete
+    fn parse_value(&mut self, location: Option<&[String]>) -> Result<JsonValue, String> {
+        match self.peek() {
+            Some('"') if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') => {
+                self.parse_multiline_basic_string()
+            }
+            Some('"') => self.parse_basic_string(),
+            Some('\'') if self.peek_at(1) == Some('\'') && self.peek_at(2) == Some('\'') => {
+                self.parse_multiline_literal_string()
+            }
+            Some('\'') => self.parse_literal_string().map(JsonValue::String),
+            Some(c @ ('[' | '{')) => {
+                self.freeze(location);
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err("Nesting is too deep".to_string());
+                }
+                let value = if c == '[' { self.parse_array() } else { self.parse_inline_table() };
+                self.depth -= 1;
+                value
+            }
+            Some(_) => self.parse_scalar(),
+            None => Err("Expected a value".to_string()),
+        }
+    }
+
+    fn freeze(&mut self, location: Option<&[String]>) {
+        let (key, parent) = match location.and_then(|path| path.split_last()) {
+            Some(split) => split,
+            None => return,
+        };
+        // Resolve through arrays of tables, as `resolve` would
+        if let Ok((_, mut resolved)) = self.resolve(parent) {
+            resolved.push(key.clone());
+            self.frozen.insert(resolved);
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank_lines();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(JsonValue::Array(items));
+            }
+            // Nested values cannot be reached by headers, so need no location
+            items.push(self.parse_value(None)?);
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                Some(c) => return Err(format!("Expected ',' or ']' in array, found '{}'", c)),
+                None => return Err("Unterminated array".to_string()),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut members: Members = Vec::new();
+        self.skip_spaces();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            let keys = self.parse_key()?;
+            self.skip_spaces();
+            if self.peek() != Some('=') {
+                return Err(format!("Expected '=' after key '{}'", keys.join(".")));
+            }
+            self.pos += 1;
+            self.skip_spaces();
+            let value = self.parse_value(None)?;
+
+            // Dotted keys build nested tables within the inline table
+            let mut table = &mut members;
+            for key in &keys[..keys.len() - 1] {
+                let idx = match table.iter().position(|(name, _)| name == key) {
+                    Some(idx) => idx,
+                    None => {
+                        table.push((key.clone(), JsonValue::Object(Vec::new())));
+                        table.len() - 1
+                    }
+                };
+                table = match &mut table[idx].1 {
+                    JsonValue::Object(nested) => nested,
+                    _ => return Err(format!("Key '{}' is already defined as a value, not a table", key)),
+                };
+            }
+            let last = &keys[keys.len() - 1];
+            if table.iter().any(|(name, _)| name == last) {
+                return Err(format!("Duplicate key '{}'", keys.join(".")));
+            }
+            table.push((last.clone(), value));
+
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => {
+                    self.pos += 1;
+                    self.skip_spaces();
+                    if self.peek() == Some('}') {
+                        return Err("Trailing comma in inline table".to_string());
+                    }
+                }
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                Some('\n') | Some('\r') => return Err("Inline tables must be on a single line".to_string()),
+                Some(c) => return Err(format!("Expected ',' or '}}' in inline table, found '{}'", c)),
+                None => return Err("Unterminated inline table".to_string()),
+            }
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<JsonValue, String> {
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err("Unterminated string".to_string()),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::String(value));
+                }
+                Some('\\') => value.push(self.parse_escape()?),
+                Some(c) if is_control(c) => return Err(format!("Control character U+{:04X} in string", c as u32)),
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_multiline_basic_string(&mut self) -> Result<JsonValue, String> {
+        self.pos += 3;
+        self.skip_first_newline();
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err("Unterminated multi-line string".to_string()),
+                Some('"') if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') => {
+                    self.pos += 3;
+                    // Up to two quotes may end the content
+                    for _ in 0..2 {
+                        if self.peek() == Some('"') {
+                            value.push('"');
+                            self.pos += 1;
+                        }
+                    }
+                    return Ok(JsonValue::String(value));
+                }
+                Some('\\') if self.line_ending_backslash() => {
+                    self.pos += 1;
+                    while let Some(' ' | '\t' | '\r' | '\n') = self.peek() {
+                        self.advance();
+                    }
+                }
+                Some('\\') => value.push(self.parse_escape()?),
+                Some(c) if is_control(c) && c != '\n' && c != '\r' => {
+                    return Err(format!("Control character U+{:04X} in string", c as u32))
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// Whether the backslash at `pos` ends its line (only whitespace after it)
+    fn line_ending_backslash(&self) -> bool {
+        let rest = self.chars[self.pos + 1..].iter();
+        for &c in rest {
+            match c {
+                ' ' | '\t' | '\r' => continue,
+                '\n' => return true,
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err("Unterminated string".to_string()),
+                Some('\'') => {
+                    let value = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some(c) if is_control(c) => return Err(format!("Control character U+{:04X} in string", c as u32)),
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    fn parse_multiline_literal_string(&mut self) -> Result<JsonValue, String> {
+        self.pos += 3;
+        self.skip_first_newline();
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err("Unterminated multi-line string".to_string()),
+                Some('\'') if self.peek_at(1) == Some('\'') && self.peek_at(2) == Some('\'') => {
+                    self.pos += 3;
+                    for _ in 0..2 {
+                        if self.peek() == Some('\'') {
+                            value.push('\'');
+                            self.pos += 1;
+                        }
+                    }
+                    return Ok(JsonValue::String(value));
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// A line break right after the opening delimiter is not part of the string
+    fn skip_first_newline(&mut self) {
+        if self.peek() == Some('\r') && self.peek_at(1) == Some('\n') {
+            self.pos += 1;
+        }
+        if self.peek() == Some('\n') {
+            self.advance();
+        }
+    }
+
+    /// An escape sequence, with `pos` on the backslash
+    fn parse_escape(&mut self) -> Result<char, String> {
+        self.pos += 1;
+        let c = self.peek().ok_or("Unterminated string")?;
+        self.pos += 1;
+        let digits = match c {
+            'b' => return Ok('\u{8}'),
+            't' => return Ok('\t'),
+            'n' => return Ok('\n'),
+            'f' => return Ok('\u{c}'),
+            'r' => return Ok('\r'),
+            '"' => return Ok('"'),
+            '\\' => return Ok('\\'),
+            'u' => 4,
+            'U' => 8,
+            other => return Err(format!("Invalid escape '\\{}' in string", other)),
+        };
+        if self.pos + digits > self.chars.len() {
+            return Err("Invalid unicode escape".to_string());
+        }
+        let hex: String = self.chars[self.pos..self.pos + digits].iter().collect();
+        self.pos += digits;
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("Invalid unicode escape '\\{}{}'", c, hex))
+    }
+
+    /// A boolean, number, or date/time
+    fn parse_scalar(&mut self) -> Result<JsonValue, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '.' | ':'))
+        {
+            self.pos += 1;
+        }
+        // A date and a time may be separated by a space
+        if is_date(&self.chars[start..self.pos])
+            && self.peek() == Some(' ')
+            && self.peek_at(1).is_some_and(|c| c.is_ascii_digit())
+            && self.peek_at(3) == Some(':')
+        {
+            self.pos += 1;
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | ':'))
+            {
+                self.pos += 1;
+            }
+        }
+        let token: String = self.chars[start..self.pos].iter().collect();
+
+        match token.as_str() {
+            "" => {
+                let c = self.peek().unwrap_or(' ');
+                return Err(format!("Expected a value, found '{}'", c));
+            }
+            "true" => return Ok(JsonValue::Boolean(true)),
+            "false" => return Ok(JsonValue::Boolean(false)),
+            "inf" | "+inf" => return Ok(JsonValue::Number(f64::INFINITY)),
+            "-inf" => return Ok(JsonValue::Number(f64::NEG_INFINITY)),
+            "nan" | "+nan" | "-nan" => return Ok(JsonValue::Number(f64::NAN)),
+            _ => {}
+        }
+        if is_date_time(&token) {
+            return Ok(JsonValue::String(token));
+        }
+        parse_number(&token).map(JsonValue::Number).ok_or_else(|| {
+            let word = token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if word && token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                format!("Invalid value '{}' (strings must be quoted)", token)
+            } else {
+                format!("Invalid value '{}'", token)
+            }
+        })
+    }
+}
+
+fn is_bare_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Control characters other than tab, which strings may not contain raw
+fn is_control(c: char) -> bool {
+    (c < ' ' && c != '\t') || c == '\u{7f}'
+}
+
+/// `YYYY-MM-DD`
+fn is_date(chars: &[char]) -> bool {
+    chars.len() == 10
+        && chars
+            .iter()
+            .enumerate()
+            .all(|(i, c)| if i == 4 || i == 7 { *c == '-' } else { c.is_ascii_digit() })
+}
+
+/// A date, time, or date-time (the parts are checked for shape only)
+fn is_date_time(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().collect();
+    let is_time = |chars: &[char]| {
+        chars.len() >= 8
+            && chars[2] == ':'
+            && chars[5] == ':'
+            && chars[..8].iter().enumerate().all(|(i, c)| i == 2 || i == 5 || c.is_ascii_digit())
+    };
+    if chars.len() >= 10 && is_date(&chars[..10]) {
+        return chars.len() == 10 || (matches!(chars[10], 'T' | 't' | ' ') && is_time(&chars[11..]));
+    }
+    is_time(&chars)
+}
+
+/// A TOML integer or float
+fn parse_number(token: &str) -> Option<f64> {
+    let (sign, digits) = match token.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, token.strip_prefix('+').unwrap_or(token)),
+    };
+
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(rest) = digits.strip_prefix(prefix) {
+            // Signs are not allowed on prefixed integers
+            if digits.len() != token.len() || !valid_underscores(rest) {
+                return None;
+            }
+            return i64::from_str_radix(&rest.replace('_', ""), radix).ok().map(|n| n as f64);
+        }
+    }
+
+    let integer_part = digits.split(['.', 'e', 'E']).next().unwrap_or("");
+    if integer_part.is_empty()
+        || !integer_part.starts_with(|c: char| c.is_ascii_digit())
+        || (integer_part.len() > 1 && integer_part.starts_with('0'))
+    {
+        return None;
+    }
+    // Every underscore sits between two digits
+    let parts_ok = digits
+        .split(['.', 'e', 'E', '+', '-'])
+        .all(|part| !part.is_empty() && valid_underscores(part));
+    if !parts_ok || digits.ends_with(['e', 'E']) {
+        return None;
+    }
+    digits.replace('_', "").parse::<f64>().ok().map(|n| sign * n)
+}
+
+/// Digits with single underscores between them
+fn valid_underscores(digits: &str) -> bool {
+    !digits.is_empty()
+        && !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+        && digits.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: &str) -> ConfigSyntaxError {
+        parse_toml(input).expect_err("input should be rejected")
+    }
+
+    #[test]
+    fn test_parse_document() {
+        let doc = parse_toml(
+            r#"# Instrument configuration
+title = "Beamline 7 \"run\""
+version = 1_024
+gain = -3.5e2
+enabled = true
+started = 1979-05-27 07:32:00Z
+tags = ["a", 'b',
+  """c""",  # trailing comma allowed
+]
+owner.name = 'Ada'
+
+[detector]
+pixels = { x = 2048, y = 2048 }
+mode = 0x1F
+
+[[run]]
+id = 1
+
+[[run]]
+id = 2
+notes = """
+first line \
+  continued"""
+"#,
+        )
+        .unwrap();
+
+        let keys: Vec<&str> = doc.as_object().unwrap().iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["title", "version", "gain", "enabled", "started", "tags", "owner", "detector", "run"]);
+        assert_eq!(doc.get("title").and_then(JsonValue::as_str), Some("Beamline 7 \"run\""));
+        assert_eq!(doc.get("version").and_then(JsonValue::as_f64), Some(1024.0));
+        assert_eq!(doc.get("gain").and_then(JsonValue::as_f64), Some(-350.0));
+        assert_eq!(doc.get("started").and_then(JsonValue::as_str), Some("1979-05-27 07:32:00Z"));
+        assert_eq!(doc.get("tags").and_then(JsonValue::as_array).map(|a| a.len()), Some(3));
+        assert_eq!(doc.get("owner").and_then(|o| o.get("name")).and_then(JsonValue::as_str), Some("Ada"));
+
+        let detector = doc.get("detector").unwrap();
+        assert_eq!(detector.get("mode").and_then(JsonValue::as_f64), Some(31.0));
+        assert_eq!(detector.get("pixels").and_then(|p| p.get("y")).and_then(JsonValue::as_f64), Some(2048.0));
+
+        let runs = doc.get("run").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].get("notes").and_then(JsonValue::as_str), Some("first line continued"));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let e = error("a = 1\nb = 2\na = 3\n");
+        assert_eq!((e.line, e.message.as_str()), (3, "Duplicate key 'a'"));
+
+        let e = error("[server]\nport = 80\n\n[server]\n");
+        assert_eq!((e.line, e.message.as_str()), (4, "Table [server] is defined more than once"));
+
+        let e = error("name = unquoted\n");
+        assert_eq!(e.message, "Invalid value 'unquoted' (strings must be quoted)");
+
+        let e = error("x = \"open\ny = 1\n");
+        assert_eq!((e.line, e.message.as_str()), (1, "Unterminated string"));
+
+        assert_eq!(error("a = 1 b = 2").message, "Expected end of line, found 'b'");
+        assert_eq!(error("key value").message, "Expected '=' after key 'key'");
+        assert_eq!(error("n = 007").message, "Invalid value '007'");
+        assert_eq!(error("p = { x = 1, }").message, "Trailing comma in inline table");
+        assert_eq!(
+            error("p = { x = 1 }\n[p.q]\n").message,
+            "Cannot add to 'p': inline tables and arrays are complete as written"
+        );
+        assert_eq!(
+            error("a = 1\n[a.b]\n").message,
+            "Key 'a' is already defined as a value, not a table"
+        );
+
+        let e = error(&format!("title = \"deep\"\na = {}", "[".repeat(50_000)));
+        assert_eq!((e.line, e.message.as_str()), (2, "Nesting is too deep"));
+        assert!(parse_toml(&format!("a = {}1{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH))).is_ok());
+    }
+}
@@ -0,0 +1,440 @@
+//! VCF analysis
+//!
+//! A VCF file opens with `##` meta-information lines (the first one
+//! `##fileformat`), then a `#CHROM` header naming the eight fixed columns,
+//! `FORMAT` and one column per sample, then one tab-separated line per
+//! variant. The file is streamed (gzipped and bgzipped files through the
+//! decompressor), checking each line against the header and tracking
+//! contigs and sort order on the way.
+
+use super::sequence::{trim_line_end, InvalidRecords, Residues};
+use super::AnalysisError;
+use crate::compression::{is_corrupt, DataReader};
+use crate::types::{VcfAnalysis, VcfContig};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Contigs tracked; records on further contigs are still counted
+const MAX_CONTIGS: usize = 10_000;
+
+/// The fixed columns of the `#CHROM` header line
+const FIXED_COLUMNS: [&str; 8] = ["CHROM", "POS", "ID", "REF", "ALT", "QUAL", "FILTER", "INFO"];
+
+/// Analyze a VCF file
+///
+/// # Arguments
+///
+/// * `path` - Path to the file (optionally gzip- or bgzip-compressed)
+///
+/// # Returns
+///
+/// A VcfAnalysis with the header fields, samples, contigs, variant counts,
+/// sort order and malformed lines.
+pub fn analyze_vcf(path: &Path) -> Result<VcfAnalysis, AnalysisError> {
+    analyze_vcf_from(DataReader::open(path)?)
+}
+
+/// Analyze VCF read from a file or archive member
+pub(crate) fn analyze_vcf_from(data: DataReader) -> Result<VcfAnalysis, AnalysisError> {
+    let mut reader = BufReader::new(data);
+    let mut scan = Scan::default();
+
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            // A damaged .gz is still analyzed up to the damage
+            Err(e) if is_corrupt(&e) => break,
+            Err(e) => return Err(e.into()),
+        }
+        line_number += 1;
+        let text = String::from_utf8_lossy(trim_line_end(&line));
+        scan.line(&text, line_number);
+    }
+
+    let mut analysis = scan.finish(line_number);
+    analysis.gzip = reader.get_ref().gzip_info();
+    Ok(analysis)
+}
+
+/// State of a scan through a VCF file
+struct Scan {
+    file_format: Option<String>,
+    /// Number of columns named by the `#CHROM` line, once it is read
+    columns: Option<usize>,
+    samples: Vec<String>,
+    variant_count: usize,
+    contigs: Vec<VcfContig>,
+    contig_index: HashMap<String, usize>,
+    info_fields: Vec<String>,
+    format_fields: Vec<String>,
+    /// Contig of the previous record and its position
+    current: Option<(String, u64)>,
+    /// Contigs whose records have ended
+    finished: HashSet<String>,
+    is_sorted: bool,
+    quality_sum: f64,
+    quality_count: u64,
+    alleles: Residues,
+    invalid: InvalidRecords,
+    orphans_reported: bool,
+}
+
+impl Default for Scan {
+    fn default() -> Self {
+        Scan {
+            file_format: None,
+            columns: None,
+            samples: Vec::new(),
+            variant_count: 0,
+            contigs: Vec::new(),
+            contig_index: HashMap::new(),
+            info_fields: Vec::new(),
+            format_fields: Vec::new(),
+            current: None,
+            finished: HashSet::new(),
+            is_sorted: true,
+            quality_sum: 0.0,
+            quality_count: 0,
+            alleles: Residues::new(),
+            invalid: InvalidRecords::default(),
+            orphans_reported: false,
+        }
+    }
+}
+
+impl Scan {
+    fn line(&mut self, text: &str, line: usize) {
+        if line == 1 {
+            match text.strip_prefix("##fileformat=") {
+                Some(version) => {
+                    self.file_format = Some(version.trim().to_string());
+                    return;
+                }
+                None => self.invalid.push(1, "The first line is not a ##fileformat line"),
+            }
+        }
+
+        if let Some(meta) = text.strip_prefix("##") {
+            if self.columns.is_some() {
+                self.invalid.push(line, "Meta-information line after the #CHROM header line");
+            } else {
+                self.meta(meta);
+            }
+        } else if let Some(header) = text.strip_prefix('#') {
+            self.header(header, line);
+        } else if text.is_empty() {
+            // Trailing blank lines are common and harmless
+        } else if let Some(columns) = self.columns {
+            self.record(text, columns, line);
+        } else if !self.orphans_reported {
+            self.orphans_reported = true;
+            self.invalid.push(line, "Variant record before the #CHROM header line");
+        }
+    }
+
+    /// A `##key=value` line
+    fn meta(&mut self, meta: &str) {
+        let Some((key, value)) = meta.split_once('=') else {
+            return;
+        };
+        let Some(id) = structured_field(value, "ID") else {
+            return;
+        };
+        match key {
+            "contig" => {
+                let length = structured_field(value, "length").and_then(|l| l.parse().ok());
+                if let Some(&idx) = self.contig_index.get(id) {
+                    self.contigs[idx].length = length;
+                    self.contigs[idx].declared = true;
+                } else if self.contigs.len() < MAX_CONTIGS {
+                    self.contig_index.insert(id.to_string(), self.contigs.len());
+                    self.contigs.push(VcfContig {
+                        name: id.to_string(),
+                        length,
+                        declared: true,
+                        variant_count: 0,
+                    });
+                }
+            }
+            "INFO" => self.info_fields.push(id.to_string()),
+            "FORMAT" => self.format_fields.push(id.to_string()),
+            _ => {}
+        }
+    }
+
+    /// The `#CHROM` header line (without its `#`)
+    fn header(&mut self, header: &str, line: usize) {
+        if self.columns.is_some() {
+            self.invalid.push(line, "Second #CHROM header line");
+            return;
+        }
+        let names: Vec<&str> = header.split('\t').collect();
+        if names.len() < FIXED_COLUMNS.len() || names[..FIXED_COLUMNS.len()] != FIXED_COLUMNS {
+            self.invalid.push(
+                line,
+                format!(
+                    "The header line must start with the tab-separated columns #{}",
+                    FIXED_COLUMNS.join(" ")
+                ),
+            );
+        } else if names.len() > FIXED_COLUMNS.len() && names[FIXED_COLUMNS.len()] != "FORMAT" {
+            self.invalid.push(line, "The ninth header column must be FORMAT when samples follow");
+        }
+        self.samples = names.iter().skip(FIXED_COLUMNS.len() + 1).map(|s| s.to_string()).collect();
+        self.columns = Some(names.len().max(FIXED_COLUMNS.len()));
+    }
+
+    /// A variant record
+    fn record(&mut self, text: &str, columns: usize, line: usize) {
+        let fields: Vec<&str> = text.split('\t').collect();
+        if fields.len() != columns {
+            self.invalid.push(
+                line,
+                format!("Record has {} columns but the header has {}", fields.len(), columns),
+            );
+            if fields.len() < FIXED_COLUMNS.len() {
+                return;
+            }
+        }
+        self.variant_count += 1;
+
+        let chrom = fields[0];
+        let contig = match self.contig_index.get(chrom) {
+            Some(&idx) => Some(idx),
+            None if self.contigs.len() < MAX_CONTIGS => {
+                self.contig_index.insert(chrom.to_string(), self.contigs.len());
+                self.contigs.push(VcfContig {
+                    name: chrom.to_string(),
+                    length: None,
+                    declared: false,
+                    variant_count: 0,
+                });
+                Some(self.contigs.len() - 1)
+            }
+            None => None,
+        };
+        if let Some(idx) = contig {
+            self.contigs[idx].variant_count += 1;
+        }
+
+        match fields[1].parse::<u64>() {
+            Ok(pos) if pos > 0 => {
+                let length = contig.and_then(|idx| self.contigs[idx].length);
+                if length.is_some_and(|length| pos > length) {
+                    self.invalid.push(
+                        line,
+                        format!("POS {} is past the end of {} ({} bp)", pos, chrom, length.unwrap_or(0)),
+                    );
+                }
+                self.track_order(chrom, pos);
+            }
+            _ => self.invalid.push(line, format!("POS '{}' is not a positive integer", fields[1])),
+        }
+
+        self.alleles.add(fields[3].as_bytes(), line);
+        for alt in fields[4].split(',') {
+            // Symbolic alleles, breakends, deletions spanning this site and
+            // missing values are not sequence
+            let symbolic = alt.starts_with('<') || alt.contains('[') || alt.contains(']');
+            if !symbolic && alt != "*" && alt != "." {
+                self.alleles.add(alt.as_bytes(), line);
+            }
+        }
+
+        if fields[5] != "." {
+            match fields[5].parse::<f64>() {
+                Ok(quality) if quality.is_finite() => {
+                    self.quality_sum += quality;
+                    self.quality_count += 1;
+                }
+                _ => self.invalid.push(line, format!("QUAL '{}' is not a number", fields[5])),
+            }
+        }
+    }
+
+    /// Records must keep each contig together and ascend in position
+    fn track_order(&mut self, chrom: &str, pos: u64) {
+        match &mut self.current {
+            Some((current, last)) if current == chrom => {
+                if pos < *last {
+                    self.is_sorted = false;
+                }
+                *last = pos;
+            }
+            _ => {
+                if self.finished.contains(chrom) {
+                    self.is_sorted = false;
+                }
+                if let Some((previous, _)) = self.current.take() {
+                    if self.finished.len() < MAX_CONTIGS {
+                        self.finished.insert(previous);
+                    }
+                }
+                self.current = Some((chrom.to_string(), pos));
+            }
+        }
+    }
+
+    fn finish(mut self, last_line: usize) -> VcfAnalysis {
+        if self.columns.is_none() {
+            self.invalid.push(last_line, "No #CHROM header line");
+        }
+        let anomalies = self
+            .alleles
+            .anomalies(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'));
+
+        VcfAnalysis {
+            file_format: self.file_format,
+            samples: self.samples,
+            variant_count: self.variant_count,
+            contigs: self.contigs,
+            info_fields: self.info_fields,
+            format_fields: self.format_fields,
+            is_sorted: self.is_sorted,
+            mean_quality: (self.quality_count > 0).then(|| self.quality_sum / self.quality_count as f64),
+            anomalies,
+            invalid_record_count: self.invalid.count,
+            invalid_records: self.invalid.samples,
+            gzip: None,
+        }
+    }
+}
+
+/// A field of a structured meta line value such as
+/// `<ID=DP,Number=1,Type=Integer,Description="Read depth, total">`
+fn structured_field<'a>(value: &'a str, key: &str) -> Option<&'a str> {
+    let inner = value.strip_prefix('<')?;
+    let inner = inner.strip_suffix('>').unwrap_or(inner);
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (idx, c) in inner.char_indices().chain(std::iter::once((inner.len(), ','))) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                let field = &inner[start..idx];
+                if let Some((k, v)) = field.split_once('=') {
+                    if k.trim() == key {
+                        return Some(v.trim().trim_matches('"'));
+                    }
+                }
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn analyze(test: &str, content: &str) -> VcfAnalysis {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_vcf_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("calls.vcf");
+        fs::write(&path, content).unwrap();
+        let analysis = analyze_vcf(&path).unwrap();
+        fs::remove_dir_all(temp_dir).ok();
+        analysis
+    }
+
+    const HEADER: &str = concat!(
+        "##fileformat=VCFv4.2\n",
+        "##contig=<ID=chr1,length=1000>\n",
+        "##contig=<ID=chr2,length=500>\n",
+        "##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Depth, all reads\">\n",
+        "##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n",
+        "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tNA001\tNA002\n",
+    );
+
+    #[test]
+    fn test_valid_vcf() {
+        let content = format!(
+            "{}{}{}{}",
+            HEADER,
+            "chr1\t10\trs1\tA\tG,T\t50\tPASS\tDP=12\tGT\t0/1\t1/1\n",
+            "chr1\t20\t.\tAC\t<DEL>\t.\tPASS\t.\tGT\t0/0\t0/1\n",
+            "chr2\t5\t.\tG\t*\t30\tPASS\t.\tGT\t0/1\t0/0\n",
+        );
+        let analysis = analyze("valid", &content);
+
+        assert_eq!(analysis.file_format.as_deref(), Some("VCFv4.2"));
+        assert_eq!(analysis.samples, vec!["NA001", "NA002"]);
+        assert_eq!(analysis.variant_count, 3);
+        assert_eq!(analysis.info_fields, vec!["DP"]);
+        assert_eq!(analysis.format_fields, vec!["GT"]);
+        assert_eq!(
+            analysis.contigs[0],
+            VcfContig {
+                name: "chr1".to_string(),
+                length: Some(1000),
+                declared: true,
+                variant_count: 2,
+            }
+        );
+        assert!(analysis.is_sorted);
+        assert_eq!(analysis.mean_quality, Some(40.0));
+        assert!(analysis.anomalies.is_empty());
+        assert_eq!(analysis.invalid_record_count, 0);
+    }
+
+    #[test]
+    fn test_malformed_and_unsorted_vcf() {
+        let content = format!(
+            "{}{}{}{}{}{}",
+            HEADER,
+            "chr2\t5\t.\tG\tA\t30\tPASS\t.\tGT\t0/1\t0/0\n",
+            "chr1\t10\t.\tA\tG\t30\tPASS\t.\tGT\t0/1\n",
+            "chr1\t0\t.\tA\tR\tlow\tPASS\t.\tGT\t0/1\t0/0\n",
+            "chr2\t900\t.\tA\tG\t30\tPASS\t.\tGT\t0/1\t0/0\n",
+            "chrM\t3\t.\tA\tG\t30\tPASS\t.\tGT\t0/1\t0/0\n",
+        );
+        let analysis = analyze("malformed", &content);
+
+        assert_eq!(analysis.variant_count, 5);
+        assert!(!analysis.is_sorted);
+        assert_eq!(
+            analysis.invalid_records,
+            vec![
+                (8, "Record has 10 columns but the header has 11".to_string()),
+                (9, "POS '0' is not a positive integer".to_string()),
+                (9, "QUAL 'low' is not a number".to_string()),
+                (10, "POS 900 is past the end of chr2 (500 bp)".to_string()),
+            ]
+        );
+        assert_eq!(analysis.anomalies[0].character, 'R');
+        let chrm = analysis.contigs.iter().find(|c| c.name == "chrM").unwrap();
+        assert!(!chrm.declared);
+    }
+
+    #[test]
+    fn test_missing_header() {
+        let analysis = analyze("missing_header", "chr1\t10\t.\tA\tG\t30\tPASS\t.\n");
+        assert!(analysis.file_format.is_none());
+        assert_eq!(analysis.variant_count, 0);
+        assert_eq!(
+            analysis.invalid_records,
+            vec![
+                (1, "The first line is not a ##fileformat line".to_string()),
+                (1, "Variant record before the #CHROM header line".to_string()),
+                (1, "No #CHROM header line".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_structured_field() {
+        let value = "<ID=DP,Number=1,Description=\"Depth, ID=x\">";
+        assert_eq!(structured_field(value, "ID"), Some("DP"));
+        assert_eq!(structured_field(value, "Description"), Some("Depth, ID=x"));
+        assert_eq!(structured_field(value, "length"), None);
+        assert_eq!(structured_field("plain", "ID"), None);
+    }
+}
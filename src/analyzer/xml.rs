@@ -0,0 +1,621 @@
+//! XML analysis
+//!
+//! XML is streamed through a byte-level tokenizer that checks
+//! well-formedness as it goes: a single root element, matching start and
+//! end tags, quoted and unique attributes, escaped `&` and `<`, and
+//! declared namespace prefixes. It records the root element, every
+//! namespace declaration and the number of elements, and recognizes the
+//! metadata standards catalogs harvest (EML, ISO 19115/19139, DataCite) by
+//! the namespace of the root element. The first error stops the analysis
+//! and is reported with its line and column.
+
+use super::AnalysisError;
+use crate::compression::{is_corrupt, DataReader};
+use crate::types::{MetadataStandard, XmlAnalysis, XmlNamespace, XmlSyntaxError};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Namespace declarations kept (documents rarely declare more than a dozen)
+const MAX_NAMESPACES: usize = 100;
+
+/// Longest entity or character reference read before giving up on it
+const MAX_REFERENCE_LENGTH: usize = 32;
+
+/// Analyze an XML file
+///
+/// # Arguments
+///
+/// * `path` - Path to the XML file
+///
+/// # Returns
+///
+/// An XmlAnalysis with the root element, namespaces, element count, the
+/// metadata standard (if recognized) and the first well-formedness error.
+pub fn analyze_xml(path: &Path) -> Result<XmlAnalysis, AnalysisError> {
+    analyze_xml_from(DataReader::open(path)?)
+}
+
+/// Analyze an XML document read from a file or archive member
+pub(crate) fn analyze_xml_from(data: DataReader) -> Result<XmlAnalysis, AnalysisError> {
+    read_document(BufReader::new(data))
+}
+
+fn read_document(reader: impl BufRead) -> Result<XmlAnalysis, AnalysisError> {
+    let mut tokenizer = XmlTokenizer::new(reader);
+    let error = match tokenizer.run() {
+        Ok(()) => None,
+        Err(Stop::Syntax(error)) => Some(error),
+        Err(Stop::Io(e)) if is_corrupt(&e) => Some(XmlSyntaxError {
+            message: "The compressed stream is damaged".to_string(),
+            line: tokenizer.line,
+            column: tokenizer.column,
+        }),
+        Err(Stop::Io(e)) => return Err(e.into()),
+    };
+
+    let standard = tokenizer.root_namespace.as_deref().and_then(metadata_standard);
+    Ok(XmlAnalysis {
+        is_well_formed: error.is_none(),
+        root_element: tokenizer.root_element,
+        root_namespace: tokenizer.root_namespace,
+        namespaces: tokenizer.namespaces,
+        element_count: tokenizer.element_count,
+        max_depth: tokenizer.max_depth,
+        standard,
+        error,
+    })
+}
+
+/// The metadata standard a root namespace belongs to
+pub fn metadata_standard(namespace: &str) -> Option<MetadataStandard> {
+    if namespace.starts_with("https://eml.ecoinformatics.org/eml-") || namespace.starts_with("eml://ecoinformatics.org/eml-") {
+        Some(MetadataStandard::Eml)
+    } else if namespace == "http://www.isotc211.org/2005/gmd"
+        || namespace == "http://www.isotc211.org/2005/gmi"
+        || namespace.starts_with("http://standards.iso.org/iso/19115/-3/")
+    {
+        Some(MetadataStandard::Iso19115)
+    } else if namespace.starts_with("http://datacite.org/schema/kernel-") {
+        Some(MetadataStandard::DataCite)
+    } else {
+        None
+    }
+}
+
+/// Why tokenizing stopped early
+enum Stop {
+    Io(io::Error),
+    Syntax(XmlSyntaxError),
+}
+
+impl From<io::Error> for Stop {
+    fn from(error: io::Error) -> Self {
+        Stop::Io(error)
+    }
+}
+
+/// An open element
+struct OpenElement {
+    name: String,
+    line: usize,
+    /// Namespace prefixes this element declares ("" for the default)
+    declared: Vec<(String, String)>,
+}
+
+struct XmlTokenizer<R: BufRead> {
+    reader: R,
+    line: usize,
+    column: usize,
+    stack: Vec<OpenElement>,
+    has_doctype: bool,
+    root_element: Option<String>,
+    root_namespace: Option<String>,
+    namespaces: Vec<XmlNamespace>,
+    element_count: usize,
+    max_depth: usize,
+}
+
+impl<R: BufRead> XmlTokenizer<R> {
+    fn new(reader: R) -> Self {
+        XmlTokenizer {
+            reader,
+            line: 1,
+            column: 1,
+            stack: Vec::new(),
+            has_doctype: false,
+            root_element: None,
+            root_namespace: None,
+            namespaces: Vec::new(),
+            element_count: 0,
+            max_depth: 0,
+        }
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.peek()?;
+        if let Some(b) = byte {
+            self.reader.consume(1);
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // UTF-8 continuation bytes do not start a new column
+                self.column += 1;
+            }
+        }
+        Ok(byte)
+    }
+
+    fn error(&self, message: impl Into<String>) -> Stop {
+        self.error_at(self.line, self.column, message)
+    }
+
+    fn error_at(&self, line: usize, column: usize, message: impl Into<String>) -> Stop {
+        Stop::Syntax(XmlSyntaxError {
+            message: message.into(),
+            line,
+            column,
+        })
+    }
+
+    fn run(&mut self) -> Result<(), Stop> {
+        if self.reader.fill_buf()?.starts_with(b"\xEF\xBB\xBF") {
+            self.reader.consume(3);
+        }
+        let mut at_start = true;
+        while let Some(byte) = self.peek()? {
+            match byte {
+                b'<' => {
+                    let (line, column) = (self.line, self.column);
+                    self.bump()?;
+                    self.markup(at_start, line, column)?;
+                }
+                b'&' => {
+                    if self.stack.is_empty() {
+                        return Err(self.error("Text outside the root element"));
+                    }
+                    self.reference()?;
+                }
+                _ => {
+                    if self.stack.is_empty() && !byte.is_ascii_whitespace() {
+                        return Err(self.error("Text outside the root element"));
+                    }
+                    self.bump()?;
+                }
+            }
+            at_start = false;
+        }
+
+        if let Some(open) = self.stack.last() {
+            return Err(self.error(format!(
+                "Unclosed element <{}> (opened on line {})",
+                open.name, open.line
+            )));
+        }
+        if self.root_element.is_none() {
+            return Err(self.error("No root element"));
+        }
+        Ok(())
+    }
+
+    /// Whatever follows a `<`
+    fn markup(&mut self, at_start: bool, line: usize, column: usize) -> Result<(), Stop> {
+        match self.peek()? {
+            Some(b'?') => {
+                self.bump()?;
+                let target = self.name()?;
+                if target.eq_ignore_ascii_case("xml") && !at_start {
+                    return Err(self.error_at(
+                        line,
+                        column,
+                        "The XML declaration (<?xml ...?>) is only allowed at the very start of the file",
+                    ));
+                }
+                self.skip_until(b"?>", "Unterminated processing instruction")
+            }
+            Some(b'!') => {
+                self.bump()?;
+                if self.peek()? == Some(b'-') {
+                    self.expect(b"--", "Invalid comment (comments start with '<!--')")?;
+                    self.comment()
+                } else if self.peek()? == Some(b'[') {
+                    self.expect(b"[CDATA[", "Invalid markup '<!['")?;
+                    if self.stack.is_empty() {
+                        return Err(self.error_at(line, column, "CDATA section outside the root element"));
+                    }
+                    self.skip_until(b"]]>", "Unterminated CDATA section")
+                } else {
+                    self.expect(b"DOCTYPE", "Invalid markup after '<!'")?;
+                    if self.root_element.is_some() {
+                        return Err(self.error_at(line, column, "DOCTYPE must come before the root element"));
+                    }
+                    self.has_doctype = true;
+                    self.doctype()
+                }
+            }
+            Some(b'/') => {
+                self.bump()?;
+                let name = self.name()?;
+                self.skip_whitespace()?;
+                if self.bump()? != Some(b'>') {
+                    return Err(self.error(format!("Expected '>' to close the end tag </{}>", name)));
+                }
+                match self.stack.pop() {
+                    Some(open) if open.name == name => Ok(()),
+                    Some(open) => Err(self.error_at(
+                        line,
+                        column,
+                        format!(
+                            "Mismatched end tag </{}>: expected </{}> (opened on line {})",
+                            name, open.name, open.line
+                        ),
+                    )),
+                    None => Err(self.error_at(line, column, format!("Unexpected end tag </{}>", name))),
+                }
+            }
+            _ => self.start_tag(line, column),
+        }
+    }
+
+    fn start_tag(&mut self, line: usize, column: usize) -> Result<(), Stop> {
+        let name = self.name()?;
+        if self.stack.is_empty() {
+            if let Some(root) = &self.root_element {
+                return Err(self.error_at(
+                    line,
+                    column,
+                    format!("Multiple root elements: <{}> after <{}>", name, root),
+                ));
+            }
+        }
+
+        let mut attributes: Vec<String> = Vec::new();
+        let mut declared = Vec::new();
+        let empty = loop {
+            let spaced = self.skip_whitespace()?;
+            match self.peek()? {
+                Some(b'>') => {
+                    self.bump()?;
+                    break false;
+                }
+                Some(b'/') => {
+                    self.bump()?;
+                    if self.bump()? != Some(b'>') {
+                        return Err(self.error("Expected '>' after '/' in an empty-element tag"));
+                    }
+                    break true;
+                }
+                None => return Err(self.error(format!("Unterminated start tag <{}>", name))),
+                Some(_) if !spaced => {
+                    return Err(self.error(format!("Expected whitespace, '>' or '/>' in start tag <{}>", name)))
+                }
+                Some(_) => {}
+            }
+
+            let attribute = self.name()?;
+            self.skip_whitespace()?;
+            if self.bump()? != Some(b'=') {
+                return Err(self.error(format!("Expected '=' after attribute '{}'", attribute)));
+            }
+            self.skip_whitespace()?;
+            let value = self.attribute_value(&attribute)?;
+            if attributes.contains(&attribute) {
+                return Err(self.error(format!("Duplicate attribute '{}' on <{}>", attribute, name)));
+            }
+            if attribute == "xmlns" {
+                declared.push((String::new(), value));
+            } else if let Some(prefix) = attribute.strip_prefix("xmlns:") {
+                if value.is_empty() {
+                    return Err(self.error(format!("Namespace prefix '{}' is bound to an empty URI", prefix)));
+                }
+                declared.push((prefix.to_string(), value));
+            }
+            attributes.push(attribute);
+        };
+
+        for (prefix, uri) in &declared {
+            let known = self.namespaces.iter().any(|ns| ns.prefix == *prefix && ns.uri == *uri);
+            if !known && self.namespaces.len() < MAX_NAMESPACES {
+                self.namespaces.push(XmlNamespace {
+                    prefix: prefix.clone(),
+                    uri: uri.clone(),
+                });
+            }
+        }
+        self.stack.push(OpenElement {
+            name: name.clone(),
+            line,
+            declared,
+        });
+
+        // Prefixes must be declared on the element or an ancestor
+        let undeclared = std::iter::once(name.as_str())
+            .chain(attributes.iter().map(String::as_str).filter(|a| *a != "xmlns" && !a.starts_with("xmlns:")))
+            .filter_map(|qualified| qualified.split_once(':').map(|(prefix, _)| prefix))
+            .find(|prefix| *prefix != "xml" && self.namespace(prefix).is_none());
+        if let Some(prefix) = undeclared {
+            return Err(self.error_at(line, column, format!("Undeclared namespace prefix '{}'", prefix)));
+        }
+
+        if self.root_element.is_none() {
+            let prefix = name.split_once(':').map_or("", |(prefix, _)| prefix);
+            self.root_namespace = self.namespace(prefix).filter(|uri| !uri.is_empty()).map(str::to_string);
+            self.root_element = Some(name);
+        }
+        self.element_count += 1;
+        self.max_depth = self.max_depth.max(self.stack.len());
+        if empty {
+            self.stack.pop();
+        }
+        Ok(())
+    }
+
+    /// The URI a prefix is bound to in the current scope
+    fn namespace(&self, prefix: &str) -> Option<&str> {
+        self.stack
+            .iter()
+            .rev()
+            .flat_map(|open| open.declared.iter())
+            .find(|(declared, _)| declared == prefix)
+            .map(|(_, uri)| uri.as_str())
+    }
+
+    /// A quoted attribute value, with references resolved
+    fn attribute_value(&mut self, attribute: &str) -> Result<String, Stop> {
+        let quote = match self.bump()? {
+            Some(q @ (b'"' | b'\'')) => q,
+            _ => {
+                return Err(self.error(format!(
+                    "The value of attribute '{}' must be in quotes",
+                    attribute
+                )))
+            }
+        };
+        let mut value = Vec::new();
+        loop {
+            match self.peek()? {
+                Some(b) if b == quote => {
+                    self.bump()?;
+                    return Ok(String::from_utf8_lossy(&value).into_owned());
+                }
+                Some(b'<') => return Err(self.error(format!("'<' in the value of attribute '{}' (write &lt;)", attribute))),
+                Some(b'&') => {
+                    let resolved = self.reference()?;
+                    value.extend_from_slice(resolved.as_bytes());
+                }
+                Some(b) => {
+                    self.bump()?;
+                    value.push(b);
+                }
+                None => return Err(self.error(format!("Unterminated value of attribute '{}'", attribute))),
+            }
+        }
+    }
+
+    /// An entity or character reference, with `&` next; returns its text
+    fn reference(&mut self) -> Result<String, Stop> {
+        let (line, column) = (self.line, self.column);
+        self.bump()?;
+        let mut body = String::new();
+        loop {
+            match self.peek()? {
+                Some(b';') => {
+                    self.bump()?;
+                    break;
+                }
+                Some(b) if (b.is_ascii_alphanumeric() || matches!(b, b'#' | b'_' | b'-' | b'.' | b':'))
+                    && body.len() < MAX_REFERENCE_LENGTH =>
+                {
+                    self.bump()?;
+                    body.push(b as char);
+                }
+                _ => {
+                    return Err(self.error_at(
+                        line,
+                        column,
+                        "Unescaped '&' (write &amp;, or end the entity reference with ';')",
+                    ))
+                }
+            }
+        }
+
+        let text = match body.as_str() {
+            "amp" => "&".to_string(),
+            "lt" => "<".to_string(),
+            "gt" => ">".to_string(),
+            "apos" => "'".to_string(),
+            "quot" => "\"".to_string(),
+            _ if body.starts_with('#') => {
+                let code = match body.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => body[1..].parse().ok(),
+                };
+                match code.filter(|&c| c != 0).and_then(char::from_u32) {
+                    Some(c) => c.to_string(),
+                    None => {
+                        return Err(self.error_at(line, column, format!("Invalid character reference '&{};'", body)))
+                    }
+                }
+            }
+            // Entities declared in a DTD cannot be checked without reading it
+            _ if self.has_doctype && !body.is_empty() => String::new(),
+            _ => return Err(self.error_at(line, column, format!("Undefined entity '&{};'", body))),
+        };
+        Ok(text)
+    }
+
+    /// An element or attribute name
+    fn name(&mut self) -> Result<String, Stop> {
+        let mut name = Vec::new();
+        while let Some(b) = self.peek()? {
+            let valid = b.is_ascii_alphabetic()
+                || matches!(b, b'_' | b':')
+                || b >= 0x80
+                || (!name.is_empty() && (b.is_ascii_digit() || matches!(b, b'-' | b'.')));
+            if !valid {
+                break;
+            }
+            self.bump()?;
+            name.push(b);
+        }
+        if name.is_empty() {
+            let found = match self.peek()? {
+                Some(b) => format!("'{}'", b as char),
+                None => "end of file".to_string(),
+            };
+            return Err(self.error(format!("Expected a name, found {}", found)));
+        }
+        Ok(String::from_utf8_lossy(&name).into_owned())
+    }
+
+    /// Skip whitespace; returns whether there was any
+    fn skip_whitespace(&mut self) -> Result<bool, Stop> {
+        let mut skipped = false;
+        while self.peek()?.is_some_and(|b| b.is_ascii_whitespace()) {
+            self.bump()?;
+            skipped = true;
+        }
+        Ok(skipped)
+    }
+
+    fn expect(&mut self, literal: &[u8], message: &str) -> Result<(), Stop> {
+        for &expected in literal {
+            if self.bump()? != Some(expected) {
+                return Err(self.error(message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip past a terminator
+    fn skip_until(&mut self, terminator: &[u8], message: &str) -> Result<(), Stop> {
+        let mut matched = 0;
+        while matched < terminator.len() {
+            match self.bump()? {
+                Some(b) if b == terminator[matched] => matched += 1,
+                // Restart, allowing for a terminator that begins with this byte
+                Some(b) => matched = usize::from(b == terminator[0]),
+                None => return Err(self.error(message)),
+            }
+        }
+        Ok(())
+    }
+
+    /// The rest of a comment, after `<!--`
+    fn comment(&mut self) -> Result<(), Stop> {
+        loop {
+            match self.bump()? {
+                Some(b'-') if self.peek()? == Some(b'-') => {
+                    self.bump()?;
+                    if self.bump()? != Some(b'>') {
+                        return Err(self.error("'--' is not allowed inside a comment"));
+                    }
+                    return Ok(());
+                }
+                Some(_) => {}
+                None => return Err(self.error("Unterminated comment")),
+            }
+        }
+    }
+
+    /// The rest of a DOCTYPE declaration, including an internal subset
+    fn doctype(&mut self) -> Result<(), Stop> {
+        let mut depth = 0usize;
+        let mut quote = None;
+        loop {
+            match (self.bump()?, quote) {
+                (None, _) => return Err(self.error("Unterminated DOCTYPE declaration")),
+                (Some(b), Some(q)) if b == q => quote = None,
+                (Some(_), Some(_)) => {}
+                (Some(b @ (b'"' | b'\'')), None) => quote = Some(b),
+                (Some(b'['), None) => depth += 1,
+                (Some(b']'), None) => depth = depth.saturating_sub(1),
+                (Some(b'>'), None) if depth == 0 => return Ok(()),
+                (Some(_), None) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(xml: &str) -> XmlAnalysis {
+        read_document(xml.as_bytes()).unwrap()
+    }
+
+    fn error(xml: &str) -> (String, usize, usize) {
+        let error = analyze(xml).error.expect("document should be rejected");
+        (error.message, error.line, error.column)
+    }
+
+    #[test]
+    fn test_eml_document() {
+        let analysis = analyze(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Generated by the field station -->
+<eml:eml xmlns:eml="https://eml.ecoinformatics.org/eml-2.2.0"
+         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         packageId="knb.1.1" system="knb">
+  <dataset>
+    <title>Stream chemistry &amp; flow, 2019&#8211;2023</title>
+    <creator><individualName><surName>Lovelace</surName></individualName></creator>
+    <abstract><![CDATA[Samples taken <weekly> at 3 sites]]></abstract>
+    <coverage/>
+  </dataset>
+</eml:eml>
+"#,
+        );
+        assert!(analysis.is_well_formed, "{:?}", analysis.error);
+        assert_eq!(analysis.root_element.as_deref(), Some("eml:eml"));
+        assert_eq!(analysis.root_namespace.as_deref(), Some("https://eml.ecoinformatics.org/eml-2.2.0"));
+        assert_eq!(analysis.standard, Some(MetadataStandard::Eml));
+        assert_eq!(analysis.namespaces.len(), 2);
+        assert_eq!(analysis.namespaces[1].prefix, "xsi");
+        assert_eq!(analysis.element_count, 8);
+        assert_eq!(analysis.max_depth, 5);
+    }
+
+    #[test]
+    fn test_standards_by_root_namespace() {
+        let iso = analyze(r#"<gmd:MD_Metadata xmlns:gmd="http://www.isotc211.org/2005/gmd"/>"#);
+        assert_eq!(iso.standard, Some(MetadataStandard::Iso19115));
+
+        let datacite = analyze(r#"<resource xmlns="http://datacite.org/schema/kernel-4"><titles/></resource>"#);
+        assert_eq!(datacite.standard, Some(MetadataStandard::DataCite));
+        assert_eq!(datacite.root_element.as_deref(), Some("resource"));
+
+        let vendor = analyze("<Instrument serial='A7'><Gain>2</Gain></Instrument>");
+        assert!(vendor.is_well_formed);
+        assert_eq!(vendor.root_namespace, None);
+        assert_eq!(vendor.standard, None);
+    }
+
+    #[test]
+    fn test_well_formedness_errors() {
+        assert_eq!(
+            error("<a>\n  <b>\n  </a>\n"),
+            ("Mismatched end tag </a>: expected </b> (opened on line 2)".to_string(), 3, 3)
+        );
+        assert_eq!(error("<a>\n  <b/>\n").0, "Unclosed element <a> (opened on line 1)");
+        assert_eq!(error("<a/>\n<b/>\n"), ("Multiple root elements: <b> after <a>".to_string(), 2, 1));
+        assert_eq!(error("<a>Salt & pepper</a>"), ("Unescaped '&' (write &amp;, or end the entity reference with ';')".to_string(), 1, 9));
+        assert_eq!(error("<a>&nbsp;</a>").0, "Undefined entity '&nbsp;'");
+        assert_eq!(error("<a x='1' x='2'/>").0, "Duplicate attribute 'x' on <a>");
+        assert_eq!(error("<a x=1/>").0, "The value of attribute 'x' must be in quotes");
+        assert_eq!(error("<a x='<'/>").0, "'<' in the value of attribute 'x' (write &lt;)");
+        assert_eq!(error("<gmd:MD_Metadata/>").0, "Undeclared namespace prefix 'gmd'");
+        assert_eq!(error("\n<?xml version='1.0'?><a/>").1, 2);
+        assert_eq!(error("<a/>trailing").0, "Text outside the root element");
+        assert_eq!(error("<!-- only a comment -->").0, "No root element");
+        assert_eq!(error("<a><!-- x -- y --></a>").0, "'--' is not allowed inside a comment");
+
+        // Entities declared in a DTD are accepted
+        assert!(analyze("<!DOCTYPE a [<!ENTITY nbsp \"&#160;\">]><a>&nbsp;</a>").is_well_formed);
+    }
+}
@@ -0,0 +1,916 @@
+//! YAML parsing
+//!
+//! A dependency-free parser for the YAML found in data metadata: instrument
+//! configurations, CITATION.cff, Zenodo and CI files. It reads block
+//! mappings and sequences (including `- key: value` entries), plain,
+//! single- and double-quoted scalars, literal and folded block scalars,
+//! single- or multi-line flow collections, comments, anchors and aliases,
+//! and multi-document streams. Tags are accepted and ignored. Scalars are
+//! resolved with the YAML 1.2 core schema, and each document is returned
+//! as a `JsonValue` tree.
+
+use super::JsonValue;
+use crate::types::ConfigSyntaxError;
+use std::collections::HashMap;
+
+/// Deepest nesting accepted (bounds recursion on hostile input)
+const MAX_DEPTH: usize = 128;
+
+const UNTERMINATED: &str = "Unterminated quoted string";
+
+/// Parse a YAML stream
+///
+/// # Arguments
+///
+/// * `input` - The YAML text
+///
+/// # Returns
+///
+/// One value per document, or the first syntax error with its line.
+pub fn parse_yaml(input: &str) -> Result<Vec<JsonValue>, ConfigSyntaxError> {
+    let mut parser = YamlParser {
+        lines: input.trim_start_matches('\u{feff}').lines().collect(),
+        pos: 0,
+        pending: None,
+        anchors: HashMap::new(),
+        depth: 0,
+    };
+    parser.parse_stream().map_err(|(line, message)| ConfigSyntaxError { message, line })
+}
+
+/// An error with the (1-based) line it was found on
+type Failure = (usize, String);
+
+/// A line with content, comments removed
+#[derive(Debug, Clone)]
+struct Line {
+    /// Line number (1-based)
+    number: usize,
+    /// Columns of indentation
+    indent: usize,
+    /// Content after the indentation
+    text: String,
+}
+
+struct YamlParser<'a> {
+    lines: Vec<&'a str>,
+    /// Index of the next unread line
+    pos: usize,
+    /// The rest of a line after a `- ` sequence indicator, read as a line
+    /// of its own at the column where it starts
+    pending: Option<Line>,
+    anchors: HashMap<String, JsonValue>,
+    depth: usize,
+}
+
+impl<'a> YamlParser<'a> {
+    fn parse_stream(&mut self) -> Result<Vec<JsonValue>, Failure> {
+        let mut documents = Vec::new();
+        let mut explicit = false;
+        loop {
+            // Directives (`%YAML 1.2`) and comments come before the document
+            while let Some(raw) = self.lines.get(self.pos) {
+                if raw.starts_with('%') || is_blank(raw) || raw.trim_start().starts_with('#') {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            match self.lines.get(self.pos) {
+                None => {
+                    if explicit {
+                        documents.push(JsonValue::Null);
+                    }
+                    return Ok(documents);
+                }
+                Some(raw) if is_marker(raw, "---") => {
+                    let rest = raw[3..].trim_start();
+                    let column = raw.len() - rest.len();
+                    self.pos += 1;
+                    if explicit {
+                        documents.push(JsonValue::Null);
+                    }
+                    explicit = true;
+                    if rest.is_empty() || rest.starts_with('#') {
+                        continue;
+                    }
+                    // Content may start on the marker line (`--- |`)
+                    self.pending = Some(Line {
+                        number: self.pos,
+                        indent: column,
+                        text: strip_comment(rest).to_string(),
+                    });
+                }
+                Some(raw) if is_marker(raw, "...") => {
+                    if explicit {
+                        documents.push(JsonValue::Null);
+                    }
+                    explicit = false;
+                    self.pos += 1;
+                    continue;
+                }
+                Some(_) => {}
+            }
+
+            documents.push(self.parse_block(0)?);
+            explicit = false;
+            if let Some(line) = self.peek_line()? {
+                return Err((line.number, format!("Unexpected content '{}'", line.text)));
+            }
+            // A `...` end marker is optional before the next document
+            if self.lines.get(self.pos).is_some_and(|raw| is_marker(raw, "...")) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    /// The next line with content, without consuming it; None at the end
+    /// of the document
+    fn peek_line(&mut self) -> Result<Option<Line>, Failure> {
+        if let Some(line) = &self.pending {
+            return Ok(Some(line.clone()));
+        }
+        while let Some(raw) = self.lines.get(self.pos) {
+            if is_marker(raw, "---") || is_marker(raw, "...") {
+                return Ok(None);
+            }
+            let trimmed = raw.trim_start_matches([' ', '\t']);
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                self.pos += 1;
+                continue;
+            }
+            let indentation = &raw[..raw.len() - trimmed.len()];
+            if indentation.contains('\t') {
+                return Err((self.pos + 1, "Tab characters are not allowed in indentation".to_string()));
+            }
+            return Ok(Some(Line {
+                number: self.pos + 1,
+                indent: indentation.len(),
+                text: strip_comment(trimmed).to_string(),
+            }));
+        }
+        Ok(None)
+    }
+
+    fn take_line(&mut self) {
+        if self.pending.take().is_none() {
+            self.pos += 1;
+        }
+    }
+
+    fn enter(&mut self, line: usize) -> Result<(), Failure> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err((line, "Nesting is too deep".to_string()));
+        }
+        Ok(())
+    }
+
+    /// A block node whose lines are indented at least `min_indent`
+    fn parse_block(&mut self, min_indent: usize) -> Result<JsonValue, Failure> {
+        let line = match self.peek_line()? {
+            Some(line) if line.indent >= min_indent => line,
+            _ => return Ok(JsonValue::Null),
+        };
+        self.enter(line.number)?;
+        let value = if is_sequence_entry(&line.text) {
+            self.parse_sequence(line.indent)
+        } else if mapping_colon(&line.text).is_some() {
+            self.parse_mapping(line.indent)
+        } else {
+            self.take_line();
+            self.parse_node(&line.text, line.number, min_indent.checked_sub(1), false)
+        };
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<JsonValue, Failure> {
+        let mut members: Vec<(String, JsonValue)> = Vec::new();
+        while let Some(line) = self.peek_line()? {
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent {
+                return Err((line.number, "Unexpected indentation".to_string()));
+            }
+            if is_sequence_entry(&line.text) {
+                return Err((line.number, "Expected a mapping key, found a sequence entry".to_string()));
+            }
+            let colon = match mapping_colon(&line.text) {
+                Some(colon) => colon,
+                None => {
+                    return Err((
+                        line.number,
+                        format!("Expected a mapping key ('key: value'), found '{}'", line.text),
+                    ))
+                }
+            };
+            let key = parse_key(&line.text[..colon]).map_err(|message| (line.number, message))?;
+            if members.iter().any(|(existing, _)| *existing == key) {
+                return Err((line.number, format!("Duplicate key '{}'", key)));
+            }
+            self.take_line();
+            let value = self.parse_node(line.text[colon + 1..].trim(), line.number, Some(indent), true)?;
+            members.push((key, value));
+        }
+        Ok(JsonValue::Object(members))
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<JsonValue, Failure> {
+        let mut items = Vec::new();
+        while let Some(line) = self.peek_line()? {
+            if line.indent < indent || (line.indent == indent && !is_sequence_entry(&line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err((line.number, "Unexpected indentation".to_string()));
+            }
+            self.take_line();
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                items.push(self.parse_block(indent + 1)?);
+            } else {
+                // `- key: value` starts a mapping at the column of `key`
+                self.pending = Some(Line {
+                    number: line.number,
+                    indent: indent + line.text.len() - rest.len(),
+                    text: rest.to_string(),
+                });
+                items.push(self.parse_block(indent + 1)?);
+            }
+        }
+        Ok(JsonValue::Array(items))
+    }
+
+    /// The value written after a key's colon (or alone on a line)
+    ///
+    /// `parent` is the indentation of the key or sequence entry that owns
+    /// the value: nested blocks and continuation lines must be deeper.
+    /// After a key, a sequence may also start at the key's own indentation.
+    fn parse_node(
+        &mut self,
+        text: &str,
+        number: usize,
+        parent: Option<usize>,
+        after_key: bool,
+    ) -> Result<JsonValue, Failure> {
+        let (anchor, text) = take_properties(text).map_err(|message| (number, message))?;
+        let deeper = |indent: usize| parent.is_none_or(|p| indent > p);
+
+        let value = if text.is_empty() {
+            match self.peek_line()? {
+                Some(next) if deeper(next.indent) => self.parse_block(next.indent)?,
+                Some(next) if after_key && Some(next.indent) == parent && is_sequence_entry(&next.text) => {
+                    self.enter(next.number)?;
+                    let value = self.parse_sequence(next.indent);
+                    self.depth -= 1;
+                    value?
+                }
+                _ => JsonValue::Null,
+            }
+        } else if let Some(name) = text.strip_prefix('*') {
+            match self.anchors.get(name) {
+                Some(value) => value.clone(),
+                None => return Err((number, format!("Unknown alias '*{}'", name))),
+            }
+        } else if text.starts_with('|') || text.starts_with('>') {
+            self.parse_block_scalar(text, number, parent)?
+        } else if text.starts_with('[') || text.starts_with('{') {
+            let flow = self.gather_flow(text, number)?;
+            let mut parser = FlowParser {
+                chars: flow.chars().collect(),
+                pos: 0,
+                depth: self.depth,
+            };
+            let value = parser.parse_value().map_err(|message| (number, message))?;
+            parser.skip_spaces();
+            if parser.pos < parser.chars.len() {
+                return Err((number, "Unexpected text after flow collection".to_string()));
+            }
+            value
+        } else if text.starts_with('"') || text.starts_with('\'') {
+            let quoted = self.gather_quoted(text, number)?;
+            let (value, end) = parse_quoted(&quoted).map_err(|message| (number, message))?;
+            if !quoted[end..].trim().is_empty() {
+                return Err((number, "Unexpected text after quoted string".to_string()));
+            }
+            JsonValue::String(value)
+        } else {
+            let mut scalar = text.to_string();
+            // Where each line of the scalar starts in it, to report errors
+            // on the line they are on
+            let mut starts = vec![(0, number)];
+            // A plain scalar continues on more deeply indented lines
+            while let Some(next) = self.peek_line()? {
+                if !deeper(next.indent) || self.pending.is_some() {
+                    break;
+                }
+                scalar.push(' ');
+                starts.push((scalar.len(), next.number));
+                scalar.push_str(&next.text);
+                self.take_line();
+            }
+            if let Some(colon) = mapping_colon(&scalar) {
+                let line = starts.iter().rev().find(|(start, _)| *start <= colon).map_or(number, |s| s.1);
+                return Err((line, "Mapping values are not allowed here".to_string()));
+            }
+            resolve_plain(&scalar)
+        };
+
+        if let Some(name) = anchor {
+            self.anchors.insert(name, value.clone());
+        }
+        Ok(value)
+    }
+
+    /// A literal (`|`) or folded (`>`) block scalar
+    fn parse_block_scalar(&mut self, header: &str, number: usize, parent: Option<usize>) -> Result<JsonValue, Failure> {
+        let folded = header.starts_with('>');
+        let mut chomping = ' ';
+        let mut explicit_indent = None;
+        for c in header[1..].trim_end().chars() {
+            match c {
+                '-' | '+' => chomping = c,
+                '1'..='9' => explicit_indent = c.to_digit(10).map(|d| d as usize + parent.unwrap_or(0)),
+                _ => return Err((number, format!("Invalid block scalar header '{}'", header))),
+            }
+        }
+        // A `- |` entry leaves no pending text behind
+        self.pending = None;
+
+        let mut lines: Vec<&str> = Vec::new();
+        let mut indent = explicit_indent;
+        while let Some(raw) = self.lines.get(self.pos) {
+            if is_blank(raw) {
+                lines.push("");
+                self.pos += 1;
+                continue;
+            }
+            let own_indent = raw.len() - raw.trim_start_matches(' ').len();
+            let content_indent = *indent.get_or_insert(own_indent);
+            if own_indent < content_indent || parent.is_some_and(|p| own_indent <= p) {
+                break;
+            }
+            lines.push(&raw[content_indent..]);
+            self.pos += 1;
+        }
+        // Trailing blank lines belong to the chomping, not the content
+        let trailing = lines.iter().rev().take_while(|line| line.is_empty()).count();
+        let content = &lines[..lines.len() - trailing];
+
+        let mut text = String::new();
+        for (idx, line) in content.iter().enumerate() {
+            if idx > 0 {
+                let previous = content[idx - 1];
+                let fold = folded
+                    && !line.is_empty()
+                    && !previous.is_empty()
+                    && !line.starts_with([' ', '\t'])
+                    && !previous.starts_with([' ', '\t']);
+                text.push(if fold { ' ' } else { '\n' });
+            }
+            text.push_str(line);
+        }
+        match chomping {
+            '-' => {}
+            '+' => text.push_str(&"\n".repeat(trailing + usize::from(!content.is_empty()))),
+            _ if !content.is_empty() => text.push('\n'),
+            _ => {}
+        }
+        Ok(JsonValue::String(text))
+    }
+
+    /// A flow collection, joined with the lines it continues onto
+    fn gather_flow(&mut self, text: &str, number: usize) -> Result<String, Failure> {
+        let mut flow = text.to_string();
+        while !flow_balanced(&flow) {
+            match self.lines.get(self.pos) {
+                Some(raw) if !is_marker(raw, "---") && !is_marker(raw, "...") => {
+                    flow.push(' ');
+                    flow.push_str(strip_comment(raw.trim()));
+                    self.pos += 1;
+                }
+                _ => return Err((number, "Unterminated flow collection (missing ']' or '}')".to_string())),
+            }
+        }
+        Ok(flow)
+    }
+
+    /// A quoted scalar, joined with the lines it continues onto
+    fn gather_quoted(&mut self, text: &str, number: usize) -> Result<String, Failure> {
+        let mut quoted = text.to_string();
+        while parse_quoted(&quoted).is_err_and(|message| message == UNTERMINATED) {
+            match self.lines.get(self.pos) {
+                Some(raw) if !is_marker(raw, "---") && !is_marker(raw, "...") => {
+                    // Line breaks fold to spaces; blank lines are kept as newlines
+                    let raw = raw.trim();
+                    if raw.is_empty() {
+                        quoted.push('\n');
+                    } else {
+                        if !quoted.ends_with('\n') {
+                            quoted.push(' ');
+                        }
+                        quoted.push_str(raw);
+                    }
+                    self.pos += 1;
+                }
+                _ => return Err((number, UNTERMINATED.to_string())),
+            }
+        }
+        // Comments after the closing quote were not stripped on joined lines
+        let (_, end) = parse_quoted(&quoted).map_err(|message| (number, message))?;
+        let after = strip_comment(&quoted[end..]).to_string();
+        quoted.truncate(end);
+        quoted.push_str(&after);
+        Ok(quoted)
+    }
+}
+
+/// A `---` or `...` marker line
+fn is_marker(raw: &str, marker: &str) -> bool {
+    raw.starts_with(marker) && raw[3..].chars().next().is_none_or(|c| c == ' ' || c == '\t')
+}
+
+fn is_blank(raw: &str) -> bool {
+    raw.trim().is_empty()
+}
+
+fn is_sequence_entry(text: &str) -> bool {
+    text == "-" || text.starts_with("- ") || text.starts_with("-\t")
+}
+
+/// Remove a trailing comment: `#` at the start or after whitespace, outside
+/// quotes
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (idx, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            // Quotes only open a scalar, not in the middle of a word (it's)
+            None if (c == '"' || c == '\'') && !previous.is_alphanumeric() => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return text[..idx].trim_end(),
+            None => {}
+        }
+        previous = c;
+    }
+    text.trim_end()
+}
+
+/// Byte index of the colon that ends a mapping key, if the text is a
+/// `key: value` pair
+fn mapping_colon(text: &str) -> Option<usize> {
+    if text.starts_with(['[', '{', '|', '>', '*', '&', '!']) {
+        return None;
+    }
+    let start = if text.starts_with(['"', '\'']) {
+        parse_quoted(text).ok()?.1
+    } else {
+        0
+    };
+    text[start..]
+        .char_indices()
+        .find(|&(idx, c)| {
+            c == ':' && text[start + idx + 1..].chars().next().is_none_or(|next| next == ' ' || next == '\t')
+        })
+        .map(|(idx, _)| start + idx)
+}
+
+/// A mapping key: plain or quoted
+fn parse_key(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.starts_with(['"', '\'']) {
+        let (key, end) = parse_quoted(text)?;
+        if !text[end..].trim().is_empty() {
+            return Err("Unexpected text after quoted key".to_string());
+        }
+        return Ok(key);
+    }
+    if text.starts_with('?') {
+        return Err("Complex mapping keys ('? ') are not supported".to_string());
+    }
+    Ok(text.to_string())
+}
+
+/// Strip `!tag` and `&anchor` properties from the start of a node
+fn take_properties(mut text: &str) -> Result<(Option<String>, &str), String> {
+    let mut anchor = None;
+    loop {
+        text = text.trim_start();
+        let is_anchor = text.starts_with('&');
+        if !is_anchor && !text.starts_with('!') {
+            return Ok((anchor, text));
+        }
+        let end = text.find([' ', '\t']).unwrap_or(text.len());
+        if is_anchor {
+            if end == 1 {
+                return Err("Anchor without a name".to_string());
+            }
+            anchor = Some(text[1..end].to_string());
+        }
+        text = &text[end..];
+    }
+}
+
+/// Parse a single- or double-quoted scalar at the start of the text;
+/// returns the value and the byte index after the closing quote
+fn parse_quoted(text: &str) -> Result<(String, usize), String> {
+    let mut chars = text.char_indices();
+    let quote = match chars.next() {
+        Some((_, c @ ('"' | '\''))) => c,
+        _ => return Err("Expected a quoted string".to_string()),
+    };
+    let mut value = String::new();
+    while let Some((idx, c)) = chars.next() {
+        if c == quote {
+            // '' is an escaped quote in single-quoted strings
+            if quote == '\'' && text[idx + 1..].starts_with('\'') {
+                chars.next();
+                value.push('\'');
+                continue;
+            }
+            return Ok((value, idx + 1));
+        }
+        if c != '\\' || quote == '\'' {
+            value.push(c);
+            continue;
+        }
+        let escape = chars.next().map(|(_, e)| e).ok_or(UNTERMINATED)?;
+        let digits = match escape {
+            '0' => {
+                value.push('\0');
+                continue;
+            }
+            'a' => '\u{7}',
+            'b' => '\u{8}',
+            't' | '\t' => '\t',
+            'n' => '\n',
+            'v' => '\u{b}',
+            'f' => '\u{c}',
+            'r' => '\r',
+            'e' => '\u{1b}',
+            ' ' => ' ',
+            '"' => '"',
+            '/' => '/',
+            '\\' => '\\',
+            'N' => '\u{85}',
+            '_' => '\u{a0}',
+            'L' => '\u{2028}',
+            'P' => '\u{2029}',
+            'x' | 'u' | 'U' => {
+                let count = match escape {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                let hex: String = chars.by_ref().take(count).map(|(_, h)| h).collect();
+                let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                match decoded {
+                    Some(c) if hex.len() == count => c,
+                    _ => return Err(format!("Invalid escape '\\{}{}' in double-quoted string", escape, hex)),
+                }
+            }
+            other => return Err(format!("Invalid escape '\\{}' in double-quoted string", other)),
+        };
+        value.push(digits);
+    }
+    Err(UNTERMINATED.to_string())
+}
+
+/// Whether every bracket and brace opened in a flow collection is closed
+fn flow_balanced(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut chars = text.chars().peekable();
+    let mut previous = ' ';
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' if !previous.is_alphanumeric() => {
+                // Skip to the closing quote (escapes in double quotes)
+                let mut closed = false;
+                while let Some(inner) = chars.next() {
+                    if c == '"' && inner == '\\' {
+                        chars.next();
+                    } else if inner == c {
+                        closed = true;
+                        break;
+                    }
+                }
+                if !closed {
+                    return false;
+                }
+            }
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    depth <= 0
+}
+
+/// Parser for flow collections (`[a, b]`, `{k: v}`)
+struct FlowParser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl FlowParser {
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('[') => self.parse_collection(']'),
+            Some('{') => self.parse_collection('}'),
+            Some('"' | '\'') => {
+                let rest: String = self.chars[self.pos..].iter().collect();
+                let (value, end) = parse_quoted(&rest)?;
+                self.pos += rest[..end].chars().count();
+                Ok(JsonValue::String(value))
+            }
+            Some('*') => Err("Aliases inside flow collections are not supported".to_string()),
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    let ends_key = c == ':' && self.chars.get(self.pos + 1).is_none_or(|n| " ,]}".contains(*n));
+                    if ",[]{}".contains(c) || ends_key {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                let plain: String = self.chars[start..self.pos].iter().collect();
+                Ok(resolve_plain(plain.trim()))
+            }
+        }
+    }
+
+    /// A `[...]` sequence or `{...}` mapping, with `pos` on the opener
+    fn parse_collection(&mut self, close: char) -> Result<JsonValue, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Nesting is too deep".to_string());
+        }
+        self.pos += 1;
+        let mut items = Vec::new();
+        let mut members: Vec<(String, JsonValue)> = Vec::new();
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(if close == ']' {
+                        JsonValue::Array(items)
+                    } else {
+                        JsonValue::Object(members)
+                    });
+                }
+                None => return Err(format!("Unterminated flow collection (missing '{}')", close)),
+                _ => {}
+            }
+
+            let value = self.parse_value()?;
+            self.skip_spaces();
+            if self.peek() == Some(':') {
+                self.pos += 1;
+                let key = match value {
+                    JsonValue::String(s) => s,
+                    JsonValue::Null => String::new(),
+                    other => plain_text(&other),
+                };
+                let member = self.parse_value()?;
+                if close == '}' {
+                    if members.iter().any(|(existing, _)| *existing == key) {
+                        return Err(format!("Duplicate key '{}'", key));
+                    }
+                    members.push((key, member));
+                } else {
+                    // `[a: 1]` is a sequence of single-pair mappings
+                    items.push(JsonValue::Object(vec![(key, member)]));
+                }
+            } else if close == '}' {
+                let key = plain_text(&value);
+                members.push((key, JsonValue::Null));
+            } else {
+                items.push(value);
+            }
+
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => {}
+                Some(c) => return Err(format!("Expected ',' or '{}' in flow collection, found '{}'", close, c)),
+                None => return Err(format!("Unterminated flow collection (missing '{}')", close)),
+            }
+        }
+    }
+}
+
+/// A scalar written back as text (for keys)
+fn plain_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Boolean(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Resolve a plain scalar with the YAML 1.2 core schema
+fn resolve_plain(text: &str) -> JsonValue {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return JsonValue::Null,
+        "true" | "True" | "TRUE" => return JsonValue::Boolean(true),
+        "false" | "False" | "FALSE" => return JsonValue::Boolean(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => return JsonValue::Number(f64::INFINITY),
+        "-.inf" | "-.Inf" | "-.INF" => return JsonValue::Number(f64::NEG_INFINITY),
+        ".nan" | ".NaN" | ".NAN" => return JsonValue::Number(f64::NAN),
+        _ => {}
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8)] {
+        if let Some(digits) = text.strip_prefix(prefix) {
+            if let Ok(n) = i64::from_str_radix(digits, radix) {
+                return JsonValue::Number(n as f64);
+            }
+        }
+    }
+    if is_core_number(text) {
+        if let Ok(n) = text.parse::<f64>() {
+            return JsonValue::Number(n);
+        }
+    }
+    JsonValue::String(text.to_string())
+}
+
+/// `[-+]? ( \.[0-9]+ | [0-9]+ ( \.[0-9]* )? ) ( [eE] [-+]? [0-9]+ )?`
+fn is_core_number(text: &str) -> bool {
+    let text = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let mantissa_ok = match fraction {
+        None => !whole.is_empty() && digits(whole),
+        Some(fraction) => digits(whole) && digits(fraction) && !(whole.is_empty() && fraction.is_empty()),
+    };
+    let exponent_ok = exponent.is_none_or(|e| {
+        let e = e.strip_prefix(['-', '+']).unwrap_or(e);
+        !e.is_empty() && digits(e)
+    });
+    mantissa_ok && exponent_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(input: &str) -> JsonValue {
+        let mut documents = parse_yaml(input).unwrap();
+        assert_eq!(documents.len(), 1);
+        documents.remove(0)
+    }
+
+    fn error(input: &str) -> ConfigSyntaxError {
+        parse_yaml(input).expect_err("input should be rejected")
+    }
+
+    #[test]
+    fn test_citation_cff() {
+        let doc = parse_one(
+            r#"# This CITATION.cff file was generated with cffinit.
+cff-version: 1.2.0
+title: "Ocean salinity: a survey"
+message: >-
+  If you use this dataset,
+  please cite it as below.
+type: dataset
+authors:
+  - given-names: Ada
+    family-names: Lovelace
+    orcid: 'https://orcid.org/0000-0002-1825-0097'
+  - name: "Analytical Engine Society"
+keywords: [ocean, salinity, "CTD #7"]
+version: 2.1
+date-released: 2024-03-01
+abstract: |
+  First line.
+    Indented line.
+
+  After a blank line.
+license: CC-BY-4.0
+"#,
+        );
+
+        let keys: Vec<&str> = doc.as_object().unwrap().iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["cff-version", "title", "message", "type", "authors", "keywords", "version", "date-released", "abstract", "license"]
+        );
+        assert_eq!(doc.get("cff-version").and_then(JsonValue::as_str), Some("1.2.0"));
+        assert_eq!(doc.get("title").and_then(JsonValue::as_str), Some("Ocean salinity: a survey"));
+        assert_eq!(
+            doc.get("message").and_then(JsonValue::as_str),
+            Some("If you use this dataset, please cite it as below.")
+        );
+        let authors = doc.get("authors").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].get("family-names").and_then(JsonValue::as_str), Some("Lovelace"));
+        assert_eq!(
+            authors[0].get("orcid").and_then(JsonValue::as_str),
+            Some("https://orcid.org/0000-0002-1825-0097")
+        );
+        let keywords = doc.get("keywords").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(keywords[2].as_str(), Some("CTD #7"));
+        assert_eq!(doc.get("version").and_then(JsonValue::as_f64), Some(2.1));
+        assert_eq!(doc.get("date-released").and_then(JsonValue::as_str), Some("2024-03-01"));
+        assert_eq!(
+            doc.get("abstract").and_then(JsonValue::as_str),
+            Some("First line.\n  Indented line.\n\nAfter a blank line.\n")
+        );
+    }
+
+    #[test]
+    fn test_structures_and_scalars() {
+        let doc = parse_one(
+            "defaults: &defaults\n  rate: 0x10\n  gain: -1.5e3\nrun:\n- id: 1\n  settings: *defaults\n- [a, {b: ~}]\n-\n  - nested\nflags: {on: true, off: False}\nempty:\nquoted: 'it''s \"fine\"'\nescaped: \"tab\\there\\u00e9\"\nlong: this plain scalar\n  continues here\n",
+        );
+        let defaults = doc.get("defaults").unwrap();
+        assert_eq!(defaults.get("rate").and_then(JsonValue::as_f64), Some(16.0));
+        assert_eq!(defaults.get("gain").and_then(JsonValue::as_f64), Some(-1500.0));
+
+        let run = doc.get("run").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(run.len(), 3);
+        assert_eq!(run[0].get("settings").and_then(|s| s.get("rate")).and_then(JsonValue::as_f64), Some(16.0));
+        assert_eq!(run[1].as_array().unwrap()[1].get("b"), Some(&JsonValue::Null));
+        assert_eq!(run[2].as_array().unwrap()[0].as_str(), Some("nested"));
+
+        assert_eq!(doc.get("flags").and_then(|f| f.get("off")), Some(&JsonValue::Boolean(false)));
+        assert_eq!(doc.get("empty"), Some(&JsonValue::Null));
+        assert_eq!(doc.get("quoted").and_then(JsonValue::as_str), Some("it's \"fine\""));
+        assert_eq!(doc.get("escaped").and_then(JsonValue::as_str), Some("tab\there\u{e9}"));
+        assert_eq!(doc.get("long").and_then(JsonValue::as_str), Some("this plain scalar continues here"));
+    }
+
+    #[test]
+    fn test_documents() {
+        let documents = parse_yaml("%YAML 1.2\n---\na: 1\n...\n---\n- x\n--- plain\n").unwrap();
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[0].get("a").and_then(JsonValue::as_f64), Some(1.0));
+        assert_eq!(documents[2].as_str(), Some("plain"));
+
+        assert!(parse_yaml("# only a comment\n").unwrap().is_empty());
+        assert_eq!(parse_yaml("---\n").unwrap(), vec![JsonValue::Null]);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let e = error("title: a\nauthors:\n  - name: x\n   bad: y\n");
+        assert_eq!((e.line, e.message.as_str()), (4, "Unexpected indentation"));
+
+        let e = error("a: 1\nb: 2\na: 3\n");
+        assert_eq!((e.line, e.message.as_str()), (3, "Duplicate key 'a'"));
+
+        let e = error("title: Survey: part 2\n");
+        assert_eq!(e.line, 1);
+        assert!(e.message.starts_with("Mapping values are not allowed here"));
+
+        // Reported where the colon is, not where the scalar starts
+        let e = error("a: 1\n  b: 2\n");
+        assert_eq!((e.line, e.message.as_str()), (2, "Mapping values are not allowed here"));
+        let e = error("a: one\n  two\n  three: 3\n");
+        assert_eq!(e.line, 3);
+
+        let e = error("a:\n\tb: 1\n");
+        assert_eq!((e.line, e.message.as_str()), (2, "Tab characters are not allowed in indentation"));
+
+        let e = error("keywords: [ocean,\n  salinity\nnext: 1\n");
+        assert_eq!((e.line, e.message.as_str()), (1, "Unterminated flow collection (missing ']' or '}')"));
+
+        let e = error("name: \"open\nnext: 1\n");
+        assert_eq!((e.line, e.message.as_str()), (1, "Unterminated quoted string"));
+
+        assert_eq!(error("a: *nowhere\n").message, "Unknown alias '*nowhere'");
+        assert_eq!(error("- a\nb: 1\n").message, "Unexpected content 'b: 1'");
+        assert_eq!(error("a: 1\n- b\n").message, "Expected a mapping key, found a sequence entry");
+        assert_eq!(error("x: \"\\q\"\n").message, "Invalid escape '\\q' in double-quoted string");
+
+        let deep = format!("{}x", "- ".repeat(500));
+        assert_eq!(error(&deep).message, "Nesting is too deep");
+    }
+}
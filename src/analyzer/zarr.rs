@@ -0,0 +1,511 @@
+//! Zarr store metadata analysis
+//!
+//! Reads the JSON metadata of a Zarr v2 store (`.zarray`, `.zgroup`,
+//! `.zattrs`) or v3 store (`zarr.json`) and, for each array, works out which
+//! chunk files the shape and chunk grid call for so absent chunks can be
+//! reported. Chunk contents are never read.
+
+use super::json::{parse_json, JsonValue};
+use super::AnalysisError;
+use crate::scanner::store_files;
+use crate::types::{ZarrAnalysis, ZarrArray, ZarrGroup};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Largest chunk grid checked for missing chunks
+const MAX_CHUNKS_CHECKED: u64 = 10_000_000;
+
+/// Missing chunk keys kept as examples
+const MAX_MISSING_EXAMPLES: usize = 10;
+
+/// Analyze the metadata of a Zarr store
+///
+/// # Arguments
+///
+/// * `dir` - Root directory of the store
+///
+/// # Returns
+///
+/// Every array and group in the store with its metadata, and for each
+/// array the chunk files that are expected but absent. Metadata files that
+/// cannot be parsed are listed as warnings.
+pub fn analyze_zarr(dir: &Path) -> Result<ZarrAnalysis, AnalysisError> {
+    let files = store_files(dir)?;
+    let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+
+    let mut analysis = ZarrAnalysis {
+        zarr_format: if present.contains("zarr.json") { 3 } else { 2 },
+        arrays: Vec::new(),
+        groups: Vec::new(),
+        file_count: files.len(),
+        warnings: Vec::new(),
+    };
+
+    for file in &files {
+        let (node_path, name) = match file.rsplit_once('/') {
+            Some((parent, name)) => (parent, name),
+            None => ("", file.as_str()),
+        };
+        let expected_name = if analysis.zarr_format == 3 { "zarr.json" } else { ".zarray" };
+        let is_group_v2 = analysis.zarr_format == 2 && name == ".zgroup";
+        if name != expected_name && !is_group_v2 {
+            continue;
+        }
+
+        let metadata = match read_json(&dir.join(file)) {
+            Ok(metadata) => metadata,
+            Err(message) => {
+                analysis.warnings.push(format!("{}: {}", file, message));
+                continue;
+            }
+        };
+
+        // v2 keeps attributes in a sibling `.zattrs`; v3 inlines them
+        let attributes = if analysis.zarr_format == 2 {
+            let attrs_file = join(node_path, ".zattrs");
+            if present.contains(attrs_file.as_str()) {
+                match read_json(&dir.join(&attrs_file)) {
+                    Ok(attrs) => attribute_list(Some(&attrs)),
+                    Err(message) => {
+                        analysis.warnings.push(format!("{}: {}", attrs_file, message));
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            }
+        } else {
+            attribute_list(metadata.get("attributes"))
+        };
+
+        let is_array = match analysis.zarr_format {
+            2 => name == ".zarray",
+            _ => metadata.get("node_type").and_then(JsonValue::as_str) == Some("array"),
+        };
+        if !is_array {
+            analysis.groups.push(ZarrGroup {
+                path: node_path.to_string(),
+                attributes,
+            });
+            continue;
+        }
+
+        let parsed = if analysis.zarr_format == 2 {
+            parse_array_v2(&metadata)
+        } else {
+            parse_array_v3(&metadata)
+        };
+        match parsed {
+            Ok((mut array, scheme)) => {
+                array.path = node_path.to_string();
+                array.attributes = attributes;
+                if let Err(message) = check_chunks(&mut array, &scheme, &present) {
+                    analysis.warnings.push(format!("{}: {}", file, message));
+                }
+                analysis.arrays.push(array);
+            }
+            Err(message) => analysis.warnings.push(format!("{}: {}", file, message)),
+        }
+    }
+
+    Ok(analysis)
+}
+
+/// How chunk indices map to file names
+struct ChunkKeys {
+    /// Key prefix (`c` for the v3 default encoding)
+    prefix: Option<&'static str>,
+    separator: String,
+}
+
+/// Read and parse a JSON metadata file
+fn read_json(path: &Path) -> Result<JsonValue, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot be read ({})", e))?;
+    parse_json(&text).map_err(|e| format!("invalid JSON ({})", e))
+}
+
+/// Join a node path and a name within the store
+fn join(node_path: &str, name: &str) -> String {
+    if node_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", node_path, name)
+    }
+}
+
+/// Parse a v2 `.zarray` document
+fn parse_array_v2(metadata: &JsonValue) -> Result<(ZarrArray, ChunkKeys), String> {
+    let shape = dimensions(metadata.get("shape"), "shape")?;
+    let chunks = dimensions(metadata.get("chunks"), "chunks")?;
+
+    let mut codecs: Vec<String> = metadata
+        .get("filters")
+        .and_then(JsonValue::as_array)
+        .unwrap_or(&[])
+        .iter()
+        .map(codec_name)
+        .collect();
+    if let Some(compressor) = metadata.get("compressor").filter(|c| **c != JsonValue::Null) {
+        codecs.push(codec_name(compressor));
+    }
+
+    let dtype = match metadata.get("dtype") {
+        Some(JsonValue::String(dtype)) => dtype.clone(),
+        Some(structured) => json_text(structured),
+        None => return Err("'dtype' is missing".to_string()),
+    };
+    let separator = metadata
+        .get("dimension_separator")
+        .and_then(JsonValue::as_str)
+        .unwrap_or(".")
+        .to_string();
+
+    Ok((
+        new_array(shape, chunks, dtype, codecs, metadata.get("fill_value"))?,
+        ChunkKeys {
+            prefix: None,
+            separator,
+        },
+    ))
+}
+
+/// Parse a v3 `zarr.json` array document
+fn parse_array_v3(metadata: &JsonValue) -> Result<(ZarrArray, ChunkKeys), String> {
+    let shape = dimensions(metadata.get("shape"), "shape")?;
+    let grid = metadata.get("chunk_grid");
+    if let Some(name) = grid.and_then(|g| g.get("name")).and_then(JsonValue::as_str) {
+        if name != "regular" {
+            return Err(format!("chunk grid '{}' is not supported", name));
+        }
+    }
+    let chunks = dimensions(
+        grid.and_then(|g| g.get("configuration")).and_then(|c| c.get("chunk_shape")),
+        "chunk_grid.configuration.chunk_shape",
+    )?;
+
+    let dtype = match metadata.get("data_type") {
+        Some(JsonValue::String(dtype)) => dtype.clone(),
+        Some(extension) => extension
+            .get("name")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| json_text(extension)),
+        None => return Err("'data_type' is missing".to_string()),
+    };
+    let codecs: Vec<String> = metadata
+        .get("codecs")
+        .and_then(JsonValue::as_array)
+        .unwrap_or(&[])
+        .iter()
+        .map(codec_name)
+        .filter(|name| name != "bytes")
+        .collect();
+
+    let encoding = metadata.get("chunk_key_encoding");
+    let v2_keys = encoding.and_then(|e| e.get("name")).and_then(JsonValue::as_str) == Some("v2");
+    let separator = encoding
+        .and_then(|e| e.get("configuration"))
+        .and_then(|c| c.get("separator"))
+        .and_then(JsonValue::as_str)
+        .unwrap_or(if v2_keys { "." } else { "/" })
+        .to_string();
+
+    Ok((
+        new_array(shape, chunks, dtype, codecs, metadata.get("fill_value"))?,
+        ChunkKeys {
+            prefix: if v2_keys { None } else { Some("c") },
+            separator,
+        },
+    ))
+}
+
+/// Assemble an array, checking the chunk shape against the shape
+fn new_array(
+    shape: Vec<u64>,
+    chunks: Vec<u64>,
+    dtype: String,
+    codecs: Vec<String>,
+    fill_value: Option<&JsonValue>,
+) -> Result<ZarrArray, String> {
+    if chunks.len() != shape.len() {
+        return Err(format!(
+            "chunk shape has {} dimensions but the array has {}",
+            chunks.len(),
+            shape.len()
+        ));
+    }
+    if chunks.contains(&0) {
+        return Err("chunk shape has a zero-length dimension".to_string());
+    }
+
+    let expected_chunks = shape
+        .iter()
+        .zip(&chunks)
+        .fold(1u64, |count, (&n, &c)| count.saturating_mul(n.div_ceil(c)));
+
+    Ok(ZarrArray {
+        path: String::new(),
+        shape,
+        chunks,
+        dtype,
+        compressor: if codecs.is_empty() { None } else { Some(codecs.join(", ")) },
+        fill_value: fill_value.map(json_text),
+        attributes: Vec::new(),
+        expected_chunks,
+        missing_chunks: Vec::new(),
+        missing_chunk_count: 0,
+    })
+}
+
+/// Read a list of non-negative integers
+fn dimensions(value: Option<&JsonValue>, name: &str) -> Result<Vec<u64>, String> {
+    let items = value
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| format!("'{}' is missing or not a list", name))?;
+    items
+        .iter()
+        .map(|item| match item.as_f64() {
+            Some(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as u64),
+            _ => Err(format!("'{}' has a value that is not a non-negative integer", name)),
+        })
+        .collect()
+}
+
+/// Name of a codec, compressor or filter (`blosc(zstd)` for Blosc)
+fn codec_name(codec: &JsonValue) -> String {
+    let name = codec
+        .get("name")
+        .or_else(|| codec.get("id"))
+        .and_then(JsonValue::as_str)
+        .unwrap_or("unknown");
+    let configuration = codec.get("configuration").unwrap_or(codec);
+
+    if name == "sharding_indexed" {
+        let inner: Vec<String> = configuration
+            .get("codecs")
+            .and_then(JsonValue::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .map(codec_name)
+            .filter(|inner| inner != "bytes")
+            .collect();
+        return format!("sharding_indexed({})", inner.join(", "));
+    }
+    match configuration.get("cname").and_then(JsonValue::as_str) {
+        Some(cname) => format!("{}({})", name, cname),
+        None => name.to_string(),
+    }
+}
+
+/// Attributes of an object as (name, JSON text) pairs
+fn attribute_list(attributes: Option<&JsonValue>) -> Vec<(String, String)> {
+    attributes
+        .and_then(JsonValue::as_object)
+        .unwrap_or(&[])
+        .iter()
+        .map(|(name, value)| (name.clone(), json_text(value)))
+        .collect()
+}
+
+/// Render a JSON value compactly
+fn json_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Boolean(b) => b.to_string(),
+        JsonValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        JsonValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(json_text).collect();
+            format!("[{}]", items.join(", "))
+        }
+        JsonValue::Object(members) => {
+            let members: Vec<String> = members
+                .iter()
+                .map(|(k, v)| format!("{}: {}", json_text(&JsonValue::String(k.clone())), json_text(v)))
+                .collect();
+            format!("{{{}}}", members.join(", "))
+        }
+    }
+}
+
+/// Record which of the array's expected chunk files are absent
+fn check_chunks(array: &mut ZarrArray, keys: &ChunkKeys, present: &HashSet<&str>) -> Result<(), String> {
+    if array.expected_chunks > MAX_CHUNKS_CHECKED {
+        return Err(format!(
+            "{} chunks are expected; chunk presence was not checked",
+            array.expected_chunks
+        ));
+    }
+    if array.expected_chunks == 0 {
+        return Ok(());
+    }
+
+    let grid: Vec<u64> = array
+        .shape
+        .iter()
+        .zip(&array.chunks)
+        .map(|(&n, &c)| n.div_ceil(c))
+        .collect();
+    let mut index = vec![0u64; grid.len()];
+
+    loop {
+        let mut parts: Vec<String> = keys.prefix.iter().map(|p| p.to_string()).collect();
+        if index.is_empty() && keys.prefix.is_none() {
+            parts.push("0".to_string());
+        }
+        parts.extend(index.iter().map(|i| i.to_string()));
+        let key = join(&array.path, &parts.join(&keys.separator));
+
+        if !present.contains(key.as_str()) {
+            array.missing_chunk_count += 1;
+            if array.missing_chunks.len() < MAX_MISSING_EXAMPLES {
+                array.missing_chunks.push(key);
+            }
+        }
+
+        // Advance the last dimension fastest
+        let mut dim = index.len();
+        loop {
+            if dim == 0 {
+                return Ok(());
+            }
+            dim -= 1;
+            index[dim] += 1;
+            if index[dim] < grid[dim] {
+                break;
+            }
+            index[dim] = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(test: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("genesis_preflight_zarr_{}", test));
+        fs::remove_dir_all(&dir).ok();
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_analyze_v2_store() {
+        let dir = store(
+            "v2",
+            &[
+                (".zgroup", r#"{"zarr_format": 2}"#),
+                (".zattrs", r#"{"title": "Ocean grid"}"#),
+                (
+                    "temp/.zarray",
+                    r#"{"zarr_format": 2, "shape": [4, 5], "chunks": [2, 3], "dtype": "<f4",
+                        "compressor": {"id": "blosc", "cname": "zstd", "clevel": 5},
+                        "filters": [{"id": "delta", "dtype": "<f4"}],
+                        "fill_value": "NaN", "order": "C"}"#,
+                ),
+                ("temp/.zattrs", r#"{"units": "K", "scale": [1, 0.5]}"#),
+                ("temp/0.0", "x"),
+                ("temp/0.1", "x"),
+                ("temp/1.1", "x"),
+            ],
+        );
+        let analysis = analyze_zarr(&dir).unwrap();
+
+        assert_eq!(analysis.zarr_format, 2);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+        assert_eq!(analysis.file_count, 7);
+        assert_eq!(analysis.groups.len(), 1);
+        assert_eq!(analysis.groups[0].attributes, vec![("title".to_string(), "\"Ocean grid\"".to_string())]);
+
+        let temp = &analysis.arrays[0];
+        assert_eq!(temp.path, "temp");
+        assert_eq!(temp.shape, vec![4, 5]);
+        assert_eq!(temp.chunks, vec![2, 3]);
+        assert_eq!(temp.dtype, "<f4");
+        assert_eq!(temp.compressor.as_deref(), Some("delta, blosc(zstd)"));
+        assert_eq!(temp.fill_value.as_deref(), Some("\"NaN\""));
+        assert_eq!(temp.attributes[1], ("scale".to_string(), "[1, 0.5]".to_string()));
+        assert_eq!(temp.expected_chunks, 4);
+        assert_eq!(temp.missing_chunk_count, 1);
+        assert_eq!(temp.missing_chunks, vec!["temp/1.0"]);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_analyze_v3_store() {
+        let dir = store(
+            "v3",
+            &[
+                ("zarr.json", r#"{"zarr_format": 3, "node_type": "group", "attributes": {}}"#),
+                (
+                    "wind/zarr.json",
+                    r#"{"zarr_format": 3, "node_type": "array", "shape": [10],
+                        "data_type": "float64",
+                        "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": [4]}},
+                        "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+                        "codecs": [{"name": "bytes", "configuration": {"endian": "little"}},
+                                   {"name": "zstd", "configuration": {"level": 3}}],
+                        "fill_value": 0, "attributes": {"units": "m/s"}}"#,
+                ),
+                ("wind/c/0", "x"),
+                ("wind/c/1", "x"),
+                ("wind/c/2", "x"),
+                (
+                    "flag/zarr.json",
+                    r#"{"zarr_format": 3, "node_type": "array", "shape": [],
+                        "data_type": "bool",
+                        "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": []}},
+                        "codecs": [{"name": "bytes"}], "fill_value": false}"#,
+                ),
+            ],
+        );
+        let analysis = analyze_zarr(&dir).unwrap();
+
+        assert_eq!(analysis.zarr_format, 3);
+        assert!(analysis.warnings.is_empty(), "{:?}", analysis.warnings);
+        assert_eq!(analysis.groups.len(), 1);
+        let paths: Vec<&str> = analysis.arrays.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, vec!["flag", "wind"]);
+
+        let flag = &analysis.arrays[0];
+        assert_eq!(flag.expected_chunks, 1);
+        assert_eq!(flag.missing_chunks, vec!["flag/c"]);
+        assert!(flag.compressor.is_none());
+
+        let wind = &analysis.arrays[1];
+        assert_eq!(wind.dtype, "float64");
+        assert_eq!(wind.compressor.as_deref(), Some("zstd"));
+        assert_eq!(wind.expected_chunks, 3);
+        assert_eq!(wind.missing_chunk_count, 0);
+        assert_eq!(wind.attributes, vec![("units".to_string(), "\"m/s\"".to_string())]);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_invalid_metadata_reported() {
+        let dir = store(
+            "invalid",
+            &[
+                (".zgroup", r#"{"zarr_format": 2"#),
+                ("a/.zarray", r#"{"shape": [4], "chunks": [2, 2], "dtype": "<i8"}"#),
+            ],
+        );
+        let analysis = analyze_zarr(&dir).unwrap();
+
+        assert!(analysis.arrays.is_empty());
+        assert_eq!(analysis.warnings.len(), 2);
+        assert!(analysis.warnings[0].starts_with(".zgroup: invalid JSON"));
+        assert!(analysis.warnings[1].contains("2 dimensions but the array has 1"));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,153 @@
+// Archive reading module
+// ZIP and tar listings and member streams implemented using only std library
+
+pub mod tar;
+pub mod zip;
+
+use crate::compression::Inflater;
+use crate::types::{ArchiveAnalysis, ArchiveEntry, ArchiveFormat, ArchiveMethod};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Separates an archive's path from a member's path (`data.zip!/raw/obs.csv`)
+pub const MEMBER_SEPARATOR: &str = "!/";
+
+/// An `InvalidData` error describing a damaged or unsupported archive
+pub(crate) fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Archive format of a path, by extension (`.zip`, `.tar`)
+///
+/// Compressed tarballs (`.tar.gz`, `.tgz`) are not read: members of a
+/// gzipped tar can only be reached by decompressing everything before them.
+pub fn format_from_path(path: &Path) -> Option<ArchiveFormat> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "zip" => Some(ArchiveFormat::Zip),
+        "tar" => Some(ArchiveFormat::Tar),
+        _ => None,
+    }
+}
+
+/// List the entries of a ZIP or tar archive
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error if the file is not a readable archive of
+/// the given format. Damage found part way through a tar archive is
+/// reported in the warnings instead.
+pub fn read_archive(path: &Path, format: ArchiveFormat) -> io::Result<ArchiveAnalysis> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let (entries, warnings) = match format {
+        ArchiveFormat::Zip => (zip::read_central_directory(&mut file, file_size)?, Vec::new()),
+        ArchiveFormat::Tar => tar::read_entries(&mut file, file_size)?,
+    };
+    Ok(ArchiveAnalysis {
+        format,
+        entries,
+        warnings,
+    })
+}
+
+/// Open a member of an archive for reading its (decompressed) contents
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error for entries that are not regular files,
+/// are encrypted, or use a compression method that cannot be decoded.
+/// Corrupt DEFLATE data is reported while reading, as an error recognised
+/// by [`crate::compression::is_corrupt`].
+pub fn open_member(archive: &Path, format: ArchiveFormat, entry: &ArchiveEntry) -> io::Result<Box<dyn Read>> {
+    if !entry.is_readable() {
+        let reason = if entry.encrypted {
+            "is encrypted".to_string()
+        } else {
+            match &entry.method {
+                ArchiveMethod::Unsupported(method) => format!("uses unsupported compression ({})", method),
+                _ => "is not a regular file".to_string(),
+            }
+        };
+        return Err(invalid(format!("member '{}' {}", entry.name, reason)));
+    }
+
+    let mut file = File::open(archive)?;
+    let data_offset = match format {
+        ArchiveFormat::Zip => {
+            let file_size = file.metadata()?.len();
+            zip::data_offset(&mut file, file_size, entry)?
+        }
+        ArchiveFormat::Tar => entry.offset,
+    };
+    file.seek(SeekFrom::Start(data_offset))?;
+    let data = file.take(entry.compressed_size);
+
+    Ok(match entry.method {
+        ArchiveMethod::Deflate => Box::new(Inflater::new(data).take(entry.size)),
+        _ => Box::new(data),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::fixture::hex;
+    use crate::testing::write_temp_file;
+    use std::fs;
+
+    fn read_member(path: &Path, format: ArchiveFormat, entry: &ArchiveEntry) -> io::Result<Vec<u8>> {
+        let mut contents = Vec::new();
+        open_member(path, format, entry)?.read_to_end(&mut contents)?;
+        Ok(contents)
+    }
+
+    #[test]
+    fn test_zip_members() {
+        // Raw DEFLATE of "id,value\n1,2.5\n" (the body of the gzip fixture)
+        let deflated = hex("cb4cd1294bcc294de532d431d233e50200");
+        let bytes = zip::fixture::zip_sized(&[
+            ("raw/", 0, b"", 0),
+            ("raw/obs.csv", 8, &deflated, 15),
+            ("notes.txt", 0, b"hello\n", 6),
+            ("model.bin", 14, b"lzma", 4),
+        ]);
+
+        let path = write_temp_file("genesis_preflight_archive_zip", "data.zip", &bytes);
+        let analysis = read_archive(&path, ArchiveFormat::Zip).unwrap();
+        let names: Vec<&str> = analysis.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["raw/", "raw/obs.csv", "notes.txt", "model.bin"]);
+
+        let entries = &analysis.entries;
+        assert_eq!(entries[0].kind, crate::types::ArchiveEntryKind::Directory);
+        assert_eq!(read_member(&path, ArchiveFormat::Zip, &entries[1]).unwrap(), b"id,value\n1,2.5\n");
+        assert_eq!(read_member(&path, ArchiveFormat::Zip, &entries[2]).unwrap(), b"hello\n");
+        assert_eq!(entries[3].method, ArchiveMethod::Unsupported("lzma".to_string()));
+        let error = read_member(&path, ArchiveFormat::Zip, &entries[3]).unwrap_err();
+        assert_eq!(error.to_string(), "member 'model.bin' uses unsupported compression (lzma)");
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_tar_members() {
+        let bytes = tar::fixture::tar(&[("obs.csv", b"id,value\n1,2.5\n"), ("notes.txt", b"hello\n")]);
+        let path = write_temp_file("genesis_preflight_archive_tar", "data.tar", &bytes);
+        let analysis = read_archive(&path, ArchiveFormat::Tar).unwrap();
+        assert_eq!(analysis.entries.len(), 2);
+        assert_eq!(read_member(&path, ArchiveFormat::Tar, &analysis.entries[1]).unwrap(), b"hello\n");
+
+        assert!(read_archive(&path, ArchiveFormat::Zip).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(format_from_path(Path::new("a/data.ZIP")), Some(ArchiveFormat::Zip));
+        assert_eq!(format_from_path(Path::new("data.tar")), Some(ArchiveFormat::Tar));
+        assert_eq!(format_from_path(Path::new("data.tar.gz")), None);
+        assert_eq!(format_from_path(Path::new("weights.npz")), None);
+    }
+}
@@ -0,0 +1,363 @@
+//! tar archive listing
+//!
+//! A tar archive is a sequence of 512-byte headers, each followed by the
+//! entry's data padded to a whole block, and ends with two zero blocks.
+//! POSIX ustar headers split long names into a prefix and a name; GNU tar
+//! stores them in `L`/`K` pseudo-entries and pax in `x` extended headers.
+//!
+//! ## References
+//!
+//! - POSIX.1-2017 pax, ustar Interchange Format: https://pubs.opengroup.org/onlinepubs/9699919799/utilities/pax.html
+//! - GNU tar Basic Tar Format: https://www.gnu.org/software/tar/manual/html_node/Standard.html
+
+use super::invalid;
+use crate::types::{ArchiveEntry, ArchiveEntryKind, ArchiveMethod};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Size of a header and of the data padding unit
+const BLOCK_SIZE: u64 = 512;
+
+/// Largest GNU long name or pax header read into memory
+const MAX_EXTENDED_HEADER: u64 = 1024 * 1024;
+
+/// Read every entry header of a tar archive
+///
+/// # Returns
+///
+/// The entries, and warnings for damage after the first header (a bad
+/// checksum, a missing end marker, data past the end of the file); the
+/// listing stops at the first such problem. A file whose first header is
+/// not a valid tar header is an error.
+pub fn read_entries(file: &mut File, file_size: u64) -> io::Result<(Vec<ArchiveEntry>, Vec<String>)> {
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    let mut offset = 0u64;
+    let mut long_name: Option<String> = None;
+    let mut long_link: Option<String> = None;
+    let mut pax: Vec<(String, String)> = Vec::new();
+
+    loop {
+        if offset + BLOCK_SIZE > file_size {
+            if offset == 0 {
+                return Err(invalid("tar header is truncated"));
+            }
+            if offset < file_size {
+                warnings.push(format!("archive is truncated at byte {}", offset));
+            } else {
+                warnings.push("end-of-archive marker is missing".to_string());
+            }
+            break;
+        }
+
+        let mut header = [0u8; BLOCK_SIZE as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        if !checksum_matches(&header) {
+            if offset == 0 {
+                return Err(invalid("tar header checksum does not match"));
+            }
+            warnings.push(format!("header at byte {} has a bad checksum; listing stopped", offset));
+            break;
+        }
+
+        let size = match number(&header[124..136]) {
+            Some(size) => size,
+            None => {
+                warnings.push(format!("header at byte {} has an unreadable size; listing stopped", offset));
+                break;
+            }
+        };
+        let data_offset = offset + BLOCK_SIZE;
+        let type_flag = header[156];
+
+        // Pseudo-entries that describe the next header
+        match type_flag {
+            b'L' | b'K' | b'x' | b'g' => {
+                if size > MAX_EXTENDED_HEADER || data_offset.saturating_add(size) > file_size {
+                    warnings.push(format!("extended header at byte {} is too large or truncated", offset));
+                    break;
+                }
+                let mut data = vec![0u8; size as usize];
+                file.read_exact(&mut data)?;
+                match type_flag {
+                    b'L' => long_name = Some(field(&data)),
+                    b'K' => long_link = Some(field(&data)),
+                    b'x' => pax = pax_records(&data),
+                    // Global pax headers set defaults we have no use for
+                    _ => {}
+                }
+                offset = data_offset + padded(size);
+                continue;
+            }
+            // GNU volume label
+            b'V' => {
+                offset = data_offset + padded(size);
+                continue;
+            }
+            _ => {}
+        }
+
+        let ustar = &header[257..263] == b"ustar\0";
+        let mut name = field(&header[..100]);
+        let prefix = field(&header[345..500]);
+        if ustar && !prefix.is_empty() {
+            name = format!("{}/{}", prefix, name);
+        }
+        let mut link = field(&header[157..257]);
+        let mut size = size;
+
+        if let Some(long) = long_name.take() {
+            name = long;
+        }
+        if let Some(long) = long_link.take() {
+            link = long;
+        }
+        for (key, value) in pax.drain(..) {
+            match key.as_str() {
+                "path" => name = value,
+                "linkpath" => link = value,
+                "size" => size = value.parse().unwrap_or(size),
+                _ => {}
+            }
+        }
+
+        let (kind, method) = match type_flag {
+            b'0' | b'\0' | b'7' => (ArchiveEntryKind::File, ArchiveMethod::Stored),
+            b'1' | b'2' => (ArchiveEntryKind::Link, ArchiveMethod::Stored),
+            b'5' => (ArchiveEntryKind::Directory, ArchiveMethod::Stored),
+            b'3' => (ArchiveEntryKind::Other("character device".to_string()), ArchiveMethod::Stored),
+            b'4' => (ArchiveEntryKind::Other("block device".to_string()), ArchiveMethod::Stored),
+            b'6' => (ArchiveEntryKind::Other("FIFO".to_string()), ArchiveMethod::Stored),
+            b'S' => (ArchiveEntryKind::File, ArchiveMethod::Unsupported("GNU sparse".to_string())),
+            b'M' => (ArchiveEntryKind::File, ArchiveMethod::Unsupported("GNU multi-volume".to_string())),
+            other => (
+                ArchiveEntryKind::Other(format!("type '{}'", other as char)),
+                ArchiveMethod::Stored,
+            ),
+        };
+        // Old tar marks directories only by a trailing slash
+        let kind = if kind == ArchiveEntryKind::File && type_flag == b'\0' && name.ends_with('/') {
+            ArchiveEntryKind::Directory
+        } else {
+            kind
+        };
+        // Links and directories carry no data whatever the size field says
+        let stored = match kind {
+            ArchiveEntryKind::Link | ArchiveEntryKind::Directory => 0,
+            _ => size,
+        };
+
+        if data_offset.saturating_add(stored) > file_size {
+            warnings.push(format!("data of '{}' is past the end of the archive", name));
+            break;
+        }
+        entries.push(ArchiveEntry {
+            name,
+            link_target: (kind == ArchiveEntryKind::Link).then_some(link),
+            kind,
+            method,
+            encrypted: false,
+            size: stored,
+            compressed_size: stored,
+            offset: data_offset,
+        });
+        offset = data_offset + padded(stored);
+    }
+
+    Ok((entries, warnings))
+}
+
+/// Whether a header's checksum field matches its bytes
+///
+/// The checksum is the sum of the header with the checksum field taken as
+/// spaces; some old implementations summed signed bytes, so both are accepted.
+fn checksum_matches(header: &[u8]) -> bool {
+    let stored = match number(&header[148..156]) {
+        Some(value) => value as i64,
+        None => return false,
+    };
+    let mut unsigned = 0i64;
+    let mut signed = 0i64;
+    for (i, &byte) in header.iter().enumerate() {
+        let byte = if (148..156).contains(&i) { b' ' } else { byte };
+        unsigned += byte as i64;
+        signed += byte as i8 as i64;
+    }
+    stored == unsigned || stored == signed
+}
+
+/// A numeric header field: octal text, or GNU base-256 when the top bit is set
+fn number(raw: &[u8]) -> Option<u64> {
+    if raw.first().is_some_and(|&b| b & 0x80 != 0) {
+        if raw[0] & 0x40 != 0 {
+            // Negative base-256 values are meaningless for sizes
+            return None;
+        }
+        let mut value = (raw[0] & 0x3F) as u64;
+        for &byte in &raw[1..] {
+            value = value.checked_mul(256)?.checked_add(byte as u64)?;
+        }
+        return Some(value);
+    }
+    let text = std::str::from_utf8(raw).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(text, 8).ok()
+}
+
+/// A NUL-terminated text field
+fn field(raw: &[u8]) -> String {
+    let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+    String::from_utf8_lossy(&raw[..end]).into_owned()
+}
+
+/// Parse pax extended header records (`<length> <key>=<value>\n`)
+fn pax_records(data: &[u8]) -> Vec<(String, String)> {
+    let mut records = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let space = match data[pos..].iter().position(|&b| b == b' ') {
+            Some(space) => pos + space,
+            None => break,
+        };
+        let length: usize = match std::str::from_utf8(&data[pos..space]).ok().and_then(|n| n.parse().ok()) {
+            Some(length) if length > space - pos && pos + length <= data.len() => length,
+            _ => break,
+        };
+        let record = String::from_utf8_lossy(&data[space + 1..pos + length]);
+        if let Some((key, value)) = record.trim_end_matches('\n').split_once('=') {
+            records.push((key.to_string(), value.to_string()));
+        }
+        pos += length;
+    }
+    records
+}
+
+/// Size rounded up to whole blocks
+fn padded(size: u64) -> u64 {
+    size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Builds small tar archives for tests
+
+    /// A ustar header for `name` with the given type flag, size and link target
+    pub fn header(name: &str, type_flag: u8, size: usize, link: &str) -> Vec<u8> {
+        let mut block = vec![0u8; 512];
+        block[..name.len()].copy_from_slice(name.as_bytes());
+        block[100..108].copy_from_slice(b"0000644\0");
+        block[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+        block[136..148].copy_from_slice(b"00000000000\0");
+        block[156] = type_flag;
+        block[157..157 + link.len()].copy_from_slice(link.as_bytes());
+        block[257..263].copy_from_slice(b"ustar\0");
+        block[263..265].copy_from_slice(b"00");
+        block[148..156].copy_from_slice(b"        ");
+        let sum: u32 = block.iter().map(|&b| b as u32).sum();
+        block[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        block
+    }
+
+    /// A tar archive of regular files, ended by two zero blocks
+    pub fn tar(members: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (name, data) in members {
+            entry(&mut out, name, b'0', data, "");
+        }
+        out.resize(out.len() + 1024, 0);
+        out
+    }
+
+    /// Append one entry with its data padded to a whole block
+    pub fn entry(out: &mut Vec<u8>, name: &str, type_flag: u8, data: &[u8], link: &str) {
+        out.extend(header(name, type_flag, data.len(), link));
+        out.extend_from_slice(data);
+        out.resize(out.len().div_ceil(512) * 512, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn list(test: &str, bytes: &[u8]) -> io::Result<(Vec<ArchiveEntry>, Vec<String>)> {
+        let temp_dir = std::env::temp_dir().join(format!("genesis_preflight_tar_{}", test));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("data.tar");
+        fs::write(&path, bytes).unwrap();
+        let mut file = File::open(&path).unwrap();
+        let result = read_entries(&mut file, bytes.len() as u64);
+        fs::remove_dir_all(&temp_dir).ok();
+        result
+    }
+
+    #[test]
+    fn test_list_entries() {
+        let mut bytes = Vec::new();
+        fixture::entry(&mut bytes, "raw/", b'5', b"", "");
+        fixture::entry(&mut bytes, "raw/obs.csv", b'0', b"id,value\n1,2.5\n", "");
+        fixture::entry(&mut bytes, "raw/latest.csv", b'2', b"", "obs.csv");
+        bytes.resize(bytes.len() + 1024, 0);
+
+        let (entries, warnings) = list("entries", &bytes).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].kind, ArchiveEntryKind::Directory);
+        assert_eq!(entries[1].name, "raw/obs.csv");
+        assert_eq!(entries[1].size, 15);
+        assert_eq!(entries[1].offset, 1024);
+        assert_eq!(entries[2].kind, ArchiveEntryKind::Link);
+        assert_eq!(entries[2].link_target.as_deref(), Some("obs.csv"));
+    }
+
+    #[test]
+    fn test_long_names() {
+        let long = format!("{}/obs.csv", "d".repeat(120));
+        let mut bytes = Vec::new();
+        let mut name = long.clone().into_bytes();
+        name.push(0);
+        fixture::entry(&mut bytes, "././@LongLink", b'L', &name, "");
+        fixture::entry(&mut bytes, &long[..100], b'0', b"1\n", "");
+        let pax = "28 path=pax/named/table.csv\n";
+        fixture::entry(&mut bytes, "PaxHeaders/x", b'x', pax.as_bytes(), "");
+        fixture::entry(&mut bytes, "table.csv", b'0', b"2\n", "");
+        bytes.resize(bytes.len() + 1024, 0);
+
+        let (entries, _) = list("long", &bytes).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec![long.as_str(), "pax/named/table.csv"]);
+    }
+
+    #[test]
+    fn test_damaged_archives() {
+        assert!(list("not_tar", &[b'x'; 1024]).is_err());
+
+        // Truncated inside the data of the second entry
+        let bytes = fixture::tar(&[("a.csv", b"1\n"), ("b.csv", &[b'2'; 2000])]);
+        let (entries, warnings) = list("truncated", &bytes[..2048]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(warnings, vec!["data of 'b.csv' is past the end of the archive"]);
+
+        let mut bytes = fixture::tar(&[("a.csv", b"1\n"), ("b.csv", b"2\n")]);
+        bytes[1024 + 10] ^= 0xFF;
+        let (entries, warnings) = list("checksum", &bytes).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(warnings[0].contains("bad checksum"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(number(b"00000001750\0"), Some(1000));
+        assert_eq!(number(b"           \0"), Some(0));
+        assert_eq!(number(&[0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0]), Some(0x1000));
+        assert_eq!(number(b"12x"), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! ZIP central directory reading
+//!
+//! A ZIP archive is listed by the central directory at its end, found
+//! through the end-of-central-directory record (and its ZIP64 variant for
+//! archives over 4 GiB or 65535 entries). Member data is located through
+//! the local header each directory entry points to.
+//!
+//! ## References
+//!
+//! - PKWARE APPNOTE.TXT 6.3: https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+
+use super::invalid;
+use crate::types::{ArchiveEntry, ArchiveEntryKind, ArchiveMethod};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+pub(crate) const ZIP_LOCAL_HEADER: u32 = 0x0403_4b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x0201_4b50;
+const ZIP_END_OF_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP64_END_OF_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+
+/// Size of the end of central directory record without its comment
+const EOCD_SIZE: usize = 22;
+
+/// Largest central directory read into memory
+const MAX_DIRECTORY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Unix file type bits of the external attributes (made by a Unix host)
+const UNIX_TYPE_MASK: u32 = 0o170000;
+const UNIX_SYMLINK: u32 = 0o120000;
+const UNIX_DIRECTORY: u32 = 0o040000;
+const UNIX_REGULAR: u32 = 0o100000;
+
+/// Read the central directory of a ZIP archive (including ZIP64)
+pub fn read_central_directory(file: &mut File, file_size: u64) -> io::Result<Vec<ArchiveEntry>> {
+    // The end record sits in the last 22 bytes plus an archive comment of up to 64 KiB
+    let tail_size = file_size.min(EOCD_SIZE as u64 + 0xFFFF);
+    let tail_start = file_size - tail_size;
+    let mut tail = vec![0u8; tail_size as usize];
+    file.seek(SeekFrom::Start(tail_start))?;
+    file.read_exact(&mut tail)?;
+
+    let eocd = (0..tail.len().saturating_sub(EOCD_SIZE - 1))
+        .rev()
+        .find(|&i| le32(&tail, i) == ZIP_END_OF_DIRECTORY)
+        .ok_or_else(|| invalid("ZIP end of central directory not found (the archive may be truncated)"))?;
+
+    let mut entry_count = le16(&tail, eocd + 10) as u64;
+    let mut directory_size = le32(&tail, eocd + 12) as u64;
+    let mut directory_offset = le32(&tail, eocd + 16) as u64;
+
+    if entry_count == 0xFFFF || directory_size == 0xFFFF_FFFF || directory_offset == 0xFFFF_FFFF {
+        if eocd < 20 || le32(&tail, eocd - 20) != ZIP64_LOCATOR {
+            return Err(invalid("ZIP64 end of central directory locator not found"));
+        }
+        let offset = le64(&tail, eocd - 12);
+        let mut record = [0u8; 56];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut record)?;
+        if le32(&record, 0) != ZIP64_END_OF_DIRECTORY {
+            return Err(invalid("ZIP64 end of central directory not found"));
+        }
+        entry_count = le64(&record, 32);
+        directory_size = le64(&record, 40);
+        directory_offset = le64(&record, 48);
+    }
+
+    if directory_size > MAX_DIRECTORY_SIZE || directory_offset.saturating_add(directory_size) > file_size {
+        return Err(invalid("ZIP central directory is outside the archive"));
+    }
+    let mut directory = vec![0u8; directory_size as usize];
+    file.seek(SeekFrom::Start(directory_offset))?;
+    file.read_exact(&mut directory)?;
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    for _ in 0..entry_count {
+        if pos + 46 > directory.len() || le32(&directory, pos) != ZIP_CENTRAL_HEADER {
+            return Err(invalid("ZIP central directory is damaged"));
+        }
+        let name_len = le16(&directory, pos + 28) as usize;
+        let extra_len = le16(&directory, pos + 30) as usize;
+        let comment_len = le16(&directory, pos + 32) as usize;
+        let end = pos + 46 + name_len + extra_len + comment_len;
+        if end > directory.len() {
+            return Err(invalid("ZIP central directory is damaged"));
+        }
+
+        let mut size = le32(&directory, pos + 24) as u64;
+        let mut compressed_size = le32(&directory, pos + 20) as u64;
+        let mut offset = le32(&directory, pos + 42) as u64;
+
+        // ZIP64 extra field: the 32-bit values that overflowed, in this order
+        let extra = &directory[pos + 46 + name_len..pos + 46 + name_len + extra_len];
+        let mut field = 0;
+        while field + 4 <= extra.len() {
+            let id = le16(extra, field);
+            let length = le16(extra, field + 2) as usize;
+            let data = &extra[field + 4..(field + 4 + length).min(extra.len())];
+            if id == 0x0001 {
+                let mut values = data.chunks_exact(8).map(|chunk| le64(chunk, 0));
+                for value in [&mut size, &mut compressed_size, &mut offset] {
+                    if *value == 0xFFFF_FFFF {
+                        *value = values.next().unwrap_or(*value);
+                    }
+                }
+            }
+            field += 4 + length;
+        }
+
+        let name = String::from_utf8_lossy(&directory[pos + 46..pos + 46 + name_len]).into_owned();
+        let made_by_unix = directory[pos + 5] == 3;
+        let unix_type = (le32(&directory, pos + 38) >> 16) & UNIX_TYPE_MASK;
+        let kind = if name.ends_with('/') || (made_by_unix && unix_type == UNIX_DIRECTORY) {
+            ArchiveEntryKind::Directory
+        } else if made_by_unix && unix_type == UNIX_SYMLINK {
+            ArchiveEntryKind::Link
+        } else if made_by_unix && unix_type != 0 && unix_type != UNIX_REGULAR {
+            ArchiveEntryKind::Other(format!("file mode {:o}", unix_type))
+        } else {
+            ArchiveEntryKind::File
+        };
+
+        entries.push(ArchiveEntry {
+            name,
+            kind,
+            method: method(le16(&directory, pos + 10)),
+            encrypted: le16(&directory, pos + 8) & 1 != 0,
+            link_target: None,
+            size,
+            compressed_size,
+            offset,
+        });
+        pos = end;
+    }
+
+    Ok(entries)
+}
+
+/// Offset of a member's data, past its local header
+pub fn data_offset(file: &mut File, file_size: u64, entry: &ArchiveEntry) -> io::Result<u64> {
+    let mut local = [0u8; 30];
+    file.seek(SeekFrom::Start(entry.offset))
+        .and_then(|_| file.read_exact(&mut local))
+        .map_err(|_| invalid("local header is past the end of the archive"))?;
+    if le32(&local, 0) != ZIP_LOCAL_HEADER {
+        return Err(invalid("local header signature not found"));
+    }
+    let data_offset = entry.offset + 30 + le16(&local, 26) as u64 + le16(&local, 28) as u64;
+    if data_offset.saturating_add(entry.compressed_size) > file_size {
+        return Err(invalid("data is past the end of the archive"));
+    }
+    Ok(data_offset)
+}
+
+/// Compression method of a ZIP member
+fn method(code: u16) -> ArchiveMethod {
+    match code {
+        0 => ArchiveMethod::Stored,
+        8 => ArchiveMethod::Deflate,
+        9 => ArchiveMethod::Unsupported("deflate64".to_string()),
+        12 => ArchiveMethod::Unsupported("bzip2".to_string()),
+        14 => ArchiveMethod::Unsupported("lzma".to_string()),
+        93 => ArchiveMethod::Unsupported("zstd".to_string()),
+        95 => ArchiveMethod::Unsupported("xz".to_string()),
+        other => ArchiveMethod::Unsupported(format!("method {}", other)),
+    }
+}
+
+pub(crate) fn le16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+pub(crate) fn le32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn le64(bytes: &[u8], at: usize) -> u64 {
+    let mut raw = [0u8; 8];
+    raw.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(raw)
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    //! Builds small ZIP archives for tests
+
+    /// A ZIP archive of the given members, stored (method 0) unless
+    /// `method` says otherwise; member bytes are written as given
+    pub fn zip(members: &[(&str, u16, &[u8])]) -> Vec<u8> {
+        let sized: Vec<(&str, u16, &[u8], u32)> = members
+            .iter()
+            .map(|&(name, method, data)| (name, method, data, data.len() as u32))
+            .collect();
+        zip_sized(&sized)
+    }
+
+    /// Like [`zip`], with the uncompressed size of each member given
+    /// (for members written already deflated)
+    pub fn zip_sized(members: &[(&str, u16, &[u8], u32)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut directory = Vec::new();
+        for (name, method, data, size) in members {
+            let offset = out.len() as u32;
+            let mut common = Vec::new();
+            common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            common.extend_from_slice(&0u16.to_le_bytes()); // flags
+            common.extend_from_slice(&method.to_le_bytes());
+            common.extend_from_slice(&[0u8; 8]); // time, date, crc
+            common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            common.extend_from_slice(&size.to_le_bytes());
+            common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+            out.extend_from_slice(b"PK\x03\x04");
+            out.extend_from_slice(&common);
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(data);
+
+            directory.extend_from_slice(b"PK\x01\x02");
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            directory.extend_from_slice(&common);
+            directory.extend_from_slice(&[0u8; 6]); // comment length, disk, internal attributes
+            directory.extend_from_slice(&[0u8; 4]); // external attributes
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+
+        let directory_offset = out.len() as u32;
+        out.extend_from_slice(&directory);
+        out.extend_from_slice(b"PK\x05\x06");
+        out.extend_from_slice(&[0u8; 4]); // disk numbers
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        out
+    }
+}
@@ -0,0 +1,398 @@
+//! Bag creation
+
+use super::{encode_manifest_path, BagError, BAGIT_VERSION, PAYLOAD_MANIFEST, TAG_MANIFEST};
+use crate::analyzer::{parse_json, JsonValue};
+use crate::crypto::sha256_file;
+use crate::scanner::store_files;
+use crate::types::{FileInfo, FileType};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Summary of a bag written by `create_bag`
+#[derive(Debug, Clone)]
+pub struct BagSummary {
+    /// Root directory of the bag
+    pub bag_dir: PathBuf,
+    /// Number of payload files
+    pub payload_files: usize,
+    /// Total payload size in bytes
+    pub payload_bytes: u64,
+    /// Payload-Oxum value written to bag-info.txt
+    pub payload_oxum: String,
+}
+
+/// Package dataset files as a BagIt bag
+///
+/// Copies every file into `<out_dir>/data/`, preserving relative paths
+/// (Zarr stores are copied file by file), and
+/// writes `bagit.txt`, `manifest-sha256.txt`, `bag-info.txt` and
+/// `tagmanifest-sha256.txt`. bag-info.txt is populated from the dataset's
+/// metadata.json where available. Hashes are computed from the copied files
+/// so the manifest reflects what actually landed in the bag.
+///
+/// # Arguments
+///
+/// * `files` - Files to package (from the scanner)
+/// * `dataset_root` - Root directory of the dataset
+/// * `out_dir` - Bag directory to create (must not exist or be empty)
+/// * `bagging_date` - Bagging-Date value (YYYY-MM-DD)
+///
+/// # Returns
+///
+/// A BagSummary describing the payload, or a BagError.
+pub fn create_bag(
+    files: &[FileInfo],
+    dataset_root: &Path,
+    out_dir: &Path,
+    bagging_date: &str,
+) -> Result<BagSummary, BagError> {
+    check_output_dir(dataset_root, out_dir)?;
+
+    let data_dir = out_dir.join("data");
+    fs::create_dir_all(&data_dir)?;
+
+    // Zarr stores are one scanned entry but BagIt lists every payload file
+    let mut sorted: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for file in files {
+        // Archive members travel inside their archive
+        if file.is_archive_member() {
+            continue;
+        }
+        if file.file_type == FileType::Zarr {
+            for member in store_files(&file.full_path)? {
+                sorted.push((file.full_path.join(&member), file.relative_path.join(&member)));
+            }
+        } else {
+            sorted.push((file.full_path.clone(), file.relative_path.clone()));
+        }
+    }
+    sorted.sort_by(|a, b| a.1.cmp(&b.1));
+
+    // Copy payload and hash the copies
+    let mut manifest = String::new();
+    let mut payload_bytes: u64 = 0;
+
+    for (source, relative_path) in &sorted {
+        let dest = data_dir.join(relative_path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        payload_bytes += fs::copy(source, &dest)?;
+
+        let hash = sha256_file(&dest)?;
+        let bag_path = format!(
+            "data/{}",
+            relative_path.to_string_lossy().replace('\\', "/")
+        );
+        manifest.push_str(&format!("{}  {}\n", hash, encode_manifest_path(&bag_path)));
+    }
+
+    let payload_oxum = format!("{}.{}", payload_bytes, sorted.len());
+
+    // Tag files
+    fs::write(
+        out_dir.join("bagit.txt"),
+        format!(
+            "BagIt-Version: {}\nTag-File-Character-Encoding: UTF-8\n",
+            BAGIT_VERSION
+        ),
+    )?;
+    fs::write(out_dir.join(PAYLOAD_MANIFEST), &manifest)?;
+
+    let metadata = read_metadata(files);
+    let bag_info = generate_bag_info(metadata.as_ref(), bagging_date, &payload_oxum);
+    fs::write(out_dir.join("bag-info.txt"), bag_info)?;
+
+    let mut tag_manifest = String::new();
+    for tag_file in ["bag-info.txt", "bagit.txt", PAYLOAD_MANIFEST] {
+        let hash = sha256_file(&out_dir.join(tag_file))?;
+        tag_manifest.push_str(&format!("{}  {}\n", hash, tag_file));
+    }
+    fs::write(out_dir.join(TAG_MANIFEST), tag_manifest)?;
+
+    Ok(BagSummary {
+        bag_dir: out_dir.to_path_buf(),
+        payload_files: sorted.len(),
+        payload_bytes,
+        payload_oxum,
+    })
+}
+
+/// Ensure the output directory is outside the dataset and usable
+///
+/// Containment is checked before anything is created, so a rejected
+/// output directory is never made (and one the user made is never removed).
+fn check_output_dir(dataset_root: &Path, out_dir: &Path) -> Result<(), BagError> {
+    let root = fs::canonicalize(dataset_root)?;
+    if resolve_path(out_dir)?.starts_with(&root) {
+        return Err(BagError::OutputInsideDataset(out_dir.to_path_buf()));
+    }
+
+    if out_dir.exists() {
+        if fs::read_dir(out_dir)?.next().is_some() {
+            return Err(BagError::OutputNotEmpty(out_dir.to_path_buf()));
+        }
+    } else {
+        fs::create_dir_all(out_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Absolute form of a path that may not exist yet
+///
+/// The part that exists is canonicalized (resolving symlinks); the rest is
+/// appended with `.` and `..` applied lexically.
+fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let mut resolved = PathBuf::new();
+    let mut exists = true;
+    for component in std::env::current_dir()?.join(path).components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir if !exists => {
+                resolved.pop();
+            }
+            Component::ParentDir | Component::Normal(_) => {
+                resolved.push(component);
+                if exists {
+                    match fs::canonicalize(&resolved) {
+                        Ok(canonical) => resolved = canonical,
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => exists = false,
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Read and parse the dataset's top-level metadata.json
+fn read_metadata(files: &[FileInfo]) -> Option<JsonValue> {
+    let meta_file = files
+        .iter()
+        .find(|f| f.relative_path == Path::new("metadata.json"))?;
+    let content = fs::read_to_string(&meta_file.full_path).ok()?;
+    parse_json(&content).ok()
+}
+
+/// Generate bag-info.txt content
+///
+/// Fields that are missing from metadata.json (or still TODO) are omitted
+/// rather than written as placeholders.
+fn generate_bag_info(
+    metadata: Option<&JsonValue>,
+    bagging_date: &str,
+    payload_oxum: &str,
+) -> String {
+    let mut info = String::new();
+
+    if let Some(meta) = metadata {
+        let organization = ["publisher", "organization"]
+            .iter()
+            .find_map(|key| meta.get(key).and_then(metadata_text))
+            .or_else(|| {
+                meta.get("contact")
+                    .and_then(|c| c.get("organization"))
+                    .and_then(metadata_text)
+            })
+            .or_else(|| meta.get("creator").and_then(metadata_text));
+        let contact = meta.get("contact");
+
+        let fields = [
+            ("Source-Organization", organization),
+            (
+                "Contact-Name",
+                contact.and_then(|c| c.get("name")).and_then(metadata_text),
+            ),
+            (
+                "Contact-Email",
+                contact.and_then(|c| c.get("email")).and_then(metadata_text),
+            ),
+            (
+                "External-Description",
+                meta.get("description").and_then(metadata_text),
+            ),
+            (
+                "External-Identifier",
+                meta.get("identifier")
+                    .or_else(|| meta.get("doi"))
+                    .and_then(metadata_text),
+            ),
+        ];
+
+        for (label, value) in fields {
+            if let Some(value) = value {
+                info.push_str(&format!("{}: {}\n", label, single_line(&value)));
+            }
+        }
+    }
+
+    info.push_str(&format!("Bagging-Date: {}\n", bagging_date));
+    info.push_str(&format!("Payload-Oxum: {}\n", payload_oxum));
+    info.push_str("Bag-Software-Agent: genesis-preflight v0.1.0\n");
+
+    info
+}
+
+/// Extract a usable text value from a metadata field
+///
+/// Accepts a string, an object with a `name`, or the first element of an
+/// array. Empty and TODO values are treated as missing.
+fn metadata_text(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => {
+            let trimmed = s.trim();
+            if trimmed.is_empty() || trimmed.contains("[TODO") {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        }
+        JsonValue::Object(_) => value.get("name").and_then(metadata_text),
+        JsonValue::Array(items) => items.first().and_then(metadata_text),
+        _ => None,
+    }
+}
+
+/// Collapse newlines so a value fits on one tag line
+fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_dataset(name: &str, metadata: &str) -> (PathBuf, Vec<FileInfo>) {
+        let root = std::env::temp_dir().join(name).join("dataset");
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("metadata.json"), metadata).unwrap();
+        fs::write(root.join("data/readings.csv"), "a,b\n1,2\n").unwrap();
+
+        let files = vec![
+            FileInfo::new(root.join("data/readings.csv"), PathBuf::from("data/readings.csv")),
+            FileInfo::new(root.join("metadata.json"), PathBuf::from("metadata.json")),
+        ];
+        (root, files)
+    }
+
+    #[test]
+    fn test_create_bag_layout() {
+        let metadata = r#"{
+            "description": "Hourly readings",
+            "publisher": "Example Lab",
+            "contact": {"name": "Ada", "email": "ada@example.org"}
+        }"#;
+        let (root, files) = make_dataset("genesis_preflight_bag_create", metadata);
+        let out = root.parent().unwrap().join("bag");
+        fs::remove_dir_all(&out).ok();
+
+        let summary = create_bag(&files, &root, &out, "2024-01-15").unwrap();
+        assert_eq!(summary.payload_files, 2);
+        assert_eq!(summary.payload_oxum, format!("{}.2", summary.payload_bytes));
+
+        assert!(out.join("data/data/readings.csv").exists());
+        assert!(out.join("data/metadata.json").exists());
+        assert!(out.join("bagit.txt").exists());
+
+        let manifest = fs::read_to_string(out.join(PAYLOAD_MANIFEST)).unwrap();
+        let lines: Vec<&str> = manifest.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("  data/data/readings.csv"));
+        assert!(lines[1].ends_with("  data/metadata.json"));
+
+        let bag_info = fs::read_to_string(out.join("bag-info.txt")).unwrap();
+        assert!(bag_info.contains("Source-Organization: Example Lab\n"));
+        assert!(bag_info.contains("Contact-Email: ada@example.org\n"));
+        assert!(bag_info.contains("Bagging-Date: 2024-01-15\n"));
+        assert!(bag_info.contains(&format!("Payload-Oxum: {}\n", summary.payload_oxum)));
+
+        let tag_manifest = fs::read_to_string(out.join(TAG_MANIFEST)).unwrap();
+        assert_eq!(tag_manifest.lines().count(), 3);
+
+        fs::remove_dir_all(root.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_create_bag_expands_zarr_store() {
+        let (root, mut files) = make_dataset("genesis_preflight_bag_zarr", "{}");
+        fs::create_dir_all(root.join("grid.zarr/temp")).unwrap();
+        fs::write(root.join("grid.zarr/.zgroup"), "{}").unwrap();
+        fs::write(root.join("grid.zarr/temp/0"), "chunk").unwrap();
+        files.push(
+            FileInfo::new(root.join("grid.zarr"), PathBuf::from("grid.zarr")).with_type(FileType::Zarr),
+        );
+        let out = root.parent().unwrap().join("bag");
+        fs::remove_dir_all(&out).ok();
+
+        let summary = create_bag(&files, &root, &out, "2024-01-15").unwrap();
+        assert_eq!(summary.payload_files, 4);
+        assert!(out.join("data/grid.zarr/temp/0").exists());
+
+        let manifest = fs::read_to_string(out.join(PAYLOAD_MANIFEST)).unwrap();
+        assert!(manifest.contains("  data/grid.zarr/.zgroup\n"));
+        assert!(manifest.contains("  data/grid.zarr/temp/0\n"));
+
+        fs::remove_dir_all(root.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_create_bag_rejects_non_empty_output() {
+        let (root, files) = make_dataset("genesis_preflight_bag_nonempty", "{}");
+        let out = root.parent().unwrap().join("bag");
+        fs::create_dir_all(&out).unwrap();
+        fs::write(out.join("existing.txt"), "x").unwrap();
+
+        let result = create_bag(&files, &root, &out, "2024-01-15");
+        assert!(matches!(result, Err(BagError::OutputNotEmpty(_))));
+
+        fs::remove_dir_all(root.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_create_bag_rejects_output_inside_dataset() {
+        let (root, files) = make_dataset("genesis_preflight_bag_inside", "{}");
+        let out = root.join("bag");
+
+        let result = create_bag(&files, &root, &out, "2024-01-15");
+        assert!(matches!(result, Err(BagError::OutputInsideDataset(_))));
+        assert!(!out.exists());
+
+        // An empty directory the user made beforehand is left alone
+        fs::create_dir(&out).unwrap();
+        let result = create_bag(&files, &root, &out, "2024-01-15");
+        assert!(matches!(result, Err(BagError::OutputInsideDataset(_))));
+        assert!(out.is_dir());
+
+        // Reaching into the dataset through a directory not yet made
+        let sneaky = root.parent().unwrap().join("new/../dataset/bag2");
+        let result = create_bag(&files, &root, &sneaky, "2024-01-15");
+        assert!(matches!(result, Err(BagError::OutputInsideDataset(_))));
+        assert!(!root.join("bag2").exists());
+
+        fs::remove_dir_all(root.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_bag_info_skips_todo_values() {
+        let metadata = parse_json(
+            r#"{"creator": "[TODO: Name]", "contact": {"email": "[TODO: contact@example.com]"}}"#,
+        )
+        .unwrap();
+        let info = generate_bag_info(Some(&metadata), "2024-01-15", "10.1");
+
+        assert!(!info.contains("Source-Organization"));
+        assert!(!info.contains("Contact-Email"));
+        assert!(info.contains("Payload-Oxum: 10.1"));
+    }
+
+    #[test]
+    fn test_metadata_text_variants() {
+        let creator = parse_json(r#"[{"name": "Grace"}]"#).unwrap();
+        assert_eq!(metadata_text(&creator), Some("Grace".to_string()));
+        assert_eq!(metadata_text(&JsonValue::Number(1.0)), None);
+    }
+}
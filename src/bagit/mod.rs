@@ -0,0 +1,134 @@
+//! BagIt packaging module (RFC 8493)
+//!
+//! This module packages a scanned dataset as a BagIt bag for archival
+//! ingestion and verifies existing bags for completeness and fixity.
+//! Only the SHA-256 algorithm is produced; other manifest algorithms are
+//! reported but not verified.
+
+mod create;
+mod validate;
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+pub use create::{create_bag, BagSummary};
+pub use validate::validate_bag;
+
+/// BagIt specification version written to bagit.txt
+pub const BAGIT_VERSION: &str = "1.0";
+
+/// Name of the payload manifest written by `create_bag`
+pub const PAYLOAD_MANIFEST: &str = "manifest-sha256.txt";
+
+/// Name of the tag manifest written by `create_bag`
+pub const TAG_MANIFEST: &str = "tagmanifest-sha256.txt";
+
+/// Errors that can occur while creating a bag
+#[derive(Debug)]
+pub enum BagError {
+    /// IO error occurred
+    Io(io::Error),
+    /// Output directory already exists and is not empty
+    OutputNotEmpty(PathBuf),
+    /// Output directory is inside the dataset being bagged
+    OutputInsideDataset(PathBuf),
+}
+
+impl fmt::Display for BagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BagError::Io(e) => write!(f, "IO error: {}", e),
+            BagError::OutputNotEmpty(p) => {
+                write!(f, "Output directory is not empty: {}", p.display())
+            }
+            BagError::OutputInsideDataset(p) => write!(
+                f,
+                "Output directory must be outside the dataset: {}",
+                p.display()
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for BagError {
+    fn from(error: io::Error) -> Self {
+        BagError::Io(error)
+    }
+}
+
+impl std::error::Error for BagError {}
+
+/// Percent-encode a path for a manifest line
+///
+/// RFC 8493 section 2.1.3 requires CR, LF and `%` to be encoded so each
+/// entry stays on one line.
+fn encode_manifest_path(path: &str) -> String {
+    path.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Decode a percent-encoded manifest path
+fn decode_manifest_path(path: &str) -> String {
+    path.replace("%0D", "\r")
+        .replace("%0d", "\r")
+        .replace("%0A", "\n")
+        .replace("%0a", "\n")
+        .replace("%25", "%")
+}
+
+/// Parse a tag file of `Label: Value` lines
+///
+/// Continuation lines (starting with whitespace) are appended to the
+/// previous value, as allowed by RFC 8493 section 2.2.2.
+fn parse_tag_file(content: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some(last) = entries.last_mut() {
+                last.1.push(' ');
+                last.1.push_str(line.trim());
+            }
+            continue;
+        }
+
+        if let Some(idx) = line.find(':') {
+            let label = line[..idx].trim().to_string();
+            let value = line[idx + 1..].trim().to_string();
+            entries.push((label, value));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_path_round_trip() {
+        let path = "data/100%\nreal.csv";
+        let encoded = encode_manifest_path(path);
+        assert_eq!(encoded, "data/100%25%0Areal.csv");
+        assert_eq!(decode_manifest_path(&encoded), path);
+    }
+
+    #[test]
+    fn test_parse_tag_file() {
+        let entries = parse_tag_file(
+            "Source-Organization: Example Lab\nExternal-Description: First line\n  continued\n",
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], ("Source-Organization".to_string(), "Example Lab".to_string()));
+        assert_eq!(entries[1].1, "First line continued");
+    }
+
+    #[test]
+    fn test_bag_error_display() {
+        let err = BagError::OutputNotEmpty(PathBuf::from("/tmp/bag"));
+        assert_eq!(format!("{}", err), "Output directory is not empty: /tmp/bag");
+    }
+}
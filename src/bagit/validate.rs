@@ -0,0 +1,489 @@
+//! Bag validation (completeness and fixity)
+
+use super::{decode_manifest_path, parse_tag_file, TAG_MANIFEST};
+use crate::crypto::sha256_file;
+use crate::types::ValidationResult;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Validate an existing BagIt bag
+///
+/// Checks that the bag declaration is present, that every payload file is
+/// listed in a manifest and every manifest entry exists (completeness), that
+/// SHA-256 checksums match (fixity), and that Payload-Oxum and the tag
+/// manifest agree with the bag contents.
+///
+/// # Arguments
+///
+/// * `bag_dir` - Root directory of the bag
+///
+/// # Returns
+///
+/// A vector of ValidationResult items; empty for a valid bag.
+pub fn validate_bag(bag_dir: &Path) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    results.extend(check_declaration(bag_dir));
+
+    let data_dir = bag_dir.join("data");
+    if !data_dir.is_dir() {
+        results.push(
+            ValidationResult::critical(
+                "BAG-003",
+                "Bag has no data/ payload directory",
+                "Place all payload files under data/",
+            )
+            .with_file(PathBuf::from("data")),
+        );
+        return results;
+    }
+
+    // Payload manifests
+    let manifests = find_manifests(bag_dir, "manifest-");
+    if manifests.is_empty() {
+        results.push(ValidationResult::critical(
+            "BAG-002",
+            "Bag has no payload manifest",
+            "Add manifest-sha256.txt listing a checksum for every payload file",
+        ));
+    }
+
+    let mut listed: HashSet<String> = HashSet::new();
+    for (name, algorithm) in &manifests {
+        let verify = algorithm == "sha256";
+        if !verify {
+            results.push(
+                ValidationResult::info(
+                    "BAG-008",
+                    format!("Manifest algorithm '{}' is not verified", algorithm),
+                    "Add a manifest-sha256.txt so fixity can be checked",
+                )
+                .with_file(PathBuf::from(name)),
+            );
+        }
+
+        let (entries, manifest_results) = read_manifest(bag_dir, name);
+        results.extend(manifest_results);
+
+        for (line, hash, path) in entries {
+            if !path.starts_with("data/") || escapes_bag(&path) {
+                results.push(
+                    ValidationResult::critical(
+                        "BAG-002",
+                        format!("Payload manifest entry outside data/: {}", path),
+                        "Payload manifests may only list files under data/",
+                    )
+                    .with_file(PathBuf::from(name))
+                    .with_line(line),
+                );
+                continue;
+            }
+
+            let full_path = bag_dir.join(&path);
+            if !full_path.is_file() {
+                results.push(
+                    ValidationResult::critical(
+                        "BAG-003",
+                        format!("File listed in manifest is missing from bag: {}", path),
+                        "Restore the missing payload file or re-create the bag",
+                    )
+                    .with_file(PathBuf::from(name))
+                    .with_line(line),
+                );
+            } else if verify {
+                results.extend(check_fixity(&full_path, &hash, &path, name, line));
+            }
+
+            listed.insert(path);
+        }
+    }
+
+    // Completeness: every payload file must be listed
+    let mut payload = Vec::new();
+    collect_files(&data_dir, bag_dir, &mut payload);
+    payload.sort();
+
+    if !manifests.is_empty() {
+        for (path, _) in &payload {
+            if !listed.contains(path) {
+                results.push(
+                    ValidationResult::critical(
+                        "BAG-005",
+                        format!("Payload file not listed in manifest: {}", path),
+                        "Re-create the bag so the manifest covers every payload file",
+                    )
+                    .with_file(PathBuf::from(path)),
+                );
+            }
+        }
+    }
+
+    results.extend(check_payload_oxum(bag_dir, &payload));
+    results.extend(check_tag_manifest(bag_dir));
+
+    results
+}
+
+/// Check bagit.txt declares the version and tag file encoding
+fn check_declaration(bag_dir: &Path) -> Vec<ValidationResult> {
+    let content = match fs::read_to_string(bag_dir.join("bagit.txt")) {
+        Ok(c) => c,
+        Err(_) => {
+            return vec![ValidationResult::critical(
+                "BAG-001",
+                "Missing bagit.txt bag declaration",
+                "Add bagit.txt with BagIt-Version and Tag-File-Character-Encoding",
+            )
+            .with_file(PathBuf::from("bagit.txt"))];
+        }
+    };
+
+    let entries = parse_tag_file(&content);
+    let mut results = Vec::new();
+
+    for label in ["BagIt-Version", "Tag-File-Character-Encoding"] {
+        if !entries.iter().any(|(l, _)| l == label) {
+            results.push(
+                ValidationResult::critical(
+                    "BAG-001",
+                    format!("bagit.txt is missing {}", label),
+                    format!("Add a '{}' line to bagit.txt", label),
+                )
+                .with_file(PathBuf::from("bagit.txt")),
+            );
+        }
+    }
+
+    results
+}
+
+/// Find manifest files with the given prefix, returning (file name, algorithm)
+fn find_manifests(bag_dir: &Path, prefix: &str) -> Vec<(String, String)> {
+    let mut manifests: Vec<(String, String)> = fs::read_dir(bag_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .filter_map(|name| {
+                    let algorithm = name.strip_prefix(prefix)?.strip_suffix(".txt")?.to_string();
+                    Some((name, algorithm))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    manifests.sort();
+    manifests
+}
+
+/// Read a manifest into (line number, hash, decoded path) entries
+fn read_manifest(
+    bag_dir: &Path,
+    name: &str,
+) -> (Vec<(usize, String, String)>, Vec<ValidationResult>) {
+    let mut entries = Vec::new();
+    let mut results = Vec::new();
+
+    let content = match fs::read_to_string(bag_dir.join(name)) {
+        Ok(c) => c,
+        Err(_) => {
+            results.push(
+                ValidationResult::critical(
+                    "BAG-002",
+                    format!("Cannot read manifest {}", name),
+                    "Ensure the manifest file is readable",
+                )
+                .with_file(PathBuf::from(name)),
+            );
+            return (entries, results);
+        }
+    };
+
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match line.split_once([' ', '\t']) {
+            Some((hash, path)) if !path.trim().is_empty() => {
+                entries.push((
+                    idx + 1,
+                    hash.to_lowercase(),
+                    decode_manifest_path(path.trim_start()),
+                ));
+            }
+            _ => {
+                results.push(
+                    ValidationResult::critical(
+                        "BAG-002",
+                        format!("Malformed manifest line in {}", name),
+                        "Manifest lines must be: checksum, whitespace, path",
+                    )
+                    .with_file(PathBuf::from(name))
+                    .with_line(idx + 1),
+                );
+            }
+        }
+    }
+
+    (entries, results)
+}
+
+/// Compare a file's SHA-256 with the manifest value
+fn check_fixity(
+    full_path: &Path,
+    expected: &str,
+    path: &str,
+    manifest: &str,
+    line: usize,
+) -> Vec<ValidationResult> {
+    match sha256_file(full_path) {
+        Ok(actual) if actual != expected => vec![ValidationResult::critical(
+            "BAG-004",
+            format!("Checksum mismatch: {}", path),
+            format!(
+                "Expected hash: {}, actual hash: {}. The payload has changed since bagging.",
+                expected, actual
+            ),
+        )
+        .with_file(PathBuf::from(manifest))
+        .with_line(line)],
+        Ok(_) => vec![],
+        Err(_) => vec![ValidationResult::critical(
+            "BAG-004",
+            format!("Cannot read payload file: {}", path),
+            "Ensure payload files are readable",
+        )
+        .with_file(PathBuf::from(manifest))
+        .with_line(line)],
+    }
+}
+
+/// Check Payload-Oxum in bag-info.txt against the actual payload
+fn check_payload_oxum(bag_dir: &Path, payload: &[(String, u64)]) -> Vec<ValidationResult> {
+    let content = match fs::read_to_string(bag_dir.join("bag-info.txt")) {
+        Ok(c) => c,
+        Err(_) => return vec![], // bag-info.txt is optional
+    };
+
+    let oxum = match parse_tag_file(&content)
+        .into_iter()
+        .find(|(label, _)| label == "Payload-Oxum")
+    {
+        Some((_, value)) => value,
+        None => return vec![],
+    };
+
+    let actual_bytes: u64 = payload.iter().map(|(_, size)| size).sum();
+    let actual = format!("{}.{}", actual_bytes, payload.len());
+
+    if oxum == actual {
+        vec![]
+    } else {
+        vec![ValidationResult::warning(
+            "BAG-006",
+            format!("Payload-Oxum mismatch: declared {}, actual {}", oxum, actual),
+            "The payload has gained, lost or resized files since bagging",
+        )
+        .with_file(PathBuf::from("bag-info.txt"))]
+    }
+}
+
+/// Verify tag files against tagmanifest-sha256.txt, if present
+fn check_tag_manifest(bag_dir: &Path) -> Vec<ValidationResult> {
+    if !bag_dir.join(TAG_MANIFEST).exists() {
+        return vec![];
+    }
+
+    let (entries, mut results) = read_manifest(bag_dir, TAG_MANIFEST);
+
+    for (line, hash, path) in entries {
+        if escapes_bag(&path) {
+            results.push(
+                ValidationResult::critical(
+                    "BAG-007",
+                    format!("Tag manifest entry outside the bag: {}", path),
+                    "Tag manifests may only list tag files inside the bag",
+                )
+                .with_file(PathBuf::from(TAG_MANIFEST))
+                .with_line(line),
+            );
+            continue;
+        }
+
+        let full_path = bag_dir.join(&path);
+        if !full_path.is_file() {
+            results.push(
+                ValidationResult::critical(
+                    "BAG-007",
+                    format!("Tag file listed in tag manifest is missing: {}", path),
+                    "Restore the tag file or re-create the bag",
+                )
+                .with_file(PathBuf::from(TAG_MANIFEST))
+                .with_line(line),
+            );
+            continue;
+        }
+
+        if let Ok(actual) = sha256_file(&full_path) {
+            if actual != hash {
+                results.push(
+                    ValidationResult::critical(
+                        "BAG-007",
+                        format!("Tag file checksum mismatch: {}", path),
+                        "Tag files were edited after bagging; re-create the tag manifest",
+                    )
+                    .with_file(PathBuf::from(TAG_MANIFEST))
+                    .with_line(line),
+                );
+            }
+        }
+    }
+
+    results
+}
+
+/// Whether a manifest path could lead out of the bag (`..` or absolute)
+fn escapes_bag(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_)))
+}
+
+/// Recursively collect (bag-relative path, size) for every file under `dir`
+fn collect_files(dir: &Path, bag_dir: &Path, out: &mut Vec<(String, u64)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        if metadata.is_dir() {
+            collect_files(&path, bag_dir, out);
+        } else if metadata.is_file() {
+            let relative = path
+                .strip_prefix(bag_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            out.push((relative, metadata.len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bagit::create_bag;
+    use crate::types::FileInfo;
+
+    fn make_bag(name: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(name);
+        fs::remove_dir_all(&base).ok();
+        let root = base.join("dataset");
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("data/readings.csv"), "a,b\n1,2\n").unwrap();
+        fs::write(root.join("README.md"), "# Readings\n").unwrap();
+
+        let files = vec![
+            FileInfo::new(root.join("README.md"), PathBuf::from("README.md")),
+            FileInfo::new(root.join("data/readings.csv"), PathBuf::from("data/readings.csv")),
+        ];
+        let bag = base.join("bag");
+        create_bag(&files, &root, &bag, "2024-01-15").unwrap();
+        (base, bag)
+    }
+
+    #[test]
+    fn test_valid_bag() {
+        let (base, bag) = make_bag("genesis_preflight_bag_valid");
+
+        let results = validate_bag(&bag);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_bag_fixity_failure() {
+        let (base, bag) = make_bag("genesis_preflight_bag_fixity");
+        fs::write(bag.join("data/README.md"), "# Tampered readings\n").unwrap();
+
+        let results = validate_bag(&bag);
+        assert!(results.iter().any(|r| r.code == "BAG-004"));
+        assert!(results.iter().any(|r| r.code == "BAG-006"));
+
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_bag_missing_and_extra_files() {
+        let (base, bag) = make_bag("genesis_preflight_bag_completeness");
+        fs::remove_file(bag.join("data/README.md")).unwrap();
+        fs::write(bag.join("data/extra.txt"), "extra").unwrap();
+
+        let results = validate_bag(&bag);
+        assert!(results.iter().any(|r| r.code == "BAG-003"));
+        assert!(results
+            .iter()
+            .any(|r| r.code == "BAG-005" && r.message.contains("data/extra.txt")));
+
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_bag_manifest_paths_cannot_leave_bag() {
+        let (base, bag) = make_bag("genesis_preflight_bag_traversal");
+        fs::write(base.join("outside.txt"), "secret").unwrap();
+        let manifest = fs::read_to_string(bag.join("manifest-sha256.txt")).unwrap();
+        fs::write(
+            bag.join("manifest-sha256.txt"),
+            manifest + &format!("{}  data/../../outside.txt\n", "0".repeat(64)),
+        )
+        .unwrap();
+
+        let results = validate_bag(&bag);
+        let escaped: Vec<&ValidationResult> =
+            results.iter().filter(|r| r.message.contains("outside.txt")).collect();
+        assert_eq!(escaped.len(), 1, "{:?}", escaped);
+        assert_eq!(escaped[0].code, "BAG-002");
+        assert!(!results.iter().any(|r| r.code == "BAG-004"));
+
+        assert!(escapes_bag("/etc/passwd"));
+        assert!(!escapes_bag("data/./readings.csv"));
+
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_bag_missing_declaration() {
+        let (base, bag) = make_bag("genesis_preflight_bag_declaration");
+        fs::remove_file(bag.join("bagit.txt")).unwrap();
+
+        let results = validate_bag(&bag);
+        assert!(results.iter().any(|r| r.code == "BAG-001"));
+        // bagit.txt is listed in the tag manifest too
+        assert!(results.iter().any(|r| r.code == "BAG-007"));
+
+        fs::remove_dir_all(base).ok();
+    }
+
+    #[test]
+    fn test_bag_tag_file_edited() {
+        let (base, bag) = make_bag("genesis_preflight_bag_tagfile");
+        let info = fs::read_to_string(bag.join("bag-info.txt")).unwrap();
+        fs::write(bag.join("bag-info.txt"), info + "Contact-Name: Someone\n").unwrap();
+
+        let results = validate_bag(&bag);
+        assert!(results.iter().any(|r| r.code == "BAG-007"));
+
+        fs::remove_dir_all(base).ok();
+    }
+}
@@ -0,0 +1,87 @@
+//! CRC-32 checksum as used by gzip and ZIP
+//!
+//! The reflected CRC-32 with polynomial 0xEDB88320 (ISO 3309, ITU-T V.42),
+//! computed a byte at a time from a 256-entry table built at compile time.
+//!
+//! ## References
+//!
+//! - RFC 1952, section 8: https://www.rfc-editor.org/rfc/rfc1952#section-8
+
+/// Reversed generator polynomial
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Remainders for every byte value
+const TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { POLYNOMIAL ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+/// Running CRC-32 over data fed in pieces
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    /// Start a new checksum
+    pub fn new() -> Self {
+        Crc32 { state: 0xFFFF_FFFF }
+    }
+
+    /// Add bytes to the checksum
+    pub fn update(&mut self, data: &[u8]) {
+        let mut c = self.state;
+        for &byte in data {
+            c = TABLE[((c ^ byte as u32) & 0xFF) as usize] ^ (c >> 8);
+        }
+        self.state = c;
+    }
+
+    /// The checksum of everything added so far
+    pub fn value(&self) -> u32 {
+        self.state ^ 0xFFFF_FFFF
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32 of a byte slice
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.value(), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,238 @@
+//! gzip container (RFC 1952)
+//!
+//! Wraps the DEFLATE decoder with gzip's member header and its CRC-32 and
+//! length trailer. Concatenated members (as written by `cat a.gz b.gz`)
+//! decode as one stream, as they do with `gunzip`.
+//!
+//! ## References
+//!
+//! - RFC 1952: https://www.rfc-editor.org/rfc/rfc1952
+
+use super::corrupt;
+use super::crc32::Crc32;
+use super::inflate::Inflater;
+use std::io::{self, Read};
+
+/// gzip magic bytes
+pub const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Header flag: extra field present
+const FEXTRA: u8 = 0x04;
+/// Header flag: original file name present
+const FNAME: u8 = 0x08;
+/// Header flag: comment present
+const FCOMMENT: u8 = 0x10;
+/// Header flag: header CRC present
+const FHCRC: u8 = 0x02;
+
+/// Where the decoder is in the file
+enum State {
+    Header,
+    Body,
+    Done,
+    /// An earlier read failed; the stream cannot continue
+    Failed,
+}
+
+/// Streaming gzip decoder
+///
+/// Yields the decompressed contents through `Read`, checking each member's
+/// CRC-32 and length once its data has been read. A bad header, corrupt
+/// DEFLATE data, a checksum or length mismatch, or trailing garbage is
+/// reported as an error recognised by [`super::is_corrupt`].
+pub struct GzDecoder<R: Read> {
+    inflater: Inflater<R>,
+    state: State,
+    crc: Crc32,
+    member_bytes: u64,
+    total_bytes: u64,
+    members: usize,
+}
+
+impl<R: Read> GzDecoder<R> {
+    /// Decode the gzip data read from `inner`
+    pub fn new(inner: R) -> Self {
+        GzDecoder {
+            inflater: Inflater::new(inner),
+            state: State::Header,
+            crc: Crc32::new(),
+            member_bytes: 0,
+            total_bytes: 0,
+            members: 0,
+        }
+    }
+
+    /// Decompressed bytes produced so far
+    pub fn uncompressed_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Members whose trailer has been verified
+    pub fn members(&self) -> usize {
+        self.members
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        let input = self.inflater.input();
+        let mut fixed = [0u8; 10];
+        for (i, byte) in fixed.iter_mut().enumerate() {
+            *byte = input.read_byte()?;
+            if i < 2 && *byte != GZIP_MAGIC[i] {
+                return Err(corrupt("not a gzip stream"));
+            }
+        }
+        if fixed[2] != 8 {
+            return Err(corrupt(format!("unsupported compression method {}", fixed[2])));
+        }
+        let flags = fixed[3];
+        if flags & 0xE0 != 0 {
+            return Err(corrupt("reserved header flags are set"));
+        }
+
+        if flags & FEXTRA != 0 {
+            let length = input.read_byte()? as usize | (input.read_byte()? as usize) << 8;
+            for _ in 0..length {
+                input.read_byte()?;
+            }
+        }
+        for flag in [FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                while input.read_byte()? != 0 {}
+            }
+        }
+        if flags & FHCRC != 0 {
+            input.read_byte()?;
+            input.read_byte()?;
+        }
+        Ok(())
+    }
+
+    fn read_trailer(&mut self) -> io::Result<()> {
+        let input = self.inflater.input();
+        let stored_crc = input.read_u32_le()?;
+        let stored_size = input.read_u32_le()?;
+
+        let computed = self.crc.value();
+        if stored_crc != computed {
+            return Err(corrupt(format!(
+                "CRC mismatch: stored {:08x}, computed {:08x}",
+                stored_crc, computed
+            )));
+        }
+        // ISIZE is the length modulo 2^32
+        if stored_size != self.member_bytes as u32 {
+            return Err(corrupt(format!(
+                "length mismatch: trailer says {} bytes, decompressed {}",
+                stored_size, self.member_bytes
+            )));
+        }
+        self.members += 1;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for GzDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.read_members(buf);
+        if result.is_err() {
+            self.state = State::Failed;
+        }
+        result
+    }
+}
+
+impl<R: Read> GzDecoder<R> {
+    fn read_members(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.state {
+                State::Header => {
+                    self.read_header()?;
+                    self.state = State::Body;
+                }
+                State::Body => {
+                    let n = self.inflater.read(buf)?;
+                    if n > 0 || buf.is_empty() {
+                        self.crc.update(&buf[..n]);
+                        self.member_bytes += n as u64;
+                        self.total_bytes += n as u64;
+                        return Ok(n);
+                    }
+                    debug_assert!(self.inflater.is_done());
+
+                    self.read_trailer()?;
+                    if self.inflater.input().at_end()? {
+                        self.state = State::Done;
+                        return Ok(0);
+                    }
+                    if self.inflater.input().peek_byte()? != GZIP_MAGIC[0] {
+                        return Err(corrupt("unexpected data after the end of the gzip stream"));
+                    }
+                    // Another member follows
+                    self.state = State::Header;
+                    self.inflater.reset();
+                    self.crc = Crc32::new();
+                    self.member_bytes = 0;
+                }
+                State::Done => return Ok(0),
+                State::Failed => return Err(corrupt("decompression already failed")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::fixture::{hex, OBS_MEMBER, ROW_MEMBER};
+    use crate::compression::is_corrupt;
+
+    fn decode(data: &[u8]) -> (io::Result<Vec<u8>>, GzDecoder<&[u8]>) {
+        let mut decoder = GzDecoder::new(data);
+        let mut output = Vec::new();
+        let result = decoder.read_to_end(&mut output).map(|_| output);
+        (result, decoder)
+    }
+
+    #[test]
+    fn test_concatenated_members() {
+        let mut data = hex(OBS_MEMBER);
+        data.extend(hex(ROW_MEMBER));
+        let (result, decoder) = decode(&data);
+        assert_eq!(result.unwrap(), b"id,value\n1,2.5\n2,3.5\n");
+        assert_eq!(decoder.members(), 2);
+        assert_eq!(decoder.uncompressed_bytes(), 21);
+    }
+
+    #[test]
+    fn test_checksum_and_length_mismatch() {
+        // CRC-32 is the second-to-last word of the member
+        let mut data = hex(ROW_MEMBER);
+        let crc = data.len() - 8;
+        data[crc] ^= 0xFF;
+        let error = decode(&data).0.unwrap_err();
+        assert!(is_corrupt(&error));
+        assert_eq!(error.to_string(), "CRC mismatch: stored 6ee8d2f2, computed 6ee8d20d");
+
+        let mut data = hex(ROW_MEMBER);
+        let size = data.len() - 4;
+        data[size] = 7;
+        let error = decode(&data).0.unwrap_err();
+        assert_eq!(error.to_string(), "length mismatch: trailer says 7 bytes, decompressed 6");
+    }
+
+    #[test]
+    fn test_damaged_streams() {
+        let error = decode(b"id,value\n").0.unwrap_err();
+        assert_eq!(error.to_string(), "not a gzip stream");
+
+        let mut data = hex(ROW_MEMBER);
+        data.truncate(data.len() - 10);
+        let error = decode(&data).0.unwrap_err();
+        assert_eq!(error.to_string(), "unexpected end of compressed data");
+
+        let mut data = hex(ROW_MEMBER);
+        data.extend_from_slice(b"junk");
+        let error = decode(&data).0.unwrap_err();
+        assert_eq!(error.to_string(), "unexpected data after the end of the gzip stream");
+    }
+}
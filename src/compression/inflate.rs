@@ -0,0 +1,525 @@
+//! DEFLATE decompression (RFC 1951)
+//!
+//! A streaming decoder for raw DEFLATE data, the format inside gzip, ZIP
+//! and zlib streams. Output is produced in chunks as the caller reads, so
+//! arbitrarily large files can be decompressed in constant memory: only
+//! the 32 KiB window that back-references may reach is kept.
+//!
+//! Huffman codes are decoded through a lookup table indexed by the next
+//! (bit-reversed) input bits, one table per code, rebuilt for every
+//! dynamic block.
+//!
+//! ## References
+//!
+//! - RFC 1951: https://www.rfc-editor.org/rfc/rfc1951
+
+use super::corrupt;
+use std::io::{self, Read};
+
+/// How far back a match may reach
+const WINDOW: usize = 32 * 1024;
+
+/// Output decoded ahead of the reader before returning control
+const CHUNK: usize = 32 * 1024;
+
+/// Longest Huffman code in DEFLATE
+const MAX_BITS: usize = 15;
+
+/// Base lengths for length symbols 257..285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// Extra bits for length symbols 257..285
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances for distance symbols 0..29
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits for distance symbols 0..29
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which code length code lengths are stored in a dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Reads DEFLATE's little-endian bit stream from any byte source
+pub(crate) struct BitReader<R: Read> {
+    inner: R,
+    buffer: Box<[u8; 8192]>,
+    buffer_pos: usize,
+    buffer_len: usize,
+    bits: u64,
+    bit_count: u32,
+}
+
+impl<R: Read> BitReader<R> {
+    fn new(inner: R) -> Self {
+        BitReader {
+            inner,
+            buffer: Box::new([0; 8192]),
+            buffer_pos: 0,
+            buffer_len: 0,
+            bits: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Next input byte, or None at the end of the input
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.buffer_pos == self.buffer_len {
+            self.buffer_len = loop {
+                match self.inner.read(&mut self.buffer[..]) {
+                    Ok(n) => break n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            };
+            self.buffer_pos = 0;
+            if self.buffer_len == 0 {
+                return Ok(None);
+            }
+        }
+        self.buffer_pos += 1;
+        Ok(Some(self.buffer[self.buffer_pos - 1]))
+    }
+
+    /// Top up the bit buffer as far as the input allows
+    fn fill(&mut self) -> io::Result<()> {
+        while self.bit_count <= 56 {
+            match self.next_byte()? {
+                Some(byte) => {
+                    self.bits |= (byte as u64) << self.bit_count;
+                    self.bit_count += 8;
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// The next `n` bits without consuming them (zero past the end of input)
+    fn peek(&mut self, n: u32) -> io::Result<u32> {
+        if self.bit_count < n {
+            self.fill()?;
+        }
+        Ok((self.bits & ((1u64 << n) - 1)) as u32)
+    }
+
+    fn consume(&mut self, n: u32) -> io::Result<()> {
+        if self.bit_count < n {
+            return Err(corrupt("unexpected end of compressed data"));
+        }
+        self.bits >>= n;
+        self.bit_count -= n;
+        Ok(())
+    }
+
+    /// Read an `n`-bit little-endian value (n <= 32)
+    pub(crate) fn read_bits(&mut self, n: u32) -> io::Result<u32> {
+        let value = self.peek(n)?;
+        self.consume(n)?;
+        Ok(value)
+    }
+
+    /// Skip to the next byte boundary
+    pub(crate) fn align(&mut self) {
+        let skip = self.bit_count % 8;
+        self.bits >>= skip;
+        self.bit_count -= skip;
+    }
+
+    /// Read a byte after aligning
+    pub(crate) fn read_byte(&mut self) -> io::Result<u8> {
+        self.align();
+        Ok(self.read_bits(8)? as u8)
+    }
+
+    /// The next byte after aligning, without consuming it
+    pub(crate) fn peek_byte(&mut self) -> io::Result<u8> {
+        self.align();
+        if self.bit_count < 8 {
+            self.fill()?;
+            if self.bit_count < 8 {
+                return Err(corrupt("unexpected end of compressed data"));
+            }
+        }
+        Ok(self.bits as u8)
+    }
+
+    /// Read a little-endian 32-bit value after aligning
+    pub(crate) fn read_u32_le(&mut self) -> io::Result<u32> {
+        self.align();
+        self.read_bits(32)
+    }
+
+    /// Whether all input has been consumed (after aligning)
+    pub(crate) fn at_end(&mut self) -> io::Result<bool> {
+        self.align();
+        if self.bit_count > 0 {
+            return Ok(false);
+        }
+        self.fill()?;
+        Ok(self.bit_count == 0)
+    }
+}
+
+/// A canonical Huffman code as a lookup table
+///
+/// Entries hold `symbol << 4 | length`; a length of zero marks bit
+/// patterns that no code starts with.
+struct Huffman {
+    table: Vec<u32>,
+    bits: u32,
+}
+
+impl Huffman {
+    /// Build the code from the code length of every symbol (0 = unused)
+    fn new(lengths: &[u8]) -> io::Result<Huffman> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // Incomplete codes are legal (e.g. a single distance code); too many are not
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(corrupt("invalid Huffman code lengths"));
+            }
+        }
+
+        let bits = (1..=MAX_BITS).rev().find(|&b| counts[b] > 0).unwrap_or(1) as u32;
+        let mut next_code = [0u32; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            next_code[length + 1] = (next_code[length] + counts[length] as u32) << 1;
+        }
+
+        let size = 1usize << bits;
+        let mut table = vec![0u32; size];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length == 0 {
+                continue;
+            }
+            let length = length as u32;
+            let code = next_code[length as usize];
+            next_code[length as usize] += 1;
+            let reversed = (code.reverse_bits() >> (32 - length)) as usize;
+            let entry = (symbol as u32) << 4 | length;
+            for slot in table.iter_mut().skip(reversed).step_by(1 << length) {
+                *slot = entry;
+            }
+        }
+        Ok(Huffman { table, bits })
+    }
+
+    fn decode<R: Read>(&self, input: &mut BitReader<R>) -> io::Result<u16> {
+        let entry = self.table[input.peek(self.bits)? as usize];
+        let length = entry & 0xF;
+        if length == 0 {
+            if input.bit_count < self.bits {
+                return Err(corrupt("unexpected end of compressed data"));
+            }
+            return Err(corrupt("invalid Huffman code"));
+        }
+        input.consume(length)?;
+        Ok((entry >> 4) as u16)
+    }
+}
+
+/// Where the decoder is in the stream
+enum State {
+    /// Expecting a block header (or the end, after the final block)
+    Header,
+    /// Inside a stored block with this many bytes left
+    Stored(usize),
+    /// Inside a compressed block with its literal/length and distance codes
+    Huffman(Box<(Huffman, Huffman)>),
+    /// The final block has ended
+    Done,
+    /// An earlier read failed; the stream cannot continue
+    Failed,
+}
+
+/// Streaming DEFLATE decoder
+///
+/// Reads compressed bytes from `R` and yields the decompressed data through
+/// `Read`. Corrupt or truncated input is reported as an `InvalidData` error
+/// recognised by [`super::is_corrupt`].
+pub struct Inflater<R: Read> {
+    input: BitReader<R>,
+    state: State,
+    last_block: bool,
+    /// Recent output: the back-reference window followed by bytes not yet read
+    window: Vec<u8>,
+    /// Start of the unread bytes in `window`
+    pos: usize,
+    /// Error to return once the output decoded before it has been read
+    pending_error: Option<io::Error>,
+}
+
+impl<R: Read> Inflater<R> {
+    /// Decode the raw DEFLATE stream read from `inner`
+    pub fn new(inner: R) -> Self {
+        Inflater {
+            input: BitReader::new(inner),
+            state: State::Header,
+            last_block: false,
+            window: Vec::with_capacity(WINDOW + CHUNK + 258),
+            pos: 0,
+            pending_error: None,
+        }
+    }
+
+    /// Whether the final block has been fully decoded and read
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, State::Done) && self.pos == self.window.len()
+    }
+
+    /// The underlying bit stream, e.g. to read a container's trailer
+    pub(crate) fn input(&mut self) -> &mut BitReader<R> {
+        &mut self.input
+    }
+
+    /// Start decoding another DEFLATE stream from the same input
+    pub(crate) fn reset(&mut self) {
+        self.state = State::Header;
+        self.last_block = false;
+        self.window.clear();
+        self.pos = 0;
+    }
+
+    /// Decode until a chunk of output is ready or the stream ends
+    fn fill_output(&mut self) -> io::Result<()> {
+        // Forget output that has been read and is out of reach of back-references
+        let reachable = self.window.len().saturating_sub(WINDOW);
+        let drop = reachable.min(self.pos);
+        if drop >= WINDOW {
+            self.window.drain(..drop);
+            self.pos -= drop;
+        }
+
+        while self.window.len() - self.pos < CHUNK {
+            match &mut self.state {
+                State::Header => {
+                    if self.last_block {
+                        self.state = State::Done;
+                        break;
+                    }
+                    self.read_block_header()?;
+                }
+                State::Stored(remaining) => {
+                    if *remaining == 0 {
+                        self.state = State::Header;
+                        continue;
+                    }
+                    *remaining -= 1;
+                    let byte = self.input.read_bits(8)? as u8;
+                    self.window.push(byte);
+                }
+                State::Huffman(codes) => {
+                    let (literal, distance) = &**codes;
+                    let symbol = literal.decode(&mut self.input)?;
+                    if symbol < 256 {
+                        self.window.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        self.state = State::Header;
+                        continue;
+                    }
+
+                    let index = (symbol - 257) as usize;
+                    if index >= LENGTH_BASE.len() {
+                        return Err(corrupt("invalid length symbol"));
+                    }
+                    let length = LENGTH_BASE[index] as usize
+                        + self.input.read_bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+                    let index = distance.decode(&mut self.input)? as usize;
+                    if index >= DISTANCE_BASE.len() {
+                        return Err(corrupt("invalid distance symbol"));
+                    }
+                    let distance = DISTANCE_BASE[index] as usize
+                        + self.input.read_bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                    if distance > self.window.len() {
+                        return Err(corrupt("back-reference before the start of the data"));
+                    }
+
+                    // Copy byte by byte: the source may overlap the bytes being written
+                    let start = self.window.len() - distance;
+                    for i in 0..length {
+                        let byte = self.window[start + i];
+                        self.window.push(byte);
+                    }
+                }
+                State::Done => break,
+                State::Failed => return Err(corrupt("decompression already failed")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a block header and set up the state for its contents
+    fn read_block_header(&mut self) -> io::Result<()> {
+        self.last_block = self.input.read_bits(1)? == 1;
+        match self.input.read_bits(2)? {
+            0 => {
+                self.input.align();
+                let length = self.input.read_bits(16)?;
+                let complement = self.input.read_bits(16)?;
+                if length != !complement & 0xFFFF {
+                    return Err(corrupt("stored block length does not match its complement"));
+                }
+                self.state = State::Stored(length as usize);
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literal = Huffman::new(&lengths)?;
+                let distance = Huffman::new(&[5; 30])?;
+                self.state = State::Huffman(Box::new((literal, distance)));
+            }
+            2 => {
+                let codes = self.read_dynamic_codes()?;
+                self.state = State::Huffman(Box::new(codes));
+            }
+            _ => return Err(corrupt("invalid block type")),
+        }
+        Ok(())
+    }
+
+    /// Read the code lengths of a dynamic Huffman block
+    fn read_dynamic_codes(&mut self) -> io::Result<(Huffman, Huffman)> {
+        let literal_count = self.input.read_bits(5)? as usize + 257;
+        let distance_count = self.input.read_bits(5)? as usize + 1;
+        let code_length_count = self.input.read_bits(4)? as usize + 4;
+        if literal_count > 286 || distance_count > 30 {
+            return Err(corrupt("too many length or distance codes"));
+        }
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_lengths[index] = self.input.read_bits(3)? as u8;
+        }
+        let code_length_code = Huffman::new(&code_lengths)?;
+
+        let total = literal_count + distance_count;
+        let mut lengths = Vec::with_capacity(total);
+        while lengths.len() < total {
+            let symbol = code_length_code.decode(&mut self.input)?;
+            let (value, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    let previous = *lengths
+                        .last()
+                        .ok_or_else(|| corrupt("repeated code length with no previous length"))?;
+                    (previous, 3 + self.input.read_bits(2)? as usize)
+                }
+                17 => (0, 3 + self.input.read_bits(3)? as usize),
+                _ => (0, 11 + self.input.read_bits(7)? as usize),
+            };
+            if lengths.len() + repeat > total {
+                return Err(corrupt("code lengths overflow the alphabet"));
+            }
+            lengths.extend(std::iter::repeat_n(value, repeat));
+        }
+
+        if lengths[256] == 0 {
+            return Err(corrupt("block has no end-of-block code"));
+        }
+        let literal = Huffman::new(&lengths[..literal_count])?;
+        let distance = Huffman::new(&lengths[literal_count..])?;
+        Ok((literal, distance))
+    }
+}
+
+impl<R: Read> Read for Inflater<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.window.len() {
+            if let Some(error) = self.pending_error.take() {
+                return Err(error);
+            }
+            if let Err(error) = self.fill_output() {
+                self.state = State::Failed;
+                if self.pos == self.window.len() {
+                    return Err(error);
+                }
+                self.pending_error = Some(error);
+            }
+        }
+
+        let n = buf.len().min(self.window.len() - self.pos);
+        buf[..n].copy_from_slice(&self.window[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Decompress a complete raw DEFLATE stream held in memory
+pub fn inflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    Inflater::new(data).read_to_end(&mut output)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::fixture::hex;
+    use crate::compression::is_corrupt;
+
+    #[test]
+    fn test_stored_and_fixed_blocks() {
+        // A stored block "abc" followed by a final fixed block "abcabcabc" (one back-reference)
+        let mut data = vec![0x00, 0x03, 0x00, 0xFC, 0xFF];
+        data.extend_from_slice(b"abc");
+        data.extend(hex("4b4c4a4e042300"));
+        assert_eq!(inflate(&data).unwrap(), b"abcabcabcabc");
+
+        assert_eq!(inflate(&hex("0300")).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_dynamic_block() {
+        // zlib.compressobj(9, DEFLATED, -15) of 20 lines "row N,value N*N\n"
+        let data = hex(
+            "3d8f310e80300c03775ec10318eab494f6390c6c48489580ef831227e3a9ae7d
+             19d73ba7e5d9cffb98d3347e02094a422a4a99d4958a27abe24a9455b112b3bd
+             6e5e647f1bb15a7127365b852b21512aac84091743b106e4b0b105845e370384
+             200d51c398099794c60ed7ccc2951e5761fa00",
+        );
+        let expected: String = (0..20).map(|i| format!("row {},value {}\n", i, i * i)).collect();
+        assert_eq!(inflate(&data).unwrap(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_corrupt_input() {
+        // Reserved block type 3
+        let error = inflate(&[0x07]).unwrap_err();
+        assert!(is_corrupt(&error));
+        assert_eq!(error.to_string(), "invalid block type");
+
+        // Stored block whose length complement is wrong
+        let error = inflate(&[0x01, 0x03, 0x00, 0x00, 0x00, b'a']).unwrap_err();
+        assert!(error.to_string().contains("complement"));
+
+        // Truncated fixed block
+        let error = inflate(&hex("4b4c4a")).unwrap_err();
+        assert!(is_corrupt(&error));
+        assert_eq!(error.to_string(), "unexpected end of compressed data");
+    }
+}
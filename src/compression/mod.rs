@@ -0,0 +1,155 @@
+// Decompression module
+// DEFLATE and gzip implemented from the specifications using only std library
+
+pub mod crc32;
+pub mod gzip;
+pub mod inflate;
+
+pub use crc32::{crc32, Crc32};
+pub use gzip::GzDecoder;
+pub use inflate::{inflate, Inflater};
+
+use crate::archive;
+use crate::types::{AnalysisResult, ArchiveMember, FileInfo, GzipInfo};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Damage found in compressed data (bad header, invalid code, checksum mismatch)
+#[derive(Debug)]
+struct CorruptData(String);
+
+impl fmt::Display for CorruptData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CorruptData {}
+
+/// An `InvalidData` error describing corrupt compressed data
+pub(crate) fn corrupt(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, CorruptData(message.into()))
+}
+
+/// Whether an I/O error came from corrupt compressed data
+///
+/// Lets readers tell a damaged stream apart from other `InvalidData`
+/// errors such as invalid UTF-8 in the decompressed text.
+pub fn is_corrupt(error: &io::Error) -> bool {
+    error.get_ref().is_some_and(|inner| inner.is::<CorruptData>())
+}
+
+/// Whether a path names a gzip-compressed file (`.gz`)
+pub fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// A data file opened for reading, decompressed on the fly if it is gzipped
+///
+/// Analyzers read through this so `data.csv.gz` is analyzed like
+/// `data.csv`, and archive members like files on disk. The first corruption error is remembered and reported by
+/// [`DataReader::gzip_info`] along with the compressed and decompressed sizes.
+pub struct DataReader {
+    source: Source,
+    compressed_bytes: u64,
+    error: Option<String>,
+}
+
+enum Source {
+    Plain(Box<dyn Read>),
+    Gzip(GzDecoder<Box<dyn Read>>),
+}
+
+impl DataReader {
+    /// Open a file, decompressing it if its name ends in `.gz`
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let compressed_bytes = file.metadata()?.len();
+        Ok(Self::new(Box::new(file), compressed_bytes, is_gzip_path(path)))
+    }
+
+    /// Open a member of a ZIP or tar archive, decompressing it if its name ends in `.gz`
+    pub fn open_member(member: &ArchiveMember) -> io::Result<Self> {
+        let data = archive::open_member(&member.archive, member.format, &member.entry)?;
+        let gzipped = is_gzip_path(Path::new(&member.entry.name));
+        Ok(Self::new(data, member.entry.size, gzipped))
+    }
+
+    fn new(data: Box<dyn Read>, compressed_bytes: u64, gzipped: bool) -> Self {
+        let source = if gzipped {
+            Source::Gzip(GzDecoder::new(data))
+        } else {
+            Source::Plain(data)
+        };
+        DataReader {
+            source,
+            compressed_bytes,
+            error: None,
+        }
+    }
+
+    /// Sizes and integrity of a gzipped file (None for uncompressed files)
+    ///
+    /// The CRC-32 and length are only checked once all data has been read.
+    pub fn gzip_info(&self) -> Option<GzipInfo> {
+        match &self.source {
+            Source::Plain(_) => None,
+            Source::Gzip(decoder) => Some(GzipInfo {
+                compressed_bytes: self.compressed_bytes,
+                uncompressed_bytes: decoder.uncompressed_bytes(),
+                error: self.error.clone(),
+            }),
+        }
+    }
+}
+
+impl Read for DataReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = match &mut self.source {
+            Source::Plain(data) => data.read(buf),
+            Source::Gzip(decoder) => decoder.read(buf),
+        };
+        if let Err(error) = &result {
+            if is_corrupt(error) && self.error.is_none() {
+                self.error = Some(error.to_string());
+            }
+        }
+        result
+    }
+}
+
+/// Gzipped data files with their compressed and decompressed sizes, in scan order
+pub fn compressed_files(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<(String, GzipInfo)> {
+    files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| {
+            let gzip = analysis.gzip()?;
+            Some((file.relative_path.to_string_lossy().to_string(), gzip.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod fixture {
+    /// Parse a hex fixture, ignoring whitespace
+    pub fn hex(text: &str) -> Vec<u8> {
+        let digits: Vec<u8> = text.bytes().filter(|b| b.is_ascii_hexdigit()).collect();
+        digits
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    /// `id,value\n1,2.5\n` gzipped with the file name obs.csv
+    pub const OBS_MEMBER: &str =
+        "1f8b08080000000002ff6f62732e63737600cb4cd1294bcc294de532d431d233e502002b3b91a90f000000";
+
+    /// `2,3.5\n` gzipped without a file name
+    pub const ROW_MEMBER: &str = "1f8b08000000000002ff33d231d633e502000dd2e86e06000000";
+}
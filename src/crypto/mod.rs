@@ -3,4 +3,4 @@
 
 pub mod sha256;
 
-pub use sha256::{sha256, sha256_hex, sha256_file};
+pub use sha256::{sha256, sha256_hex, sha256_file, sha256_reader};
@@ -50,6 +50,7 @@ const K: [u32; 64] = [
 
 /// Rotate right (circular right shift)
 #[inline]
+#[allow(clippy::manual_rotate)]
 fn rotr(x: u32, n: u32) -> u32 {
     (x >> n) | (x << (32 - n))
 }
@@ -254,14 +255,25 @@ pub fn sha256_hex(data: &[u8]) -> String {
 ///
 /// Returns an error if the file cannot be opened or read.
 pub fn sha256_file(path: &Path) -> io::Result<String> {
-    let mut file = File::open(path)?;
+    sha256_reader(File::open(path)?)
+}
+
+/// Compute SHA-256 hash of everything read from a reader
+///
+/// Streams the data in chunks, like [`sha256_file`]; used for archive
+/// members, which are read through a decompressor rather than opened.
+///
+/// # Errors
+///
+/// Returns the first error the reader reports.
+pub fn sha256_reader(mut reader: impl Read) -> io::Result<String> {
     let mut state = H;
     let mut buffer = [0u8; 8192]; // 8KB buffer for reading
     let mut total_bytes = 0u64;
     let mut pending = Vec::new();
 
     loop {
-        let bytes_read = file.read(&mut buffer)?;
+        let bytes_read = reader.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
         }
@@ -374,6 +386,12 @@ mod tests {
         std::fs::remove_file(temp_file).ok();
     }
 
+    #[test]
+    fn test_sha256_reader_matches_in_memory_hash() {
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        assert_eq!(sha256_reader(&data[..]).unwrap(), sha256_hex(&data));
+    }
+
     #[test]
     fn test_sha256_file_large() {
         // Test with a file larger than the buffer size
@@ -0,0 +1,429 @@
+//! croissant.json generation (MLCommons Croissant 1.0 JSON-LD)
+
+use super::{escape_json, is_generated_file};
+use crate::types::{AnalysisResult, ArchiveFormat, ColumnType, CsvAnalysis, DatasetSummary, FileInfo, FileType};
+
+/// Croissant specification version the generated document conforms to
+const CROISSANT_CONFORMS_TO: &str = "http://mlcommons.org/croissant/1.0";
+
+/// Generate a croissant.json file for a dataset
+///
+/// Creates Croissant JSON-LD metadata so ML tooling can load the dataset
+/// directly. Every data file becomes a `cr:FileObject` in `distribution`
+/// (with its SHA-256 when hashing was enabled), except Zarr stores, which
+/// are directories: they become a `cr:FileSet` whose `includes` glob
+/// matches the files of the store. Every analyzed CSV/TSV
+/// becomes a `cr:RecordSet` whose fields extract the CSV columns.
+///
+/// `files` and `analyses` are parallel slices, as produced by the scanner
+/// and analyzer.
+pub fn generate_croissant(
+    summary: &DatasetSummary,
+    files: &[FileInfo],
+    analyses: &[AnalysisResult],
+) -> String {
+    let mut json = String::new();
+
+    json.push_str("{\n");
+    push_context(&mut json);
+    json.push_str("  \"@type\": \"sc:Dataset\",\n");
+    json.push_str(&format!("  \"conformsTo\": \"{}\",\n", CROISSANT_CONFORMS_TO));
+    json.push_str("  \"name\": \"[TODO: Dataset Name]\",\n");
+    json.push_str("  \"description\": \"[TODO: Provide a comprehensive description of this dataset]\",\n");
+    json.push_str("  \"license\": \"[TODO: License URL, e.g., https://creativecommons.org/licenses/by/4.0/]\",\n");
+    json.push_str("  \"url\": \"[TODO: Landing page URL for this dataset]\",\n");
+    json.push_str("  \"creator\": {\n");
+    json.push_str("    \"@type\": \"sc:Person\",\n");
+    json.push_str("    \"name\": \"[TODO: Name of dataset creator or organization]\"\n");
+    json.push_str("  },\n");
+    json.push_str("  \"datePublished\": \"[TODO: YYYY-MM-DD]\",\n");
+    json.push_str(&format!(
+        "  \"dateModified\": \"{}\",\n",
+        escape_json(&summary.scan_timestamp)
+    ));
+
+    // Distribution: one FileObject per data file (not the documentation
+    // this tool writes, whose hashes change whenever it is updated)
+    let data_files: Vec<(&FileInfo, &AnalysisResult)> = files
+        .iter()
+        .zip(analyses.iter())
+        .filter(|(f, _)| f.is_data() && !f.is_archive_member() && !is_generated_file(f))
+        .collect();
+    json.push_str("  \"distribution\": [\n");
+    for (idx, (file, analysis)) in data_files.iter().enumerate() {
+        let comma = if idx < data_files.len() - 1 { "," } else { "" };
+        let path = file_object_id(file);
+
+        json.push_str("    {\n");
+        if file.file_type == FileType::Zarr {
+            push_file_set(&mut json, file, &path);
+            json.push_str(&format!("    }}{}\n", comma));
+            continue;
+        }
+        json.push_str("      \"@type\": \"cr:FileObject\",\n");
+        json.push_str(&format!("      \"@id\": \"{}\",\n", escape_json(&path)));
+        json.push_str(&format!(
+            "      \"name\": \"{}\",\n",
+            escape_json(file.file_name().unwrap_or(&path))
+        ));
+        json.push_str(&format!("      \"contentUrl\": \"{}\",\n", escape_json(&path)));
+        json.push_str(&format!("      \"contentSize\": \"{} B\",\n", file.size_bytes));
+        if let Some(ref hash) = file.sha256_hash {
+            json.push_str(&format!("      \"sha256\": \"{}\",\n", hash));
+        }
+        json.push_str(&format!(
+            "      \"encodingFormat\": \"{}\"\n",
+            encoding_format(file.file_type, analysis)
+        ));
+        json.push_str(&format!("    }}{}\n", comma));
+    }
+    json.push_str("  ],\n");
+
+    // Record sets: one per analyzed table (CSV, TSV, Parquet, or each
+    // table of a SQLite database)
+    let csv_files: Vec<(&FileInfo, Option<&str>, &CsvAnalysis)> = files
+        .iter()
+        .zip(analyses.iter())
+        .filter(|(file, _)| !file.is_archive_member())
+        .flat_map(|(file, analysis)| analysis.tables().into_iter().map(move |(table, csv)| (file, table, csv)))
+        .collect();
+
+    json.push_str("  \"recordSet\": [\n");
+    for (idx, (file, table, csv)) in csv_files.iter().enumerate() {
+        let comma = if idx < csv_files.len() - 1 { "," } else { "" };
+        push_record_set(&mut json, file, *table, csv);
+        json.push_str(&format!("    }}{}\n", comma));
+    }
+    json.push_str("  ]\n");
+
+    json.push_str("}\n");
+
+    json
+}
+
+/// Write the standard Croissant 1.0 JSON-LD context
+fn push_context(json: &mut String) {
+    json.push_str("  \"@context\": {\n");
+    json.push_str("    \"@language\": \"en\",\n");
+    json.push_str("    \"@vocab\": \"https://schema.org/\",\n");
+    json.push_str("    \"sc\": \"https://schema.org/\",\n");
+    json.push_str("    \"cr\": \"http://mlcommons.org/croissant/\",\n");
+    json.push_str("    \"rai\": \"http://mlcommons.org/croissant/RAI/\",\n");
+    json.push_str("    \"dct\": \"http://purl.org/dc/terms/\",\n");
+    json.push_str("    \"citeAs\": \"cr:citeAs\",\n");
+    json.push_str("    \"column\": \"cr:column\",\n");
+    json.push_str("    \"conformsTo\": \"dct:conformsTo\",\n");
+    json.push_str("    \"data\": { \"@id\": \"cr:data\", \"@type\": \"@json\" },\n");
+    json.push_str("    \"dataType\": { \"@id\": \"cr:dataType\", \"@type\": \"@vocab\" },\n");
+    json.push_str("    \"examples\": { \"@id\": \"cr:examples\", \"@type\": \"@json\" },\n");
+    json.push_str("    \"extract\": \"cr:extract\",\n");
+    json.push_str("    \"field\": \"cr:field\",\n");
+    json.push_str("    \"fileObject\": \"cr:fileObject\",\n");
+    json.push_str("    \"fileProperty\": \"cr:fileProperty\",\n");
+    json.push_str("    \"fileSet\": \"cr:fileSet\",\n");
+    json.push_str("    \"format\": \"cr:format\",\n");
+    json.push_str("    \"includes\": \"cr:includes\",\n");
+    json.push_str("    \"isLiveDataset\": \"cr:isLiveDataset\",\n");
+    json.push_str("    \"jsonPath\": \"cr:jsonPath\",\n");
+    json.push_str("    \"key\": \"cr:key\",\n");
+    json.push_str("    \"md5\": \"cr:md5\",\n");
+    json.push_str("    \"parentField\": \"cr:parentField\",\n");
+    json.push_str("    \"path\": \"cr:path\",\n");
+    json.push_str("    \"recordSet\": \"cr:recordSet\",\n");
+    json.push_str("    \"references\": \"cr:references\",\n");
+    json.push_str("    \"regex\": \"cr:regex\",\n");
+    json.push_str("    \"repeated\": \"cr:repeated\",\n");
+    json.push_str("    \"replace\": \"cr:replace\",\n");
+    json.push_str("    \"separator\": \"cr:separator\",\n");
+    json.push_str("    \"source\": \"cr:source\",\n");
+    json.push_str("    \"subField\": \"cr:subField\",\n");
+    json.push_str("    \"transform\": \"cr:transform\"\n");
+    json.push_str("  },\n");
+}
+
+/// Write the members of a FileSet covering the files of a Zarr store
+fn push_file_set(json: &mut String, file: &FileInfo, path: &str) {
+    json.push_str("      \"@type\": \"cr:FileSet\",\n");
+    json.push_str(&format!("      \"@id\": \"{}\",\n", escape_json(path)));
+    json.push_str(&format!(
+        "      \"name\": \"{}\",\n",
+        escape_json(file.file_name().unwrap_or(path))
+    ));
+    json.push_str("      \"encodingFormat\": \"application/x-zarr\",\n");
+    json.push_str(&format!("      \"includes\": \"{}/**\"\n", escape_json(path)));
+}
+
+/// Write a RecordSet (without its closing brace) describing a CSV file, or
+/// one table of a database
+fn push_record_set(json: &mut String, file: &FileInfo, table: Option<&str>, csv: &CsvAnalysis) {
+    let file_id = file_object_id(file);
+    let record_set_id = match table {
+        Some(table) => format!("{}/{}", record_set_id(file), table),
+        None => record_set_id(file),
+    };
+    let source = match table {
+        Some(table) => format!("{}, table {}", file_id, table),
+        None => file_id.clone(),
+    };
+
+    json.push_str("    {\n");
+    json.push_str("      \"@type\": \"cr:RecordSet\",\n");
+    json.push_str(&format!("      \"@id\": \"{}\",\n", escape_json(&record_set_id)));
+    json.push_str(&format!("      \"name\": \"{}\",\n", escape_json(&record_set_id)));
+    json.push_str(&format!(
+        "      \"description\": \"Records from {} ({} rows)\",\n",
+        escape_json(&source),
+        csv.row_count
+    ));
+    json.push_str("      \"field\": [\n");
+
+    let col_count = csv.columns.len();
+    for (idx, column) in csv.columns.iter().enumerate() {
+        let comma = if idx < col_count - 1 { "," } else { "" };
+        let default_name = format!("column_{}", idx);
+        let col_name = column.name.as_deref().unwrap_or(&default_name);
+
+        json.push_str("        {\n");
+        json.push_str("          \"@type\": \"cr:Field\",\n");
+        json.push_str(&format!(
+            "          \"@id\": \"{}/{}\",\n",
+            escape_json(&record_set_id),
+            escape_json(col_name)
+        ));
+        json.push_str(&format!("          \"name\": \"{}\",\n", escape_json(col_name)));
+        json.push_str(&format!(
+            "          \"description\": \"[TODO: Describe {}] (inferred type: {})\",\n",
+            escape_json(col_name),
+            column.inferred_type
+        ));
+        json.push_str(&format!(
+            "          \"dataType\": \"{}\",\n",
+            column_type_to_croissant(column.inferred_type)
+        ));
+        json.push_str("          \"source\": {\n");
+        json.push_str(&format!(
+            "            \"fileObject\": {{ \"@id\": \"{}\" }},\n",
+            escape_json(&file_id)
+        ));
+        json.push_str(&format!(
+            "            \"extract\": {{ \"column\": \"{}\" }}\n",
+            escape_json(col_name)
+        ));
+        json.push_str("          }\n");
+        json.push_str(&format!("        }}{}\n", comma));
+    }
+
+    json.push_str("      ]\n");
+}
+
+/// FileObject identifier: the relative path with forward slashes
+fn file_object_id(file: &FileInfo) -> String {
+    file.relative_path.to_string_lossy().replace('\\', "/")
+}
+
+/// RecordSet identifier: the relative path without extension
+fn record_set_id(file: &FileInfo) -> String {
+    file.relative_path
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Convert ColumnType to a schema.org data type
+fn column_type_to_croissant(col_type: ColumnType) -> &'static str {
+    match col_type {
+        ColumnType::Integer => "sc:Integer",
+        ColumnType::Float => "sc:Float",
+        ColumnType::Boolean => "sc:Boolean",
+        ColumnType::Timestamp => "sc:DateTime",
+        ColumnType::Date => "sc:Date",
+        ColumnType::Time => "sc:Time",
+        ColumnType::String | ColumnType::Identifier | ColumnType::Unknown => "sc:Text",
+    }
+}
+
+/// MIME type used for a FileObject's encodingFormat
+fn encoding_format(file_type: FileType, analysis: &AnalysisResult) -> &'static str {
+    match analysis {
+        AnalysisResult::Parquet(_) => return "application/x-parquet",
+        AnalysisResult::Sqlite(_) => return "application/vnd.sqlite3",
+        AnalysisResult::Tiff(_) => return "image/tiff",
+        AnalysisResult::Pdf(_) => return "application/pdf",
+        AnalysisResult::Archive(archive) => {
+            return match archive.format {
+                ArchiveFormat::Zip => "application/zip",
+                ArchiveFormat::Tar => "application/x-tar",
+            }
+        }
+        _ => {}
+    }
+    match file_type {
+        FileType::Csv => "text/csv",
+        FileType::Tsv => "text/tab-separated-values",
+        FileType::Json => "application/json",
+        FileType::JsonLines => "application/jsonlines",
+        FileType::Text => "text/plain",
+        FileType::Markdown => "text/markdown",
+        FileType::Yaml => "application/yaml",
+        FileType::Toml => "application/toml",
+        FileType::Xml => "application/xml",
+        FileType::Fasta => "text/x-fasta",
+        FileType::Fastq => "text/x-fastq",
+        FileType::Vcf => "text/x-vcf",
+        FileType::Zarr => "application/x-zarr",
+        FileType::Binary | FileType::Unknown => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::parse_json;
+    use crate::types::ColumnInfo;
+    use std::path::PathBuf;
+
+    fn sample_dataset() -> (Vec<FileInfo>, Vec<AnalysisResult>) {
+        let files = vec![
+            FileInfo::new(
+                PathBuf::from("/data/README.md"),
+                PathBuf::from("README.md"),
+            ),
+            FileInfo::new(
+                PathBuf::from("/data/data/measurements.csv"),
+                PathBuf::from("data/measurements.csv"),
+            )
+            .with_size(128)
+            .with_hash("ab".repeat(32)),
+        ];
+
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.row_count = 5;
+        csv.columns = vec![
+            ColumnInfo::new(0)
+                .with_name("timestamp".to_string())
+                .with_type(ColumnType::Timestamp),
+            ColumnInfo::new(1)
+                .with_name("temperature".to_string())
+                .with_type(ColumnType::Float),
+        ];
+
+        let analyses = vec![AnalysisResult::NotAnalyzed, AnalysisResult::Csv(csv)];
+        (files, analyses)
+    }
+
+    #[test]
+    fn test_generate_croissant_is_valid_json() {
+        let (files, analyses) = sample_dataset();
+        let content = generate_croissant(&DatasetSummary::new(), &files, &analyses);
+
+        let value = parse_json(&content).expect("croissant.json should parse");
+        assert_eq!(value.get("@type").and_then(|v| v.as_str()), Some("sc:Dataset"));
+        assert_eq!(
+            value.get("conformsTo").and_then(|v| v.as_str()),
+            Some(CROISSANT_CONFORMS_TO)
+        );
+    }
+
+    #[test]
+    fn test_croissant_distribution_has_sha256() {
+        let (files, analyses) = sample_dataset();
+        let content = generate_croissant(&DatasetSummary::new(), &files, &analyses);
+        let value = parse_json(&content).unwrap();
+
+        let distribution = value.get("distribution").and_then(|d| d.as_array()).unwrap();
+        // README is documentation, not data
+        assert_eq!(distribution.len(), 1);
+        assert_eq!(
+            distribution[0].get("contentUrl").and_then(|v| v.as_str()),
+            Some("data/measurements.csv")
+        );
+        assert_eq!(
+            distribution[0].get("sha256").and_then(|v| v.as_str()),
+            Some("ab".repeat(32).as_str())
+        );
+        assert_eq!(
+            distribution[0].get("encodingFormat").and_then(|v| v.as_str()),
+            Some("text/csv")
+        );
+    }
+
+    #[test]
+    fn test_croissant_distribution_has_sequence_files() {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/data/genome.fa"), PathBuf::from("genome.fa")).with_size(64),
+            FileInfo::new(PathBuf::from("/data/calls.vcf.gz"), PathBuf::from("calls.vcf.gz")).with_size(32),
+        ];
+        let analyses = vec![AnalysisResult::NotAnalyzed, AnalysisResult::NotAnalyzed];
+        let content = generate_croissant(&DatasetSummary::new(), &files, &analyses);
+        let value = parse_json(&content).unwrap();
+
+        let distribution = value.get("distribution").and_then(|d| d.as_array()).unwrap();
+        let formats: Vec<&str> = distribution
+            .iter()
+            .filter_map(|d| d.get("encodingFormat").and_then(|v| v.as_str()))
+            .collect();
+        assert_eq!(formats, vec!["text/x-fasta", "text/x-vcf"]);
+        assert_eq!(distribution[0].get("contentUrl").and_then(|v| v.as_str()), Some("genome.fa"));
+    }
+
+    #[test]
+    fn test_croissant_zarr_store_is_file_set() {
+        let files = vec![FileInfo::new(PathBuf::from("/data/grid.zarr"), PathBuf::from("grid.zarr"))
+            .with_type(FileType::Zarr)
+            .with_hash("ab".repeat(32))];
+        let content = generate_croissant(&DatasetSummary::new(), &files, &[AnalysisResult::NotAnalyzed]);
+        let value = parse_json(&content).unwrap();
+
+        let distribution = value.get("distribution").and_then(|d| d.as_array()).unwrap();
+        assert_eq!(distribution[0].get("@type").and_then(|v| v.as_str()), Some("cr:FileSet"));
+        assert_eq!(distribution[0].get("includes").and_then(|v| v.as_str()), Some("grid.zarr/**"));
+        assert!(distribution[0].get("contentUrl").is_none());
+        assert!(distribution[0].get("sha256").is_none());
+    }
+
+    #[test]
+    fn test_croissant_record_set_fields() {
+        let (files, analyses) = sample_dataset();
+        let content = generate_croissant(&DatasetSummary::new(), &files, &analyses);
+        let value = parse_json(&content).unwrap();
+
+        let record_sets = value.get("recordSet").and_then(|r| r.as_array()).unwrap();
+        assert_eq!(record_sets.len(), 1);
+        assert_eq!(
+            record_sets[0].get("@id").and_then(|v| v.as_str()),
+            Some("data/measurements")
+        );
+
+        let fields = record_sets[0].get("field").and_then(|f| f.as_array()).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].get("dataType").and_then(|v| v.as_str()), Some("sc:Float"));
+
+        let source = fields[1].get("source").unwrap();
+        assert_eq!(
+            source.get("fileObject").and_then(|f| f.get("@id")).and_then(|v| v.as_str()),
+            Some("data/measurements.csv")
+        );
+        assert_eq!(
+            source.get("extract").and_then(|e| e.get("column")).and_then(|v| v.as_str()),
+            Some("temperature")
+        );
+    }
+
+    #[test]
+    fn test_croissant_empty_dataset() {
+        let content = generate_croissant(&DatasetSummary::new(), &[], &[]);
+        let value = parse_json(&content).unwrap();
+        assert_eq!(value.get("distribution").and_then(|d| d.as_array()).map(|d| d.len()), Some(0));
+        assert_eq!(value.get("recordSet").and_then(|r| r.as_array()).map(|r| r.len()), Some(0));
+    }
+
+    #[test]
+    fn test_column_type_to_croissant() {
+        assert_eq!(column_type_to_croissant(ColumnType::Integer), "sc:Integer");
+        assert_eq!(column_type_to_croissant(ColumnType::Float), "sc:Float");
+        assert_eq!(column_type_to_croissant(ColumnType::Boolean), "sc:Boolean");
+        assert_eq!(column_type_to_croissant(ColumnType::Timestamp), "sc:DateTime");
+        assert_eq!(column_type_to_croissant(ColumnType::Date), "sc:Date");
+        assert_eq!(column_type_to_croissant(ColumnType::Identifier), "sc:Text");
+    }
+}
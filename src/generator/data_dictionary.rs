@@ -0,0 +1,255 @@
+//! DATA_DICTIONARY.md / DATA_DICTIONARY.csv generation (column codebook)
+
+use super::escape_csv;
+use crate::types::{AnalysisResult, ColumnInfo, CsvAnalysis, FileInfo};
+
+/// File name of the Markdown data dictionary
+pub const DATA_DICTIONARY_MD: &str = "DATA_DICTIONARY.md";
+
+/// File name of the CSV data dictionary
+pub const DATA_DICTIONARY_CSV: &str = "DATA_DICTIONARY.csv";
+
+/// Generate a Markdown data dictionary
+///
+/// Lists every column of every analyzed CSV/TSV file with its inferred
+/// type, null count, distinct count, numeric range and sample values.
+/// The Description and Unit cells are left blank for the researcher to
+/// fill in. Output is deterministic so it can be regenerated and diffed.
+pub fn generate_data_dictionary(files: &[FileInfo], analyses: &[AnalysisResult]) -> String {
+    let mut md = String::new();
+
+    md.push_str("# Data Dictionary\n\n");
+    md.push_str("Codebook for every column of every tabular file in this dataset.\n");
+    md.push_str("Fill in the **Description** and **Unit** cells for each column ");
+    md.push_str("(use `-` for unitless columns).\n");
+
+    let tables = tabular_files(files, analyses);
+    if tables.is_empty() {
+        md.push_str("\nNo CSV, TSV, Parquet or SQLite tables were found in this dataset.\n");
+        return md;
+    }
+
+    for (path, csv) in tables {
+        md.push_str(&format!("\n## {}\n\n", path));
+        md.push_str(&format!("- Rows: {}\n", csv.row_count));
+        md.push_str(&format!("- Columns: {}\n\n", csv.column_count));
+        md.push_str("| Column | Type | Nulls | Unique | Range | Sample Values | Description | Unit |\n");
+        md.push_str("|--------|------|-------|--------|-------|---------------|-------------|------|\n");
+
+        for column in &csv.columns {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |  |  |\n",
+                escape_cell(&column_name(column)),
+                column.inferred_type,
+                column.null_count,
+                unique_text(column),
+                range_text(column),
+                escape_cell(&column.sample_values.join(", ")),
+            ));
+        }
+    }
+
+    md
+}
+
+/// Generate a CSV data dictionary
+///
+/// Same content as `generate_data_dictionary`, one row per column, for
+/// curators who prefer filling the codebook in a spreadsheet.
+pub fn generate_data_dictionary_csv(files: &[FileInfo], analyses: &[AnalysisResult]) -> String {
+    let mut csv_out = String::new();
+
+    csv_out.push_str("file,column,type,null_count,unique_count,min,max,sample_values,description,unit\n");
+
+    for (path, csv) in tabular_files(files, analyses) {
+        for column in &csv.columns {
+            let fields = [
+                path.clone(),
+                column_name(column),
+                column.inferred_type.to_string(),
+                column.null_count.to_string(),
+                unique_text(column),
+                column.min_value.map(format_number).unwrap_or_default(),
+                column.max_value.map(format_number).unwrap_or_default(),
+                column.sample_values.join("; "),
+                String::new(),
+                String::new(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+            csv_out.push_str(&row.join(","));
+            csv_out.push('\n');
+        }
+    }
+
+    csv_out
+}
+
+/// Check whether a file is a generated data dictionary
+pub fn is_data_dictionary(file: &FileInfo) -> bool {
+    file.file_name()
+        .map(|name| name == DATA_DICTIONARY_MD || name == DATA_DICTIONARY_CSV)
+        .unwrap_or(false)
+}
+
+/// Split a Markdown table row into trimmed cells, honouring `\|` escapes
+pub(crate) fn split_table_row(line: &str) -> Vec<String> {
+    let inner = line.trim().trim_start_matches('|');
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = inner.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                current.push('|');
+                chars.next();
+            }
+            '|' => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    cells
+}
+
+/// Collect (relative path, analysis) for every analyzed tabular file, sorted by path
+///
+/// Each table of a database is listed as `path#table`.
+fn tabular_files<'a>(
+    files: &'a [FileInfo],
+    analyses: &'a [AnalysisResult],
+) -> Vec<(String, &'a CsvAnalysis)> {
+    let mut tables: Vec<(String, &CsvAnalysis)> = files
+        .iter()
+        .zip(analyses.iter())
+        .filter(|(file, _)| !is_data_dictionary(file))
+        .flat_map(|(file, analysis)| {
+            let path = file.relative_path.to_string_lossy().replace('\\', "/");
+            analysis.tables().into_iter().map(move |(table, csv)| match table {
+                Some(table) => (format!("{}#{}", path, table), csv),
+                None => (path.clone(), csv),
+            })
+        })
+        .collect();
+
+    tables.sort_by(|a, b| a.0.cmp(&b.0));
+    tables
+}
+
+/// Column name, falling back to the positional name used in schema files
+fn column_name(column: &ColumnInfo) -> String {
+    column
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("column_{}", column.index))
+}
+
+/// Distinct count, marked with `+` when tracking hit its cap
+fn unique_text(column: &ColumnInfo) -> String {
+    if column.unique_count_capped {
+        format!("{}+", column.unique_count)
+    } else {
+        column.unique_count.to_string()
+    }
+}
+
+/// Numeric range as `min to max`, or empty for non-numeric columns
+fn range_text(column: &ColumnInfo) -> String {
+    match (column.min_value, column.max_value) {
+        (Some(min), Some(max)) => format!("{} to {}", format_number(min), format_number(max)),
+        _ => String::new(),
+    }
+}
+
+/// Format a number without a trailing `.0` for whole values
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Escape a value for a Markdown table cell
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ColumnType;
+    use std::path::PathBuf;
+
+    fn sample() -> (Vec<FileInfo>, Vec<AnalysisResult>) {
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.column_count = 2;
+        csv.row_count = 3;
+        csv.columns = vec![
+            ColumnInfo::new(0)
+                .with_name("site".to_string())
+                .with_type(ColumnType::String)
+                .with_unique_count(2, false)
+                .add_sample("north".to_string())
+                .add_sample("a|b".to_string()),
+            ColumnInfo::new(1)
+                .with_name("depth_m".to_string())
+                .with_type(ColumnType::Float)
+                .with_null_count(1)
+                .with_unique_count(1000, true)
+                .with_range(Some(-3.0), Some(40.25)),
+        ];
+
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/data/readings.csv"), PathBuf::from("data/readings.csv")),
+            FileInfo::new(PathBuf::from("/d/README.md"), PathBuf::from("README.md")),
+        ];
+        (files, vec![AnalysisResult::Csv(csv), AnalysisResult::NotAnalyzed])
+    }
+
+    #[test]
+    fn test_generate_data_dictionary_markdown() {
+        let (files, analyses) = sample();
+        let md = generate_data_dictionary(&files, &analyses);
+
+        assert!(md.starts_with("# Data Dictionary\n"));
+        assert!(md.contains("\n## data/readings.csv\n"));
+        assert!(md.contains("- Rows: 3\n"));
+        assert!(md.contains("| site | string | 0 | 2 |  | north, a\\|b |  |  |\n"));
+        assert!(md.contains("| depth_m | float | 1 | 1000+ | -3 to 40.25 |  |  |  |\n"));
+    }
+
+    #[test]
+    fn test_generate_data_dictionary_csv() {
+        let (files, analyses) = sample();
+        let csv = generate_data_dictionary_csv(&files, &analyses);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("file,column,type"));
+        assert_eq!(lines[2], "data/readings.csv,depth_m,float,1,1000+,-3,40.25,,,");
+    }
+
+    #[test]
+    fn test_generate_data_dictionary_no_tables() {
+        let md = generate_data_dictionary(&[], &[]);
+        assert!(md.contains("No CSV, TSV, Parquet or SQLite tables"));
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("plain"), "plain");
+        assert_eq!(escape_csv("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_split_table_row_escapes() {
+        assert_eq!(split_table_row("| a | b\\|c |  |"), vec!["a", "b|c", ""]);
+    }
+}
@@ -1,5 +1,6 @@
 //! DATACARD.md generation
 
+use super::update::marked_region;
 use crate::types::DatasetSummary;
 
 /// Generate a DATACARD.md file for a dataset
@@ -40,16 +41,22 @@ pub fn generate_datacard(summary: &DatasetSummary) -> String {
 
     // Data Format
     content.push_str("## Data Format\n\n");
-    content.push_str(&format!(
+    let mut format_summary = format!(
         "This dataset contains {} files totaling {}.\n\n",
         summary.total_files,
         summary.format_size()
-    ));
-
-    content.push_str("**File Types:**\n");
+    );
+    format_summary.push_str("**File Types:**\n");
     for (file_type, count) in &summary.file_type_counts {
-        content.push_str(&format!("- {}: {} files\n", file_type, count));
+        format_summary.push_str(&format!("- {}: {} files\n", file_type, count));
+    }
+    if !summary.image_sizes.is_empty() {
+        format_summary.push_str("\n**Image Sizes:**\n");
+        for (size, count) in &summary.image_sizes {
+            format_summary.push_str(&format!("- {}: {} images\n", size, count));
+        }
     }
+    content.push_str(&marked_region("data-format", &format_summary));
     content.push('\n');
 
     content.push_str("**File Structure:**\n");
@@ -112,6 +119,17 @@ mod tests {
         assert!(content.contains("genesis-preflight"));
     }
 
+    #[test]
+    fn test_datacard_image_sizes() {
+        let mut summary = DatasetSummary::new();
+        let content = generate_datacard(&summary);
+        assert!(!content.contains("**Image Sizes:**"));
+
+        summary.image_sizes = vec![("224x224, 3 channels".to_string(), 950), ("224x224, 1 channel".to_string(), 50)];
+        let content = generate_datacard(&summary);
+        assert!(content.contains("**Image Sizes:**\n- 224x224, 3 channels: 950 images\n- 224x224, 1 channel: 50 images\n"));
+    }
+
     #[test]
     fn test_datacard_has_todos() {
         let summary = DatasetSummary::new();
@@ -0,0 +1,203 @@
+//! Line-based unified diff (for update and preview output)
+
+/// Lines of context around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Largest LCS table (old lines x new lines) computed exactly
+///
+/// Beyond this the changed middle section is shown as a full replacement,
+/// which keeps memory bounded for very large manifests.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// A single line-level edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit<'a> {
+    Keep(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Produce a unified diff between two texts
+///
+/// Returns an empty string when the texts are identical. Labels are used
+/// for the `---`/`+++` header lines.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = diff_lines(&old_lines, &new_lines);
+
+    let mut out = String::new();
+    out.push_str(&format!("--- {}\n", old_label));
+    out.push_str(&format!("+++ {}\n", new_label));
+
+    for (start, end) in hunk_ranges(&edits) {
+        // Line numbers (1-based) at the start of the hunk
+        let old_start = 1 + edits[..start].iter().filter(|e| !matches!(e, Edit::Insert(_))).count();
+        let new_start = 1 + edits[..start].iter().filter(|e| !matches!(e, Edit::Delete(_))).count();
+        let old_count = edits[start..end].iter().filter(|e| !matches!(e, Edit::Insert(_))).count();
+        let new_count = edits[start..end].iter().filter(|e| !matches!(e, Edit::Delete(_))).count();
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_position(old_start, old_count),
+            hunk_position(new_start, new_count)
+        ));
+
+        for edit in &edits[start..end] {
+            match edit {
+                Edit::Keep(line) => out.push_str(&format!(" {}\n", line)),
+                Edit::Delete(line) => out.push_str(&format!("-{}\n", line)),
+                Edit::Insert(line) => out.push_str(&format!("+{}\n", line)),
+            }
+        }
+    }
+
+    out
+}
+
+/// Format a hunk position as `start,count` (start is 0 for an empty range)
+fn hunk_position(start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", start - 1)
+    } else {
+        format!("{},{}", start, count)
+    }
+}
+
+/// Compute line edits: common prefix/suffix, then LCS on the middle
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut edits: Vec<Edit> = old[..prefix].iter().map(|l| Edit::Keep(l)).collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) <= MAX_LCS_CELLS {
+        edits.extend(lcs_edits(old_mid, new_mid));
+    } else {
+        edits.extend(old_mid.iter().map(|l| Edit::Delete(l)));
+        edits.extend(new_mid.iter().map(|l| Edit::Insert(l)));
+    }
+
+    edits.extend(old[old.len() - suffix..].iter().map(|l| Edit::Keep(l)));
+    edits
+}
+
+/// Classic dynamic-programming LCS diff
+fn lcs_edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let n = old.len();
+    let m = new.len();
+    let width = m + 1;
+
+    // table[i][j] = LCS length of old[i..] and new[j..]
+    let mut table = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if old[i] == new[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            edits.push(Edit::Keep(old[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            edits.push(Edit::Delete(old[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Insert(new[j]));
+            j += 1;
+        }
+    }
+    edits.extend(old[i..].iter().map(|l| Edit::Delete(l)));
+    edits.extend(new[j..].iter().map(|l| Edit::Insert(l)));
+
+    edits
+}
+
+/// Group changed edits into hunk ranges with surrounding context
+fn hunk_ranges(edits: &[Edit]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for (idx, edit) in edits.iter().enumerate() {
+        if matches!(edit, Edit::Keep(_)) {
+            continue;
+        }
+
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + 1 + CONTEXT_LINES).min(edits.len());
+
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new"), "");
+    }
+
+    #[test]
+    fn test_single_change() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n";
+        let diff = unified_diff(old, new, "a/file", "b/file");
+
+        assert_eq!(
+            diff,
+            "--- a/file\n+++ b/file\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn test_new_file() {
+        let diff = unified_diff("", "a\nb\n", "/dev/null", "b/file");
+        assert!(diff.contains("@@ -0,0 +1,2 @@\n+a\n+b\n"));
+    }
+
+    #[test]
+    fn test_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 19\n", "line nineteen\n");
+        let diff = unified_diff(&old, &new, "a", "b");
+        assert_eq!(diff.matches("@@ -").count(), 2);
+    }
+
+    #[test]
+    fn test_insertion_in_middle() {
+        let diff = unified_diff("a\nc\n", "a\nb\nc\n", "a", "b");
+        assert!(diff.contains(" a\n+b\n c\n"));
+    }
+}
@@ -1,18 +1,20 @@
 //! MANIFEST.sha256 generation
 
-use crate::types::FileInfo;
+use crate::types::{FileInfo, FileType};
 
 /// Generate a MANIFEST.sha256 file
 ///
 /// Creates a manifest file listing SHA-256 hashes for all files in the dataset.
-/// Format is compatible with `sha256sum -c` command.
+/// Format is compatible with `sha256sum -c` command, so archive members
+/// and Zarr stores (directories), which `sha256sum` cannot open, are left
+/// out.
 pub fn generate_manifest(files: &[FileInfo]) -> String {
     let mut manifest = String::new();
 
     // Collect files with hashes
     let mut files_with_hashes: Vec<&FileInfo> = files
         .iter()
-        .filter(|f| f.sha256_hash.is_some())
+        .filter(|f| f.sha256_hash.is_some() && !f.is_archive_member() && f.file_type != FileType::Zarr)
         .collect();
 
     // Sort by relative path for consistency
@@ -97,6 +99,46 @@ mod tests {
         assert!(!manifest.contains("no_hash.txt"));
     }
 
+    #[test]
+    fn test_manifest_skips_archive_members() {
+        let entry = crate::types::ArchiveEntry {
+            name: "obs.csv".to_string(),
+            kind: crate::types::ArchiveEntryKind::File,
+            method: crate::types::ArchiveMethod::Stored,
+            encrypted: false,
+            link_target: None,
+            size: 4,
+            compressed_size: 4,
+            offset: 512,
+        };
+        let files = vec![
+            FileInfo::new(PathBuf::from("data.tar"), PathBuf::from("data.tar")).with_hash("abc123".to_string()),
+            FileInfo::new(PathBuf::from("data.tar!/obs.csv"), PathBuf::from("data.tar!/obs.csv"))
+                .with_hash("def456".to_string())
+                .with_archive_member(crate::types::ArchiveMember {
+                    archive: PathBuf::from("data.tar"),
+                    format: crate::types::ArchiveFormat::Tar,
+                    entry,
+                }),
+        ];
+
+        let manifest = generate_manifest(&files);
+
+        assert_eq!(manifest, "abc123  data.tar\n");
+    }
+
+    #[test]
+    fn test_manifest_skips_zarr_stores() {
+        let files = vec![
+            FileInfo::new(PathBuf::from("grid.zarr"), PathBuf::from("grid.zarr"))
+                .with_type(FileType::Zarr)
+                .with_hash("abc123".to_string()),
+            FileInfo::new(PathBuf::from("obs.csv"), PathBuf::from("obs.csv")).with_hash("def456".to_string()),
+        ];
+
+        assert_eq!(generate_manifest(&files), "def456  obs.csv\n");
+    }
+
     #[test]
     fn test_manifest_format() {
         let files = vec![FileInfo::new(
@@ -1,25 +1,48 @@
 //! Documentation generation module
 //!
 //! This module generates missing documentation files for datasets,
-//! including README, metadata.json, schema files, and manifests.
+//! including README, metadata.json, Croissant metadata, data dictionaries,
+//! schema files, and manifests.
 
+mod croissant;
+mod data_dictionary;
 mod datacard;
+mod diff;
 mod manifest;
 mod metadata_json;
 mod readme;
 mod schema;
+mod update;
 
-use crate::types::{AnalysisResult, Config, DatasetSummary, FileInfo, ValidationResult};
+use crate::crypto::sha256_hex;
+use crate::types::{AnalysisResult, Config, DatasetSummary, FileInfo, JsonShape, ValidationResult};
 use std::fmt;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
-
+use std::path::{Path, PathBuf};
+
+pub use croissant::generate_croissant;
+pub use data_dictionary::{
+    generate_data_dictionary, generate_data_dictionary_csv, is_data_dictionary,
+    DATA_DICTIONARY_CSV, DATA_DICTIONARY_MD,
+};
+pub(crate) use data_dictionary::split_table_row;
 pub use datacard::generate_datacard;
+pub use diff::unified_diff;
 pub use manifest::generate_manifest;
 pub use metadata_json::generate_metadata;
 pub use readme::generate_readme;
-pub use schema::generate_schema;
+pub use schema::{generate_hdf5_schema, generate_json_schema, generate_schema, table_schema_name};
+pub use update::{
+    keep_existing, marked_region, merge_croissant, merge_data_dictionary, merge_data_dictionary_csv,
+    merge_markdown, merge_metadata_json, merge_schema_json, replace_all,
+};
+
+/// Merges regenerated content into an existing file: `(existing, fresh) -> merged`
+///
+/// Used by `generate --update`; returning `existing` unchanged means there
+/// is nothing to update.
+pub type MergeFn = fn(&str, &str) -> Result<String, String>;
 
 /// Represents a generated file
 #[derive(Debug, Clone)]
@@ -30,6 +53,10 @@ pub struct GeneratedFile {
     pub content: String,
     /// Whether the file was created (true) or skipped because it exists (false)
     pub was_created: bool,
+    /// Whether an existing file was merge-updated (`generate --update`)
+    pub was_updated: bool,
+    /// Unified diff of the changes made to an updated file
+    pub diff: Option<String>,
 }
 
 impl GeneratedFile {
@@ -39,6 +66,8 @@ impl GeneratedFile {
             path,
             content: String::new(),
             was_created: true,
+            was_updated: false,
+            diff: None,
         }
     }
 
@@ -48,6 +77,30 @@ impl GeneratedFile {
             path,
             content: String::new(),
             was_created: false,
+            was_updated: false,
+            diff: None,
+        }
+    }
+
+    /// Create a GeneratedFile that was merge-updated in place
+    pub fn updated(path: PathBuf, diff: String) -> Self {
+        GeneratedFile {
+            path,
+            content: String::new(),
+            was_created: false,
+            was_updated: true,
+            diff: Some(diff),
+        }
+    }
+
+    /// Planned action for this file: `create`, `update` or `skip`
+    pub fn action(&self) -> &'static str {
+        if self.was_created {
+            "create"
+        } else if self.was_updated {
+            "update"
+        } else {
+            "skip"
         }
     }
 }
@@ -61,6 +114,8 @@ pub enum GenerationError {
     FileExists(PathBuf),
     /// Invalid output directory
     InvalidOutputDir(String),
+    /// Existing file could not be merged with regenerated content
+    MergeFailed(PathBuf, String),
 }
 
 impl fmt::Display for GenerationError {
@@ -69,6 +124,9 @@ impl fmt::Display for GenerationError {
             GenerationError::Io(e) => write!(f, "IO error: {}", e),
             GenerationError::FileExists(p) => write!(f, "File exists: {}", p.display()),
             GenerationError::InvalidOutputDir(s) => write!(f, "Invalid output directory: {}", s),
+            GenerationError::MergeFailed(p, s) => {
+                write!(f, "Cannot update {}: {}", p.display(), s)
+            }
         }
     }
 }
@@ -84,7 +142,8 @@ impl std::error::Error for GenerationError {}
 /// Generate documentation files for a dataset
 ///
 /// Creates missing documentation files based on validation results and analysis.
-/// Never overwrites existing files.
+/// Never overwrites existing files, unless `config.update` is set, in which
+/// case machine-owned content in existing files is merge-updated.
 ///
 /// # Arguments
 ///
@@ -106,54 +165,170 @@ pub fn generate_documentation(
     let output_dir = config.get_output_dir();
 
     // Create dataset summary
-    let summary = create_dataset_summary(files);
+    let summary = create_dataset_summary(files, analyses);
 
     // Generate README if missing
-    if !has_readme(files) {
+    if config.update || !has_readme(files) {
         let content = readme::generate_readme(&summary);
         let path = output_dir.join("README.md");
-        generated.push(write_file(&path, &content, config)?);
+        generated.push(write_generated(&path, &content, merge_markdown, config)?);
     }
 
     // Generate metadata.json if missing
-    if !has_metadata_json(files) {
+    if config.update || !has_metadata_json(files) {
         let content = metadata_json::generate_metadata(&summary);
         let path = output_dir.join("metadata.json");
-        generated.push(write_file(&path, &content, config)?);
-    }
-
-    // Generate MANIFEST.sha256 if files have hashes
-    if files.iter().any(|f| f.sha256_hash.is_some()) {
-        let content = manifest::generate_manifest(files);
-        let path = output_dir.join("MANIFEST.sha256");
-        generated.push(write_file(&path, &content, config)?);
+        generated.push(write_generated(&path, &content, merge_metadata_json, config)?);
     }
 
     // Generate DATACARD.md if missing
-    if !has_datacard(files) {
+    if config.update || !has_datacard(files) {
         let content = datacard::generate_datacard(&summary);
         let path = output_dir.join("DATACARD.md");
-        generated.push(write_file(&path, &content, config)?);
+        generated.push(write_generated(&path, &content, merge_markdown, config)?);
     }
 
-    // Generate schema files for CSV files
+    // Generate croissant.json if missing
+    if config.update || !has_croissant(files) {
+        let content = croissant::generate_croissant(&summary, files, analyses);
+        let path = output_dir.join("croissant.json");
+        generated.push(write_generated(&path, &content, merge_croissant, config)?);
+    }
+
+    // Generate the data dictionary if there are tabular files
+    if analyses.iter().any(|a| !a.tables().is_empty()) {
+        let existing = files.iter().find(|f| is_data_dictionary(f));
+        let use_csv = match existing {
+            Some(f) => f.file_name() == Some(DATA_DICTIONARY_CSV),
+            None => config.dictionary_csv,
+        };
+
+        if config.update || existing.is_none() {
+            let path = output_dir.join(if use_csv { DATA_DICTIONARY_CSV } else { DATA_DICTIONARY_MD });
+            let file = if use_csv {
+                let content = data_dictionary::generate_data_dictionary_csv(files, analyses);
+                write_generated(&path, &content, merge_data_dictionary_csv, config)?
+            } else {
+                let content = data_dictionary::generate_data_dictionary(files, analyses);
+                write_generated(&path, &content, merge_data_dictionary, config)?
+            };
+            generated.push(file);
+        }
+    }
+
+    // Generate schema files for tabular files (Parquet schemas keep the
+    // extension, as `sim.parquet` and `sim.csv` may sit side by side; each
+    // table of a SQLite database gets its own schema)
     for (file, analysis) in files.iter().zip(analyses.iter()) {
-        if let AnalysisResult::Csv(csv_analysis) = analysis {
-            if let Some(filename) = file.file_name() {
-                let schema_name = format!("{}.schema.json", filename.trim_end_matches(".csv"));
+        if file.is_archive_member() {
+            continue;
+        }
+        if let Some(filename) = file.file_name() {
+            for (table, csv_analysis) in analysis.tables() {
+                let (schema_name, title) = match table {
+                    Some(table) => (schema::table_schema_name(filename, table), format!("{}#{}", filename, table)),
+                    None => (format!("{}.schema.json", filename.trim_end_matches(".csv")), filename.to_string()),
+                };
                 let schema_path = output_dir.join(&schema_name);
 
-                if !schema_path.exists() {
-                    let content = schema::generate_schema(csv_analysis, filename);
-                    generated.push(write_file(&schema_path, &content, config)?);
+                if config.update || !schema_path.exists() {
+                    let content = schema::generate_schema(csv_analysis, &title);
+                    generated.push(write_generated(&schema_path, &content, merge_schema_json, config)?);
+                }
+            }
+        }
+    }
+
+    // Generate schema files for HDF5 files (named after the full file name,
+    // so `sim.h5` and `sim.csv` do not collide)
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Hdf5(hdf5_analysis) = analysis {
+            if let Some(filename) = file.file_name() {
+                let schema_path = output_dir.join(format!("{}.schema.json", filename));
+
+                if config.update || !schema_path.exists() {
+                    let content = schema::generate_hdf5_schema(hdf5_analysis, filename);
+                    generated.push(write_generated(&schema_path, &content, merge_schema_json, config)?);
+                }
+            }
+        }
+    }
+
+    // Generate schema files for JSON data files (named after the full file
+    // name, so `obs.json` and `obs.csv` do not collide)
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let Some(structure) = json_data_structure(file, analysis) {
+            if let Some(filename) = file.file_name() {
+                let schema_path = output_dir.join(format!("{}.schema.json", filename));
+
+                if config.update || !schema_path.exists() {
+                    let content = schema::generate_json_schema(structure, filename);
+                    generated.push(write_generated(&schema_path, &content, merge_schema_json, config)?);
                 }
             }
         }
     }
 
+    // Generate MANIFEST.sha256 last so it covers updated documentation
+    if files.iter().any(|f| f.sha256_hash.is_some()) {
+        let manifest_files = refresh_hashes(files, &generated, &config.target_path, "MANIFEST.sha256");
+        let content = manifest::generate_manifest(&manifest_files);
+        let path = output_dir.join("MANIFEST.sha256");
+        generated.push(write_generated(&path, &content, replace_all, config)?);
+    }
+
     Ok(generated)
 }
 
+/// Prepare files for a manifest after documentation was written
+///
+/// Drops the manifest itself, re-hashes any file that was merge-updated
+/// during this run from its new content, and adds the files this run
+/// created under the dataset `root`, so the manifest matches what is (or,
+/// with `--dry-run`, would be) on disk and a later `--update` finds nothing
+/// to change. Created files are only added when the dataset was hashed.
+pub fn refresh_hashes(
+    files: &[FileInfo],
+    generated: &[GeneratedFile],
+    root: &Path,
+    manifest_name: &str,
+) -> Vec<FileInfo> {
+    let mut refreshed: Vec<FileInfo> = files
+        .iter()
+        .filter(|f| f.relative_path != Path::new(manifest_name))
+        .map(|f| {
+            let updated = generated
+                .iter()
+                .find(|g| g.was_updated && g.path == f.full_path);
+            match updated {
+                Some(g) if f.sha256_hash.is_some() => {
+                    let mut refreshed = f.clone();
+                    refreshed.sha256_hash = Some(sha256_hex(g.content.as_bytes()));
+                    refreshed.size_bytes = g.content.len() as u64;
+                    refreshed
+                }
+                _ => f.clone(),
+            }
+        })
+        .collect();
+
+    if files.iter().any(|f| f.sha256_hash.is_some()) {
+        for g in generated.iter().filter(|g| g.was_created) {
+            let relative = match g.path.strip_prefix(root) {
+                Ok(r) if r != Path::new(manifest_name) => r,
+                _ => continue,
+            };
+            refreshed.push(
+                FileInfo::new(g.path.clone(), relative.to_path_buf())
+                    .with_size(g.content.len() as u64)
+                    .with_hash(sha256_hex(g.content.as_bytes())),
+            );
+        }
+    }
+
+    refreshed
+}
+
 /// Check if dataset has a README file
 fn has_readme(files: &[FileInfo]) -> bool {
     files.iter().any(|f| {
@@ -181,11 +356,69 @@ fn has_datacard(files: &[FileInfo]) -> bool {
     })
 }
 
+/// Check if a file is documentation written by this generator
+///
+/// Dataset statistics exclude these so that regenerating documentation
+/// does not change the numbers it reports.
+pub fn is_generated_file(file: &FileInfo) -> bool {
+    // Generated documentation is only written at the dataset root
+    let at_root = file
+        .relative_path
+        .parent()
+        .map(|p| p.as_os_str().is_empty())
+        .unwrap_or(true);
+    let name = match file.file_name() {
+        Some(n) if at_root => n,
+        _ => return false,
+    };
+    matches!(
+        name,
+        "README.md"
+            | "metadata.json"
+            | "DATACARD.md"
+            | "MANIFEST.txt"
+            | "MANIFEST.sha256"
+            | "croissant.json"
+            | DATA_DICTIONARY_MD
+            | DATA_DICTIONARY_CSV
+    ) || name.ends_with(".schema.json")
+}
+
+/// Structure of a JSON data file that gets its own schema
+///
+/// Documentation the tool reads or writes (metadata.json, croissant.json,
+/// schemas) and files inside archives are left out.
+pub fn json_data_structure<'a>(file: &FileInfo, analysis: &'a AnalysisResult) -> Option<&'a JsonShape> {
+    let structure = match analysis {
+        AnalysisResult::Json(json) => json.structure.as_ref()?,
+        _ => return None,
+    };
+    let is_schema = file.file_name().is_some_and(|name| name.ends_with(".schema.json"));
+    if is_generated_file(file) || is_schema || file.is_archive_member() {
+        return None;
+    }
+    Some(structure)
+}
+
+/// Check if dataset has a croissant.json file
+fn has_croissant(files: &[FileInfo]) -> bool {
+    files.iter().any(|f| {
+        f.file_name()
+            .map(|name| name == "croissant.json")
+            .unwrap_or(false)
+    })
+}
+
 /// Create a dataset summary from file list
-fn create_dataset_summary(files: &[FileInfo]) -> DatasetSummary {
+fn create_dataset_summary(files: &[FileInfo], analyses: &[AnalysisResult]) -> DatasetSummary {
     use crate::types::FileType;
     use std::collections::HashMap;
 
+    let files: Vec<&FileInfo> = files
+        .iter()
+        .filter(|f| !is_generated_file(f) && !f.is_archive_member())
+        .collect();
+
     let mut summary = DatasetSummary::new();
     summary.total_files = files.len();
     summary.total_size = files.iter().map(|f| f.size_bytes).sum();
@@ -197,7 +430,10 @@ fn create_dataset_summary(files: &[FileInfo]) -> DatasetSummary {
     }
 
     summary.file_type_counts = type_counts.into_iter().collect();
-    summary.file_type_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    summary
+        .file_type_counts
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+    summary.image_sizes = crate::analyzer::image_distribution(analyses);
 
     // Set timestamp
     summary.scan_timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
@@ -205,31 +441,102 @@ fn create_dataset_summary(files: &[FileInfo]) -> DatasetSummary {
     summary
 }
 
-/// Write a file, never overwriting existing files
-fn write_file(
-    path: &PathBuf,
+/// Write generated content to `path`
+///
+/// Missing files are created. Existing files are skipped, unless
+/// `config.update` is set: then `merge` combines the existing text with the
+/// regenerated content and the file is rewritten only if that changes it.
+/// With `config.dry_run` nothing is written; the returned plan (including
+/// the diff of an update) is the same as for a real run.
+pub fn write_generated(
+    path: &Path,
     content: &str,
+    merge: MergeFn,
     config: &Config,
 ) -> Result<GeneratedFile, GenerationError> {
-    if path.exists() {
+    if !path.exists() {
+        if !config.dry_run {
+            fs::write(path, content)?;
+        }
         if config.verbose {
-            println!("Skipping existing file: {}", path.display());
+            println!("{}: {}", action_verb(config, "Created", "Would create"), path.display());
         }
-        Ok(GeneratedFile {
-            path: path.clone(),
-            content: content.to_string(),
-            was_created: false,
-        })
-    } else {
-        fs::write(path, content)?;
+        let mut file = GeneratedFile::created(path.to_path_buf());
+        file.content = content.to_string();
+        return Ok(file);
+    }
+
+    if !config.update {
         if config.verbose {
-            println!("Created: {}", path.display());
+            println!("{}: {} (already exists)", action_verb(config, "Skipped", "Would skip"), path.display());
         }
-        Ok(GeneratedFile {
-            path: path.clone(),
-            content: content.to_string(),
-            was_created: true,
-        })
+        let mut file = GeneratedFile::skipped(path.to_path_buf());
+        file.content = content.to_string();
+        return Ok(file);
+    }
+
+    let existing = fs::read_to_string(path)?;
+    let merged = merge(&existing, content)
+        .map_err(|e| GenerationError::MergeFailed(path.to_path_buf(), e))?;
+
+    if merged == existing {
+        if config.verbose {
+            println!("{}: {}", action_verb(config, "Unchanged", "Would leave unchanged"), path.display());
+        }
+        let mut file = GeneratedFile::skipped(path.to_path_buf());
+        file.content = existing;
+        return Ok(file);
+    }
+
+    let label = path
+        .strip_prefix(config.get_output_dir())
+        .unwrap_or(path)
+        .to_string_lossy();
+    let diff = unified_diff(&existing, &merged, &format!("a/{}", label), &format!("b/{}", label));
+    if !config.dry_run {
+        fs::write(path, &merged)?;
+    }
+    if config.verbose {
+        println!("{}: {}", action_verb(config, "Updated", "Would update"), path.display());
+    }
+
+    let mut file = GeneratedFile::updated(path.to_path_buf(), diff);
+    file.content = merged;
+    Ok(file)
+}
+
+/// Verb for a verbose progress line, worded for `--dry-run` when planning
+fn action_verb<'a>(config: &Config, done: &'a str, planned: &'a str) -> &'a str {
+    if config.dry_run {
+        planned
+    } else {
+        done
+    }
+}
+
+/// Escape string for JSON
+pub(crate) fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+pub(crate) fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
     }
 }
 
@@ -306,7 +613,6 @@ mod chrono {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Command;
     use std::path::PathBuf;
 
     #[test]
@@ -346,8 +652,61 @@ mod tests {
             .with_size(512),
         ];
 
-        let summary = create_dataset_summary(&files);
+        let summary = create_dataset_summary(&files, &[]);
         assert_eq!(summary.total_files, 2);
         assert_eq!(summary.total_size, 1536);
+        assert!(summary.image_sizes.is_empty());
+    }
+
+    #[test]
+    fn test_is_generated_file() {
+        let readme = FileInfo::new(PathBuf::from("README.md"), PathBuf::from("README.md"));
+        let nested = FileInfo::new(PathBuf::from("a/README.md"), PathBuf::from("a/README.md"));
+        let schema = FileInfo::new(PathBuf::from("x.schema.json"), PathBuf::from("x.schema.json"));
+        assert!(is_generated_file(&readme));
+        assert!(!is_generated_file(&nested));
+        assert!(is_generated_file(&schema));
+    }
+
+    #[test]
+    fn test_write_generated_modes() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_write_generated");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("MANIFEST.txt");
+        fs::remove_file(&path).ok();
+
+        let config = Config::new(temp_dir.clone(), crate::types::Command::Generate);
+        let created = write_generated(&path, "a\n", replace_all, &config).unwrap();
+        assert!(created.was_created);
+
+        // Existing files are skipped without --update
+        let skipped = write_generated(&path, "b\n", replace_all, &config).unwrap();
+        assert!(!skipped.was_created && !skipped.was_updated);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\n");
+
+        // With --update the merge result is written and diffed
+        let config = config.with_update(true);
+        let updated = write_generated(&path, "b\n", replace_all, &config).unwrap();
+        assert!(updated.was_updated);
+        assert!(updated.diff.unwrap().contains("-a\n+b\n"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "b\n");
+
+        let unchanged = write_generated(&path, "b\n", replace_all, &config).unwrap();
+        assert!(!unchanged.was_updated);
+
+        // A dry run reports the same plan without touching the file
+        let config = config.with_dry_run(true);
+        let planned = write_generated(&path, "c\n", replace_all, &config).unwrap();
+        assert!(planned.was_updated);
+        assert!(planned.diff.unwrap().contains("-b\n+c\n"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "b\n");
+
+        let new_path = temp_dir.join("README.md");
+        fs::remove_file(&new_path).ok();
+        let planned = write_generated(&new_path, "# Title\n", replace_all, &config).unwrap();
+        assert!(planned.was_created);
+        assert!(!new_path.exists());
+
+        fs::remove_dir_all(temp_dir).ok();
     }
 }
@@ -1,5 +1,6 @@
 //! README.md generation
 
+use super::update::marked_region;
 use crate::types::DatasetSummary;
 
 /// Generate a README.md file for a dataset
@@ -16,17 +17,23 @@ pub fn generate_readme(summary: &DatasetSummary) -> String {
     content.push_str("## Description\n\n");
     content.push_str("[TODO: Provide a brief description of this dataset, including its purpose, scope, and scientific context]\n\n");
 
-    // Contents
+    // Contents (machine-owned, refreshed by `generate --update`)
     content.push_str("## Contents\n\n");
-    content.push_str(&format!(
+    let mut contents = format!(
         "This dataset contains {} files totaling {}:\n\n",
         summary.total_files,
         summary.format_size()
-    ));
-
+    );
     for (file_type, count) in &summary.file_type_counts {
-        content.push_str(&format!("- {} {} files\n", count, file_type));
+        contents.push_str(&format!("- {} {} files\n", count, file_type));
+    }
+    if !summary.image_sizes.is_empty() {
+        contents.push_str("\nImages by size:\n\n");
+        for (size, count) in &summary.image_sizes {
+            contents.push_str(&format!("- {} images of {}\n", count, size));
+        }
     }
+    content.push_str(&marked_region("contents", &contents));
     content.push('\n');
 
     // Data Files
@@ -1,6 +1,7 @@
-//! JSON Schema generation for CSV files
+//! JSON Schema generation for CSV, JSON and HDF5 files
 
-use crate::types::{ColumnType, CsvAnalysis};
+use super::escape_json;
+use crate::types::{hdf5_filter_name, ColumnType, CsvAnalysis, Hdf5Analysis, Hdf5Datatype, JsonShape};
 
 /// Generate a JSON Schema for a CSV file
 ///
@@ -54,6 +55,180 @@ pub fn generate_schema(csv_analysis: &CsvAnalysis, filename: &str) -> String {
     schema
 }
 
+/// Schema file name for one table of a database file
+///
+/// Keeps the full file name and appends the table name, with characters
+/// that are unsafe in file names replaced by `_`
+/// (`survey.db` table `samples` → `survey.db.samples.schema.json`).
+pub fn table_schema_name(file_name: &str, table: &str) -> String {
+    let table: String = table
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    format!("{}.{}.schema.json", file_name, table)
+}
+
+/// Generate a JSON Schema for a JSON data file
+///
+/// Describes the merged structure of the document: nested objects with
+/// their members (those present in every object are `required`), array
+/// item types, and the range of numeric values.
+pub fn generate_json_schema(structure: &JsonShape, filename: &str) -> String {
+    let mut members = vec![
+        "\"$schema\": \"http://json-schema.org/draft-07/schema#\"".to_string(),
+        format!("\"title\": \"Schema for {}\"", escape_json(filename)),
+        "\"description\": \"Auto-generated schema from JSON structure\"".to_string(),
+    ];
+    members.extend(shape_members(structure, ""));
+    format!("{{\n  {}\n}}\n", members.join(",\n  "))
+}
+
+/// Schema keywords for a merged JSON shape, indented for nesting under `indent`
+fn shape_members(shape: &JsonShape, indent: &str) -> Vec<String> {
+    let inner = format!("{}  ", indent);
+    let mut members = Vec::new();
+
+    let types = shape.json_types();
+    match types.as_slice() {
+        [] => {}
+        [single] => members.push(format!("\"type\": \"{}\"", single)),
+        several => {
+            let names: Vec<String> = several.iter().map(|t| format!("\"{}\"", t)).collect();
+            members.push(format!("\"type\": [{}]", names.join(", ")));
+        }
+    }
+
+    if !shape.properties.is_empty() {
+        let properties: Vec<String> = shape
+            .properties
+            .iter()
+            .map(|(name, member)| {
+                format!("\"{}\": {}", escape_json(name), shape_schema(member, &format!("{}  ", inner)))
+            })
+            .collect();
+        members.push(format!(
+            "\"properties\": {{\n{}    {}\n{}  }}",
+            indent,
+            properties.join(&format!(",\n{}    ", indent)),
+            indent
+        ));
+
+        let required: Vec<String> = shape
+            .properties
+            .iter()
+            .filter(|(_, member)| shape.is_required(member))
+            .map(|(name, _)| format!("\"{}\"", escape_json(name)))
+            .collect();
+        if !required.is_empty() {
+            members.push(format!("\"required\": [{}]", required.join(", ")));
+        }
+    }
+
+    if shape.array_count > 0 {
+        let items = match shape.items {
+            Some(ref items) => shape_schema(items, &inner),
+            None => "{}".to_string(),
+        };
+        members.push(format!("\"items\": {}", items));
+    }
+
+    if let (Some(min), Some(max)) = (shape.min_value, shape.max_value) {
+        members.push(format!("\"minimum\": {}", min));
+        members.push(format!("\"maximum\": {}", max));
+    }
+
+    members
+}
+
+/// A nested schema object for a merged JSON shape
+fn shape_schema(shape: &JsonShape, indent: &str) -> String {
+    let members = shape_members(shape, indent);
+    if members.is_empty() {
+        return "{}".to_string();
+    }
+    format!("{{\n{}  {}\n{}}}", indent, members.join(&format!(",\n{}  ", indent)), indent)
+}
+
+/// Generate a JSON Schema describing the datasets of an HDF5 file
+///
+/// Each dataset becomes a property keyed by its path. Structure that JSON
+/// Schema cannot express (HDF5 datatype, shape, chunking, filters) is
+/// recorded in `x-hdf5-*` keywords; a `long_name` or `description`
+/// attribute becomes the description and a `units` attribute `x-units`.
+pub fn generate_hdf5_schema(analysis: &Hdf5Analysis, filename: &str) -> String {
+    let mut schema = String::new();
+
+    schema.push_str("{\n");
+    schema.push_str("  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n");
+    schema.push_str(&format!("  \"title\": \"Schema for {}\",\n", escape_json(filename)));
+    schema.push_str("  \"description\": \"Auto-generated schema from HDF5 structure\",\n");
+    schema.push_str("  \"type\": \"object\",\n");
+    schema.push_str("  \"properties\": {\n");
+
+    let dataset_count = analysis.datasets.len();
+    for (idx, dataset) in analysis.datasets.iter().enumerate() {
+        let element_type = hdf5_type_to_json_type(dataset.datatype);
+        let description = dataset
+            .attribute("long_name")
+            .or_else(|| dataset.attribute("description"))
+            .map(|a| a.value.clone())
+            .unwrap_or_else(|| format!("Dataset {} (type: {})", dataset.path, dataset.datatype));
+
+        let mut members = Vec::new();
+        if dataset.shape.is_empty() {
+            members.push(format!("\"type\": \"{}\"", element_type));
+        } else {
+            members.push("\"type\": \"array\"".to_string());
+            members.push(format!("\"items\": {{ \"type\": \"{}\" }}", element_type));
+        }
+        members.push(format!("\"description\": \"{}\"", escape_json(&description)));
+        members.push(format!("\"x-hdf5-datatype\": \"{}\"", dataset.datatype));
+        members.push(format!("\"x-hdf5-shape\": {}", number_list(&dataset.shape)));
+        if let Some(ref chunks) = dataset.chunks {
+            members.push(format!("\"x-hdf5-chunks\": {}", number_list(chunks)));
+        }
+        if !dataset.filters.is_empty() {
+            let names: Vec<String> = dataset
+                .filters
+                .iter()
+                .map(|&id| format!("\"{}\"", hdf5_filter_name(id)))
+                .collect();
+            members.push(format!("\"x-hdf5-filters\": [{}]", names.join(", ")));
+        }
+        if let Some(units) = dataset.attribute("units") {
+            members.push(format!("\"x-units\": \"{}\"", escape_json(&units.value)));
+        }
+
+        let comma = if idx < dataset_count - 1 { "," } else { "" };
+        schema.push_str(&format!("    \"{}\": {{\n", escape_json(&dataset.path)));
+        schema.push_str(&format!("      {}\n", members.join(",\n      ")));
+        schema.push_str(&format!("    }}{}\n", comma));
+    }
+
+    schema.push_str("  }\n");
+    schema.push_str("}\n");
+
+    schema
+}
+
+/// Convert an HDF5 element type to a JSON Schema type
+fn hdf5_type_to_json_type(datatype: Hdf5Datatype) -> &'static str {
+    match datatype {
+        Hdf5Datatype::Integer { .. } => "integer",
+        Hdf5Datatype::Float { .. } => "number",
+        Hdf5Datatype::String { .. } | Hdf5Datatype::VarString | Hdf5Datatype::Enum => "string",
+        Hdf5Datatype::Compound { .. } => "object",
+        Hdf5Datatype::VarSequence | Hdf5Datatype::Array => "array",
+        _ => "string",
+    }
+}
+
+/// Format numbers as a JSON array
+fn number_list(values: &[u64]) -> String {
+    let items: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
 /// Convert ColumnType to JSON Schema type
 fn column_type_to_json_type(col_type: ColumnType) -> &'static str {
     match col_type {
@@ -66,20 +241,17 @@ fn column_type_to_json_type(col_type: ColumnType) -> &'static str {
     }
 }
 
-/// Escape string for JSON
-fn escape_json(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::ColumnInfo;
 
+    #[test]
+    fn test_table_schema_name() {
+        assert_eq!(table_schema_name("survey.db", "samples"), "survey.db.samples.schema.json");
+        assert_eq!(table_schema_name("survey.db", "site visits/2024"), "survey.db.site_visits_2024.schema.json");
+    }
+
     #[test]
     fn test_generate_schema() {
         let mut analysis = CsvAnalysis::new(',', true);
@@ -116,6 +288,111 @@ mod tests {
         assert_eq!(column_type_to_json_type(ColumnType::Timestamp), "string");
     }
 
+    #[test]
+    fn test_generate_json_schema() {
+        let mut record = JsonShape {
+            object_count: 3,
+            ..Default::default()
+        };
+        let id = JsonShape {
+            integer_count: 3,
+            min_value: Some(1.0),
+            max_value: Some(7.0),
+            ..Default::default()
+        };
+        let note = JsonShape {
+            string_count: 1,
+            null_count: 1,
+            ..Default::default()
+        };
+        let tags = JsonShape {
+            array_count: 3,
+            items: Some(Box::new(JsonShape {
+                string_count: 4,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        record.properties = vec![("id".to_string(), id), ("note".to_string(), note), ("tags".to_string(), tags)];
+        let root = JsonShape {
+            array_count: 1,
+            items: Some(Box::new(record)),
+            ..Default::default()
+        };
+
+        let schema = generate_json_schema(&root, "obs.json");
+        let parsed = crate::analyzer::parse_json(&schema).expect("schema should be valid JSON");
+        assert_eq!(parsed.get("title").and_then(|v| v.as_str()), Some("Schema for obs.json"));
+        assert_eq!(parsed.get("type").and_then(|v| v.as_str()), Some("array"));
+
+        let items = parsed.get("items").unwrap();
+        let required: Vec<&str> = items
+            .get("required")
+            .and_then(|r| r.as_array())
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(required, vec!["id", "tags"]);
+
+        let props = items.get("properties").unwrap();
+        assert_eq!(props.get("id").and_then(|p| p.get("type")).and_then(|v| v.as_str()), Some("integer"));
+        assert_eq!(props.get("id").and_then(|p| p.get("maximum")).and_then(|v| v.as_f64()), Some(7.0));
+        let note_types = props.get("note").and_then(|p| p.get("type")).and_then(|v| v.as_array()).unwrap();
+        assert_eq!(note_types.len(), 2);
+        let tag_items = props.get("tags").and_then(|p| p.get("items")).unwrap();
+        assert_eq!(tag_items.get("type").and_then(|v| v.as_str()), Some("string"));
+        assert!(schema.contains("\n      \"id\": {\n        \"type\": \"integer\",\n"));
+    }
+
+    #[test]
+    fn test_generate_hdf5_schema() {
+        use crate::analyzer::parse_json;
+        use crate::types::{Hdf5Attribute, Hdf5Dataset};
+
+        let mut analysis = Hdf5Analysis::new(0);
+        analysis.datasets = vec![
+            Hdf5Dataset {
+                path: "/temperature".to_string(),
+                shape: vec![10, 20],
+                datatype: Hdf5Datatype::Float { size: 8 },
+                chunks: Some(vec![5, 10]),
+                filters: vec![2, 1],
+                attributes: vec![Hdf5Attribute {
+                    name: "units".to_string(),
+                    datatype: Hdf5Datatype::VarString,
+                    value: "K".to_string(),
+                }],
+            },
+            Hdf5Dataset {
+                path: "/count".to_string(),
+                shape: vec![],
+                datatype: Hdf5Datatype::Integer { size: 4, signed: true },
+                chunks: None,
+                filters: vec![],
+                attributes: vec![],
+            },
+        ];
+
+        let schema = generate_hdf5_schema(&analysis, "sim.h5");
+        let parsed = parse_json(&schema).unwrap();
+        let props = parsed.get("properties").unwrap();
+
+        let temperature = props.get("/temperature").unwrap();
+        assert_eq!(temperature.get("type").and_then(|v| v.as_str()), Some("array"));
+        assert_eq!(temperature.get("x-hdf5-datatype").and_then(|v| v.as_str()), Some("float64"));
+        assert_eq!(temperature.get("x-units").and_then(|v| v.as_str()), Some("K"));
+        assert!(schema.contains("\"x-hdf5-filters\": [\"shuffle\", \"deflate\"]"));
+        assert!(schema.contains("\"x-hdf5-chunks\": [5, 10]"));
+
+        let count = props.get("/count").unwrap();
+        assert_eq!(count.get("type").and_then(|v| v.as_str()), Some("integer"));
+        assert_eq!(
+            count.get("description").and_then(|v| v.as_str()),
+            Some("Dataset /count (type: int32)")
+        );
+    }
+
     #[test]
     fn test_escape_json() {
         assert_eq!(escape_json("hello"), "hello");
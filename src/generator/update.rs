@@ -0,0 +1,898 @@
+//! Merge-update of previously generated documentation
+//!
+//! `generate --update` regenerates machine-owned content while preserving
+//! everything the researcher edited:
+//!
+//! - Markdown: only regions between `<!-- genesis-preflight:begin NAME -->`
+//!   and `<!-- genesis-preflight:end NAME -->` markers are replaced.
+//! - metadata.json: the `files` value and the members of
+//!   `genesis_preflight` are spliced into the existing text; everything
+//!   else is kept exactly as written.
+//! - croissant.json: `distribution` and `recordSet` entries are matched
+//!   by `@id`; file sizes, hashes and field types are regenerated, while
+//!   names, edited descriptions and the dataset-level properties are kept.
+//! - `*.schema.json`: column/dataset `type`, `items`, `examples` and the
+//!   `x-*` structure keywords are regenerated; titles, descriptions and any
+//!   added keywords are kept.
+//! - Data dictionaries: rows are regenerated; Description and Unit cells
+//!   are carried over by file and column name.
+//!
+//! Each merge returns the existing text unchanged when nothing
+//! machine-owned differs, so untouched files are never reformatted.
+
+use super::data_dictionary::split_table_row;
+use super::{escape_csv, escape_json};
+use crate::analyzer::{parse_json, parse_line_rfc4180, JsonValue};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Prefix of a region start marker
+const REGION_BEGIN: &str = "<!-- genesis-preflight:begin ";
+
+/// Prefix of a region end marker
+const REGION_END: &str = "<!-- genesis-preflight:end ";
+
+/// Keys in a schema column that are owned by the generator
+const SCHEMA_MACHINE_KEYS: [&str; 9] = [
+    "type",
+    "items",
+    "properties",
+    "examples",
+    "x-hdf5-datatype",
+    "x-hdf5-shape",
+    "x-hdf5-chunks",
+    "x-hdf5-filters",
+    "x-units",
+];
+
+/// Keys in a croissant.json distribution entry that are owned by the generator
+const CROISSANT_FILE_KEYS: [&str; 6] = ["@type", "contentUrl", "contentSize", "sha256", "encodingFormat", "includes"];
+
+/// Keys in a croissant.json record set field that are owned by the generator
+const CROISSANT_FIELD_KEYS: [&str; 3] = ["@type", "dataType", "source"];
+
+/// Keys the generator writes only for JSON data schemas; in other schemas
+/// they are the user's, so they are updated but never removed
+const SCHEMA_REFRESHED_KEYS: [&str; 3] = ["required", "minimum", "maximum"];
+
+/// Wrap generated Markdown in a named machine-owned region
+pub fn marked_region(name: &str, body: &str) -> String {
+    format!("{}{} -->\n{}{}{} -->\n", REGION_BEGIN, name, body, REGION_END, name)
+}
+
+/// Replace marked regions in `existing` with the same regions from `fresh`
+///
+/// Text outside markers is kept verbatim. Regions the user deleted are not
+/// re-inserted; files without markers are returned unchanged.
+pub fn merge_markdown(existing: &str, fresh: &str) -> Result<String, String> {
+    let fresh_regions = extract_regions(fresh);
+
+    let mut merged = String::with_capacity(existing.len());
+    let mut lines = existing.split_inclusive('\n');
+
+    while let Some(line) = lines.next() {
+        merged.push_str(line);
+
+        let name = match region_name(line, REGION_BEGIN) {
+            Some(n) => n,
+            None => continue,
+        };
+
+        // Collect the old body up to the matching end marker
+        let mut old_body = String::new();
+        let mut end_line = None;
+        for inner in lines.by_ref() {
+            if region_name(inner, REGION_END) == Some(name) {
+                end_line = Some(inner);
+                break;
+            }
+            old_body.push_str(inner);
+        }
+
+        let end_line = match end_line {
+            Some(l) => l,
+            None => return Err(format!("Region '{}' has no end marker", name)),
+        };
+
+        match fresh_regions.get(name) {
+            Some(body) => merged.push_str(body),
+            None => merged.push_str(&old_body),
+        }
+        merged.push_str(end_line);
+    }
+
+    Ok(merged)
+}
+
+/// Merge a regenerated metadata.json into an existing one
+///
+/// Machine-owned values are spliced into the existing text rather than
+/// re-serialized, so the user's values keep their original spelling
+/// (integers beyond 2^53, `1.10`, `1e-7`) and layout.
+pub fn merge_metadata_json(existing: &str, fresh: &str) -> Result<String, String> {
+    let old = parse_json(existing).map_err(|e| format!("existing file is not valid JSON: {}", e))?;
+    let new = parse_json(fresh)?;
+
+    if !matches!(old, JsonValue::Object(_)) {
+        return Err("existing file is not a JSON object".to_string());
+    }
+
+    // Only the file statistics decide whether anything changed; the
+    // generation timestamp is refreshed along with them.
+    if old.get("files") == new.get("files") {
+        return Ok(existing.to_string());
+    }
+
+    let fresh_members = object_members(fresh, skip_whitespace(fresh.as_bytes(), 0))?;
+    let mut merged = existing.to_string();
+    for (key, range) in &fresh_members {
+        match key.as_str() {
+            "files" => merged = splice_member(&merged, &[], key, &fresh[range.clone()])?,
+            // Members of the tool's own object are refreshed one by one, so
+            // any the user added to it are kept
+            "genesis_preflight" if matches!(old.get(key), Some(JsonValue::Object(_))) => {
+                for (member, value) in object_members(fresh, range.start)? {
+                    merged = splice_member(&merged, &[key], &member, &fresh[value])?;
+                }
+            }
+            "genesis_preflight" => merged = splice_member(&merged, &[], key, &fresh[range.clone()])?,
+            _ => {}
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Merge a regenerated croissant.json into an existing one
+///
+/// File objects and record sets follow the data: entries for files that
+/// are gone are dropped and new ones are added. Like metadata.json, the
+/// merged parts are spliced into the existing text, so the user's name,
+/// description, license and url keep their original form; `dateModified`
+/// is refreshed along with them.
+pub fn merge_croissant(existing: &str, fresh: &str) -> Result<String, String> {
+    let old = parse_json(existing).map_err(|e| format!("existing file is not valid JSON: {}", e))?;
+    let new = parse_json(fresh)?;
+
+    if !matches!(old, JsonValue::Object(_)) {
+        return Err("existing file is not a JSON object".to_string());
+    }
+
+    let distribution = merge_nodes(old.get("distribution"), new.get("distribution"), &|members, fresh| {
+        refresh_members(members, fresh, &CROISSANT_FILE_KEYS);
+    });
+    let record_sets = merge_nodes(old.get("recordSet"), new.get("recordSet"), &|members, fresh| {
+        refresh_members(members, fresh, &["@type"]);
+        refresh_description(members, fresh);
+        let old_fields = members.iter().find(|(k, _)| k == "field").map(|(_, v)| v.clone());
+        let fields = merge_nodes(old_fields.as_ref(), fresh.get("field"), &|members, fresh| {
+            refresh_members(members, fresh, &CROISSANT_FIELD_KEYS);
+            refresh_description(members, fresh);
+        });
+        set_member(members, "field", fields);
+    });
+
+    if old.get("distribution") == Some(&distribution) && old.get("recordSet") == Some(&record_sets) {
+        return Ok(existing.to_string());
+    }
+
+    let mut merged = splice_member(existing, &[], "distribution", &member_json(&distribution))?;
+    merged = splice_member(&merged, &[], "recordSet", &member_json(&record_sets))?;
+    if let Some(date) = new.get("dateModified") {
+        merged = splice_member(&merged, &[], "dateModified", &member_json(date))?;
+    }
+    Ok(merged)
+}
+
+/// Members of a JSON object, in order
+type Members = Vec<(String, JsonValue)>;
+
+/// Merge two arrays of JSON-LD nodes, matching nodes by `@id`
+///
+/// The result holds the fresh nodes in their fresh order; a node that
+/// already existed keeps its old members and is brought up to date by
+/// `refresh`.
+fn merge_nodes(
+    old: Option<&JsonValue>,
+    fresh: Option<&JsonValue>,
+    refresh: &dyn Fn(&mut Members, &JsonValue),
+) -> JsonValue {
+    let old_nodes = old.and_then(|o| o.as_array()).unwrap_or(&[]);
+    let nodes = fresh
+        .and_then(|f| f.as_array())
+        .unwrap_or(&[])
+        .iter()
+        .map(|node| {
+            let id = node.get("@id");
+            match old_nodes.iter().find(|o| id.is_some() && o.get("@id") == id) {
+                Some(JsonValue::Object(members)) => {
+                    let mut members = members.clone();
+                    refresh(&mut members, node);
+                    JsonValue::Object(members)
+                }
+                _ => node.clone(),
+            }
+        })
+        .collect();
+    JsonValue::Array(nodes)
+}
+
+/// Refresh a description the generator wrote; an edited one is kept
+///
+/// Generated descriptions are `[TODO: ...]` placeholders, or the
+/// `Records from ...` summary of a record set.
+fn refresh_description(members: &mut Vec<(String, JsonValue)>, fresh: &JsonValue) {
+    let generated = match members.iter().find(|(k, _)| k == "description") {
+        Some((_, JsonValue::String(s))) => s.starts_with("[TODO") || s.starts_with("Records from "),
+        Some(_) => false,
+        None => true,
+    };
+    if let (true, Some(description)) = (generated, fresh.get("description")) {
+        set_member(members, "description", description.clone());
+    }
+}
+
+/// Merge a regenerated CSV, JSON or HDF5 schema into an existing one
+///
+/// CSV schemas describe rows, so their columns sit under
+/// `items.properties`; HDF5 schemas list datasets under `properties`.
+/// JSON data schemas take either form after their root; one without named
+/// members (an array of numbers) only has its root keywords refreshed.
+pub fn merge_schema_json(existing: &str, fresh: &str) -> Result<String, String> {
+    let old = parse_json(existing).map_err(|e| format!("existing file is not valid JSON: {}", e))?;
+    let new = parse_json(fresh)?;
+
+    let nested = new.get("items").is_some();
+    let properties_of = |schema: &JsonValue| -> Option<JsonValue> {
+        if nested {
+            schema.get("items").and_then(|i| i.get("properties")).cloned()
+        } else {
+            schema.get("properties").cloned()
+        }
+    };
+
+    let mut root = match old {
+        JsonValue::Object(ref m) => m.clone(),
+        _ => return Err("existing file is not a JSON object".to_string()),
+    };
+
+    let old_props = properties_of(&old);
+    let new_props = match properties_of(&new) {
+        Some(JsonValue::Object(p)) => p,
+        _ => {
+            refresh_machine_keys(&mut root, &new);
+            return Ok(unless_unchanged(existing, &old, JsonValue::Object(root)));
+        }
+    };
+
+    // Rebuild properties in data column (or dataset) order
+    let mut properties = Vec::new();
+    for (column, fresh_prop) in &new_props {
+        let merged = match old_props.as_ref().and_then(|p| p.get(column)) {
+            Some(JsonValue::Object(old_members)) => {
+                let mut members = old_members.clone();
+                refresh_machine_keys(&mut members, fresh_prop);
+                JsonValue::Object(members)
+            }
+            _ => fresh_prop.clone(),
+        };
+        properties.push((column.clone(), merged));
+    }
+
+    // As with SCHEMA_REFRESHED_KEYS, a hand-written `required` list in a
+    // CSV or HDF5 schema is left alone
+    let new_container = if nested { new.get("items") } else { Some(&new) };
+    let required = new_container.and_then(|c| c.get("required")).cloned();
+    if nested {
+        let mut items = match old.get("items") {
+            Some(JsonValue::Object(m)) => m.clone(),
+            _ => match new.get("items") {
+                Some(JsonValue::Object(m)) => m.clone(),
+                _ => Vec::new(),
+            },
+        };
+        set_member(&mut items, "properties", JsonValue::Object(properties));
+        if let Some(required) = required {
+            set_member(&mut items, "required", required);
+        }
+        set_member(&mut root, "items", JsonValue::Object(items));
+    } else {
+        set_member(&mut root, "properties", JsonValue::Object(properties));
+        if let Some(required) = required {
+            set_member(&mut root, "required", required);
+        }
+    }
+
+    Ok(unless_unchanged(existing, &old, JsonValue::Object(root)))
+}
+
+/// Replace the generator-owned keywords of a schema object with fresh ones
+fn refresh_machine_keys(members: &mut Vec<(String, JsonValue)>, fresh: &JsonValue) {
+    refresh_members(members, fresh, &SCHEMA_MACHINE_KEYS);
+    for key in SCHEMA_REFRESHED_KEYS {
+        if let Some(value) = fresh.get(key) {
+            set_member(members, key, value.clone());
+        }
+    }
+}
+
+/// Copy `keys` from `fresh`, removing those it no longer has
+fn refresh_members(members: &mut Vec<(String, JsonValue)>, fresh: &JsonValue, keys: &[&str]) {
+    for key in keys {
+        match fresh.get(key) {
+            Some(value) => set_member(members, key, value.clone()),
+            None => members.retain(|(k, _)| k != key),
+        }
+    }
+}
+
+/// The existing text if merging changed nothing, else the merged value
+fn unless_unchanged(existing: &str, old: &JsonValue, merged: JsonValue) -> String {
+    if &merged == old {
+        existing.to_string()
+    } else {
+        to_json_pretty(&merged)
+    }
+}
+
+/// Merge a regenerated DATA_DICTIONARY.md, keeping Description and Unit cells
+pub fn merge_data_dictionary(existing: &str, fresh: &str) -> Result<String, String> {
+    // (file, column) -> (description, unit) from the existing dictionary
+    let mut documented: HashMap<(String, String), (String, String)> = HashMap::new();
+    let mut section = String::new();
+    for line in existing.lines() {
+        if let Some(heading) = line.trim().strip_prefix("## ") {
+            section = heading.trim().trim_matches('`').to_string();
+        } else if line.trim_start().starts_with('|') {
+            let cells = split_table_row(line);
+            if cells.len() >= 8 {
+                documented.insert(
+                    (section.clone(), cells[0].clone()),
+                    (cells[6].clone(), cells[7].clone()),
+                );
+            }
+        }
+    }
+
+    let mut merged = String::with_capacity(fresh.len());
+    section.clear();
+    for line in fresh.split_inclusive('\n') {
+        if let Some(heading) = line.trim().strip_prefix("## ") {
+            section = heading.trim().to_string();
+        } else if line.starts_with('|') {
+            let cells = split_table_row(line);
+            if let Some((description, unit)) = cells
+                .first()
+                .and_then(|c| documented.get(&(section.clone(), c.clone())))
+            {
+                // Rows end with "|  |  |\n": refill the two blank cells
+                if let Some(prefix) = line.trim_end().strip_suffix("|  |  |") {
+                    merged.push_str(&format!(
+                        "{}| {} | {} |\n",
+                        prefix,
+                        description.replace('|', "\\|"),
+                        unit.replace('|', "\\|")
+                    ));
+                    continue;
+                }
+            }
+        }
+        merged.push_str(line);
+    }
+
+    Ok(merged)
+}
+
+/// Merge a regenerated DATA_DICTIONARY.csv, keeping description and unit fields
+pub fn merge_data_dictionary_csv(existing: &str, fresh: &str) -> Result<String, String> {
+    let mut old_lines = existing.lines();
+    let header = parse_line_rfc4180(old_lines.next().unwrap_or(""), ',');
+    let find = |label: &str| header.iter().position(|h| h.eq_ignore_ascii_case(label));
+
+    let mut documented: HashMap<(String, String), (String, String)> = HashMap::new();
+    if let (Some(f), Some(c), Some(d), Some(u)) =
+        (find("file"), find("column"), find("description"), find("unit"))
+    {
+        for line in old_lines {
+            let fields = parse_line_rfc4180(line, ',');
+            if let (Some(file), Some(column)) = (fields.get(f), fields.get(c)) {
+                documented.insert(
+                    (file.clone(), column.clone()),
+                    (
+                        fields.get(d).cloned().unwrap_or_default(),
+                        fields.get(u).cloned().unwrap_or_default(),
+                    ),
+                );
+            }
+        }
+    }
+
+    let mut merged = String::with_capacity(fresh.len());
+    for (idx, line) in fresh.lines().enumerate() {
+        let mut fields = parse_line_rfc4180(line, ',');
+        if idx > 0 && fields.len() == 10 {
+            if let Some((description, unit)) =
+                documented.get(&(fields[0].clone(), fields[1].clone()))
+            {
+                fields[8] = description.clone();
+                fields[9] = unit.clone();
+                let row: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+                merged.push_str(&row.join(","));
+                merged.push('\n');
+                continue;
+            }
+        }
+        merged.push_str(line);
+        merged.push('\n');
+    }
+
+    Ok(merged)
+}
+
+/// Never change an existing file (for files without machine-owned parts)
+pub fn keep_existing(existing: &str, _fresh: &str) -> Result<String, String> {
+    Ok(existing.to_string())
+}
+
+/// Replace the generated file wholesale (fully machine-owned files)
+pub fn replace_all(_existing: &str, fresh: &str) -> Result<String, String> {
+    Ok(fresh.to_string())
+}
+
+/// Collect region name -> body from generated Markdown
+fn extract_regions(text: &str) -> HashMap<String, String> {
+    let mut regions = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in text.split_inclusive('\n') {
+        if let Some((ref name, ref mut body)) = current {
+            if region_name(line, REGION_END) == Some(name.as_str()) {
+                let (name, body) = current.take().unwrap_or_default();
+                regions.insert(name, body);
+            } else {
+                body.push_str(line);
+            }
+        } else if let Some(name) = region_name(line, REGION_BEGIN) {
+            current = Some((name.to_string(), String::new()));
+        }
+    }
+
+    regions
+}
+
+/// Return the region name if `line` is a marker with the given prefix
+fn region_name<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix(prefix)?
+        .strip_suffix("-->")
+        .map(|name| name.trim())
+}
+
+/// Set (or append) an object member, keeping its position if it exists
+fn set_member(members: &mut Vec<(String, JsonValue)>, key: &str, value: JsonValue) {
+    match members.iter_mut().find(|(k, _)| k == key) {
+        Some(member) => member.1 = value,
+        None => members.push((key.to_string(), value)),
+    }
+}
+
+/// Set member `key` of the object reached through `path` to the JSON text `value`
+///
+/// An existing member has only its value replaced; a new member is added
+/// after the last one. The rest of `text` is left untouched.
+fn splice_member(text: &str, path: &[&str], key: &str, value: &str) -> Result<String, String> {
+    let mut open = skip_whitespace(text.as_bytes(), 0);
+    for step in path {
+        open = object_members(text, open)?
+            .into_iter()
+            .find(|(name, _)| name == step)
+            .map(|(_, range)| range.start)
+            .ok_or_else(|| format!("'{}' is missing", step))?;
+    }
+
+    let members = object_members(text, open)?;
+    if let Some((_, range)) = members.iter().find(|(name, _)| name == key) {
+        return Ok(format!("{}{}{}", &text[..range.start], value, &text[range.end..]));
+    }
+
+    let indent = "  ".repeat(path.len() + 1);
+    match members.last() {
+        Some((_, last)) => Ok(format!(
+            "{},\n{}\"{}\": {}{}",
+            &text[..last.end],
+            indent,
+            escape_json(key),
+            value,
+            &text[last.end..]
+        )),
+        None => {
+            let end = value_end(text.as_bytes(), open)?;
+            Ok(format!(
+                "{}{{\n{}\"{}\": {}\n{}}}{}",
+                &text[..open],
+                indent,
+                escape_json(key),
+                value,
+                "  ".repeat(path.len()),
+                &text[end..]
+            ))
+        }
+    }
+}
+
+/// Members of the JSON object starting at `open`, with the byte range of
+/// each value in `text`
+///
+/// `text` must already have parsed as JSON. Keys are returned as written,
+/// without decoding escapes.
+fn object_members(text: &str, open: usize) -> Result<Vec<(String, Range<usize>)>, String> {
+    let bytes = text.as_bytes();
+    if bytes.get(open) != Some(&b'{') {
+        return Err("expected a JSON object".to_string());
+    }
+
+    let mut members = Vec::new();
+    let mut pos = skip_whitespace(bytes, open + 1);
+    if bytes.get(pos) == Some(&b'}') {
+        return Ok(members);
+    }
+    loop {
+        let key_end = value_end(bytes, pos)?;
+        let key = text.get(pos + 1..key_end - 1).ok_or("malformed JSON key")?;
+        pos = skip_whitespace(bytes, key_end);
+        if bytes.get(pos) != Some(&b':') {
+            return Err(format!("expected ':' after key '{}'", key));
+        }
+        pos = skip_whitespace(bytes, pos + 1);
+        let end = value_end(bytes, pos)?;
+        members.push((key.to_string(), pos..end));
+
+        pos = skip_whitespace(bytes, end);
+        match bytes.get(pos) {
+            Some(b',') => pos = skip_whitespace(bytes, pos + 1),
+            Some(b'}') => return Ok(members),
+            _ => return Err(format!("expected ',' or '}}' after '{}'", key)),
+        }
+    }
+}
+
+/// Byte offset just past the JSON value starting at `pos`
+fn value_end(bytes: &[u8], pos: usize) -> Result<usize, String> {
+    let rest = bytes.get(pos..).ok_or("unexpected end of JSON")?;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (offset, &byte) in rest.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+                if depth == 0 {
+                    return Ok(pos + offset + 1);
+                }
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return Ok(pos + offset),
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(pos + offset + 1);
+                }
+            }
+            b',' | b' ' | b'\t' | b'\n' | b'\r' if depth == 0 => return Ok(pos + offset),
+            _ => {}
+        }
+    }
+
+    if depth == 0 && !in_string {
+        Ok(bytes.len())
+    } else {
+        Err("unexpected end of JSON".to_string())
+    }
+}
+
+/// Offset of the first non-whitespace byte at or after `pos`
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while matches!(bytes.get(pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Serialize a JSON value with two-space indentation
+fn to_json_pretty(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write_json_value(&mut out, value, 0);
+    out.push('\n');
+    out
+}
+
+/// Serialize a JSON value for use as a member of the root object
+fn member_json(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write_json_value(&mut out, value, 1);
+    out
+}
+
+/// Append a JSON value at the given indentation depth
+fn write_json_value(out: &mut String, value: &JsonValue, depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+    let closing = "  ".repeat(depth);
+
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Boolean(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Number(n) => {
+            if n.fract() == 0.0 && n.abs() < 1e15 {
+                out.push_str(&format!("{}", *n as i64));
+            } else {
+                out.push_str(&format!("{}", n));
+            }
+        }
+        JsonValue::String(s) => out.push_str(&format!("\"{}\"", escape_json(s))),
+        JsonValue::Array(items) if items.is_empty() => out.push_str("[]"),
+        JsonValue::Array(items) => {
+            out.push_str("[\n");
+            for (idx, item) in items.iter().enumerate() {
+                out.push_str(&indent);
+                write_json_value(out, item, depth + 1);
+                out.push_str(if idx < items.len() - 1 { ",\n" } else { "\n" });
+            }
+            out.push_str(&closing);
+            out.push(']');
+        }
+        JsonValue::Object(members) if members.is_empty() => out.push_str("{}"),
+        JsonValue::Object(members) => {
+            out.push_str("{\n");
+            for (idx, (key, member)) in members.iter().enumerate() {
+                out.push_str(&format!("{}\"{}\": ", indent, escape_json(key)));
+                write_json_value(out, member, depth + 1);
+                out.push_str(if idx < members.len() - 1 { ",\n" } else { "\n" });
+            }
+            out.push_str(&closing);
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_markdown_replaces_only_regions() {
+        let existing = format!(
+            "# My Dataset\n\nHand-written intro.\n\n{}\nMore prose.\n",
+            marked_region("contents", "3 files\n")
+        );
+        let fresh = format!(
+            "# [TODO: Title]\n\n{}\n",
+            marked_region("contents", "5 files\n- 5 csv files\n")
+        );
+
+        let merged = merge_markdown(&existing, &fresh).unwrap();
+        assert!(merged.starts_with("# My Dataset\n\nHand-written intro.\n"));
+        assert!(merged.contains("5 files\n- 5 csv files\n"));
+        assert!(!merged.contains("3 files"));
+        assert!(merged.ends_with("More prose.\n"));
+    }
+
+    #[test]
+    fn test_merge_markdown_without_markers_is_unchanged() {
+        let existing = "# Hand-written README\n";
+        let fresh = marked_region("contents", "5 files\n");
+        assert_eq!(merge_markdown(existing, &fresh).unwrap(), existing);
+    }
+
+    #[test]
+    fn test_merge_markdown_unterminated_region() {
+        let existing = "<!-- genesis-preflight:begin contents -->\n3 files\n";
+        assert!(merge_markdown(existing, "").is_err());
+    }
+
+    #[test]
+    fn test_merge_metadata_json_keeps_user_keys() {
+        let existing = r#"{"title": "Ocean Temps", "files": [{"count": 3}], "genesis_preflight": {"generated": "old"}}"#;
+        let fresh = r#"{"title": "[TODO]", "files": [{"count": 5}], "genesis_preflight": {"generated": "new"}}"#;
+
+        let merged = merge_metadata_json(existing, fresh).unwrap();
+        let value = parse_json(&merged).unwrap();
+        assert_eq!(value.get("title").and_then(|v| v.as_str()), Some("Ocean Temps"));
+        assert_eq!(
+            value.get("genesis_preflight").and_then(|g| g.get("generated")).and_then(|v| v.as_str()),
+            Some("new")
+        );
+        assert!(merged.contains("\"count\": 5"));
+    }
+
+    #[test]
+    fn test_merge_metadata_json_keeps_values_as_written() {
+        let existing = r#"{"grant_id": 12345678901234567890, "ratio": 1.10, "tol": 1e-7, "tags": ["a", "b"],
+  "files": [{"count": 3}], "genesis_preflight": {"generated": "old", "reviewed_by": "kim"}}"#;
+        let fresh = "{\n  \"title\": \"[TODO]\",\n  \"files\": [\n    {\n      \"count\": 5\n    }\n  ],\n  \
+                     \"genesis_preflight\": {\n    \"generated\": \"new\",\n    \"note\": \"Review\"\n  }\n}\n";
+
+        let merged = merge_metadata_json(existing, fresh).unwrap();
+        assert_eq!(
+            merged,
+            "{\"grant_id\": 12345678901234567890, \"ratio\": 1.10, \"tol\": 1e-7, \"tags\": [\"a\", \"b\"],\n  \
+             \"files\": [\n    {\n      \"count\": 5\n    }\n  ], \
+             \"genesis_preflight\": {\"generated\": \"new\", \"reviewed_by\": \"kim\",\n    \"note\": \"Review\"}}"
+        );
+        assert!(parse_json(&merged).is_ok());
+
+        // Missing machine-owned members are added after the last member
+        let merged = merge_metadata_json("{\n  \"title\": \"T\"\n}\n", fresh).unwrap();
+        assert!(merged.starts_with("{\n  \"title\": \"T\",\n  \"files\": [\n"));
+        assert!(merged.ends_with("\"note\": \"Review\"\n  }\n}\n"));
+    }
+
+    #[test]
+    fn test_merge_metadata_json_unchanged_files() {
+        let existing = "{\"files\": [{\"count\": 3}], \"genesis_preflight\": {\"generated\": \"old\"}}";
+        let fresh = "{\"files\": [{\"count\": 3}], \"genesis_preflight\": {\"generated\": \"new\"}}";
+        assert_eq!(merge_metadata_json(existing, fresh).unwrap(), existing);
+    }
+
+    #[test]
+    fn test_merge_croissant() {
+        let existing = r#"{"@type": "sc:Dataset", "name": "Station Temps", "grant": 12345678901234567890,
+  "dateModified": "old",
+  "distribution": [
+    {"@type": "cr:FileObject", "@id": "a.csv", "name": "a.csv", "description": "Raw readings",
+     "contentUrl": "a.csv", "contentSize": "8 B", "sha256": "aaa", "encodingFormat": "text/csv"},
+    {"@type": "cr:FileObject", "@id": "gone.csv", "contentUrl": "gone.csv"}
+  ],
+  "recordSet": [
+    {"@type": "cr:RecordSet", "@id": "a", "description": "Records from a.csv (1 rows)", "field": [
+      {"@type": "cr:Field", "@id": "a/t", "description": "Air temperature", "dataType": "sc:Integer"}
+    ]}
+  ]
+}"#;
+        let fresh = r#"{"@type": "sc:Dataset", "name": "[TODO: Dataset Name]", "dateModified": "new",
+  "distribution": [
+    {"@type": "cr:FileObject", "@id": "a.csv", "name": "a.csv",
+     "contentUrl": "a.csv", "contentSize": "12 B", "sha256": "bbb", "encodingFormat": "text/csv"}
+  ],
+  "recordSet": [
+    {"@type": "cr:RecordSet", "@id": "a", "description": "Records from a.csv (2 rows)", "field": [
+      {"@type": "cr:Field", "@id": "a/t", "description": "[TODO: Describe t]", "dataType": "sc:Float"}
+    ]}
+  ]
+}"#;
+
+        let merged = merge_croissant(existing, fresh).unwrap();
+        assert!(merged.starts_with(r#"{"@type": "sc:Dataset", "name": "Station Temps", "grant": 12345678901234567890,"#));
+        let value = parse_json(&merged).unwrap();
+        assert_eq!(value.get("dateModified").and_then(|v| v.as_str()), Some("new"));
+
+        let distribution = value.get("distribution").and_then(|d| d.as_array()).unwrap();
+        assert_eq!(distribution.len(), 1);
+        assert_eq!(distribution[0].get("sha256").and_then(|v| v.as_str()), Some("bbb"));
+        assert_eq!(distribution[0].get("contentSize").and_then(|v| v.as_str()), Some("12 B"));
+        assert_eq!(distribution[0].get("description").and_then(|v| v.as_str()), Some("Raw readings"));
+
+        let record_set = &value.get("recordSet").and_then(|r| r.as_array()).unwrap()[0];
+        assert_eq!(record_set.get("description").and_then(|v| v.as_str()), Some("Records from a.csv (2 rows)"));
+        let field = &record_set.get("field").and_then(|f| f.as_array()).unwrap()[0];
+        assert_eq!(field.get("description").and_then(|v| v.as_str()), Some("Air temperature"));
+        assert_eq!(field.get("dataType").and_then(|v| v.as_str()), Some("sc:Float"));
+
+        // Nothing left to refresh: the file is returned as it is
+        assert_eq!(merge_croissant(&merged, fresh).unwrap(), merged);
+    }
+
+    #[test]
+    fn test_merge_schema_json() {
+        let existing = r#"{"title": "Readings", "items": {"type": "object", "properties": {
+            "depth": {"type": "integer", "description": "Depth in metres", "minimum": 0},
+            "removed": {"type": "string"}
+        }}}"#;
+        let fresh = r#"{"title": "Schema for x.csv", "items": {"type": "object", "properties": {
+            "depth": {"type": "number", "description": "Column 0", "examples": ["1.5"]},
+            "site": {"type": "string", "description": "Column 1"}
+        }}}"#;
+
+        let merged = parse_json(&merge_schema_json(existing, fresh).unwrap()).unwrap();
+        assert_eq!(merged.get("title").and_then(|v| v.as_str()), Some("Readings"));
+
+        let props = merged.get("items").and_then(|i| i.get("properties")).unwrap();
+        let depth = props.get("depth").unwrap();
+        assert_eq!(depth.get("type").and_then(|v| v.as_str()), Some("number"));
+        assert_eq!(depth.get("description").and_then(|v| v.as_str()), Some("Depth in metres"));
+        assert_eq!(depth.get("minimum").and_then(|v| v.as_f64()), Some(0.0));
+        assert!(depth.get("examples").is_some());
+        assert!(props.get("site").is_some());
+        assert!(props.get("removed").is_none());
+    }
+
+    #[test]
+    fn test_merge_hdf5_schema_json() {
+        let existing = r#"{"title": "Simulation", "type": "object", "properties": {
+            "/t": {"type": "array", "description": "Air temperature", "x-hdf5-shape": [10]}
+        }}"#;
+        let fresh = r#"{"title": "Schema for sim.h5", "type": "object", "properties": {
+            "/t": {"type": "array", "description": "Dataset /t", "x-hdf5-shape": [12]}
+        }}"#;
+
+        let merged = parse_json(&merge_schema_json(existing, fresh).unwrap()).unwrap();
+        let t = merged.get("properties").and_then(|p| p.get("/t")).unwrap();
+        assert_eq!(t.get("description").and_then(|v| v.as_str()), Some("Air temperature"));
+        let shape = t.get("x-hdf5-shape").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(shape[0].as_f64(), Some(12.0));
+        assert!(merged.get("items").is_none());
+        assert!(merge_schema_json(existing, existing).unwrap() == existing);
+    }
+
+    #[test]
+    fn test_merge_json_data_schema() {
+        let existing = r#"{"title": "Stations", "type": "object", "properties": {
+            "site": {"type": "string", "description": "Station code"},
+            "depth": {"type": "integer", "minimum": 0, "maximum": 10}
+        }, "required": ["site", "depth"]}"#;
+        let fresh = r#"{"title": "Schema for stations.json", "type": "object", "properties": {
+            "site": {"type": ["string", "null"]},
+            "depth": {"type": "number", "minimum": -1.5, "maximum": 12}
+        }, "required": ["depth"]}"#;
+
+        let merged = parse_json(&merge_schema_json(existing, fresh).unwrap()).unwrap();
+        let site = merged.get("properties").and_then(|p| p.get("site")).unwrap();
+        assert_eq!(site.get("description").and_then(|v| v.as_str()), Some("Station code"));
+        assert_eq!(site.get("type").and_then(|v| v.as_array()).map(|t| t.len()), Some(2));
+        let depth = merged.get("properties").and_then(|p| p.get("depth")).unwrap();
+        assert_eq!(depth.get("minimum").and_then(|v| v.as_f64()), Some(-1.5));
+        assert_eq!(merged.get("required").and_then(|v| v.as_array()).map(|r| r.len()), Some(1));
+
+        // An array of numbers has no properties; its root keywords are refreshed
+        let existing = r#"{"title": "Levels", "type": "array", "items": {"type": "integer"}}"#;
+        let fresh = r#"{"title": "Schema for levels.json", "type": "array", "items": {"type": "number"}}"#;
+        let merged = parse_json(&merge_schema_json(existing, fresh).unwrap()).unwrap();
+        assert_eq!(merged.get("title").and_then(|v| v.as_str()), Some("Levels"));
+        assert_eq!(merged.get("items").and_then(|i| i.get("type")).and_then(|v| v.as_str()), Some("number"));
+    }
+
+    #[test]
+    fn test_merge_data_dictionary_keeps_descriptions() {
+        let existing = "## a.csv\n\n\
+            | Column | Type | Nulls | Unique | Range | Sample Values | Description | Unit |\n\
+            |--------|------|-------|--------|-------|---------------|-------------|------|\n\
+            | depth | integer | 0 | 3 | 1 to 3 | 1, 2 | Depth below surface | m |\n";
+        let fresh = "## a.csv\n\n\
+            | Column | Type | Nulls | Unique | Range | Sample Values | Description | Unit |\n\
+            |--------|------|-------|--------|-------|---------------|-------------|------|\n\
+            | depth | integer | 0 | 4 | 1 to 9 | 1, 2 |  |  |\n\
+            | site | string | 0 | 2 |  | a |  |  |\n";
+
+        let merged = merge_data_dictionary(existing, fresh).unwrap();
+        assert!(merged.contains("| depth | integer | 0 | 4 | 1 to 9 | 1, 2 | Depth below surface | m |\n"));
+        assert!(merged.contains("| site | string | 0 | 2 |  | a |  |  |\n"));
+    }
+
+    #[test]
+    fn test_merge_data_dictionary_csv() {
+        let existing = "file,column,type,null_count,unique_count,min,max,sample_values,description,unit\n\
+                        a.csv,depth,integer,0,3,1,3,1; 2,\"Depth, below surface\",m\n";
+        let fresh = "file,column,type,null_count,unique_count,min,max,sample_values,description,unit\n\
+                     a.csv,depth,integer,0,4,1,9,1; 2,,\n";
+
+        let merged = merge_data_dictionary_csv(existing, fresh).unwrap();
+        assert!(merged.contains("a.csv,depth,integer,0,4,1,9,1; 2,\"Depth, below surface\",m\n"));
+    }
+
+    #[test]
+    fn test_to_json_pretty() {
+        let value = parse_json(r#"{"a": [1, 2.5], "b": {}, "c": "x\"y"}"#).unwrap();
+        assert_eq!(
+            to_json_pretty(&value),
+            "{\n  \"a\": [\n    1,\n    2.5\n  ],\n  \"b\": {},\n  \"c\": \"x\\\"y\"\n}\n"
+        );
+    }
+}
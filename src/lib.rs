@@ -4,9 +4,15 @@
 #![forbid(unsafe_code)]
 
 pub mod analyzer;
+pub mod archive;
+pub mod bagit;
+pub mod compression;
 pub mod crypto;
 pub mod generator;
 pub mod reporter;
 pub mod scanner;
 pub mod types;
 pub mod validator;
+
+#[cfg(test)]
+mod testing;
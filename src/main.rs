@@ -5,10 +5,16 @@
 
 #![forbid(unsafe_code)]
 
-use genesis_preflight::analyzer::analyze_file;
+use genesis_preflight::analyzer::{analyze_file, image_distribution};
+use genesis_preflight::bagit::{create_bag, validate_bag};
+use genesis_preflight::compression::compressed_files;
 use genesis_preflight::generator::{
-    generate_datacard, generate_manifest, generate_metadata, generate_readme,
-    generate_schema, GeneratedFile,
+    generate_croissant, generate_data_dictionary, generate_data_dictionary_csv,
+    generate_datacard, generate_hdf5_schema, generate_json_schema, generate_manifest, generate_metadata, generate_readme,
+    generate_schema, table_schema_name,
+    is_data_dictionary, is_generated_file, json_data_structure, merge_croissant, merge_data_dictionary, merge_data_dictionary_csv,
+    merge_markdown, merge_metadata_json, merge_schema_json, refresh_hashes, replace_all,
+    write_generated, GeneratedFile, DATA_DICTIONARY_CSV, DATA_DICTIONARY_MD,
 };
 use genesis_preflight::reporter::{
     generate_json_report, generate_report, print_terminal_report,
@@ -19,7 +25,11 @@ use genesis_preflight::types::{
 };
 use genesis_preflight::validator::{
     check_integrity, validate_all_content, check_data_quality, calculate_fair_scores,
-    check_naming_conventions, validate_metadata, check_structure,
+    check_naming_conventions, validate_metadata, check_structure, validate_croissant,
+    validate_data_dictionary, validate_hdf5, validate_netcdf, validate_fits, validate_numpy,
+    validate_zarr, validate_tiff, check_images, validate_pdf, validate_gzip,
+    validate_archives, validate_json, validate_jsonl, validate_config, validate_xml,
+    validate_sqlite, validate_sequences, validate_vcf,
 };
 use std::path::PathBuf;
 use std::process;
@@ -37,6 +47,13 @@ fn main() {
 
     print_header(&config);
 
+    // BagIt commands have their own flow
+    match config.command {
+        Command::Bag => run_bag(&config),
+        Command::ValidateBag => run_validate_bag(&config),
+        _ => {}
+    }
+
     // Scan directory
     let files = match scan_directory(&config.target_path, &config) {
         Ok(f) => f,
@@ -71,11 +88,13 @@ fn main() {
     };
 
     // Generate report
-    let report = generate_report(&files, &validation, &generated, &config);
+    let report = generate_report(&files, &validation, &generated, &config)
+        .with_image_sizes(image_distribution(&analyses))
+        .with_compressed_files(compressed_files(&files, &analyses));
 
     // Output report
     match config.command {
-        Command::Report if config.json_output => {
+        Command::Report | Command::Generate if config.json_output => {
             println!("{}", generate_json_report(&report));
         }
         _ => {
@@ -111,7 +130,14 @@ fn parse_args() -> Result<Config, String> {
         "scan" => Command::Scan,
         "generate" => Command::Generate,
         "report" => Command::Report,
-        cmd => return Err(format!("Unknown command '{}'. Use scan, generate, or report.", cmd)),
+        "bag" => Command::Bag,
+        "validate-bag" => Command::ValidateBag,
+        cmd => {
+            return Err(format!(
+                "Unknown command '{}'. Use scan, generate, report, bag, or validate-bag.",
+                cmd
+            ))
+        }
     };
 
     // Parse path (required)
@@ -134,10 +160,10 @@ fn parse_args() -> Result<Config, String> {
     let mut i = 3;
     while i < args.len() {
         match args[i].as_str() {
-            "--output-dir" | "-o" => {
+            "--output-dir" | "-o" | "--out" => {
                 i += 1;
                 if i >= args.len() {
-                    return Err("Flag --output-dir requires a value".to_string());
+                    return Err(format!("Flag {} requires a value", args[i - 1]));
                 }
                 config.output_dir = Some(PathBuf::from(&args[i]));
             }
@@ -150,9 +176,21 @@ fn parse_args() -> Result<Config, String> {
             "--no-hash" => {
                 config.skip_hash = true;
             }
+            "--scan-archives" => {
+                config.scan_archives = true;
+            }
             "--json" => {
                 config.json_output = true;
             }
+            "--dictionary-csv" => {
+                config.dictionary_csv = true;
+            }
+            "--update" => {
+                config.update = true;
+            }
+            "--dry-run" => {
+                config.dry_run = true;
+            }
             flag => {
                 return Err(format!(
                     "Unknown flag '{}'. Use --help to see available options.",
@@ -168,6 +206,18 @@ fn parse_args() -> Result<Config, String> {
         return Err("Cannot use --verbose and --quiet together".to_string());
     }
 
+    if config.update && config.command != Command::Generate {
+        return Err("--update can only be used with the generate command".to_string());
+    }
+
+    if config.dry_run && config.command != Command::Generate {
+        return Err("--dry-run can only be used with the generate command".to_string());
+    }
+
+    if config.command == Command::Bag && config.output_dir.is_none() {
+        return Err("The bag command requires --out <DIR>".to_string());
+    }
+
     Ok(config)
 }
 
@@ -182,16 +232,23 @@ fn print_help() {
     println!("    scan        Scan and validate a dataset");
     println!("    generate    Scan, validate, and generate documentation");
     println!("    report      Generate a detailed compliance report");
+    println!("    bag         Package the dataset as a BagIt bag (requires --out)");
+    println!("    validate-bag  Check an existing BagIt bag for completeness and fixity");
     println!();
     println!("ARGUMENTS:");
-    println!("    <PATH>      Path to dataset directory");
+    println!("    <PATH>      Path to dataset directory (or bag directory for validate-bag)");
     println!();
     println!("OPTIONS:");
     println!("    -o, --output-dir <DIR>    Directory for generated files (default: dataset root)");
+    println!("        --out <DIR>           Bag directory to create (bag command)");
     println!("    -v, --verbose             Show detailed progress information");
     println!("    -q, --quiet               Suppress all non-error output");
     println!("        --no-hash             Skip SHA-256 hashing for faster scanning");
-    println!("        --json                Output report in JSON format (report command only)");
+    println!("        --scan-archives       Hash and analyze the files inside .zip and .tar archives");
+    println!("        --json                Output report in JSON format (report, generate and validate-bag)");
+    println!("        --dictionary-csv      Write DATA_DICTIONARY.csv instead of DATA_DICTIONARY.md");
+    println!("        --update              Refresh generated content in existing files (generate only)");
+    println!("        --dry-run             Show what generate would write, with diffs, without writing");
     println!("    -h, --help                Print this help message");
     println!("    -V, --version             Print version information");
     println!();
@@ -202,9 +259,19 @@ fn print_help() {
     println!("    # Generate documentation");
     println!("    genesis-preflight generate ./my-dataset");
     println!();
+    println!("    # Refresh manifest, file counts and schemas after data changes");
+    println!("    genesis-preflight generate ./my-dataset --update");
+    println!();
+    println!("    # Preview an update before touching a shared directory");
+    println!("    genesis-preflight generate ./my-dataset --update --dry-run");
+    println!();
     println!("    # JSON report for CI/CD");
     println!("    genesis-preflight report ./my-dataset --json");
     println!();
+    println!("    # Package for archival and verify the bag");
+    println!("    genesis-preflight bag ./my-dataset --out ./my-dataset-bag");
+    println!("    genesis-preflight validate-bag ./my-dataset-bag");
+    println!();
     println!("EXIT CODES:");
     println!("    0    No issues, score >= 80");
     println!("    1    Warnings present or score < 80");
@@ -223,6 +290,66 @@ fn print_header(config: &Config) {
     println!();
 }
 
+/// Package the dataset as a BagIt bag, verify it, and exit
+fn run_bag(config: &Config) -> ! {
+    let files = match scan_directory(&config.target_path, config) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Error scanning directory: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let out_dir = config.get_output_dir();
+    let bagging_date = get_current_timestamp();
+    let summary = match create_bag(&files, &config.target_path, out_dir, &bagging_date[..10]) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error creating bag: {}", e);
+            process::exit(1);
+        }
+    };
+
+    if !config.quiet {
+        println!("Created bag: {}", summary.bag_dir.display());
+        println!(
+            "Payload: {} files, {} bytes (Payload-Oxum {})",
+            summary.payload_files, summary.payload_bytes, summary.payload_oxum
+        );
+        println!();
+    }
+
+    let validation = validate_bag(&summary.bag_dir);
+    let report = generate_report(&files, &validation, &[], config);
+    print_terminal_report(&report, config);
+
+    process::exit(report.exit_code());
+}
+
+/// Validate an existing BagIt bag and exit
+fn run_validate_bag(config: &Config) -> ! {
+    // Hashes are checked against the manifests, so skip scanner hashing
+    let scan_config = config.clone().with_skip_hash(true);
+    let files = match scan_directory(&config.target_path, &scan_config) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Error scanning directory: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let validation = validate_bag(&config.target_path);
+    let report = generate_report(&files, &validation, &[], config);
+
+    if config.json_output {
+        println!("{}", generate_json_report(&report));
+    } else {
+        print_terminal_report(&report, config);
+    }
+
+    process::exit(report.exit_code());
+}
+
 /// Analyze all files
 fn analyze_files(files: &[FileInfo], config: &Config) -> Vec<AnalysisResult> {
     let mut analyses = Vec::new();
@@ -256,6 +383,63 @@ fn validate_dataset(
     // Metadata validation
     validation.extend(validate_metadata(files));
 
+    // Croissant metadata (if croissant.json exists)
+    validation.extend(validate_croissant(files, analyses));
+
+    // Column documentation in the data dictionary
+    validation.extend(validate_data_dictionary(files, analyses));
+
+    // Units and descriptions on HDF5 datasets
+    validation.extend(validate_hdf5(files, analyses));
+
+    // CF conventions in netCDF classic files
+    validation.extend(validate_netcdf(files, analyses));
+
+    // FITS header keywords, column units and sizes
+    validation.extend(validate_fits(files, analyses));
+
+    // NumPy array sizes
+    validation.extend(validate_numpy(files, analyses));
+
+    // Zarr chunk files
+    validation.extend(validate_zarr(files, analyses));
+
+    // GeoTIFF coordinate reference systems
+    validation.extend(validate_tiff(files, analyses));
+
+    // Image sizes and channels across the collection
+    validation.extend(check_images(files, analyses));
+
+    // PDF encryption and text layers
+    validation.extend(validate_pdf(files, analyses));
+
+    // gzip stream integrity
+    validation.extend(validate_gzip(files, analyses));
+
+    // ZIP/tar archive entries
+    validation.extend(validate_archives(files, analyses));
+
+    // JSON syntax errors
+    validation.extend(validate_json(files, analyses));
+
+    // JSON Lines records
+    validation.extend(validate_jsonl(files, analyses));
+
+    // YAML/TOML syntax errors
+    validation.extend(validate_config(files, analyses));
+
+    // XML well-formedness
+    validation.extend(validate_xml(files, analyses));
+
+    // SQLite write-ahead logs and damage
+    validation.extend(validate_sqlite(files, analyses));
+
+    // FASTA/FASTQ records
+    validation.extend(validate_sequences(files, analyses));
+
+    // VCF header, records and sort order
+    validation.extend(validate_vcf(files, analyses));
+
     // FAIR compliance
     validation.extend(calculate_fair_scores(files, analyses));
 
@@ -280,6 +464,10 @@ fn validate_dataset(
 }
 
 /// Generate documentation files
+///
+/// Missing files are created. With `--update`, machine-owned content in
+/// existing files is merge-updated and user edits are preserved. With
+/// `--dry-run`, the same plan is returned but nothing is written.
 fn generate_documentation(
     files: &[FileInfo],
     analyses: &[AnalysisResult],
@@ -295,19 +483,29 @@ fn generate_documentation(
         .unwrap_or(&config.target_path)
         .clone();
 
-    // Create dataset summary
+    // Create dataset summary (excluding documentation generated by this tool
+    // and archive members, which their archive already accounts for)
+    let content_files: Vec<&FileInfo> = files
+        .iter()
+        .filter(|f| !is_generated_file(f) && !f.is_archive_member())
+        .collect();
     let mut summary = DatasetSummary::new();
-    summary.total_files = files.len();
-    summary.total_size = files.iter().map(|f| f.size_bytes).sum();
+    summary.total_files = content_files.len();
+    summary.total_size = content_files.iter().map(|f| f.size_bytes).sum();
     summary.scan_timestamp = get_current_timestamp();
 
     // Count file types using a simple loop (Vec-based storage)
     use std::collections::HashMap;
     let mut type_counts: HashMap<FileType, usize> = HashMap::new();
-    for file in files {
+    for file in content_files {
         *type_counts.entry(file.file_type).or_insert(0) += 1;
     }
     summary.file_type_counts = type_counts.into_iter().collect();
+    // Stable order so regenerated content only differs when counts change
+    summary
+        .file_type_counts
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+    summary.image_sizes = image_distribution(analyses);
 
     if config.verbose {
         println!();
@@ -316,102 +514,130 @@ fn generate_documentation(
 
     // Generate README
     let readme_path = output_dir.join("README.md");
-    if !readme_path.exists() {
-        let content = generate_readme(&summary);
-        std::fs::write(&readme_path, content)
-            .map_err(|e| format!("Failed to write README.md: {}", e))?;
-        generated.push(GeneratedFile::created(readme_path));
-        if config.verbose {
-            println!("Created: README.md");
-        }
-    } else {
-        generated.push(GeneratedFile::skipped(readme_path));
-        if config.verbose {
-            println!("Skipped: README.md (already exists)");
-        }
-    }
+    let content = generate_readme(&summary);
+    generated.push(
+        write_generated(&readme_path, &content, merge_markdown, config)
+            .map_err(|e| format!("Failed to write README.md: {}", e))?,
+    );
 
     // Generate metadata.json
     let metadata_path = output_dir.join("metadata.json");
-    if !metadata_path.exists() {
-        let content = generate_metadata(&summary);
-        std::fs::write(&metadata_path, content)
-            .map_err(|e| format!("Failed to write metadata.json: {}", e))?;
-        generated.push(GeneratedFile::created(metadata_path));
-        if config.verbose {
-            println!("Created: metadata.json");
-        }
-    } else {
-        generated.push(GeneratedFile::skipped(metadata_path));
-        if config.verbose {
-            println!("Skipped: metadata.json (already exists)");
-        }
-    }
+    let content = generate_metadata(&summary);
+    generated.push(
+        write_generated(&metadata_path, &content, merge_metadata_json, config)
+            .map_err(|e| format!("Failed to write metadata.json: {}", e))?,
+    );
 
     // Generate DATACARD.md
     let datacard_path = output_dir.join("DATACARD.md");
-    if !datacard_path.exists() {
-        let content = generate_datacard(&summary);
-        std::fs::write(&datacard_path, content)
-            .map_err(|e| format!("Failed to write DATACARD.md: {}", e))?;
-        generated.push(GeneratedFile::created(datacard_path));
-        if config.verbose {
-            println!("Created: DATACARD.md");
+    let content = generate_datacard(&summary);
+    generated.push(
+        write_generated(&datacard_path, &content, merge_markdown, config)
+            .map_err(|e| format!("Failed to write DATACARD.md: {}", e))?,
+    );
+
+    // Generate croissant.json
+    let croissant_path = output_dir.join("croissant.json");
+    let content = generate_croissant(&summary, files, analyses);
+    generated.push(
+        write_generated(&croissant_path, &content, merge_croissant, config)
+            .map_err(|e| format!("Failed to write croissant.json: {}", e))?,
+    );
+
+    // Generate the data dictionary (codebook) for tabular datasets
+    let has_tables = analyses.iter().any(|a| !a.tables().is_empty());
+    if has_tables {
+        // An existing dictionary in either format is kept in that format
+        let existing = files.iter().find(|f| is_data_dictionary(f));
+        let use_csv = match existing {
+            Some(f) => f.file_name() == Some(DATA_DICTIONARY_CSV),
+            None => config.dictionary_csv,
+        };
+        let dictionary_path = match existing {
+            Some(f) => f.full_path.clone(),
+            None => output_dir.join(if use_csv { DATA_DICTIONARY_CSV } else { DATA_DICTIONARY_MD }),
+        };
+
+        let result = if use_csv {
+            let content = generate_data_dictionary_csv(files, analyses);
+            write_generated(&dictionary_path, &content, merge_data_dictionary_csv, config)
+        } else {
+            let content = generate_data_dictionary(files, analyses);
+            write_generated(&dictionary_path, &content, merge_data_dictionary, config)
+        };
+        generated.push(result.map_err(|e| format!("Failed to write data dictionary: {}", e))?);
+    }
+
+    // Generate schema files for tabular datasets (not for tables inside archives)
+    for (idx, analysis) in analyses.iter().enumerate() {
+        if files.get(idx).is_some_and(|f| f.is_archive_member()) {
+            continue;
         }
-    } else {
-        generated.push(GeneratedFile::skipped(datacard_path));
-        if config.verbose {
-            println!("Skipped: DATACARD.md (already exists)");
+        // Get the corresponding file info to get the filename
+        let file_name = if idx < files.len() {
+            files[idx]
+                .relative_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("data.csv")
+        } else {
+            "data.csv"
+        };
+        // A SQLite database gives one schema per table
+        for (table, csv_analysis) in analysis.tables() {
+            let (schema_path, title) = match table {
+                Some(table) => (output_dir.join(table_schema_name(file_name, table)), format!("{}#{}", file_name, table)),
+                None => {
+                    let schema_base = file_name.trim_end_matches(".csv").trim_end_matches(".CSV");
+                    (output_dir.join(format!("{}.schema.json", schema_base)), file_name.to_string())
+                }
+            };
+
+            let content = generate_schema(csv_analysis, &title);
+            generated.push(
+                write_generated(&schema_path, &content, merge_schema_json, config)
+                    .map_err(|e| format!("Failed to write schema file: {}", e))?,
+            );
         }
     }
 
-    // Generate MANIFEST.txt
-    let manifest_path = output_dir.join("MANIFEST.txt");
-    if !manifest_path.exists() {
-        let content = generate_manifest(files);
-        std::fs::write(&manifest_path, content)
-            .map_err(|e| format!("Failed to write MANIFEST.txt: {}", e))?;
-        generated.push(GeneratedFile::created(manifest_path));
-        if config.verbose {
-            println!("Created: MANIFEST.txt");
-        }
-    } else {
-        generated.push(GeneratedFile::skipped(manifest_path));
-        if config.verbose {
-            println!("Skipped: MANIFEST.txt (already exists)");
+    // Generate schema files for HDF5 files, named after the full file name
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Hdf5(ref hdf5_analysis) = analysis {
+            let file_name = file.file_name().unwrap_or("data.h5");
+            let schema_path = output_dir.join(format!("{}.schema.json", file_name));
+
+            let content = generate_hdf5_schema(hdf5_analysis, file_name);
+            generated.push(
+                write_generated(&schema_path, &content, merge_schema_json, config)
+                    .map_err(|e| format!("Failed to write schema file: {}", e))?,
+            );
         }
     }
 
-    // Generate schema files for CSV datasets
-    for (idx, analysis) in analyses.iter().enumerate() {
-        if let AnalysisResult::Csv(ref csv_analysis) = analysis {
-            // Get the corresponding file info to get the filename
-            let file_name = if idx < files.len() {
-                files[idx]
-                    .relative_path
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("data.csv")
-            } else {
-                "data.csv"
-            };
-            let schema_base = file_name.trim_end_matches(".csv").trim_end_matches(".CSV");
-            let schema_path = output_dir.join(format!("{}.schema.json", schema_base));
-
-            if !schema_path.exists() {
-                let content = generate_schema(csv_analysis, file_name);
-                std::fs::write(&schema_path, content)
-                    .map_err(|e| format!("Failed to write schema file: {}", e))?;
-                generated.push(GeneratedFile::created(schema_path));
-                if config.verbose {
-                    println!("Created: {}.schema.json", schema_base);
-                }
-            } else {
-                generated.push(GeneratedFile::skipped(schema_path));
-            }
+    // Generate schema files for JSON data files, named after the full file name
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let Some(structure) = json_data_structure(file, analysis) {
+            let file_name = file.file_name().unwrap_or("data.json");
+            let schema_path = output_dir.join(format!("{}.schema.json", file_name));
+
+            let content = generate_json_schema(structure, file_name);
+            generated.push(
+                write_generated(&schema_path, &content, merge_schema_json, config)
+                    .map_err(|e| format!("Failed to write schema file: {}", e))?,
+            );
         }
     }
 
+    // Generate MANIFEST.txt last so it covers documentation updated above
+    let manifest_path = output_dir.join("MANIFEST.txt");
+    let manifest_files = refresh_hashes(files, &generated, &config.target_path, "MANIFEST.txt");
+    let content = generate_manifest(&manifest_files);
+    generated.push(
+        write_generated(&manifest_path, &content, replace_all, config)
+            .map_err(|e| format!("Failed to write MANIFEST.txt: {}", e))?,
+    );
+
     Ok(generated)
 }
 
@@ -462,6 +688,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_parse_args_scan() {
         let args = vec![
             "genesis-preflight".to_string(),
@@ -501,6 +728,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_get_current_timestamp_reasonable_year() {
         let ts = get_current_timestamp();
         let year: i32 = ts[0..4].parse().unwrap();
@@ -1,6 +1,7 @@
 //! JSON report generation
 
 use super::Report;
+use crate::generator::escape_json;
 
 /// Generate a JSON report
 ///
@@ -11,6 +12,7 @@ pub fn generate_json_report(report: &Report) -> String {
     json.push_str("{\n");
     json.push_str(&format!("  \"dataset_path\": \"{}\",\n", escape_json(&report.dataset_path)));
     json.push_str(&format!("  \"scan_timestamp\": \"{}\",\n", escape_json(&report.scan_timestamp)));
+    json.push_str(&format!("  \"dry_run\": {},\n", report.dry_run));
 
     // Score
     json.push_str("  \"score\": {\n");
@@ -26,10 +28,42 @@ pub fn generate_json_report(report: &Report) -> String {
 
     // Files
     json.push_str("  \"files\": {\n");
-    json.push_str(&format!("    \"count\": {},\n", report.files.len()));
+    // Archive members are counted separately, their archive already covers their bytes
+    let members = report.files.iter().filter(|f| f.is_archive_member()).count();
+    json.push_str(&format!("    \"count\": {},\n", report.files.len() - members));
 
-    let total_size: u64 = report.files.iter().map(|f| f.size_bytes).sum();
-    json.push_str(&format!("    \"total_size_bytes\": {}\n", total_size));
+    let total_size: u64 = report.files.iter().filter(|f| !f.is_archive_member()).map(|f| f.size_bytes).sum();
+    json.push_str(&format!("    \"total_size_bytes\": {},\n", total_size));
+    json.push_str(&format!("    \"archive_members\": {},\n", members));
+    json.push_str("    \"image_sizes\": [");
+    for (idx, (size, count)) in report.image_sizes.iter().enumerate() {
+        let comma = if idx < report.image_sizes.len() - 1 { "," } else { "" };
+        json.push_str(&format!(
+            "\n      {{\"size\": \"{}\", \"count\": {}}}{}",
+            escape_json(size),
+            count,
+            comma
+        ));
+    }
+    if !report.image_sizes.is_empty() {
+        json.push_str("\n    ");
+    }
+    json.push_str("],\n");
+    json.push_str("    \"compressed\": [");
+    for (idx, (path, gzip)) in report.compressed_files.iter().enumerate() {
+        let comma = if idx < report.compressed_files.len() - 1 { "," } else { "" };
+        json.push_str(&format!(
+            "\n      {{\"path\": \"{}\", \"compressed_bytes\": {}, \"uncompressed_bytes\": {}}}{}",
+            escape_json(path),
+            gzip.compressed_bytes,
+            gzip.uncompressed_bytes,
+            comma
+        ));
+    }
+    if !report.compressed_files.is_empty() {
+        json.push_str("\n    ");
+    }
+    json.push_str("]\n");
     json.push_str("  },\n");
 
     // Validation results
@@ -65,7 +99,16 @@ pub fn generate_json_report(report: &Report) -> String {
 
         json.push_str("    {\n");
         json.push_str(&format!("      \"path\": \"{}\",\n", escape_json(&gen_file.path.to_string_lossy())));
-        json.push_str(&format!("      \"was_created\": {}\n", gen_file.was_created));
+        json.push_str(&format!("      \"was_created\": {},\n", gen_file.was_created));
+        json.push_str(&format!("      \"was_updated\": {},\n", gen_file.was_updated));
+        json.push_str(&format!("      \"action\": \"{}\"", gen_file.action()));
+
+        if let Some(ref diff) = gen_file.diff {
+            json.push_str(",\n");
+            json.push_str(&format!("      \"diff\": \"{}\"", escape_json(diff)));
+        }
+
+        json.push('\n');
         json.push_str(&format!("    }}{}\n", comma));
     }
     json.push_str("  ],\n");
@@ -78,22 +121,11 @@ pub fn generate_json_report(report: &Report) -> String {
     json
 }
 
-/// Escape string for JSON
-fn escape_json(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generator::GeneratedFile;
     use crate::reporter::ComplianceScore;
-    use crate::types::ValidationResult;
-    use std::path::PathBuf;
+    use crate::types::{GzipInfo, ValidationResult};
 
     #[test]
     fn test_generate_json_report() {
@@ -113,6 +145,9 @@ mod tests {
                 warning_count: 0,
                 info_count: 0,
             },
+            dry_run: false,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
         };
 
         let json = generate_json_report(&report);
@@ -121,6 +156,46 @@ mod tests {
         assert!(json.contains("\"score\":"));
         assert!(json.contains("\"total\": 100"));
         assert!(json.contains("\"exit_code\":"));
+        assert!(json.contains("\"image_sizes\": []"));
+        assert!(json.contains("\"compressed\": []"));
+    }
+
+    #[test]
+    fn test_json_image_sizes_and_compressed_files() {
+        let report = Report {
+            dataset_path: "/test".to_string(),
+            scan_timestamp: "2024-01-15".to_string(),
+            files: vec![],
+            validation_results: vec![],
+            generated_files: vec![],
+            score: ComplianceScore {
+                total: 100,
+                findable: 25,
+                accessible: 25,
+                interoperable: 25,
+                reusable: 25,
+                critical_count: 0,
+                warning_count: 0,
+                info_count: 0,
+            },
+            dry_run: false,
+            image_sizes: vec![("224x224, 3 channels".to_string(), 3), ("64x64, 1 channel".to_string(), 1)],
+            compressed_files: vec![(
+                "obs.csv.gz".to_string(),
+                GzipInfo { compressed_bytes: 1200, uncompressed_bytes: 9800, error: None },
+            )],
+        };
+
+        let json = crate::analyzer::parse_json(&generate_json_report(&report)).unwrap();
+        let sizes = json.get("files").and_then(|f| f.get("image_sizes")).and_then(|s| s.as_array()).unwrap();
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].get("size").and_then(|s| s.as_str()), Some("224x224, 3 channels"));
+        assert_eq!(sizes[1].get("count").and_then(|c| c.as_f64()), Some(1.0));
+
+        let compressed = json.get("files").and_then(|f| f.get("compressed")).and_then(|c| c.as_array()).unwrap();
+        assert_eq!(compressed.len(), 1);
+        assert_eq!(compressed[0].get("path").and_then(|p| p.as_str()), Some("obs.csv.gz"));
+        assert_eq!(compressed[0].get("uncompressed_bytes").and_then(|b| b.as_f64()), Some(9800.0));
     }
 
     #[test]
@@ -150,6 +225,9 @@ mod tests {
                 warning_count: 0,
                 info_count: 0,
             },
+            dry_run: false,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
         };
 
         let json = generate_json_report(&report);
@@ -158,4 +236,40 @@ mod tests {
         assert!(json.contains("\"code\": \"TEST-001\""));
         assert!(json.contains("\"message\": \"Test issue\""));
     }
+
+    #[test]
+    fn test_json_dry_run_plan() {
+        use crate::generator::GeneratedFile;
+        use std::path::PathBuf;
+
+        let report = Report {
+            dataset_path: "/test".to_string(),
+            scan_timestamp: "2024-01-15".to_string(),
+            files: vec![],
+            validation_results: vec![],
+            generated_files: vec![
+                GeneratedFile::created(PathBuf::from("/test/README.md")),
+                GeneratedFile::updated(PathBuf::from("/test/MANIFEST.txt"), "-a\n+b\n".to_string()),
+            ],
+            score: ComplianceScore {
+                total: 100,
+                findable: 25,
+                accessible: 25,
+                interoperable: 25,
+                reusable: 25,
+                critical_count: 0,
+                warning_count: 0,
+                info_count: 0,
+            },
+            dry_run: true,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
+        };
+
+        let json = generate_json_report(&report);
+
+        assert!(json.contains("\"dry_run\": true"));
+        assert!(json.contains("\"action\": \"create\"\n"));
+        assert!(json.contains("\"action\": \"update\",\n      \"diff\": \"-a\\n+b\\n\"\n"));
+    }
 }
@@ -7,7 +7,7 @@ mod score;
 mod terminal;
 
 use crate::generator::GeneratedFile;
-use crate::types::{Config, FileInfo, ValidationResult};
+use crate::types::{Config, FileInfo, GzipInfo, ValidationResult};
 
 pub use json_report::generate_json_report;
 pub use score::{calculate_score, ComplianceScore};
@@ -28,9 +28,27 @@ pub struct Report {
     pub generated_files: Vec<GeneratedFile>,
     /// Compliance score
     pub score: ComplianceScore,
+    /// Whether generated files are a plan only (`generate --dry-run`)
+    pub dry_run: bool,
+    /// Count of PNG/JPEG images per size and channel count, most common first
+    pub image_sizes: Vec<(String, usize)>,
+    /// Gzipped data files with their compressed and decompressed sizes
+    pub compressed_files: Vec<(String, GzipInfo)>,
 }
 
 impl Report {
+    /// Set the image size distribution shown in the summary
+    pub fn with_image_sizes(mut self, image_sizes: Vec<(String, usize)>) -> Self {
+        self.image_sizes = image_sizes;
+        self
+    }
+
+    /// Set the gzipped files whose sizes are shown in the summary
+    pub fn with_compressed_files(mut self, compressed_files: Vec<(String, GzipInfo)>) -> Self {
+        self.compressed_files = compressed_files;
+        self
+    }
+
     /// Get exit code based on score and issues
     pub fn exit_code(&self) -> i32 {
         if self.score.critical_count > 0 || self.score.total < 50 {
@@ -72,6 +90,9 @@ pub fn generate_report(
         validation_results: validation.to_vec(),
         generated_files: generated.to_vec(),
         score,
+        dry_run: config.dry_run,
+        image_sizes: Vec::new(),
+        compressed_files: Vec::new(),
     }
 }
 
@@ -112,6 +133,7 @@ fn get_current_timestamp() -> String {
 }
 
 #[cfg(test)]
+#[allow(unused_mut)]
 mod tests {
     use super::*;
     use crate::types::Command;
@@ -149,6 +171,9 @@ mod tests {
                 warning_count: 0,
                 info_count: 0,
             },
+            dry_run: false,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
         };
 
         assert_eq!(report.exit_code(), 0);
@@ -172,6 +197,9 @@ mod tests {
                 warning_count: 3,
                 info_count: 2,
             },
+            dry_run: false,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
         };
 
         assert_eq!(report.exit_code(), 1);
@@ -195,6 +223,9 @@ mod tests {
                 warning_count: 3,
                 info_count: 1,
             },
+            dry_run: false,
+            image_sizes: Vec::new(),
+            compressed_files: Vec::new(),
         };
 
         assert_eq!(report.exit_code(), 2);
@@ -1,7 +1,8 @@
 //! Terminal report output
 
 use super::{ComplianceScore, Report};
-use crate::types::{Config, FileType, ValidationSeverity};
+use crate::generator::GeneratedFile;
+use crate::types::{Config, FileInfo, FileType, ValidationSeverity};
 use std::collections::HashMap;
 
 /// Print report to terminal
@@ -24,7 +25,7 @@ pub fn print_terminal_report(report: &Report, config: &Config) {
     print_summary(report);
     print_compliance_score(&report.score);
     print_issues(report);
-    print_generated_files(report);
+    print_generated_files(report, config);
     print_next_steps(report);
 
     println!("================================================================");
@@ -34,14 +35,17 @@ pub fn print_terminal_report(report: &Report, config: &Config) {
 fn print_summary(report: &Report) {
     println!("SUMMARY");
     println!("-------");
-    println!("Files scanned: {}", report.files.len());
+    // Archive members are counted separately, their archive already covers their bytes
+    let (members, files): (Vec<&FileInfo>, Vec<&FileInfo>) =
+        report.files.iter().partition(|f| f.is_archive_member());
+    println!("Files scanned: {}", files.len());
 
-    let total_size: u64 = report.files.iter().map(|f| f.size_bytes).sum();
+    let total_size: u64 = files.iter().map(|f| f.size_bytes).sum();
     println!("Total size: {}", format_size(total_size));
 
     // Count file types
     let mut type_counts: HashMap<FileType, usize> = HashMap::new();
-    for file in &report.files {
+    for file in &files {
         *type_counts.entry(file.file_type).or_insert(0) += 1;
     }
 
@@ -56,6 +60,28 @@ fn print_summary(report: &Report) {
         print!("{} {}", count, file_type);
     }
     println!();
+    if !report.image_sizes.is_empty() {
+        let sizes: Vec<String> = report
+            .image_sizes
+            .iter()
+            .map(|(size, count)| format!("{} × {}", count, size))
+            .collect();
+        println!("Image sizes: {}", sizes.join("; "));
+    }
+    if !report.compressed_files.is_empty() {
+        let compressed: u64 = report.compressed_files.iter().map(|(_, g)| g.compressed_bytes).sum();
+        let uncompressed: u64 = report.compressed_files.iter().map(|(_, g)| g.uncompressed_bytes).sum();
+        println!(
+            "Gzipped files: {} ({} compressed, {} uncompressed)",
+            report.compressed_files.len(),
+            format_size(compressed),
+            format_size(uncompressed)
+        );
+    }
+    if !members.is_empty() {
+        let uncompressed: u64 = members.iter().map(|f| f.size_bytes).sum();
+        println!("Archive members: {} ({} uncompressed)", members.len(), format_size(uncompressed));
+    }
     println!();
 }
 
@@ -140,13 +166,18 @@ fn print_issues(report: &Report) {
 }
 
 /// Print generated files section
-fn print_generated_files(report: &Report) {
+fn print_generated_files(report: &Report, config: &Config) {
     if report.generated_files.is_empty() {
         return;
     }
 
-    println!("GENERATED FILES");
-    println!("---------------");
+    if report.dry_run {
+        println!("GENERATION PLAN (dry run, nothing written)");
+        println!("-----------------------------------------");
+    } else {
+        println!("GENERATED FILES");
+        println!("---------------");
+    }
 
     for gen_file in &report.generated_files {
         let filename = gen_file
@@ -155,8 +186,19 @@ fn print_generated_files(report: &Report) {
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
 
-        if gen_file.was_created {
+        if report.dry_run {
+            print_planned_file(gen_file, filename, config);
+        } else if gen_file.was_created {
             println!("Created: {}", filename);
+        } else if gen_file.was_updated {
+            println!("Updated: {}", filename);
+            if let Some(ref diff) = gen_file.diff {
+                for line in diff.lines() {
+                    println!("    {}", line);
+                }
+            }
+        } else if config.update {
+            println!("Unchanged: {}", filename);
         } else {
             println!("Skipped: {} (already exists)", filename);
         }
@@ -164,6 +206,24 @@ fn print_generated_files(report: &Report) {
     println!();
 }
 
+/// Print one entry of a `--dry-run` plan
+fn print_planned_file(gen_file: &GeneratedFile, filename: &str, config: &Config) {
+    if gen_file.was_created {
+        println!("Would create: {}", filename);
+    } else if gen_file.was_updated {
+        println!("Would update: {}", filename);
+        if let Some(ref diff) = gen_file.diff {
+            for line in diff.lines() {
+                println!("    {}", line);
+            }
+        }
+    } else if config.update {
+        println!("Would leave unchanged: {}", filename);
+    } else {
+        println!("Would skip: {} (already exists)", filename);
+    }
+}
+
 /// Print next steps section
 fn print_next_steps(report: &Report) {
     println!("NEXT STEPS");
@@ -171,6 +231,10 @@ fn print_next_steps(report: &Report) {
 
     let has_generated = report.generated_files.iter().any(|f| f.was_created);
 
+    if report.dry_run && report.generated_files.iter().any(|f| f.was_created || f.was_updated) {
+        println!("Re-run without --dry-run to write the planned files.");
+    }
+
     if has_generated {
         println!("1. Review and complete all [TODO] sections in generated files");
     }
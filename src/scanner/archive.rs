@@ -0,0 +1,118 @@
+//! Archive member listing
+//!
+//! With `--scan-archives`, each regular file inside a ZIP or tar archive is
+//! reported as a virtual entry of its own, at `<archive>!/<member path>`,
+//! so it is hashed and analyzed without extracting anything to disk.
+
+use crate::archive::{self, MEMBER_SEPARATOR};
+use crate::crypto::sha256_reader;
+use crate::types::{ArchiveEntryKind, ArchiveMember, Config, FileInfo, FileType};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Build virtual FileInfos for the regular files in an archive
+///
+/// Directories, links, hidden files and macOS resource forks (`__MACOSX/`)
+/// are left out, as are entries whose path would escape the extraction
+/// directory and repeats of a name already listed; the archive validator
+/// reports those. Members that cannot be decoded are listed without a hash.
+///
+/// # Returns
+///
+/// The members in archive order, or an error if the archive cannot be read.
+pub fn build_member_infos(archive_info: &FileInfo, config: &Config) -> io::Result<Vec<FileInfo>> {
+    let format = match archive::format_from_path(&archive_info.full_path) {
+        Some(format) => format,
+        None => return Ok(Vec::new()),
+    };
+    let listing = archive::read_archive(&archive_info.full_path, format)?;
+
+    let mut seen = HashSet::new();
+    let mut members = Vec::new();
+    for entry in listing.entries {
+        let name = entry.name.trim_start_matches("./").to_string();
+        if entry.kind != ArchiveEntryKind::File
+            || entry.escapes_root()
+            || name.split('/').any(|part| part.starts_with('.') || part == "__MACOSX")
+            || !seen.insert(name.clone())
+        {
+            continue;
+        }
+
+        let relative_path = member_path(&archive_info.relative_path, &name);
+        let full_path = member_path(&archive_info.full_path, &name);
+        let mut info = FileInfo::new(full_path, relative_path)
+            .with_type(FileType::from_path(Path::new(&name)))
+            .with_size(entry.size);
+        if let Some(modified) = archive_info.modified {
+            info = info.with_modified(modified);
+        }
+
+        if !config.skip_hash && entry.is_readable() {
+            match archive::open_member(&archive_info.full_path, format, &entry).and_then(sha256_reader) {
+                Ok(hash) => info = info.with_hash(hash),
+                Err(e) => {
+                    if config.verbose {
+                        eprintln!("Warning: Cannot hash {}: {}", info.relative_path.display(), e);
+                    }
+                }
+            }
+        }
+
+        members.push(info.with_archive_member(ArchiveMember {
+            archive: archive_info.full_path.clone(),
+            format,
+            entry,
+        }));
+    }
+
+    Ok(members)
+}
+
+/// `data.zip!/raw/obs.csv`
+fn member_path(archive: &Path, name: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}{}", archive.display(), MEMBER_SEPARATOR, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::zip::fixture;
+    use crate::crypto::sha256_hex;
+    use crate::types::Command;
+    use std::fs;
+
+    #[test]
+    fn test_member_infos() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_scan_archive_members");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("data.zip");
+        fs::write(
+            &path,
+            fixture::zip(&[
+                ("raw/", 0, b""),
+                ("raw/obs.csv", 0, b"id,value\n1,2.5\n"),
+                ("./raw/obs.csv", 0, b"id,value\n9,9.9\n"),
+                ("../evil.sh", 0, b"rm -rf ~\n"),
+                ("__MACOSX/raw/._obs.csv", 0, b"resource fork"),
+                ("model.bin", 14, b"lzma"),
+            ]),
+        )
+        .unwrap();
+
+        let config = Config::new(temp_dir.clone(), Command::Scan);
+        let archive_info = FileInfo::new(path.clone(), PathBuf::from("data.zip"));
+        let members = build_member_infos(&archive_info, &config).unwrap();
+
+        let paths: Vec<String> = members.iter().map(|m| m.relative_path.display().to_string()).collect();
+        assert_eq!(paths, vec!["data.zip!/raw/obs.csv", "data.zip!/model.bin"]);
+        assert_eq!(members[0].file_type, FileType::Csv);
+        assert_eq!(members[0].size_bytes, 15);
+        assert_eq!(members[0].sha256_hash.as_deref(), Some(sha256_hex(b"id,value\n1,2.5\n").as_str()));
+        assert!(members[0].is_archive_member());
+        assert!(members[1].sha256_hash.is_none());
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+}
@@ -1,7 +1,9 @@
 //! Directory traversal implementation
 
 use crate::types::{Config, FileInfo};
+use super::archive;
 use super::file_info_builder;
+use super::zarr;
 use super::ScanError;
 use std::fs;
 use std::path::Path;
@@ -109,6 +111,22 @@ fn walk_recursive(
                 continue;
             }
 
+            // A Zarr store is one logical entry, not thousands of chunk files
+            if zarr::is_zarr_store(&path) {
+                match zarr::build_store_info(&path, root, config) {
+                    Ok(info) => {
+                        files.push(info);
+                        *file_count += 1;
+                    }
+                    Err(e) => {
+                        if config.verbose {
+                            eprintln!("Warning: Cannot read Zarr store {}: {}", path.display(), e);
+                        }
+                    }
+                }
+                continue;
+            }
+
             // Recurse into subdirectory
             walk_recursive(root, &path, files, file_count, depth + 1, config)?;
         } else if metadata.is_file() {
@@ -123,8 +141,20 @@ fn walk_recursive(
             // Build FileInfo
             match file_info_builder::build_file_info(&path, root, config) {
                 Ok(info) => {
+                    // Members follow their archive as entries of their own
+                    let members = if config.scan_archives {
+                        archive::build_member_infos(&info, config).unwrap_or_else(|e| {
+                            if config.verbose {
+                                eprintln!("Warning: Cannot list archive {}: {}", path.display(), e);
+                            }
+                            Vec::new()
+                        })
+                    } else {
+                        Vec::new()
+                    };
                     files.push(info);
-                    *file_count += 1;
+                    *file_count += 1 + members.len();
+                    files.extend(members);
 
                     // Progress indication
                     if config.verbose && (*file_count).is_multiple_of(1000) {
@@ -183,6 +213,28 @@ mod tests {
         fs::remove_dir_all(temp_dir).ok();
     }
 
+    #[test]
+    fn test_walk_collapses_zarr_store() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_walk_zarr");
+        fs::create_dir_all(temp_dir.join("grid.zarr/c/0")).unwrap();
+
+        fs::write(temp_dir.join("notes.txt"), "notes").unwrap();
+        fs::write(temp_dir.join("grid.zarr/zarr.json"), "{}").unwrap();
+        fs::write(temp_dir.join("grid.zarr/c/0/0"), "chunk").unwrap();
+        fs::write(temp_dir.join("grid.zarr/c/0/1"), "chunk").unwrap();
+
+        let config = Config::new(temp_dir.clone(), Command::Scan);
+        let mut result = walk_directory(&temp_dir, &config).unwrap();
+        result.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].file_name(), Some("grid.zarr"));
+        assert_eq!(result[0].file_type, crate::types::FileType::Zarr);
+        assert_eq!(result[0].size_bytes, 12);
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
     #[test]
     fn test_walk_nested() {
         let temp_dir = std::env::temp_dir().join("genesis_preflight_walk_nested");
@@ -3,14 +3,19 @@
 //! This module handles traversing dataset directories and collecting
 //! metadata about all files found.
 
+mod archive;
 mod directory;
 mod file_info_builder;
+mod zarr;
 
 use crate::types::{Config, FileInfo};
 use std::fmt;
 use std::io;
 use std::path::{Path, PathBuf};
 
+pub use archive::build_member_infos;
+pub use zarr::{hash_store, is_zarr_store, store_files};
+
 /// Errors that can occur during directory scanning
 #[derive(Debug)]
 pub enum ScanError {
@@ -0,0 +1,133 @@
+//! Zarr store recognition
+//!
+//! A Zarr store is a directory tree of small metadata and chunk files that
+//! together hold one array or group hierarchy. The scanner reports each
+//! store as a single logical entry instead of listing every chunk.
+
+use crate::crypto::{sha256_file, sha256_hex};
+use crate::types::{Config, FileInfo, FileType};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Files that mark a directory as the root of a Zarr store
+/// (`.zarray`/`.zgroup`/`.zattrs` for v2, `zarr.json` for v3)
+const STORE_MARKERS: &[&str] = &[".zarray", ".zgroup", ".zattrs", "zarr.json"];
+
+/// Whether a directory is the root of a Zarr store
+pub fn is_zarr_store(dir: &Path) -> bool {
+    STORE_MARKERS.iter().any(|marker| dir.join(marker).is_file())
+}
+
+/// List every file in a store, hidden metadata files included
+///
+/// Paths are relative to the store root, use `/` separators, and are
+/// sorted. Symlinks are not followed.
+pub fn store_files(dir: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    collect_files(dir, "", &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &relative, files)?;
+        } else if file_type.is_file() {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// SHA-256 of a store, computed over its file listing
+///
+/// The digest covers one `<sha256>  <path>` line per file, sorted by path,
+/// so any changed, added, removed or renamed chunk changes it.
+pub fn hash_store(dir: &Path) -> io::Result<String> {
+    let mut listing = String::new();
+    for file in store_files(dir)? {
+        let hash = sha256_file(&dir.join(&file))?;
+        listing.push_str(&format!("{}  {}\n", hash, file));
+    }
+    Ok(sha256_hex(listing.as_bytes()))
+}
+
+/// Build the logical FileInfo for a Zarr store
+///
+/// The size is the total of all files in the store and the modification
+/// time is that of the most recently modified file.
+pub fn build_store_info(path: &Path, root: &Path, config: &Config) -> io::Result<FileInfo> {
+    let relative_path = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    let mut info = FileInfo::new(path.to_path_buf(), relative_path).with_type(FileType::Zarr);
+
+    let mut size = 0u64;
+    let mut modified: Option<SystemTime> = None;
+    for file in store_files(path)? {
+        let metadata = fs::metadata(path.join(&file))?;
+        size += metadata.len();
+        if let Ok(time) = metadata.modified() {
+            modified = Some(modified.map_or(time, |latest| latest.max(time)));
+        }
+    }
+    info = info.with_size(size);
+    if let Some(modified) = modified {
+        info = info.with_modified(modified);
+    }
+
+    if !config.skip_hash {
+        match hash_store(path) {
+            Ok(hash) => info = info.with_hash(hash),
+            Err(e) => {
+                if config.verbose {
+                    eprintln!("Warning: Cannot hash Zarr store {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Command;
+
+    #[test]
+    fn test_store_info() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_zarr_store_info");
+        let store = temp_dir.join("grid.zarr");
+        fs::create_dir_all(store.join("temp")).unwrap();
+        fs::write(store.join(".zgroup"), r#"{"zarr_format": 2}"#).unwrap();
+        fs::write(store.join("temp/.zarray"), "{}").unwrap();
+        fs::write(store.join("temp/0.0"), "abcd").unwrap();
+
+        assert!(is_zarr_store(&store));
+        assert!(!is_zarr_store(&temp_dir));
+        assert_eq!(store_files(&store).unwrap(), vec![".zgroup", "temp/.zarray", "temp/0.0"]);
+
+        let config = Config::new(temp_dir.clone(), Command::Scan);
+        let info = build_store_info(&store, &temp_dir, &config).unwrap();
+        assert_eq!(info.file_type, FileType::Zarr);
+        assert_eq!(info.relative_path, Path::new("grid.zarr"));
+        assert_eq!(info.size_bytes, 18 + 2 + 4);
+
+        // Any change to a chunk changes the store hash
+        let before = info.sha256_hash.unwrap();
+        fs::write(store.join("temp/0.0"), "abce").unwrap();
+        assert_ne!(hash_store(&store).unwrap(), before);
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+}
@@ -0,0 +1,13 @@
+//! Helpers shared by unit tests
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Write `bytes` to `name` in the temp directory `dir`, creating it if needed
+pub(crate) fn write_temp_file(dir: &str, name: &str, bytes: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir().join(dir);
+    fs::create_dir_all(&temp_dir).unwrap();
+    let path = temp_dir.join(name);
+    fs::write(&path, bytes).unwrap();
+    path
+}
@@ -1,4 +1,5 @@
 use super::{ColumnType, FileType};
+use std::fmt;
 
 /// Information about a column in a CSV file
 #[derive(Debug, Clone)]
@@ -11,6 +12,14 @@ pub struct ColumnInfo {
     pub inferred_type: ColumnType,
     /// Number of null/empty values
     pub null_count: usize,
+    /// Number of distinct non-null values
+    pub unique_count: usize,
+    /// Whether distinct-value tracking hit its cap (unique_count is a lower bound)
+    pub unique_count_capped: bool,
+    /// Minimum numeric value (numeric columns only)
+    pub min_value: Option<f64>,
+    /// Maximum numeric value (numeric columns only)
+    pub max_value: Option<f64>,
     /// Sample values from this column
     pub sample_values: Vec<String>,
 }
@@ -23,6 +32,10 @@ impl ColumnInfo {
             name: None,
             inferred_type: ColumnType::Unknown,
             null_count: 0,
+            unique_count: 0,
+            unique_count_capped: false,
+            min_value: None,
+            max_value: None,
             sample_values: Vec::new(),
         }
     }
@@ -45,6 +58,20 @@ impl ColumnInfo {
         self
     }
 
+    /// Set the distinct value count
+    pub fn with_unique_count(mut self, unique_count: usize, capped: bool) -> Self {
+        self.unique_count = unique_count;
+        self.unique_count_capped = capped;
+        self
+    }
+
+    /// Set the numeric range
+    pub fn with_range(mut self, min_value: Option<f64>, max_value: Option<f64>) -> Self {
+        self.min_value = min_value;
+        self.max_value = max_value;
+        self
+    }
+
     /// Add a sample value
     pub fn add_sample(mut self, value: String) -> Self {
         if self.sample_values.len() < 5 && !self.sample_values.contains(&value) {
@@ -67,6 +94,8 @@ pub struct CsvAnalysis {
     pub row_count: usize,
     /// Information about each column
     pub columns: Vec<ColumnInfo>,
+    /// Sizes and integrity of the file, if it is gzip-compressed
+    pub gzip: Option<GzipInfo>,
 }
 
 impl CsvAnalysis {
@@ -78,10 +107,22 @@ impl CsvAnalysis {
             column_count: 0,
             row_count: 0,
             columns: Vec::new(),
+            gzip: None,
         }
     }
 }
 
+/// Sizes and integrity of a gzip-compressed data file (`data.csv.gz`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GzipInfo {
+    /// Size of the compressed file on disk
+    pub compressed_bytes: u64,
+    /// Bytes decompressed (up to the damage, if the stream is corrupt)
+    pub uncompressed_bytes: u64,
+    /// What is wrong with the stream: corrupt data, CRC or length mismatch
+    pub error: Option<String>,
+}
+
 /// Type of JSON root element
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JsonRootType {
@@ -91,6 +132,98 @@ pub enum JsonRootType {
     Array,
 }
 
+/// Merged structure of the JSON values found at one place in a document
+///
+/// Objects are merged member by member and the elements of arrays into one
+/// item shape, so an array of a million records is summarized as a single
+/// record shape.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonShape {
+    /// Number of `null` values
+    pub null_count: usize,
+    /// Number of `true`/`false` values
+    pub boolean_count: usize,
+    /// Number of whole numbers
+    pub integer_count: usize,
+    /// Number of numbers with a fractional part
+    pub number_count: usize,
+    /// Number of strings
+    pub string_count: usize,
+    /// Number of objects
+    pub object_count: usize,
+    /// Number of arrays
+    pub array_count: usize,
+    /// Smallest number
+    pub min_value: Option<f64>,
+    /// Largest number
+    pub max_value: Option<f64>,
+    /// Object members in order of first appearance; a member's `total()`
+    /// is the number of objects that have it
+    pub properties: Vec<(String, JsonShape)>,
+    /// Elements of all the arrays, merged
+    pub items: Option<Box<JsonShape>>,
+}
+
+impl JsonShape {
+    /// Number of values merged into this shape
+    pub fn total(&self) -> usize {
+        self.null_count
+            + self.boolean_count
+            + self.integer_count
+            + self.number_count
+            + self.string_count
+            + self.object_count
+            + self.array_count
+    }
+
+    /// Whether a member is present in every object merged
+    pub fn is_required(&self, member: &JsonShape) -> bool {
+        member.total() == self.object_count
+    }
+
+    /// JSON Schema type names of the values seen (`integer` only when no
+    /// number had a fractional part)
+    pub fn json_types(&self) -> Vec<&'static str> {
+        let mut types = Vec::new();
+        if self.object_count > 0 {
+            types.push("object");
+        }
+        if self.array_count > 0 {
+            types.push("array");
+        }
+        if self.string_count > 0 {
+            types.push("string");
+        }
+        if self.number_count > 0 {
+            types.push("number");
+        } else if self.integer_count > 0 {
+            types.push("integer");
+        }
+        if self.boolean_count > 0 {
+            types.push("boolean");
+        }
+        if self.null_count > 0 {
+            types.push("null");
+        }
+        types
+    }
+}
+
+/// Where and why a JSON document stopped parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonSyntaxError {
+    /// What was wrong
+    pub message: String,
+    /// Byte offset of the error from the start of the document
+    pub offset: u64,
+    /// Line of the error (1-based)
+    pub line: usize,
+    /// Column of the error, in characters (1-based)
+    pub column: usize,
+    /// The line around the error, shortened to a few dozen characters
+    pub snippet: String,
+}
+
 /// Analysis result for a JSON file
 #[derive(Debug, Clone)]
 pub struct JsonAnalysis {
@@ -100,6 +233,12 @@ pub struct JsonAnalysis {
     pub root_type: JsonRootType,
     /// Top-level keys (if root is an object)
     pub top_level_keys: Vec<String>,
+    /// Merged structure of the document (if valid)
+    pub structure: Option<JsonShape>,
+    /// The first syntax error (if invalid)
+    pub error: Option<JsonSyntaxError>,
+    /// Sizes and integrity of the file, if it is gzip-compressed
+    pub gzip: Option<GzipInfo>,
 }
 
 impl JsonAnalysis {
@@ -109,6 +248,9 @@ impl JsonAnalysis {
             is_valid,
             root_type,
             top_level_keys: Vec::new(),
+            structure: None,
+            error: None,
+            gzip: None,
         }
     }
 
@@ -118,10 +260,300 @@ impl JsonAnalysis {
             is_valid: false,
             root_type: JsonRootType::Object,
             top_level_keys: Vec::new(),
+            structure: None,
+            error: None,
+            gzip: None,
+        }
+    }
+}
+
+/// A top-level field of the records in a JSON Lines file
+#[derive(Debug, Clone)]
+pub struct JsonLinesField {
+    /// Field name
+    pub name: String,
+    /// Number of object records that have the field (null values included)
+    pub present_count: usize,
+    /// Records per JSON type of the value (`null`, `boolean`, `integer`,
+    /// `number`, `string`, `array`, `object`), in order of first appearance
+    pub type_counts: Vec<(&'static str, usize)>,
+}
+
+impl JsonLinesField {
+    /// JSON types of the non-null values, most common first
+    pub fn value_types(&self) -> Vec<(&'static str, usize)> {
+        let mut types: Vec<(&'static str, usize)> =
+            self.type_counts.iter().copied().filter(|&(name, _)| name != "null").collect();
+        types.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        types
+    }
+}
+
+/// Analysis result for a JSON Lines (NDJSON) file
+#[derive(Debug, Clone)]
+pub struct JsonLinesAnalysis {
+    /// Number of lines that parsed as JSON
+    pub record_count: usize,
+    /// Number of valid records that are not JSON objects
+    pub non_object_count: usize,
+    /// Number of non-blank lines that are not valid JSON
+    pub invalid_line_count: usize,
+    /// The first invalid lines as (line number, parse error)
+    pub invalid_lines: Vec<(usize, String)>,
+    /// Top-level fields of the object records, in order of first appearance
+    pub fields: Vec<JsonLinesField>,
+    /// The same fields as a tabular analysis, so schema generation, data
+    /// dictionaries and Croissant record sets treat JSON Lines like CSV
+    pub table: CsvAnalysis,
+}
+
+/// Format of a configuration or metadata file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// YAML (`.yaml`, `.yml`, `CITATION.cff`)
+    Yaml,
+    /// TOML (`.toml`)
+    Toml,
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFormat::Yaml => write!(f, "YAML"),
+            ConfigFormat::Toml => write!(f, "TOML"),
         }
     }
 }
 
+/// Where and why a YAML or TOML file stopped parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSyntaxError {
+    /// What was wrong
+    pub message: String,
+    /// Line of the error (1-based)
+    pub line: usize,
+}
+
+impl fmt::Display for ConfigSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Analysis result for a YAML or TOML file
+#[derive(Debug, Clone)]
+pub struct ConfigAnalysis {
+    /// Which format the file is in
+    pub format: ConfigFormat,
+    /// Whether the file parsed
+    pub is_valid: bool,
+    /// Keys of the top-level mapping or table (of the first YAML document)
+    pub top_level_keys: Vec<String>,
+    /// Number of documents (a YAML stream may hold several; TOML has one)
+    pub document_count: usize,
+    /// The first syntax error (if invalid)
+    pub error: Option<ConfigSyntaxError>,
+}
+
+/// A metadata standard, recognized by the namespace of an XML root element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataStandard {
+    /// Ecological Metadata Language
+    Eml,
+    /// ISO 19115 geographic metadata, as ISO 19139 or ISO 19115-3 XML
+    Iso19115,
+    /// DataCite metadata schema
+    DataCite,
+}
+
+impl fmt::Display for MetadataStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataStandard::Eml => write!(f, "EML"),
+            MetadataStandard::Iso19115 => write!(f, "ISO 19115/19139"),
+            MetadataStandard::DataCite => write!(f, "DataCite"),
+        }
+    }
+}
+
+/// A namespace declared in an XML document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlNamespace {
+    /// Prefix (empty for the default namespace)
+    pub prefix: String,
+    /// Namespace URI
+    pub uri: String,
+}
+
+/// Where and why an XML file is not well-formed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlSyntaxError {
+    /// What was wrong
+    pub message: String,
+    /// Line of the error (1-based)
+    pub line: usize,
+    /// Column of the error, in characters (1-based)
+    pub column: usize,
+}
+
+/// Analysis result for an XML file
+#[derive(Debug, Clone)]
+pub struct XmlAnalysis {
+    /// Whether the document is well-formed (and namespace-well-formed)
+    pub is_well_formed: bool,
+    /// Qualified name of the root element
+    pub root_element: Option<String>,
+    /// Namespace URI of the root element
+    pub root_namespace: Option<String>,
+    /// Namespaces declared anywhere in the document, in order of appearance
+    pub namespaces: Vec<XmlNamespace>,
+    /// Number of elements read
+    pub element_count: usize,
+    /// Deepest element nesting (the root is depth 1)
+    pub max_depth: usize,
+    /// Metadata standard the root namespace belongs to
+    pub standard: Option<MetadataStandard>,
+    /// The first well-formedness error (if any)
+    pub error: Option<XmlSyntaxError>,
+}
+
+/// Format of a sequence file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceFormat {
+    /// FASTA (`.fasta`, `.fa`, `.fna`, `.faa`, ...)
+    Fasta,
+    /// FASTQ (`.fastq`, `.fq`)
+    Fastq,
+}
+
+impl fmt::Display for SequenceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceFormat::Fasta => write!(f, "FASTA"),
+            SequenceFormat::Fastq => write!(f, "FASTQ"),
+        }
+    }
+}
+
+/// Alphabet the residues of a sequence file are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceAlphabet {
+    /// DNA nucleotides (IUPAC codes)
+    Dna,
+    /// RNA nucleotides (U instead of T)
+    Rna,
+    /// Amino acids
+    Protein,
+}
+
+impl fmt::Display for SequenceAlphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceAlphabet::Dna => write!(f, "DNA"),
+            SequenceAlphabet::Rna => write!(f, "RNA"),
+            SequenceAlphabet::Protein => write!(f, "protein"),
+        }
+    }
+}
+
+/// A character that does not belong to a file's alphabet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResidueAnomaly {
+    /// The character
+    pub character: char,
+    /// How often it occurs
+    pub count: u64,
+    /// Line of its first occurrence (1-based)
+    pub first_line: usize,
+}
+
+/// Distribution of sequence or read lengths
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthStats {
+    /// Shortest length
+    pub min: u64,
+    /// Longest length
+    pub max: u64,
+    /// Mean length
+    pub mean: f64,
+    /// Median length
+    pub median: u64,
+    /// N50: the length at which sequences this long or longer hold half the residues
+    pub n50: u64,
+}
+
+/// Analysis result for a FASTA or FASTQ file
+#[derive(Debug, Clone)]
+pub struct SequenceAnalysis {
+    /// Which format the file is in
+    pub format: SequenceFormat,
+    /// Number of sequences (FASTA) or reads (FASTQ)
+    pub record_count: usize,
+    /// Residues over all records
+    pub total_length: u64,
+    /// Distribution of record lengths (None without records)
+    pub lengths: Option<LengthStats>,
+    /// Records without any residues
+    pub empty_records: usize,
+    /// Alphabet most residues belong to
+    pub alphabet: Option<SequenceAlphabet>,
+    /// Characters outside that alphabet, most frequent first
+    pub anomalies: Vec<ResidueAnomaly>,
+    /// Offset of the FASTQ quality encoding (33, or 64 for old Illumina files)
+    pub quality_offset: Option<u8>,
+    /// Mean Phred quality over all FASTQ bases
+    pub mean_quality: Option<f64>,
+    /// Number of malformed records
+    pub invalid_record_count: usize,
+    /// The first malformed records as (line number, problem)
+    pub invalid_records: Vec<(usize, String)>,
+    /// Sizes and integrity of the file, if it is gzip-compressed
+    pub gzip: Option<GzipInfo>,
+}
+
+/// A contig (chromosome or scaffold) of a VCF file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcfContig {
+    /// Contig name (`ID` of `##contig`, or `CHROM` of the records)
+    pub name: String,
+    /// Length declared in `##contig`
+    pub length: Option<u64>,
+    /// Whether a `##contig` line declares it
+    pub declared: bool,
+    /// Number of variant records on it
+    pub variant_count: usize,
+}
+
+/// Analysis result for a VCF file
+#[derive(Debug, Clone)]
+pub struct VcfAnalysis {
+    /// Version from the `##fileformat` line (e.g. `VCFv4.2`)
+    pub file_format: Option<String>,
+    /// Sample names from the `#CHROM` header line
+    pub samples: Vec<String>,
+    /// Number of variant records
+    pub variant_count: usize,
+    /// Declared contigs and those the records use, in order of appearance
+    pub contigs: Vec<VcfContig>,
+    /// IDs of the `##INFO` fields
+    pub info_fields: Vec<String>,
+    /// IDs of the `##FORMAT` fields
+    pub format_fields: Vec<String>,
+    /// Whether records are grouped by contig and sorted by position
+    /// (required for tabix indexing)
+    pub is_sorted: bool,
+    /// Mean of the QUAL column (missing values left out)
+    pub mean_quality: Option<f64>,
+    /// Characters other than nucleotides in REF and ALT alleles
+    pub anomalies: Vec<ResidueAnomaly>,
+    /// Number of malformed lines
+    pub invalid_record_count: usize,
+    /// The first malformed lines as (line number, problem)
+    pub invalid_records: Vec<(usize, String)>,
+    /// Sizes and integrity of the file, if it is gzip-compressed
+    pub gzip: Option<GzipInfo>,
+}
+
 /// Analysis result for a text file
 #[derive(Debug, Clone)]
 pub struct TextAnalysis {
@@ -160,12 +592,26 @@ pub enum BinaryType {
     Hdf5,
     /// NetCDF scientific data format
     Netcdf,
+    /// FITS astronomical data format
+    Fits,
+    /// Apache Parquet columnar format
+    Parquet,
+    /// NumPy `.npy` array
+    Npy,
+    /// ZIP archive (including NumPy `.npz` archives)
+    Zip,
+    /// POSIX (ustar/pax) or GNU tar archive
+    Tar,
+    /// TIFF or BigTIFF image (including GeoTIFF)
+    Tiff,
     /// PNG image
     Png,
     /// JPEG image
     Jpeg,
     /// PDF document
     Pdf,
+    /// SQLite 3 database
+    Sqlite,
     /// Unknown binary type
     Unknown,
 }
@@ -175,15 +621,1007 @@ pub enum BinaryType {
 pub struct BinaryAnalysis {
     /// Detected binary file type
     pub binary_type: BinaryType,
+    /// Image header fields (PNG and JPEG only)
+    pub image: Option<ImageInfo>,
 }
 
 impl BinaryAnalysis {
     /// Create a new BinaryAnalysis
     pub fn new(binary_type: BinaryType) -> Self {
-        BinaryAnalysis { binary_type }
+        BinaryAnalysis {
+            binary_type,
+            image: None,
+        }
+    }
+
+    /// Set the image header fields
+    pub fn with_image(mut self, image: ImageInfo) -> Self {
+        self.image = Some(image);
+        self
+    }
+}
+
+/// Header fields of a PNG or JPEG image
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageInfo {
+    /// Stored width in pixels
+    pub width: u32,
+    /// Stored height in pixels
+    pub height: u32,
+    /// Bits per sample (PNG bit depth, JPEG sample precision)
+    pub bit_depth: u8,
+    /// Colour type (`grayscale`, `RGB`, `RGBA`, `palette`, `YCbCr`, `CMYK`, ...)
+    pub color_type: String,
+    /// Channels per pixel once decoded (a palette decodes to 3)
+    pub channels: u8,
+    /// Frame count (more than 1 for animated PNG)
+    pub frames: u32,
+    /// EXIF orientation (1-8), if the image carries one
+    pub orientation: Option<u16>,
+}
+
+impl ImageInfo {
+    /// Size and channel label used to group images, e.g. `224x224, 3 channels`
+    pub fn shape_label(&self) -> String {
+        format!(
+            "{}x{}, {} channel{}",
+            self.width,
+            self.height,
+            self.channels,
+            if self.channels == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Element type of an HDF5 dataset or attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hdf5Datatype {
+    /// Fixed-point integer of `size` bytes
+    Integer { size: u32, signed: bool },
+    /// IEEE floating point of `size` bytes
+    Float { size: u32 },
+    /// Fixed-length string of `size` bytes
+    String { size: u32 },
+    /// Variable-length string
+    VarString,
+    /// Variable-length sequence
+    VarSequence,
+    /// Compound (struct) type with the given number of members
+    Compound { members: u32 },
+    /// Enumeration
+    Enum,
+    /// Array of a base type
+    Array,
+    /// Object or region reference
+    Reference,
+    /// Opaque bytes
+    Opaque,
+    /// Bit field
+    Bitfield,
+    /// Time (deprecated class)
+    Time,
+    /// Datatype class not understood
+    Unknown,
+}
+
+impl Hdf5Datatype {
+    /// Whether values of this type are numbers (and should carry units)
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Hdf5Datatype::Integer { .. } | Hdf5Datatype::Float { .. })
+    }
+}
+
+impl fmt::Display for Hdf5Datatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hdf5Datatype::Integer { size, signed: true } => write!(f, "int{}", *size as u64 * 8),
+            Hdf5Datatype::Integer { size, signed: false } => write!(f, "uint{}", *size as u64 * 8),
+            Hdf5Datatype::Float { size } => write!(f, "float{}", *size as u64 * 8),
+            Hdf5Datatype::String { size } => write!(f, "string({})", size),
+            Hdf5Datatype::VarString => write!(f, "vlen string"),
+            Hdf5Datatype::VarSequence => write!(f, "vlen sequence"),
+            Hdf5Datatype::Compound { members } => write!(f, "compound({} members)", members),
+            Hdf5Datatype::Enum => write!(f, "enum"),
+            Hdf5Datatype::Array => write!(f, "array"),
+            Hdf5Datatype::Reference => write!(f, "reference"),
+            Hdf5Datatype::Opaque => write!(f, "opaque"),
+            Hdf5Datatype::Bitfield => write!(f, "bitfield"),
+            Hdf5Datatype::Time => write!(f, "time"),
+            Hdf5Datatype::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// An attribute attached to an HDF5 group or dataset
+#[derive(Debug, Clone)]
+pub struct Hdf5Attribute {
+    /// Attribute name
+    pub name: String,
+    /// Attribute element type
+    pub datatype: Hdf5Datatype,
+    /// Rendered value (strings and small numeric arrays; a placeholder otherwise)
+    pub value: String,
+}
+
+/// A group in an HDF5 file
+#[derive(Debug, Clone)]
+pub struct Hdf5Group {
+    /// Absolute path within the file (`/` for the root group)
+    pub path: String,
+    /// Attributes attached to the group
+    pub attributes: Vec<Hdf5Attribute>,
+}
+
+/// A dataset in an HDF5 file
+#[derive(Debug, Clone)]
+pub struct Hdf5Dataset {
+    /// Absolute path within the file
+    pub path: String,
+    /// Current dimensions (empty for scalars)
+    pub shape: Vec<u64>,
+    /// Element type
+    pub datatype: Hdf5Datatype,
+    /// Chunk dimensions, if the dataset is chunked
+    pub chunks: Option<Vec<u64>>,
+    /// Filter pipeline ids in application order (1 = deflate, 2 = shuffle, ...)
+    pub filters: Vec<u16>,
+    /// Attributes attached to the dataset
+    pub attributes: Vec<Hdf5Attribute>,
+}
+
+impl Hdf5Dataset {
+    /// Find an attribute by name (case-insensitive)
+    pub fn attribute(&self, name: &str) -> Option<&Hdf5Attribute> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.eq_ignore_ascii_case(name))
     }
 }
 
+/// Name of a registered HDF5 filter id
+pub fn hdf5_filter_name(id: u16) -> String {
+    match id {
+        1 => "deflate".to_string(),
+        2 => "shuffle".to_string(),
+        3 => "fletcher32".to_string(),
+        4 => "szip".to_string(),
+        5 => "nbit".to_string(),
+        6 => "scaleoffset".to_string(),
+        32001 => "blosc".to_string(),
+        32004 => "lz4".to_string(),
+        32008 => "bitshuffle".to_string(),
+        32015 => "zstd".to_string(),
+        other => format!("filter {}", other),
+    }
+}
+
+/// Structural analysis of an HDF5 file
+#[derive(Debug, Clone)]
+pub struct Hdf5Analysis {
+    /// Superblock version (0-3)
+    pub superblock_version: u8,
+    /// Groups, in traversal order starting with the root group
+    pub groups: Vec<Hdf5Group>,
+    /// Datasets, in traversal order
+    pub datasets: Vec<Hdf5Dataset>,
+    /// Parts of the file that could not be read (the rest is still listed)
+    pub warnings: Vec<String>,
+}
+
+impl Hdf5Analysis {
+    /// Create an empty analysis for the given superblock version
+    pub fn new(superblock_version: u8) -> Self {
+        Hdf5Analysis {
+            superblock_version,
+            groups: Vec::new(),
+            datasets: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// On-disk variant of a netCDF classic file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetcdfFormat {
+    /// CDF-1: classic format (`CDF\x01`)
+    Classic,
+    /// CDF-2: 64-bit offset format (`CDF\x02`)
+    Offset64,
+    /// CDF-5: 64-bit data format (`CDF\x05`)
+    Data64,
+}
+
+impl fmt::Display for NetcdfFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetcdfFormat::Classic => write!(f, "classic"),
+            NetcdfFormat::Offset64 => write!(f, "64-bit offset"),
+            NetcdfFormat::Data64 => write!(f, "64-bit data"),
+        }
+    }
+}
+
+/// External type of a netCDF variable or attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetcdfType {
+    /// 8-bit signed integer
+    Byte,
+    /// 8-bit character (text)
+    Char,
+    /// 16-bit signed integer
+    Short,
+    /// 32-bit signed integer
+    Int,
+    /// 32-bit IEEE float
+    Float,
+    /// 64-bit IEEE float
+    Double,
+    /// 8-bit unsigned integer (CDF-5)
+    UByte,
+    /// 16-bit unsigned integer (CDF-5)
+    UShort,
+    /// 32-bit unsigned integer (CDF-5)
+    UInt,
+    /// 64-bit signed integer (CDF-5)
+    Int64,
+    /// 64-bit unsigned integer (CDF-5)
+    UInt64,
+}
+
+impl NetcdfType {
+    /// Map an `nc_type` code to a type
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(NetcdfType::Byte),
+            2 => Some(NetcdfType::Char),
+            3 => Some(NetcdfType::Short),
+            4 => Some(NetcdfType::Int),
+            5 => Some(NetcdfType::Float),
+            6 => Some(NetcdfType::Double),
+            7 => Some(NetcdfType::UByte),
+            8 => Some(NetcdfType::UShort),
+            9 => Some(NetcdfType::UInt),
+            10 => Some(NetcdfType::Int64),
+            11 => Some(NetcdfType::UInt64),
+            _ => None,
+        }
+    }
+
+    /// Size of one value in bytes
+    pub fn size(&self) -> u64 {
+        match self {
+            NetcdfType::Byte | NetcdfType::Char | NetcdfType::UByte => 1,
+            NetcdfType::Short | NetcdfType::UShort => 2,
+            NetcdfType::Int | NetcdfType::Float | NetcdfType::UInt => 4,
+            NetcdfType::Double | NetcdfType::Int64 | NetcdfType::UInt64 => 8,
+        }
+    }
+
+    /// Whether values of this type are numbers (everything but `char`)
+    pub fn is_numeric(&self) -> bool {
+        *self != NetcdfType::Char
+    }
+}
+
+impl fmt::Display for NetcdfType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NetcdfType::Byte => "byte",
+            NetcdfType::Char => "char",
+            NetcdfType::Short => "short",
+            NetcdfType::Int => "int",
+            NetcdfType::Float => "float",
+            NetcdfType::Double => "double",
+            NetcdfType::UByte => "ubyte",
+            NetcdfType::UShort => "ushort",
+            NetcdfType::UInt => "uint",
+            NetcdfType::Int64 => "int64",
+            NetcdfType::UInt64 => "uint64",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A dimension declared in a netCDF file
+#[derive(Debug, Clone)]
+pub struct NetcdfDimension {
+    /// Dimension name
+    pub name: String,
+    /// Length (the current record count for the unlimited dimension)
+    pub length: u64,
+    /// Whether this is the record (unlimited) dimension
+    pub is_unlimited: bool,
+}
+
+/// A global or variable attribute in a netCDF file
+#[derive(Debug, Clone)]
+pub struct NetcdfAttribute {
+    /// Attribute name
+    pub name: String,
+    /// Attribute type
+    pub nc_type: NetcdfType,
+    /// Number of values (characters for text attributes)
+    pub count: u64,
+    /// Rendered value: the text for `char` attributes, comma-separated numbers otherwise
+    pub value: String,
+    /// Numeric values (empty for `char` attributes; capped for long arrays)
+    pub numbers: Vec<f64>,
+}
+
+/// A variable in a netCDF file
+#[derive(Debug, Clone)]
+pub struct NetcdfVariable {
+    /// Variable name
+    pub name: String,
+    /// Names of the variable's dimensions, outermost first
+    pub dimensions: Vec<String>,
+    /// Lengths of the variable's dimensions
+    pub shape: Vec<u64>,
+    /// Variable type
+    pub nc_type: NetcdfType,
+    /// Attributes attached to the variable
+    pub attributes: Vec<NetcdfAttribute>,
+}
+
+impl NetcdfVariable {
+    /// Find an attribute by name (attribute names are case-sensitive in netCDF)
+    pub fn attribute(&self, name: &str) -> Option<&NetcdfAttribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Whether this is a coordinate variable (1-D, named after its dimension)
+    pub fn is_coordinate(&self) -> bool {
+        self.dimensions.len() == 1 && self.dimensions[0] == self.name
+    }
+}
+
+/// Header analysis of a netCDF classic, 64-bit offset or 64-bit data file
+#[derive(Debug, Clone)]
+pub struct NetcdfAnalysis {
+    /// On-disk format variant
+    pub format: NetcdfFormat,
+    /// Number of records along the unlimited dimension
+    pub record_count: u64,
+    /// Dimensions in declaration order
+    pub dimensions: Vec<NetcdfDimension>,
+    /// Global attributes
+    pub global_attributes: Vec<NetcdfAttribute>,
+    /// Variables in declaration order
+    pub variables: Vec<NetcdfVariable>,
+}
+
+impl NetcdfAnalysis {
+    /// Create an empty analysis for the given format
+    pub fn new(format: NetcdfFormat) -> Self {
+        NetcdfAnalysis {
+            format,
+            record_count: 0,
+            dimensions: Vec::new(),
+            global_attributes: Vec::new(),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Find a global attribute by name
+    pub fn global_attribute(&self, name: &str) -> Option<&NetcdfAttribute> {
+        self.global_attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Find a variable by name
+    pub fn variable(&self, name: &str) -> Option<&NetcdfVariable> {
+        self.variables.iter().find(|v| v.name == name)
+    }
+}
+
+/// Kind of a FITS header-data unit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FitsHduKind {
+    /// The primary HDU (starts with `SIMPLE`)
+    Primary,
+    /// `XTENSION = 'IMAGE'`
+    Image,
+    /// `XTENSION = 'TABLE'` (ASCII table)
+    AsciiTable,
+    /// `XTENSION = 'BINTABLE'`
+    BinaryTable,
+    /// Any other extension type
+    Other(String),
+}
+
+impl FitsHduKind {
+    /// Whether this HDU holds a table with `TFIELDS` columns
+    pub fn is_table(&self) -> bool {
+        matches!(self, FitsHduKind::AsciiTable | FitsHduKind::BinaryTable)
+    }
+}
+
+impl fmt::Display for FitsHduKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitsHduKind::Primary => write!(f, "primary"),
+            FitsHduKind::Image => write!(f, "IMAGE"),
+            FitsHduKind::AsciiTable => write!(f, "TABLE"),
+            FitsHduKind::BinaryTable => write!(f, "BINTABLE"),
+            FitsHduKind::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A column of a FITS ASCII or binary table
+#[derive(Debug, Clone)]
+pub struct FitsColumn {
+    /// Column name (`TTYPEn`), if given
+    pub name: Option<String>,
+    /// Column format (`TFORMn`), e.g. `1E` or `20A`
+    pub format: String,
+    /// Physical unit (`TUNITn`), if given
+    pub unit: Option<String>,
+}
+
+impl FitsColumn {
+    /// Whether the column holds text or logical values, which take no unit
+    pub fn is_unitless(&self) -> bool {
+        let code = self
+            .format
+            .trim()
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .chars()
+            .next();
+        matches!(code, Some('A') | Some('L'))
+    }
+}
+
+/// Size of a FITS header or data block
+pub const FITS_BLOCK_SIZE: u64 = 2880;
+
+/// Round a size up to whole FITS blocks
+pub fn fits_padded_size(size: u64) -> u64 {
+    size.div_ceil(FITS_BLOCK_SIZE).saturating_mul(FITS_BLOCK_SIZE)
+}
+
+/// One header-data unit of a FITS file
+#[derive(Debug, Clone)]
+pub struct FitsHdu {
+    /// Position in the file (0 is the primary HDU)
+    pub index: usize,
+    /// HDU kind
+    pub kind: FitsHduKind,
+    /// Extension name (`EXTNAME`), if given
+    pub name: Option<String>,
+    /// Bits per data value (`BITPIX`; negative for floating point)
+    pub bitpix: i64,
+    /// Axis lengths (`NAXIS1` ... `NAXISn`)
+    pub axes: Vec<u64>,
+    /// Table columns (empty for images)
+    pub columns: Vec<FitsColumn>,
+    /// Mandatory keywords that are missing or have invalid values
+    pub missing_keywords: Vec<String>,
+    /// Byte offset of the header
+    pub header_offset: u64,
+    /// Byte offset of the data (header length included, padded to 2880)
+    pub data_offset: u64,
+    /// Size of the data implied by the header, before padding
+    pub data_size: u64,
+}
+
+impl FitsHdu {
+    /// Byte offset just past the data, before padding
+    pub fn data_end(&self) -> u64 {
+        self.data_offset.saturating_add(self.data_size)
+    }
+
+    /// Byte offset where the next HDU starts
+    pub fn padded_end(&self) -> u64 {
+        self.data_offset.saturating_add(fits_padded_size(self.data_size))
+    }
+}
+
+/// Header analysis of a FITS file
+#[derive(Debug, Clone)]
+pub struct FitsAnalysis {
+    /// Header-data units in file order
+    pub hdus: Vec<FitsHdu>,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Parts of the file that could not be read (earlier HDUs are still listed)
+    pub warnings: Vec<String>,
+}
+
+impl FitsAnalysis {
+    /// Create an empty analysis for a file of the given size
+    pub fn new(file_size: u64) -> Self {
+        FitsAnalysis {
+            hdus: Vec::new(),
+            file_size,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// A leaf column of a Parquet file
+#[derive(Debug, Clone)]
+pub struct ParquetColumn {
+    /// Column path, with nested fields joined by `.`
+    pub name: String,
+    /// Physical storage type (e.g. `INT64`, `BYTE_ARRAY`)
+    pub physical_type: String,
+    /// Logical type annotation (e.g. `STRING`, `TIMESTAMP(MICROS,UTC)`), if any
+    pub logical_type: Option<String>,
+    /// Whether values may be null (the column or an ancestor is optional)
+    pub nullable: bool,
+    /// Whether the column or an ancestor is repeated
+    pub repeated: bool,
+    /// Compression codecs used by the column's chunks
+    pub codecs: Vec<String>,
+    /// Null count summed over row groups (if every row group has statistics)
+    pub null_count: Option<u64>,
+    /// Smallest value from the statistics, rendered as text
+    pub min_value: Option<String>,
+    /// Largest value from the statistics, rendered as text
+    pub max_value: Option<String>,
+    /// Compressed size of the column's chunks in bytes
+    pub compressed_size: u64,
+    /// Uncompressed size of the column's chunks in bytes
+    pub uncompressed_size: u64,
+}
+
+/// A row group of a Parquet file
+#[derive(Debug, Clone)]
+pub struct ParquetRowGroup {
+    /// Number of rows
+    pub row_count: u64,
+    /// Uncompressed size of all column data in bytes
+    pub total_byte_size: u64,
+}
+
+/// Footer analysis of a Parquet file
+#[derive(Debug, Clone)]
+pub struct ParquetAnalysis {
+    /// Format version from the footer
+    pub version: i64,
+    /// Writer that produced the file (e.g. `parquet-cpp-arrow version 15.0.0`)
+    pub created_by: Option<String>,
+    /// Total number of rows
+    pub row_count: u64,
+    /// Row groups in file order
+    pub row_groups: Vec<ParquetRowGroup>,
+    /// Leaf columns in schema order
+    pub columns: Vec<ParquetColumn>,
+    /// The same columns as a tabular analysis, so schema generation, data
+    /// dictionaries and Croissant record sets treat Parquet like CSV
+    pub table: CsvAnalysis,
+}
+
+/// A column declared in a SQLite `CREATE TABLE` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteColumn {
+    /// Column name
+    pub name: String,
+    /// Declared type as written (empty if none: SQLite columns need no type)
+    pub declared_type: String,
+    /// Part of the primary key
+    pub primary_key: bool,
+    /// Declared NOT NULL
+    pub not_null: bool,
+}
+
+/// A table of a SQLite database
+#[derive(Debug, Clone)]
+pub struct SqliteTable {
+    /// Table name
+    pub name: String,
+    /// Columns in declaration order
+    pub columns: Vec<SqliteColumn>,
+    /// Number of rows (every leaf of the table's b-tree is counted)
+    pub row_count: u64,
+    /// Stored as a clustered index (`WITHOUT ROWID`)
+    pub without_rowid: bool,
+    /// Virtual table (FTS, R*Tree, ...), whose rows live elsewhere or nowhere
+    pub is_virtual: bool,
+    /// The rows profiled as a tabular analysis, so schema generation, data
+    /// dictionaries and Croissant record sets treat the table like CSV
+    pub table: CsvAnalysis,
+}
+
+/// An index of a SQLite database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteIndex {
+    /// Index name
+    pub name: String,
+    /// Table the index belongs to
+    pub table: String,
+    /// Indexed columns (or expressions) in key order; empty for automatic
+    /// indexes, whose columns are declared by the table's constraints
+    pub columns: Vec<String>,
+    /// Whether the index enforces uniqueness
+    pub unique: bool,
+    /// Created by SQLite for a PRIMARY KEY or UNIQUE constraint
+    pub automatic: bool,
+}
+
+/// Analysis result for a SQLite database
+#[derive(Debug, Clone)]
+pub struct SqliteAnalysis {
+    /// Page size in bytes
+    pub page_size: u32,
+    /// Number of pages
+    pub page_count: u32,
+    /// Text encoding (`UTF-8`, `UTF-16le` or `UTF-16be`)
+    pub text_encoding: String,
+    /// `PRAGMA user_version`, often used by applications as a schema version
+    pub user_version: u32,
+    /// Whether the database is in write-ahead-log mode
+    pub wal_mode: bool,
+    /// User tables (SQLite's internal `sqlite_*` tables are left out)
+    pub tables: Vec<SqliteTable>,
+    /// Indexes, including those SQLite creates for constraints
+    pub indexes: Vec<SqliteIndex>,
+    /// View names
+    pub views: Vec<String>,
+    /// The first damaged structure found, if any (the tables read before it
+    /// are still reported)
+    pub error: Option<String>,
+}
+
+/// Header of a NumPy `.npy` array
+#[derive(Debug, Clone)]
+pub struct NpyHeader {
+    /// Format version (major, minor)
+    pub version: (u8, u8),
+    /// dtype descriptor as written (e.g. `<f8`, or a structured field list)
+    pub descr: String,
+    /// Whether the data is in column-major order
+    pub fortran_order: bool,
+    /// Array shape (empty for a scalar)
+    pub shape: Vec<u64>,
+    /// Bytes per element, if the dtype has a fixed size (object arrays do not)
+    pub item_size: Option<u64>,
+    /// Bytes before the data: magic, version, length field and header dict
+    pub header_size: u64,
+}
+
+impl NpyHeader {
+    /// Number of elements (1 for a scalar)
+    pub fn element_count(&self) -> u64 {
+        self.shape.iter().fold(1u64, |count, &n| count.saturating_mul(n))
+    }
+
+    /// Size of the array data in bytes, if the dtype has a fixed size
+    pub fn data_size(&self) -> Option<u64> {
+        self.item_size.map(|size| size.saturating_mul(self.element_count()))
+    }
+
+    /// Size the `.npy` bytes should have: header plus data
+    pub fn expected_size(&self) -> Option<u64> {
+        self.data_size().map(|size| size.saturating_add(self.header_size))
+    }
+}
+
+/// One array of a `.npy` file or `.npz` archive
+#[derive(Debug, Clone)]
+pub struct NumpyArray {
+    /// Array name (the `.npz` member name without `.npy`, or the file name)
+    pub name: String,
+    /// Parsed header, if it could be read
+    pub header: Option<NpyHeader>,
+    /// Size of the `.npy` bytes (the file size, or the member's uncompressed size)
+    pub stored_size: u64,
+    /// ZIP compression method of an `.npz` member (`stored`, `deflate`, ...)
+    pub compression: Option<String>,
+}
+
+/// Header analysis of a NumPy `.npy` file or `.npz` archive
+#[derive(Debug, Clone)]
+pub struct NumpyAnalysis {
+    /// Whether the file is an `.npz` archive
+    pub archive: bool,
+    /// Arrays in file (or archive directory) order
+    pub arrays: Vec<NumpyArray>,
+    /// Members or headers that could not be read
+    pub warnings: Vec<String>,
+}
+
+/// Container format of an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// ZIP archive (central directory at the end)
+    Zip,
+    /// POSIX (ustar/pax) or GNU tar archive
+    Tar,
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveFormat::Zip => write!(f, "ZIP"),
+            ArchiveFormat::Tar => write!(f, "tar"),
+        }
+    }
+}
+
+/// What an archive entry is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveEntryKind {
+    /// Regular file
+    File,
+    /// Directory
+    Directory,
+    /// Symbolic or hard link
+    Link,
+    /// Anything else (device, FIFO, ...), with a description
+    Other(String),
+}
+
+/// How the data of an archive entry is stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveMethod {
+    /// Uncompressed (ZIP method 0, and every tar entry)
+    Stored,
+    /// DEFLATE (ZIP method 8)
+    Deflate,
+    /// A method that cannot be decoded here (bzip2, LZMA, zstd, sparse tar data, ...)
+    Unsupported(String),
+}
+
+impl fmt::Display for ArchiveMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveMethod::Stored => write!(f, "stored"),
+            ArchiveMethod::Deflate => write!(f, "deflate"),
+            ArchiveMethod::Unsupported(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// One entry of a ZIP or tar archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// Path inside the archive, as stored
+    pub name: String,
+    /// File, directory, link or other
+    pub kind: ArchiveEntryKind,
+    /// How the data is stored
+    pub method: ArchiveMethod,
+    /// Whether the data is encrypted (ZIP only)
+    pub encrypted: bool,
+    /// Link target (tar symbolic and hard links)
+    pub link_target: Option<String>,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Stored size in bytes
+    pub compressed_size: u64,
+    /// ZIP: offset of the local header; tar: offset of the data
+    pub offset: u64,
+}
+
+impl ArchiveEntry {
+    /// Whether the entry is a regular file whose data can be decoded
+    pub fn is_readable(&self) -> bool {
+        self.kind == ArchiveEntryKind::File
+            && !self.encrypted
+            && !matches!(self.method, ArchiveMethod::Unsupported(_))
+    }
+
+    /// Whether extracting the entry would write outside the extraction directory
+    ///
+    /// True for absolute names (`/etc/passwd`, `C:\x`), names whose `..`
+    /// components climb above the root, and links pointing there.
+    pub fn escapes_root(&self) -> bool {
+        if path_escapes(&self.name, "") {
+            return true;
+        }
+        match &self.link_target {
+            // Symbolic link targets are relative to the link's directory
+            Some(target) => {
+                let name = self.name.replace('\\', "/");
+                let parent = name.trim_end_matches('/').rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+                path_escapes(target, parent)
+            }
+            None => false,
+        }
+    }
+}
+
+/// Whether `path`, resolved against the relative directory `base`, leaves the root
+fn path_escapes(path: &str, base: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let bytes = path.as_bytes();
+    if path.starts_with('/') || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':') {
+        return true;
+    }
+    let mut depth: i64 = 0;
+    for component in base.split('/').chain(path.split('/')) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                depth -= 1;
+                if depth < 0 {
+                    return true;
+                }
+            }
+            _ => depth += 1,
+        }
+    }
+    false
+}
+
+/// Entries of a ZIP or tar archive
+#[derive(Debug, Clone)]
+pub struct ArchiveAnalysis {
+    /// Container format
+    pub format: ArchiveFormat,
+    /// Every entry, in archive order
+    pub entries: Vec<ArchiveEntry>,
+    /// Problems that stopped or limited the listing
+    pub warnings: Vec<String>,
+}
+
+/// Structure and metadata of a PDF document
+#[derive(Debug, Clone)]
+pub struct PdfAnalysis {
+    /// Version from the `%PDF-` header (e.g. `1.7`)
+    pub version: String,
+    /// Page count from the page tree root, if it could be read
+    pub page_count: Option<u64>,
+    /// Whether the trailer has an `/Encrypt` dictionary
+    pub encrypted: bool,
+    /// `/Title` from the Info dictionary (not read when encrypted)
+    pub title: Option<String>,
+    /// `/Author` from the Info dictionary (not read when encrypted)
+    pub author: Option<String>,
+    /// `/CreationDate` in ISO 8601 form
+    pub creation_date: Option<String>,
+    /// Whether any page uses fonts; None if the pages could not be read
+    pub has_fonts: Option<bool>,
+    /// Parts of the file that could not be read
+    pub warnings: Vec<String>,
+}
+
+impl PdfAnalysis {
+    /// Create an empty analysis for a file with the given version
+    pub fn new(version: String) -> Self {
+        PdfAnalysis {
+            version,
+            page_count: None,
+            encrypted: false,
+            title: None,
+            author: None,
+            creation_date: None,
+            has_fonts: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// One image (IFD) of a TIFF file
+#[derive(Debug, Clone)]
+pub struct TiffPage {
+    /// Image width in pixels
+    pub width: u64,
+    /// Image height in pixels
+    pub height: u64,
+    /// Bits per sample, one value per sample (channel)
+    pub bits_per_sample: Vec<u16>,
+    /// Samples (channels) per pixel
+    pub samples_per_pixel: u16,
+    /// Sample format (`unsigned integer`, `signed integer`, `float`, ...)
+    pub sample_format: String,
+    /// Compression scheme (`none`, `LZW`, `Deflate`, `JPEG`, ...)
+    pub compression: String,
+    /// Photometric interpretation (`RGB`, `BlackIsZero`, ...), if given
+    pub photometric: Option<String>,
+    /// Whether the image is stored in tiles rather than strips
+    pub tiled: bool,
+}
+
+/// A key from a GeoTIFF GeoKey directory
+#[derive(Debug, Clone)]
+pub struct GeoKey {
+    /// Key ID (e.g. 3072 for ProjectedCSTypeGeoKey)
+    pub id: u16,
+    /// Key name, or `GeoKey <id>` for keys not in the specification
+    pub name: String,
+    /// Value rendered as text
+    pub value: String,
+}
+
+/// GeoTIFF georeferencing of the first image
+#[derive(Debug, Clone, Default)]
+pub struct GeoTiffInfo {
+    /// Keys from the GeoKeyDirectoryTag
+    pub keys: Vec<GeoKey>,
+    /// ModelTiepointTag values, six per tiepoint (I, J, K, X, Y, Z)
+    pub tiepoints: Vec<f64>,
+    /// ModelPixelScaleTag values (ScaleX, ScaleY, ScaleZ)
+    pub pixel_scale: Vec<f64>,
+    /// Whether a ModelTransformationTag is present
+    pub has_transformation: bool,
+}
+
+impl GeoTiffInfo {
+    /// Coordinate reference system from the projected or geographic CRS key
+    ///
+    /// Returns `EPSG:<code>`, `user-defined`, or None when neither key is
+    /// set (or set to 0, "undefined").
+    pub fn crs(&self) -> Option<String> {
+        [3072, 2048].iter().find_map(|id| {
+            let key = self.keys.iter().find(|k| k.id == *id)?;
+            match key.value.as_str() {
+                "0" => None,
+                "32767" => Some("user-defined".to_string()),
+                code => Some(format!("EPSG:{}", code)),
+            }
+        })
+    }
+
+    /// Whether raster positions are tied to model coordinates
+    pub fn is_georeferenced(&self) -> bool {
+        self.has_transformation || !self.tiepoints.is_empty()
+    }
+}
+
+/// Tag analysis of a TIFF or BigTIFF file
+#[derive(Debug, Clone)]
+pub struct TiffAnalysis {
+    /// Whether the file uses the 64-bit BigTIFF layout
+    pub big_tiff: bool,
+    /// Images (IFDs) in file order
+    pub pages: Vec<TiffPage>,
+    /// GeoTIFF tags of the first image, if any are present
+    pub geo: Option<GeoTiffInfo>,
+    /// Parts of the file that could not be read (earlier pages are still listed)
+    pub warnings: Vec<String>,
+}
+
+/// An array in a Zarr store
+#[derive(Debug, Clone)]
+pub struct ZarrArray {
+    /// Path of the array within the store (empty for a root array)
+    pub path: String,
+    /// Array shape
+    pub shape: Vec<u64>,
+    /// Chunk shape (the shard shape when sharding is used)
+    pub chunks: Vec<u64>,
+    /// Data type (e.g. `<f8` for v2, `float64` for v3)
+    pub dtype: String,
+    /// Compressor and filters, or the v3 codec chain (e.g. `blosc(zstd)`)
+    pub compressor: Option<String>,
+    /// Fill value as JSON text
+    pub fill_value: Option<String>,
+    /// User attributes as (name, JSON text)
+    pub attributes: Vec<(String, String)>,
+    /// Number of chunk files the shape and chunk shape call for
+    pub expected_chunks: u64,
+    /// Keys of expected chunk files that are absent (at most a sample)
+    pub missing_chunks: Vec<String>,
+    /// Number of expected chunk files that are absent
+    pub missing_chunk_count: u64,
+}
+
+/// A group in a Zarr store
+#[derive(Debug, Clone)]
+pub struct ZarrGroup {
+    /// Path of the group within the store (empty for the root group)
+    pub path: String,
+    /// User attributes as (name, JSON text)
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Metadata analysis of a Zarr store
+#[derive(Debug, Clone)]
+pub struct ZarrAnalysis {
+    /// Zarr format version (2 or 3)
+    pub zarr_format: u8,
+    /// Arrays, sorted by path
+    pub arrays: Vec<ZarrArray>,
+    /// Groups, sorted by path
+    pub groups: Vec<ZarrGroup>,
+    /// Number of files in the store
+    pub file_count: usize,
+    /// Metadata that could not be read
+    pub warnings: Vec<String>,
+}
+
 /// Result of analyzing a file
 #[derive(Debug, Clone)]
 pub enum AnalysisResult {
@@ -191,14 +1629,85 @@ pub enum AnalysisResult {
     Csv(CsvAnalysis),
     /// JSON analysis result
     Json(JsonAnalysis),
+    /// JSON Lines records (record schema, invalid lines)
+    JsonLines(JsonLinesAnalysis),
     /// Text analysis result
     Text(TextAnalysis),
     /// Binary analysis result
     Binary(BinaryAnalysis),
+    /// HDF5 structure (groups, datasets, attributes)
+    Hdf5(Hdf5Analysis),
+    /// NetCDF classic header (dimensions, variables, attributes)
+    Netcdf(NetcdfAnalysis),
+    /// FITS headers (HDUs, image axes, table columns)
+    Fits(FitsAnalysis),
+    /// Parquet footer (schema, row groups, statistics)
+    Parquet(ParquetAnalysis),
+    /// SQLite database (schema, row counts, profiled tables)
+    Sqlite(SqliteAnalysis),
+    /// NumPy array headers (`.npy`, or each member of an `.npz`)
+    Numpy(NumpyAnalysis),
+    /// Zarr store metadata (arrays, groups, chunk presence)
+    Zarr(ZarrAnalysis),
+    /// TIFF tags (pages, sample layout, GeoTIFF georeferencing)
+    Tiff(TiffAnalysis),
+    /// PDF structure and Info dictionary
+    Pdf(PdfAnalysis),
+    /// ZIP or tar archive listing
+    Archive(ArchiveAnalysis),
+    /// YAML or TOML configuration/metadata file
+    Config(ConfigAnalysis),
+    /// XML document
+    Xml(XmlAnalysis),
+    /// FASTA or FASTQ sequence file
+    Sequence(SequenceAnalysis),
+    /// VCF variant file
+    Vcf(VcfAnalysis),
     /// File was not analyzed
     NotAnalyzed,
 }
 
+impl AnalysisResult {
+    /// Column-level analysis of a tabular file (CSV/TSV, JSON Lines or Parquet)
+    pub fn table(&self) -> Option<&CsvAnalysis> {
+        match self {
+            AnalysisResult::Csv(csv) => Some(csv),
+            AnalysisResult::JsonLines(jsonl) => Some(&jsonl.table),
+            AnalysisResult::Parquet(parquet) => Some(&parquet.table),
+            _ => None,
+        }
+    }
+
+    /// Every table of a file, with its name if the file holds several
+    ///
+    /// A SQLite database gives one entry per (non-virtual) table; the
+    /// formats of `table` give a single unnamed entry.
+    pub fn tables(&self) -> Vec<(Option<&str>, &CsvAnalysis)> {
+        match self {
+            AnalysisResult::Sqlite(sqlite) => sqlite
+                .tables
+                .iter()
+                .filter(|table| !table.is_virtual)
+                .map(|table| (Some(table.name.as_str()), &table.table))
+                .collect(),
+            _ => self.table().map(|table| (None, table)).into_iter().collect(),
+        }
+    }
+
+    /// Sizes and integrity of a gzip-compressed CSV/TSV, JSON, JSON Lines,
+    /// FASTA/FASTQ or VCF file
+    pub fn gzip(&self) -> Option<&GzipInfo> {
+        match self {
+            AnalysisResult::Csv(csv) => csv.gzip.as_ref(),
+            AnalysisResult::Json(json) => json.gzip.as_ref(),
+            AnalysisResult::JsonLines(jsonl) => jsonl.table.gzip.as_ref(),
+            AnalysisResult::Sequence(sequence) => sequence.gzip.as_ref(),
+            AnalysisResult::Vcf(vcf) => vcf.gzip.as_ref(),
+            _ => None,
+        }
+    }
+}
+
 /// Summary of an entire dataset
 #[derive(Debug, Clone)]
 pub struct DatasetSummary {
@@ -208,6 +1717,8 @@ pub struct DatasetSummary {
     pub total_size: u64,
     /// Count of each file type
     pub file_type_counts: Vec<(FileType, usize)>,
+    /// Count of PNG/JPEG images per size and channel count, most common first
+    pub image_sizes: Vec<(String, usize)>,
     /// Timestamp when scan was performed
     pub scan_timestamp: String,
 }
@@ -219,6 +1730,7 @@ impl DatasetSummary {
             total_files: 0,
             total_size: 0,
             file_type_counts: Vec::new(),
+            image_sizes: Vec::new(),
             scan_timestamp: String::new(),
         }
     }
@@ -263,6 +1775,18 @@ mod tests {
         assert_eq!(col.sample_values.len(), 1);
     }
 
+    #[test]
+    fn test_column_info_statistics() {
+        let col = ColumnInfo::new(1)
+            .with_unique_count(1000, true)
+            .with_range(Some(-2.5), Some(31.0));
+
+        assert_eq!(col.unique_count, 1000);
+        assert!(col.unique_count_capped);
+        assert_eq!(col.min_value, Some(-2.5));
+        assert_eq!(col.max_value, Some(31.0));
+    }
+
     #[test]
     fn test_csv_analysis_creation() {
         let analysis = CsvAnalysis::new(',', true);
@@ -9,6 +9,10 @@ pub enum Command {
     Generate,
     /// Generate machine-readable JSON report
     Report,
+    /// Package the dataset as a BagIt bag
+    Bag,
+    /// Validate an existing BagIt bag
+    ValidateBag,
 }
 
 /// Runtime configuration for the tool
@@ -28,6 +32,14 @@ pub struct Config {
     pub skip_hash: bool,
     /// Generate JSON output (for report command)
     pub json_output: bool,
+    /// Write the data dictionary as CSV instead of Markdown
+    pub dictionary_csv: bool,
+    /// Merge-update existing generated documentation (generate command)
+    pub update: bool,
+    /// Plan generation without writing any files (generate command)
+    pub dry_run: bool,
+    /// List the members of ZIP and tar archives as files of their own
+    pub scan_archives: bool,
 }
 
 impl Config {
@@ -41,6 +53,10 @@ impl Config {
             quiet: false,
             skip_hash: false,
             json_output: false,
+            dictionary_csv: false,
+            update: false,
+            dry_run: false,
+            scan_archives: false,
         }
     }
 
@@ -74,6 +90,30 @@ impl Config {
         self
     }
 
+    /// Write the data dictionary as CSV
+    pub fn with_dictionary_csv(mut self, dictionary_csv: bool) -> Self {
+        self.dictionary_csv = dictionary_csv;
+        self
+    }
+
+    /// Merge-update existing generated documentation
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Plan generation without writing any files
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// List the members of ZIP and tar archives
+    pub fn with_scan_archives(mut self, scan_archives: bool) -> Self {
+        self.scan_archives = scan_archives;
+        self
+    }
+
     /// Get the effective output directory (defaults to target_path if not set)
     pub fn get_output_dir(&self) -> &PathBuf {
         self.output_dir.as_ref().unwrap_or(&self.target_path)
@@ -90,6 +130,10 @@ impl Default for Config {
             quiet: false,
             skip_hash: false,
             json_output: false,
+            dictionary_csv: false,
+            update: false,
+            dry_run: false,
+            scan_archives: false,
         }
     }
 }
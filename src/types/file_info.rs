@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::time::SystemTime;
 
-use super::FileType;
+use super::{ArchiveEntry, ArchiveFormat, FileType};
 
 /// Information about a file in the dataset
 #[derive(Debug, Clone)]
@@ -20,6 +20,20 @@ pub struct FileInfo {
     pub sha256_hash: Option<String>,
     /// Whether this is a hidden file
     pub is_hidden: bool,
+    /// The archive and entry this file is read from, for archive members
+    /// (`data.zip!/raw/obs.csv`) listed by `--scan-archives`
+    pub archive_member: Option<ArchiveMember>,
+}
+
+/// Location of a file inside a ZIP or tar archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMember {
+    /// Absolute path to the archive
+    pub archive: PathBuf,
+    /// Container format of the archive
+    pub format: ArchiveFormat,
+    /// The member's entry in the archive
+    pub entry: ArchiveEntry,
 }
 
 impl FileInfo {
@@ -40,6 +54,7 @@ impl FileInfo {
             file_type,
             sha256_hash: None,
             is_hidden,
+            archive_member: None,
         }
     }
 
@@ -72,6 +87,17 @@ impl FileInfo {
         self
     }
 
+    /// Mark the file as a member of an archive
+    pub fn with_archive_member(mut self, member: ArchiveMember) -> Self {
+        self.archive_member = Some(member);
+        self
+    }
+
+    /// Whether this is a virtual entry for an archive member rather than a file on disk
+    pub fn is_archive_member(&self) -> bool {
+        self.archive_member.is_some()
+    }
+
     /// Get the file name
     pub fn file_name(&self) -> Option<&str> {
         self.relative_path.file_name()?.to_str()
@@ -85,6 +111,7 @@ impl FileInfo {
     /// Check if this file is likely a documentation file
     pub fn is_documentation(&self) -> bool {
         matches!(self.file_type, FileType::Markdown | FileType::Text)
+            || self.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
             || self
                 .file_name()
                 .map(|n| {
@@ -99,7 +126,15 @@ impl FileInfo {
     pub fn is_data(&self) -> bool {
         matches!(
             self.file_type,
-            FileType::Csv | FileType::Tsv | FileType::Json | FileType::Binary
+            FileType::Csv
+                | FileType::Tsv
+                | FileType::Json
+                | FileType::JsonLines
+                | FileType::Fasta
+                | FileType::Fastq
+                | FileType::Vcf
+                | FileType::Binary
+                | FileType::Zarr
         )
     }
 }
@@ -140,6 +175,12 @@ mod tests {
             PathBuf::from("data.csv"),
         );
         assert!(!csv.is_documentation());
+
+        let manual = FileInfo::new(
+            PathBuf::from("/data/docs/Manual.PDF"),
+            PathBuf::from("docs/Manual.PDF"),
+        );
+        assert!(manual.is_documentation());
     }
 
     #[test]
@@ -150,6 +191,12 @@ mod tests {
         );
         assert!(csv.is_data());
 
+        let calls = FileInfo::new(
+            PathBuf::from("/data/calls.vcf.gz"),
+            PathBuf::from("calls.vcf.gz"),
+        );
+        assert!(calls.is_data());
+
         let readme = FileInfo::new(
             PathBuf::from("/data/README.md"),
             PathBuf::from("README.md"),
@@ -10,12 +10,28 @@ pub enum FileType {
     Tsv,
     /// JSON data file
     Json,
+    /// JSON Lines / NDJSON file (one JSON record per line)
+    JsonLines,
     /// Plain text file
     Text,
     /// Markdown documentation file
     Markdown,
+    /// YAML configuration or metadata file (including CITATION.cff)
+    Yaml,
+    /// TOML configuration file
+    Toml,
+    /// XML document (EML, ISO 19139, DataCite, instrument metadata)
+    Xml,
+    /// FASTA nucleotide or protein sequences
+    Fasta,
+    /// FASTQ sequencing reads with qualities
+    Fastq,
+    /// VCF (Variant Call Format) variants
+    Vcf,
     /// Binary file (not text-based)
     Binary,
+    /// Zarr store (a directory of metadata and chunk files, treated as one entry)
+    Zarr,
     /// Unknown or unsupported file type
     Unknown,
 }
@@ -27,9 +43,16 @@ impl FileType {
             "csv" => FileType::Csv,
             "tsv" => FileType::Tsv,
             "json" => FileType::Json,
+            "jsonl" | "ndjson" => FileType::JsonLines,
             "txt" => FileType::Text,
             "md" | "markdown" => FileType::Markdown,
-            "bin" | "dat" | "hdf5" | "h5" | "nc" | "netcdf" | "png" | "jpg" | "jpeg" | "pdf" => {
+            "yaml" | "yml" | "cff" => FileType::Yaml,
+            "toml" => FileType::Toml,
+            "xml" => FileType::Xml,
+            "fasta" | "fa" | "fas" | "fna" | "ffn" | "faa" | "frn" => FileType::Fasta,
+            "fastq" | "fq" => FileType::Fastq,
+            "vcf" => FileType::Vcf,
+            "bin" | "dat" | "hdf5" | "h5" | "nc" | "nc4" | "netcdf" | "fits" | "fit" | "fts" | "parquet" | "pq" | "npy" | "npz" | "tif" | "tiff" | "png" | "jpg" | "jpeg" | "pdf" | "zip" | "tar" | "sqlite" | "sqlite3" | "db" | "db3" => {
                 FileType::Binary
             }
             _ => FileType::Unknown,
@@ -37,11 +60,27 @@ impl FileType {
     }
 
     /// Infer file type from path
+    ///
+    /// Gzipped CSV, TSV, JSON, JSON Lines, FASTA, FASTQ and VCF files
+    /// (`data.csv.gz`, `reads.fq.gz`) take the type of their contents,
+    /// which the analyzers read through the decompressor.
     pub fn from_path(path: &Path) -> Self {
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(Self::from_extension)
-            .unwrap_or(FileType::Unknown)
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if extension.is_some_and(|ext| ext.eq_ignore_ascii_case("gz")) {
+            return match path.file_stem().map(|stem| Self::from_path(Path::new(stem))) {
+                Some(
+                    inner @ (FileType::Csv
+                    | FileType::Tsv
+                    | FileType::Json
+                    | FileType::JsonLines
+                    | FileType::Fasta
+                    | FileType::Fastq
+                    | FileType::Vcf),
+                ) => inner,
+                _ => FileType::Unknown,
+            };
+        }
+        extension.map(Self::from_extension).unwrap_or(FileType::Unknown)
     }
 }
 
@@ -51,9 +90,17 @@ impl fmt::Display for FileType {
             FileType::Csv => write!(f, "CSV"),
             FileType::Tsv => write!(f, "TSV"),
             FileType::Json => write!(f, "JSON"),
+            FileType::JsonLines => write!(f, "JSON Lines"),
             FileType::Text => write!(f, "Text"),
             FileType::Markdown => write!(f, "Markdown"),
+            FileType::Yaml => write!(f, "YAML"),
+            FileType::Toml => write!(f, "TOML"),
+            FileType::Xml => write!(f, "XML"),
+            FileType::Fasta => write!(f, "FASTA"),
+            FileType::Fastq => write!(f, "FASTQ"),
+            FileType::Vcf => write!(f, "VCF"),
             FileType::Binary => write!(f, "Binary"),
+            FileType::Zarr => write!(f, "Zarr"),
             FileType::Unknown => write!(f, "Unknown"),
         }
     }
@@ -75,12 +122,39 @@ mod tests {
         assert_eq!(FileType::from_extension("CSV"), FileType::Csv);
         assert_eq!(FileType::from_extension("tsv"), FileType::Tsv);
         assert_eq!(FileType::from_extension("json"), FileType::Json);
+        assert_eq!(FileType::from_extension("jsonl"), FileType::JsonLines);
+        assert_eq!(FileType::from_extension("NDJSON"), FileType::JsonLines);
         assert_eq!(FileType::from_extension("md"), FileType::Markdown);
         assert_eq!(FileType::from_extension("txt"), FileType::Text);
+        assert_eq!(FileType::from_extension("yml"), FileType::Yaml);
+        assert_eq!(FileType::from_extension("cff"), FileType::Yaml);
+        assert_eq!(FileType::from_extension("fa"), FileType::Fasta);
+        assert_eq!(FileType::from_extension("FASTQ"), FileType::Fastq);
+        assert_eq!(FileType::from_extension("vcf"), FileType::Vcf);
+        assert_eq!(FileType::from_extension("TOML"), FileType::Toml);
+        assert_eq!(FileType::from_extension("xml"), FileType::Xml);
         assert_eq!(FileType::from_extension("hdf5"), FileType::Binary);
+        assert_eq!(FileType::from_extension("fits"), FileType::Binary);
+        assert_eq!(FileType::from_extension("parquet"), FileType::Binary);
+        assert_eq!(FileType::from_extension("npz"), FileType::Binary);
+        assert_eq!(FileType::from_extension("tiff"), FileType::Binary);
+        assert_eq!(FileType::from_extension("zip"), FileType::Binary);
+        assert_eq!(FileType::from_extension("sqlite"), FileType::Binary);
         assert_eq!(FileType::from_extension("xyz"), FileType::Unknown);
     }
 
+    #[test]
+    fn test_from_path_gzip() {
+        assert_eq!(FileType::from_path(Path::new("data/obs.csv.gz")), FileType::Csv);
+        assert_eq!(FileType::from_path(Path::new("obs.TSV.GZ")), FileType::Tsv);
+        assert_eq!(FileType::from_path(Path::new("records.json.gz")), FileType::Json);
+        assert_eq!(FileType::from_path(Path::new("events.jsonl.gz")), FileType::JsonLines);
+        assert_eq!(FileType::from_path(Path::new("reads_R1.fq.gz")), FileType::Fastq);
+        assert_eq!(FileType::from_path(Path::new("calls.vcf.gz")), FileType::Vcf);
+        assert_eq!(FileType::from_path(Path::new("notes.txt.gz")), FileType::Unknown);
+        assert_eq!(FileType::from_path(Path::new("archive.gz")), FileType::Unknown);
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(format!("{}", FileType::Csv), "CSV");
@@ -210,6 +210,7 @@ mod tests {
         let warning = ValidationResult::warning("W1", "Warning", "Fix");
         let info = ValidationResult::info("I1", "Info", "Fix");
 
+        #[allow(clippy::useless_vec)]
         let mut results = vec![info.clone(), warning.clone(), critical.clone()];
         results.sort();
 
@@ -0,0 +1,225 @@
+//! ZIP and tar archive checks
+//!
+//! Flags archive entries that are unsafe or unusable once a downloader
+//! extracts them: paths that climb out of the extraction directory (the
+//! "zip slip" attack), names that appear twice so one copy silently
+//! overwrites the other, and members stored in a way that cannot be read
+//! here, which therefore go unhashed and unanalyzed.
+
+use crate::types::{AnalysisResult, ArchiveAnalysis, ArchiveEntry, ArchiveMethod, FileInfo, ValidationResult};
+use std::collections::HashMap;
+
+/// Most entry names listed in one message
+const MAX_LISTED: usize = 5;
+
+/// Validate the entries of ZIP and tar archives
+pub fn validate_archives(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Archive(archive) = analysis {
+            results.extend(check_archive(file, archive));
+        }
+    }
+
+    results
+}
+
+/// Check one archive
+fn check_archive(file: &FileInfo, archive: &ArchiveAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    let escaping: Vec<&ArchiveEntry> = archive.entries.iter().filter(|e| e.escapes_root()).collect();
+    if !escaping.is_empty() {
+        results.push(
+            ValidationResult::critical(
+                "ARCHIVE-001",
+                format!(
+                    "{} has {} entr{} that would be extracted outside the target directory: {}",
+                    file_name,
+                    escaping.len(),
+                    if escaping.len() == 1 { "y" } else { "ies" },
+                    list_entries(escaping.iter().map(|e| match &e.link_target {
+                        Some(target) => format!("{} -> {}", e.name, target),
+                        None => e.name.clone(),
+                    }))
+                ),
+                "Rebuild the archive from a directory with relative paths only; unpacking it as is can overwrite files anywhere on the user's system",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut order = Vec::new();
+    for entry in &archive.entries {
+        let name = normalize(&entry.name);
+        if name.is_empty() {
+            continue;
+        }
+        let count = counts.entry(name.clone()).or_insert(0);
+        if *count == 1 {
+            order.push(name);
+        }
+        *count += 1;
+    }
+    if !order.is_empty() {
+        results.push(
+            ValidationResult::warning(
+                "ARCHIVE-002",
+                format!(
+                    "{} contains {} name{} more than once: {}",
+                    file_name,
+                    order.len(),
+                    if order.len() == 1 { "" } else { "s" },
+                    list_entries(order.iter().map(|name| format!("{} ({} copies)", name, counts[name])))
+                ),
+                "Remove the duplicates; when the archive is extracted only the last copy survives, and which one that is depends on the tool",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    let unreadable: Vec<String> = archive
+        .entries
+        .iter()
+        .filter(|e| e.encrypted || matches!(e.method, ArchiveMethod::Unsupported(_)))
+        .map(|e| {
+            let reason = if e.encrypted { "encrypted".to_string() } else { e.method.to_string() };
+            format!("{} ({})", e.name, reason)
+        })
+        .collect();
+    if !unreadable.is_empty() {
+        results.push(
+            ValidationResult::warning(
+                "ARCHIVE-003",
+                format!(
+                    "{} has {} member{} that could not be read, so they were not hashed or analyzed: {}",
+                    file_name,
+                    unreadable.len(),
+                    if unreadable.len() == 1 { "" } else { "s" },
+                    list_entries(unreadable.into_iter())
+                ),
+                "Store members uncompressed or with DEFLATE, without encryption, so they can be verified after download",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    for warning in &archive.warnings {
+        results.push(
+            ValidationResult::info(
+                "ARCHIVE-004",
+                format!("{}: {}", file_name, warning),
+                "Check that the archive was copied completely; entries after the damage were not listed",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+/// An entry name as it would be extracted (`./a//b/` is `a/b`)
+fn normalize(name: &str) -> String {
+    name.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// List up to `MAX_LISTED` entries, and how many more there are
+fn list_entries(entries: impl Iterator<Item = String>) -> String {
+    let entries: Vec<String> = entries.collect();
+    let mut listed: Vec<String> = entries.iter().take(MAX_LISTED).cloned().collect();
+    if entries.len() > MAX_LISTED {
+        listed.push(format!("and {} more", entries.len() - MAX_LISTED));
+    }
+    listed.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ArchiveEntryKind, ArchiveFormat, FileType};
+    use std::path::PathBuf;
+
+    fn entry(name: &str, kind: ArchiveEntryKind, method: ArchiveMethod, link: Option<&str>) -> ArchiveEntry {
+        ArchiveEntry {
+            name: name.to_string(),
+            kind,
+            method,
+            encrypted: false,
+            link_target: link.map(str::to_string),
+            size: 10,
+            compressed_size: 10,
+            offset: 0,
+        }
+    }
+
+    fn validate(entries: Vec<ArchiveEntry>, warnings: Vec<String>) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/raw.tar"), PathBuf::from("raw.tar")).with_type(FileType::Binary)];
+        let archive = ArchiveAnalysis {
+            format: ArchiveFormat::Tar,
+            entries,
+            warnings,
+        };
+        validate_archives(&files, &[AnalysisResult::Archive(archive)])
+    }
+
+    #[test]
+    fn test_clean_archive_passes() {
+        let results = validate(
+            vec![
+                entry("raw/", ArchiveEntryKind::Directory, ArchiveMethod::Stored, None),
+                entry("raw/obs.csv", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+                entry("raw/latest.csv", ArchiveEntryKind::Link, ArchiveMethod::Stored, Some("obs.csv")),
+                entry("docs/../README.md", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+            ],
+            Vec::new(),
+        );
+        assert!(results.is_empty(), "{:?}", results);
+    }
+
+    #[test]
+    fn test_path_traversal() {
+        let results = validate(
+            vec![
+                entry("../../.bashrc", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+                entry("/etc/cron.d/job", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+                entry("raw/home", ArchiveEntryKind::Link, ArchiveMethod::Stored, Some("../../home")),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "ARCHIVE-001");
+        assert_eq!(
+            results[0].message,
+            "raw.tar has 3 entries that would be extracted outside the target directory: ../../.bashrc, /etc/cron.d/job, raw/home -> ../../home"
+        );
+    }
+
+    #[test]
+    fn test_duplicates_and_unreadable_members() {
+        let mut encrypted = entry("secret.csv", ArchiveEntryKind::File, ArchiveMethod::Deflate, None);
+        encrypted.encrypted = true;
+        let results = validate(
+            vec![
+                entry("obs.csv", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+                entry("./obs.csv", ArchiveEntryKind::File, ArchiveMethod::Stored, None),
+                entry("model.bin", ArchiveEntryKind::File, ArchiveMethod::Unsupported("lzma".to_string()), None),
+                encrypted,
+            ],
+            vec!["end-of-archive marker is missing".to_string()],
+        );
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["ARCHIVE-002", "ARCHIVE-003", "ARCHIVE-004"]);
+        assert_eq!(results[0].message, "raw.tar contains 1 name more than once: obs.csv (2 copies)");
+        assert_eq!(
+            results[1].message,
+            "raw.tar has 2 members that could not be read, so they were not hashed or analyzed: model.bin (lzma), secret.csv (encrypted)"
+        );
+    }
+}
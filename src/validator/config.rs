@@ -0,0 +1,97 @@
+//! YAML and TOML syntax checks
+//!
+//! Configuration and citation files are read by tools that stop at the
+//! first syntax error (a CITATION.cff that does not parse gives no
+//! citation at all). The analyzer records where parsing stopped; these
+//! checks report it with its line.
+
+use crate::types::{AnalysisResult, ConfigFormat, ConfigSyntaxError, FileInfo, ValidationResult};
+
+/// Report YAML and TOML files that do not parse
+pub fn validate_config(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| {
+            let config = match analysis {
+                AnalysisResult::Config(config) => config,
+                _ => return None,
+            };
+            let error = config.error.as_ref()?;
+            Some(
+                ValidationResult::critical(
+                    "CONFIG-001",
+                    format!(
+                        "{} is not valid {}: {} (line {})",
+                        file.relative_path.to_string_lossy(),
+                        config.format,
+                        error.message,
+                        error.line
+                    ),
+                    config_suggestion(config.format, error),
+                )
+                .with_file(file.relative_path.clone())
+                .with_line(error.line),
+            )
+        })
+        .collect()
+}
+
+/// How to fix a syntax error, for the mistakes hand-edited files usually have
+fn config_suggestion(format: ConfigFormat, error: &ConfigSyntaxError) -> &'static str {
+    let message = error.message.as_str();
+    if message.starts_with("Tab characters") {
+        "Indent with spaces; YAML does not allow tabs in indentation"
+    } else if message.starts_with("Mapping values are not allowed") {
+        "Quote the value (\"...\"); a plain YAML value cannot contain ': '"
+    } else if message == "Unexpected indentation" {
+        "Align the line with the other keys of its mapping (same number of spaces)"
+    } else if message.starts_with("Duplicate key") || message.ends_with("defined more than once") {
+        "Remove or rename the repeated key; readers keep only one of the values, or reject the file"
+    } else if message.ends_with("(strings must be quoted)") {
+        "Put the value in double quotes; TOML strings are always quoted"
+    } else {
+        match format {
+            ConfigFormat::Yaml => "Fix the YAML syntax at the reported line",
+            ConfigFormat::Toml => "Fix the TOML syntax at the reported line",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ConfigAnalysis, FileType};
+    use std::path::PathBuf;
+
+    fn validate(name: &str, format: ConfigFormat, error: Option<(&str, usize)>) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d").join(name), PathBuf::from(name)).with_type(FileType::Yaml)];
+        let analysis = ConfigAnalysis {
+            format,
+            is_valid: error.is_none(),
+            top_level_keys: Vec::new(),
+            document_count: 1,
+            error: error.map(|(message, line)| ConfigSyntaxError {
+                message: message.to_string(),
+                line,
+            }),
+        };
+        validate_config(&files, &[AnalysisResult::Config(analysis)])
+    }
+
+    #[test]
+    fn test_invalid_config_reported() {
+        let results = validate("CITATION.cff", ConfigFormat::Yaml, Some(("Unexpected indentation", 7)));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "CONFIG-001");
+        assert_eq!(results[0].line_number, Some(7));
+        assert_eq!(results[0].message, "CITATION.cff is not valid YAML: Unexpected indentation (line 7)");
+        assert!(results[0].suggestion.contains("Align"));
+
+        let results = validate("settings.toml", ConfigFormat::Toml, Some(("Invalid value 'on' (strings must be quoted)", 2)));
+        assert!(results[0].message.starts_with("settings.toml is not valid TOML"));
+        assert!(results[0].suggestion.contains("double quotes"));
+
+        assert!(validate("CITATION.cff", ConfigFormat::Yaml, None).is_empty());
+    }
+}
@@ -3,6 +3,10 @@
 //! Validates the actual content of documentation files, not just their presence.
 //! This provides genuine FAIR compliance checking beyond simple file existence.
 
+use super::metadata_model::{
+    FieldProblem, FieldStatus, MetadataDocument, MetadataField, Problem, CREATOR, DESCRIPTION, KEYWORDS, LICENSE,
+    TITLE,
+};
 use crate::types::{ValidationResult, ValidationSeverity};
 use std::fs;
 use std::path::Path;
@@ -123,79 +127,37 @@ pub fn validate_metadata_content(path: &Path) -> Vec<ValidationResult> {
         }
     };
 
-    // Parse JSON (simple check without full parser)
-    let check_field = |field: &str, content: &str| -> bool {
-        let pattern = format!("\"{}\"", field);
-        if !content.contains(&pattern) {
-            return false;
-        }
-        // Check if value is non-empty (not just "")
-        // Look for pattern: "field": "value" where value is not empty
-        let field_pattern = format!("\"{}\"", field);
-        if let Some(pos) = content.find(&field_pattern) {
-            let after = &content[pos + field_pattern.len()..];
-            // Skip to value
-            if let Some(colon_pos) = after.find(':') {
-                let value_part = after[colon_pos + 1..].trim_start();
-                // Check if it's not empty string or TODO
-                if value_part.starts_with("\"\"")
-                    || value_part.starts_with("\"[TODO]\"")
-                    || value_part.starts_with("\"[TODO")
-                {
-                    return false;
-                }
-                // Check for empty array
-                if value_part.starts_with("[]") {
-                    return false;
-                }
-                return true;
-            }
-        }
-        false
-    };
-
-    // Check required fields
-    let required_fields = [
-        ("title", "FAIR-F101", "Dataset title is required for findability"),
-        ("description", "FAIR-F102", "Dataset description is required for findability"),
-    ];
-
-    for (field, code, message) in required_fields {
-        if !check_field(field, &content) {
-            results.push(
-                ValidationResult::new(
-                    ValidationSeverity::Critical,
-                    code.to_string(),
-                    format!("{}: '{}' field missing or empty", message, field),
-                )
-                .with_suggestion(format!(
+    // Unparseable metadata.json is reported by the metadata checks (META-004)
+    if let Some(document) = MetadataDocument::parse(&content) {
+        // Check required fields
+        let required_fields = [
+            (TITLE, "FAIR-F101", "Dataset title is required for findability"),
+            (DESCRIPTION, "FAIR-F102", "Dataset description is required for findability"),
+        ];
+
+        for (field, code, message) in required_fields {
+            if let Some(result) = check_field_content(&document, &field, ValidationSeverity::Critical, code, message) {
+                results.push(result.with_suggestion(format!(
                     "Add a meaningful '{}' field to metadata.json",
-                    field
-                )),
-            );
+                    field.name
+                )));
+            }
         }
-    }
-
-    // Check recommended fields
-    let recommended_fields = [
-        ("keywords", "FAIR-F103", "Keywords help others discover your dataset"),
-        ("creator", "FAIR-F104", "Creator/author information aids attribution"),
-        ("license", "FAIR-A101", "License information is required for accessibility"),
-    ];
 
-    for (field, code, message) in recommended_fields {
-        if !check_field(field, &content) {
-            results.push(
-                ValidationResult::new(
-                    ValidationSeverity::Warning,
-                    code.to_string(),
-                    format!("{}: '{}' field missing or empty", message, field),
-                )
-                .with_suggestion(format!(
-                    "Add a '{}' field to metadata.json",
-                    field
-                )),
-            );
+        // Check recommended fields
+        let recommended_fields = [
+            (KEYWORDS, "FAIR-F103", "Keywords help others discover your dataset"),
+            (CREATOR, "FAIR-F104", "Creator/author information aids attribution"),
+            (LICENSE, "FAIR-A101", "License information is required for accessibility"),
+        ];
+
+        for (field, code, message) in recommended_fields {
+            if let Some(result) = check_field_content(&document, &field, ValidationSeverity::Warning, code, message) {
+                results.push(result.with_suggestion(format!(
+                    "Add a '{}' field to metadata.json, or fill in its placeholder",
+                    field.name
+                )));
+            }
         }
     }
 
@@ -220,6 +182,43 @@ pub fn validate_metadata_content(path: &Path) -> Vec<ValidationResult> {
     results
 }
 
+/// Report a metadata.json field that is missing, empty or a TODO placeholder
+///
+/// Values of the wrong type are left to the metadata checks (META-010).
+fn check_field_content(
+    document: &MetadataDocument,
+    field: &MetadataField,
+    severity: ValidationSeverity,
+    code: &str,
+    message: &str,
+) -> Option<ValidationResult> {
+    let problems: Vec<FieldProblem> = match document.check(field) {
+        FieldStatus::Missing => {
+            return Some(ValidationResult::new(
+                severity,
+                code.to_string(),
+                format!("{}: '{}' field missing or empty", message, field.name),
+            ))
+        }
+        FieldStatus::Present(problems) => problems
+            .into_iter()
+            .filter(|p| matches!(p.problem, Problem::Empty | Problem::MissingMember(_) | Problem::Placeholder))
+            .collect(),
+    };
+    let first = problems.first()?;
+
+    let described: Vec<String> = problems.iter().map(FieldProblem::describe).collect();
+    let result = ValidationResult::new(
+        severity,
+        code.to_string(),
+        format!("{}: {}", message, described.join(", ")),
+    );
+    Some(match first.line {
+        Some(line) => result.with_line(line),
+        None => result,
+    })
+}
+
 /// Validate README content
 ///
 /// Checks that README has substantive content with proper sections.
@@ -526,8 +525,8 @@ pub fn validate_all_content(
 ) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
-    // Find and validate specific files
-    for file in files {
+    // Find and validate specific files (documentation inside archives cannot be read from disk)
+    for file in files.iter().filter(|f| !f.is_archive_member()) {
         let filename = file
             .relative_path
             .file_name()
@@ -616,6 +615,39 @@ mod tests {
         std::fs::remove_dir_all(temp_dir).ok();
     }
 
+    #[test]
+    fn test_validate_metadata_content_top_level_only() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_metadata_content_test");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let file_path = temp_dir.join("metadata.json");
+        {
+            let mut file = std::fs::File::create(&file_path).unwrap();
+            writeln!(file, "{{").unwrap();
+            writeln!(file, "  \"description\": \"Mentions \\\"title\\\": in passing\",").unwrap();
+            writeln!(file, "  \"contact\": {{\"title\": \"Dr\", \"name\": \"Ada\"}},").unwrap();
+            writeln!(file, "  \"creator\": \"Ada\",").unwrap();
+            writeln!(file, "  \"license\": \"MIT\",").unwrap();
+            writeln!(file, "  \"keywords\": [\"ocean\", \"[TODO: keyword2]\"]").unwrap();
+            writeln!(file, "}}").unwrap();
+        }
+
+        let results = validate_metadata_content(&file_path);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["FAIR-F101", "FAIR-F103", "CONTENT-002"]);
+        assert_eq!(
+            results[0].message,
+            "Dataset title is required for findability: 'title' field missing or empty"
+        );
+        assert_eq!(
+            results[1].message,
+            "Keywords help others discover your dataset: /keywords/1 is still a [TODO] placeholder"
+        );
+        assert_eq!(results[1].line_number, Some(6));
+
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+
     #[test]
     fn test_detect_license_type() {
         assert_eq!(
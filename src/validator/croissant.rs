@@ -0,0 +1,474 @@
+//! Croissant metadata validation
+//!
+//! Checks that an existing croissant.json describes the dataset as it is on
+//! disk: every FileObject must point at a real file (with a matching SHA-256
+//! when one is declared) and every field must extract a column that exists.
+
+use crate::analyzer::{parse_json, JsonValue};
+use crate::types::{AnalysisResult, CsvAnalysis, FileInfo, ValidationResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Validate croissant.json files against the dataset
+///
+/// `files` and `analyses` are parallel slices, as produced by the scanner
+/// and analyzer. Datasets without a croissant.json produce no results.
+pub fn validate_croissant(
+    files: &[FileInfo],
+    analyses: &[AnalysisResult],
+) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for file in files.iter().filter(|f| !f.is_archive_member()) {
+        if file.file_name() == Some("croissant.json") {
+            results.extend(validate_croissant_file(file, files, analyses));
+        }
+    }
+
+    results
+}
+
+/// Validate a single croissant.json file
+fn validate_croissant_file(
+    croissant: &FileInfo,
+    files: &[FileInfo],
+    analyses: &[AnalysisResult],
+) -> Vec<ValidationResult> {
+    let file_path = croissant.relative_path.clone();
+
+    let content = match fs::read_to_string(&croissant.full_path) {
+        Ok(c) => c,
+        Err(_) => {
+            return vec![ValidationResult::critical(
+                "CROISSANT-001",
+                "Cannot read croissant.json file",
+                "Ensure croissant.json is readable",
+            )
+            .with_file(file_path)];
+        }
+    };
+
+    let root = match parse_json(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![ValidationResult::critical(
+                "CROISSANT-001",
+                format!("croissant.json is not valid JSON: {}", e),
+                "Fix the JSON syntax or regenerate croissant.json",
+            )
+            .with_file(file_path)];
+        }
+    };
+
+    let mut results = Vec::new();
+    results.extend(check_dataset_properties(&root, &file_path));
+
+    // Resolve paths relative to the directory holding croissant.json
+    let base_dir = croissant
+        .relative_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let dataset_files: HashMap<String, (&FileInfo, &AnalysisResult)> = files
+        .iter()
+        .zip(analyses.iter())
+        .map(|(f, a)| (normalize_path(&f.relative_path), (f, a)))
+        .collect();
+
+    // FileObject @id -> resolved dataset path (None when it isn't a local file)
+    let mut file_objects: HashMap<String, Option<String>> = HashMap::new();
+
+    let distribution = root
+        .get("distribution")
+        .and_then(|d| d.as_array())
+        .unwrap_or(&[]);
+
+    for entry in distribution {
+        if !has_type(entry, "FileObject") {
+            // FileSets describe globs; nothing concrete to resolve
+            if let Some(id) = entry.get("@id").and_then(|v| v.as_str()) {
+                file_objects.insert(id.to_string(), None);
+            }
+            continue;
+        }
+
+        let id = entry
+            .get("@id")
+            .or_else(|| entry.get("name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let content_url = match entry.get("contentUrl").and_then(|v| v.as_str()) {
+            Some(url) => url,
+            None => {
+                results.push(
+                    ValidationResult::warning(
+                        "CROISSANT-003",
+                        format!("FileObject '{}' has no contentUrl", id),
+                        "Add a contentUrl pointing at the file relative to croissant.json",
+                    )
+                    .with_file(file_path.clone()),
+                );
+                file_objects.insert(id, None);
+                continue;
+            }
+        };
+
+        if is_remote_url(content_url) {
+            file_objects.insert(id, None);
+            continue;
+        }
+
+        let resolved = normalize_path(&base_dir.join(content_url));
+        match dataset_files.get(&resolved) {
+            Some((file, _)) => {
+                results.extend(check_fixity(entry, &id, file, &file_path));
+                file_objects.insert(id, Some(resolved));
+            }
+            None => {
+                results.push(
+                    ValidationResult::critical(
+                        "CROISSANT-003",
+                        format!(
+                            "FileObject '{}' references a file that does not exist: {}",
+                            id, content_url
+                        ),
+                        "Update contentUrl or regenerate croissant.json after moving files",
+                    )
+                    .with_file(file_path.clone()),
+                );
+                file_objects.insert(id, None);
+            }
+        }
+    }
+
+    let record_sets = root
+        .get("recordSet")
+        .and_then(|r| r.as_array())
+        .unwrap_or(&[]);
+
+    for record_set in record_sets {
+        let fields = record_set
+            .get("field")
+            .and_then(|f| f.as_array())
+            .unwrap_or(&[]);
+
+        for field in fields {
+            results.extend(check_field_source(
+                field,
+                &file_objects,
+                &dataset_files,
+                &file_path,
+            ));
+        }
+    }
+
+    results
+}
+
+/// Check required dataset-level properties
+fn check_dataset_properties(root: &JsonValue, file_path: &Path) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    if !has_type(root, "Dataset") {
+        results.push(
+            ValidationResult::warning(
+                "CROISSANT-002",
+                "croissant.json root is not a schema.org Dataset",
+                "Set \"@type\": \"sc:Dataset\" on the root object",
+            )
+            .with_file(file_path.to_path_buf()),
+        );
+    }
+
+    for field in ["name", "description", "license", "url"] {
+        let value = root.get(field);
+        let is_set = match value {
+            Some(JsonValue::String(s)) => !s.trim().is_empty() && !s.contains("[TODO"),
+            Some(JsonValue::Array(items)) => !items.is_empty(),
+            Some(JsonValue::Object(_)) => true,
+            _ => false,
+        };
+
+        if !is_set {
+            results.push(
+                ValidationResult::warning(
+                    "CROISSANT-002",
+                    format!("croissant.json '{}' is missing or incomplete", field),
+                    format!("Provide a '{}' value in croissant.json", field),
+                )
+                .with_file(file_path.to_path_buf()),
+            );
+        }
+    }
+
+    results
+}
+
+/// Compare a FileObject's declared sha256 with the file on disk
+fn check_fixity(
+    entry: &JsonValue,
+    id: &str,
+    file: &FileInfo,
+    file_path: &Path,
+) -> Vec<ValidationResult> {
+    let declared = match entry.get("sha256").and_then(|v| v.as_str()) {
+        Some(h) => h,
+        None => return vec![],
+    };
+
+    match file.sha256_hash {
+        Some(ref actual) if !actual.eq_ignore_ascii_case(declared) => vec![ValidationResult::critical(
+            "CROISSANT-004",
+            format!("FileObject '{}' sha256 does not match the file contents", id),
+            format!(
+                "Expected hash: {}, actual hash: {}. Regenerate croissant.json if changes are intentional.",
+                declared, actual
+            ),
+        )
+        .with_file(file_path.to_path_buf())],
+        _ => vec![],
+    }
+}
+
+/// Check that a field's source references a known FileObject and column
+fn check_field_source(
+    field: &JsonValue,
+    file_objects: &HashMap<String, Option<String>>,
+    dataset_files: &HashMap<String, (&FileInfo, &AnalysisResult)>,
+    file_path: &Path,
+) -> Vec<ValidationResult> {
+    let field_name = field
+        .get("@id")
+        .or_else(|| field.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("<unnamed>");
+
+    let source = match field.get("source") {
+        Some(s) => s,
+        None => return vec![],
+    };
+
+    let object_id = match source
+        .get("fileObject")
+        .and_then(|f| f.get("@id"))
+        .and_then(|v| v.as_str())
+    {
+        Some(id) => id,
+        None => return vec![], // fileSet or recordSet sources are not checked
+    };
+
+    let resolved = match file_objects.get(object_id) {
+        Some(Some(path)) => path,
+        Some(None) => return vec![],
+        None => {
+            return vec![ValidationResult::warning(
+                "CROISSANT-005",
+                format!(
+                    "Field '{}' references unknown FileObject '{}'",
+                    field_name, object_id
+                ),
+                "Declare the FileObject in distribution or fix the source reference",
+            )
+            .with_file(file_path.to_path_buf())];
+        }
+    };
+
+    let column = match source
+        .get("extract")
+        .and_then(|e| e.get("column"))
+        .and_then(|v| v.as_str())
+    {
+        Some(c) => c,
+        None => return vec![],
+    };
+
+    let csv = match dataset_files.get(resolved).and_then(|(_, analysis)| analysis.table()) {
+        Some(csv) => csv,
+        None => return vec![],
+    };
+
+    if csv_has_column(csv, column) {
+        vec![]
+    } else {
+        vec![ValidationResult::critical(
+            "CROISSANT-006",
+            format!(
+                "Field '{}' extracts column '{}' which does not exist in {}",
+                field_name, column, resolved
+            ),
+            "Update the field's extract.column or regenerate croissant.json",
+        )
+        .with_file(file_path.to_path_buf())]
+    }
+}
+
+/// Check whether a CSV analysis contains a named column
+fn csv_has_column(csv: &CsvAnalysis, column: &str) -> bool {
+    csv.columns.iter().enumerate().any(|(idx, c)| match c.name {
+        Some(ref name) => name == column,
+        None => column == format!("column_{}", idx),
+    })
+}
+
+/// Check whether a JSON-LD node has the given type (with or without prefix)
+fn has_type(node: &JsonValue, type_name: &str) -> bool {
+    let matches = |t: &str| {
+        t == type_name || t.rsplit([':', '/']).next() == Some(type_name)
+    };
+
+    match node.get("@type") {
+        Some(JsonValue::String(t)) => matches(t),
+        Some(JsonValue::Array(types)) => types.iter().filter_map(|t| t.as_str()).any(matches),
+        _ => false,
+    }
+}
+
+/// Check whether a contentUrl points outside the dataset
+fn is_remote_url(url: &str) -> bool {
+    url.contains("://")
+}
+
+/// Normalize a relative path to forward slashes, resolving `.` and `..`
+fn normalize_path(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in PathBuf::from(path.to_string_lossy().replace('\\', "/")).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                parts.pop();
+            }
+            other => parts.push(other.as_os_str().to_string_lossy().to_string()),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ColumnInfo, ColumnType};
+
+    fn setup(name: &str, croissant: &str) -> (PathBuf, Vec<FileInfo>, Vec<AnalysisResult>) {
+        let temp_dir = std::env::temp_dir().join(name);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("croissant.json"), croissant).unwrap();
+
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.columns = vec![ColumnInfo::new(0)
+            .with_name("temperature".to_string())
+            .with_type(ColumnType::Float)];
+
+        let files = vec![
+            FileInfo::new(temp_dir.join("croissant.json"), PathBuf::from("croissant.json")),
+            FileInfo::new(
+                temp_dir.join("data/readings.csv"),
+                PathBuf::from("data/readings.csv"),
+            )
+            .with_hash("ab".repeat(32)),
+        ];
+        let analyses = vec![AnalysisResult::NotAnalyzed, AnalysisResult::Csv(csv)];
+
+        (temp_dir, files, analyses)
+    }
+
+    fn croissant_doc(content_url: &str, sha256: &str, column: &str) -> String {
+        format!(
+            r#"{{
+  "@type": "sc:Dataset",
+  "name": "Readings",
+  "description": "Temperature readings",
+  "license": "https://creativecommons.org/licenses/by/4.0/",
+  "url": "https://example.org/readings",
+  "distribution": [
+    {{ "@type": "cr:FileObject", "@id": "readings", "contentUrl": "{}", "sha256": "{}" }}
+  ],
+  "recordSet": [
+    {{ "@type": "cr:RecordSet", "@id": "rs", "field": [
+      {{ "@type": "cr:Field", "@id": "rs/t", "source": {{
+        "fileObject": {{ "@id": "readings" }}, "extract": {{ "column": "{}" }} }} }}
+    ] }}
+  ]
+}}"#,
+            content_url, sha256, column
+        )
+    }
+
+    #[test]
+    fn test_valid_croissant() {
+        let doc = croissant_doc("data/readings.csv", &"ab".repeat(32), "temperature");
+        let (temp_dir, files, analyses) = setup("genesis_preflight_croissant_valid", &doc);
+
+        let results = validate_croissant(&files, &analyses);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_croissant_missing_file() {
+        let doc = croissant_doc("data/missing.csv", &"ab".repeat(32), "temperature");
+        let (temp_dir, files, analyses) = setup("genesis_preflight_croissant_missing", &doc);
+
+        let results = validate_croissant(&files, &analyses);
+        assert!(results.iter().any(|r| r.code == "CROISSANT-003"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_croissant_hash_mismatch() {
+        let doc = croissant_doc("data/readings.csv", &"cd".repeat(32), "temperature");
+        let (temp_dir, files, analyses) = setup("genesis_preflight_croissant_hash", &doc);
+
+        let results = validate_croissant(&files, &analyses);
+        assert!(results.iter().any(|r| r.code == "CROISSANT-004"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_croissant_unknown_column() {
+        let doc = croissant_doc("./data/readings.csv", &"ab".repeat(32), "pressure");
+        let (temp_dir, files, analyses) = setup("genesis_preflight_croissant_column", &doc);
+
+        let results = validate_croissant(&files, &analyses);
+        assert!(results.iter().any(|r| r.code == "CROISSANT-006"));
+        assert!(!results.iter().any(|r| r.code == "CROISSANT-003"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_croissant_invalid_json() {
+        let (temp_dir, files, analyses) =
+            setup("genesis_preflight_croissant_invalid", "{\"name\": }");
+
+        let results = validate_croissant(&files, &analyses);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "CROISSANT-001");
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_croissant_todo_properties() {
+        let doc = r#"{"@type": "sc:Dataset", "name": "[TODO: Dataset Name]"}"#;
+        let (temp_dir, files, analyses) = setup("genesis_preflight_croissant_todo", doc);
+
+        let results = validate_croissant(&files, &analyses);
+        // name (TODO), description, license, url
+        assert_eq!(results.iter().filter(|r| r.code == "CROISSANT-002").count(), 4);
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("./data/a.csv")), "data/a.csv");
+        assert_eq!(normalize_path(Path::new("docs/../data/a.csv")), "data/a.csv");
+    }
+}
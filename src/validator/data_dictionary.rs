@@ -0,0 +1,341 @@
+//! Data dictionary validation
+//!
+//! Checks that every column of every CSV/TSV file is documented with a
+//! non-TODO description in DATA_DICTIONARY.md (or DATA_DICTIONARY.csv).
+
+use crate::analyzer::parse_line_rfc4180;
+use crate::generator::{
+    is_data_dictionary, split_table_row, DATA_DICTIONARY_CSV, DATA_DICTIONARY_MD,
+};
+use crate::types::{AnalysisResult, CsvAnalysis, FileInfo, ValidationResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A documented column from the data dictionary
+#[derive(Debug, Clone)]
+struct DictionaryEntry {
+    /// Data file the column belongs to (dataset-relative, `/`-separated)
+    file: String,
+    /// Column name
+    column: String,
+    /// Description cell
+    description: String,
+    /// 1-based line number in the dictionary
+    line: usize,
+}
+
+/// Validate column documentation against the data dictionary
+///
+/// `files` and `analyses` are parallel slices, as produced by the scanner
+/// and analyzer. Datasets without CSV/TSV/Parquet files or SQLite tables
+/// produce no results.
+pub fn validate_data_dictionary(
+    files: &[FileInfo],
+    analyses: &[AnalysisResult],
+) -> Vec<ValidationResult> {
+    let tables: Vec<(String, &CsvAnalysis)> = files
+        .iter()
+        .zip(analyses.iter())
+        .filter(|(file, _)| !is_data_dictionary(file))
+        .flat_map(|(file, analysis)| {
+            let path = normalize_path(&file.relative_path);
+            // Each table of a database is documented as `path#table`
+            analysis.tables().into_iter().map(move |(table, csv)| match table {
+                Some(table) => (format!("{}#{}", path, table), csv),
+                None => (path.clone(), csv),
+            })
+        })
+        .collect();
+
+    if tables.is_empty() {
+        return vec![];
+    }
+
+    // Prefer the Markdown dictionary when both exist
+    let dictionary = [DATA_DICTIONARY_MD, DATA_DICTIONARY_CSV].iter().find_map(|name| {
+        files
+            .iter()
+            .find(|f| f.relative_path == Path::new(name))
+    });
+
+    let dictionary = match dictionary {
+        Some(d) => d,
+        None => {
+            return vec![ValidationResult::info(
+                "DICT-001",
+                "No data dictionary documents the CSV columns",
+                "Run 'genesis-preflight generate' to create DATA_DICTIONARY.md and fill in each column's description and unit",
+            )];
+        }
+    };
+
+    let dictionary_path = dictionary.relative_path.clone();
+    let content = match fs::read_to_string(&dictionary.full_path) {
+        Ok(c) => c,
+        Err(_) => {
+            return vec![ValidationResult::warning(
+                "DICT-001",
+                "Cannot read data dictionary",
+                "Ensure the data dictionary is readable",
+            )
+            .with_file(dictionary_path)];
+        }
+    };
+
+    let entries = if dictionary.file_name() == Some(DATA_DICTIONARY_CSV) {
+        parse_csv_dictionary(&content)
+    } else {
+        parse_markdown_dictionary(&content)
+    };
+
+    let mut by_column: HashMap<(&str, &str), &DictionaryEntry> = HashMap::new();
+    for entry in &entries {
+        by_column.insert((entry.file.as_str(), entry.column.as_str()), entry);
+    }
+
+    let mut results = Vec::new();
+
+    for (path, csv) in &tables {
+        for column in &csv.columns {
+            let name = column
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("column_{}", column.index));
+
+            match by_column.get(&(path.as_str(), name.as_str())) {
+                None => results.push(
+                    ValidationResult::warning(
+                        "DICT-002",
+                        format!("Column '{}' in {} is not in the data dictionary", name, path),
+                        "Regenerate the data dictionary or add a row for this column",
+                    )
+                    .with_file(dictionary_path.clone()),
+                ),
+                Some(entry) if !is_documented(&entry.description) => results.push(
+                    ValidationResult::warning(
+                        "DICT-003",
+                        format!("Column '{}' in {} has no description", name, path),
+                        "Describe what the column measures, how it was collected and its unit",
+                    )
+                    .with_file(dictionary_path.clone())
+                    .with_line(entry.line),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+
+    // Stale entries: documented columns that no longer exist
+    for entry in &entries {
+        let exists = tables.iter().any(|(path, csv)| {
+            *path == entry.file
+                && csv.columns.iter().any(|c| {
+                    c.name.as_deref() == Some(entry.column.as_str())
+                        || (c.name.is_none() && format!("column_{}", c.index) == entry.column)
+                })
+        });
+        if !exists {
+            results.push(
+                ValidationResult::info(
+                    "DICT-004",
+                    format!(
+                        "Data dictionary documents '{}' in {}, which does not exist",
+                        entry.column, entry.file
+                    ),
+                    "Remove the stale row or regenerate the data dictionary",
+                )
+                .with_file(dictionary_path.clone())
+                .with_line(entry.line),
+            );
+        }
+    }
+
+    results
+}
+
+/// Parse DATA_DICTIONARY.md
+///
+/// Each `## <path>` heading starts a file section; the first table row in
+/// a section is its header and locates the Column and Description cells.
+fn parse_markdown_dictionary(content: &str) -> Vec<DictionaryEntry> {
+    let mut entries = Vec::new();
+    let mut current_file: Option<String> = None;
+    let mut columns: Option<(usize, usize)> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            current_file = Some(heading.trim().trim_matches('`').to_string());
+            columns = None;
+            continue;
+        }
+
+        let file = match current_file {
+            Some(ref f) if trimmed.starts_with('|') => f,
+            _ => continue,
+        };
+
+        let cells = split_table_row(trimmed);
+
+        let (column_idx, description_idx) = match columns {
+            Some(c) => c,
+            None => {
+                let find = |label: &str| {
+                    cells.iter().position(|c| c.eq_ignore_ascii_case(label))
+                };
+                if let (Some(c), Some(d)) = (find("column"), find("description")) {
+                    columns = Some((c, d));
+                }
+                continue;
+            }
+        };
+
+        // Separator row
+        if cells.iter().all(|c| c.chars().all(|ch| ch == '-' || ch == ':')) {
+            continue;
+        }
+
+        if let Some(column) = cells.get(column_idx).filter(|c| !c.is_empty()) {
+            entries.push(DictionaryEntry {
+                file: file.clone(),
+                column: column.clone(),
+                description: cells.get(description_idx).cloned().unwrap_or_default(),
+                line: idx + 1,
+            });
+        }
+    }
+
+    entries
+}
+
+/// Parse DATA_DICTIONARY.csv (header row names the file, column and description fields)
+fn parse_csv_dictionary(content: &str) -> Vec<DictionaryEntry> {
+    let mut lines = content.lines().enumerate();
+
+    let header = match lines.next() {
+        Some((_, line)) => parse_line_rfc4180(line, ','),
+        None => return vec![],
+    };
+    let find = |label: &str| header.iter().position(|h| h.eq_ignore_ascii_case(label));
+    let (file_idx, column_idx, description_idx) =
+        match (find("file"), find("column"), find("description")) {
+            (Some(f), Some(c), Some(d)) => (f, c, d),
+            _ => return vec![],
+        };
+
+    lines
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(idx, line)| {
+            let fields = parse_line_rfc4180(line, ',');
+            Some(DictionaryEntry {
+                file: normalize_path(Path::new(fields.get(file_idx)?)),
+                column: fields.get(column_idx)?.clone(),
+                description: fields.get(description_idx).cloned().unwrap_or_default(),
+                line: idx + 1,
+            })
+        })
+        .collect()
+}
+
+/// Check whether a description is filled in
+fn is_documented(description: &str) -> bool {
+    let trimmed = description.trim();
+    !trimmed.is_empty() && !trimmed.to_uppercase().contains("TODO")
+}
+
+/// Normalize a dataset-relative path for comparison
+fn normalize_path(path: &Path) -> String {
+    let s = path.to_string_lossy().replace('\\', "/");
+    s.trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ColumnInfo;
+    use std::path::PathBuf;
+
+    fn make_dataset(name: &str, dictionary_name: &str, dictionary: &str) -> (PathBuf, Vec<FileInfo>, Vec<AnalysisResult>) {
+        let root = std::env::temp_dir().join(name);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(dictionary_name), dictionary).unwrap();
+
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.column_count = 2;
+        csv.columns = vec![
+            ColumnInfo::new(0).with_name("site".to_string()),
+            ColumnInfo::new(1).with_name("depth_m".to_string()),
+        ];
+
+        let files = vec![
+            FileInfo::new(root.join("data/readings.csv"), PathBuf::from("data/readings.csv")),
+            FileInfo::new(root.join(dictionary_name), PathBuf::from(dictionary_name)),
+        ];
+        (root, files, vec![AnalysisResult::Csv(csv), AnalysisResult::NotAnalyzed])
+    }
+
+    #[test]
+    fn test_documented_dictionary_passes() {
+        let md = "# Data Dictionary\n\n## data/readings.csv\n\n\
+                  | Column | Type | Description | Unit |\n\
+                  |--------|------|-------------|------|\n\
+                  | site | string | Sampling site name | - |\n\
+                  | depth_m | float | Depth below surface | m |\n";
+        let (root, files, analyses) = make_dataset("genesis_preflight_dict_ok", DATA_DICTIONARY_MD, md);
+
+        let results = validate_data_dictionary(&files, &analyses);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn test_missing_and_todo_descriptions() {
+        let md = "## data/readings.csv\n\n\
+                  | Column | Description |\n\
+                  |--------|-------------|\n\
+                  | site | [TODO: describe] |\n\
+                  | old_column | Removed last year |\n";
+        let (root, files, analyses) = make_dataset("genesis_preflight_dict_todo", DATA_DICTIONARY_MD, md);
+
+        let results = validate_data_dictionary(&files, &analyses);
+        let todo = results.iter().find(|r| r.code == "DICT-003").unwrap();
+        assert_eq!(todo.line_number, Some(5));
+        assert!(results
+            .iter()
+            .any(|r| r.code == "DICT-002" && r.message.contains("depth_m")));
+        assert!(results
+            .iter()
+            .any(|r| r.code == "DICT-004" && r.message.contains("old_column")));
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn test_csv_dictionary() {
+        let csv = "file,column,description,unit\n\
+                   data/readings.csv,site,\"Site name, as recorded\",-\n\
+                   data/readings.csv,depth_m,,m\n";
+        let (root, files, analyses) = make_dataset("genesis_preflight_dict_csv", DATA_DICTIONARY_CSV, csv);
+
+        let results = validate_data_dictionary(&files, &analyses);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "DICT-003");
+        assert_eq!(results[0].line_number, Some(3));
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn test_no_dictionary() {
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.columns = vec![ColumnInfo::new(0).with_name("a".to_string())];
+        let files = vec![FileInfo::new(PathBuf::from("/d/a.csv"), PathBuf::from("a.csv"))];
+
+        let results = validate_data_dictionary(&files, &[AnalysisResult::Csv(csv)]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "DICT-001");
+    }
+}
@@ -6,7 +6,7 @@
 //! - Interoperable: Has schema, uses standard formats
 //! - Reusable: Has documentation, provenance, citation info
 
-use crate::types::{AnalysisResult, FileInfo, FileType, ValidationResult};
+use crate::types::{AnalysisResult, BinaryType, FileInfo, FileType, ValidationResult};
 
 /// Calculate FAIR compliance scores
 ///
@@ -14,35 +14,35 @@ use crate::types::{AnalysisResult, FileInfo, FileType, ValidationResult};
 /// The actual scoring is done by the reporter module.
 pub fn calculate_fair_scores(
     files: &[FileInfo],
-    _analyses: &[AnalysisResult],
+    analyses: &[AnalysisResult],
 ) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
     // Findable checks
-    results.extend(check_findable(files));
+    results.extend(check_findable(files, analyses));
 
     // Accessible checks
-    results.extend(check_accessible(files));
+    results.extend(check_accessible(files, analyses));
 
     // Interoperable checks
     results.extend(check_interoperable(files));
 
     // Reusable checks
-    results.extend(check_reusable(files));
+    results.extend(check_reusable(files, analyses));
 
     results
 }
 
 /// Check Findable criteria
-fn check_findable(files: &[FileInfo]) -> Vec<ValidationResult> {
+fn check_findable(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
-    // Check for metadata
+    // Check for metadata (metadata.json, or an EML/ISO/DataCite record)
     let has_metadata = files.iter().any(|f| {
         f.file_name()
             .map(|name| name == "metadata.json")
             .unwrap_or(false)
-    });
+    }) || has_standard_metadata(analyses);
 
     if !has_metadata {
         results.push(ValidationResult::warning(
@@ -59,7 +59,7 @@ fn check_findable(files: &[FileInfo]) -> Vec<ValidationResult> {
 }
 
 /// Check Accessible criteria
-fn check_accessible(files: &[FileInfo]) -> Vec<ValidationResult> {
+fn check_accessible(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
     // Check for license
@@ -83,7 +83,12 @@ fn check_accessible(files: &[FileInfo]) -> Vec<ValidationResult> {
     // Check for standard formats
     let non_standard_formats = files
         .iter()
-        .filter(|f| matches!(f.file_type, FileType::Unknown | FileType::Binary))
+        .enumerate()
+        .filter(|(idx, f)| match f.file_type {
+            FileType::Unknown => true,
+            FileType::Binary => !is_standard_binary(analyses.get(*idx)),
+            _ => false,
+        })
         .count();
 
     if non_standard_formats > 0 && non_standard_formats as f32 / files.len() as f32 > 0.1 {
@@ -93,13 +98,46 @@ fn check_accessible(files: &[FileInfo]) -> Vec<ValidationResult> {
                 "{} files use non-standard or unknown formats",
                 non_standard_formats
             ),
-            "Consider converting to standard formats (CSV, JSON, HDF5, NetCDF)",
+            "Consider converting to standard formats (CSV, JSON, Parquet, HDF5, NetCDF)",
         ));
     }
 
     results
 }
 
+/// Whether the dataset has a well-formed XML record in a metadata standard
+/// catalogs harvest (EML, ISO 19115/19139, DataCite)
+fn has_standard_metadata(analyses: &[AnalysisResult]) -> bool {
+    analyses.iter().any(|analysis| {
+        matches!(analysis, AnalysisResult::Xml(xml) if xml.is_well_formed && xml.standard.is_some())
+    })
+}
+
+/// Whether a binary file is a recognised scientific data format
+fn is_standard_binary(analysis: Option<&AnalysisResult>) -> bool {
+    match analysis {
+        Some(AnalysisResult::Hdf5(_))
+        | Some(AnalysisResult::Netcdf(_))
+        | Some(AnalysisResult::Fits(_))
+        | Some(AnalysisResult::Parquet(_))
+        | Some(AnalysisResult::Sqlite(_))
+        | Some(AnalysisResult::Numpy(_))
+        | Some(AnalysisResult::Tiff(_))
+        | Some(AnalysisResult::Archive(_)) => true,
+        Some(AnalysisResult::Binary(binary)) => matches!(
+            binary.binary_type,
+            BinaryType::Hdf5
+                | BinaryType::Netcdf
+                | BinaryType::Fits
+                | BinaryType::Parquet
+                | BinaryType::Sqlite
+                | BinaryType::Npy
+                | BinaryType::Tiff
+        ),
+        _ => false,
+    }
+}
+
 /// Check Interoperable criteria
 fn check_interoperable(files: &[FileInfo]) -> Vec<ValidationResult> {
     let mut results = Vec::new();
@@ -139,7 +177,7 @@ fn check_interoperable(files: &[FileInfo]) -> Vec<ValidationResult> {
 }
 
 /// Check Reusable criteria
-fn check_reusable(files: &[FileInfo]) -> Vec<ValidationResult> {
+fn check_reusable(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
     // Check for documentation
@@ -172,7 +210,8 @@ fn check_reusable(files: &[FileInfo]) -> Vec<ValidationResult> {
         ));
     }
 
-    // Check for citation information
+    // Check for citation information (standard metadata records carry
+    // creators, title and date)
     let has_citation = files.iter().any(|f| {
         f.file_name()
             .map(|name| {
@@ -180,7 +219,7 @@ fn check_reusable(files: &[FileInfo]) -> Vec<ValidationResult> {
                     || name == "metadata.json"
             })
             .unwrap_or(false)
-    });
+    }) || has_standard_metadata(analyses);
 
     if !has_citation {
         results.push(ValidationResult::info(
@@ -204,7 +243,7 @@ mod tests {
             PathBuf::from("data.csv"),
             PathBuf::from("data.csv"),
         )];
-        let results = check_findable(&files);
+        let results = check_findable(&files, &[]);
         assert!(results.iter().any(|r| r.code == "FAIR-F001"));
     }
 
@@ -214,17 +253,40 @@ mod tests {
             PathBuf::from("metadata.json"),
             PathBuf::from("metadata.json"),
         )];
-        let results = check_findable(&files);
+        let results = check_findable(&files, &[]);
         assert!(!results.iter().any(|r| r.code == "FAIR-F001"));
     }
 
+    #[test]
+    fn test_check_findable_standard_xml_metadata() {
+        use crate::types::{MetadataStandard, XmlAnalysis};
+
+        let files = vec![FileInfo::new(PathBuf::from("eml.xml"), PathBuf::from("eml.xml"))];
+        let eml = |is_well_formed| {
+            vec![AnalysisResult::Xml(XmlAnalysis {
+                is_well_formed,
+                root_element: Some("eml:eml".to_string()),
+                root_namespace: Some("https://eml.ecoinformatics.org/eml-2.2.0".to_string()),
+                namespaces: Vec::new(),
+                element_count: 1,
+                max_depth: 1,
+                standard: Some(MetadataStandard::Eml),
+                error: None,
+            })]
+        };
+        assert!(!check_findable(&files, &eml(true)).iter().any(|r| r.code == "FAIR-F001"));
+        assert!(!check_reusable(&files, &eml(true)).iter().any(|r| r.code == "FAIR-R003"));
+        // A broken record is not credited
+        assert!(check_findable(&files, &eml(false)).iter().any(|r| r.code == "FAIR-F001"));
+    }
+
     #[test]
     fn test_check_accessible_no_license() {
         let files = vec![FileInfo::new(
             PathBuf::from("data.csv"),
             PathBuf::from("data.csv"),
         )];
-        let results = check_accessible(&files);
+        let results = check_accessible(&files, &[]);
         assert!(results.iter().any(|r| r.code == "FAIR-A001"));
     }
 
@@ -234,10 +296,30 @@ mod tests {
             PathBuf::from("LICENSE"),
             PathBuf::from("LICENSE"),
         )];
-        let results = check_accessible(&files);
+        let results = check_accessible(&files, &[]);
         assert!(!results.iter().any(|r| r.code == "FAIR-A001"));
     }
 
+    #[test]
+    fn test_check_accessible_scientific_binaries_are_standard() {
+        use crate::types::{BinaryAnalysis, Hdf5Analysis};
+
+        let files = vec![
+            FileInfo::new(PathBuf::from("sim.h5"), PathBuf::from("sim.h5")),
+            FileInfo::new(PathBuf::from("run.nc"), PathBuf::from("run.nc")),
+            FileInfo::new(PathBuf::from("blob.bin"), PathBuf::from("blob.bin")),
+        ];
+        let analyses = vec![
+            AnalysisResult::Hdf5(Hdf5Analysis::new(0)),
+            AnalysisResult::Binary(BinaryAnalysis::new(BinaryType::Netcdf)),
+            AnalysisResult::Binary(BinaryAnalysis::new(BinaryType::Unknown)),
+        ];
+
+        let results = check_accessible(&files, &analyses);
+        let a002 = results.iter().find(|r| r.code == "FAIR-A002").unwrap();
+        assert!(a002.message.starts_with("1 files"));
+    }
+
     #[test]
     fn test_check_interoperable_no_schema() {
         let files = vec![FileInfo::new(
@@ -260,7 +342,7 @@ mod tests {
                 PathBuf::from("metadata.json"),
             ),
         ];
-        let results = check_reusable(&files);
+        let results = check_reusable(&files, &[]);
 
         // Should not have critical reusability issues
         assert!(!results.iter().any(|r| r.code == "FAIR-R001"));
@@ -0,0 +1,208 @@
+//! FITS header checks
+//!
+//! Flags HDUs that other FITS readers will reject or misread: mandatory
+//! keywords that are missing, table columns without physical units, and
+//! headers whose declared data size does not match the file.
+
+use crate::types::{AnalysisResult, FileInfo, FitsAnalysis, ValidationResult, FITS_BLOCK_SIZE};
+
+/// Validate the headers of FITS files
+pub fn validate_fits(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Fits(fits) = analysis {
+            results.extend(check_file(file, fits));
+        }
+    }
+
+    results
+}
+
+/// Check one FITS file
+fn check_file(file: &FileInfo, fits: &FitsAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    for hdu in &fits.hdus {
+        let label = match &hdu.name {
+            Some(name) => format!("HDU {} ({} '{}')", hdu.index, hdu.kind, name),
+            None => format!("HDU {} ({})", hdu.index, hdu.kind),
+        };
+
+        if !hdu.missing_keywords.is_empty() {
+            results.push(
+                ValidationResult::warning(
+                    "FITS-001",
+                    format!(
+                        "{} in {} is missing mandatory keywords or has invalid values: {}",
+                        label,
+                        file_name,
+                        hdu.missing_keywords.join(", ")
+                    ),
+                    "Add the keywords the FITS standard requires for this HDU type; other readers will reject the header",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+
+        for (number, column) in hdu.columns.iter().enumerate().map(|(i, c)| (i + 1, c)) {
+            if column.unit.is_some() || column.is_unitless() {
+                continue;
+            }
+            let column_name = column
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("column {}", number));
+            results.push(
+                ValidationResult::warning(
+                    "FITS-002",
+                    format!("Column '{}' in {} of {} has no unit", column_name, label, file_name),
+                    format!(
+                        "Add TUNIT{} with the physical unit (IAU-style, e.g. 'Jy', 'deg' or 'erg/s')",
+                        number
+                    ),
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+
+        if hdu.data_end() > fits.file_size {
+            results.push(
+                ValidationResult::warning(
+                    "FITS-003",
+                    format!(
+                        "{} in {} declares {} bytes of data but the file ends {} bytes early",
+                        label,
+                        file_name,
+                        hdu.data_size,
+                        hdu.data_end() - fits.file_size
+                    ),
+                    "The file is truncated or BITPIX/NAXISn/PCOUNT/GCOUNT are wrong; re-export or re-download it",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    // Trailing bytes and block padding are only meaningful once every HDU was read
+    if let Some(last) = fits.hdus.last().filter(|_| fits.warnings.is_empty()) {
+        if last.data_end() <= fits.file_size {
+            let size_issue = if fits.file_size > last.padded_end() {
+                Some(format!(
+                    "{} has {} bytes after its last HDU that are not a valid extension",
+                    file_name,
+                    fits.file_size - last.padded_end()
+                ))
+            } else if !fits.file_size.is_multiple_of(FITS_BLOCK_SIZE) {
+                Some(format!(
+                    "{} is {} bytes long, not a multiple of {} (the last block is not padded)",
+                    file_name, fits.file_size, FITS_BLOCK_SIZE
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = size_issue {
+                results.push(
+                    ValidationResult::warning(
+                        "FITS-003",
+                        message,
+                        "Check the headers against the data; FITS files are whole 2880-byte blocks",
+                    )
+                    .with_file(file.relative_path.clone()),
+                );
+            }
+        }
+    }
+
+    for warning in &fits.warnings {
+        results.push(
+            ValidationResult::info(
+                "FITS-004",
+                format!("Part of {} could not be read: {}", file_name, warning),
+                "Check the file with fitsverify; HDUs that could not be read were not validated",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FitsColumn, FitsHdu, FitsHduKind};
+    use std::path::PathBuf;
+
+    fn hdu(index: usize, kind: FitsHduKind, data_offset: u64, data_size: u64) -> FitsHdu {
+        FitsHdu {
+            index,
+            kind,
+            name: None,
+            bitpix: 8,
+            axes: vec![data_size],
+            columns: vec![],
+            missing_keywords: vec![],
+            header_offset: data_offset - FITS_BLOCK_SIZE,
+            data_offset,
+            data_size,
+        }
+    }
+
+    fn column(name: &str, format: &str, unit: Option<&str>) -> FitsColumn {
+        FitsColumn {
+            name: Some(name.to_string()),
+            format: format.to_string(),
+            unit: unit.map(str::to_string),
+        }
+    }
+
+    fn validate(analysis: FitsAnalysis) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/obs.fits"), PathBuf::from("obs.fits"))];
+        validate_fits(&files, &[AnalysisResult::Fits(analysis)])
+    }
+
+    #[test]
+    fn test_valid_file_passes() {
+        let mut table = hdu(1, FitsHduKind::BinaryTable, 5760, 100);
+        table.columns = vec![column("FLUX", "1E", Some("Jy")), column("NAME", "12A", None)];
+        let mut analysis = FitsAnalysis::new(8640);
+        analysis.hdus = vec![hdu(0, FitsHduKind::Primary, 2880, 0), table];
+
+        let results = validate(analysis);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_header_problems_reported() {
+        let mut table = hdu(1, FitsHduKind::BinaryTable, 5760, 100);
+        table.name = Some("EVENTS".to_string());
+        table.missing_keywords = vec!["GCOUNT".to_string()];
+        table.columns = vec![column("ENERGY", "1D", None)];
+        let mut analysis = FitsAnalysis::new(8000);
+        analysis.hdus = vec![hdu(0, FitsHduKind::Primary, 2880, 0), table];
+
+        let results = validate(analysis);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["FITS-001", "FITS-002", "FITS-003"]);
+        assert!(results[0].message.contains("HDU 1 (BINTABLE 'EVENTS')"));
+        assert!(results[1].suggestion.contains("TUNIT1"));
+        assert!(results[2].message.contains("not a multiple of 2880"));
+    }
+
+    #[test]
+    fn test_size_mismatches() {
+        let mut truncated = FitsAnalysis::new(4000);
+        truncated.hdus = vec![hdu(0, FitsHduKind::Primary, 2880, 2000)];
+        let results = validate(truncated);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("880 bytes early"));
+
+        let mut trailing = FitsAnalysis::new(8640);
+        trailing.hdus = vec![hdu(0, FitsHduKind::Primary, 2880, 10)];
+        let results = validate(trailing);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("2880 bytes after its last HDU"));
+    }
+}
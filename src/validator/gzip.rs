@@ -0,0 +1,69 @@
+//! gzip integrity checks
+//!
+//! Gzipped CSV, TSV and JSON files are analyzed through the built-in
+//! decompressor, which checks every member's CRC-32 and length. A file
+//! that fails is damaged: whoever downloads it gets an error part way
+//! through, or silently truncated data.
+
+use crate::types::{AnalysisResult, FileInfo, ValidationResult};
+
+/// Report gzip-compressed files whose stream is corrupt or fails its checksum
+pub fn validate_gzip(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| {
+            let gzip = analysis.gzip()?;
+            let error = gzip.error.as_ref()?;
+            Some(
+                ValidationResult::critical(
+                    "GZIP-001",
+                    format!(
+                        "{} is damaged: {} (after {} bytes of decompressed data)",
+                        file.relative_path.to_string_lossy(),
+                        error,
+                        gzip.uncompressed_bytes
+                    ),
+                    "Recompress the file from the original data and check it with gzip -t; only the readable part was analyzed",
+                )
+                .with_file(file.relative_path.clone()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CsvAnalysis, FileType, GzipInfo};
+    use std::path::PathBuf;
+
+    fn validate(error: Option<&str>) -> Vec<ValidationResult> {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/obs.csv.gz"), PathBuf::from("obs.csv.gz")).with_type(FileType::Csv),
+        ];
+        let mut csv = CsvAnalysis::new(',', true);
+        csv.gzip = Some(GzipInfo {
+            compressed_bytes: 120,
+            uncompressed_bytes: 4096,
+            error: error.map(str::to_string),
+        });
+        validate_gzip(&files, &[AnalysisResult::Csv(csv)])
+    }
+
+    #[test]
+    fn test_intact_file_passes() {
+        assert!(validate(None).is_empty());
+    }
+
+    #[test]
+    fn test_crc_mismatch_reported() {
+        let results = validate(Some("CRC mismatch: stored 0badf00d, computed 8d3e52a1"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "GZIP-001");
+        assert_eq!(
+            results[0].message,
+            "obs.csv.gz is damaged: CRC mismatch: stored 0badf00d, computed 8d3e52a1 (after 4096 bytes of decompressed data)"
+        );
+    }
+}
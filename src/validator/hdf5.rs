@@ -0,0 +1,151 @@
+//! HDF5 dataset documentation checks
+//!
+//! Self-describing formats are only self-describing if the attributes are
+//! filled in: numeric datasets need `units`, and every dataset needs a
+//! human-readable `long_name` (or `description`).
+
+use crate::types::{AnalysisResult, FileInfo, Hdf5Analysis, Hdf5Dataset, ValidationResult};
+
+/// Validate the attributes of datasets in HDF5 files
+pub fn validate_hdf5(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Hdf5(hdf5) = analysis {
+            results.extend(check_file(file, hdf5));
+        }
+    }
+
+    results
+}
+
+/// Check one HDF5 file
+fn check_file(file: &FileInfo, hdf5: &Hdf5Analysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    for dataset in hdf5.datasets.iter().filter(|d| !is_dimension_only(d)) {
+        if dataset.datatype.is_numeric() && !has_value(dataset, &["units", "unit"]) {
+            results.push(
+                ValidationResult::warning(
+                    "HDF5-001",
+                    format!("Dataset '{}' in {} has no 'units' attribute", dataset.path, file_name),
+                    "Add a 'units' attribute (UDUNITS syntax, e.g. 'K' or 'm s-1'; use '1' for dimensionless values)",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+
+        if !has_value(dataset, &["long_name", "description"]) {
+            results.push(
+                ValidationResult::info(
+                    "HDF5-002",
+                    format!("Dataset '{}' in {} has no 'long_name' attribute", dataset.path, file_name),
+                    "Add a 'long_name' or 'description' attribute explaining what the dataset contains",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    for warning in &hdf5.warnings {
+        results.push(
+            ValidationResult::info(
+                "HDF5-003",
+                format!("Part of {} could not be read: {}", file_name, warning),
+                "Check the file with h5dump; objects that could not be read were not validated",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+/// Whether any of the named attributes is present with a non-empty value
+fn has_value(dataset: &Hdf5Dataset, names: &[&str]) -> bool {
+    names.iter().any(|name| {
+        dataset
+            .attribute(name)
+            .map(|a| !a.value.trim().is_empty())
+            .unwrap_or(false)
+    })
+}
+
+/// Whether a dataset only exists to define a netCDF-4 dimension
+///
+/// netCDF-4 writes each dimension without a coordinate variable as an
+/// empty dimension scale; these are not data and need no attributes.
+fn is_dimension_only(dataset: &Hdf5Dataset) -> bool {
+    dataset
+        .attribute("NAME")
+        .map(|a| a.value.starts_with("This is a netCDF dimension but not a netCDF variable"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Hdf5Attribute, Hdf5Datatype};
+    use std::path::PathBuf;
+
+    fn dataset(path: &str, datatype: Hdf5Datatype, attributes: &[(&str, &str)]) -> Hdf5Dataset {
+        Hdf5Dataset {
+            path: path.to_string(),
+            shape: vec![10],
+            datatype,
+            chunks: None,
+            filters: vec![],
+            attributes: attributes
+                .iter()
+                .map(|(name, value)| Hdf5Attribute {
+                    name: name.to_string(),
+                    datatype: Hdf5Datatype::VarString,
+                    value: value.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn validate(analysis: Hdf5Analysis) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/sim.h5"), PathBuf::from("sim.h5"))];
+        validate_hdf5(&files, &[AnalysisResult::Hdf5(analysis)])
+    }
+
+    #[test]
+    fn test_documented_datasets_pass() {
+        let mut analysis = Hdf5Analysis::new(0);
+        analysis.datasets = vec![
+            dataset(
+                "/temperature",
+                Hdf5Datatype::Float { size: 8 },
+                &[("units", "K"), ("long_name", "Air temperature")],
+            ),
+            dataset("/station", Hdf5Datatype::VarString, &[("description", "Station name")]),
+            dataset(
+                "/x",
+                Hdf5Datatype::Float { size: 4 },
+                &[("NAME", "This is a netCDF dimension but not a netCDF variable.        10")],
+            ),
+        ];
+
+        let results = validate(analysis);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_missing_attributes() {
+        let mut analysis = Hdf5Analysis::new(2);
+        analysis.datasets = vec![
+            dataset("/pressure", Hdf5Datatype::Integer { size: 4, signed: true }, &[("units", " ")]),
+            dataset("/label", Hdf5Datatype::VarString, &[]),
+        ];
+        analysis.warnings.push("/big: dense link storage".to_string());
+
+        let results = validate(analysis);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["HDF5-001", "HDF5-002", "HDF5-002", "HDF5-003"]);
+        assert!(results[0].message.contains("/pressure"));
+        assert!(results[2].message.contains("/label"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Image collection checks
+//!
+//! Compares the PNG and JPEG images of a dataset with each other: models
+//! trained on an image collection usually expect one size and channel
+//! count, so stray grayscale or odd-sized images are flagged, as are
+//! images whose EXIF orientation rotates them on display.
+
+use crate::types::{AnalysisResult, FileInfo, ImageInfo, ValidationResult};
+use std::collections::HashMap;
+
+/// Most file names listed in one message
+const MAX_LISTED: usize = 5;
+
+/// Check the PNG and JPEG images of a dataset for consistency
+pub fn check_images(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let images: Vec<(&FileInfo, &ImageInfo)> = files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| match analysis {
+            AnalysisResult::Binary(binary) => binary.image.as_ref().map(|image| (file, image)),
+            _ => None,
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    if images.len() >= 2 {
+        results.extend(check_sizes(&images));
+        results.extend(check_channels(&images));
+    }
+    results.extend(check_orientation(&images));
+    results
+}
+
+/// The most common value and how often it occurs (ties go to the smaller value)
+fn most_common<T: Copy + Ord + std::hash::Hash>(values: impl Iterator<Item = T>) -> (T, usize, usize) {
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let distinct = counts.len();
+    let (value, count) = counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .expect("at least one image");
+    (value, count, distinct)
+}
+
+/// List up to `MAX_LISTED` images with a detail each, and how many more there are
+fn list_images<'a>(images: impl Iterator<Item = (&'a FileInfo, String)>) -> String {
+    let images: Vec<_> = images.collect();
+    let mut listed: Vec<String> = images
+        .iter()
+        .take(MAX_LISTED)
+        .map(|(file, detail)| format!("{} ({})", file.relative_path.to_string_lossy(), detail))
+        .collect();
+    if images.len() > MAX_LISTED {
+        listed.push(format!("and {} more", images.len() - MAX_LISTED));
+    }
+    listed.join(", ")
+}
+
+/// Flag images whose size differs from the collection's
+fn check_sizes(images: &[(&FileInfo, &ImageInfo)]) -> Vec<ValidationResult> {
+    let ((width, height), count, distinct) = most_common(images.iter().map(|(_, i)| (i.width, i.height)));
+    if distinct == 1 {
+        return Vec::new();
+    }
+
+    // Without a majority size there is no outlier to point at
+    if count * 2 <= images.len() {
+        return vec![ValidationResult::info(
+            "IMAGE-003",
+            format!(
+                "The {} images come in {} different sizes; the most common, {}x{}, covers {}",
+                images.len(),
+                distinct,
+                width,
+                height,
+                count
+            ),
+            "Document the size range in the README, or resize the images if models are expected to use them as-is",
+        )];
+    }
+
+    let outliers = images
+        .iter()
+        .filter(|(_, i)| (i.width, i.height) != (width, height))
+        .map(|(file, i)| (*file, format!("{}x{}", i.width, i.height)));
+    vec![ValidationResult::warning(
+        "IMAGE-001",
+        format!(
+            "{} of {} images differ from the common size {}x{}: {}",
+            images.len() - count,
+            images.len(),
+            width,
+            height,
+            list_images(outliers)
+        ),
+        "Check whether these images were cropped or exported differently; resize them or document the exception",
+    )]
+}
+
+/// Flag images whose channel count differs from the collection's
+fn check_channels(images: &[(&FileInfo, &ImageInfo)]) -> Vec<ValidationResult> {
+    let (channels, count, distinct) = most_common(images.iter().map(|(_, i)| i.channels));
+    if distinct == 1 {
+        return Vec::new();
+    }
+
+    let outliers = images
+        .iter()
+        .filter(|(_, i)| i.channels != channels)
+        .map(|(file, i)| {
+            let plural = if i.channels == 1 { "" } else { "s" };
+            (*file, format!("{}, {} channel{}", i.color_type, i.channels, plural))
+        });
+    vec![ValidationResult::warning(
+        "IMAGE-002",
+        format!(
+            "{} of {} images do not have the common {} channels: {}",
+            images.len() - count,
+            images.len(),
+            channels,
+            list_images(outliers)
+        ),
+        "Convert the images to one colour mode (e.g. all RGB); mixed grayscale and colour images break batch loading",
+    )]
+}
+
+/// Flag images stored rotated or mirrored, with an EXIF orientation to undo it
+fn check_orientation(images: &[(&FileInfo, &ImageInfo)]) -> Vec<ValidationResult> {
+    let rotated: Vec<_> = images
+        .iter()
+        .filter_map(|(file, i)| match i.orientation {
+            Some(orientation) if orientation != 1 => Some((*file, orientation_name(orientation).to_string())),
+            _ => None,
+        })
+        .collect();
+    if rotated.is_empty() {
+        return Vec::new();
+    }
+
+    vec![ValidationResult::info(
+        "IMAGE-004",
+        format!(
+            "{} images are stored rotated or mirrored and rely on their EXIF orientation: {}",
+            rotated.len(),
+            list_images(rotated.into_iter())
+        ),
+        "Loaders that ignore EXIF see these images sideways; apply the orientation to the pixels (e.g. exiftran -ai) before release",
+    )]
+}
+
+/// What displaying an image with an EXIF orientation does to the stored pixels
+fn orientation_name(orientation: u16) -> &'static str {
+    match orientation {
+        2 => "mirrored",
+        3 => "rotated 180°",
+        4 => "flipped vertically",
+        5 => "mirrored and rotated 90° counter-clockwise",
+        6 => "rotated 90° clockwise",
+        7 => "mirrored and rotated 90° clockwise",
+        8 => "rotated 90° counter-clockwise",
+        _ => "unchanged",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BinaryAnalysis, BinaryType, FileType};
+    use std::path::PathBuf;
+
+    fn validate(images: &[(&str, u32, u32, u8, Option<u16>)]) -> Vec<ValidationResult> {
+        let files: Vec<FileInfo> = images
+            .iter()
+            .map(|(name, ..)| FileInfo::new(PathBuf::from(name), PathBuf::from(name)).with_type(FileType::Binary))
+            .collect();
+        let analyses: Vec<AnalysisResult> = images
+            .iter()
+            .map(|(_, width, height, channels, orientation)| {
+                let image = ImageInfo {
+                    width: *width,
+                    height: *height,
+                    bit_depth: 8,
+                    color_type: if *channels == 1 { "grayscale" } else { "RGB" }.to_string(),
+                    channels: *channels,
+                    frames: 1,
+                    orientation: *orientation,
+                };
+                AnalysisResult::Binary(BinaryAnalysis::new(BinaryType::Png).with_image(image))
+            })
+            .collect();
+        check_images(&files, &analyses)
+    }
+
+    #[test]
+    fn test_consistent_collection_passes() {
+        let results = validate(&[
+            ("cats/1.png", 224, 224, 3, None),
+            ("cats/2.png", 224, 224, 3, Some(1)),
+            ("dogs/1.png", 224, 224, 3, None),
+        ]);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_outliers_reported() {
+        let results = validate(&[
+            ("cats/1.png", 224, 224, 3, None),
+            ("cats/2.png", 224, 224, 3, None),
+            ("cats/3.png", 224, 224, 3, Some(6)),
+            ("dogs/1.png", 200, 224, 1, None),
+        ]);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["IMAGE-001", "IMAGE-002", "IMAGE-004"]);
+        assert_eq!(
+            results[0].message,
+            "1 of 4 images differ from the common size 224x224: dogs/1.png (200x224)"
+        );
+        assert_eq!(
+            results[1].message,
+            "1 of 4 images do not have the common 3 channels: dogs/1.png (grayscale, 1 channel)"
+        );
+        assert!(results[2].message.ends_with("cats/3.png (rotated 90° clockwise)"));
+    }
+
+    #[test]
+    fn test_mixed_sizes_without_majority() {
+        let results = validate(&[
+            ("a.jpg", 640, 480, 3, None),
+            ("b.jpg", 1024, 768, 3, None),
+            ("c.jpg", 800, 600, 3, None),
+            ("d.jpg", 640, 480, 3, None),
+        ]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "IMAGE-003");
+        assert_eq!(
+            results[0].message,
+            "The 4 images come in 3 different sizes; the most common, 640x480, covers 2"
+        );
+    }
+}
@@ -4,10 +4,11 @@
 //! against a previously generated MANIFEST.txt file.
 
 use crate::crypto::sha256_file;
-use crate::types::{FileInfo, ValidationResult, ValidationSeverity};
+use crate::scanner::hash_store;
+use crate::types::{FileInfo, FileType, ValidationResult, ValidationSeverity};
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 /// Result of an integrity check
@@ -143,6 +144,15 @@ fn parse_manifest(manifest_path: &Path) -> Result<HashMap<String, String>, Strin
     Ok(entries)
 }
 
+/// Hash a scanned entry: a file's contents, or a Zarr store's file listing
+fn hash_entry(file_info: &FileInfo) -> io::Result<String> {
+    if file_info.file_type == FileType::Zarr {
+        hash_store(&file_info.full_path)
+    } else {
+        sha256_file(&file_info.full_path)
+    }
+}
+
 /// Verify dataset integrity against a manifest file
 ///
 /// Compares the SHA-256 hashes of current files against those stored
@@ -175,7 +185,8 @@ pub fn verify_manifest(
 
     // Build a map of current files
     let mut current_files: HashMap<String, &FileInfo> = HashMap::new();
-    for file in files {
+    // (archive members are not listed in manifests; their archive is)
+    for file in files.iter().filter(|f| !f.is_archive_member()) {
         let rel_path = file.relative_path.to_string_lossy().to_string();
         current_files.insert(rel_path, file);
     }
@@ -199,7 +210,7 @@ pub fn verify_manifest(
                 }
             } else {
                 // Hash not computed - compute it now
-                match sha256_file(&file_info.full_path) {
+                match hash_entry(file_info) {
                     Ok(actual_hash) => {
                         if actual_hash.to_lowercase() != *expected_hash {
                             issues.push(IntegrityIssue::modified(
@@ -232,12 +243,20 @@ pub fn verify_manifest(
 
     // Check for new files (in current but not in manifest)
     for (path, file_info) in current_files {
+        // Zarr stores are directories, which manifests do not list
+        if file_info.file_type == FileType::Zarr {
+            continue;
+        }
+
         // Skip documentation files that might have been generated after manifest
         if path == "MANIFEST.txt"
             || path.ends_with("/MANIFEST.txt")
             || path == "README.md"
             || path == "metadata.json"
             || path == "DATACARD.md"
+            || path == "croissant.json"
+            || path == "DATA_DICTIONARY.md"
+            || path == "DATA_DICTIONARY.csv"
             || path.ends_with(".schema.json")
         {
             continue;
@@ -247,7 +266,7 @@ pub fn verify_manifest(
         let actual_hash = if let Some(ref hash) = file_info.sha256_hash {
             hash.clone()
         } else {
-            sha256_file(&file_info.full_path).unwrap_or_else(|_| "unknown".to_string())
+            hash_entry(file_info).unwrap_or_else(|_| "unknown".to_string())
         };
 
         issues.push(IntegrityIssue::new_file(path, actual_hash));
@@ -374,6 +393,7 @@ mod tests {
         {
             let mut file = File::create(&manifest_path).unwrap();
             writeln!(file, "# This is a comment").unwrap();
+            #[allow(clippy::writeln_empty_string)]
             writeln!(file, "").unwrap();
             writeln!(
                 file,
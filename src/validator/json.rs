@@ -0,0 +1,125 @@
+//! JSON syntax checks
+//!
+//! A JSON file that does not parse cannot be loaded at all, and most
+//! parsers only say so with a byte offset. The analyzer records where the
+//! first error is; these checks report it with its line, column and the
+//! text around it, and recognize the usual hand-editing mistakes.
+
+use crate::types::{AnalysisResult, FileInfo, JsonSyntaxError, ValidationResult};
+
+/// Report JSON data files that are not valid JSON
+///
+/// metadata.json and croissant.json have checks of their own.
+pub fn validate_json(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| {
+            let json = match analysis {
+                AnalysisResult::Json(json) => json,
+                _ => return None,
+            };
+            let error = json.error.as_ref()?;
+            let own_check = matches!(file.file_name(), Some("metadata.json" | "croissant.json"));
+            if own_check && !file.is_archive_member() {
+                return None;
+            }
+            Some(
+                ValidationResult::critical(
+                    "JSON-001",
+                    format!(
+                        "{} is not valid JSON: {}",
+                        file.relative_path.to_string_lossy(),
+                        describe_syntax_error(error)
+                    ),
+                    syntax_suggestion(error),
+                )
+                .with_file(file.relative_path.clone())
+                .with_line(error.line),
+            )
+        })
+        .collect()
+}
+
+/// An error with its position and the text around it
+pub(crate) fn describe_syntax_error(error: &JsonSyntaxError) -> String {
+    let mut description = format!("{} at line {}, column {}", error.message, error.line, error.column);
+    if !error.snippet.is_empty() {
+        description.push_str(&format!(" (near `{}`)", error.snippet));
+    }
+    description
+}
+
+/// How to fix a syntax error, for the mistakes hand-edited JSON usually has
+pub(crate) fn syntax_suggestion(error: &JsonSyntaxError) -> &'static str {
+    let message = error.message.as_str();
+    if message.starts_with("Trailing comma") {
+        "Remove the comma after the last element; JSON does not allow trailing commas"
+    } else if message.ends_with("found '''") {
+        "Use double quotes for strings and keys; JSON does not accept single quotes"
+    } else if message.ends_with("found '/'") || message.ends_with("found '#'") {
+        "Remove the comment; JSON has no comments (put notes in a \"description\" field instead)"
+    } else if message.ends_with("found 'N'") || message.ends_with("found 'I'") {
+        "Replace NaN and Infinity with null (or a string); JSON has no literal for them"
+    } else if message.starts_with("Expected a string key") {
+        "Put object keys in double quotes"
+    } else if message == "Unexpected end of input" {
+        "The file ends early; check for an unclosed bracket, brace or string, or a truncated copy"
+    } else {
+        "Fix the JSON syntax at the reported line and column"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, JsonAnalysis};
+    use std::path::PathBuf;
+
+    fn error(message: &str) -> JsonSyntaxError {
+        JsonSyntaxError {
+            message: message.to_string(),
+            offset: 118,
+            line: 6,
+            column: 3,
+            snippet: "}".to_string(),
+        }
+    }
+
+    fn validate(name: &str, error: Option<JsonSyntaxError>) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d").join(name), PathBuf::from(name)).with_type(FileType::Json)];
+        let analysis = JsonAnalysis {
+            error,
+            ..JsonAnalysis::invalid()
+        };
+        validate_json(&files, &[AnalysisResult::Json(analysis)])
+    }
+
+    #[test]
+    fn test_invalid_data_file_reported() {
+        let results = validate("samples.json", Some(error("Trailing comma before closing brace")));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "JSON-001");
+        assert_eq!(results[0].line_number, Some(6));
+        assert_eq!(
+            results[0].message,
+            "samples.json is not valid JSON: Trailing comma before closing brace at line 6, column 3 (near `}`)"
+        );
+        assert!(results[0].suggestion.contains("trailing commas"));
+
+        assert!(validate("samples.json", None).is_empty());
+        assert!(validate("metadata.json", Some(error("Unexpected end of input"))).is_empty());
+    }
+
+    #[test]
+    fn test_targeted_suggestions() {
+        let suggestion = |message: &str| syntax_suggestion(&error(message));
+        assert!(suggestion("Expected a string key, found '''").contains("double quotes"));
+        assert!(suggestion("Expected a value, found '/'").contains("comments"));
+        assert!(suggestion("Expected a value, found 'N'").contains("NaN"));
+        assert!(suggestion("Invalid number: expected a digit, found 'I'").contains("Infinity"));
+        assert!(suggestion("Expected a string key, found 'n'").contains("keys in double quotes"));
+        assert!(suggestion("Unexpected end of input").contains("ends early"));
+        assert_eq!(suggestion("Invalid escape in string, found 'x'"), "Fix the JSON syntax at the reported line and column");
+    }
+}
@@ -0,0 +1,184 @@
+//! JSON Lines checks
+//!
+//! A JSON Lines file is only as usable as its worst line: most loaders stop
+//! at the first line that does not parse. Records that are not objects, or
+//! fields whose type changes from record to record, load but break the
+//! tabular view that schemas and dictionaries describe.
+
+use crate::types::{AnalysisResult, FileInfo, JsonLinesAnalysis, ValidationResult};
+
+/// Most lines or fields listed in one message
+const MAX_LISTED: usize = 5;
+
+/// Validate the records of JSON Lines files
+pub fn validate_jsonl(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::JsonLines(jsonl) = analysis {
+            results.extend(check_jsonl(file, jsonl));
+        }
+    }
+
+    results
+}
+
+/// Check one JSON Lines file
+fn check_jsonl(file: &FileInfo, jsonl: &JsonLinesAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    if jsonl.invalid_line_count > 0 {
+        let mut listed: Vec<String> = jsonl
+            .invalid_lines
+            .iter()
+            .take(MAX_LISTED)
+            .map(|(line, error)| format!("line {} ({})", line, error))
+            .collect();
+        if jsonl.invalid_line_count > listed.len() {
+            listed.push(format!("and {} more", jsonl.invalid_line_count - listed.len()));
+        }
+        results.push(
+            ValidationResult::warning(
+                "JSONL-001",
+                format!(
+                    "{} has {} line{} that {} not valid JSON: {}",
+                    file_name,
+                    jsonl.invalid_line_count,
+                    if jsonl.invalid_line_count == 1 { "" } else { "s" },
+                    if jsonl.invalid_line_count == 1 { "is" } else { "are" },
+                    listed.join(", ")
+                ),
+                "Fix or remove these lines; each line must hold one complete JSON value, with no pretty-printing across lines",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    if jsonl.non_object_count > 0 {
+        results.push(
+            ValidationResult::warning(
+                "JSONL-002",
+                format!(
+                    "{}: {} of {} records are not JSON objects, so they have no named fields",
+                    file_name, jsonl.non_object_count, jsonl.record_count
+                ),
+                "Write every record as an object with the same keys, so the file can be loaded as a table",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    // Integers and fractions are both JSON numbers; only other mixes count
+    let mixed: Vec<String> = jsonl
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let types = field.value_types();
+            let kinds = types
+                .iter()
+                .filter(|(name, _)| *name != "number" || !types.iter().any(|(other, _)| *other == "integer"))
+                .count();
+            if kinds < 2 {
+                return None;
+            }
+            let counts: Vec<String> = types.iter().map(|(name, count)| format!("{} {}", count, name)).collect();
+            Some(format!("{} ({})", field.name, counts.join(", ")))
+        })
+        .collect();
+    if !mixed.is_empty() {
+        let mut listed: Vec<String> = mixed.iter().take(MAX_LISTED).cloned().collect();
+        if mixed.len() > MAX_LISTED {
+            listed.push(format!("and {} more", mixed.len() - MAX_LISTED));
+        }
+        results.push(
+            ValidationResult::info(
+                "JSONL-003",
+                format!(
+                    "{} has {} field{} whose type differs between records: {}",
+                    file_name,
+                    mixed.len(),
+                    if mixed.len() == 1 { "" } else { "s" },
+                    listed.join(", ")
+                ),
+                "Use one JSON type per field (null for missing values) so loaders infer a consistent column type",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CsvAnalysis, FileType, JsonLinesField};
+    use std::path::PathBuf;
+
+    fn field(name: &str, type_counts: Vec<(&'static str, usize)>) -> JsonLinesField {
+        JsonLinesField {
+            name: name.to_string(),
+            present_count: type_counts.iter().map(|(_, count)| count).sum(),
+            type_counts,
+        }
+    }
+
+    fn validate(jsonl: JsonLinesAnalysis) -> Vec<ValidationResult> {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/events.jsonl"), PathBuf::from("events.jsonl")).with_type(FileType::JsonLines),
+        ];
+        validate_jsonl(&files, &[AnalysisResult::JsonLines(jsonl)])
+    }
+
+    fn analysis(fields: Vec<JsonLinesField>) -> JsonLinesAnalysis {
+        JsonLinesAnalysis {
+            record_count: 10,
+            non_object_count: 0,
+            invalid_line_count: 0,
+            invalid_lines: Vec::new(),
+            fields,
+            table: CsvAnalysis::new(',', true),
+        }
+    }
+
+    #[test]
+    fn test_clean_file_passes() {
+        let results = validate(analysis(vec![
+            field("id", vec![("integer", 10)]),
+            field("temp", vec![("integer", 4), ("number", 5), ("null", 1)]),
+        ]));
+        assert!(results.is_empty(), "{:?}", results);
+    }
+
+    #[test]
+    fn test_invalid_lines_and_non_objects() {
+        let mut jsonl = analysis(Vec::new());
+        jsonl.invalid_line_count = 7;
+        jsonl.invalid_lines = (1..=7).map(|line| (line * 2, "Unexpected end of input".to_string())).collect();
+        jsonl.non_object_count = 2;
+
+        let results = validate(jsonl);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["JSONL-001", "JSONL-002"]);
+        assert!(results[0]
+            .message
+            .starts_with("events.jsonl has 7 lines that are not valid JSON: line 2 (Unexpected end of input), line 4"));
+        assert!(results[0].message.ends_with("line 10 (Unexpected end of input), and 2 more"));
+        assert_eq!(results[1].message, "events.jsonl: 2 of 10 records are not JSON objects, so they have no named fields");
+    }
+
+    #[test]
+    fn test_mixed_field_types() {
+        let results = validate(analysis(vec![
+            field("site", vec![("string", 9), ("integer", 1)]),
+            field("depth", vec![("number", 10)]),
+        ]));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "JSONL-003");
+        assert_eq!(
+            results[0].message,
+            "events.jsonl has 1 field whose type differs between records: site (9 string, 1 integer)"
+        );
+    }
+}
@@ -1,5 +1,10 @@
 //! Metadata file validation
 
+use super::json::{describe_syntax_error, syntax_suggestion};
+use super::metadata_model::{
+    FieldStatus, MetadataDocument, Problem, CREATOR, DATE, DESCRIPTION, KEYWORDS, LICENSE, TITLE,
+};
+use crate::analyzer::json_syntax_error;
 use crate::types::{FileInfo, ValidationResult};
 use std::fs;
 
@@ -23,7 +28,7 @@ pub fn validate_metadata(files: &[FileInfo]) -> Vec<ValidationResult> {
 
 /// Validate README file has substantive content
 fn validate_readme_content(files: &[FileInfo]) -> Vec<ValidationResult> {
-    let readme = files.iter().find(|f| {
+    let readme = files.iter().filter(|f| !f.is_archive_member()).find(|f| {
         f.file_name()
             .map(|name| name.to_uppercase().starts_with("README"))
             .unwrap_or(false)
@@ -64,7 +69,7 @@ fn validate_readme_content(files: &[FileInfo]) -> Vec<ValidationResult> {
 
 /// Validate metadata.json file structure
 fn validate_metadata_json(files: &[FileInfo]) -> Vec<ValidationResult> {
-    let metadata_file = files.iter().find(|f| {
+    let metadata_file = files.iter().filter(|f| !f.is_archive_member()).find(|f| {
         f.file_name()
             .map(|name| name == "metadata.json")
             .unwrap_or(false)
@@ -96,64 +101,75 @@ fn validate_json_structure(
 ) -> Vec<ValidationResult> {
     let mut results = Vec::new();
 
-    // Try to parse as JSON (simple check)
-    let trimmed = content.trim();
-    if !trimmed.starts_with('{') || !trimmed.ends_with('}') {
+    if let Some(error) = json_syntax_error(content) {
         return vec![ValidationResult::critical(
             "META-004",
-            "metadata.json is not valid JSON",
+            format!("metadata.json is not valid JSON: {}", describe_syntax_error(&error)),
+            syntax_suggestion(&error),
+        )
+        .with_file(file_path.to_path_buf())
+        .with_line(error.line)];
+    }
+    if !content.trim_start_matches('\u{feff}').trim_start().starts_with('{') {
+        return vec![ValidationResult::critical(
+            "META-004",
+            "metadata.json is not a JSON object",
             "Ensure metadata.json contains valid JSON object",
         )
         .with_file(file_path.to_path_buf())];
     }
 
-    // Check for required fields (simple string matching)
+    let document = match MetadataDocument::parse(content) {
+        Some(document) => document,
+        None => return results,
+    };
+
+    // Required fields, at the top level of the document
     let required_fields = [
-        ("title", "META-005", "Add 'title' field to metadata.json"),
-        (
-            "description",
-            "META-006",
-            "Add 'description' field to metadata.json",
-        ),
-        (
-            "creator",
-            "META-007",
-            "Add 'creator' or 'author' field to metadata.json",
-        ),
-        ("date", "META-008", "Add 'date' field to metadata.json"),
-        (
-            "license",
-            "META-009",
-            "Add 'license' field to metadata.json",
-        ),
+        (TITLE, "META-005", "Add 'title' field to metadata.json"),
+        (DESCRIPTION, "META-006", "Add 'description' field to metadata.json"),
+        (CREATOR, "META-007", "Add 'creator' or 'author' field to metadata.json"),
+        (DATE, "META-008", "Add 'date' field to metadata.json"),
+        (LICENSE, "META-009", "Add 'license' field to metadata.json"),
     ];
 
     for (field, code, suggestion) in &required_fields {
-        // Simple check: look for "field": in the JSON
-        // For "creator", also accept "author"
-        let has_field = if *field == "creator" {
-            content.contains("\"creator\"") || content.contains("\"author\"")
-        } else if *field == "date" {
-            content.contains("\"date\"") || content.contains("\"created\"")
-        } else {
-            content.contains(&format!("\"{}\"", field))
-        };
-
-        if !has_field {
+        if document.check(field) == FieldStatus::Missing {
             results.push(
-                ValidationResult::warning(*code, format!("Missing {} field", field), *suggestion)
+                ValidationResult::warning(*code, format!("Missing {} field", field.name), *suggestion)
                     .with_file(file_path.to_path_buf()),
             );
         }
     }
 
+    // Values of the wrong type (empty and TODO values are content checks)
+    for field in [TITLE, DESCRIPTION, CREATOR, DATE, LICENSE, KEYWORDS] {
+        let problems = match document.check(&field) {
+            FieldStatus::Present(problems) => problems,
+            FieldStatus::Missing => continue,
+        };
+        for problem in problems {
+            if let Problem::WrongType { expected, .. } = problem.problem {
+                let mut result = ValidationResult::warning(
+                    "META-010",
+                    format!("metadata.json: {}", problem.describe()),
+                    format!("Write '{}' as {} so catalogs can read it", field.name, expected),
+                )
+                .with_file(file_path.to_path_buf());
+                if let Some(line) = problem.line {
+                    result = result.with_line(line);
+                }
+                results.push(result);
+            }
+        }
+    }
+
     results
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
     use std::path::PathBuf;
 
     #[test]
@@ -260,6 +276,52 @@ mod tests {
         let results = validate_metadata_json(&files);
         assert!(results.iter().any(|r| r.code == "META-004"));
 
+        fs::write(&meta_path, "{\n  \"title\": \"Survey\",\n  \"keywords\": ['ocean', 'salinity']\n}\n").unwrap();
+        let results = validate_metadata_json(&files);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "META-004");
+        assert_eq!(results[0].line_number, Some(3));
+        assert_eq!(
+            results[0].message,
+            "metadata.json is not valid JSON: Expected a value, found ''' at line 3, column 16 (near `\"keywords\": ['ocean', 'salinity']`)"
+        );
+        assert!(results[0].suggestion.contains("double quotes"));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn test_validate_metadata_json_top_level_typed() {
+        let temp_dir = std::env::temp_dir().join("genesis_preflight_meta_json_typed");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let meta_path = temp_dir.join("metadata.json");
+        fs::write(
+            &meta_path,
+            r#"{
+                "title": 42,
+                "description": "A test dataset",
+                "creator": "Test User",
+                "date": "2024-01-01",
+                "contact": {"name": "Test User", "license": "MIT"},
+                "keywords": "ocean, salinity"
+            }"#,
+        )
+        .unwrap();
+
+        let files = vec![FileInfo::new(
+            meta_path.clone(),
+            PathBuf::from("metadata.json"),
+        )];
+        let results = validate_metadata_json(&files);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        // license only appears inside contact
+        assert_eq!(codes, vec!["META-009", "META-010", "META-010"]);
+        assert_eq!(results[1].message, "metadata.json: /title must be a string, not a number");
+        assert_eq!(results[1].line_number, Some(2));
+        assert_eq!(results[2].message, "metadata.json: /keywords must be an array of strings, not a string");
+        assert_eq!(results[2].line_number, Some(7));
+
         fs::remove_dir_all(temp_dir).ok();
     }
 
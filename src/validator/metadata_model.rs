@@ -0,0 +1,284 @@
+//! Typed model of metadata.json
+//!
+//! metadata.json is read with the crate's JSON parser and its top-level
+//! fields checked against the types catalogs expect. A field only counts if
+//! it is a member of the root object: a `"title"` inside `contact`, or
+//! inside a string value, is not the dataset's title. Every problem carries
+//! the JSON pointer of the offending value and the line it starts on.
+
+use crate::analyzer::{json_pointer_token, parse_json_with_locations, JsonLocations, JsonValue};
+
+/// How a metadata.json field must be written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldKind {
+    /// A string with real content
+    Text,
+    /// A person or organization: a name, an object with a `name`, or an
+    /// array of these
+    Agent,
+    /// An array of keyword strings
+    Keywords,
+}
+
+/// A top-level metadata.json field
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MetadataField {
+    /// Preferred key
+    pub name: &'static str,
+    /// Other keys accepted in its place
+    pub aliases: &'static [&'static str],
+    pub kind: FieldKind,
+}
+
+pub(crate) const TITLE: MetadataField = MetadataField {
+    name: "title",
+    aliases: &[],
+    kind: FieldKind::Text,
+};
+
+pub(crate) const DESCRIPTION: MetadataField = MetadataField {
+    name: "description",
+    aliases: &[],
+    kind: FieldKind::Text,
+};
+
+pub(crate) const CREATOR: MetadataField = MetadataField {
+    name: "creator",
+    aliases: &["author"],
+    kind: FieldKind::Agent,
+};
+
+pub(crate) const DATE: MetadataField = MetadataField {
+    name: "date",
+    aliases: &["created"],
+    kind: FieldKind::Text,
+};
+
+pub(crate) const LICENSE: MetadataField = MetadataField {
+    name: "license",
+    aliases: &[],
+    kind: FieldKind::Text,
+};
+
+pub(crate) const KEYWORDS: MetadataField = MetadataField {
+    name: "keywords",
+    aliases: &[],
+    kind: FieldKind::Keywords,
+};
+
+/// What is wrong with a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Problem {
+    /// The value has the wrong JSON type
+    WrongType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// An empty string or array
+    Empty,
+    /// An object without a member it needs (e.g. an author without `name`)
+    MissingMember(&'static str),
+    /// A `[TODO...]` placeholder left from generation
+    Placeholder,
+}
+
+/// A problem with one value of metadata.json
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldProblem {
+    /// JSON pointer of the value (e.g. `/keywords/2`)
+    pub pointer: String,
+    /// Line the value starts on
+    pub line: Option<usize>,
+    pub problem: Problem,
+}
+
+impl FieldProblem {
+    /// The problem in words, naming the value by its pointer
+    pub fn describe(&self) -> String {
+        match &self.problem {
+            Problem::WrongType { expected, found } => {
+                format!("{} must be {}, not {}", self.pointer, expected, found)
+            }
+            Problem::Empty => format!("{} is empty", self.pointer),
+            Problem::MissingMember(member) => format!("{} has no {}", self.pointer, member),
+            Problem::Placeholder => format!("{} is still a [TODO] placeholder", self.pointer),
+        }
+    }
+}
+
+/// How a field of metadata.json checks out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FieldStatus {
+    /// Not a member of the root object, under any accepted key
+    Missing,
+    /// Present; with the problems found (none if it is usable)
+    Present(Vec<FieldProblem>),
+}
+
+/// metadata.json, parsed
+pub(crate) struct MetadataDocument {
+    root: JsonValue,
+    locations: JsonLocations,
+}
+
+impl MetadataDocument {
+    /// Parse metadata.json; None unless it is a valid JSON object
+    pub fn parse(content: &str) -> Option<Self> {
+        let (root, locations) = parse_json_with_locations(content.trim_start_matches('\u{feff}')).ok()?;
+        root.as_object()?;
+        Some(MetadataDocument { root, locations })
+    }
+
+    /// Check a field against its kind
+    pub fn check(&self, field: &MetadataField) -> FieldStatus {
+        let found = std::iter::once(field.name)
+            .chain(field.aliases.iter().copied())
+            .find_map(|key| self.root.get(key).map(|value| (key, value)));
+        let (key, value) = match found {
+            Some(found) => found,
+            None => return FieldStatus::Missing,
+        };
+
+        let mut problems = Vec::new();
+        let pointer = format!("/{}", json_pointer_token(key));
+        match field.kind {
+            FieldKind::Text => self.check_text(value, pointer, &mut problems),
+            FieldKind::Agent => self.check_agent(value, pointer, true, &mut problems),
+            FieldKind::Keywords => match value {
+                JsonValue::Array(items) if items.is_empty() => {
+                    problems.push(self.problem(pointer, Problem::Empty));
+                }
+                JsonValue::Array(items) => {
+                    for (idx, item) in items.iter().enumerate() {
+                        self.check_text(item, format!("{}/{}", pointer, idx), &mut problems);
+                    }
+                }
+                other => problems.push(self.problem(
+                    pointer,
+                    Problem::WrongType {
+                        expected: "an array of strings",
+                        found: type_name(other),
+                    },
+                )),
+            },
+        }
+        FieldStatus::Present(problems)
+    }
+
+    /// A string with content
+    fn check_text(&self, value: &JsonValue, pointer: String, problems: &mut Vec<FieldProblem>) {
+        match value {
+            JsonValue::String(text) if text.trim().is_empty() => problems.push(self.problem(pointer, Problem::Empty)),
+            JsonValue::String(text) if text.contains("[TODO") => {
+                problems.push(self.problem(pointer, Problem::Placeholder))
+            }
+            JsonValue::String(_) => {}
+            other => problems.push(self.problem(
+                pointer,
+                Problem::WrongType {
+                    expected: "a string",
+                    found: type_name(other),
+                },
+            )),
+        }
+    }
+
+    /// A name, an object with a name, or (at the top) an array of these
+    fn check_agent(&self, value: &JsonValue, pointer: String, allow_array: bool, problems: &mut Vec<FieldProblem>) {
+        match value {
+            JsonValue::String(_) => self.check_text(value, pointer, problems),
+            JsonValue::Object(_) => match value.get("name") {
+                Some(name) => self.check_text(name, format!("{}/name", pointer), problems),
+                None => problems.push(self.problem(pointer, Problem::MissingMember("name"))),
+            },
+            JsonValue::Array(items) if allow_array => {
+                if items.is_empty() {
+                    problems.push(self.problem(pointer.clone(), Problem::Empty));
+                }
+                for (idx, item) in items.iter().enumerate() {
+                    self.check_agent(item, format!("{}/{}", pointer, idx), false, problems);
+                }
+            }
+            other => problems.push(self.problem(
+                pointer,
+                Problem::WrongType {
+                    expected: "a name or an object with a name",
+                    found: type_name(other),
+                },
+            )),
+        }
+    }
+
+    fn problem(&self, pointer: String, problem: Problem) -> FieldProblem {
+        // A missing `name` member is reported where its object starts
+        let line = self
+            .locations
+            .line(&pointer)
+            .or_else(|| self.locations.line(pointer.rsplit_once('/').map_or("", |(parent, _)| parent)));
+        FieldProblem { pointer, line, problem }
+    }
+}
+
+/// A JSON type, as named in messages
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Boolean(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"{
+  "title": 42,
+  "description": "  ",
+  "author": [{"name": "Ada"}, {"email": "x@y.org"}],
+  "date": "[TODO: YYYY-MM-DD]",
+  "keywords": ["ocean", "", 7],
+  "contact": {"license": "MIT"}
+}"#;
+
+    fn problems(status: FieldStatus) -> Vec<String> {
+        match status {
+            FieldStatus::Present(problems) => problems
+                .iter()
+                .map(|p| format!("{} @{}", p.describe(), p.line.unwrap_or(0)))
+                .collect(),
+            FieldStatus::Missing => vec!["missing".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_typed_fields() {
+        let doc = MetadataDocument::parse(CONTENT).unwrap();
+        assert_eq!(problems(doc.check(&TITLE)), vec!["/title must be a string, not a number @2"]);
+        assert_eq!(problems(doc.check(&DESCRIPTION)), vec!["/description is empty @3"]);
+        assert_eq!(problems(doc.check(&CREATOR)), vec!["/author/1 has no name @4"]);
+        assert_eq!(problems(doc.check(&DATE)), vec!["/date is still a [TODO] placeholder @5"]);
+        assert_eq!(
+            problems(doc.check(&KEYWORDS)),
+            vec!["/keywords/1 is empty @6", "/keywords/2 must be a string, not a number @6"]
+        );
+        // Nested under contact, so not the dataset's license
+        assert_eq!(doc.check(&LICENSE), FieldStatus::Missing);
+    }
+
+    #[test]
+    fn test_valid_and_unparseable_documents() {
+        let doc = MetadataDocument::parse(
+            r#"{"title": "Ocean survey", "creator": "Ada", "keywords": ["ocean"], "license": "CC-BY-4.0"}"#,
+        )
+        .unwrap();
+        for field in [TITLE, CREATOR, KEYWORDS, LICENSE] {
+            assert_eq!(doc.check(&field), FieldStatus::Present(Vec::new()));
+        }
+        assert!(MetadataDocument::parse("[1, 2]").is_none());
+        assert!(MetadataDocument::parse("{\"title\": }").is_none());
+    }
+}
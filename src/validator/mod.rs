@@ -3,23 +3,62 @@
 //! This module validates datasets against FAIR principles and best practices
 //! for scientific data management.
 
+mod archive;
+mod config;
 mod content;
+mod croissant;
+mod data_dictionary;
 mod data_quality;
 mod fair;
+mod fits;
+mod gzip;
+mod hdf5;
+mod image;
 mod integrity;
+mod json;
+mod jsonl;
 mod metadata;
+mod metadata_model;
 mod naming;
+mod netcdf;
+mod numpy;
+mod pdf;
+mod sequence;
+mod sqlite;
 mod structure;
+mod tiff;
+mod vcf;
+mod xml;
+mod zarr;
 
 use crate::types::{AnalysisResult, FileInfo, ValidationResult};
 
+pub use archive::validate_archives;
+pub use config::validate_config;
 pub use content::{validate_all_content, detect_todo_markers, TodoLocation};
+pub use croissant::validate_croissant;
+pub use data_dictionary::validate_data_dictionary;
 pub use data_quality::check_data_quality;
 pub use fair::calculate_fair_scores;
+pub use fits::validate_fits;
+pub use gzip::validate_gzip;
+pub use hdf5::validate_hdf5;
+pub use image::check_images;
 pub use integrity::check_integrity;
+pub use json::validate_json;
+pub use jsonl::validate_jsonl;
 pub use metadata::validate_metadata;
 pub use naming::check_naming_conventions;
+pub use netcdf::validate_netcdf;
+pub use numpy::validate_numpy;
+pub use pdf::validate_pdf;
+pub use sequence::validate_sequences;
+pub use sqlite::validate_sqlite;
 pub use structure::check_structure;
+pub use tiff::validate_tiff;
+pub use vcf::validate_vcf;
+pub use xml::validate_xml;
+pub use zarr::validate_zarr;
 
 /// Validate a dataset for FAIR compliance and quality
 ///
@@ -64,6 +103,63 @@ pub fn validate_dataset(
     // Check data quality
     results.extend(data_quality::check_data_quality(files, analyses));
 
+    // Check Croissant metadata against the files it describes
+    results.extend(croissant::validate_croissant(files, analyses));
+
+    // Check column documentation in the data dictionary
+    results.extend(data_dictionary::validate_data_dictionary(files, analyses));
+
+    // Check that HDF5 datasets carry units and descriptions
+    results.extend(hdf5::validate_hdf5(files, analyses));
+
+    // Check netCDF classic files against the CF conventions
+    results.extend(netcdf::validate_netcdf(files, analyses));
+
+    // Check FITS headers for mandatory keywords, column units and sizes
+    results.extend(fits::validate_fits(files, analyses));
+
+    // Check NumPy array headers against the stored data size
+    results.extend(numpy::validate_numpy(files, analyses));
+
+    // Check Zarr stores for missing chunk files
+    results.extend(zarr::validate_zarr(files, analyses));
+
+    // Check GeoTIFF rasters for a CRS and georeferencing
+    results.extend(tiff::validate_tiff(files, analyses));
+
+    // Check PNG/JPEG collections for consistent sizes and channels
+    results.extend(image::check_images(files, analyses));
+
+    // Check PDFs for encryption and a text layer
+    results.extend(pdf::validate_pdf(files, analyses));
+
+    // Check gzipped data files for corrupt streams and CRC mismatches
+    results.extend(gzip::validate_gzip(files, analyses));
+
+    // Check ZIP/tar archives for path traversal, duplicates and unreadable members
+    results.extend(archive::validate_archives(files, analyses));
+
+    // Check JSON data files for syntax errors
+    results.extend(json::validate_json(files, analyses));
+
+    // Check JSON Lines files for invalid lines and inconsistent records
+    results.extend(jsonl::validate_jsonl(files, analyses));
+
+    // Check YAML and TOML files for syntax errors
+    results.extend(config::validate_config(files, analyses));
+
+    // Check XML files for well-formedness
+    results.extend(xml::validate_xml(files, analyses));
+
+    // Check SQLite databases for pending write-ahead logs and damage
+    results.extend(sqlite::validate_sqlite(files, analyses));
+
+    // Check FASTA and FASTQ files for malformed records and stray characters
+    results.extend(sequence::validate_sequences(files, analyses));
+
+    // Check VCF files for malformed lines, sort order and undeclared contigs
+    results.extend(vcf::validate_vcf(files, analyses));
+
     // Calculate FAIR scores (adds validation results for missing elements)
     results.extend(fair::calculate_fair_scores(files, analyses));
 
@@ -105,7 +201,6 @@ pub fn validate_dataset_with_integrity(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::FileType;
     use std::path::PathBuf;
 
     #[test]
@@ -0,0 +1,388 @@
+//! CF-conventions checks for netCDF classic files
+//!
+//! The CF (Climate and Forecast) conventions are what make a netCDF file
+//! usable by other tools: a `Conventions` attribute naming the version,
+//! `units` and `standard_name` on variables, coordinate variables for the
+//! dimensions data is laid out along, and fill values that cannot be
+//! mistaken for data.
+//!
+//! NetCDF-4 files are analyzed as HDF5; for those only the `Conventions`
+//! check applies here, and dataset attributes are checked by the HDF5
+//! validator.
+
+use crate::types::{
+    AnalysisResult, FileInfo, Hdf5Analysis, NetcdfAnalysis, NetcdfVariable, ValidationResult,
+};
+use std::collections::HashSet;
+
+/// Validate netCDF headers against the CF conventions
+pub fn validate_netcdf(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        match analysis {
+            AnalysisResult::Netcdf(netcdf) => results.extend(check_file(file, netcdf)),
+            AnalysisResult::Hdf5(hdf5) => {
+                if let Some(conventions) = netcdf4_conventions(hdf5) {
+                    results.extend(check_conventions(file, conventions));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    results
+}
+
+/// Check one netCDF file
+fn check_file(file: &FileInfo, netcdf: &NetcdfAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    let conventions = netcdf
+        .global_attribute("Conventions")
+        .map(|a| a.value.as_str())
+        .unwrap_or("");
+    results.extend(check_conventions(file, conventions));
+
+    let bounds = referenced_names(netcdf, "bounds");
+    let auxiliary = referenced_names(netcdf, "coordinates");
+
+    for variable in &netcdf.variables {
+        if !needs_description(variable) || bounds.contains(variable.name.as_str()) {
+            continue;
+        }
+
+        if !has_value(variable, "units") {
+            results.push(
+                ValidationResult::warning(
+                    "CF-002",
+                    format!("Variable '{}' in {} has no 'units' attribute", variable.name, file_name),
+                    "Add a 'units' attribute (UDUNITS syntax, e.g. 'K' or 'days since 2000-01-01'; use '1' for dimensionless values)",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+
+        if !has_value(variable, "standard_name") {
+            results.push(
+                ValidationResult::info(
+                    "CF-003",
+                    format!("Variable '{}' in {} has no 'standard_name' attribute", variable.name, file_name),
+                    "Add a 'standard_name' from the CF standard name table so tools can identify the quantity",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    for dimension in data_dimensions(netcdf, &bounds) {
+        let has_coordinate = netcdf.variables.iter().any(|v| {
+            v.dimensions.len() == 1
+                && v.dimensions[0] == dimension
+                && (v.name == dimension || auxiliary.contains(v.name.as_str()))
+        });
+        if !has_coordinate {
+            results.push(
+                ValidationResult::info(
+                    "CF-004",
+                    format!("Dimension '{}' in {} has no coordinate variable", dimension, file_name),
+                    format!(
+                        "Add a 1-D variable named '{}' holding the coordinate values (with units) along that dimension",
+                        dimension
+                    ),
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    for variable in &netcdf.variables {
+        for problem in fill_value_problems(variable) {
+            results.push(
+                ValidationResult::warning(
+                    "CF-005",
+                    format!("Variable '{}' in {}: {}", variable.name, file_name, problem),
+                    "Give _FillValue a single value of the variable's type, outside valid_min/valid_max/valid_range",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    results
+}
+
+/// Check the global `Conventions` attribute (empty if absent) names CF
+fn check_conventions(file: &FileInfo, conventions: &str) -> Option<ValidationResult> {
+    let conventions = conventions.trim();
+    if conventions.contains("CF-") {
+        return None;
+    }
+
+    let file_name = file.relative_path.to_string_lossy();
+    let message = if conventions.is_empty() {
+        format!("{} has no global 'Conventions' attribute", file_name)
+    } else {
+        format!("{} does not declare CF conventions (Conventions = '{}')", file_name, conventions)
+    };
+    Some(
+        ValidationResult::warning(
+            "CF-001",
+            message,
+            "Add a global 'Conventions' attribute naming the CF version, e.g. 'CF-1.8'",
+        )
+        .with_file(file.relative_path.clone()),
+    )
+}
+
+/// The root group's `Conventions` (empty if absent) when an HDF5 file is netCDF-4
+///
+/// The netCDF library stamps every netCDF-4 file with `_NCProperties`.
+fn netcdf4_conventions(hdf5: &Hdf5Analysis) -> Option<&str> {
+    let root = hdf5.groups.iter().find(|g| g.path == "/")?;
+    root.attributes.iter().find(|a| a.name == "_NCProperties")?;
+    Some(
+        root.attributes
+            .iter()
+            .find(|a| a.name == "Conventions")
+            .map(|a| a.value.as_str())
+            .unwrap_or(""),
+    )
+}
+
+/// Whether a variable holds data that should carry units and a standard name
+///
+/// Text variables, grid mapping containers and flag variables are exempt;
+/// CF does not give them units.
+fn needs_description(variable: &NetcdfVariable) -> bool {
+    variable.nc_type.is_numeric()
+        && variable.attribute("grid_mapping_name").is_none()
+        && variable.attribute("flag_meanings").is_none()
+}
+
+/// Whether an attribute is present with a non-empty value
+fn has_value(variable: &NetcdfVariable, name: &str) -> bool {
+    variable
+        .attribute(name)
+        .map(|a| !a.value.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Names listed in a space-separated attribute (`bounds`, `coordinates`) of any variable
+fn referenced_names<'a>(netcdf: &'a NetcdfAnalysis, attribute: &str) -> HashSet<&'a str> {
+    netcdf
+        .variables
+        .iter()
+        .filter_map(|v| v.attribute(attribute))
+        .flat_map(|a| a.value.split_whitespace())
+        .collect()
+}
+
+/// Dimensions that numeric data variables are laid out along, in declaration order
+///
+/// String-length dimensions (used only by text variables) and the vertex
+/// dimension of cell bounds need no coordinate variable.
+fn data_dimensions<'a>(netcdf: &'a NetcdfAnalysis, bounds: &HashSet<&str>) -> Vec<&'a str> {
+    let used: HashSet<&str> = netcdf
+        .variables
+        .iter()
+        .filter(|v| v.nc_type.is_numeric() && !bounds.contains(v.name.as_str()))
+        .flat_map(|v| v.dimensions.iter().map(String::as_str))
+        .collect();
+
+    netcdf
+        .dimensions
+        .iter()
+        .map(|d| d.name.as_str())
+        .filter(|name| used.contains(name))
+        .collect()
+}
+
+/// Describe everything wrong with a variable's `_FillValue`
+fn fill_value_problems(variable: &NetcdfVariable) -> Vec<String> {
+    let mut problems = Vec::new();
+    let fill = match variable.attribute("_FillValue") {
+        Some(fill) => fill,
+        None => return problems,
+    };
+
+    if fill.nc_type != variable.nc_type {
+        problems.push(format!(
+            "_FillValue is {} but the variable is {}",
+            fill.nc_type, variable.nc_type
+        ));
+    }
+    if fill.nc_type.is_numeric() && fill.count != 1 {
+        problems.push(format!("_FillValue has {} values instead of one", fill.count));
+    }
+
+    if let Some(&value) = fill.numbers.first() {
+        let (min, max) = valid_bounds(variable);
+        let above_min = min.map(|min| value >= min).unwrap_or(true);
+        let below_max = max.map(|max| value <= max).unwrap_or(true);
+        if (min.is_some() || max.is_some()) && above_min && below_max {
+            problems.push(format!("_FillValue {} lies inside the valid range", fill.value));
+        }
+    }
+
+    problems
+}
+
+/// Lower and upper valid bounds from `valid_range` or `valid_min`/`valid_max`
+fn valid_bounds(variable: &NetcdfVariable) -> (Option<f64>, Option<f64>) {
+    if let Some(range) = variable.attribute("valid_range") {
+        if range.numbers.len() == 2 {
+            return (Some(range.numbers[0]), Some(range.numbers[1]));
+        }
+    }
+    let first = |name: &str| variable.attribute(name).and_then(|a| a.numbers.first().copied());
+    (first("valid_min"), first("valid_max"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{NetcdfAttribute, NetcdfDimension, NetcdfFormat, NetcdfType};
+    use std::path::PathBuf;
+
+    fn text(name: &str, value: &str) -> NetcdfAttribute {
+        NetcdfAttribute {
+            name: name.to_string(),
+            nc_type: NetcdfType::Char,
+            count: value.len() as u64,
+            value: value.to_string(),
+            numbers: vec![],
+        }
+    }
+
+    fn number(name: &str, nc_type: NetcdfType, numbers: &[f64]) -> NetcdfAttribute {
+        NetcdfAttribute {
+            name: name.to_string(),
+            nc_type,
+            count: numbers.len() as u64,
+            value: numbers.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", "),
+            numbers: numbers.to_vec(),
+        }
+    }
+
+    fn variable(name: &str, dims: &[&str], nc_type: NetcdfType, attributes: Vec<NetcdfAttribute>) -> NetcdfVariable {
+        NetcdfVariable {
+            name: name.to_string(),
+            dimensions: dims.iter().map(|d| d.to_string()).collect(),
+            shape: vec![2; dims.len()],
+            nc_type,
+            attributes,
+        }
+    }
+
+    fn analysis(dims: &[&str], globals: Vec<NetcdfAttribute>, variables: Vec<NetcdfVariable>) -> NetcdfAnalysis {
+        let mut analysis = NetcdfAnalysis::new(NetcdfFormat::Classic);
+        analysis.dimensions = dims
+            .iter()
+            .map(|d| NetcdfDimension {
+                name: d.to_string(),
+                length: 2,
+                is_unlimited: false,
+            })
+            .collect();
+        analysis.global_attributes = globals;
+        analysis.variables = variables;
+        analysis
+    }
+
+    fn validate(analysis: NetcdfAnalysis) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/ocean.nc"), PathBuf::from("ocean.nc"))];
+        validate_netcdf(&files, &[AnalysisResult::Netcdf(analysis)])
+    }
+
+    fn described(name: &str, units: &str) -> Vec<NetcdfAttribute> {
+        vec![text("units", units), text("standard_name", name)]
+    }
+
+    #[test]
+    fn test_cf_compliant_file_passes() {
+        let mut sst = described("sea_surface_temperature", "K");
+        sst.push(number("_FillValue", NetcdfType::Float, &[-999.0]));
+        sst.push(number("valid_range", NetcdfType::Float, &[270.0, 310.0]));
+        let mut time = described("time", "days since 2024-01-01");
+        time.push(text("bounds", "time_bnds"));
+
+        let results = validate(analysis(
+            &["time", "station", "nv", "name_strlen"],
+            vec![text("Conventions", "CF-1.8")],
+            vec![
+                variable("time", &["time"], NetcdfType::Double, time),
+                variable("time_bnds", &["time", "nv"], NetcdfType::Double, vec![]),
+                variable("lat", &["station"], NetcdfType::Float, described("latitude", "degrees_north")),
+                variable("station_name", &["station", "name_strlen"], NetcdfType::Char, vec![]),
+                variable(
+                    "sst",
+                    &["time", "station"],
+                    NetcdfType::Float,
+                    [sst, vec![text("coordinates", "lat station_name")]].concat(),
+                ),
+                variable("crs", &[], NetcdfType::Int, vec![text("grid_mapping_name", "latitude_longitude")]),
+            ],
+        ));
+
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_cf_problems_reported() {
+        let results = validate(analysis(
+            &["x", "y"],
+            vec![text("Conventions", "COARDS")],
+            vec![
+                variable("x", &["x"], NetcdfType::Double, described("projection_x_coordinate", "m")),
+                variable(
+                    "depth",
+                    &["y", "x"],
+                    NetcdfType::Short,
+                    vec![
+                        number("_FillValue", NetcdfType::Int, &[0.0, 1.0]),
+                        number("valid_min", NetcdfType::Short, &[-100.0]),
+                    ],
+                ),
+            ],
+        ));
+
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["CF-001", "CF-002", "CF-003", "CF-004", "CF-005", "CF-005", "CF-005"]);
+        assert!(results[0].message.contains("COARDS"));
+        assert!(results[3].message.contains("'y'"));
+        assert!(results[4].message.contains("_FillValue is int but the variable is short"));
+        assert!(results[6].message.contains("inside the valid range"));
+    }
+
+    #[test]
+    fn test_netcdf4_conventions() {
+        use crate::types::{Hdf5Analysis, Hdf5Attribute, Hdf5Datatype, Hdf5Group};
+
+        let attribute = |name: &str, value: &str| Hdf5Attribute {
+            name: name.to_string(),
+            datatype: Hdf5Datatype::VarString,
+            value: value.to_string(),
+        };
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/model.nc"), PathBuf::from("model.nc")),
+            FileInfo::new(PathBuf::from("/d/plain.h5"), PathBuf::from("plain.h5")),
+        ];
+        let mut netcdf4 = Hdf5Analysis::new(2);
+        netcdf4.groups.push(Hdf5Group {
+            path: "/".to_string(),
+            attributes: vec![attribute("_NCProperties", "version=2,netcdf=4.9.2")],
+        });
+        let mut plain = Hdf5Analysis::new(0);
+        plain.groups.push(Hdf5Group {
+            path: "/".to_string(),
+            attributes: vec![],
+        });
+
+        let results = validate_netcdf(&files, &[AnalysisResult::Hdf5(netcdf4), AnalysisResult::Hdf5(plain)]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "CF-001");
+        assert!(results[0].message.contains("model.nc"));
+    }
+}
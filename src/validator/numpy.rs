@@ -0,0 +1,156 @@
+//! NumPy array checks
+//!
+//! Compares the data size each `.npy` header declares (shape × item size)
+//! with the bytes actually stored, which catches arrays truncated by an
+//! interrupted save or transfer.
+
+use crate::types::{AnalysisResult, FileInfo, NumpyAnalysis, ValidationResult};
+
+/// Validate the array headers of `.npy` and `.npz` files
+pub fn validate_numpy(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Numpy(numpy) = analysis {
+            results.extend(check_file(file, numpy));
+        }
+    }
+
+    results
+}
+
+/// Check one `.npy` file or `.npz` archive
+fn check_file(file: &FileInfo, numpy: &NumpyAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    for array in &numpy.arrays {
+        let header = match &array.header {
+            Some(header) => header,
+            None => continue,
+        };
+        let expected = match header.expected_size() {
+            Some(expected) => expected,
+            None => continue,
+        };
+        if expected == array.stored_size {
+            continue;
+        }
+
+        let label = if numpy.archive {
+            format!("Array '{}' in {}", array.name, file_name)
+        } else {
+            file_name.to_string()
+        };
+        let shape = match header.shape.as_slice() {
+            [single] => format!("({},)", single),
+            dims => format!(
+                "({})",
+                dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+        };
+        let declared = format!(
+            "shape {} of {} ({} bytes with the header)",
+            shape, header.descr, expected
+        );
+
+        let message = if array.stored_size < expected {
+            format!(
+                "{} is truncated: the header declares {} but only {} bytes are stored",
+                label, declared, array.stored_size
+            )
+        } else {
+            format!(
+                "{} has {} bytes after its data: the header declares {}",
+                label,
+                array.stored_size - expected,
+                declared
+            )
+        };
+        results.push(
+            ValidationResult::warning(
+                "NPY-001",
+                message,
+                "Re-save the array with numpy.save/numpy.savez or re-transfer the file; numpy.load rejects truncated arrays",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    for warning in &numpy.warnings {
+        results.push(
+            ValidationResult::info(
+                "NPY-002",
+                format!("Part of {} could not be read: {}", file_name, warning),
+                "Check that the file loads with numpy.load; arrays whose header could not be read were not validated",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{NpyHeader, NumpyArray};
+    use std::path::PathBuf;
+
+    fn array(name: &str, shape: Vec<u64>, stored_size: u64) -> NumpyArray {
+        NumpyArray {
+            name: name.to_string(),
+            header: Some(NpyHeader {
+                version: (1, 0),
+                descr: "'<f8'".to_string(),
+                fortran_order: false,
+                shape,
+                item_size: Some(8),
+                header_size: 128,
+            }),
+            stored_size,
+            compression: None,
+        }
+    }
+
+    fn validate(name: &str, archive: bool, arrays: Vec<NumpyArray>) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d").join(name), PathBuf::from(name))];
+        let analysis = NumpyAnalysis {
+            archive,
+            arrays,
+            warnings: vec![],
+        };
+        validate_numpy(&files, &[AnalysisResult::Numpy(analysis)])
+    }
+
+    #[test]
+    fn test_complete_arrays_pass() {
+        let results = validate("weights.npy", false, vec![array("weights", vec![10, 10], 928)]);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_truncated_array_reported() {
+        let results = validate("weights.npy", false, vec![array("weights", vec![10, 10], 600)]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "NPY-001");
+        assert!(results[0].message.contains("weights.npy is truncated"));
+        assert!(results[0].message.contains("shape (10, 10) of '<f8' (928 bytes"));
+    }
+
+    #[test]
+    fn test_archive_members_checked() {
+        let mut compressed = array("images", vec![4], 0);
+        compressed.header = None;
+        compressed.compression = Some("deflate".to_string());
+        let results = validate(
+            "train.npz",
+            true,
+            vec![array("labels", vec![5], 168), array("extra", vec![2], 200), compressed],
+        );
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.starts_with("Array 'extra' in train.npz has 56 bytes after its data"));
+        assert!(results[0].message.contains("shape (2,)"));
+    }
+}
@@ -0,0 +1,133 @@
+//! PDF document checks
+//!
+//! Flags PDFs that archives and text-mining tools cannot use as intended:
+//! encrypted files, image-only scans without a text layer, and PDFs whose
+//! name hides them from the documentation count.
+
+use crate::types::{AnalysisResult, FileInfo, PdfAnalysis, ValidationResult};
+
+/// Validate the structure and text layer of PDF documents
+pub fn validate_pdf(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Pdf(pdf) = analysis {
+            results.extend(check_file(file, pdf));
+        }
+    }
+
+    results
+}
+
+/// Check one PDF
+fn check_file(file: &FileInfo, pdf: &PdfAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    if pdf.encrypted {
+        results.push(
+            ValidationResult::warning(
+                "PDF-001",
+                format!("{} is encrypted", file_name),
+                "Save an unencrypted copy; archives cannot index, migrate or validate encrypted documents",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    if pdf.has_fonts == Some(false) {
+        let pages = match pdf.page_count {
+            Some(1) => "its page".to_string(),
+            Some(count) => format!("any of its {} pages", count),
+            None => "any page".to_string(),
+        };
+        results.push(
+            ValidationResult::warning(
+                "PDF-002",
+                format!(
+                    "{} uses no fonts on {}, so it is likely a scanned image without a text layer",
+                    file_name, pages
+                ),
+                "Run OCR (e.g. ocrmypdf) to add a text layer, so the document can be searched, read aloud and ingested",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    if !file.is_documentation() {
+        let extension = file
+            .extension()
+            .map(|e| format!("its .{} extension", e))
+            .unwrap_or_else(|| "its missing extension".to_string());
+        results.push(
+            ValidationResult::info(
+                "PDF-003",
+                format!(
+                    "{} is a PDF document but is not counted as documentation because of {}",
+                    file_name, extension
+                ),
+                "Rename the file with a .pdf extension so tools and readers recognise it",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    for warning in &pdf.warnings {
+        results.push(
+            ValidationResult::info(
+                "PDF-004",
+                format!("Structure of {} could not be fully read: {}", file_name, warning),
+                "Check that the file opens in a PDF reader; a repaired copy can be saved with qpdf or any reader's Save As",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileType;
+    use std::path::PathBuf;
+
+    fn validate(name: &str, pdf: PdfAnalysis) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from(name), PathBuf::from(name)).with_type(FileType::Binary)];
+        validate_pdf(&files, &[AnalysisResult::Pdf(pdf)])
+    }
+
+    #[test]
+    fn test_text_pdf_passes() {
+        let mut pdf = PdfAnalysis::new("1.7".to_string());
+        pdf.page_count = Some(12);
+        pdf.has_fonts = Some(true);
+        assert!(validate("docs/methods.pdf", pdf).is_empty());
+    }
+
+    #[test]
+    fn test_problem_pdfs() {
+        let mut pdf = PdfAnalysis::new("1.4".to_string());
+        pdf.encrypted = true;
+        pdf.page_count = Some(3);
+        pdf.has_fonts = Some(false);
+        let results = validate("protocol.dat", pdf);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["PDF-001", "PDF-002", "PDF-003"]);
+        assert_eq!(
+            results[1].message,
+            "protocol.dat uses no fonts on any of its 3 pages, so it is likely a scanned image without a text layer"
+        );
+        assert_eq!(
+            results[2].message,
+            "protocol.dat is a PDF document but is not counted as documentation because of its .dat extension"
+        );
+
+        // Unknown font use is not reported as a scan
+        let mut pdf = PdfAnalysis::new("1.5".to_string());
+        pdf.warnings.push("the page tree could not be read".to_string());
+        let results = validate("notes.pdf", pdf);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "PDF-004");
+    }
+}
@@ -0,0 +1,169 @@
+//! FASTA and FASTQ checks
+//!
+//! Aligners and assemblers either stop at a malformed record or silently
+//! drop it, and a FASTQ read whose quality string is out of step with its
+//! bases shifts every read after it. Characters outside the nucleotide or
+//! amino acid alphabet usually mean a corrupted or mislabelled file.
+
+use crate::types::{AnalysisResult, FileInfo, SequenceAnalysis, SequenceFormat, ValidationResult};
+
+/// Most records or characters listed in one message
+const MAX_LISTED: usize = 5;
+
+/// Validate the records of FASTA and FASTQ files
+pub fn validate_sequences(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Sequence(sequence) = analysis {
+            results.extend(check_sequences(file, sequence));
+        }
+    }
+
+    results
+}
+
+/// Check one sequence file
+fn check_sequences(file: &FileInfo, sequence: &SequenceAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    if sequence.invalid_record_count > 0 {
+        let mut listed: Vec<String> = sequence
+            .invalid_records
+            .iter()
+            .take(MAX_LISTED)
+            .map(|(line, problem)| format!("line {} ({})", line, problem))
+            .collect();
+        if sequence.invalid_record_count > listed.len() {
+            listed.push(format!("and {} more", sequence.invalid_record_count - listed.len()));
+        }
+        let suggestion = match sequence.format {
+            SequenceFormat::Fasta => {
+                "Start every record with a '>' header line holding its identifier, followed by its sequence lines"
+            }
+            SequenceFormat::Fastq => {
+                "Write every read as four lines ('@' header, bases, '+' separator, qualities) with as many quality characters as bases; check the file was not truncated during transfer"
+            }
+        };
+        let mut result = ValidationResult::critical(
+            "SEQ-001",
+            format!(
+                "{} has {} malformed {} record{}: {}",
+                file_name,
+                sequence.invalid_record_count,
+                sequence.format,
+                if sequence.invalid_record_count == 1 { "" } else { "s" },
+                listed.join(", ")
+            ),
+            suggestion,
+        )
+        .with_file(file.relative_path.clone());
+        if let Some((line, _)) = sequence.invalid_records.first() {
+            result = result.with_line(*line);
+        }
+        results.push(result);
+    }
+
+    if let (Some(alphabet), Some(first)) = (sequence.alphabet, sequence.anomalies.first()) {
+        let listed: Vec<String> = sequence
+            .anomalies
+            .iter()
+            .take(MAX_LISTED)
+            .map(|a| format!("{:?} ×{} (first on line {})", a.character, a.count, a.first_line))
+            .collect();
+        results.push(
+            ValidationResult::warning(
+                "SEQ-002",
+                format!(
+                    "{} has characters that are not {} residue codes: {}",
+                    file_name,
+                    alphabet,
+                    listed.join(", ")
+                ),
+                "Remove stray characters (spaces, digits, line-ending debris) from sequence lines, or check the file holds the kind of sequence its extension says",
+            )
+            .with_file(file.relative_path.clone())
+            .with_line(first.first_line),
+        );
+    }
+
+    if sequence.empty_records > 0 {
+        results.push(
+            ValidationResult::warning(
+                "SEQ-003",
+                format!(
+                    "{}: {} of {} records have no sequence",
+                    file_name, sequence.empty_records, sequence.record_count
+                ),
+                "Remove empty records; many tools reject zero-length sequences",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, ResidueAnomaly, SequenceAlphabet};
+    use std::path::PathBuf;
+
+    fn analysis(format: SequenceFormat) -> SequenceAnalysis {
+        SequenceAnalysis {
+            format,
+            record_count: 20,
+            total_length: 3000,
+            lengths: None,
+            empty_records: 0,
+            alphabet: Some(SequenceAlphabet::Dna),
+            anomalies: Vec::new(),
+            quality_offset: None,
+            mean_quality: None,
+            invalid_record_count: 0,
+            invalid_records: Vec::new(),
+            gzip: None,
+        }
+    }
+
+    fn validate(sequence: SequenceAnalysis) -> Vec<ValidationResult> {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/reads.fastq"), PathBuf::from("reads.fastq")).with_type(FileType::Fastq),
+        ];
+        validate_sequences(&files, &[AnalysisResult::Sequence(sequence)])
+    }
+
+    #[test]
+    fn test_clean_file_passes() {
+        assert!(validate(analysis(SequenceFormat::Fastq)).is_empty());
+    }
+
+    #[test]
+    fn test_problems_reported() {
+        let mut sequence = analysis(SequenceFormat::Fastq);
+        sequence.invalid_record_count = 7;
+        sequence.invalid_records = (1..=7)
+            .map(|read| (read * 4, "Quality string has 3 characters but the read has 4 bases".to_string()))
+            .collect();
+        sequence.anomalies = vec![ResidueAnomaly {
+            character: ' ',
+            count: 3,
+            first_line: 10,
+        }];
+        sequence.empty_records = 2;
+
+        let results = validate(sequence);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["SEQ-001", "SEQ-002", "SEQ-003"]);
+        assert!(results[0]
+            .message
+            .starts_with("reads.fastq has 7 malformed FASTQ records: line 4 (Quality string"));
+        assert!(results[0].message.ends_with("and 2 more"));
+        assert_eq!(results[0].line_number, Some(4));
+        assert!(results[0].suggestion.contains("four lines"));
+        assert!(results[1].message.contains("not DNA residue codes: ' ' ×3 (first on line 10)"));
+        assert!(results[2].message.ends_with("2 of 20 records have no sequence"));
+    }
+}
@@ -0,0 +1,102 @@
+//! SQLite database checks
+//!
+//! A database in write-ahead-log mode keeps recent transactions in a
+//! `-wal` file beside it until they are checkpointed, so publishing the
+//! database file alone loses them. A damaged b-tree makes tables
+//! unreadable past the damage.
+
+use crate::types::{AnalysisResult, FileInfo, ValidationResult};
+use std::path::PathBuf;
+
+/// Report SQLite databases with pending write-ahead logs or damage
+pub fn validate_sqlite(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        let sqlite = match analysis {
+            AnalysisResult::Sqlite(sqlite) => sqlite,
+            _ => continue,
+        };
+        let path = file.relative_path.to_string_lossy();
+
+        let mut wal_path = file.relative_path.clone().into_os_string();
+        wal_path.push("-wal");
+        let wal_path = PathBuf::from(wal_path);
+        if let Some(wal) = files.iter().find(|f| f.relative_path == wal_path && f.size_bytes > 0) {
+            results.push(
+                ValidationResult::warning(
+                    "SQLITE-001",
+                    format!(
+                        "{} has a write-ahead log ({}, {} bytes) whose transactions are not yet in the database file",
+                        path,
+                        wal.relative_path.to_string_lossy(),
+                        wal.size_bytes
+                    ),
+                    "Close every connection to the database (or run PRAGMA wal_checkpoint(TRUNCATE)) so the log is merged, then remove the -wal and -shm files",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+
+        if let Some(error) = &sqlite.error {
+            results.push(
+                ValidationResult::critical(
+                    "SQLITE-002",
+                    format!("{} is damaged: {}", path, error),
+                    "Run PRAGMA integrity_check; recover what it can with the sqlite3 .recover command, or re-export the database from its source",
+                )
+                .with_file(file.relative_path.clone()),
+            );
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, SqliteAnalysis};
+
+    fn database(error: Option<&str>) -> AnalysisResult {
+        AnalysisResult::Sqlite(SqliteAnalysis {
+            page_size: 4096,
+            page_count: 12,
+            text_encoding: "UTF-8".to_string(),
+            user_version: 0,
+            wal_mode: true,
+            tables: Vec::new(),
+            indexes: Vec::new(),
+            views: Vec::new(),
+            error: error.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn test_pending_wal_reported() {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/survey.db"), PathBuf::from("survey.db")).with_type(FileType::Binary),
+            FileInfo::new(PathBuf::from("/d/survey.db-wal"), PathBuf::from("survey.db-wal")).with_size(32_992),
+        ];
+        let results = validate_sqlite(&files, &[database(None), AnalysisResult::NotAnalyzed]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "SQLITE-001");
+        assert!(results[0].message.contains("survey.db-wal, 32992 bytes"));
+
+        // An empty log has nothing to lose
+        let files = vec![files[0].clone(), files[1].clone().with_size(0)];
+        assert!(validate_sqlite(&files, &[database(None), AnalysisResult::NotAnalyzed]).is_empty());
+    }
+
+    #[test]
+    fn test_damaged_database_reported() {
+        let files = vec![FileInfo::new(PathBuf::from("/d/survey.db"), PathBuf::from("survey.db"))];
+        let results = validate_sqlite(
+            &files,
+            &[database(Some("table samples: page 9 is outside the database (8 pages; the file may be truncated)"))],
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "SQLITE-002");
+        assert!(results[0].message.starts_with("survey.db is damaged: table samples: page 9"));
+    }
+}
@@ -0,0 +1,130 @@
+//! TIFF and GeoTIFF checks
+//!
+//! Flags GeoTIFF rasters whose georeferencing is incomplete: model
+//! coordinates without a coordinate reference system, or a CRS without
+//! the tiepoints or transformation that place pixels in it.
+
+use crate::types::{AnalysisResult, FileInfo, TiffAnalysis, ValidationResult};
+
+/// Validate the GeoTIFF tags and IFD chains of TIFF files
+pub fn validate_tiff(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Tiff(tiff) = analysis {
+            results.extend(check_file(file, tiff));
+        }
+    }
+
+    results
+}
+
+/// Check one TIFF file
+fn check_file(file: &FileInfo, tiff: &TiffAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    if let Some(geo) = &tiff.geo {
+        match geo.crs() {
+            None => results.push(
+                ValidationResult::warning(
+                    "TIFF-001",
+                    format!(
+                        "{} is a GeoTIFF without a coordinate reference system (no ProjectedCSTypeGeoKey or GeographicTypeGeoKey)",
+                        file_name
+                    ),
+                    "Assign the CRS the coordinates are in, e.g. with gdal_edit.py -a_srs EPSG:<code>; without it the raster cannot be overlaid on other data",
+                )
+                .with_file(file.relative_path.clone()),
+            ),
+            Some(crs) if !geo.is_georeferenced() => results.push(
+                ValidationResult::warning(
+                    "TIFF-002",
+                    format!(
+                        "{} declares {} but has no ModelTiepointTag or ModelTransformationTag",
+                        file_name, crs
+                    ),
+                    "Write the raster's origin and pixel size (or an affine transform) so pixels can be placed in the CRS",
+                )
+                .with_file(file.relative_path.clone()),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    for warning in &tiff.warnings {
+        results.push(
+            ValidationResult::info(
+                "TIFF-003",
+                format!("Tags in {} could not be read: {}", file_name, warning),
+                "Check that the file opens in an image viewer or with gdalinfo; pages after the damage were not listed",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, GeoKey, GeoTiffInfo};
+    use std::path::PathBuf;
+
+    fn validate(geo: Option<GeoTiffInfo>, warnings: Vec<String>) -> Vec<ValidationResult> {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/dem.tif"), PathBuf::from("dem.tif")).with_type(FileType::Binary),
+        ];
+        let analysis = TiffAnalysis {
+            big_tiff: false,
+            pages: vec![],
+            geo,
+            warnings,
+        };
+        validate_tiff(&files, &[AnalysisResult::Tiff(analysis)])
+    }
+
+    fn geo(crs: Option<&str>, tiepoints: Vec<f64>) -> GeoTiffInfo {
+        let mut keys = vec![GeoKey {
+            id: 1024,
+            name: "GTModelTypeGeoKey".to_string(),
+            value: "2".to_string(),
+        }];
+        if let Some(code) = crs {
+            keys.push(GeoKey {
+                id: 2048,
+                name: "GeographicTypeGeoKey".to_string(),
+                value: code.to_string(),
+            });
+        }
+        GeoTiffInfo {
+            keys,
+            tiepoints,
+            pixel_scale: vec![0.01, 0.01, 0.0],
+            has_transformation: false,
+        }
+    }
+
+    #[test]
+    fn test_complete_geotiff_passes() {
+        let tiepoints = vec![0.0, 0.0, 0.0, -120.0, 40.0, 0.0];
+        assert!(validate(Some(geo(Some("4326"), tiepoints)), vec![]).is_empty());
+        assert!(validate(None, vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_incomplete_georeferencing() {
+        let tiepoints = vec![0.0, 0.0, 0.0, -120.0, 40.0, 0.0];
+        let results = validate(Some(geo(Some("0"), tiepoints)), vec!["page 2 could not be read".to_string()]);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["TIFF-001", "TIFF-003"]);
+
+        let results = validate(Some(geo(Some("4326"), vec![])), vec![]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].message,
+            "dem.tif declares EPSG:4326 but has no ModelTiepointTag or ModelTransformationTag"
+        );
+    }
+}
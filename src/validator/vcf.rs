@@ -0,0 +1,189 @@
+//! VCF checks
+//!
+//! bcftools, GATK and htslib-based readers reject a VCF whose header or
+//! records break the column layout, and tabix can only index files sorted
+//! by contig and position. Undeclared contigs load, but leave readers
+//! without the reference lengths they need to check positions.
+
+use crate::types::{AnalysisResult, FileInfo, ValidationResult, VcfAnalysis};
+
+/// Most lines, alleles or contigs listed in one message
+const MAX_LISTED: usize = 5;
+
+/// Validate the header and records of VCF files
+pub fn validate_vcf(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Vcf(vcf) = analysis {
+            results.extend(check_vcf(file, vcf));
+        }
+    }
+
+    results
+}
+
+/// Check one VCF file
+fn check_vcf(file: &FileInfo, vcf: &VcfAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let file_name = file.relative_path.to_string_lossy();
+
+    if vcf.invalid_record_count > 0 {
+        let mut listed: Vec<String> = vcf
+            .invalid_records
+            .iter()
+            .take(MAX_LISTED)
+            .map(|(line, problem)| format!("line {} ({})", line, problem))
+            .collect();
+        if vcf.invalid_record_count > listed.len() {
+            listed.push(format!("and {} more", vcf.invalid_record_count - listed.len()));
+        }
+        let mut result = ValidationResult::critical(
+            "VCF-001",
+            format!(
+                "{} has {} malformed line{}: {}",
+                file_name,
+                vcf.invalid_record_count,
+                if vcf.invalid_record_count == 1 { "" } else { "s" },
+                listed.join(", ")
+            ),
+            "Start the file with ##fileformat, keep meta lines before the tab-separated #CHROM header, and give every record the header's columns; bcftools view reports the same problems",
+        )
+        .with_file(file.relative_path.clone());
+        if let Some((line, _)) = vcf.invalid_records.first() {
+            result = result.with_line(*line);
+        }
+        results.push(result);
+    }
+
+    if !vcf.is_sorted {
+        results.push(
+            ValidationResult::warning(
+                "VCF-002",
+                format!("{} is not sorted by contig and position, so it cannot be indexed", file_name),
+                "Sort it with bcftools sort, then compress with bgzip and index with tabix -p vcf",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    if let Some(first) = vcf.anomalies.first() {
+        let listed: Vec<String> = vcf
+            .anomalies
+            .iter()
+            .take(MAX_LISTED)
+            .map(|a| format!("{:?} ×{}", a.character, a.count))
+            .collect();
+        results.push(
+            ValidationResult::warning(
+                "VCF-003",
+                format!(
+                    "{} has REF or ALT alleles with characters other than A, C, G, T and N: {}",
+                    file_name,
+                    listed.join(", ")
+                ),
+                "Write alleles as bases; IUPAC ambiguity codes are not allowed in VCF, and structural variants use symbolic alleles such as <DEL>",
+            )
+            .with_file(file.relative_path.clone())
+            .with_line(first.first_line),
+        );
+    }
+
+    let undeclared: Vec<&str> = vcf
+        .contigs
+        .iter()
+        .filter(|contig| !contig.declared)
+        .map(|contig| contig.name.as_str())
+        .collect();
+    if !undeclared.is_empty() {
+        let mut listed: Vec<&str> = undeclared.iter().take(MAX_LISTED).copied().collect();
+        let more = format!("and {} more", undeclared.len().saturating_sub(MAX_LISTED));
+        if undeclared.len() > MAX_LISTED {
+            listed.push(&more);
+        }
+        results.push(
+            ValidationResult::info(
+                "VCF-004",
+                format!(
+                    "{} has records on contig{} without a ##contig header line: {}",
+                    file_name,
+                    if undeclared.len() == 1 { "" } else { "s" },
+                    listed.join(", ")
+                ),
+                "Add ##contig=<ID=...,length=...> lines for the reference used (bcftools reheader --fai adds them from a .fai index)",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, ResidueAnomaly, VcfContig};
+    use std::path::PathBuf;
+
+    fn contig(name: &str, declared: bool) -> VcfContig {
+        VcfContig {
+            name: name.to_string(),
+            length: declared.then_some(1000),
+            declared,
+            variant_count: 4,
+        }
+    }
+
+    fn analysis() -> VcfAnalysis {
+        VcfAnalysis {
+            file_format: Some("VCFv4.2".to_string()),
+            samples: vec!["NA001".to_string()],
+            variant_count: 8,
+            contigs: vec![contig("chr1", true), contig("chr2", true)],
+            info_fields: vec!["DP".to_string()],
+            format_fields: vec!["GT".to_string()],
+            is_sorted: true,
+            mean_quality: Some(42.0),
+            anomalies: Vec::new(),
+            invalid_record_count: 0,
+            invalid_records: Vec::new(),
+            gzip: None,
+        }
+    }
+
+    fn validate(vcf: VcfAnalysis) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/calls.vcf"), PathBuf::from("calls.vcf")).with_type(FileType::Vcf)];
+        validate_vcf(&files, &[AnalysisResult::Vcf(vcf)])
+    }
+
+    #[test]
+    fn test_clean_file_passes() {
+        assert!(validate(analysis()).is_empty());
+    }
+
+    #[test]
+    fn test_problems_reported() {
+        let mut vcf = analysis();
+        vcf.invalid_record_count = 1;
+        vcf.invalid_records = vec![(12, "Record has 9 columns but the header has 10".to_string())];
+        vcf.is_sorted = false;
+        vcf.anomalies = vec![ResidueAnomaly {
+            character: 'R',
+            count: 2,
+            first_line: 15,
+        }];
+        vcf.contigs.push(contig("chrM", false));
+
+        let results = validate(vcf);
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["VCF-001", "VCF-002", "VCF-003", "VCF-004"]);
+        assert_eq!(
+            results[0].message,
+            "calls.vcf has 1 malformed line: line 12 (Record has 9 columns but the header has 10)"
+        );
+        assert_eq!(results[0].line_number, Some(12));
+        assert!(results[1].suggestion.contains("bcftools sort"));
+        assert_eq!(results[2].line_number, Some(15));
+        assert!(results[3].message.ends_with("without a ##contig header line: chrM"));
+    }
+}
@@ -0,0 +1,99 @@
+//! XML well-formedness checks
+//!
+//! An XML file that is not well-formed is rejected whole by every XML
+//! parser, so a broken EML or ISO record is invisible to the catalogs it
+//! was written for. The analyzer stops at the first error; these checks
+//! report it with its line and column.
+
+use crate::types::{AnalysisResult, FileInfo, ValidationResult, XmlSyntaxError};
+
+/// Report XML files that are not well-formed
+pub fn validate_xml(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    files
+        .iter()
+        .zip(analyses.iter())
+        .filter_map(|(file, analysis)| {
+            let xml = match analysis {
+                AnalysisResult::Xml(xml) => xml,
+                _ => return None,
+            };
+            let error = xml.error.as_ref()?;
+            let standard = xml.standard.map(|s| format!(" {}", s)).unwrap_or_default();
+            Some(
+                ValidationResult::critical(
+                    "XML-001",
+                    format!(
+                        "{} is not well-formed{} XML: {} at line {}, column {}",
+                        file.relative_path.to_string_lossy(),
+                        standard,
+                        error.message,
+                        error.line,
+                        error.column
+                    ),
+                    xml_suggestion(error),
+                )
+                .with_file(file.relative_path.clone())
+                .with_line(error.line),
+            )
+        })
+        .collect()
+}
+
+/// How to fix a well-formedness error
+fn xml_suggestion(error: &XmlSyntaxError) -> &'static str {
+    let message = error.message.as_str();
+    if message.starts_with("Unescaped '&'") {
+        "Write a literal '&' as &amp; (and '<' as &lt;)"
+    } else if message.starts_with("Undefined entity") {
+        "Use a character reference (e.g. &#160; for &nbsp;); XML only predefines &amp; &lt; &gt; &apos; &quot;"
+    } else if message.starts_with("Mismatched end tag") || message.starts_with("Unclosed element") {
+        "Close elements in the reverse order they were opened"
+    } else if message.starts_with("Multiple root elements") || message == "Text outside the root element" {
+        "Wrap the whole document in a single root element"
+    } else if message.starts_with("Undeclared namespace prefix") {
+        "Declare the prefix with an xmlns:prefix=\"...\" attribute on the root element"
+    } else {
+        "Fix the XML at the reported line and column; any XML parser will reject the file until then"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, MetadataStandard, XmlAnalysis};
+    use std::path::PathBuf;
+
+    fn validate(error: Option<&str>) -> Vec<ValidationResult> {
+        let files = vec![FileInfo::new(PathBuf::from("/d/eml.xml"), PathBuf::from("eml.xml")).with_type(FileType::Xml)];
+        let analysis = XmlAnalysis {
+            is_well_formed: error.is_none(),
+            root_element: Some("eml:eml".to_string()),
+            root_namespace: Some("https://eml.ecoinformatics.org/eml-2.2.0".to_string()),
+            namespaces: Vec::new(),
+            element_count: 12,
+            max_depth: 4,
+            standard: Some(MetadataStandard::Eml),
+            error: error.map(|message| XmlSyntaxError {
+                message: message.to_string(),
+                line: 14,
+                column: 22,
+            }),
+        };
+        validate_xml(&files, &[AnalysisResult::Xml(analysis)])
+    }
+
+    #[test]
+    fn test_malformed_xml_reported() {
+        let results = validate(Some("Unescaped '&' (write &amp;, or end the entity reference with ';')"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].code, "XML-001");
+        assert_eq!(results[0].line_number, Some(14));
+        assert!(results[0]
+            .message
+            .starts_with("eml.xml is not well-formed EML XML: Unescaped '&'"));
+        assert!(results[0].message.ends_with("at line 14, column 22"));
+        assert!(results[0].suggestion.contains("&amp;"));
+
+        assert!(validate(None).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Zarr store checks
+//!
+//! Flags arrays whose chunk files, as implied by the shape and chunk grid,
+//! are not all present, and metadata that could not be read.
+
+use crate::types::{AnalysisResult, FileInfo, ValidationResult, ZarrAnalysis};
+
+/// Validate the metadata and chunk files of Zarr stores
+pub fn validate_zarr(files: &[FileInfo], analyses: &[AnalysisResult]) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+
+    for (file, analysis) in files.iter().zip(analyses.iter()) {
+        if let AnalysisResult::Zarr(zarr) = analysis {
+            results.extend(check_store(file, zarr));
+        }
+    }
+
+    results
+}
+
+/// Check one Zarr store
+fn check_store(file: &FileInfo, zarr: &ZarrAnalysis) -> Vec<ValidationResult> {
+    let mut results = Vec::new();
+    let store_name = file.relative_path.to_string_lossy();
+
+    for array in zarr.arrays.iter().filter(|a| a.missing_chunk_count > 0) {
+        let label = if array.path.is_empty() {
+            format!("The array in {}", store_name)
+        } else {
+            format!("Array '{}' in {}", array.path, store_name)
+        };
+        let mut examples = array.missing_chunks.join(", ");
+        if array.missing_chunk_count > array.missing_chunks.len() as u64 {
+            examples.push_str(", ...");
+        }
+        results.push(
+            ValidationResult::warning(
+                "ZARR-001",
+                format!(
+                    "{} is missing {} of {} chunk files ({})",
+                    label, array.missing_chunk_count, array.expected_chunks, examples
+                ),
+                "Missing chunks read back as the fill value; unless they were left empty on purpose, the store was copied incompletely",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    for warning in &zarr.warnings {
+        results.push(
+            ValidationResult::info(
+                "ZARR-002",
+                format!("Metadata in {} could not be read: {}", store_name, warning),
+                "Check that the store opens with zarr.open; arrays with unreadable metadata were not validated",
+            )
+            .with_file(file.relative_path.clone()),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileType, ZarrArray};
+    use std::path::PathBuf;
+
+    fn array(path: &str, expected: u64, missing: &[&str], missing_count: u64) -> ZarrArray {
+        ZarrArray {
+            path: path.to_string(),
+            shape: vec![expected],
+            chunks: vec![1],
+            dtype: "<f8".to_string(),
+            compressor: None,
+            fill_value: None,
+            attributes: vec![],
+            expected_chunks: expected,
+            missing_chunks: missing.iter().map(|m| m.to_string()).collect(),
+            missing_chunk_count: missing_count,
+        }
+    }
+
+    fn validate(arrays: Vec<ZarrArray>, warnings: Vec<String>) -> Vec<ValidationResult> {
+        let files = vec![
+            FileInfo::new(PathBuf::from("/d/grid.zarr"), PathBuf::from("grid.zarr")).with_type(FileType::Zarr),
+        ];
+        let analysis = ZarrAnalysis {
+            zarr_format: 2,
+            arrays,
+            groups: vec![],
+            file_count: 10,
+            warnings,
+        };
+        validate_zarr(&files, &[AnalysisResult::Zarr(analysis)])
+    }
+
+    #[test]
+    fn test_complete_store_passes() {
+        let results = validate(vec![array("temp", 8, &[], 0)], vec![]);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+    }
+
+    #[test]
+    fn test_missing_chunks_reported() {
+        let results = validate(
+            vec![array("temp", 8, &["temp/3", "temp/5"], 2), array("", 40, &["0"], 25)],
+            vec!["wind/.zarray: invalid JSON (unexpected end)".to_string()],
+        );
+        let codes: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
+        assert_eq!(codes, vec!["ZARR-001", "ZARR-001", "ZARR-002"]);
+        assert_eq!(
+            results[0].message,
+            "Array 'temp' in grid.zarr is missing 2 of 8 chunk files (temp/3, temp/5)"
+        );
+        assert!(results[1].message.starts_with("The array in grid.zarr is missing 25 of 40"));
+        assert!(results[1].message.ends_with("(0, ...)"));
+        assert!(results[2].message.contains("wind/.zarray"));
+    }
+}
@@ -154,7 +154,7 @@ mod json_analysis {
 mod validation {
     use super::*;
     use genesis_preflight::types::{FileInfo, ValidationSeverity};
-    use genesis_preflight::validator::{check_structure, validate_metadata};
+    use genesis_preflight::validator::check_structure;
 
     #[test]
     fn test_validate_complete_dataset() {
@@ -297,7 +297,6 @@ mod content_validation {
 }
 
 mod generator {
-    use super::*;
     use genesis_preflight::generator::{generate_readme, generate_metadata};
     use genesis_preflight::types::{DatasetSummary, FileType};
 
@@ -331,6 +330,226 @@ mod generator {
     }
 }
 
+mod croissant {
+    use super::*;
+    use genesis_preflight::analyzer::analyze_file;
+    use genesis_preflight::generator::generate_croissant;
+    use genesis_preflight::scanner::scan_directory;
+    use genesis_preflight::types::{Command, Config, DatasetSummary};
+    use genesis_preflight::validator::validate_croissant;
+
+    #[test]
+    fn test_generated_croissant_round_trip() {
+        let dir = create_temp_dir("croissant_round_trip");
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::write(
+            dir.join("data/readings.csv"),
+            "station,temperature\nA,21.5\nB,19.0\n",
+        )
+        .unwrap();
+
+        let config = Config::new(dir.clone(), Command::Generate);
+        let files = scan_directory(&dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        let content = generate_croissant(&DatasetSummary::new(), &files, &analyses);
+        fs::write(dir.join("croissant.json"), content).unwrap();
+
+        // Rescan so croissant.json itself is part of the dataset
+        let files = scan_directory(&dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        let results = validate_croissant(&files, &analyses);
+
+        // Only the TODO dataset-level properties should be reported
+        assert!(
+            results.iter().all(|r| r.code == "CROISSANT-002"),
+            "Unexpected issues: {:?}",
+            results
+        );
+
+        cleanup_temp_dir(&dir);
+    }
+}
+
+mod data_dictionary {
+    use super::*;
+    use genesis_preflight::analyzer::analyze_file;
+    use genesis_preflight::generator::generate_data_dictionary;
+    use genesis_preflight::scanner::scan_directory;
+    use genesis_preflight::types::{Command, Config};
+    use genesis_preflight::validator::validate_data_dictionary;
+
+    #[test]
+    fn test_generated_dictionary_round_trip() {
+        let dir = create_temp_dir("data_dictionary_round_trip");
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::write(
+            dir.join("data/readings.csv"),
+            "station,temperature\nA,21.5\nB,19.0\n",
+        )
+        .unwrap();
+
+        let config = Config::new(dir.clone(), Command::Generate);
+        let files = scan_directory(&dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        let content = generate_data_dictionary(&files, &analyses);
+        assert!(content.contains("| temperature | float | 0 | 2 | 19 to 21.5 |"));
+        fs::write(dir.join("DATA_DICTIONARY.md"), &content).unwrap();
+
+        // Blank descriptions are reported for every column
+        let files = scan_directory(&dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        let results = validate_data_dictionary(&files, &analyses);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.code == "DICT-003"));
+
+        // Filling them in clears the issues
+        let filled = content
+            .lines()
+            .map(|line| {
+                if line.starts_with("| station ") || line.starts_with("| temperature ") {
+                    line.replacen("|  |  |", "| Documented column | - |", 1)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(dir.join("DATA_DICTIONARY.md"), filled).unwrap();
+        let results = validate_data_dictionary(&files, &analyses);
+        assert!(results.is_empty(), "Unexpected issues: {:?}", results);
+
+        cleanup_temp_dir(&dir);
+    }
+}
+
+mod update {
+    use super::*;
+    use genesis_preflight::analyzer::analyze_file;
+    use genesis_preflight::generator::generate_documentation;
+    use genesis_preflight::scanner::scan_directory;
+    use genesis_preflight::types::{Command, Config};
+    use genesis_preflight::validator::validate_croissant;
+
+    fn run_generate(dir: &std::path::Path, update: bool) -> Vec<genesis_preflight::generator::GeneratedFile> {
+        run_with(dir, Config::new(dir.to_path_buf(), Command::Generate).with_update(update))
+    }
+
+    fn run_with(dir: &std::path::Path, config: Config) -> Vec<genesis_preflight::generator::GeneratedFile> {
+        let files = scan_directory(dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        generate_documentation(&files, &analyses, &[], &config).unwrap()
+    }
+
+    #[test]
+    fn test_update_preserves_user_edits() {
+        let dir = create_temp_dir("generate_update");
+        fs::create_dir_all(dir.join("data")).unwrap();
+        fs::write(dir.join("data/readings.csv"), "station,temperature\nA,21.5\n").unwrap();
+        run_generate(&dir, false);
+
+        // Researcher edits prose and metadata; then the data grows
+        let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+        fs::write(dir.join("README.md"), readme.replace("[TODO: Dataset Title]", "Station Temps")).unwrap();
+        let metadata = fs::read_to_string(dir.join("metadata.json")).unwrap();
+        fs::write(dir.join("metadata.json"), metadata.replace("[TODO: Dataset Title]", "Station Temps")).unwrap();
+        fs::write(dir.join("data/extra.csv"), "a\n1\n").unwrap();
+
+        let generated = run_generate(&dir, true);
+        assert!(generated
+            .iter()
+            .any(|g| g.was_updated && g.path.ends_with("README.md")));
+
+        let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+        assert!(readme.starts_with("# Station Temps"));
+        assert!(readme.contains("- 2 CSV files"));
+        let metadata = fs::read_to_string(dir.join("metadata.json")).unwrap();
+        assert!(metadata.contains("\"title\": \"Station Temps\""));
+        assert!(metadata.contains("\"count\": 2"));
+
+        // Updating again changes nothing
+        let generated = run_generate(&dir, true);
+        assert!(generated.iter().all(|g| !g.was_updated && !g.was_created));
+
+        cleanup_temp_dir(&dir);
+    }
+
+    #[test]
+    fn test_update_after_generate_is_noop() {
+        let dir = create_temp_dir("generate_then_update");
+        fs::write(dir.join("readings.csv"), "station,temperature\nA,21.5\n").unwrap();
+        run_generate(&dir, false);
+
+        // The manifest already covers the documentation it was written with
+        let manifest = fs::read_to_string(dir.join("MANIFEST.sha256")).unwrap();
+        assert!(manifest.contains("  README.md\n"));
+        assert!(manifest.contains("  readings.schema.json\n"));
+
+        let generated = run_generate(&dir, true);
+        let changed: Vec<_> = generated.iter().filter(|g| g.was_updated || g.was_created).collect();
+        assert!(changed.is_empty(), "{:?}", changed.iter().map(|g| &g.path).collect::<Vec<_>>());
+
+        cleanup_temp_dir(&dir);
+    }
+
+    #[test]
+    fn test_update_refreshes_croissant() {
+        let dir = create_temp_dir("generate_update_croissant");
+        fs::write(dir.join("readings.csv"), "station,temperature\nA,21.5\n").unwrap();
+        run_generate(&dir, false);
+
+        let croissant = fs::read_to_string(dir.join("croissant.json")).unwrap();
+        fs::write(dir.join("croissant.json"), croissant.replace("[TODO: Dataset Name]", "Station Temps")).unwrap();
+        fs::write(dir.join("readings.csv"), "station,temperature\nA,21.5\nB,19.0\n").unwrap();
+        run_generate(&dir, true);
+
+        let croissant = fs::read_to_string(dir.join("croissant.json")).unwrap();
+        assert!(croissant.contains("\"name\": \"Station Temps\""));
+
+        // A scan after the update finds every hash and size current
+        let config = Config::new(dir.clone(), Command::Scan);
+        let files = scan_directory(&dir, &config).unwrap();
+        let analyses: Vec<_> = files.iter().map(analyze_file).collect();
+        let problems: Vec<_> = validate_croissant(&files, &analyses)
+            .into_iter()
+            .filter(|r| r.code != "CROISSANT-002")
+            .collect();
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        cleanup_temp_dir(&dir);
+    }
+
+    #[test]
+    fn test_dry_run_plans_without_writing() {
+        let dir = create_temp_dir("generate_dry_run");
+        fs::write(dir.join("readings.csv"), "station,temperature\nA,21.5\n").unwrap();
+
+        // Nothing exists yet: everything would be created, nothing is
+        let config = Config::new(dir.clone(), Command::Generate).with_dry_run(true);
+        let plan = run_with(&dir, config);
+        assert!(!plan.is_empty());
+        assert!(plan.iter().all(|g| g.was_created && !g.path.exists()));
+
+        run_generate(&dir, false);
+        fs::write(dir.join("more.csv"), "a\n1\n").unwrap();
+        let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+
+        // The dry-run diff matches what a real update then writes
+        let config = Config::new(dir.clone(), Command::Generate)
+            .with_update(true)
+            .with_dry_run(true);
+        let plan = run_with(&dir, config);
+        let planned = plan.iter().find(|g| g.path.ends_with("README.md")).unwrap();
+        assert!(planned.was_updated);
+        assert_eq!(fs::read_to_string(dir.join("README.md")).unwrap(), readme);
+
+        let applied = run_generate(&dir, true);
+        let applied = applied.iter().find(|g| g.path.ends_with("README.md")).unwrap();
+        assert_eq!(planned.diff, applied.diff);
+
+        cleanup_temp_dir(&dir);
+    }
+}
+
 mod crypto {
     use super::*;
     use genesis_preflight::crypto::sha256_file;
@@ -371,7 +590,7 @@ mod crypto {
 }
 
 mod scoring {
-    use genesis_preflight::types::{ValidationResult, ValidationSeverity};
+    use genesis_preflight::types::ValidationResult;
     use genesis_preflight::reporter::calculate_score;
 
     #[test]